@@ -0,0 +1,135 @@
+//! Pause levels and the matrix of what each level allows.
+//!
+//! A single `paused: bool` forces every instruction author to decide, on
+//! their own, whether their instruction should honour it. Those decisions
+//! drift: deposits get gated, withdrawals that auto-claim rewards do not.
+//! Here the decision is made once, as a table, and every gated instruction
+//! asks the same question:
+//!
+//! ```
+//! use security_common::circuit_breaker::{Operation, PauseLevel};
+//! assert!(PauseLevel::WindDown.allows(Operation::Withdraw));
+//! assert!(!PauseLevel::Frozen.allows(Operation::Withdraw));
+//! ```
+//!
+//! | Level      | Deposit | Claim | Withdraw | Accrue | Admin |
+//! |------------|---------|-------|----------|--------|-------|
+//! | `Active`   | yes     | yes   | yes      | yes    | yes   |
+//! | `WindDown` | no      | yes   | yes      | yes    | yes   |
+//! | `Frozen`   | no      | no    | no       | no     | yes   |
+
+use anchor_lang::prelude::*;
+
+/// How far the protocol is shut down. Ordered by severity.
+#[derive(
+    AnchorSerialize,
+    AnchorDeserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    InitSpace,
+)]
+pub enum PauseLevel {
+    /// Normal operation.
+    Active,
+    /// Orderly exit: no new money in, users may still leave with what they have.
+    WindDown,
+    /// Incident response: nothing moves and no state advances until the admin acts.
+    Frozen,
+}
+
+/// The kinds of instruction a pause level can gate.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// Adds funds or positions.
+    Deposit,
+    /// Pays out accrued rewards without touching principal.
+    Claim,
+    /// Returns principal, including any rewards settled along the way.
+    Withdraw,
+    /// Advances accumulators, indices or any other time-driven state.
+    Accrue,
+    /// Configuration changes and the pause switch itself.
+    Admin,
+}
+
+impl PauseLevel {
+    pub const ALL: [PauseLevel; 3] = [PauseLevel::Active, PauseLevel::WindDown, PauseLevel::Frozen];
+
+    /// Whether `op` may run at this level. Exhaustive on both axes, so adding
+    /// a level or an operation does not compile until the table is updated.
+    pub const fn allows(self, op: Operation) -> bool {
+        match (self, op) {
+            (PauseLevel::Active, _) => true,
+            (_, Operation::Admin) => true,
+            (PauseLevel::WindDown, Operation::Deposit) => false,
+            (PauseLevel::WindDown, Operation::Claim | Operation::Withdraw | Operation::Accrue) => {
+                true
+            }
+            (
+                PauseLevel::Frozen,
+                Operation::Deposit | Operation::Claim | Operation::Withdraw | Operation::Accrue,
+            ) => false,
+        }
+    }
+}
+
+impl Operation {
+    pub const ALL: [Operation; 5] = [
+        Operation::Deposit,
+        Operation::Claim,
+        Operation::Withdraw,
+        Operation::Accrue,
+        Operation::Admin,
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expected(level: PauseLevel, op: Operation) -> bool {
+        use Operation::*;
+        use PauseLevel::*;
+        match level {
+            Active => true,
+            WindDown => matches!(op, Claim | Withdraw | Accrue | Admin),
+            Frozen => matches!(op, Admin),
+        }
+    }
+
+    #[test]
+    fn matrix_matches_documented_table() {
+        for level in PauseLevel::ALL {
+            for op in Operation::ALL {
+                assert_eq!(level.allows(op), expected(level, op), "{level:?} / {op:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn higher_levels_never_allow_more() {
+        for op in Operation::ALL {
+            for pair in PauseLevel::ALL.windows(2) {
+                assert!(
+                    pair[1].allows(op) <= pair[0].allows(op),
+                    "{:?} allows {op:?} but {:?} does not",
+                    pair[1],
+                    pair[0]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn admin_is_never_locked_out() {
+        for level in PauseLevel::ALL {
+            assert!(level.allows(Operation::Admin), "{level:?} locks out admin");
+        }
+    }
+}
@@ -0,0 +1,9 @@
+//! Helpers shared by the security pattern programs.
+//!
+//! Nothing in here is vulnerable on purpose. Programs reach for these types on
+//! their secure paths so the fix is the same everywhere.
+
+pub mod circuit_breaker;
+pub mod math;
+pub mod pda;
+pub mod time;
@@ -0,0 +1,41 @@
+//! Fixed-point helpers with an explicit rounding direction.
+//!
+//! Every division in a financial program rounds one way or the other. The
+//! rule is: round against the caller. Amounts the protocol *receives*
+//! (fees owed, shares burned, debt) round up; amounts it *pays out*
+//! (withdrawals, shares minted, interest earned) round down. Pick the helper
+//! by which side of that line the result is on, not by which is closer.
+
+/// `floor(a * b / c)` using a `u128` intermediate.
+///
+/// Use for anything the protocol pays out.
+///
+/// ```
+/// use security_common::math::mul_div_floor;
+/// assert_eq!(mul_div_floor(10, 3, 4), Some(7)); // 7.5 -> 7
+/// assert_eq!(mul_div_floor(1, 1, 0), None);
+/// ```
+pub fn mul_div_floor(a: u64, b: u64, c: u64) -> Option<u64> {
+    if c == 0 {
+        return None;
+    }
+    let product = (a as u128).checked_mul(b as u128)?;
+    u64::try_from(product / c as u128).ok()
+}
+
+/// `ceil(a * b / c)` using a `u128` intermediate.
+///
+/// Use for anything the protocol collects.
+///
+/// ```
+/// use security_common::math::mul_div_ceil;
+/// assert_eq!(mul_div_ceil(10, 3, 4), Some(8)); // 7.5 -> 8
+/// assert_eq!(mul_div_ceil(8, 1, 4), Some(2)); // exact stays exact
+/// ```
+pub fn mul_div_ceil(a: u64, b: u64, c: u64) -> Option<u64> {
+    if c == 0 {
+        return None;
+    }
+    let product = (a as u128).checked_mul(b as u128)?;
+    u64::try_from(product.div_ceil(c as u128)).ok()
+}
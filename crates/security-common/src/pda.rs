@@ -0,0 +1,35 @@
+//! Collision-free seeds for variable-length input.
+//!
+//! The runtime derives a PDA from the *concatenation* of its seeds. Passing
+//! two strings as two seeds does not keep them apart: `["abc", "def"]` and
+//! `["ab", "cdef"]` hash the same bytes and land on the same address.
+//!
+//! `hashed_seed` length-prefixes every part before hashing, so a boundary
+//! shift changes the input, and always returns 32 bytes, so it also fits
+//! under the runtime's per-seed length limit no matter how long the input is.
+
+use anchor_lang::solana_program::hash::hashv;
+
+/// `sha256(len(p0) || p0 || len(p1) || p1 || ...)` with `u32` little-endian lengths.
+///
+/// ```
+/// use security_common::pda::hashed_seed;
+/// assert_ne!(
+///     hashed_seed(&[b"abc", b"def"]),
+///     hashed_seed(&[b"ab", b"cdef"]),
+/// );
+/// assert_ne!(hashed_seed(&[b"", b"x"]), hashed_seed(&[b"x", b""]));
+/// ```
+pub fn hashed_seed(parts: &[&[u8]]) -> [u8; 32] {
+    let lengths: Vec<[u8; 4]> = parts
+        .iter()
+        .map(|part| (part.len() as u32).to_le_bytes())
+        .collect();
+
+    let mut input: Vec<&[u8]> = Vec::with_capacity(parts.len() * 2);
+    for (part, len) in parts.iter().zip(lengths.iter()) {
+        input.push(len);
+        input.push(part);
+    }
+    hashv(&input).to_bytes()
+}
@@ -0,0 +1,79 @@
+//! Typed time units.
+//!
+//! `Clock` exposes three clocks as bare integers: `slot`, `epoch` and
+//! `unix_timestamp`. Comparing one against another compiles fine and is
+//! always wrong. Wrapping each in its own type turns unit mixing into a
+//! compile error:
+//!
+//! ```compile_fail
+//! use security_common::time::{Epoch, UnixTs};
+//! let unlock = Epoch(500);
+//! let now = UnixTs(1_700_000_000);
+//! let _ = now >= unlock; // mismatched types
+//! ```
+//!
+//! Account fields and instruction arguments stay as raw `u64`/`i64` so the
+//! IDL is unchanged; wrap them at the point of comparison.
+
+use anchor_lang::prelude::*;
+
+/// A slot height (`Clock::slot`). Roughly 400ms each.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Slot(pub u64);
+
+/// Seconds since the Unix epoch (`Clock::unix_timestamp`). Signed, like the sysvar.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UnixTs(pub i64);
+
+/// A Solana epoch number (`Clock::epoch`). Roughly two days each on mainnet.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Epoch(pub u64);
+
+impl Slot {
+    pub fn checked_add_slots(self, slots: u64) -> Option<Slot> {
+        self.0.checked_add(slots).map(Slot)
+    }
+
+    /// Slots elapsed since `earlier`, or `None` if `earlier` is in the future.
+    pub fn slots_since(self, earlier: Slot) -> Option<u64> {
+        self.0.checked_sub(earlier.0)
+    }
+}
+
+impl UnixTs {
+    pub fn checked_add_secs(self, secs: i64) -> Option<UnixTs> {
+        self.0.checked_add(secs).map(UnixTs)
+    }
+
+    /// Seconds elapsed since `earlier`, or `None` if `earlier` is in the future.
+    pub fn secs_since(self, earlier: UnixTs) -> Option<i64> {
+        self.0.checked_sub(earlier.0).filter(|secs| *secs >= 0)
+    }
+}
+
+impl Epoch {
+    pub fn checked_add_epochs(self, epochs: u64) -> Option<Epoch> {
+        self.0.checked_add(epochs).map(Epoch)
+    }
+}
+
+/// Typed accessors for the `Clock` sysvar.
+pub trait ClockExt {
+    fn current_slot(&self) -> Slot;
+    fn current_unix_ts(&self) -> UnixTs;
+    fn current_epoch(&self) -> Epoch;
+}
+
+impl ClockExt for Clock {
+    fn current_slot(&self) -> Slot {
+        Slot(self.slot)
+    }
+
+    fn current_unix_ts(&self) -> UnixTs {
+        UnixTs(self.unix_timestamp)
+    }
+
+    fn current_epoch(&self) -> Epoch {
+        Epoch(self.epoch)
+    }
+}
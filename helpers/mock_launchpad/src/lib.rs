@@ -0,0 +1,47 @@
+//! A permissionless launchpad: anyone creates a `Config` for their own
+//! launch, naming themselves admin and choosing the fee treasury.
+//!
+//! Not a pattern program: nothing here is vulnerable on purpose. Its
+//! `Config` has the same name, and so the same discriminator, as 70's.
+
+use anchor_lang::prelude::*;
+
+declare_id!("4YmeLMwY4AbNVm3bgrDdCqdDJznWgxCEgU9Emky2uZUR");
+
+#[program]
+pub mod mock_launchpad {
+    use super::*;
+
+    pub fn create_config(ctx: Context<CreateConfig>, treasury: Pubkey, fee_bps: u16) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.creator.key();
+        config.treasury = treasury;
+        config.fee_bps = fee_bps;
+        config.bump = ctx.bumps.config;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct CreateConfig<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [b"config", creator.key().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    pub fee_bps: u16,
+    pub bump: u8,
+}
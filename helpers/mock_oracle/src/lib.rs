@@ -0,0 +1,78 @@
+//! A push-style price feed used by the oracle pattern programs.
+//!
+//! Not a pattern program: nothing here is vulnerable on purpose. The feed
+//! authority sets the price and, so tests can simulate a feed that stopped
+//! updating, optionally the publish time as well.
+
+use anchor_lang::prelude::*;
+
+declare_id!("4eEbEV8BgqhASUY2tTrfynTmvs9uPaVA8T7mLBTHWuwu");
+
+/// Prices are quoted with this many decimals.
+pub const PRICE_DECIMALS: u32 = 6;
+
+#[program]
+pub mod mock_oracle {
+    use super::*;
+
+    pub fn initialize_feed(ctx: Context<InitializeFeed>, feed_id: u64, price: u64) -> Result<()> {
+        let feed = &mut ctx.accounts.feed;
+        feed.feed_id = feed_id;
+        feed.authority = ctx.accounts.authority.key();
+        feed.price = price;
+        feed.publish_time = Clock::get()?.unix_timestamp;
+        feed.bump = ctx.bumps.feed;
+        Ok(())
+    }
+
+    /// Publishes `price`, stamped now unless `publish_time` is given.
+    pub fn set_price(ctx: Context<SetPrice>, price: u64, publish_time: Option<i64>) -> Result<()> {
+        let feed = &mut ctx.accounts.feed;
+        feed.price = price;
+        feed.publish_time = match publish_time {
+            Some(ts) => ts,
+            None => Clock::get()?.unix_timestamp,
+        };
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(feed_id: u64)]
+pub struct InitializeFeed<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PriceFeed::INIT_SPACE,
+        seeds = [b"feed", authority.key().as_ref(), feed_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub feed: Account<'info, PriceFeed>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPrice<'info> {
+    #[account(
+        mut,
+        seeds = [b"feed", authority.key().as_ref(), feed.feed_id.to_le_bytes().as_ref()],
+        bump = feed.bump,
+        has_one = authority
+    )]
+    pub feed: Account<'info, PriceFeed>,
+    pub authority: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PriceFeed {
+    pub feed_id: u64,
+    pub authority: Pubkey,
+    /// Quote price with `PRICE_DECIMALS` decimals.
+    pub price: u64,
+    /// Unix timestamp the price was published at.
+    pub publish_time: i64,
+    pub bump: u8,
+}
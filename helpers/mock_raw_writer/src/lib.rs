@@ -0,0 +1,56 @@
+//! Stands in for a program the attacker deployed.
+//!
+//! Not a pattern program: it writes caller-supplied bytes into an account it
+//! owns, which is all an attacker's own program needs to do to fabricate
+//! another protocol's account layout. The target signs, so only whoever
+//! created it can write to it.
+//!
+//! Any instruction it does not recognise is answered by echoing the first
+//! account's data, after its 8-byte discriminator, as return data. A caller
+//! that CPIs an unchecked program id gets back whatever the attacker wrote.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::set_return_data;
+
+declare_id!("FBMQQUYsSVd2dMMPduanRnwkKpw6iTsnCVMDVx6WWRuK");
+
+#[program]
+pub mod mock_raw_writer {
+    use super::*;
+
+    /// Copies `data` into the target account starting at `offset`.
+    pub fn write(ctx: Context<Write>, offset: u32, data: Vec<u8>) -> Result<()> {
+        let mut target = ctx.accounts.target.try_borrow_mut_data()?;
+        let start = offset as usize;
+        let end = start.checked_add(data.len()).ok_or(RawWriterError::OutOfBounds)?;
+        require!(end <= target.len(), RawWriterError::OutOfBounds);
+        target[start..end].copy_from_slice(&data);
+        Ok(())
+    }
+
+    /// Answers any other instruction with the first account's body.
+    pub fn fallback<'info>(
+        _program_id: &Pubkey,
+        accounts: &'info [AccountInfo<'info>],
+        _data: &[u8],
+    ) -> Result<()> {
+        let source = accounts.first().ok_or(ErrorCode::AccountNotEnoughKeys)?;
+        let data = source.try_borrow_data()?;
+        let body = data.get(8..).ok_or(RawWriterError::OutOfBounds)?;
+        set_return_data(body);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Write<'info> {
+    /// CHECK: any account this program owns; raw bytes are the point.
+    #[account(mut, signer, owner = crate::ID)]
+    pub target: AccountInfo<'info>,
+}
+
+#[error_code]
+pub enum RawWriterError {
+    #[msg("Write extends past the end of the account")]
+    OutOfBounds,
+}
@@ -0,0 +1,85 @@
+//! A curated adapter registry used as a CPI target.
+//!
+//! Not a pattern program: nothing here is vulnerable on purpose. The curator
+//! registers one `Entry` per adapter id at `[b"entry", id]`, and `lookup`
+//! returns the adapter of that entry. A caller that CPIs `lookup` through a
+//! program account it never checked may not be talking to this program.
+
+use anchor_lang::prelude::*;
+
+declare_id!("HrD1g9vTvXRbinEgnXiF6mvqKHxCuTxjsccoN9uRKjNQ");
+
+#[program]
+pub mod mock_registry {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let registry = &mut ctx.accounts.registry;
+        registry.curator = ctx.accounts.curator.key();
+        registry.bump = ctx.bumps.registry;
+        Ok(())
+    }
+
+    pub fn register(ctx: Context<Register>, id: u64, adapter: Pubkey) -> Result<()> {
+        let entry = &mut ctx.accounts.entry;
+        entry.adapter = adapter;
+        entry.id = id;
+        entry.bump = ctx.bumps.entry;
+        Ok(())
+    }
+
+    /// Returns the adapter registered under `id`.
+    pub fn lookup(ctx: Context<Lookup>, _id: u64) -> Result<Pubkey> {
+        Ok(ctx.accounts.entry.adapter)
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = curator, space = 8 + Registry::INIT_SPACE, seeds = [b"registry"], bump)]
+    pub registry: Account<'info, Registry>,
+    #[account(mut)]
+    pub curator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct Register<'info> {
+    #[account(seeds = [b"registry"], bump = registry.bump, has_one = curator)]
+    pub registry: Account<'info, Registry>,
+    #[account(
+        init,
+        payer = curator,
+        space = 8 + Entry::INIT_SPACE,
+        seeds = [b"entry", id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub entry: Account<'info, Entry>,
+    #[account(mut)]
+    pub curator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct Lookup<'info> {
+    #[account(seeds = [b"entry", id.to_le_bytes().as_ref()], bump = entry.bump)]
+    pub entry: Account<'info, Entry>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Registry {
+    pub curator: Pubkey,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Entry {
+    /// Where integrations route to for this id.
+    pub adapter: Pubkey,
+    pub id: u64,
+    pub bump: u8,
+}
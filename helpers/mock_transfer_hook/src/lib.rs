@@ -0,0 +1,135 @@
+//! A Token-2022 transfer hook whose owner can block selected transfers.
+//!
+//! Not a pattern program: it stands in for a hook controlled by whoever
+//! created the mint. `set_blocked` names a transfer authority; from then on
+//! every transfer signed by that authority fails inside the hook, while all
+//! other transfers of the mint go through.
+
+use anchor_lang::prelude::*;
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::instruction::{ExecuteInstruction, TransferHookInstruction};
+
+declare_id!("5Yu1hU83dSEE6WRiurMxvfhQUmwSyxUvn4LgsrX8vdHz");
+
+#[program]
+pub mod mock_transfer_hook {
+    use super::*;
+
+    /// Creates the hook's config and the extra-account-metas list Token-2022
+    /// reads to resolve the config on every transfer.
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let config = &mut ctx.accounts.hook_config;
+        config.mint = ctx.accounts.mint.key();
+        config.authority = ctx.accounts.authority.key();
+        config.blocked = Pubkey::default();
+        config.bump = ctx.bumps.hook_config;
+
+        let metas = [ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"hook_config".to_vec() },
+                Seed::AccountKey { index: 1 },
+            ],
+            false,
+            false,
+        )?];
+        let mut data = ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?;
+        ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &metas)?;
+        Ok(())
+    }
+
+    /// Blocks every transfer whose authority is `blocked`.
+    /// `Pubkey::default()` lifts the block.
+    pub fn set_blocked(ctx: Context<SetBlocked>, blocked: Pubkey) -> Result<()> {
+        ctx.accounts.hook_config.blocked = blocked;
+        Ok(())
+    }
+
+    /// Token-2022 calls the hook with the interface's own discriminator,
+    /// not an Anchor one, so `Execute` is dispatched here.
+    pub fn fallback<'info>(
+        program_id: &Pubkey,
+        accounts: &'info [AccountInfo<'info>],
+        data: &[u8],
+    ) -> Result<()> {
+        match TransferHookInstruction::unpack(data)? {
+            TransferHookInstruction::Execute { amount } => execute(program_id, accounts, amount),
+            _ => Err(ProgramError::InvalidInstructionData.into()),
+        }
+    }
+}
+
+/// Accounts: source, mint, destination, authority, extra-account-metas,
+/// then the extras from the list (here, only the hook config).
+fn execute<'info>(program_id: &Pubkey, accounts: &'info [AccountInfo<'info>], amount: u64) -> Result<()> {
+    let [_source, mint, _destination, authority, _extra_metas, config_info, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys.into());
+    };
+
+    let (expected, _) = Pubkey::find_program_address(&[b"hook_config", mint.key.as_ref()], program_id);
+    require_keys_eq!(config_info.key(), expected, HookError::InvalidConfig);
+    let config = Account::<HookConfig>::try_from(config_info)?;
+
+    if config.blocked != Pubkey::default() && config.blocked == authority.key() {
+        msg!("Transfer hook: blocked transfer of {} signed by {}", amount, authority.key);
+        return err!(HookError::TransferBlocked);
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + HookConfig::INIT_SPACE,
+        seeds = [b"hook_config", mint.key().as_ref()],
+        bump
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+    /// CHECK: Created here with the seeds Token-2022 derives; holds TLV data, not an Anchor account.
+    #[account(
+        init,
+        payer = authority,
+        space = ExtraAccountMetaList::size_of(1).unwrap(),
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+    /// CHECK: Only its key is used, as a seed.
+    pub mint: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetBlocked<'info> {
+    #[account(
+        mut,
+        seeds = [b"hook_config", hook_config.mint.as_ref()],
+        bump = hook_config.bump,
+        has_one = authority @ HookError::Unauthorized
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+    pub authority: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct HookConfig {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    /// Transfer authority whose transfers fail; `Pubkey::default()` for none.
+    pub blocked: Pubkey,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum HookError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Hook config does not match the mint")]
+    InvalidConfig,
+    #[msg("Transfer blocked by hook")]
+    TransferBlocked,
+}
@@ -0,0 +1,87 @@
+//! A bookkeeping-only vault used as a CPI target.
+//!
+//! Not a pattern program: nothing here is vulnerable on purpose. `withdraw`
+//! fills partially when the balance is short and reports the amount actually
+//! withdrawn through return data, which is what callers must check.
+
+use anchor_lang::prelude::*;
+
+declare_id!("G8peozezgp2emunkd51cWwveGB5ddvG6A3pFfQETWHnS");
+
+#[program]
+pub mod mock_vault {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>, authority: Pubkey) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.authority = authority;
+        vault.balance = 0;
+        vault.bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.balance = vault.balance.checked_add(amount).ok_or(MockVaultError::ArithmeticError)?;
+        Ok(())
+    }
+
+    /// Withdraws up to `amount` and returns how much was actually withdrawn.
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<u64> {
+        let vault = &mut ctx.accounts.vault;
+        let withdrawn = amount.min(vault.balance);
+        vault.balance -= withdrawn;
+        Ok(withdrawn)
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(authority: Pubkey)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", authority.as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, seeds = [b"vault", vault.authority.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    pub depositor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", authority.key().as_ref()],
+        bump = vault.bump,
+        has_one = authority @ MockVaultError::Unauthorized
+    )]
+    pub vault: Account<'info, Vault>,
+    pub authority: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub authority: Pubkey,
+    pub balance: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum MockVaultError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
@@ -1,8 +1,15 @@
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
 use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use security_common::math::mul_div_floor;
+use security_common::time::{ClockExt, UnixTs};
 
 declare_id!("3UFE7yLEjqFt2WDGHkWeUnfR2C3ttJUYad2ty3V2TEsa");
 
+/// Version byte written into every `VaultV2`.
+pub const VAULT_V2_VERSION: u8 = 2;
+
 #[program]
 pub mod vault {
     use super::*;
@@ -12,6 +19,7 @@ pub mod vault {
         vault.owner = ctx.accounts.owner.key();
         vault.balance = 0;
         vault.bump = ctx.bumps.vault;
+        vault.open_allowances = 0;
         Ok(())
     }
 
@@ -28,6 +36,13 @@ pub mod vault {
 
         let vault = &mut ctx.accounts.vault;
         vault.balance = vault.balance.checked_add(amount).unwrap();
+
+        emit!(DepositEvent {
+            vault: ctx.accounts.vault.key(),
+            owner: ctx.accounts.owner.key(),
+            mint: None,
+            amount,
+        });
         
         Ok(())
     }
@@ -44,6 +59,16 @@ pub mod vault {
         require!(vault.owner == ctx.accounts.authority.key(), VaultError::Unauthorized);
         require!(vault.balance >= amount, VaultError::InsufficientFunds);
 
+        let authority = ctx.accounts.authority.key();
+        if ctx.accounts.vault.owner != &crate::ID {
+            exploit_detected(ctx.accounts.vault.key(), authority, ExploitKind::ForeignVault);
+        }
+        if ctx.accounts.vault.key() != pda(&[b"vault", authority.as_ref()])
+            || ctx.accounts.vault_pda.key() != pda(&[b"vault_pda", authority.as_ref()])
+        {
+            exploit_detected(ctx.accounts.vault.key(), authority, ExploitKind::UnexpectedAddress);
+        }
+
         // Signer seeds derived from the UNVERIFIED vault data
         let seeds = &[
             b"vault_pda",
@@ -64,6 +89,14 @@ pub mod vault {
         
         transfer(cpi_ctx, amount)?;
 
+        emit!(WithdrawEvent {
+            vault: ctx.accounts.vault.key(),
+            recipient: ctx.accounts.authority.key(),
+            mint: None,
+            amount,
+            secure: false,
+        });
+
         Ok(())
     }
 
@@ -71,7 +104,9 @@ pub mod vault {
     // 1. `Account<Vault>` verifies Program ID ownership and Type Discriminator.
     // 2. `seeds` constraint ensures PDA matches the vault.
     // 3. `has_one` constraint enforces authority matches vault owner.
+    // 4. The owner's rate limit, once configured, applies here too.
     pub fn withdraw_secure(ctx: Context<WithdrawSecure>, amount: u64) -> Result<()> {
+        enforce_rate_limit(&ctx.accounts.rate_limit, amount)?;
         let vault = &mut ctx.accounts.vault;
 
         require!(vault.balance >= amount, VaultError::InsufficientFunds);
@@ -96,97 +131,1778 @@ pub mod vault {
         
         transfer(cpi_ctx, amount)?;
 
+        emit!(WithdrawEvent {
+            vault: ctx.accounts.vault.key(),
+            recipient: ctx.accounts.owner.key(),
+            mint: None,
+            amount,
+            secure: true,
+        });
+
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-pub struct InitializeVault<'info> {
-    #[account(
-        init,
-        payer = owner,
-        space = 8 + Vault::INIT_SPACE,
-        seeds = [b"vault", owner.key().as_ref()],
-        bump
-    )]
-    pub vault: Account<'info, Vault>,
-    #[account(
-        seeds = [b"vault_pda", owner.key().as_ref()],
-        bump
-    )]
-    pub vault_pda: SystemAccount<'info>,
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
+    pub fn initialize_vault_indexed(ctx: Context<InitializeVaultIndexed>, _index: u16) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.owner = ctx.accounts.owner.key();
+        vault.balance = 0;
+        vault.bump = ctx.bumps.vault;
+        vault.open_allowances = 0;
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct Deposit<'info> {
-    #[account(
-        mut,
-        seeds = [b"vault", owner.key().as_ref()],
-        bump = vault.bump,
-        has_one = owner
-    )]
-    pub vault: Account<'info, Vault>,
-    #[account(
-        mut,
-        seeds = [b"vault_pda", owner.key().as_ref()],
-        bump
-    )]
-    pub vault_pda: SystemAccount<'info>,
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
+    pub fn deposit_indexed(ctx: Context<DepositIndexed>, _index: u16, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.owner.to_account_info(),
+            to: ctx.accounts.vault_pda.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            cpi_accounts
+        );
+        transfer(cpi_ctx, amount)?;
 
-#[derive(Accounts)]
-pub struct WithdrawInsecure<'info> {
-    // VULNERABLE: AccountInfo skips all Anchor safety checks
-    /// CHECK: Unsafe. Any account data can be passed here.
-    #[account(mut)] 
-    pub vault: AccountInfo<'info>,
-    
-    // VULNERABLE: No seeds check to verify PDA derivation
-    /// CHECK: Unsafe. No relationship to vault is enforced.
-    #[account(mut)]
-    pub vault_pda: AccountInfo<'info>,
-    
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
+        let vault = &mut ctx.accounts.vault;
+        vault.balance = vault.balance.checked_add(amount).unwrap();
 
-#[derive(Accounts)]
-pub struct WithdrawSecure<'info> {
-    // SECURE: Account wrapper validates Owner and Discriminator
-    #[account(
-        mut,
-        seeds = [b"vault", owner.key().as_ref()],
-        bump = vault.bump,
-        has_one = owner @ VaultError::Unauthorized
-    )]
-    pub vault: Account<'info, Vault>,
-    
-    // SECURE: Seeds constraint validates PDA derivation
-    #[account(
-        mut,
-        seeds = [b"vault_pda", owner.key().as_ref()],
-        bump
-    )]
-    pub vault_pda: SystemAccount<'info>,
-    
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
+        emit!(DepositEvent {
+            vault: ctx.accounts.vault.key(),
+            owner: ctx.accounts.owner.key(),
+            mint: None,
+            amount,
+        });
 
-#[account]
-#[derive(InitSpace)]
-pub struct Vault {
-    pub owner: Pubkey,
-    pub balance: u64,
-    pub bump: u8,
+        Ok(())
+    }
+
+    // VULNERABLE: Index Missing From Vault Seeds
+    // 1. `vault` is checked with `has_one = owner` but no `seeds`, so any of
+    //    the owner's vaults passes, whatever `index` says.
+    // 2. `vault_pda` is derived from `index`, so the lamports come from a
+    //    different vault than the balance is debited from.
+    // 3. One vault's record ends up backed by lamports that are gone, and
+    //    the other's lamports by a record that never saw them leave.
+    pub fn withdraw_indexed_insecure(ctx: Context<WithdrawIndexedInsecure>, index: u16, amount: u64) -> Result<()> {
+        let index_bytes = index.to_le_bytes();
+        let owner = ctx.accounts.owner.key();
+        if ctx.accounts.vault.key() != pda(&[b"vault", owner.as_ref(), index_bytes.as_ref()]) {
+            exploit_detected(ctx.accounts.vault.key(), owner, ExploitKind::UnexpectedAddress);
+        }
+
+        let vault = &mut ctx.accounts.vault;
+
+        require!(vault.balance >= amount, VaultError::InsufficientFunds);
+        vault.balance = vault.balance.checked_sub(amount).unwrap();
+
+        let seeds = &[
+            b"vault_pda",
+            ctx.accounts.owner.key.as_ref(),
+            index_bytes.as_ref(),
+            &[ctx.bumps.vault_pda],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault_pda.to_account_info(),
+            to: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            cpi_accounts,
+            signer
+        );
+
+        transfer(cpi_ctx, amount)?;
+
+        emit!(WithdrawEvent {
+            vault: ctx.accounts.vault.key(),
+            recipient: ctx.accounts.owner.key(),
+            mint: None,
+            amount,
+            secure: false,
+        });
+
+        Ok(())
+    }
+
+    // SECURE: Index in Every Seed
+    // 1. `vault` and `vault_pda` are both derived from the owner and the
+    //    same `index`, so the record and the lamports belong together.
+    // 2. `has_one` still ties the vault to the signing owner.
+    pub fn withdraw_indexed_secure(ctx: Context<WithdrawIndexedSecure>, index: u16, amount: u64) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+
+        require!(vault.balance >= amount, VaultError::InsufficientFunds);
+        vault.balance = vault.balance.checked_sub(amount).unwrap();
+
+        let index_bytes = index.to_le_bytes();
+        let seeds = &[
+            b"vault_pda",
+            ctx.accounts.owner.key.as_ref(),
+            index_bytes.as_ref(),
+            &[ctx.bumps.vault_pda],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault_pda.to_account_info(),
+            to: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            cpi_accounts,
+            signer
+        );
+
+        transfer(cpi_ctx, amount)?;
+
+        emit!(WithdrawEvent {
+            vault: ctx.accounts.vault.key(),
+            recipient: ctx.accounts.owner.key(),
+            mint: None,
+            amount,
+            secure: true,
+        });
+
+        Ok(())
+    }
+
+    pub fn initialize_token_vault(_ctx: Context<InitializeTokenVault>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn deposit_tokens(ctx: Context<DepositTokens>, amount: u64) -> Result<()> {
+        let cpi_accounts = token::Transfer {
+            from: ctx.accounts.source.to_account_info(),
+            to: ctx.accounts.vault_token.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts
+        );
+        token::transfer(cpi_ctx, amount)?;
+
+        emit!(DepositEvent {
+            vault: ctx.accounts.vault_token.owner,
+            owner: ctx.accounts.owner.key(),
+            mint: Some(ctx.accounts.vault_token.mint),
+            amount,
+        });
+        Ok(())
+    }
+
+    // VULNERABLE: Missing Account Validation (SPL Token)
+    // 1. `vault` is an unchecked `AccountInfo`; any bytes that decode as a
+    //    `Vault` pass the authority check.
+    // 2. The `Vault` PDA that signs is derived from `vault_owner`, an account
+    //    nothing ties to `vault` or to the signer.
+    // 3. `vault_token` and `destination` are never checked, so whichever token
+    //    account that PDA controls is drained to the caller.
+    pub fn withdraw_tokens_insecure(ctx: Context<WithdrawTokensInsecure>, amount: u64) -> Result<()> {
+        let vault_data = &ctx.accounts.vault.try_borrow_data()?;
+        let vault = Vault::try_deserialize_unchecked(&mut &vault_data[..])?;
+
+        require!(vault.owner == ctx.accounts.authority.key(), VaultError::Unauthorized);
+
+        let authority = ctx.accounts.authority.key();
+        if ctx.accounts.vault.owner != &crate::ID {
+            exploit_detected(ctx.accounts.vault.key(), authority, ExploitKind::ForeignVault);
+        }
+        if ctx.accounts.vault.key() != pda(&[b"vault", authority.as_ref()])
+            || ctx.accounts.vault_owner.key() != authority
+        {
+            exploit_detected(ctx.accounts.vault.key(), authority, ExploitKind::UnexpectedAddress);
+        }
+
+        let seeds = &[
+            b"vault",
+            ctx.accounts.vault_owner.key.as_ref(),
+            &[vault.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = token::Transfer {
+            from: ctx.accounts.vault_token.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer
+        );
+        token::transfer(cpi_ctx, amount)?;
+
+        emit!(WithdrawEvent {
+            vault: ctx.accounts.vault.key(),
+            recipient: ctx.accounts.destination.key(),
+            mint: Some(token::accessor::mint(&ctx.accounts.vault_token)?),
+            amount,
+            secure: false,
+        });
+        Ok(())
+    }
+
+    // SECURE: Anchor Validation (SPL Token)
+    // 1. `Account<Vault>` with `seeds` and `has_one` ties the vault to the
+    //    signing owner.
+    // 2. `vault_token` must be the owner's token PDA, with the vault as its
+    //    authority.
+    // 3. The signer seeds come from the validated owner and stored bump.
+    pub fn withdraw_tokens_secure(ctx: Context<WithdrawTokensSecure>, amount: u64) -> Result<()> {
+        let seeds = &[
+            b"vault",
+            ctx.accounts.owner.key.as_ref(),
+            &[ctx.accounts.vault.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = token::Transfer {
+            from: ctx.accounts.vault_token.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer
+        );
+        token::transfer(cpi_ctx, amount)?;
+
+        emit!(WithdrawEvent {
+            vault: ctx.accounts.vault.key(),
+            recipient: ctx.accounts.destination.key(),
+            mint: Some(ctx.accounts.vault_token.mint),
+            amount,
+            secure: true,
+        });
+        Ok(())
+    }
+
+    // VULNERABLE: Unchecked Close
+    // 1. Any signer can close any vault; nothing ties `authority` to
+    //    `vault.owner`.
+    // 2. The rent goes to a caller-supplied `destination`.
+    // 3. Only the lamports move. The data and program owner stay, so rent
+    //    sent back in the same transaction revives the vault as it was.
+    pub fn vulnerable_close_vault(ctx: Context<VulnerableCloseVault>) -> Result<()> {
+        let vault = &ctx.accounts.vault;
+        let destination = &ctx.accounts.destination;
+
+        let authority = ctx.accounts.authority.key();
+        if vault.owner != &crate::ID {
+            exploit_detected(vault.key(), authority, ExploitKind::ForeignVault);
+        } else if let Ok(record) = Vault::try_deserialize(&mut &vault.try_borrow_data()?[..]) {
+            if record.owner != authority || record.owner != destination.key() {
+                exploit_detected(vault.key(), authority, ExploitKind::RentRedirected);
+            }
+        }
+
+        **destination.try_borrow_mut_lamports()? = destination
+            .lamports()
+            .checked_add(vault.lamports())
+            .unwrap();
+        **vault.try_borrow_mut_lamports()? = 0;
+
+        Ok(())
+    }
+
+    // SECURE: Anchor Close
+    // 1. `seeds` and `has_one` tie the vault to the signing owner, and the
+    //    vault must be empty.
+    // 2. `close = owner` refunds the rent to the owner, then assigns the
+    //    account to the System Program and empties its data, so it cannot
+    //    come back as a `Vault`.
+    pub fn secure_close_vault(_ctx: Context<SecureCloseVault>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Lets `delegate` withdraw up to `amount` lamports from the owner's
+    /// vault for the next `expires_in_secs` seconds.
+    pub fn approve_delegate(ctx: Context<ApproveDelegate>, amount: u64, expires_in_secs: i64) -> Result<()> {
+        let expiry = Clock::get()?
+            .current_unix_ts()
+            .checked_add_secs(expires_in_secs)
+            .ok_or(VaultError::ArithmeticError)?;
+
+        let allowance = &mut ctx.accounts.allowance;
+        allowance.owner = ctx.accounts.owner.key();
+        allowance.delegate = ctx.accounts.delegate.key();
+        allowance.remaining_amount = amount;
+        allowance.expiry = expiry.0;
+        allowance.bump = ctx.bumps.allowance;
+
+        let vault = &mut ctx.accounts.vault;
+        vault.open_allowances = vault.open_allowances.checked_add(1).ok_or(VaultError::ArithmeticError)?;
+        Ok(())
+    }
+
+    pub fn revoke_delegate(ctx: Context<RevokeDelegate>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.open_allowances = vault.open_allowances.checked_sub(1).ok_or(VaultError::ArithmeticError)?;
+        Ok(())
+    }
+
+    // VULNERABLE: Allowance Checked but Never Spent
+    // 1. `amount` is compared with `remaining_amount`, which is never
+    //    decremented, so the delegate can repeat the withdrawal until the
+    //    vault is empty.
+    // 2. `expiry` is stored but never read, so the allowance never lapses.
+    pub fn delegated_withdraw_insecure(ctx: Context<DelegatedWithdraw>, amount: u64) -> Result<()> {
+        let allowance = &ctx.accounts.allowance;
+        require!(amount <= allowance.remaining_amount, VaultError::AllowanceExceeded);
+        if Clock::get()?.current_unix_ts() >= UnixTs(allowance.expiry) {
+            exploit_detected(ctx.accounts.vault.key(), ctx.accounts.delegate.key(), ExploitKind::ExpiredAllowance);
+        }
+
+        let vault = &mut ctx.accounts.vault;
+        require!(vault.balance >= amount, VaultError::InsufficientFunds);
+        vault.balance = vault.balance.checked_sub(amount).unwrap();
+
+        let seeds = &[
+            b"vault_pda",
+            ctx.accounts.owner.key.as_ref(),
+            &[ctx.bumps.vault_pda],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault_pda.to_account_info(),
+            to: ctx.accounts.delegate.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            cpi_accounts,
+            signer
+        );
+
+        transfer(cpi_ctx, amount)?;
+
+        emit!(WithdrawEvent {
+            vault: ctx.accounts.vault.key(),
+            recipient: ctx.accounts.delegate.key(),
+            mint: None,
+            amount,
+            secure: false,
+        });
+
+        Ok(())
+    }
+
+    // SECURE: Allowance Spent and Expiring
+    // 1. The allowance must not have expired.
+    // 2. `remaining_amount` is decremented with `checked_sub`, so the
+    //    delegate can withdraw at most the approved total.
+    // 3. `revoke_delegate` closes the allowance; a closed allowance fails
+    //    to load.
+    // 4. Delegated withdrawals count against the owner's rate limit.
+    pub fn delegated_withdraw_secure(ctx: Context<DelegatedWithdraw>, amount: u64) -> Result<()> {
+        enforce_rate_limit(&ctx.accounts.rate_limit, amount)?;
+        let now = Clock::get()?.current_unix_ts();
+        let allowance = &mut ctx.accounts.allowance;
+        require!(now < UnixTs(allowance.expiry), VaultError::AllowanceExpired);
+        allowance.remaining_amount = allowance
+            .remaining_amount
+            .checked_sub(amount)
+            .ok_or(VaultError::AllowanceExceeded)?;
+
+        let vault = &mut ctx.accounts.vault;
+        require!(vault.balance >= amount, VaultError::InsufficientFunds);
+        vault.balance = vault.balance.checked_sub(amount).unwrap();
+
+        let seeds = &[
+            b"vault_pda",
+            ctx.accounts.owner.key.as_ref(),
+            &[ctx.bumps.vault_pda],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault_pda.to_account_info(),
+            to: ctx.accounts.delegate.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            cpi_accounts,
+            signer
+        );
+
+        transfer(cpi_ctx, amount)?;
+
+        emit!(WithdrawEvent {
+            vault: ctx.accounts.vault.key(),
+            recipient: ctx.accounts.delegate.key(),
+            mint: None,
+            amount,
+            secure: true,
+        });
+
+        Ok(())
+    }
+
+    /// Caps the owner's withdrawals at `max_per_window` lamports per
+    /// `window_secs` seconds.
+    pub fn configure_rate_limit(ctx: Context<ConfigureRateLimit>, max_per_window: u64, window_secs: i64) -> Result<()> {
+        require!(window_secs > 0, VaultError::InvalidWindow);
+
+        let rate_limit = &mut ctx.accounts.rate_limit;
+        rate_limit.owner = ctx.accounts.owner.key();
+        rate_limit.max_per_window = max_per_window;
+        rate_limit.window_secs = window_secs;
+        rate_limit.checkpoint_ts = Clock::get()?.current_unix_ts().0;
+        rate_limit.used = 0;
+        rate_limit.bump = ctx.bumps.rate_limit;
+        Ok(())
+    }
+
+    // VULNERABLE: Window Reset on Caller-Supplied Time
+    // 1. `now` is an instruction argument, so the caller decides whether
+    //    the window has elapsed.
+    // 2. Any `now` at least `window_secs` past the window start resets
+    //    `used` to zero and moves the window start to that `now`.
+    // 3. Passing a later `now` on every call resets the window every call;
+    //    the limit never binds.
+    pub fn rate_limited_withdraw_insecure(ctx: Context<RateLimitedWithdraw>, amount: u64, now: i64) -> Result<()> {
+        if now > Clock::get()?.current_unix_ts().0 {
+            exploit_detected(ctx.accounts.vault.key(), ctx.accounts.owner.key(), ExploitKind::FutureTimestamp);
+        }
+
+        let rate_limit = &mut ctx.accounts.rate_limit;
+        if now.wrapping_sub(rate_limit.checkpoint_ts) >= rate_limit.window_secs {
+            rate_limit.checkpoint_ts = now;
+            rate_limit.used = 0;
+        }
+        let used = rate_limit.used.checked_add(amount).ok_or(VaultError::ArithmeticError)?;
+        require!(used <= rate_limit.max_per_window, VaultError::RateLimitExceeded);
+        rate_limit.used = used;
+
+        let vault = &mut ctx.accounts.vault;
+        require!(vault.balance >= amount, VaultError::InsufficientFunds);
+        vault.balance = vault.balance.checked_sub(amount).unwrap();
+
+        let seeds = &[
+            b"vault_pda",
+            ctx.accounts.owner.key.as_ref(),
+            &[ctx.bumps.vault_pda],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault_pda.to_account_info(),
+            to: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            cpi_accounts,
+            signer
+        );
+
+        transfer(cpi_ctx, amount)?;
+
+        emit!(WithdrawEvent {
+            vault: ctx.accounts.vault.key(),
+            recipient: ctx.accounts.owner.key(),
+            mint: None,
+            amount,
+            secure: false,
+        });
+
+        Ok(())
+    }
+
+    // SECURE: Checkpointed Rolling Window
+    // 1. Time comes from the Clock sysvar and must not run backwards from
+    //    the stored checkpoint.
+    // 2. `used` drains at `max_per_window` per `window_secs` since the
+    //    checkpoint, so the limit rolls instead of resetting all at once.
+    // 3. The checkpoint moves to now on every withdrawal; no call can
+    //    release more capacity than the elapsed time allows.
+    pub fn rate_limited_withdraw_secure(ctx: Context<RateLimitedWithdraw>, amount: u64) -> Result<()> {
+        ctx.accounts.rate_limit.consume(amount, Clock::get()?.current_unix_ts())?;
+
+        let vault = &mut ctx.accounts.vault;
+        require!(vault.balance >= amount, VaultError::InsufficientFunds);
+        vault.balance = vault.balance.checked_sub(amount).unwrap();
+
+        let seeds = &[
+            b"vault_pda",
+            ctx.accounts.owner.key.as_ref(),
+            &[ctx.bumps.vault_pda],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault_pda.to_account_info(),
+            to: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            cpi_accounts,
+            signer
+        );
+
+        transfer(cpi_ctx, amount)?;
+
+        emit!(WithdrawEvent {
+            vault: ctx.accounts.vault.key(),
+            recipient: ctx.accounts.owner.key(),
+            mint: None,
+            amount,
+            secure: true,
+        });
+
+        Ok(())
+    }
+
+    // VULNERABLE: Layout Migration Without a Version Check
+    // 1. The account is grown to the v2 size and its existing bytes are
+    //    read as `VaultV2` directly, skipping the discriminator.
+    // 2. v2 puts a version byte between `owner` and `balance`, so every
+    //    field after `owner` is read one byte off: the low byte of the v1
+    //    balance becomes the version, and the bump lands in the top byte
+    //    of the new balance.
+    // 3. The shifted values are written back under the v2 discriminator;
+    //    the vault now records a balance it never held.
+    pub fn migrate_vault_insecure(ctx: Context<MigrateVault>) -> Result<()> {
+        let vault_info = ctx.accounts.vault.to_account_info();
+        if vault_info.try_borrow_data()?.starts_with(&Vault::DISCRIMINATOR) {
+            exploit_detected(vault_info.key(), ctx.accounts.owner.key(), ExploitKind::LayoutMismatch);
+        }
+        grow_vault(&vault_info, &ctx.accounts.owner, &ctx.accounts.system_program, false)?;
+
+        let mut vault = VaultV2::try_deserialize_unchecked(&mut &vault_info.try_borrow_data()?[..])?;
+        require!(vault.owner == ctx.accounts.owner.key(), VaultError::Unauthorized);
+        vault.version = VAULT_V2_VERSION;
+        vault.migrated_at = Clock::get()?.current_unix_ts().0;
+
+        vault.try_serialize(&mut &mut vault_info.try_borrow_mut_data()?[..])?;
+        Ok(())
+    }
+
+    // SECURE: Versioned Migration
+    // 1. A v2 account is recognised by its discriminator and version byte
+    //    and rejected, so migration runs once.
+    // 2. Anything else must deserialize as a v1 `Vault`, discriminator
+    //    included, before the account is touched.
+    // 3. The account is reallocated with zero-fill, and every v2 field is
+    //    written explicitly: v1 fields copied by name, new fields zeroed.
+    pub fn migrate_vault_secure(ctx: Context<MigrateVault>) -> Result<()> {
+        let vault_info = ctx.accounts.vault.to_account_info();
+        {
+            let data = vault_info.try_borrow_data()?;
+            let is_v2 = data.len() == 8 + VaultV2::INIT_SPACE
+                && data[..8] == VaultV2::DISCRIMINATOR
+                && data[8 + 32] == VAULT_V2_VERSION;
+            require!(!is_v2, VaultError::AlreadyMigrated);
+        }
+
+        let old = Vault::try_deserialize(&mut &vault_info.try_borrow_data()?[..])?;
+        require!(old.owner == ctx.accounts.owner.key(), VaultError::Unauthorized);
+
+        grow_vault(&vault_info, &ctx.accounts.owner, &ctx.accounts.system_program, true)?;
+
+        let vault = VaultV2 {
+            owner: old.owner,
+            version: VAULT_V2_VERSION,
+            balance: old.balance,
+            bump: old.bump,
+            migrated_at: Clock::get()?.current_unix_ts().0,
+            open_allowances: old.open_allowances,
+            reserved: [0; 30],
+        };
+        vault.try_serialize(&mut &mut vault_info.try_borrow_mut_data()?[..])?;
+        Ok(())
+    }
+
+    // VULNERABLE: Batch Trusts Every Remaining Account
+    // 1. Vault records arrive as raw `[vault, vault_pda]` pairs and are read
+    //    with `try_deserialize_unchecked`: no program owner, no
+    //    discriminator.
+    // 2. Only `vault.owner` and the balance are checked. Nothing derives
+    //    the record's address from `index`, so any of the owner's records
+    //    can be debited for any of the owner's PDAs.
+    // 3. Keys are not deduplicated, so one record can appear in several
+    //    entries of the same batch.
+    pub fn batch_withdraw_insecure<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchWithdraw<'info>>,
+        withdrawals: Vec<BatchWithdrawal>,
+    ) -> Result<()> {
+        require!(
+            ctx.remaining_accounts.len() == withdrawals.len() * 2,
+            VaultError::BatchLengthMismatch
+        );
+        let owner = &ctx.accounts.owner;
+
+        for (i, info) in ctx.remaining_accounts.iter().enumerate() {
+            if ctx.remaining_accounts[..i].iter().any(|earlier| earlier.key == info.key) {
+                exploit_detected(info.key(), owner.key(), ExploitKind::DuplicateAccount);
+            }
+        }
+
+        for (item, pair) in withdrawals.iter().zip(ctx.remaining_accounts.chunks(2)) {
+            let (vault_info, vault_pda) = (&pair[0], &pair[1]);
+            let index_bytes = item.index.to_le_bytes();
+            if vault_info.owner != &crate::ID {
+                exploit_detected(vault_info.key(), owner.key(), ExploitKind::ForeignVault);
+            }
+            if vault_info.key() != pda(&[b"vault", owner.key.as_ref(), index_bytes.as_ref()]) {
+                exploit_detected(vault_info.key(), owner.key(), ExploitKind::UnexpectedAddress);
+            }
+
+            let mut vault = Vault::try_deserialize_unchecked(&mut &vault_info.try_borrow_data()?[..])?;
+            require!(vault.owner == owner.key(), VaultError::Unauthorized);
+            require!(vault.balance >= item.amount, VaultError::InsufficientFunds);
+            vault.balance = vault.balance.checked_sub(item.amount).unwrap();
+            vault.try_serialize(&mut &mut vault_info.try_borrow_mut_data()?[..])?;
+
+            pay_indexed(vault_pda, owner, &ctx.accounts.system_program, item.index, item.amount)?;
+            emit!(WithdrawEvent {
+                vault: vault_info.key(),
+                recipient: owner.key(),
+                mint: None,
+                amount: item.amount,
+                secure: false,
+            });
+        }
+
+        Ok(())
+    }
+
+    // SECURE: Validate Each Remaining Account Like a Declared One
+    // 1. `Account::<Vault>::try_from` checks the program owner and the
+    //    discriminator, as `Account<'info, Vault>` would in a context.
+    // 2. The record and the PDA must both derive from the owner and the
+    //    entry's `index`, and the record must name the signing owner.
+    // 3. Every key may appear once per batch.
+    pub fn batch_withdraw_secure<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchWithdraw<'info>>,
+        withdrawals: Vec<BatchWithdrawal>,
+    ) -> Result<()> {
+        require!(
+            ctx.remaining_accounts.len() == withdrawals.len() * 2,
+            VaultError::BatchLengthMismatch
+        );
+        let owner = &ctx.accounts.owner;
+
+        let mut seen: Vec<Pubkey> = Vec::with_capacity(ctx.remaining_accounts.len());
+        for info in ctx.remaining_accounts.iter() {
+            require!(!seen.contains(info.key), VaultError::DuplicateAccount);
+            seen.push(info.key());
+        }
+
+        for (item, pair) in withdrawals.iter().zip(ctx.remaining_accounts.chunks(2)) {
+            let (vault_info, vault_pda) = (&pair[0], &pair[1]);
+            let index_bytes = item.index.to_le_bytes();
+
+            let mut vault = Account::<Vault>::try_from(vault_info)?;
+            require_keys_eq!(vault.owner, owner.key(), VaultError::Unauthorized);
+            let expected_vault = Pubkey::create_program_address(
+                &[b"vault", owner.key.as_ref(), index_bytes.as_ref(), &[vault.bump]],
+                &crate::ID,
+            )
+            .map_err(|_| VaultError::InvalidVaultAddress)?;
+            require_keys_eq!(vault_info.key(), expected_vault, VaultError::InvalidVaultAddress);
+            let (expected_pda, _) = Pubkey::find_program_address(
+                &[b"vault_pda", owner.key.as_ref(), index_bytes.as_ref()],
+                &crate::ID,
+            );
+            require_keys_eq!(vault_pda.key(), expected_pda, VaultError::InvalidVaultAddress);
+
+            require!(vault.balance >= item.amount, VaultError::InsufficientFunds);
+            vault.balance = vault.balance.checked_sub(item.amount).unwrap();
+            vault.exit(&crate::ID)?;
+
+            pay_indexed(vault_pda, owner, &ctx.accounts.system_program, item.index, item.amount)?;
+            emit!(WithdrawEvent {
+                vault: vault_info.key(),
+                recipient: owner.key(),
+                mint: None,
+                amount: item.amount,
+                secure: true,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Compares `vault.balance` with the lamports actually in `vault_pda`
+    /// and emits a `VaultReconciled` event. Anyone may call it to flag
+    /// drift. With `repair`, the owner lowers a balance that claims more
+    /// than the PDA holds; a surplus is only flagged, never credited.
+    pub fn reconcile_vault(ctx: Context<ReconcileVault>, repair: bool) -> Result<()> {
+        let tracked = ctx.accounts.vault.balance;
+        let actual = ctx.accounts.vault_pda.lamports();
+        let vault = &mut ctx.accounts.vault;
+
+        let repaired = repair && tracked > actual;
+        if repair {
+            require!(ctx.accounts.caller.key() == vault.owner, VaultError::Unauthorized);
+        }
+        if repaired {
+            vault.balance = actual;
+        }
+        if tracked != actual {
+            msg!("Vault drift: tracked {} lamports, vault_pda holds {}", tracked, actual);
+        }
+
+        emit!(VaultReconciled {
+            vault: vault.key(),
+            tracked,
+            actual,
+            repaired,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+
+    // VULNERABLE: Unauthenticated Ownership Transfer
+    // 1. `caller` signs, but is never compared to `vault.owner`.
+    // 2. `new_owner` comes straight from instruction data and takes effect
+    //    at once, so a typo or a hostile caller locks the real owner out.
+    pub fn vulnerable_transfer_vault_ownership(
+        ctx: Context<VulnerableTransferVaultOwnership>,
+        new_owner: Pubkey,
+    ) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        if ctx.accounts.caller.key() != vault.owner {
+            exploit_detected(vault.key(), ctx.accounts.caller.key(), ExploitKind::UnauthorizedOwnerChange);
+        }
+
+        vault.owner = new_owner;
+        Ok(())
+    }
+
+    // SECURE: Two-Step Ownership Transfer (step 1 of 2)
+    // 1. Only the current owner, signing, can propose a new owner.
+    // 2. The proposal is recorded in its own PDA; nothing changes yet.
+    pub fn propose_vault_owner(ctx: Context<ProposeVaultOwner>, new_owner: Pubkey) -> Result<()> {
+        require_keys_neq!(new_owner, ctx.accounts.owner.key(), VaultError::InvalidNewOwner);
+
+        let proposal = &mut ctx.accounts.ownership_transfer;
+        proposal.vault = ctx.accounts.vault.key();
+        proposal.owner = ctx.accounts.owner.key();
+        proposal.new_owner = new_owner;
+        proposal.bump = ctx.bumps.ownership_transfer;
+        Ok(())
+    }
+
+    /// Withdraws a pending proposal and refunds its rent to the owner.
+    pub fn cancel_vault_owner_transfer(_ctx: Context<CancelVaultOwnerTransfer>) -> Result<()> {
+        Ok(())
+    }
+
+    // SECURE: Two-Step Ownership Transfer (step 2 of 2)
+    // 1. Only the proposed owner, signing, can accept.
+    // 2. Vault addresses are derived from the owner, so the vault is
+    //    re-created at the new owner's seeds and the lamports follow it.
+    // 3. The new vault keeps the old one's layout, so a v2 vault stays v2.
+    // 4. The old vault and the proposal are closed to the previous owner.
+    // 5. So is everything else keyed to the previous owner: the rate limit,
+    //    the token vault, which must be empty, and every open allowance.
+    //    The vault counts them, so none can be left out of
+    //    `remaining_accounts`.
+    pub fn accept_vault_ownership<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AcceptVaultOwnership<'info>>,
+    ) -> Result<()> {
+        let vault = &ctx.accounts.vault;
+        require!(
+            ctx.remaining_accounts.len() == usize::from(vault.open_allowances),
+            VaultError::AllowancesOutstanding
+        );
+
+        let new_vault = &mut ctx.accounts.new_vault;
+        new_vault.owner = ctx.accounts.new_owner.key();
+        new_vault.balance = vault.balance;
+        new_vault.bump = ctx.bumps.new_vault;
+        new_vault.open_allowances = 0;
+        new_vault.migrated_at = vault.migrated_at;
+        new_vault.reserved = vault.reserved;
+
+        let seeds = &[
+            b"vault_pda",
+            ctx.accounts.owner.key.as_ref(),
+            &[ctx.bumps.vault_pda],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault_pda.to_account_info(),
+            to: ctx.accounts.new_vault_pda.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            cpi_accounts,
+            signer
+        );
+
+        transfer(cpi_ctx, ctx.accounts.vault_pda.lamports())?;
+
+        let owner = ctx.accounts.owner.to_account_info();
+        if ctx.accounts.rate_limit.owner == &crate::ID {
+            close_program_account(&ctx.accounts.rate_limit, &owner)?;
+        }
+
+        if ctx.accounts.vault_token.owner == &token::ID {
+            let vault_token = TokenAccount::try_deserialize(&mut &ctx.accounts.vault_token.try_borrow_data()?[..])?;
+            require!(vault_token.amount == 0, VaultError::TokenVaultNotEmpty);
+
+            let seeds = &[
+                b"vault",
+                ctx.accounts.owner.key.as_ref(),
+                &[ctx.accounts.vault.bump],
+            ];
+            let signer = &[&seeds[..]];
+
+            let cpi_accounts = token::CloseAccount {
+                account: ctx.accounts.vault_token.to_account_info(),
+                destination: owner.clone(),
+                authority: ctx.accounts.vault.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer
+            );
+            token::close_account(cpi_ctx)?;
+        }
+
+        // A closed allowance no longer loads, so one passed twice fails
+        // instead of counting twice
+        for info in ctx.remaining_accounts.iter() {
+            let allowance = Account::<Allowance>::try_from(info)?;
+            require_keys_eq!(allowance.owner, owner.key(), VaultError::Unauthorized);
+            allowance.close(owner.clone())?;
+        }
+        Ok(())
+    }
+}
+
+/// This program's PDA for `seeds`.
+fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &crate::ID).0
+}
+
+/// Reports that a vulnerable path just accepted input its secure
+/// counterpart would reject. The call still goes through.
+fn exploit_detected(vault: Pubkey, signer: Pubkey, kind: ExploitKind) {
+    emit!(ExploitDetected { vault, signer, kind });
+}
+
+/// Pays `amount` lamports from the owner's vault PDA number `index`.
+fn pay_indexed<'info>(
+    vault_pda: &AccountInfo<'info>,
+    owner: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    index: u16,
+    amount: u64,
+) -> Result<()> {
+    let index_bytes = index.to_le_bytes();
+    let (_, bump) = Pubkey::find_program_address(
+        &[b"vault_pda", owner.key.as_ref(), index_bytes.as_ref()],
+        &crate::ID,
+    );
+    let seeds = &[
+        b"vault_pda",
+        owner.key.as_ref(),
+        index_bytes.as_ref(),
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: vault_pda.clone(),
+        to: owner.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        system_program.to_account_info(),
+        cpi_accounts,
+        signer
+    );
+
+    transfer(cpi_ctx, amount)
+}
+
+/// Counts a withdrawal of `amount` against the owner's rate limit, if one
+/// is configured. Until `configure_rate_limit` runs, the PDA is empty and
+/// withdrawals are unlimited.
+fn enforce_rate_limit(rate_limit: &AccountInfo, amount: u64) -> Result<()> {
+    if rate_limit.owner != &crate::ID {
+        return Ok(());
+    }
+    let mut limit = RateLimit::try_deserialize(&mut &rate_limit.try_borrow_data()?[..])?;
+    limit.consume(amount, Clock::get()?.current_unix_ts())?;
+    limit.try_serialize(&mut &mut rate_limit.try_borrow_mut_data()?[..])
+}
+
+/// Refunds a program-owned account's rent to `destination` and hands the
+/// emptied account back to the System Program, as `close = ...` does.
+fn close_program_account(info: &AccountInfo, destination: &AccountInfo) -> Result<()> {
+    **destination.try_borrow_mut_lamports()? = destination
+        .lamports()
+        .checked_add(info.lamports())
+        .ok_or(VaultError::ArithmeticError)?;
+    **info.try_borrow_mut_lamports()? = 0;
+    info.assign(&System::id());
+    info.realloc(0, false)?;
+    Ok(())
+}
+
+/// Tops the vault up to rent exemption at the v2 size and reallocates it.
+fn grow_vault<'info>(
+    vault: &AccountInfo<'info>,
+    owner: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    zero_init: bool,
+) -> Result<()> {
+    let new_len = 8 + VaultV2::INIT_SPACE;
+    let shortfall = Rent::get()?.minimum_balance(new_len).saturating_sub(vault.lamports());
+    if shortfall > 0 {
+        let cpi_accounts = Transfer {
+            from: owner.to_account_info(),
+            to: vault.clone(),
+        };
+        transfer(CpiContext::new(system_program.to_account_info(), cpi_accounts), shortfall)?;
+    }
+    vault.realloc(new_len, zero_init)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeVault<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+    #[account(
+        seeds = [b"vault_pda", owner.key().as_ref()],
+        bump
+    )]
+    pub vault_pda: SystemAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner
+    )]
+    pub vault: Account<'info, VersionedVault>,
+    #[account(
+        mut,
+        seeds = [b"vault_pda", owner.key().as_ref()],
+        bump
+    )]
+    pub vault_pda: SystemAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawInsecure<'info> {
+    // VULNERABLE: AccountInfo skips all Anchor safety checks
+    /// CHECK: Unsafe. Any account data can be passed here.
+    #[account(mut)] 
+    pub vault: AccountInfo<'info>,
+    
+    // VULNERABLE: No seeds check to verify PDA derivation
+    /// CHECK: Unsafe. No relationship to vault is enforced.
+    #[account(mut)]
+    pub vault_pda: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawSecure<'info> {
+    // SECURE: Account wrapper validates Owner and Discriminator
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::Unauthorized
+    )]
+    pub vault: Account<'info, VersionedVault>,
+    
+    // SECURE: Seeds constraint validates PDA derivation
+    #[account(
+        mut,
+        seeds = [b"vault_pda", owner.key().as_ref()],
+        bump
+    )]
+    pub vault_pda: SystemAccount<'info>,
+
+    // SECURE: Seeds pin the owner's limit, so it cannot be left out
+    /// CHECK: Empty until `configure_rate_limit`; loaded in the handler.
+    #[account(
+        mut,
+        seeds = [b"rate_limit", owner.key().as_ref()],
+        bump
+    )]
+    pub rate_limit: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u16)]
+pub struct InitializeVaultIndexed<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", owner.key().as_ref(), index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+    #[account(
+        seeds = [b"vault_pda", owner.key().as_ref(), index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault_pda: SystemAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u16)]
+pub struct DepositIndexed<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref(), index.to_le_bytes().as_ref()],
+        bump = vault.bump,
+        has_one = owner
+    )]
+    pub vault: Account<'info, Vault>,
+    #[account(
+        mut,
+        seeds = [b"vault_pda", owner.key().as_ref(), index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault_pda: SystemAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u16)]
+pub struct WithdrawIndexedInsecure<'info> {
+    // VULNERABLE: No seeds, so the index never pins which vault this is
+    #[account(
+        mut,
+        has_one = owner @ VaultError::Unauthorized
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_pda", owner.key().as_ref(), index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault_pda: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u16)]
+pub struct WithdrawIndexedSecure<'info> {
+    // SECURE: The index is part of the vault's seeds
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref(), index.to_le_bytes().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::Unauthorized
+    )]
+    pub vault: Account<'info, Vault>,
+
+    // SECURE: And of the PDA holding its lamports
+    #[account(
+        mut,
+        seeds = [b"vault_pda", owner.key().as_ref(), index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault_pda: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeTokenVault<'info> {
+    #[account(
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner
+    )]
+    pub vault: Account<'info, VersionedVault>,
+    // The Vault PDA is the token account's authority
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"vault_token", owner.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault
+    )]
+    pub vault_token: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault_token", owner.key().as_ref()],
+        bump
+    )]
+    pub vault_token: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub source: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTokensInsecure<'info> {
+    // VULNERABLE: AccountInfo skips all Anchor safety checks
+    /// CHECK: Unsafe. Any account data can be passed here.
+    pub vault: AccountInfo<'info>,
+
+    // VULNERABLE: Supplies the signer seeds; never tied to `vault`
+    /// CHECK: Unsafe. Any pubkey can be passed here.
+    pub vault_owner: AccountInfo<'info>,
+
+    // VULNERABLE: The PDA that signs; its derivation is checked only by the CPI
+    /// CHECK: Unsafe. No relationship to vault is enforced.
+    pub vault_authority: AccountInfo<'info>,
+
+    // VULNERABLE: No seeds or authority check on either token account
+    /// CHECK: Unsafe. Any token account can be passed here.
+    #[account(mut)]
+    pub vault_token: AccountInfo<'info>,
+    /// CHECK: Unsafe. Any token account can be passed here.
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTokensSecure<'info> {
+    // SECURE: Account wrapper validates Owner and Discriminator
+    #[account(
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::Unauthorized
+    )]
+    pub vault: Account<'info, VersionedVault>,
+
+    // SECURE: Seeds and authority constraints validate the token account
+    #[account(
+        mut,
+        seeds = [b"vault_token", owner.key().as_ref()],
+        bump,
+        token::authority = vault
+    )]
+    pub vault_token: Account<'info, TokenAccount>,
+
+    #[account(mut, token::mint = vault_token.mint)]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableCloseVault<'info> {
+    // VULNERABLE: No seeds or has_one check on the vault being closed
+    /// CHECK: Unsafe. Any vault can be passed here.
+    #[account(mut)]
+    pub vault: AccountInfo<'info>,
+
+    // VULNERABLE: The rent goes wherever the caller says
+    /// CHECK: Unsafe. No relationship to vault.owner is enforced.
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SecureCloseVault<'info> {
+    // SECURE: Owner verified, rent refunded to the owner, data wiped
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::Unauthorized,
+        constraint = vault.balance == 0 @ VaultError::VaultNotEmpty,
+        constraint = vault.open_allowances == 0 @ VaultError::AllowancesOutstanding,
+        close = owner
+    )]
+    pub vault: Account<'info, VersionedVault>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveDelegate<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::Unauthorized
+    )]
+    pub vault: Account<'info, VersionedVault>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Allowance::INIT_SPACE,
+        seeds = [b"allowance", owner.key().as_ref(), delegate.key().as_ref()],
+        bump
+    )]
+    pub allowance: Account<'info, Allowance>,
+    /// CHECK: Only its key is recorded.
+    pub delegate: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeDelegate<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::Unauthorized
+    )]
+    pub vault: Account<'info, VersionedVault>,
+    #[account(
+        mut,
+        seeds = [b"allowance", owner.key().as_ref(), allowance.delegate.as_ref()],
+        bump = allowance.bump,
+        has_one = owner @ VaultError::Unauthorized,
+        close = owner
+    )]
+    pub allowance: Account<'info, Allowance>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DelegatedWithdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::Unauthorized
+    )]
+    pub vault: Account<'info, VersionedVault>,
+    #[account(
+        mut,
+        seeds = [b"vault_pda", owner.key().as_ref()],
+        bump
+    )]
+    pub vault_pda: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"allowance", owner.key().as_ref(), delegate.key().as_ref()],
+        bump = allowance.bump,
+        has_one = owner @ VaultError::Unauthorized,
+        has_one = delegate @ VaultError::Unauthorized
+    )]
+    pub allowance: Account<'info, Allowance>,
+    /// CHECK: Empty until `configure_rate_limit`; only the secure
+    /// handler loads it.
+    #[account(
+        mut,
+        seeds = [b"rate_limit", owner.key().as_ref()],
+        bump
+    )]
+    pub rate_limit: UncheckedAccount<'info>,
+    /// CHECK: Pinned by the vault and allowance seeds.
+    pub owner: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureRateLimit<'info> {
+    #[account(
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::Unauthorized
+    )]
+    pub vault: Account<'info, VersionedVault>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + RateLimit::INIT_SPACE,
+        seeds = [b"rate_limit", owner.key().as_ref()],
+        bump
+    )]
+    pub rate_limit: Account<'info, RateLimit>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RateLimitedWithdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::Unauthorized
+    )]
+    pub vault: Account<'info, VersionedVault>,
+    #[account(
+        mut,
+        seeds = [b"vault_pda", owner.key().as_ref()],
+        bump
+    )]
+    pub vault_pda: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"rate_limit", owner.key().as_ref()],
+        bump = rate_limit.bump,
+        has_one = owner @ VaultError::Unauthorized
+    )]
+    pub rate_limit: Account<'info, RateLimit>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    // Raw account: it holds v1 bytes on the way in and v2 bytes on the way
+    // out, so neither `Account<Vault>` nor `Account<VaultV2>` fits
+    /// CHECK: Pinned by seeds; the layout is checked in the handler.
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReconcileVault<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.owner.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, VersionedVault>,
+    #[account(
+        seeds = [b"vault_pda", vault.owner.as_ref()],
+        bump
+    )]
+    pub vault_pda: SystemAccount<'info>,
+    /// Any signer may flag drift; only the vault owner may repair it.
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableTransferVaultOwnership<'info> {
+    // VULNERABLE: No seeds or has_one, so any vault can be rewritten
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeVaultOwner<'info> {
+    #[account(
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::Unauthorized
+    )]
+    pub vault: Account<'info, VersionedVault>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + OwnershipTransfer::INIT_SPACE,
+        seeds = [b"ownership_transfer", vault.key().as_ref()],
+        bump
+    )]
+    pub ownership_transfer: Account<'info, OwnershipTransfer>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelVaultOwnerTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"ownership_transfer", ownership_transfer.vault.as_ref()],
+        bump = ownership_transfer.bump,
+        has_one = owner @ VaultError::Unauthorized,
+        close = owner
+    )]
+    pub ownership_transfer: Account<'info, OwnershipTransfer>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptVaultOwnership<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::Unauthorized,
+        close = owner
+    )]
+    pub vault: Account<'info, VersionedVault>,
+    #[account(
+        mut,
+        seeds = [b"vault_pda", owner.key().as_ref()],
+        bump
+    )]
+    pub vault_pda: SystemAccount<'info>,
+    // SECURE: The proposal must name both this vault and the signer
+    #[account(
+        mut,
+        seeds = [b"ownership_transfer", vault.key().as_ref()],
+        bump = ownership_transfer.bump,
+        has_one = owner @ VaultError::Unauthorized,
+        has_one = new_owner @ VaultError::Unauthorized,
+        close = owner
+    )]
+    pub ownership_transfer: Account<'info, OwnershipTransfer>,
+    // SECURE: `init` fails if the new owner already has a vault
+    #[account(
+        init,
+        payer = new_owner,
+        space = vault.space(),
+        seeds = [b"vault", new_owner.key().as_ref()],
+        bump
+    )]
+    pub new_vault: Account<'info, VersionedVault>,
+    #[account(
+        mut,
+        seeds = [b"vault_pda", new_owner.key().as_ref()],
+        bump
+    )]
+    pub new_vault_pda: SystemAccount<'info>,
+    // SECURE: The previous owner's limit and token vault are pinned by
+    // seeds, so the new owner cannot leave them behind
+    /// CHECK: Empty unless `configure_rate_limit` ran; closed in the handler.
+    #[account(
+        mut,
+        seeds = [b"rate_limit", owner.key().as_ref()],
+        bump
+    )]
+    pub rate_limit: UncheckedAccount<'info>,
+    /// CHECK: Empty unless `initialize_token_vault` ran; closed in the handler.
+    #[account(
+        mut,
+        seeds = [b"vault_token", owner.key().as_ref()],
+        bump
+    )]
+    pub vault_token: UncheckedAccount<'info>,
+    /// CHECK: Pinned by the vault and proposal seeds; receives their rent.
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub new_owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Vaults are passed as `remaining_accounts` in `[vault, vault_pda]`
+/// pairs, one pair per entry of `withdrawals`.
+#[derive(Accounts)]
+pub struct BatchWithdraw<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// One entry of a batch withdrawal: `amount` lamports from vault `index`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BatchWithdrawal {
+    pub index: u16,
+    pub amount: u64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub owner: Pubkey,
+    pub balance: u64,
+    pub bump: u8,
+    /// Allowances approved and not yet revoked.
+    pub open_allowances: u16,
+}
+
+/// The v2 vault layout. `owner` stays first; the version byte follows it.
+#[account]
+#[derive(InitSpace)]
+pub struct VaultV2 {
+    pub owner: Pubkey,
+    /// Always `VAULT_V2_VERSION`.
+    pub version: u8,
+    pub balance: u64,
+    pub bump: u8,
+    pub migrated_at: i64,
+    /// Allowances approved and not yet revoked.
+    pub open_allowances: u16,
+    /// Zeroed space for later fields, so the next migration needs no realloc.
+    pub reserved: [u8; 30],
+}
+
+/// The owner's main vault in either layout, so every handler keeps working
+/// after `migrate_vault_secure`. It accepts the `Vault` or `VaultV2`
+/// discriminator and writes back in the layout it read.
+#[derive(Clone)]
+pub struct VersionedVault {
+    pub owner: Pubkey,
+    pub balance: u64,
+    pub bump: u8,
+    pub open_allowances: u16,
+    /// `None` for a v1 vault.
+    pub migrated_at: Option<i64>,
+    reserved: [u8; 30],
+}
+
+impl VersionedVault {
+    /// Account size for the layout this vault was read from.
+    pub fn space(&self) -> usize {
+        match self.migrated_at {
+            None => 8 + Vault::INIT_SPACE,
+            Some(_) => 8 + VaultV2::INIT_SPACE,
+        }
+    }
+}
+
+impl From<Vault> for VersionedVault {
+    fn from(vault: Vault) -> Self {
+        Self {
+            owner: vault.owner,
+            balance: vault.balance,
+            bump: vault.bump,
+            open_allowances: vault.open_allowances,
+            migrated_at: None,
+            reserved: [0; 30],
+        }
+    }
+}
+
+impl From<VaultV2> for VersionedVault {
+    fn from(vault: VaultV2) -> Self {
+        Self {
+            owner: vault.owner,
+            balance: vault.balance,
+            bump: vault.bump,
+            open_allowances: vault.open_allowances,
+            migrated_at: Some(vault.migrated_at),
+            reserved: vault.reserved,
+        }
+    }
+}
+
+impl AccountDeserialize for VersionedVault {
+    fn try_deserialize(buf: &mut &[u8]) -> Result<Self> {
+        if !buf.starts_with(&VaultV2::DISCRIMINATOR) {
+            return Vault::try_deserialize(buf).map(Self::from);
+        }
+        let vault = VaultV2::try_deserialize(buf)?;
+        // `migrate_vault_insecure` also writes the v2 discriminator, over
+        // shifted fields; its version byte is whatever the balance left
+        require!(vault.version == VAULT_V2_VERSION, ErrorCode::AccountDidNotDeserialize);
+        Ok(Self::from(vault))
+    }
+
+    /// Picks the layout by size alone. Only `init` calls this, on a zeroed
+    /// account created at `space()`.
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
+        if buf.len() == 8 + VaultV2::INIT_SPACE {
+            VaultV2::try_deserialize_unchecked(buf).map(Self::from)
+        } else {
+            Vault::try_deserialize_unchecked(buf).map(Self::from)
+        }
+    }
+}
+
+impl AccountSerialize for VersionedVault {
+    fn try_serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<()> {
+        match self.migrated_at {
+            None => Vault {
+                owner: self.owner,
+                balance: self.balance,
+                bump: self.bump,
+                open_allowances: self.open_allowances,
+            }
+            .try_serialize(writer),
+            Some(migrated_at) => VaultV2 {
+                owner: self.owner,
+                version: VAULT_V2_VERSION,
+                balance: self.balance,
+                bump: self.bump,
+                migrated_at,
+                open_allowances: self.open_allowances,
+                reserved: self.reserved,
+            }
+            .try_serialize(writer),
+        }
+    }
+}
+
+impl Owner for VersionedVault {
+    fn owner() -> Pubkey {
+        crate::ID
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Allowance {
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub remaining_amount: u64,
+    pub expiry: i64,
+    pub bump: u8,
+}
+
+/// A proposed change of vault owner, waiting for the new owner to accept.
+#[account]
+#[derive(InitSpace)]
+pub struct OwnershipTransfer {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RateLimit {
+    pub owner: Pubkey,
+    pub max_per_window: u64,
+    pub window_secs: i64,
+    /// Time `used` was last brought up to date.
+    pub checkpoint_ts: i64,
+    /// Lamports counted against the limit as of `checkpoint_ts`.
+    pub used: u64,
+    pub bump: u8,
+}
+
+impl RateLimit {
+    /// Counts `amount` against the limit at time `now`. `used` drains at
+    /// `max_per_window` per `window_secs` since the checkpoint, and the
+    /// checkpoint then moves to `now`.
+    pub fn consume(&mut self, amount: u64, now: UnixTs) -> Result<()> {
+        let elapsed = now
+            .secs_since(UnixTs(self.checkpoint_ts))
+            .ok_or(VaultError::ClockWentBackwards)?
+            .min(self.window_secs);
+        let released = mul_div_floor(self.max_per_window, elapsed as u64, self.window_secs as u64)
+            .ok_or(VaultError::ArithmeticError)?;
+        let used = self
+            .used
+            .saturating_sub(released)
+            .checked_add(amount)
+            .ok_or(VaultError::ArithmeticError)?;
+        require!(used <= self.max_per_window, VaultError::RateLimitExceeded);
+        self.used = used;
+        self.checkpoint_ts = now.0;
+        Ok(())
+    }
+}
+
+#[event]
+pub struct DepositEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    /// `None` for SOL deposits.
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+}
+
+#[event]
+pub struct WithdrawEvent {
+    pub vault: Pubkey,
+    pub recipient: Pubkey,
+    /// `None` for SOL withdrawals.
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    /// Whether a secure instruction made the withdrawal.
+    pub secure: bool,
+}
+
+/// What a vulnerable path let through that its secure counterpart rejects.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ExploitKind {
+    /// Vault record not owned by this program.
+    ForeignVault,
+    /// Vault record or PDA not at the address derived from the signer.
+    UnexpectedAddress,
+    /// Vault closed by someone other than its owner, or rent sent elsewhere.
+    RentRedirected,
+    /// Delegate withdrew on an expired allowance.
+    ExpiredAllowance,
+    /// Caller-supplied time ahead of the Clock sysvar.
+    FutureTimestamp,
+    /// v1 bytes read as the v2 layout.
+    LayoutMismatch,
+    /// Same account passed more than once in a batch.
+    DuplicateAccount,
+    /// Vault owner rewritten by someone other than the owner.
+    UnauthorizedOwnerChange,
+}
+
+#[event]
+pub struct ExploitDetected {
+    pub vault: Pubkey,
+    pub signer: Pubkey,
+    pub kind: ExploitKind,
+}
+
+#[event]
+pub struct VaultReconciled {
+    pub vault: Pubkey,
+    /// `vault.balance` before any repair.
+    pub tracked: u64,
+    /// Lamports held by `vault_pda`.
+    pub actual: u64,
+    pub repaired: bool,
+    pub slot: u64,
 }
 
 #[error_code]
@@ -195,4 +1911,32 @@ pub enum VaultError {
     Unauthorized,
     #[msg("Insufficient funds")]
     InsufficientFunds,
+    #[msg("Vault still holds a balance")]
+    VaultNotEmpty,
+    #[msg("Amount exceeds the remaining allowance")]
+    AllowanceExceeded,
+    #[msg("Allowance has expired")]
+    AllowanceExpired,
+    #[msg("Rate limit window must be positive")]
+    InvalidWindow,
+    #[msg("Withdrawal exceeds the rate limit")]
+    RateLimitExceeded,
+    #[msg("Clock moved backwards since the last checkpoint")]
+    ClockWentBackwards,
+    #[msg("Vault is already on the v2 layout")]
+    AlreadyMigrated,
+    #[msg("Expected one vault and vault_pda pair per withdrawal")]
+    BatchLengthMismatch,
+    #[msg("Account appears more than once in the batch")]
+    DuplicateAccount,
+    #[msg("Vault address does not match its owner and index")]
+    InvalidVaultAddress,
+    #[msg("New owner must differ from the current owner")]
+    InvalidNewOwner,
+    #[msg("Token vault still holds tokens")]
+    TokenVaultNotEmpty,
+    #[msg("Every open allowance must be revoked or passed in to be closed")]
+    AllowancesOutstanding,
+    #[msg("Arithmetic error")]
+    ArithmeticError,
 }
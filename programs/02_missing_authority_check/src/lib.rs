@@ -1,17 +1,85 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::Discriminator;
+use security_common::time::{ClockExt, Epoch, Slot, UnixTs};
 
 declare_id!("HmbTLCmaGvZhKnn1Zfa1JVnp7vkMV4DYVxPLWBVoN65L");
 
+/// Most owners a multisig can have; also the most approvals a proposal
+/// can record.
+pub const MAX_MULTISIG_OWNERS: usize = 10;
+
+/// Entries the audit log keeps before overwriting the oldest.
+pub const AUDIT_LOG_LEN: usize = 16;
+
+/// Most destinations an emergency withdrawal can be sent to.
+pub const MAX_ALLOWED_DESTINATIONS: usize = 4;
+
+/// Most the fee can rise, in bps, within one epoch through
+/// `Config::set_fee`. Decreases are not limited.
+pub const MAX_FEE_INCREASE_PER_EPOCH_BPS: u16 = 100;
+
+/// `Config::version` for the current layout, written by `initialize` and
+/// by a migration from `ConfigV1`.
+pub const CONFIG_V2_VERSION: u8 = 2;
+
+/// Session key permission bits.
+pub const PERMISSION_SET_FEE: u8 = 1 << 0;
+pub const PERMISSION_SET_PAUSED: u8 = 1 << 1;
+
 #[program]
 pub mod protocol_config {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>, initial_fee_bps: u16) -> Result<()> {
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        initial_fee_bps: u16,
+        min_delay_secs: i64,
+        guardian: Pubkey,
+        treasury: Pubkey,
+        max_fee_bps: u16,
+    ) -> Result<()> {
+        require!(min_delay_secs >= 0, ConfigError::InvalidDelay);
+        require_keys_neq!(guardian, ctx.accounts.admin.key(), ConfigError::InvalidGuardian);
+        require!(
+            initial_fee_bps <= max_fee_bps && max_fee_bps <= 10_000,
+            ConfigError::InvalidFeeCap
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.guardian = guardian;
+        config.pending_admin = None;
+        config.nomination_expiry = 0;
+        config.fee_bps = initial_fee_bps;
+        config.is_paused = false;
+        config.min_delay_secs = min_delay_secs;
+        config.last_fee_change_epoch = 0;
+        config.fee_change_this_epoch = 0;
+        config.bump = ctx.bumps.config;
+        config.version = CONFIG_V2_VERSION;
+        config.treasury = treasury;
+        config.max_fee_bps = max_fee_bps;
+
+        let audit_log = &mut ctx.accounts.audit_log;
+        audit_log.config = config.key();
+        audit_log.bump = ctx.bumps.audit_log;
+        Ok(())
+    }
+
+    /// Creates a config in the layout this program used before it had a
+    /// version, standing in for one a pre-upgrade deploy left behind. It
+    /// lives at its own address, so it never shadows the live config.
+    pub fn initialize_legacy_config(ctx: Context<InitializeLegacyConfig>, initial_fee_bps: u16) -> Result<()> {
         let config = &mut ctx.accounts.config;
         config.admin = ctx.accounts.admin.key();
         config.pending_admin = None;
         config.fee_bps = initial_fee_bps;
         config.bump = ctx.bumps.config;
+
+        let audit_log = &mut ctx.accounts.audit_log;
+        audit_log.config = config.key();
+        audit_log.bump = ctx.bumps.audit_log;
         Ok(())
     }
 
@@ -43,12 +111,645 @@ pub mod protocol_config {
         Ok(())
     }
 
-    // SECURE: Signer Check + Ownership Check
+    // VULNERABLE: Unbounded Fee Change
+    // The admin is checked properly, but can move the fee to any value in
+    // one transaction. Users who deposited at 1% can be charged 50% on
+    // their next withdrawal, with no time to leave.
+    pub fn vulnerable_unbounded_update_fee(ctx: Context<UnboundedUpdateFee>, new_fee_bps: u16) -> Result<()> {
+        ctx.accounts.config.fee_bps = new_fee_bps;
+        Ok(())
+    }
+
+    // SECURE: Signer Check + Ownership Check + Per-Epoch Bound
+    // Increases within one epoch add up to at most
+    // `MAX_FEE_INCREASE_PER_EPOCH_BPS`.
     pub fn secure_update_fee(ctx: Context<SecureUpdateFee>, new_fee_bps: u16) -> Result<()> {
+        let old_fee_bps = ctx.accounts.config.set_fee(new_fee_bps, Clock::get()?.current_epoch())?;
+
+        ctx.accounts.audit_log.append(
+            ctx.accounts.admin.key(),
+            AuditAction::SetFee,
+            old_fee_bps.into(),
+            new_fee_bps.into(),
+        )
+    }
+
+    /// Step 1 of the two-step transfer: the admin nominates a successor,
+    /// who can accept until `valid_for_slots` slots from now.
+    pub fn nominate_admin(ctx: Context<NominateAdmin>, new_admin: Pubkey, valid_for_slots: u64) -> Result<()> {
+        require!(valid_for_slots > 0, ConfigError::InvalidNominationWindow);
+
         let config = &mut ctx.accounts.config;
-        config.fee_bps = new_fee_bps;
+        let old_nominee = config.pending_admin.replace(new_admin);
+        config.nomination_expiry = Clock::get()?
+            .current_slot()
+            .checked_add_slots(valid_for_slots)
+            .ok_or(ConfigError::ArithmeticError)?
+            .0;
+
+        ctx.accounts.audit_log.append(
+            ctx.accounts.admin.key(),
+            AuditAction::NominateAdmin,
+            old_nominee.as_ref().map_or(0, key_prefix),
+            key_prefix(&new_admin),
+        )
+    }
+
+    /// Withdraws a nomination before it is accepted.
+    pub fn cancel_nomination(ctx: Context<NominateAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let nominee = config.pending_admin.take().ok_or(ConfigError::NoPendingNomination)?;
+        config.nomination_expiry = 0;
+
+        ctx.accounts.audit_log.append(
+            ctx.accounts.admin.key(),
+            AuditAction::CancelNomination,
+            key_prefix(&nominee),
+            0,
+        )
+    }
+
+    // VULNERABLE: Nomination Never Expires
+    // The nominee must sign, but `nomination_expiry` is never read. A
+    // nomination the admin forgot about, or made for a key since lost or
+    // sold, can be accepted months later.
+    pub fn vulnerable_accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(
+            config.pending_admin == Some(ctx.accounts.new_admin.key()),
+            ConfigError::NoPendingNomination
+        );
+
+        config.admin = ctx.accounts.new_admin.key();
+        config.pending_admin = None;
+        Ok(())
+    }
+
+    // SECURE: Nomination Expires
+    // 1. Only the nominee, signing, can accept.
+    // 2. The Clock slot must not be past `nomination_expiry`.
+    // 3. The change is recorded in the audit log.
+    pub fn secure_accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(
+            config.pending_admin == Some(ctx.accounts.new_admin.key()),
+            ConfigError::NoPendingNomination
+        );
+        require!(
+            Clock::get()?.current_slot() <= Slot(config.nomination_expiry),
+            ConfigError::NominationExpired
+        );
+
+        let old_admin = std::mem::replace(&mut config.admin, ctx.accounts.new_admin.key());
+        config.pending_admin = None;
+        config.nomination_expiry = 0;
+
+        ctx.accounts.audit_log.append(
+            ctx.accounts.new_admin.key(),
+            AuditAction::SetAdmin,
+            key_prefix(&old_admin),
+            key_prefix(ctx.accounts.new_admin.key),
+        )
+    }
+
+    /// Gives the config admin the `Admin` role, which grants and revokes
+    /// every other role.
+    pub fn initialize_roles(ctx: Context<InitializeRoles>) -> Result<()> {
+        let grant = &mut ctx.accounts.admin_role;
+        grant.holder = ctx.accounts.admin.key();
+        grant.role = Role::Admin;
+        grant.granted_by = ctx.accounts.admin.key();
+        grant.bump = ctx.bumps.admin_role;
+
+        let admin = ctx.accounts.admin.key();
+        ctx.accounts.audit_log.append(admin, AuditAction::GrantRole, Role::Admin as u64, key_prefix(&admin))
+    }
+
+    pub fn grant_role(ctx: Context<GrantRole>, role: Role) -> Result<()> {
+        let grant = &mut ctx.accounts.role_grant;
+        grant.holder = ctx.accounts.holder.key();
+        grant.role = role;
+        grant.granted_by = ctx.accounts.authority.key();
+        grant.bump = ctx.bumps.role_grant;
+
+        ctx.accounts.audit_log.append(
+            ctx.accounts.authority.key(),
+            AuditAction::GrantRole,
+            role as u64,
+            key_prefix(ctx.accounts.holder.key),
+        )
+    }
+
+    pub fn revoke_role(ctx: Context<RevokeRole>) -> Result<()> {
+        let grant = &ctx.accounts.role_grant;
+        let (role, holder) = (grant.role as u64, key_prefix(&grant.holder));
+        ctx.accounts.audit_log.append(ctx.accounts.authority.key(), AuditAction::RevokeRole, role, holder)
+    }
+
+    // VULNERABLE: Role Check Without Signature
+    // The role account is real, but `pauser` is an AccountInfo. Anyone can
+    // pass a pauser's pubkey together with their role account.
+    pub fn vulnerable_set_paused(ctx: Context<VulnerableSetPaused>, paused: bool) -> Result<()> {
+        let grant = &ctx.accounts.role_grant;
+        require!(
+            grant.holder == ctx.accounts.pauser.key() && grant.role == Role::Pauser,
+            ConfigError::Unauthorized
+        );
+
+        ctx.accounts.config.is_paused = paused;
+        Ok(())
+    }
+
+    // SECURE: Signer + Role PDA
+    // The role account must sit at the Pauser seeds for the signing key.
+    pub fn secure_set_paused(ctx: Context<SecureSetPaused>, paused: bool) -> Result<()> {
+        let was_paused = ctx.accounts.config.is_paused;
+        ctx.accounts.config.is_paused = paused;
+
+        ctx.accounts.audit_log.append(
+            ctx.accounts.pauser.key(),
+            AuditAction::SetPaused,
+            was_paused.into(),
+            paused.into(),
+        )
+    }
+
+    // VULNERABLE: Role Check Without Signature
+    pub fn vulnerable_set_fee_as_manager(
+        ctx: Context<VulnerableSetFeeAsManager>,
+        new_fee_bps: u16,
+    ) -> Result<()> {
+        let grant = &ctx.accounts.role_grant;
+        require!(
+            grant.holder == ctx.accounts.fee_manager.key() && grant.role == Role::FeeManager,
+            ConfigError::Unauthorized
+        );
+
+        ctx.accounts.config.fee_bps = new_fee_bps;
+        Ok(())
+    }
+
+    // SECURE: Signer + Role PDA
+    // The fee manager is held to the same per-epoch bound as the admin.
+    pub fn secure_set_fee_as_manager(
+        ctx: Context<SecureSetFeeAsManager>,
+        new_fee_bps: u16,
+    ) -> Result<()> {
+        let old_fee_bps = ctx.accounts.config.set_fee(new_fee_bps, Clock::get()?.current_epoch())?;
+
+        ctx.accounts.audit_log.append(
+            ctx.accounts.fee_manager.key(),
+            AuditAction::SetFee,
+            old_fee_bps.into(),
+            new_fee_bps.into(),
+        )
+    }
+
+    /// Queues `change`, executable once `config.min_delay_secs` has passed.
+    /// Only one change can be pending at a time.
+    pub fn queue_config_change(ctx: Context<QueueConfigChange>, change: ConfigChange) -> Result<()> {
+        if let ConfigChange::SetMinDelay { min_delay_secs } = change {
+            require!(min_delay_secs >= 0, ConfigError::InvalidDelay);
+        }
+        let queued_at = Clock::get()?.current_unix_ts();
+
+        let pending = &mut ctx.accounts.pending_change;
+        pending.change = change;
+        pending.queued_at = queued_at.0;
+        pending.eta = queued_at
+            .checked_add_secs(ctx.accounts.config.min_delay_secs)
+            .ok_or(ConfigError::ArithmeticError)?
+            .0;
+        pending.bump = ctx.bumps.pending_change;
+
+        let (current, queued) = change.audit_values(&ctx.accounts.config);
+        ctx.accounts.audit_log.append(ctx.accounts.admin.key(), AuditAction::QueueChange, current, queued)
+    }
+
+    // VULNERABLE: Caller-Supplied Time
+    // The admin signs and the change was queued, but the delay is checked
+    // against `now` from instruction data. Passing any time past the ETA
+    // executes the change at once, and users get no window to react.
+    pub fn vulnerable_execute_config_change(ctx: Context<ExecuteConfigChange>, now: i64) -> Result<()> {
+        require!(now >= ctx.accounts.pending_change.eta, ConfigError::TimelockNotExpired);
+
+        ctx.accounts.apply()?;
+        Ok(())
+    }
+
+    // SECURE: Clock Sysvar
+    // The ETA is compared with the cluster's clock, which the caller
+    // cannot choose.
+    pub fn secure_execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+        let now = Clock::get()?.current_unix_ts();
+        require!(now >= UnixTs(ctx.accounts.pending_change.eta), ConfigError::TimelockNotExpired);
+
+        let (action, old_value, new_value) = ctx.accounts.apply()?;
+        let admin = ctx.accounts.admin.key();
+        ctx.accounts.audit_log.append(admin, action, old_value, new_value)
+    }
+
+    /// Drops the pending change and refunds its rent to the admin.
+    pub fn cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+        let (current, queued) = ctx.accounts.pending_change.change.audit_values(&ctx.accounts.config);
+        ctx.accounts.audit_log.append(ctx.accounts.admin.key(), AuditAction::CancelChange, current, queued)
+    }
+
+    /// The guardian blocks a queued change. The admin proposes, the guardian
+    /// can only say no: it has no instruction that changes `Config` itself.
+    pub fn veto_pending_change(ctx: Context<VetoPendingChange>) -> Result<()> {
+        let (current, queued) = ctx.accounts.pending_change.change.audit_values(&ctx.accounts.config);
+        ctx.accounts.audit_log.append(ctx.accounts.guardian.key(), AuditAction::VetoChange, current, queued)
+    }
+
+    /// Lets `owners` run admin actions once `threshold` of them approve.
+    /// The single admin keeps working alongside it.
+    pub fn create_multisig(ctx: Context<CreateMultisig>, owners: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        require!(owners.len() <= MAX_MULTISIG_OWNERS, ConfigError::TooManyOwners);
+        require!(
+            threshold > 0 && threshold as usize <= owners.len(),
+            ConfigError::InvalidThreshold
+        );
+        for (i, owner) in owners.iter().enumerate() {
+            require!(!owners[..i].contains(owner), ConfigError::DuplicateOwner);
+        }
+
+        let owner_count = owners.len() as u64;
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.config = ctx.accounts.config.key();
+        multisig.owners = owners;
+        multisig.threshold = threshold;
+        multisig.proposal_count = 0;
+        multisig.bump = ctx.bumps.multisig;
+
+        ctx.accounts.audit_log.append(
+            ctx.accounts.admin.key(),
+            AuditAction::CreateMultisig,
+            owner_count,
+            threshold.into(),
+        )
+    }
+
+    pub fn propose_admin_action(ctx: Context<ProposeAdminAction>, action: AdminAction) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        require!(
+            multisig.owners.contains(ctx.accounts.proposer.key),
+            ConfigError::NotMultisigOwner
+        );
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.multisig = multisig.key();
+        proposal.id = multisig.proposal_count;
+        proposal.action = action;
+        proposal.approvals = Vec::new();
+        proposal.executed = false;
+        proposal.bump = ctx.bumps.proposal;
+
+        multisig.proposal_count = multisig
+            .proposal_count
+            .checked_add(1)
+            .ok_or(ConfigError::ArithmeticError)?;
+        Ok(())
+    }
+
+    // VULNERABLE: Repeat Approvals Recorded
+    // Every call appends the signer, so one owner can fill `approvals`
+    // up to `MAX_MULTISIG_OWNERS` and lock every other owner out with
+    // `ProposalFull`.
+    pub fn vulnerable_approve(ctx: Context<Approve>) -> Result<()> {
+        require!(
+            ctx.accounts.multisig.owners.contains(ctx.accounts.owner.key),
+            ConfigError::NotMultisigOwner
+        );
+        let proposal = &mut ctx.accounts.proposal;
+        require!(!proposal.executed, ConfigError::AlreadyExecuted);
+        require!(proposal.approvals.len() < MAX_MULTISIG_OWNERS, ConfigError::ProposalFull);
+
+        proposal.approvals.push(ctx.accounts.owner.key());
+        Ok(())
+    }
+
+    // SECURE: One Approval per Owner
+    // A repeat approver is rejected before the push, so the list holds
+    // distinct owners and cannot fill up before they have all approved.
+    pub fn secure_approve(ctx: Context<Approve>) -> Result<()> {
+        require!(
+            ctx.accounts.multisig.owners.contains(ctx.accounts.owner.key),
+            ConfigError::NotMultisigOwner
+        );
+        let proposal = &mut ctx.accounts.proposal;
+        require!(!proposal.executed, ConfigError::AlreadyExecuted);
+        require!(
+            !proposal.approvals.contains(ctx.accounts.owner.key),
+            ConfigError::AlreadyApproved
+        );
+        require!(proposal.approvals.len() < MAX_MULTISIG_OWNERS, ConfigError::ProposalFull);
+
+        proposal.approvals.push(ctx.accounts.owner.key());
+        Ok(())
+    }
+
+    // VULNERABLE: Approvals Counted, Not Approvers
+    // `approvals.len()` counts every entry, so one owner approving
+    // `threshold` times meets the threshold alone.
+    pub fn vulnerable_execute(ctx: Context<Execute>) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        require!(!proposal.executed, ConfigError::AlreadyExecuted);
+        require!(
+            proposal.approvals.len() >= ctx.accounts.multisig.threshold as usize,
+            ConfigError::ThresholdNotMet
+        );
+
+        ctx.accounts.apply()?;
+        Ok(())
+    }
+
+    // SECURE: Distinct Owners Counted
+    // Each current owner counts once, however often they approved. An
+    // approval from a key that is no longer an owner does not count.
+    pub fn secure_execute(ctx: Context<Execute>) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        require!(!proposal.executed, ConfigError::AlreadyExecuted);
+
+        let approvers = ctx
+            .accounts
+            .multisig
+            .owners
+            .iter()
+            .filter(|owner| proposal.approvals.contains(owner))
+            .count();
+        require!(
+            approvers >= ctx.accounts.multisig.threshold as usize,
+            ConfigError::ThresholdNotMet
+        );
+
+        let (action, old_value, new_value) = ctx.accounts.apply()?;
+        let multisig = ctx.accounts.multisig.key();
+        ctx.accounts.audit_log.append(multisig, action, old_value, new_value)
+    }
+
+    /// Creates the treasury. Emergency withdrawals can only go to
+    /// `allowed_destinations`, at most `max_withdrawal` lamports at a time.
+    pub fn initialize_treasury(
+        ctx: Context<InitializeTreasury>,
+        allowed_destinations: Vec<Pubkey>,
+        max_withdrawal: u64,
+    ) -> Result<()> {
+        require!(
+            allowed_destinations.len() <= MAX_ALLOWED_DESTINATIONS,
+            ConfigError::TooManyDestinations
+        );
+
+        let destination_count = allowed_destinations.len() as u64;
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.config = ctx.accounts.config.key();
+        treasury.allowed_destinations = allowed_destinations;
+        treasury.max_withdrawal = max_withdrawal;
+        treasury.bump = ctx.bumps.treasury;
+
+        ctx.accounts.audit_log.append(
+            ctx.accounts.admin.key(),
+            AuditAction::InitializeTreasury,
+            destination_count,
+            max_withdrawal,
+        )
+    }
+
+    pub fn fund_treasury(ctx: Context<FundTreasury>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.funder.to_account_info(),
+            to: ctx.accounts.treasury.to_account_info(),
+        };
+        transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts), amount)
+    }
+
+    // VULNERABLE: No Signer, No Authority, Any Destination
+    // Nothing ties the call to the admin: there is no signer and no
+    // has_one. Anyone can send any amount to any account.
+    pub fn vulnerable_emergency_withdraw(ctx: Context<VulnerableEmergencyWithdraw>, amount: u64) -> Result<()> {
+        let treasury = ctx.accounts.treasury.to_account_info();
+        move_lamports(&treasury, &ctx.accounts.destination, amount)
+    }
+
+    // SECURE: Admin Signer + Allowlist + Cap
+    // 1. `has_one = admin` with a `Signer` proves the admin authorized it.
+    // 2. The destination must be on the allowlist fixed at creation, so a
+    //    stolen admin key cannot send funds to the thief.
+    // 3. Each withdrawal is capped, and the treasury stays rent-exempt.
+    // 4. An event and an audit entry record every withdrawal.
+    pub fn secure_emergency_withdraw(ctx: Context<SecureEmergencyWithdraw>, amount: u64) -> Result<()> {
+        let treasury = &ctx.accounts.treasury;
+        require!(
+            treasury.allowed_destinations.contains(ctx.accounts.destination.key),
+            ConfigError::DestinationNotAllowed
+        );
+        require!(amount <= treasury.max_withdrawal, ConfigError::WithdrawalCapExceeded);
+
+        let treasury = treasury.to_account_info();
+        let balance = treasury.lamports();
+        let available = balance.saturating_sub(Rent::get()?.minimum_balance(treasury.data_len()));
+        require!(amount <= available, ConfigError::InsufficientFunds);
+        move_lamports(&treasury, &ctx.accounts.destination, amount)?;
+
+        emit!(EmergencyWithdrawal {
+            treasury: treasury.key(),
+            destination: ctx.accounts.destination.key(),
+            admin: ctx.accounts.admin.key(),
+            amount,
+        });
+        ctx.accounts.audit_log.append(
+            ctx.accounts.admin.key(),
+            AuditAction::EmergencyWithdraw,
+            balance,
+            balance - amount,
+        )
+    }
+
+    /// Lets `session_key` act for the admin until `valid_for_secs` from now,
+    /// for the actions in `permissions`.
+    pub fn issue_session_key(ctx: Context<IssueSessionKey>, permissions: u8, valid_for_secs: i64) -> Result<()> {
+        require!(valid_for_secs > 0, ConfigError::InvalidDelay);
+
+        let session = &mut ctx.accounts.session;
+        session.authority = ctx.accounts.admin.key();
+        session.session_key = ctx.accounts.session_key.key();
+        session.permissions = permissions;
+        session.expires_at = Clock::get()?
+            .current_unix_ts()
+            .checked_add_secs(valid_for_secs)
+            .ok_or(ConfigError::ArithmeticError)?
+            .0;
+        session.bump = ctx.bumps.session;
+
+        ctx.accounts.audit_log.append(
+            ctx.accounts.admin.key(),
+            AuditAction::IssueSessionKey,
+            key_prefix(ctx.accounts.session_key.key),
+            permissions.into(),
+        )
+    }
+
+    pub fn revoke_session_key(ctx: Context<RevokeSessionKey>) -> Result<()> {
+        let session = &ctx.accounts.session;
+        let (session_key, permissions) = (key_prefix(&session.session_key), session.permissions.into());
+        ctx.accounts.audit_log.append(
+            ctx.accounts.authority.key(),
+            AuditAction::RevokeSessionKey,
+            session_key,
+            permissions,
+        )
+    }
+
+    // VULNERABLE: Session Key Treated as Full Admin
+    // 1. Any `SessionKey` whose key signs is accepted, whoever issued it.
+    // 2. `permissions` and `expires_at` are never read.
+    // 3. Every action is allowed, including replacing the admin.
+    pub fn vulnerable_session_admin_action(ctx: Context<VulnerableSessionAdminAction>, action: AdminAction) -> Result<()> {
+        apply_admin_action(&mut ctx.accounts.config, action)?;
+        Ok(())
+    }
+
+    // SECURE: Scoped, Expiring Session Key
+    // 1. The session PDA is derived from the *current* admin, so sessions
+    //    issued by anyone else, or by a previous admin, do not load.
+    // 2. The session must not have expired.
+    // 3. The action's permission bit must be set. `SetAdmin` has no bit;
+    //    a session key can never hand over the admin role.
+    pub fn secure_session_admin_action(ctx: Context<SecureSessionAdminAction>, action: AdminAction) -> Result<()> {
+        let session = &ctx.accounts.session;
+        require!(
+            Clock::get()?.current_unix_ts() < UnixTs(session.expires_at),
+            ConfigError::SessionExpired
+        );
+        let required = match action {
+            AdminAction::SetFee { .. } => PERMISSION_SET_FEE,
+            AdminAction::SetPaused { .. } => PERMISSION_SET_PAUSED,
+            AdminAction::SetAdmin { .. } => return err!(ConfigError::PermissionDenied),
+        };
+        require!(session.permissions & required == required, ConfigError::PermissionDenied);
+
+        let (action, old_value, new_value) = apply_admin_action(&mut ctx.accounts.config, action)?;
+        ctx.accounts.audit_log.append(
+            ctx.accounts.session_key.key(),
+            action,
+            old_value,
+            new_value,
+        )
+    }
+
+    // VULNERABLE: Pause Flag Ignored
+    // `is_paused` is set by the pauser but never read here, so pausing
+    // the protocol stops nothing users can do.
+    pub fn vulnerable_user_action(ctx: Context<VulnerableUserAction>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.user.to_account_info(),
+            to: ctx.accounts.treasury.to_account_info(),
+        };
+        transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts), amount)
+    }
+
+    // SECURE: Pause Enforced by a Constraint
+    // The `config` constraint rejects the call while `is_paused` is set.
+    pub fn secure_user_action(ctx: Context<SecureUserAction>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.user.to_account_info(),
+            to: ctx.accounts.treasury.to_account_info(),
+        };
+        transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts), amount)
+    }
+
+    // VULNERABLE: Migration Anyone Can Call
+    // 1. No authority check: any signer can migrate anyone's config and
+    //    choose the new fields, including the guardian and where
+    //    `treasury` points.
+    // 2. The discriminator is not checked, and the new fields are written
+    //    without validation.
+    pub fn vulnerable_migrate_config(
+        ctx: Context<VulnerableMigrateConfig>,
+        guardian: Pubkey,
+        min_delay_secs: i64,
+        treasury: Pubkey,
+        max_fee_bps: u16,
+    ) -> Result<()> {
+        let config_info = ctx.accounts.config.to_account_info();
+        let old = ConfigV1::try_deserialize_unchecked(&mut &config_info.try_borrow_data()?[..])?;
+
+        grow_config(&config_info, &ctx.accounts.authority, &ctx.accounts.system_program)?;
+        Config::upgrade(old, guardian, min_delay_secs, treasury, max_fee_bps)
+            .try_serialize(&mut &mut config_info.try_borrow_mut_data()?[..])?;
         Ok(())
     }
+
+    // SECURE: Admin-Only, One-Time Migration
+    // 1. A config already carrying the `Config` discriminator is rejected.
+    // 2. Anything else must deserialize as a `ConfigV1`, discriminator
+    //    included, and the signer must be its admin.
+    // 3. The new fields are validated before they are written.
+    // 4. The migration is recorded in the audit log.
+    pub fn migrate_config(
+        ctx: Context<MigrateConfig>,
+        guardian: Pubkey,
+        min_delay_secs: i64,
+        treasury: Pubkey,
+        max_fee_bps: u16,
+    ) -> Result<()> {
+        let config_info = ctx.accounts.config.to_account_info();
+        require!(
+            config_info.try_borrow_data()?[..8] != Config::DISCRIMINATOR,
+            ConfigError::AlreadyMigrated
+        );
+
+        let old = ConfigV1::try_deserialize(&mut &config_info.try_borrow_data()?[..])?;
+        require_keys_eq!(old.admin, ctx.accounts.authority.key(), ConfigError::Unauthorized);
+        require!(
+            old.fee_bps <= max_fee_bps && max_fee_bps <= 10_000,
+            ConfigError::InvalidFeeCap
+        );
+        require_keys_neq!(guardian, old.admin, ConfigError::InvalidGuardian);
+        require!(min_delay_secs >= 0, ConfigError::InvalidDelay);
+
+        grow_config(&config_info, &ctx.accounts.authority, &ctx.accounts.system_program)?;
+        Config::upgrade(old, guardian, min_delay_secs, treasury, max_fee_bps)
+            .try_serialize(&mut &mut config_info.try_borrow_mut_data()?[..])?;
+
+        ctx.accounts.audit_log.append(
+            ctx.accounts.authority.key(),
+            AuditAction::Migrate,
+            1,
+            CONFIG_V2_VERSION.into(),
+        )
+    }
+}
+
+/// Tops the config up to rent exemption at the v2 size and reallocates it.
+fn grow_config<'info>(
+    config: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let new_len = 8 + Config::INIT_SPACE;
+    let shortfall = Rent::get()?.minimum_balance(new_len).saturating_sub(config.lamports());
+    if shortfall > 0 {
+        let cpi_accounts = Transfer {
+            from: payer.to_account_info(),
+            to: config.clone(),
+        };
+        transfer(CpiContext::new(system_program.to_account_info(), cpi_accounts), shortfall)?;
+    }
+    config.realloc(new_len, true)?;
+    Ok(())
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ConfigError::InsufficientFunds)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(ConfigError::ArithmeticError)?;
+    Ok(())
 }
 
 #[derive(Accounts)]
@@ -56,11 +757,42 @@ pub struct Initialize<'info> {
     #[account(
         init,
         payer = admin,
-        space = 8 + 32 + 33 + 2 + 1,
+        space = 8 + Config::INIT_SPACE,
         seeds = [b"config"],
         bump
     )]
     pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + AuditLog::INIT_SPACE,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeLegacyConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + ConfigV1::INIT_SPACE,
+        seeds = [b"legacy_config", admin.key().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, ConfigV1>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + AuditLog::INIT_SPACE,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
     #[account(mut)]
     pub admin: Signer<'info>,
     pub system_program: Program<'info, System>,
@@ -102,19 +834,1135 @@ pub struct SecureUpdateFee<'info> {
     )]
     pub config: Account<'info, Config>,
 
+    #[account(
+        mut,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+
     pub admin: Signer<'info>,
 }
 
-#[account]
-pub struct Config {
-    pub admin: Pubkey,
-    pub pending_admin: Option<Pubkey>,
-    pub fee_bps: u16,
-    pub bump: u8,
+#[derive(Accounts)]
+pub struct UnboundedUpdateFee<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ConfigError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
 }
 
-#[error_code]
-pub enum ConfigError {
-    #[msg("Unauthorized access")]
-    Unauthorized,
+#[derive(Accounts)]
+pub struct NominateAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ConfigError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    // Only the secure path writes to the log
+    #[account(
+        mut,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+
+    pub new_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeRoles<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ConfigError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + RoleGrant::INIT_SPACE,
+        seeds = [b"role".as_ref(), &[Role::Admin as u8], admin.key().as_ref()],
+        bump
+    )]
+    pub admin_role: Account<'info, RoleGrant>,
+    #[account(
+        mut,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(role: Role)]
+pub struct GrantRole<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        seeds = [b"role".as_ref(), &[Role::Admin as u8], authority.key().as_ref()],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Account<'info, RoleGrant>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RoleGrant::INIT_SPACE,
+        seeds = [b"role".as_ref(), &[role as u8], holder.key().as_ref()],
+        bump
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    /// CHECK: Only its key is recorded.
+    pub holder: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeRole<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        seeds = [b"role".as_ref(), &[Role::Admin as u8], authority.key().as_ref()],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Account<'info, RoleGrant>,
+    #[account(
+        mut,
+        seeds = [b"role".as_ref(), &[role_grant.role as u8], role_grant.holder.as_ref()],
+        bump = role_grant.bump,
+        close = authority
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    #[account(
+        mut,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableSetPaused<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub role_grant: Account<'info, RoleGrant>,
+
+    /// CHECK: Unsafe. Compared to the role holder, but never required to sign.
+    pub pauser: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SecureSetPaused<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [b"role".as_ref(), &[Role::Pauser as u8], pauser.key().as_ref()],
+        bump = role_grant.bump
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+
+    pub pauser: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableSetFeeAsManager<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub role_grant: Account<'info, RoleGrant>,
+
+    /// CHECK: Unsafe. Compared to the role holder, but never required to sign.
+    pub fee_manager: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SecureSetFeeAsManager<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [b"role".as_ref(), &[Role::FeeManager as u8], fee_manager.key().as_ref()],
+        bump = role_grant.bump
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+
+    pub fee_manager: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct QueueConfigChange<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ConfigError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + PendingConfigChange::INIT_SPACE,
+        seeds = [b"pending_change", config.key().as_ref()],
+        bump
+    )]
+    pub pending_change: Account<'info, PendingConfigChange>,
+    #[account(
+        mut,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteConfigChange<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ConfigError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"pending_change", config.key().as_ref()],
+        bump = pending_change.bump,
+        close = admin
+    )]
+    pub pending_change: Account<'info, PendingConfigChange>,
+    // Only the secure path writes to the log
+    #[account(
+        mut,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+impl<'info> ExecuteConfigChange<'info> {
+    /// Applies the pending change and returns it as an audit record.
+    fn apply(&mut self) -> Result<(AuditAction, u64, u64)> {
+        let config = &mut self.config;
+        Ok(match self.pending_change.change {
+            ConfigChange::SetFee { fee_bps } => {
+                let old = config.set_fee(fee_bps, Clock::get()?.current_epoch())?;
+                (AuditAction::SetFee, old.into(), fee_bps.into())
+            }
+            ConfigChange::SetMinDelay { min_delay_secs } => {
+                let old = std::mem::replace(&mut config.min_delay_secs, min_delay_secs);
+                (AuditAction::SetMinDelay, old as u64, min_delay_secs as u64)
+            }
+        })
+    }
+}
+
+#[derive(Accounts)]
+pub struct CancelConfigChange<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ConfigError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"pending_change", config.key().as_ref()],
+        bump = pending_change.bump,
+        close = admin
+    )]
+    pub pending_change: Account<'info, PendingConfigChange>,
+    #[account(
+        mut,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VetoPendingChange<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = guardian @ ConfigError::Unauthorized,
+        has_one = admin @ ConfigError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"pending_change", config.key().as_ref()],
+        bump = pending_change.bump,
+        close = admin
+    )]
+    pub pending_change: Account<'info, PendingConfigChange>,
+    #[account(
+        mut,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+    pub guardian: Signer<'info>,
+    /// Receives the rent back, as the one who paid it when queueing.
+    #[account(mut)]
+    pub admin: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateMultisig<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ConfigError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Multisig::INIT_SPACE,
+        seeds = [b"multisig", config.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdminAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"multisig", multisig.config.as_ref()],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + MultisigProposal::INIT_SPACE,
+        seeds = [b"proposal", multisig.key().as_ref(), multisig.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, MultisigProposal>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Approve<'info> {
+    #[account(
+        seeds = [b"multisig", multisig.config.as_ref()],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        seeds = [b"proposal", multisig.key().as_ref(), proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, MultisigProposal>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Execute<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        seeds = [b"multisig", config.key().as_ref()],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        seeds = [b"proposal", multisig.key().as_ref(), proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, MultisigProposal>,
+    // Only the secure path writes to the log
+    #[account(
+        mut,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+    pub executor: Signer<'info>,
+}
+
+impl<'info> Execute<'info> {
+    /// Applies the proposal's action and returns it as an audit record.
+    fn apply(&mut self) -> Result<(AuditAction, u64, u64)> {
+        self.proposal.executed = true;
+        apply_admin_action(&mut self.config, self.proposal.action)
+    }
+}
+
+/// Applies `action` to `config` and returns it as an audit record. Fee
+/// changes go through `Config::set_fee`, so the per-epoch bound holds
+/// for every caller.
+fn apply_admin_action(config: &mut Config, action: AdminAction) -> Result<(AuditAction, u64, u64)> {
+    Ok(match action {
+        AdminAction::SetFee { fee_bps } => {
+            let old = config.set_fee(fee_bps, Clock::get()?.current_epoch())?;
+            (AuditAction::SetFee, old.into(), fee_bps.into())
+        }
+        AdminAction::SetAdmin { admin } => {
+            let old = std::mem::replace(&mut config.admin, admin);
+            (AuditAction::SetAdmin, key_prefix(&old), key_prefix(&admin))
+        }
+        AdminAction::SetPaused { paused } => {
+            let old = std::mem::replace(&mut config.is_paused, paused);
+            (AuditAction::SetPaused, old.into(), paused.into())
+        }
+    })
+}
+
+/// The first eight bytes of `key`, read little-endian. Enough for an
+/// audit entry to be matched against known keys.
+fn key_prefix(key: &Pubkey) -> u64 {
+    let mut prefix = [0u8; 8];
+    prefix.copy_from_slice(&key.to_bytes()[..8]);
+    u64::from_le_bytes(prefix)
+}
+
+#[derive(Accounts)]
+pub struct InitializeTreasury<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ConfigError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Treasury::INIT_SPACE,
+        seeds = [b"treasury", config.key().as_ref()],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        mut,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundTreasury<'info> {
+    #[account(
+        mut,
+        seeds = [b"treasury", treasury.config.as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableEmergencyWithdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"treasury", treasury.config.as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    // VULNERABLE: Any account, chosen by the caller
+    /// CHECK: Unsafe. No allowlist, and no signer anywhere in this context.
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SecureEmergencyWithdraw<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ConfigError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", config.key().as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// CHECK: Checked against `treasury.allowed_destinations`.
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct IssueSessionKey<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ConfigError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + SessionKey::INIT_SPACE,
+        seeds = [b"session", admin.key().as_ref(), session_key.key().as_ref()],
+        bump
+    )]
+    pub session: Account<'info, SessionKey>,
+    /// CHECK: Only its key is recorded.
+    pub session_key: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeSessionKey<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"session", authority.key().as_ref(), session.session_key.as_ref()],
+        bump = session.bump,
+        has_one = authority @ ConfigError::Unauthorized,
+        close = authority
+    )]
+    pub session: Account<'info, SessionKey>,
+    #[account(
+        mut,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableSessionAdminAction<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    // VULNERABLE: Only checks that the session names the signer
+    #[account(has_one = session_key @ ConfigError::Unauthorized)]
+    pub session: Account<'info, SessionKey>,
+
+    pub session_key: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SecureSessionAdminAction<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    // SECURE: Derived from the current admin and the signing session key
+    #[account(
+        seeds = [b"session", config.admin.as_ref(), session_key.key().as_ref()],
+        bump = session.bump
+    )]
+    pub session: Account<'info, SessionKey>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+
+    pub session_key: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableUserAction<'info> {
+    // VULNERABLE: `is_paused` is never checked
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"treasury", config.key().as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SecureUserAction<'info> {
+    // SECURE: Rejected while the protocol is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.is_paused @ ConfigError::PausedError
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"treasury", config.key().as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableMigrateConfig<'info> {
+    // VULNERABLE: Any program-owned account, whoever signs
+    /// CHECK: Unsafe. Read as a `ConfigV1` without its discriminator.
+    #[account(mut, owner = crate::ID)]
+    pub config: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    // SECURE: Seeds tie the config to the signer
+    /// CHECK: Read as v1 or v2 by the handler; the seeds pin the address.
+    #[account(mut, seeds = [b"legacy_config", authority.key().as_ref()], bump)]
+    pub config: UncheckedAccount<'info>,
+    // Only the secure path writes to the log
+    #[account(
+        mut,
+        seeds = [b"audit_log", config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// The config every handler loads and `initialize` writes. `migrate_config`
+/// brings a `ConfigV1` to this layout.
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    /// Can veto queued config changes, and do nothing else.
+    pub guardian: Pubkey,
+    pub pending_admin: Option<Pubkey>,
+    /// Last slot in which `pending_admin` can accept.
+    pub nomination_expiry: u64,
+    pub fee_bps: u16,
+    pub is_paused: bool,
+    /// Seconds a queued change must wait before it can execute.
+    pub min_delay_secs: i64,
+    /// Epoch `fee_change_this_epoch` refers to.
+    pub last_fee_change_epoch: u64,
+    /// Total fee increase, in bps, made through `set_fee` during
+    /// `last_fee_change_epoch`.
+    pub fee_change_this_epoch: u16,
+    pub bump: u8,
+    pub version: u8,
+    pub treasury: Pubkey,
+    /// `set_fee` rejects any fee above this.
+    pub max_fee_bps: u16,
+}
+
+/// The config layout from before versioning. Only
+/// `initialize_legacy_config` writes it and only the migrations load it.
+#[account]
+#[derive(InitSpace)]
+pub struct ConfigV1 {
+    pub admin: Pubkey,
+    pub pending_admin: Option<Pubkey>,
+    pub fee_bps: u16,
+    pub bump: u8,
+}
+
+impl Config {
+    /// Carries the v1 fields over and fills in every field added since.
+    fn upgrade(old: ConfigV1, guardian: Pubkey, min_delay_secs: i64, treasury: Pubkey, max_fee_bps: u16) -> Self {
+        Self {
+            admin: old.admin,
+            guardian,
+            pending_admin: old.pending_admin,
+            nomination_expiry: 0,
+            fee_bps: old.fee_bps,
+            is_paused: false,
+            min_delay_secs,
+            last_fee_change_epoch: 0,
+            fee_change_this_epoch: 0,
+            bump: old.bump,
+            version: CONFIG_V2_VERSION,
+            treasury,
+            max_fee_bps,
+        }
+    }
+
+    /// Moves the fee to `new_fee_bps`, at most `max_fee_bps` and within
+    /// the per-epoch increase bound, and returns the old fee. Every secure
+    /// path that changes the fee goes through here.
+    fn set_fee(&mut self, new_fee_bps: u16, epoch: Epoch) -> Result<u16> {
+        require!(new_fee_bps <= self.max_fee_bps, ConfigError::FeeAboveMax);
+        self.record_fee_change(new_fee_bps, epoch)?;
+        Ok(std::mem::replace(&mut self.fee_bps, new_fee_bps))
+    }
+
+    /// Counts a move to `new_fee_bps` in `epoch` against the per-epoch
+    /// increase bound.
+    fn record_fee_change(&mut self, new_fee_bps: u16, epoch: Epoch) -> Result<()> {
+        if Epoch(self.last_fee_change_epoch) != epoch {
+            self.last_fee_change_epoch = epoch.0;
+            self.fee_change_this_epoch = 0;
+        }
+        if new_fee_bps > self.fee_bps {
+            let increased = self
+                .fee_change_this_epoch
+                .checked_add(new_fee_bps - self.fee_bps)
+                .ok_or(ConfigError::ArithmeticError)?;
+            require!(
+                increased <= MAX_FEE_INCREASE_PER_EPOCH_BPS,
+                ConfigError::FeeChangeLimitExceeded
+            );
+            self.fee_change_this_epoch = increased;
+        }
+        Ok(())
+    }
+}
+
+/// `threshold` of `owners` must approve an admin action.
+#[account]
+#[derive(InitSpace)]
+pub struct Multisig {
+    pub config: Pubkey,
+    #[max_len(MAX_MULTISIG_OWNERS)]
+    pub owners: Vec<Pubkey>,
+    pub threshold: u8,
+    pub proposal_count: u64,
+    pub bump: u8,
+}
+
+/// An admin action the multisig can run.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum AdminAction {
+    SetFee { fee_bps: u16 },
+    SetAdmin { admin: Pubkey },
+    SetPaused { paused: bool },
+}
+
+/// One proposed action, at `[b"proposal", multisig, id]`.
+#[account]
+#[derive(InitSpace)]
+pub struct MultisigProposal {
+    pub multisig: Pubkey,
+    pub id: u64,
+    pub action: AdminAction,
+    /// Every approval in the order it arrived, repeats included.
+    #[max_len(MAX_MULTISIG_OWNERS)]
+    pub approvals: Vec<Pubkey>,
+    pub executed: bool,
+    pub bump: u8,
+}
+
+/// A hot key allowed to act for `authority`, at
+/// `[b"session", authority, session_key]`.
+#[account]
+#[derive(InitSpace)]
+pub struct SessionKey {
+    pub authority: Pubkey,
+    pub session_key: Pubkey,
+    /// `PERMISSION_*` bits.
+    pub permissions: u8,
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+/// Lamports held for the protocol, at `[b"treasury", config]`.
+#[account]
+#[derive(InitSpace)]
+pub struct Treasury {
+    pub config: Pubkey,
+    #[max_len(MAX_ALLOWED_DESTINATIONS)]
+    pub allowed_destinations: Vec<Pubkey>,
+    /// Most lamports one emergency withdrawal can move.
+    pub max_withdrawal: u64,
+    pub bump: u8,
+}
+
+#[event]
+pub struct EmergencyWithdrawal {
+    pub treasury: Pubkey,
+    pub destination: Pubkey,
+    pub admin: Pubkey,
+    pub amount: u64,
+}
+
+/// What an audit entry records.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default, InitSpace)]
+pub enum AuditAction {
+    #[default]
+    SetFee,
+    SetPaused,
+    SetMinDelay,
+    /// Values are `key_prefix` of the old and new admin.
+    SetAdmin,
+    /// Values are the treasury's lamports before and after.
+    EmergencyWithdraw,
+    /// Values are the role and `key_prefix` of the holder.
+    GrantRole,
+    /// Values are the role and `key_prefix` of the holder.
+    RevokeRole,
+    /// Values are `key_prefix` of the previous nominee, or 0, and the new one.
+    NominateAdmin,
+    /// Values are `key_prefix` of the withdrawn nominee, and 0.
+    CancelNomination,
+    /// Values are the field's current value and the queued one.
+    QueueChange,
+    /// Values are the field's current value and the dropped one.
+    CancelChange,
+    /// Values are the field's current value and the vetoed one.
+    VetoChange,
+    /// Values are the number of owners and the threshold.
+    CreateMultisig,
+    /// Values are the number of allowed destinations and the cap.
+    InitializeTreasury,
+    /// Values are `key_prefix` of the session key and its permission bits.
+    IssueSessionKey,
+    /// Values are `key_prefix` of the session key and its permission bits.
+    RevokeSessionKey,
+    /// Values are the config layout version before and after.
+    Migrate,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default, InitSpace)]
+pub struct AuditEntry {
+    pub slot: u64,
+    pub actor: Pubkey,
+    pub action: AuditAction,
+    pub old_value: u64,
+    pub new_value: u64,
+}
+
+/// Ring buffer of the last `AUDIT_LOG_LEN` secure admin changes, at
+/// `[b"audit_log", config]`.
+///
+/// Entry `total - 1` is the newest and lives at
+/// `entries[(total - 1) % AUDIT_LOG_LEN]`. Until the buffer wraps,
+/// `entries[..total]` is already oldest-first.
+#[account]
+#[derive(InitSpace)]
+pub struct AuditLog {
+    pub config: Pubkey,
+    /// Entries ever appended, including overwritten ones.
+    pub total: u64,
+    pub entries: [AuditEntry; AUDIT_LOG_LEN],
+    pub bump: u8,
+}
+
+impl AuditLog {
+    fn append(&mut self, actor: Pubkey, action: AuditAction, old_value: u64, new_value: u64) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        self.push(AuditEntry { slot, actor, action, old_value, new_value })
+    }
+
+    fn push(&mut self, entry: AuditEntry) -> Result<()> {
+        self.entries[(self.total % AUDIT_LOG_LEN as u64) as usize] = entry;
+        self.total = self.total.checked_add(1).ok_or(ConfigError::ArithmeticError)?;
+        Ok(())
+    }
+
+    /// Retained entries, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &AuditEntry> {
+        let retained = self.total.min(AUDIT_LOG_LEN as u64) as usize;
+        let oldest = self.total as usize - retained;
+        (oldest..oldest + retained).map(|i| &self.entries[i % AUDIT_LOG_LEN])
+    }
+}
+
+/// A change that has to wait out the timelock.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ConfigChange {
+    SetFee { fee_bps: u16 },
+    SetMinDelay { min_delay_secs: i64 },
+}
+
+impl ConfigChange {
+    /// The changed field's value in `config`, and the value this change
+    /// would set, as audit entry values.
+    fn audit_values(&self, config: &Config) -> (u64, u64) {
+        match *self {
+            ConfigChange::SetFee { fee_bps } => (config.fee_bps.into(), fee_bps.into()),
+            ConfigChange::SetMinDelay { min_delay_secs } => {
+                (config.min_delay_secs as u64, min_delay_secs as u64)
+            }
+        }
+    }
+}
+
+/// The queued change, at `[b"pending_change", config]`.
+#[account]
+#[derive(InitSpace)]
+pub struct PendingConfigChange {
+    pub change: ConfigChange,
+    pub queued_at: i64,
+    /// Earliest time the change can execute.
+    pub eta: i64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum Role {
+    /// Grants and revokes roles.
+    Admin,
+    /// Day-to-day operations.
+    Operator,
+    /// Pauses and unpauses the protocol.
+    Pauser,
+    /// Changes the fee.
+    FeeManager,
+}
+
+/// One role held by one key, at `[b"role", role, holder]`.
+#[account]
+#[derive(InitSpace)]
+pub struct RoleGrant {
+    pub holder: Pubkey,
+    pub role: Role,
+    pub granted_by: Pubkey,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum ConfigError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Delay must not be negative")]
+    InvalidDelay,
+    #[msg("Timelock has not expired")]
+    TimelockNotExpired,
+    #[msg("Too many multisig owners")]
+    TooManyOwners,
+    #[msg("Threshold must be between 1 and the number of owners")]
+    InvalidThreshold,
+    #[msg("Owner listed more than once")]
+    DuplicateOwner,
+    #[msg("Signer is not a multisig owner")]
+    NotMultisigOwner,
+    #[msg("Proposal cannot record more approvals")]
+    ProposalFull,
+    #[msg("Proposal already executed")]
+    AlreadyExecuted,
+    #[msg("Not enough approvals")]
+    ThresholdNotMet,
+    #[msg("No nomination pending for this signer")]
+    NoPendingNomination,
+    #[msg("Nomination has expired")]
+    NominationExpired,
+    #[msg("Nomination must be valid for at least one slot")]
+    InvalidNominationWindow,
+    #[msg("Session key has expired")]
+    SessionExpired,
+    #[msg("Session key lacks the permission for this action")]
+    PermissionDenied,
+    #[msg("Fee increase exceeds the per-epoch limit")]
+    FeeChangeLimitExceeded,
+    #[msg("Protocol is paused")]
+    PausedError,
+    #[msg("Too many allowed destinations")]
+    TooManyDestinations,
+    #[msg("Destination is not on the treasury allowlist")]
+    DestinationNotAllowed,
+    #[msg("Amount exceeds the per-withdrawal cap")]
+    WithdrawalCapExceeded,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Guardian must differ from the admin")]
+    InvalidGuardian,
+    #[msg("Config is already on the v2 layout")]
+    AlreadyMigrated,
+    #[msg("Fee cap must be at least the current fee and at most 10000 bps")]
+    InvalidFeeCap,
+    #[msg("Fee is above the configured maximum")]
+    FeeAboveMax,
+    #[msg("Owner has already approved this proposal")]
+    AlreadyApproved,
+    #[msg("Arithmetic error")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log() -> AuditLog {
+        AuditLog {
+            config: Pubkey::default(),
+            total: 0,
+            entries: [AuditEntry::default(); AUDIT_LOG_LEN],
+            bump: 0,
+        }
+    }
+
+    fn entry(slot: u64) -> AuditEntry {
+        AuditEntry { slot, ..AuditEntry::default() }
+    }
+
+    fn config(fee_bps: u16) -> Config {
+        Config {
+            admin: Pubkey::default(),
+            guardian: Pubkey::default(),
+            pending_admin: None,
+            nomination_expiry: 0,
+            fee_bps,
+            is_paused: false,
+            min_delay_secs: 0,
+            last_fee_change_epoch: 0,
+            fee_change_this_epoch: 0,
+            bump: 0,
+            version: CONFIG_V2_VERSION,
+            treasury: Pubkey::default(),
+            max_fee_bps: 10_000,
+        }
+    }
+
+    #[test]
+    fn fee_increases_within_an_epoch_add_up() {
+        let mut config = config(100);
+        config.set_fee(160, Epoch(7)).unwrap();
+        config.set_fee(200, Epoch(7)).unwrap();
+        assert!(config.set_fee(201, Epoch(7)).is_err());
+        assert_eq!(config.fee_bps, 200);
+    }
+
+    #[test]
+    fn decreases_are_unbounded_and_do_not_refund_the_allowance() {
+        let mut config = config(5_000);
+        config.set_fee(100, Epoch(7)).unwrap();
+        config.set_fee(200, Epoch(7)).unwrap();
+        config.set_fee(100, Epoch(7)).unwrap();
+        assert!(config.set_fee(101, Epoch(7)).is_err());
+    }
+
+    #[test]
+    fn a_new_epoch_resets_the_allowance() {
+        let mut config = config(100);
+        config.set_fee(200, Epoch(7)).unwrap();
+        config.set_fee(300, Epoch(8)).unwrap();
+        assert_eq!(config.fee_change_this_epoch, MAX_FEE_INCREASE_PER_EPOCH_BPS);
+    }
+
+    #[test]
+    fn no_fee_change_exceeds_the_cap() {
+        let mut config = config(100);
+        config.max_fee_bps = 150;
+        assert!(config.set_fee(151, Epoch(7)).is_err());
+        config.set_fee(150, Epoch(7)).unwrap();
+        assert_eq!(config.fee_change_this_epoch, 50);
+    }
+
+    #[test]
+    fn iter_is_oldest_first_before_wrapping() {
+        let mut log = log();
+        for slot in 1..=3 {
+            log.push(entry(slot)).unwrap();
+        }
+        let slots: Vec<u64> = log.iter().map(|e| e.slot).collect();
+        assert_eq!(slots, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn wrapping_overwrites_the_oldest_entries() {
+        let mut log = log();
+        let appended = AUDIT_LOG_LEN as u64 + 5;
+        for slot in 1..=appended {
+            log.push(entry(slot)).unwrap();
+        }
+        let slots: Vec<u64> = log.iter().map(|e| e.slot).collect();
+        assert_eq!(log.total, appended);
+        assert_eq!(slots, (6..=appended).collect::<Vec<_>>());
+    }
 }
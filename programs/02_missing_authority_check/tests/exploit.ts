@@ -7,6 +7,7 @@ import {
     LAMPORTS_PER_SOL 
 } from "@solana/web3.js";
 import { expect } from "chai";
+import { sleep } from "../../../tests/utils";
 import { ProtocolConfig } from "../../../target/types/protocol_config";
 
 describe("02: Missing Authority Check", () => {
@@ -17,13 +18,36 @@ describe("02: Missing Authority Check", () => {
     
     let legitimateAdmin: Keypair;
     let attacker: Keypair;
+    let guardian: Keypair;
     let configPda: PublicKey;
+    let auditLogPda: PublicKey;
 
     const INITIAL_FEE_BPS = 100;
+    const MIN_DELAY_SECS = 3;
+    const MAX_FEE_BPS = 1_000;
+    const AUDIT_LOG_LEN = 16;
+
+    /** Retained audit entries, oldest first, plus the running total. */
+    async function auditEntries() {
+        const log = await program.account.auditLog.fetch(auditLogPda);
+        const total = log.total.toNumber();
+        const retained = Math.min(total, AUDIT_LOG_LEN);
+        const entries = Array.from(
+            { length: retained },
+            (_, i) => log.entries[(total - retained + i) % AUDIT_LOG_LEN]
+        );
+        return { total, entries };
+    }
+
+    /** The first eight bytes of `key`, read little-endian, as the audit log records keys. */
+    function keyPrefix(key: PublicKey): string {
+        return new anchor.BN(key.toBuffer().subarray(0, 8), "le").toString();
+    }
 
     before(async () => {
         legitimateAdmin = Keypair.generate();
         attacker = Keypair.generate();
+        guardian = Keypair.generate();
 
         const airdropAdmin = await provider.connection.requestAirdrop(
             legitimateAdmin.publicKey,
@@ -41,14 +65,32 @@ describe("02: Missing Authority Check", () => {
             [Buffer.from("config")],
             program.programId
         );
+
+        [auditLogPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("audit_log"), configPda.toBuffer()],
+            program.programId
+        );
     });
 
     describe("Setup: Initialize protocol", () => {
+        let treasuryWallet: PublicKey;
+
+        before(() => {
+            treasuryWallet = Keypair.generate().publicKey;
+        });
+
         it("initializes config with admin", async () => {
             await program.methods
-                .initialize(INITIAL_FEE_BPS)
+                .initialize(
+                    INITIAL_FEE_BPS,
+                    new anchor.BN(MIN_DELAY_SECS),
+                    guardian.publicKey,
+                    treasuryWallet,
+                    MAX_FEE_BPS
+                )
                 .accounts({
                     config: configPda,
+                    auditLog: auditLogPda,
                     admin: legitimateAdmin.publicKey,
                     systemProgram: SystemProgram.programId,
                 })
@@ -56,8 +98,182 @@ describe("02: Missing Authority Check", () => {
                 .rpc();
 
             const config = await program.account.config.fetch(configPda);
+            expect(config.version).to.equal(2);
             expect(config.admin.toString()).to.equal(legitimateAdmin.publicKey.toString());
+            expect(config.guardian.toString()).to.equal(guardian.publicKey.toString());
             expect(config.feeBps).to.equal(INITIAL_FEE_BPS);
+            expect(config.minDelaySecs.toNumber()).to.equal(MIN_DELAY_SECS);
+            expect(config.treasury.toString()).to.equal(treasuryWallet.toString());
+            expect(config.maxFeeBps).to.equal(MAX_FEE_BPS);
+        });
+
+        it("secure handlers load the config", async () => {
+            await program.methods
+                .secureUpdateFee(INITIAL_FEE_BPS)
+                .accounts({ config: configPda, auditLog: auditLogPda, admin: legitimateAdmin.publicKey })
+                .signers([legitimateAdmin])
+                .rpc();
+
+            const config = await program.account.config.fetch(configPda);
+            expect(config.feeBps).to.equal(INITIAL_FEE_BPS);
+        });
+
+        it("rejects a fee above max_fee_bps", async () => {
+            try {
+                await program.methods
+                    .secureUpdateFee(MAX_FEE_BPS + 1)
+                    .accounts({ config: configPda, auditLog: auditLogPda, admin: legitimateAdmin.publicKey })
+                    .signers([legitimateAdmin])
+                    .rpc();
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("FeeAboveMax");
+            }
+        });
+    });
+
+    // Each admin's legacy config sits at its own address, so migrating
+    // one never touches the live config the rest of the suite uses.
+    describe("Config migration: an upgrade anyone can run", () => {
+        let legacyAdmin: Keypair;
+        let legacyGuardian: PublicKey;
+        let treasuryWallet: PublicKey;
+
+        function legacyConfigOf(admin: PublicKey): PublicKey {
+            return PublicKey.findProgramAddressSync(
+                [Buffer.from("legacy_config"), admin.toBuffer()],
+                program.programId
+            )[0];
+        }
+
+        function legacyAuditLogOf(admin: PublicKey): PublicKey {
+            return PublicKey.findProgramAddressSync(
+                [Buffer.from("audit_log"), legacyConfigOf(admin).toBuffer()],
+                program.programId
+            )[0];
+        }
+
+        const migrate = (
+            signer: Keypair,
+            admin: PublicKey,
+            { guardian = legacyGuardian, treasury = treasuryWallet, maxFeeBps = MAX_FEE_BPS } = {}
+        ) =>
+            program.methods
+                .migrateConfig(guardian, new anchor.BN(MIN_DELAY_SECS), treasury, maxFeeBps)
+                .accounts({
+                    config: legacyConfigOf(admin),
+                    auditLog: legacyAuditLogOf(admin),
+                    authority: signer.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([signer])
+                .rpc();
+
+        before(async () => {
+            legacyAdmin = Keypair.generate();
+            legacyGuardian = Keypair.generate().publicKey;
+            treasuryWallet = Keypair.generate().publicKey;
+
+            const airdrop = await provider.connection.requestAirdrop(legacyAdmin.publicKey, LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(airdrop);
+
+            for (const admin of [legitimateAdmin, legacyAdmin]) {
+                await program.methods
+                    .initializeLegacyConfig(INITIAL_FEE_BPS)
+                    .accounts({
+                        config: legacyConfigOf(admin.publicKey),
+                        auditLog: legacyAuditLogOf(admin.publicKey),
+                        admin: admin.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([admin])
+                    .rpc();
+            }
+        });
+
+        describe("Secure: migrate_config", () => {
+            it("rejects a caller who is not the admin", async () => {
+                try {
+                    await migrate(attacker, legitimateAdmin.publicKey, { treasury: attacker.publicKey });
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("ConstraintSeeds");
+                }
+            });
+
+            it("rejects a fee cap below the current fee", async () => {
+                try {
+                    await migrate(legitimateAdmin, legitimateAdmin.publicKey, { maxFeeBps: 0 });
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("InvalidFeeCap");
+                }
+            });
+
+            it("rejects the admin as guardian", async () => {
+                try {
+                    await migrate(legitimateAdmin, legitimateAdmin.publicKey, { guardian: legitimateAdmin.publicKey });
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("InvalidGuardian");
+                }
+            });
+
+            it("upgrades the config in place, keeping v1 fields and filling the rest", async () => {
+                const legacyConfig = legacyConfigOf(legitimateAdmin.publicKey);
+                const before = await program.account.configV1.fetch(legacyConfig);
+
+                await migrate(legitimateAdmin, legitimateAdmin.publicKey);
+
+                const config = await program.account.config.fetch(legacyConfig);
+                expect(config.version).to.equal(2);
+                expect(config.admin.toString()).to.equal(before.admin.toString());
+                expect(config.pendingAdmin).to.equal(null);
+                expect(config.feeBps).to.equal(before.feeBps);
+                expect(config.guardian.toString()).to.equal(legacyGuardian.toString());
+                expect(config.isPaused).to.equal(false);
+                expect(config.minDelaySecs.toNumber()).to.equal(MIN_DELAY_SECS);
+                expect(config.treasury.toString()).to.equal(treasuryWallet.toString());
+                expect(config.maxFeeBps).to.equal(MAX_FEE_BPS);
+
+                const log = await program.account.auditLog.fetch(legacyAuditLogOf(legitimateAdmin.publicKey));
+                expect(log.total.toNumber()).to.equal(1);
+                expect(Object.keys(log.entries[0].action)[0]).to.equal("migrate");
+                expect(log.entries[0].oldValue.toNumber()).to.equal(1);
+                expect(log.entries[0].newValue.toNumber()).to.equal(2);
+            });
+
+            it("refuses to migrate twice", async () => {
+                try {
+                    await migrate(legitimateAdmin, legitimateAdmin.publicKey);
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("AlreadyMigrated");
+                }
+            });
+        });
+
+        describe("Exploit: vulnerable_migrate_config", () => {
+            it("lets the attacker migrate someone else's config and redirect the treasury", async () => {
+                const legacyConfig = legacyConfigOf(legacyAdmin.publicKey);
+
+                await program.methods
+                    .vulnerableMigrateConfig(attacker.publicKey, new anchor.BN(0), attacker.publicKey, 10_000)
+                    .accounts({
+                        config: legacyConfig,
+                        authority: attacker.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([attacker])
+                    .rpc();
+
+                const config = await program.account.config.fetch(legacyConfig);
+                console.log("EXPLOIT SUCCEEDED: Attacker migrated a config they do not administer");
+                expect(config.treasury.toString()).to.equal(attacker.publicKey.toString());
+                expect(config.guardian.toString()).to.equal(attacker.publicKey.toString());
+                expect(config.maxFeeBps).to.equal(10_000);
+                expect(config.admin.toString()).to.equal(legacyAdmin.publicKey.toString());
+            });
         });
     });
 
@@ -128,6 +344,7 @@ describe("02: Missing Authority Check", () => {
                     .secureUpdateFee(500)
                     .accounts({
                         config: configPda,
+                        auditLog: auditLogPda,
                         admin: legitimateAdmin.publicKey,
                     })
                     .signers([attacker])
@@ -145,6 +362,7 @@ describe("02: Missing Authority Check", () => {
                     .secureUpdateFee(500)
                     .accounts({
                         config: configPda,
+                        auditLog: auditLogPda,
                         admin: attacker.publicKey,
                     })
                     .signers([attacker])
@@ -163,6 +381,7 @@ describe("02: Missing Authority Check", () => {
                 .secureUpdateFee(newFee)
                 .accounts({
                     config: configPda,
+                    auditLog: auditLogPda,
                     admin: legitimateAdmin.publicKey,
                 })
                 .signers([legitimateAdmin])
@@ -173,4 +392,1178 @@ describe("02: Missing Authority Check", () => {
             console.log(`Legitimate admin updated fee to ${config.feeBps} bps`);
         });
     });
+
+    describe("Roles: role accounts checked without a signature", () => {
+        let pauser: Keypair;
+        let feeManager: Keypair;
+
+        const ROLE = { admin: 0, operator: 1, pauser: 2, feeManager: 3 };
+
+        function roleOf(role: keyof typeof ROLE, holder: PublicKey): PublicKey {
+            return PublicKey.findProgramAddressSync(
+                [Buffer.from("role"), Buffer.from([ROLE[role]]), holder.toBuffer()],
+                program.programId
+            )[0];
+        }
+
+        async function grant(role: keyof typeof ROLE, holder: PublicKey, authority: Keypair) {
+            await program.methods
+                .grantRole({ [role]: {} } as any)
+                .accounts({
+                    config: configPda,
+                    adminRole: roleOf("admin", authority.publicKey),
+                    roleGrant: roleOf(role, holder),
+                    holder,
+                    auditLog: auditLogPda,
+                    authority: authority.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([authority])
+                .rpc();
+        }
+
+        before(async () => {
+            pauser = Keypair.generate();
+            feeManager = Keypair.generate();
+
+            await program.methods
+                .initializeRoles()
+                .accounts({
+                    config: configPda,
+                    adminRole: roleOf("admin", legitimateAdmin.publicKey),
+                    auditLog: auditLogPda,
+                    admin: legitimateAdmin.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([legitimateAdmin])
+                .rpc();
+
+            await grant("pauser", pauser.publicKey, legitimateAdmin);
+            await grant("feeManager", feeManager.publicKey, legitimateAdmin);
+        });
+
+        describe("Exploit: vulnerable role-gated instructions", () => {
+            it("attacker pauses the protocol with the pauser's pubkey and role account", async () => {
+                await program.methods
+                    .vulnerableSetPaused(true)
+                    .accounts({
+                        config: configPda,
+                        roleGrant: roleOf("pauser", pauser.publicKey),
+                        pauser: pauser.publicKey,
+                    })
+                    .rpc();
+
+                const config = await program.account.config.fetch(configPda);
+                console.log("EXPLOIT SUCCEEDED: Protocol paused without the pauser's signature");
+                expect(config.isPaused).to.equal(true);
+            });
+
+            it("attacker sets the fee with the fee manager's pubkey and role account", async () => {
+                await program.methods
+                    .vulnerableSetFeeAsManager(999)
+                    .accounts({
+                        config: configPda,
+                        roleGrant: roleOf("feeManager", feeManager.publicKey),
+                        feeManager: feeManager.publicKey,
+                    })
+                    .rpc();
+
+                const config = await program.account.config.fetch(configPda);
+                expect(config.feeBps).to.equal(999);
+            });
+        });
+
+        describe("Secure: signer + role PDA", () => {
+            it("records each grant in the audit log", async () => {
+                const { entries } = await auditEntries();
+                const entry = entries[entries.length - 1];
+
+                expect(entry.action).to.deep.equal({ grantRole: {} });
+                expect(entry.actor.toString()).to.equal(legitimateAdmin.publicKey.toString());
+                expect(entry.oldValue.toNumber()).to.equal(ROLE.feeManager);
+                expect(entry.newValue.toString()).to.equal(keyPrefix(feeManager.publicKey));
+            });
+
+            it("rejects the pauser's pubkey without their signature", async () => {
+                try {
+                    await program.methods
+                        .secureSetPaused(false)
+                        .accounts({
+                            config: configPda,
+                            auditLog: auditLogPda,
+                            roleGrant: roleOf("pauser", pauser.publicKey),
+                            pauser: pauser.publicKey,
+                        })
+                        .signers([attacker])
+                        .rpc();
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.message).to.include("unknown signer");
+                }
+            });
+
+            it("rejects another holder's role account", async () => {
+                try {
+                    await program.methods
+                        .secureSetPaused(false)
+                        .accounts({
+                            config: configPda,
+                            auditLog: auditLogPda,
+                            roleGrant: roleOf("pauser", pauser.publicKey),
+                            pauser: attacker.publicKey,
+                        })
+                        .signers([attacker])
+                        .rpc();
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("ConstraintSeeds");
+                }
+            });
+
+            it("rejects a role account for a different role", async () => {
+                try {
+                    await program.methods
+                        .secureSetFeeAsManager(500)
+                        .accounts({
+                            config: configPda,
+                            auditLog: auditLogPda,
+                            roleGrant: roleOf("pauser", pauser.publicKey),
+                            feeManager: pauser.publicKey,
+                        })
+                        .signers([pauser])
+                        .rpc();
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("ConstraintSeeds");
+                }
+            });
+
+            it("lets role holders act with their own signature", async () => {
+                await program.methods
+                    .secureSetPaused(false)
+                    .accounts({
+                        config: configPda,
+                        auditLog: auditLogPda,
+                        roleGrant: roleOf("pauser", pauser.publicKey),
+                        pauser: pauser.publicKey,
+                    })
+                    .signers([pauser])
+                    .rpc();
+                await program.methods
+                    .secureSetFeeAsManager(INITIAL_FEE_BPS)
+                    .accounts({
+                        config: configPda,
+                        auditLog: auditLogPda,
+                        roleGrant: roleOf("feeManager", feeManager.publicKey),
+                        feeManager: feeManager.publicKey,
+                    })
+                    .signers([feeManager])
+                    .rpc();
+
+                const config = await program.account.config.fetch(configPda);
+                expect(config.isPaused).to.equal(false);
+                expect(config.feeBps).to.equal(INITIAL_FEE_BPS);
+            });
+
+            it("holds the fee manager to the per-epoch increase bound", async () => {
+                try {
+                    await program.methods
+                        .secureSetFeeAsManager(INITIAL_FEE_BPS + 101)
+                        .accounts({
+                            config: configPda,
+                            auditLog: auditLogPda,
+                            roleGrant: roleOf("feeManager", feeManager.publicKey),
+                            feeManager: feeManager.publicKey,
+                        })
+                        .signers([feeManager])
+                        .rpc();
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("FeeChangeLimitExceeded");
+                }
+            });
+
+            it("only an Admin role holder can grant roles", async () => {
+                try {
+                    await grant("feeManager", attacker.publicKey, attacker);
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("AccountNotInitialized");
+                }
+            });
+
+            it("a revoked role no longer authorizes", async () => {
+                await program.methods
+                    .revokeRole()
+                    .accounts({
+                        config: configPda,
+                        adminRole: roleOf("admin", legitimateAdmin.publicKey),
+                        roleGrant: roleOf("feeManager", feeManager.publicKey),
+                        auditLog: auditLogPda,
+                        authority: legitimateAdmin.publicKey,
+                    })
+                    .signers([legitimateAdmin])
+                    .rpc();
+
+                try {
+                    await program.methods
+                        .secureSetFeeAsManager(500)
+                        .accounts({
+                            config: configPda,
+                            auditLog: auditLogPda,
+                            roleGrant: roleOf("feeManager", feeManager.publicKey),
+                            feeManager: feeManager.publicKey,
+                        })
+                        .signers([feeManager])
+                        .rpc();
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("AccountNotInitialized");
+                }
+            });
+        });
+    });
+
+    describe("Timelock: config changes executed against caller-supplied time", () => {
+        let pendingChange: PublicKey;
+
+        function adminAccounts(admin: PublicKey) {
+            return { config: configPda, pendingChange, auditLog: auditLogPda, admin };
+        }
+
+        async function queueFee(feeBps: number) {
+            await program.methods
+                .queueConfigChange({ setFee: { feeBps } })
+                .accounts({ ...adminAccounts(legitimateAdmin.publicKey), systemProgram: SystemProgram.programId })
+                .signers([legitimateAdmin])
+                .rpc();
+        }
+
+        before(async () => {
+            [pendingChange] = PublicKey.findProgramAddressSync(
+                [Buffer.from("pending_change"), configPda.toBuffer()],
+                program.programId
+            );
+        });
+
+        describe("Exploit: vulnerable_execute_config_change", () => {
+            it("admin skips the delay by passing a future timestamp", async () => {
+                await queueFee(90);
+
+                const farFuture = Math.floor(Date.now() / 1000) + 365 * 24 * 60 * 60;
+                await program.methods
+                    .vulnerableExecuteConfigChange(new anchor.BN(farFuture))
+                    .accounts(adminAccounts(legitimateAdmin.publicKey))
+                    .signers([legitimateAdmin])
+                    .rpc();
+
+                const config = await program.account.config.fetch(configPda);
+                console.log("EXPLOIT SUCCEEDED: Fee changed in the block it was queued");
+                expect(config.feeBps).to.equal(90);
+            });
+        });
+
+        describe("Secure: secure_execute_config_change", () => {
+            it("rejects execution before the delay has passed", async () => {
+                await queueFee(80);
+
+                try {
+                    await program.methods
+                        .secureExecuteConfigChange()
+                        .accounts(adminAccounts(legitimateAdmin.publicKey))
+                        .signers([legitimateAdmin])
+                        .rpc();
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("TimelockNotExpired");
+                }
+            });
+
+            it("only the admin can cancel a queued change", async () => {
+                try {
+                    await program.methods
+                        .cancelConfigChange()
+                        .accounts(adminAccounts(attacker.publicKey))
+                        .signers([attacker])
+                        .rpc();
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("Unauthorized");
+                }
+
+                await program.methods
+                    .cancelConfigChange()
+                    .accounts(adminAccounts(legitimateAdmin.publicKey))
+                    .signers([legitimateAdmin])
+                    .rpc();
+                expect(await program.account.pendingConfigChange.fetchNullable(pendingChange)).to.be.null;
+            });
+
+            it("executes once the delay has passed on the cluster clock", async () => {
+                await queueFee(80);
+                await sleep(MIN_DELAY_SECS + 2);
+
+                await program.methods
+                    .secureExecuteConfigChange()
+                    .accounts(adminAccounts(legitimateAdmin.publicKey))
+                    .signers([legitimateAdmin])
+                    .rpc();
+
+                const config = await program.account.config.fetch(configPda);
+                expect(config.feeBps).to.equal(80);
+            });
+        });
+    });
+
+    describe("Multisig: approvals counted without deduplication", () => {
+        let owners: Keypair[];
+        let multisigPda: PublicKey;
+
+        const THRESHOLD = 2;
+        const MAX_APPROVALS = 10;
+
+        function proposalAt(id: number): PublicKey {
+            return PublicKey.findProgramAddressSync(
+                [Buffer.from("proposal"), multisigPda.toBuffer(), new anchor.BN(id).toArrayLike(Buffer, "le", 8)],
+                program.programId
+            )[0];
+        }
+
+        async function propose(action: any, proposer: Keypair): Promise<PublicKey> {
+            const { proposalCount } = await program.account.multisig.fetch(multisigPda);
+            const proposal = proposalAt(proposalCount.toNumber());
+            await program.methods
+                .proposeAdminAction(action)
+                .accounts({
+                    multisig: multisigPda,
+                    proposal,
+                    proposer: proposer.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([proposer])
+                .rpc();
+            return proposal;
+        }
+
+        async function approve(
+            proposal: PublicKey,
+            owner: Keypair,
+            method: "vulnerableApprove" | "secureApprove" = "secureApprove"
+        ) {
+            await program.methods[method]()
+                .accounts({ multisig: multisigPda, proposal, owner: owner.publicKey })
+                .signers([owner])
+                .rpc();
+        }
+
+        function execute(method: "vulnerableExecute" | "secureExecute", proposal: PublicKey, executor: Keypair) {
+            return program.methods[method]()
+                .accounts({
+                    config: configPda,
+                    multisig: multisigPda,
+                    proposal,
+                    auditLog: auditLogPda,
+                    executor: executor.publicKey,
+                })
+                .signers([executor])
+                .rpc();
+        }
+
+        before(async () => {
+            owners = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+            const sig = await provider.connection.requestAirdrop(owners[0].publicKey, 2 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+
+            [multisigPda] = PublicKey.findProgramAddressSync(
+                [Buffer.from("multisig"), configPda.toBuffer()],
+                program.programId
+            );
+
+            await program.methods
+                .createMultisig(owners.map((owner) => owner.publicKey), THRESHOLD)
+                .accounts({
+                    config: configPda,
+                    multisig: multisigPda,
+                    auditLog: auditLogPda,
+                    admin: legitimateAdmin.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([legitimateAdmin])
+                .rpc();
+        });
+
+        describe("Exploit: vulnerable_execute", () => {
+            it("one compromised owner approves twice and makes the attacker admin", async () => {
+                const proposal = await propose({ setAdmin: { admin: attacker.publicKey } }, owners[0]);
+                await approve(proposal, owners[0], "vulnerableApprove");
+                await approve(proposal, owners[0], "vulnerableApprove");
+
+                await execute("vulnerableExecute", proposal, owners[0]);
+
+                const config = await program.account.config.fetch(configPda);
+                console.log(`EXPLOIT SUCCEEDED: 1 of ${owners.length} owners met a ${THRESHOLD}-of-${owners.length} threshold`);
+                expect(config.admin.toString()).to.equal(attacker.publicKey.toString());
+            });
+
+            it("restore admin for further tests", async () => {
+                await program.methods
+                    .vulnerableTransferAdmin(legitimateAdmin.publicKey)
+                    .accounts({ config: configPda, caller: attacker.publicKey })
+                    .signers([attacker])
+                    .rpc();
+            });
+
+            it("one owner fills the proposal so the others cannot approve", async () => {
+                const proposal = await propose({ setPaused: { paused: true } }, owners[0]);
+                for (let i = 0; i < MAX_APPROVALS; i++) {
+                    await approve(proposal, owners[0], "vulnerableApprove");
+                }
+
+                try {
+                    await approve(proposal, owners[1], "vulnerableApprove");
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("ProposalFull");
+                }
+                console.log("EXPLOIT SUCCEEDED: One owner locked every other owner out of the proposal");
+            });
+        });
+
+        describe("Secure: secure_approve + secure_execute", () => {
+            let proposal: PublicKey;
+
+            before(async () => {
+                proposal = await propose({ setFee: { feeBps: 70 } }, owners[0]);
+            });
+
+            it("rejects approvals from non-owners", async () => {
+                try {
+                    await approve(proposal, attacker);
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("NotMultisigOwner");
+                }
+            });
+
+            it("rejects a second approval from the same owner", async () => {
+                await approve(proposal, owners[0]);
+
+                try {
+                    await approve(proposal, owners[0]);
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("AlreadyApproved");
+                }
+
+                const { approvals } = await program.account.multisigProposal.fetch(proposal);
+                expect(approvals).to.have.lengthOf(1);
+            });
+
+            it("one owner's approval alone does not meet the threshold", async () => {
+                try {
+                    await execute("secureExecute", proposal, owners[0]);
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("ThresholdNotMet");
+                }
+            });
+
+            it("executes with approvals from two distinct owners, once", async () => {
+                await approve(proposal, owners[1]);
+                await execute("secureExecute", proposal, owners[0]);
+
+                const config = await program.account.config.fetch(configPda);
+                expect(config.feeBps).to.equal(70);
+
+                try {
+                    await execute("secureExecute", proposal, owners[0]);
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("AlreadyExecuted");
+                }
+            });
+        });
+    });
+
+    describe("Audit log: every secure admin change recorded", () => {
+        it("holds one entry per secure change, oldest first, and none from vulnerable paths", async () => {
+            const { total, entries } = await auditEntries();
+            const [multisigPda] = PublicKey.findProgramAddressSync(
+                [Buffer.from("multisig"), configPda.toBuffer()],
+                program.programId
+            );
+
+            for (const entry of entries) {
+                console.log(
+                    `  slot ${entry.slot.toNumber()}: ${Object.keys(entry.action)[0]} ` +
+                    `${entry.oldValue.toString()} -> ${entry.newValue.toString()} by ${entry.actor.toString().slice(0, 8)}...`
+                );
+            }
+
+            const actions = entries.map((entry: any) => Object.keys(entry.action)[0]);
+            expect(actions).to.deep.equal([
+                "setFee",
+                "setFee",
+                "grantRole",
+                "grantRole",
+                "grantRole",
+                "setPaused",
+                "setFee",
+                "revokeRole",
+                "queueChange",
+                "queueChange",
+                "cancelChange",
+                "queueChange",
+                "setFee",
+                "createMultisig",
+                "setFee",
+            ]);
+            expect(total).to.equal(actions.length);
+
+            const feeMoves = entries
+                .filter((entry: any) => "setFee" in entry.action)
+                .map((entry: any) => [entry.oldValue.toNumber(), entry.newValue.toNumber()]);
+            expect(feeMoves).to.deep.equal([
+                [INITIAL_FEE_BPS, INITIAL_FEE_BPS],
+                [999, 150],
+                [999, INITIAL_FEE_BPS],
+                [90, 80],
+                [80, 70],
+            ]);
+
+            expect(entries[0].actor.toString()).to.equal(legitimateAdmin.publicKey.toString());
+            expect(entries[entries.length - 1].actor.toString()).to.equal(multisigPda.toString());
+            for (let i = 1; i < entries.length; i++) {
+                expect(entries[i].slot.toNumber()).to.be.at.least(entries[i - 1].slot.toNumber());
+            }
+        });
+    });
+
+    describe("Treasury: emergency withdraw without authority", () => {
+        let safeWallet: Keypair;
+        let treasuryPda: PublicKey;
+
+        const CAP = 1 * LAMPORTS_PER_SOL;
+        const FUNDS = 3 * LAMPORTS_PER_SOL;
+
+        async function fund(amount: number) {
+            await program.methods
+                .fundTreasury(new anchor.BN(amount))
+                .accounts({
+                    treasury: treasuryPda,
+                    funder: legitimateAdmin.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([legitimateAdmin])
+                .rpc();
+        }
+
+        function secureWithdraw(amount: number, destination: PublicKey, admin: Keypair) {
+            return program.methods
+                .secureEmergencyWithdraw(new anchor.BN(amount))
+                .accounts({
+                    config: configPda,
+                    treasury: treasuryPda,
+                    destination,
+                    auditLog: auditLogPda,
+                    admin: admin.publicKey,
+                })
+                .signers([admin])
+                .rpc();
+        }
+
+        before(async () => {
+            safeWallet = Keypair.generate();
+            [treasuryPda] = PublicKey.findProgramAddressSync(
+                [Buffer.from("treasury"), configPda.toBuffer()],
+                program.programId
+            );
+
+            await program.methods
+                .initializeTreasury([safeWallet.publicKey], new anchor.BN(CAP))
+                .accounts({
+                    config: configPda,
+                    treasury: treasuryPda,
+                    auditLog: auditLogPda,
+                    admin: legitimateAdmin.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([legitimateAdmin])
+                .rpc();
+            await fund(FUNDS);
+        });
+
+        describe("Exploit: vulnerable_emergency_withdraw", () => {
+            it("attacker drains the treasury without any signature from the admin", async () => {
+                const before = await provider.connection.getBalance(attacker.publicKey);
+
+                await program.methods
+                    .vulnerableEmergencyWithdraw(new anchor.BN(FUNDS))
+                    .accounts({ treasury: treasuryPda, destination: attacker.publicKey })
+                    .rpc();
+
+                const after = await provider.connection.getBalance(attacker.publicKey);
+                console.log(`EXPLOIT SUCCEEDED: Attacker received ${(after - before) / LAMPORTS_PER_SOL} SOL from the treasury`);
+                expect(after - before).to.equal(FUNDS);
+            });
+
+            after(async () => {
+                await fund(FUNDS);
+            });
+        });
+
+        describe("Secure: secure_emergency_withdraw", () => {
+            it("rejects anyone but the admin", async () => {
+                try {
+                    await secureWithdraw(CAP, safeWallet.publicKey, attacker);
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("Unauthorized");
+                }
+            });
+
+            it("rejects destinations off the allowlist, even for the admin", async () => {
+                try {
+                    await secureWithdraw(CAP, legitimateAdmin.publicKey, legitimateAdmin);
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("DestinationNotAllowed");
+                }
+            });
+
+            it("rejects amounts over the cap", async () => {
+                try {
+                    await secureWithdraw(CAP + 1, safeWallet.publicKey, legitimateAdmin);
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("WithdrawalCapExceeded");
+                }
+            });
+
+            it("sends a capped amount to an allowed destination and records it", async () => {
+                const events: any[] = [];
+                const listener = program.addEventListener("EmergencyWithdrawal", (event) => {
+                    events.push(event);
+                });
+                const logBefore = await auditEntries();
+
+                await secureWithdraw(CAP, safeWallet.publicKey, legitimateAdmin);
+
+                await sleep(1);
+                await program.removeEventListener(listener);
+
+                expect(await provider.connection.getBalance(safeWallet.publicKey)).to.equal(CAP);
+                expect(events).to.have.lengthOf(1);
+                expect(events[0].destination.toString()).to.equal(safeWallet.publicKey.toString());
+                expect(events[0].amount.toNumber()).to.equal(CAP);
+
+                const log = await auditEntries();
+                const entry = log.entries[log.entries.length - 1];
+                expect(log.total).to.equal(logBefore.total + 1);
+                expect(entry.action).to.deep.equal({ emergencyWithdraw: {} });
+                expect(entry.oldValue.toNumber() - entry.newValue.toNumber()).to.equal(CAP);
+            });
+        });
+    });
+
+    describe("Admin nomination: stale nominations accepted forever", () => {
+        let successor: Keypair;
+
+        const WINDOW_SLOTS = 5;
+
+        function nominate(newAdmin: PublicKey, admin: Keypair) {
+            return program.methods
+                .nominateAdmin(newAdmin, new anchor.BN(WINDOW_SLOTS))
+                .accounts({ config: configPda, auditLog: auditLogPda, admin: admin.publicKey })
+                .signers([admin])
+                .rpc();
+        }
+
+        function accept(method: "vulnerableAcceptAdmin" | "secureAcceptAdmin", newAdmin: Keypair) {
+            return program.methods[method]()
+                .accounts({ config: configPda, auditLog: auditLogPda, newAdmin: newAdmin.publicKey })
+                .signers([newAdmin])
+                .rpc();
+        }
+
+        async function waitPastExpiry() {
+            const { nominationExpiry } = await program.account.config.fetch(configPda);
+            while ((await provider.connection.getSlot("confirmed")) <= nominationExpiry.toNumber()) {
+                await sleep(0.4);
+            }
+        }
+
+        before(async () => {
+            successor = Keypair.generate();
+        });
+
+        describe("Exploit: vulnerable_accept_admin", () => {
+            it("accepts a nomination after it has expired", async () => {
+                await nominate(successor.publicKey, legitimateAdmin);
+                await waitPastExpiry();
+
+                await accept("vulnerableAcceptAdmin", successor);
+
+                const config = await program.account.config.fetch(configPda);
+                console.log("EXPLOIT SUCCEEDED: Expired nomination still made the nominee admin");
+                expect(config.admin.toString()).to.equal(successor.publicKey.toString());
+            });
+
+            it("restore admin for further tests", async () => {
+                await nominate(legitimateAdmin.publicKey, successor);
+                await accept("secureAcceptAdmin", legitimateAdmin);
+
+                const config = await program.account.config.fetch(configPda);
+                expect(config.admin.toString()).to.equal(legitimateAdmin.publicKey.toString());
+                expect(config.pendingAdmin).to.be.null;
+            });
+        });
+
+        describe("Secure: secure_accept_admin + cancel_nomination", () => {
+            it("rejects an expired nomination", async () => {
+                await nominate(successor.publicKey, legitimateAdmin);
+                await waitPastExpiry();
+
+                try {
+                    await accept("secureAcceptAdmin", successor);
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("NominationExpired");
+                }
+            });
+
+            it("rejects acceptance by anyone but the nominee", async () => {
+                await nominate(successor.publicKey, legitimateAdmin);
+
+                try {
+                    await accept("secureAcceptAdmin", attacker);
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("NoPendingNomination");
+                }
+            });
+
+            it("only the admin can cancel, and a cancelled nomination cannot be accepted", async () => {
+                try {
+                    await program.methods
+                        .cancelNomination()
+                        .accounts({ config: configPda, auditLog: auditLogPda, admin: attacker.publicKey })
+                        .signers([attacker])
+                        .rpc();
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("Unauthorized");
+                }
+
+                await program.methods
+                    .cancelNomination()
+                    .accounts({ config: configPda, auditLog: auditLogPda, admin: legitimateAdmin.publicKey })
+                    .signers([legitimateAdmin])
+                    .rpc();
+
+                try {
+                    await accept("secureAcceptAdmin", successor);
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("NoPendingNomination");
+                }
+            });
+        });
+    });
+
+    describe("Session keys: a hot key treated as full admin", () => {
+        let hotKey: Keypair;
+
+        const PERMISSION_SET_FEE = 1 << 0;
+        const PERMISSION_SET_PAUSED = 1 << 1;
+
+        function sessionOf(authority: PublicKey, sessionKey: PublicKey): PublicKey {
+            return PublicKey.findProgramAddressSync(
+                [Buffer.from("session"), authority.toBuffer(), sessionKey.toBuffer()],
+                program.programId
+            )[0];
+        }
+
+        async function issue(sessionKey: PublicKey, permissions: number, validForSecs: number) {
+            await program.methods
+                .issueSessionKey(permissions, new anchor.BN(validForSecs))
+                .accounts({
+                    config: configPda,
+                    session: sessionOf(legitimateAdmin.publicKey, sessionKey),
+                    sessionKey,
+                    auditLog: auditLogPda,
+                    admin: legitimateAdmin.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([legitimateAdmin])
+                .rpc();
+        }
+
+        function secureAction(action: any, sessionKey: Keypair) {
+            return program.methods
+                .secureSessionAdminAction(action)
+                .accounts({
+                    config: configPda,
+                    session: sessionOf(legitimateAdmin.publicKey, sessionKey.publicKey),
+                    auditLog: auditLogPda,
+                    sessionKey: sessionKey.publicKey,
+                })
+                .signers([sessionKey])
+                .rpc();
+        }
+
+        before(async () => {
+            hotKey = Keypair.generate();
+            await issue(hotKey.publicKey, PERMISSION_SET_FEE, 600);
+        });
+
+        describe("Exploit: vulnerable_session_admin_action", () => {
+            it("a fee-only session key replaces the admin", async () => {
+                await program.methods
+                    .vulnerableSessionAdminAction({ setAdmin: { admin: hotKey.publicKey } })
+                    .accounts({
+                        config: configPda,
+                        session: sessionOf(legitimateAdmin.publicKey, hotKey.publicKey),
+                        sessionKey: hotKey.publicKey,
+                    })
+                    .signers([hotKey])
+                    .rpc();
+
+                const config = await program.account.config.fetch(configPda);
+                console.log("EXPLOIT SUCCEEDED: A session key scoped to fees took over the admin role");
+                expect(config.admin.toString()).to.equal(hotKey.publicKey.toString());
+            });
+
+            it("restore admin for further tests", async () => {
+                await program.methods
+                    .vulnerableTransferAdmin(legitimateAdmin.publicKey)
+                    .accounts({ config: configPda, caller: attacker.publicKey })
+                    .signers([attacker])
+                    .rpc();
+            });
+        });
+
+        describe("Secure: secure_session_admin_action", () => {
+            it("allows the permitted action", async () => {
+                await secureAction({ setFee: { feeBps: 60 } }, hotKey);
+
+                const config = await program.account.config.fetch(configPda);
+                expect(config.feeBps).to.equal(60);
+            });
+
+            it("rejects actions outside the session's permissions", async () => {
+                for (const action of [{ setPaused: { paused: true } }, { setAdmin: { admin: hotKey.publicKey } }]) {
+                    try {
+                        await secureAction(action, hotKey);
+                        expect.fail("Should have thrown");
+                    } catch (error: any) {
+                        expect(error.error?.errorCode?.code).to.equal("PermissionDenied");
+                    }
+                }
+            });
+
+            it("rejects an expired session", async () => {
+                const shortLived = Keypair.generate();
+                await issue(shortLived.publicKey, PERMISSION_SET_FEE | PERMISSION_SET_PAUSED, 1);
+                await sleep(3);
+
+                try {
+                    await secureAction({ setFee: { feeBps: 1 } }, shortLived);
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("SessionExpired");
+                }
+            });
+
+            it("only the admin can issue session keys", async () => {
+                try {
+                    await program.methods
+                        .issueSessionKey(PERMISSION_SET_FEE, new anchor.BN(600))
+                        .accounts({
+                            config: configPda,
+                            session: sessionOf(attacker.publicKey, attacker.publicKey),
+                            sessionKey: attacker.publicKey,
+                            auditLog: auditLogPda,
+                            admin: attacker.publicKey,
+                            systemProgram: SystemProgram.programId,
+                        })
+                        .signers([attacker])
+                        .rpc();
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("Unauthorized");
+                }
+            });
+
+            it("a revoked session no longer loads", async () => {
+                await program.methods
+                    .revokeSessionKey()
+                    .accounts({
+                        config: configPda,
+                        session: sessionOf(legitimateAdmin.publicKey, hotKey.publicKey),
+                        auditLog: auditLogPda,
+                        authority: legitimateAdmin.publicKey,
+                    })
+                    .signers([legitimateAdmin])
+                    .rpc();
+
+                try {
+                    await secureAction({ setFee: { feeBps: 1 } }, hotKey);
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("AccountNotInitialized");
+                }
+            });
+        });
+    });
+
+    describe("Pause: user actions that ignore the circuit breaker", () => {
+        let guard: Keypair;
+        let user: Keypair;
+        let treasuryPda: PublicKey;
+        let guardRole: PublicKey;
+
+        const AMOUNT = LAMPORTS_PER_SOL / 10;
+
+        function setPaused(paused: boolean) {
+            return program.methods
+                .secureSetPaused(paused)
+                .accounts({
+                    config: configPda,
+                    roleGrant: guardRole,
+                    auditLog: auditLogPda,
+                    pauser: guard.publicKey,
+                })
+                .signers([guard])
+                .rpc();
+        }
+
+        function userAction(method: "vulnerableUserAction" | "secureUserAction") {
+            return program.methods[method](new anchor.BN(AMOUNT))
+                .accounts({
+                    config: configPda,
+                    treasury: treasuryPda,
+                    user: user.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([user])
+                .rpc();
+        }
+
+        before(async () => {
+            guard = Keypair.generate();
+            user = Keypair.generate();
+            const sig = await provider.connection.requestAirdrop(user.publicKey, LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+
+            [treasuryPda] = PublicKey.findProgramAddressSync(
+                [Buffer.from("treasury"), configPda.toBuffer()],
+                program.programId
+            );
+            [guardRole] = PublicKey.findProgramAddressSync(
+                [Buffer.from("role"), Buffer.from([2]), guard.publicKey.toBuffer()],
+                program.programId
+            );
+
+            await program.methods
+                .grantRole({ pauser: {} })
+                .accounts({
+                    config: configPda,
+                    adminRole: PublicKey.findProgramAddressSync(
+                        [Buffer.from("role"), Buffer.from([0]), legitimateAdmin.publicKey.toBuffer()],
+                        program.programId
+                    )[0],
+                    roleGrant: guardRole,
+                    holder: guard.publicKey,
+                    auditLog: auditLogPda,
+                    authority: legitimateAdmin.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([legitimateAdmin])
+                .rpc();
+
+            await setPaused(true);
+        });
+
+        describe("Exploit: vulnerable_user_action", () => {
+            it("goes through while the protocol is paused", async () => {
+                const before = await provider.connection.getBalance(treasuryPda);
+                await userAction("vulnerableUserAction");
+                const after = await provider.connection.getBalance(treasuryPda);
+
+                console.log("EXPLOIT SUCCEEDED: User action executed while paused");
+                expect(after - before).to.equal(AMOUNT);
+            });
+        });
+
+        describe("Secure: secure_user_action", () => {
+            it("is rejected while paused", async () => {
+                try {
+                    await userAction("secureUserAction");
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("PausedError");
+                }
+            });
+
+            it("works again once unpaused", async () => {
+                await setPaused(false);
+
+                const before = await provider.connection.getBalance(treasuryPda);
+                await userAction("secureUserAction");
+                const after = await provider.connection.getBalance(treasuryPda);
+                expect(after - before).to.equal(AMOUNT);
+            });
+        });
+    });
+
+    describe("Fee bounds: instant fee hikes", () => {
+        const MAX_INCREASE_BPS = 100;
+
+        function updateFee(method: "vulnerableUnboundedUpdateFee" | "secureUpdateFee", feeBps: number) {
+            const accounts = { config: configPda, admin: legitimateAdmin.publicKey };
+            return program.methods[method](feeBps)
+                .accounts(method === "secureUpdateFee" ? { ...accounts, auditLog: auditLogPda } : accounts)
+                .signers([legitimateAdmin])
+                .rpc();
+        }
+
+        /** Waits out the current epoch if it is about to end, so two calls land in the same one. */
+        async function awaitRoomyEpoch() {
+            const { epoch, slotIndex, slotsInEpoch } = await provider.connection.getEpochInfo("confirmed");
+            if (slotsInEpoch - slotIndex > 20) {
+                return;
+            }
+            while ((await provider.connection.getEpochInfo("confirmed")).epoch === epoch) {
+                await sleep(0.4);
+            }
+        }
+
+        describe("Exploit: vulnerable_unbounded_update_fee", () => {
+            it("admin raises the fee to 50% in one transaction", async () => {
+                const { feeBps: before } = await program.account.config.fetch(configPda);
+
+                await updateFee("vulnerableUnboundedUpdateFee", 5_000);
+
+                const config = await program.account.config.fetch(configPda);
+                console.log(`EXPLOIT SUCCEEDED: Fee jumped from ${before} to ${config.feeBps} bps at once`);
+                expect(config.feeBps).to.equal(5_000);
+
+                await updateFee("vulnerableUnboundedUpdateFee", before);
+            });
+        });
+
+        describe("Secure: secure_update_fee", () => {
+            it("allows increases up to the per-epoch bound, then rejects", async () => {
+                await awaitRoomyEpoch();
+                const { feeBps } = await program.account.config.fetch(configPda);
+                const { epoch } = await provider.connection.getEpochInfo("confirmed");
+
+                await updateFee("secureUpdateFee", feeBps + MAX_INCREASE_BPS);
+
+                try {
+                    await updateFee("secureUpdateFee", feeBps + MAX_INCREASE_BPS + 1);
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("FeeChangeLimitExceeded");
+                }
+
+                const config = await program.account.config.fetch(configPda);
+                expect(config.lastFeeChangeEpoch.toNumber()).to.equal(epoch);
+                expect(config.feeChangeThisEpoch).to.equal(MAX_INCREASE_BPS);
+            });
+
+            it("always allows decreases", async () => {
+                const { feeBps } = await program.account.config.fetch(configPda);
+
+                await updateFee("secureUpdateFee", feeBps - MAX_INCREASE_BPS);
+
+                const config = await program.account.config.fetch(configPda);
+                expect(config.feeBps).to.equal(feeBps - MAX_INCREASE_BPS);
+            });
+        });
+    });
+
+    describe("Guardian: separating who proposes from who can block", () => {
+        let pendingChange: PublicKey;
+
+        const queueFee = (signer: Keypair, feeBps: number) =>
+            program.methods
+                .queueConfigChange({ setFee: { feeBps } })
+                .accounts({
+                    config: configPda,
+                    pendingChange,
+                    auditLog: auditLogPda,
+                    admin: signer.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([signer])
+                .rpc();
+
+        const veto = (signer: Keypair) =>
+            program.methods
+                .vetoPendingChange()
+                .accounts({
+                    config: configPda,
+                    pendingChange,
+                    auditLog: auditLogPda,
+                    guardian: signer.publicKey,
+                    admin: legitimateAdmin.publicKey,
+                })
+                .signers([signer])
+                .rpc();
+
+        before(async () => {
+            [pendingChange] = PublicKey.findProgramAddressSync(
+                [Buffer.from("pending_change"), configPda.toBuffer()],
+                program.programId
+            );
+        });
+
+        it("the guardian vetoes a queued change and the admin gets the rent back", async () => {
+            await queueFee(legitimateAdmin, 5_000);
+            const adminBefore = await provider.connection.getBalance(legitimateAdmin.publicKey);
+
+            await veto(guardian);
+
+            expect(await provider.connection.getAccountInfo(pendingChange)).to.be.null;
+            const adminAfter = await provider.connection.getBalance(legitimateAdmin.publicKey);
+            expect(adminAfter).to.be.greaterThan(adminBefore);
+        });
+
+        it("rejects a veto from anyone but the guardian", async () => {
+            await queueFee(legitimateAdmin, 5_000);
+
+            for (const signer of [attacker, legitimateAdmin]) {
+                try {
+                    await veto(signer);
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.error?.errorCode?.code).to.equal("Unauthorized");
+                }
+            }
+
+            await veto(guardian);
+        });
+
+        it("the guardian cannot initiate changes", async () => {
+            try {
+                await queueFee(guardian, 0);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("Unauthorized");
+            }
+
+            try {
+                await program.methods
+                    .secureUpdateFee(0)
+                    .accounts({ config: configPda, auditLog: auditLogPda, admin: guardian.publicKey })
+                    .signers([guardian])
+                    .rpc();
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("Unauthorized");
+            }
+        });
+    });
 });
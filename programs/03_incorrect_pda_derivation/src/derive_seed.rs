@@ -0,0 +1,35 @@
+//! Seeds built from user-supplied strings.
+//!
+//! Raw strings make poor seeds for two reasons. The runtime rejects any seed
+//! over 32 bytes, and it hashes all seeds as one concatenated byte string,
+//! so `["ali", "cedevs"]` and `["alice", "devs"]` derive the same address.
+//!
+//! `hashed` fixes both: every part is length-prefixed and the whole is
+//! hashed into a single 32-byte seed. A single string is just `&[value]`.
+
+use security_common::pda::hashed_seed;
+
+/// `sha256(len(p0) || p0 || len(p1) || p1 || ...)`, always 32 bytes.
+pub fn hashed(parts: &[&str]) -> [u8; 32] {
+    let bytes: Vec<&[u8]> = parts.iter().map(|part| part.as_bytes()).collect();
+    hashed_seed(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shifted_boundaries_do_not_collide() {
+        assert_eq!(["ali", "cedevs"].concat(), ["alice", "devs"].concat());
+        assert_ne!(hashed(&["ali", "cedevs"]), hashed(&["alice", "devs"]));
+        assert_ne!(hashed(&["alicedevs"]), hashed(&["alice", "devs"]));
+    }
+
+    #[test]
+    fn long_strings_fit_one_seed() {
+        let long = "a".repeat(200);
+        assert_eq!(hashed(&[&long]).len(), 32);
+        assert_ne!(hashed(&[&long]), hashed(&[&long[1..]]));
+    }
+}
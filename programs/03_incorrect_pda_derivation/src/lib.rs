@@ -1,7 +1,24 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer};
+use mock_vault::Vault;
+use security_common::time::{ClockExt, UnixTs};
+use unicode_normalization::UnicodeNormalization;
+
+pub mod derive_seed;
 
 declare_id!("3UFE7yLEjqFt2WDGHkWeUnfR2C3ttJUYad2ty3V2TEsa");
 
+/// Bounds on a normalized username, in bytes. 32 is also the runtime's
+/// per-seed limit.
+pub const MIN_USERNAME_LEN: usize = 3;
+pub const MAX_USERNAME_LEN: usize = 32;
+
+/// Longest org or team name. Hashed seeds have no 32-byte limit.
+pub const MAX_TEAM_NAME_LEN: usize = 64;
+
+/// Vault balance, in the vault program, a profile's owner needs for a badge.
+pub const MIN_BADGE_BALANCE: u64 = 1_000;
+
 #[program]
 pub mod incorrect_pda_derivation {
     use super::*;
@@ -29,6 +46,358 @@ pub mod incorrect_pda_derivation {
         profile.bump = ctx.bumps.profile;
         Ok(())
     }
+
+    /// Creates the username registry. `claim_duration_secs` is how long a
+    /// claim lasts before it has to be renewed, and `recovery_delay_secs`
+    /// how long a squatted profile stays flagged before it can be reclaimed.
+    pub fn initialize_registry(
+        ctx: Context<InitializeRegistry>,
+        claim_duration_secs: i64,
+        recovery_delay_secs: i64,
+    ) -> Result<()> {
+        require!(
+            claim_duration_secs > 0 && recovery_delay_secs > 0,
+            ProfileError::InvalidDuration
+        );
+
+        let registry = &mut ctx.accounts.registry;
+        registry.authority = ctx.accounts.authority.key();
+        registry.claim_duration_secs = claim_duration_secs;
+        registry.recovery_delay_secs = recovery_delay_secs;
+        registry.bump = ctx.bumps.registry;
+        Ok(())
+    }
+
+    // SECURE: Global Username Registry
+    // 1. The profile stays keyed by its owner; the name lives in a separate
+    //    `UsernameClaim` that points at it.
+    // 2. The claim is keyed by the *normalized* name, so "Alice" and a
+    //    full-width "Ａｌｉｃｅ" cannot be claimed twice.
+    // 3. Claims expire, and disputes go to the registry authority.
+    pub fn claim_username(ctx: Context<ClaimUsername>, username: String) -> Result<()> {
+        let now = Clock::get()?.current_unix_ts();
+        let claim = &mut ctx.accounts.claim;
+        claim.owner = ctx.accounts.owner.key();
+        claim.profile = ctx.accounts.profile.key();
+        claim.username = normalize_username(&username)?;
+        claim.claimed_at = now.0;
+        claim.expires_at = now
+            .checked_add_secs(ctx.accounts.registry.claim_duration_secs)
+            .ok_or(ProfileError::ArithmeticError)?
+            .0;
+        claim.bump = ctx.bumps.claim;
+        Ok(())
+    }
+
+    /// Extends a live claim by another `claim_duration_secs` from now.
+    pub fn renew_username(ctx: Context<RenewUsername>) -> Result<()> {
+        let now = Clock::get()?.current_unix_ts();
+        let claim = &mut ctx.accounts.claim;
+        require!(now < UnixTs(claim.expires_at), ProfileError::ClaimExpired);
+
+        claim.expires_at = now
+            .checked_add_secs(ctx.accounts.registry.claim_duration_secs)
+            .ok_or(ProfileError::ArithmeticError)?
+            .0;
+        Ok(())
+    }
+
+    /// Anyone can close an expired claim, which frees the name. The rent
+    /// goes back to the owner.
+    pub fn release_expired_username(ctx: Context<ReleaseExpiredUsername>) -> Result<()> {
+        let now = Clock::get()?.current_unix_ts();
+        require!(now >= UnixTs(ctx.accounts.claim.expires_at), ProfileError::ClaimNotExpired);
+        Ok(())
+    }
+
+    /// A user with a profile contests someone else's claim. The registry
+    /// authority decides in `resolve_dispute`.
+    pub fn open_dispute(ctx: Context<OpenDispute>) -> Result<()> {
+        let dispute = &mut ctx.accounts.dispute;
+        dispute.claim = ctx.accounts.claim.key();
+        dispute.challenger = ctx.accounts.challenger.key();
+        dispute.challenger_profile = ctx.accounts.challenger_profile.key();
+        dispute.opened_at = Clock::get()?.current_unix_ts().0;
+        dispute.bump = ctx.bumps.dispute;
+        Ok(())
+    }
+
+    /// Closes the dispute. If `uphold`, the name moves to the challenger
+    /// with a fresh claim period.
+    pub fn resolve_dispute(ctx: Context<ResolveDispute>, uphold: bool) -> Result<()> {
+        if uphold {
+            let now = Clock::get()?.current_unix_ts();
+            let dispute = &ctx.accounts.dispute;
+            let claim = &mut ctx.accounts.claim;
+            claim.owner = dispute.challenger;
+            claim.profile = dispute.challenger_profile;
+            claim.claimed_at = now.0;
+            claim.expires_at = now
+                .checked_add_secs(ctx.accounts.registry.claim_duration_secs)
+                .ok_or(ProfileError::ArithmeticError)?
+                .0;
+        }
+        Ok(())
+    }
+
+    // VULNERABLE: Recovery Without Authority
+    // Meant to undo squatting, but anyone can call it, on any profile, and it
+    // takes effect at once. The recovery tool is now the squatter's best tool.
+    pub fn vulnerable_reclaim_profile(ctx: Context<VulnerableReclaimProfile>, new_authority: Pubkey) -> Result<()> {
+        ctx.accounts.profile.authority = new_authority;
+        Ok(())
+    }
+
+    // SECURE: Flag, Wait, Then Reclaim
+    // 1. Only the registry authority can flag a squatted profile.
+    // 2. The flag records the outcome up front: a new authority, or `None`
+    //    to tombstone the name.
+    // 3. Nothing changes until `recovery_delay_secs` have passed, so the
+    //    flag is public and can be cancelled before it takes effect.
+    pub fn flag_squatter(ctx: Context<FlagSquatter>, rightful_owner: Option<Pubkey>) -> Result<()> {
+        let now = Clock::get()?.current_unix_ts();
+        let flag = &mut ctx.accounts.flag;
+        flag.profile = ctx.accounts.profile.key();
+        flag.squatter = ctx.accounts.profile.authority;
+        flag.rightful_owner = rightful_owner;
+        flag.flagged_at = now.0;
+        flag.reclaimable_at = now
+            .checked_add_secs(ctx.accounts.registry.recovery_delay_secs)
+            .ok_or(ProfileError::ArithmeticError)?
+            .0;
+        flag.bump = ctx.bumps.flag;
+        Ok(())
+    }
+
+    /// Drops a flag before it is acted on.
+    pub fn cancel_squat_flag(_ctx: Context<CancelSquatFlag>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Applies a flag once its waiting period is over. A tombstoned profile
+    /// keeps its account, so the name cannot be created again, but its
+    /// authority is the default key, which nobody can sign for.
+    pub fn reclaim_profile(ctx: Context<ReclaimProfile>) -> Result<()> {
+        let flag = &ctx.accounts.flag;
+        require!(
+            Clock::get()?.current_unix_ts() >= UnixTs(flag.reclaimable_at),
+            ProfileError::RecoveryNotReady
+        );
+
+        ctx.accounts.profile.authority = flag.rightful_owner.unwrap_or_default();
+        Ok(())
+    }
+
+    // VULNERABLE: Foreign PDA Accepted Without Validation
+    // 1. The vault is an `UncheckedAccount` decoded with `try_deserialize`,
+    //    which only checks the 8-byte discriminator, not the owning program
+    // 2. Nothing re-derives the vault's address, so someone else's genuine
+    //    vault works just as well as a forged one
+    pub fn vulnerable_claim_badge(ctx: Context<VulnerableClaimBadge>) -> Result<()> {
+        let data = ctx.accounts.vault.try_borrow_data()?;
+        let vault = Vault::try_deserialize(&mut &data[..])?;
+        drop(data);
+
+        issue_badge(&mut ctx.accounts.badge, &ctx.accounts.profile, ctx.accounts.vault.key(), &vault, ctx.bumps.badge)
+    }
+
+    // SECURE: Cross-Program PDA Validation
+    // 1. `Account<Vault>` checks the owner is the vault program and the
+    //    discriminator is `Vault`'s
+    // 2. `seeds::program = mock_vault::ID` re-derives the address from the
+    //    signer's key in the vault program's address space
+    // 3. `bump = vault.bump` pins the canonical bump the vault program stored
+    pub fn secure_claim_badge(ctx: Context<SecureClaimBadge>) -> Result<()> {
+        let vault_key = ctx.accounts.vault.key();
+        issue_badge(&mut ctx.accounts.badge, &ctx.accounts.profile, vault_key, &ctx.accounts.vault, ctx.bumps.badge)
+    }
+
+    // VULNERABLE: Raw Strings Concatenated Into Seeds
+    // The runtime hashes seeds back to back, so org "ali" with team "cedevs"
+    // derives the same address as org "alice" with team "devs". Names over
+    // 32 bytes cannot be used at all.
+    pub fn vulnerable_create_team(ctx: Context<VulnerableCreateTeam>, org: String, name: String) -> Result<()> {
+        write_team(&mut ctx.accounts.team, ctx.accounts.owner.key(), org, name, ctx.bumps.team)
+    }
+
+    // SECURE: Length-Prefixed, Hashed Seed
+    // `derive_seed::hashed` length-prefixes each part and hashes the result
+    // to 32 bytes, so boundaries count and long names fit.
+    pub fn secure_create_team(ctx: Context<SecureCreateTeam>, org: String, name: String) -> Result<()> {
+        write_team(&mut ctx.accounts.team, ctx.accounts.owner.key(), org, name, ctx.bumps.team)
+    }
+
+    /// Locks `amount` of the maker's tokens for `recipient` in a token
+    /// account owned by the escrow PDA. After `timeout_secs` they can be
+    /// refunded to the maker.
+    pub fn create_escrow(
+        ctx: Context<CreateEscrow>,
+        id: u64,
+        recipient: Pubkey,
+        amount: u64,
+        timeout_secs: i64,
+    ) -> Result<()> {
+        require!(amount > 0, ProfileError::InvalidAmount);
+        require!(timeout_secs > 0, ProfileError::InvalidDuration);
+
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.maker = ctx.accounts.maker.key();
+        escrow.recipient = recipient;
+        escrow.mint = ctx.accounts.mint.key();
+        escrow.id = id;
+        escrow.amount = amount;
+        escrow.expires_at = Clock::get()?
+            .current_unix_ts()
+            .checked_add_secs(timeout_secs)
+            .ok_or(ProfileError::ArithmeticError)?
+            .0;
+        escrow.bump = ctx.bumps.escrow;
+        escrow.vault_bump = ctx.bumps.vault;
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.maker_token.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.maker.to_account_info(),
+        };
+        token::transfer(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts), amount)
+    }
+
+    // VULNERABLE: Signer Seeds Rebuilt From Instruction Data
+    // 1. The caller supplies maker, recipient, id and bump, and the program
+    //    signs with whatever PDA they derive
+    // 2. Nothing loads the `Escrow` those seeds belong to, so nothing checks
+    //    the caller is the maker or the destination is the recipient's
+    // 3. Anyone who reads an escrow's seeds off-chain can drain its vault
+    pub fn vulnerable_release(
+        ctx: Context<VulnerableRelease>,
+        maker: Pubkey,
+        recipient: Pubkey,
+        id: u64,
+        bump: u8,
+    ) -> Result<()> {
+        let id = id.to_le_bytes();
+        let seeds: &[&[u8]] = &[b"escrow", maker.as_ref(), recipient.as_ref(), &id, &[bump]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.escrow.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, &[seeds]),
+            ctx.accounts.vault.amount,
+        )
+    }
+
+    // SECURE: Stored Escrow, Canonical Bump
+    // 1. The `Escrow` account is loaded and re-derived with its stored
+    //    canonical bump; the signer seeds come from it, not from the caller
+    // 2. Only the maker can release, and only to a token account the
+    //    recipient owns, of the escrowed mint
+    pub fn release_to_recipient(ctx: Context<ReleaseToRecipient>) -> Result<()> {
+        let accounts = &ctx.accounts;
+        pay_out_escrow(
+            &accounts.escrow,
+            &accounts.vault,
+            &accounts.recipient_token,
+            &accounts.maker.to_account_info(),
+            &accounts.token_program,
+        )
+    }
+
+    /// Returns the tokens to the maker once the escrow has expired. Anyone
+    /// can send it, since the funds can only go back to the maker.
+    pub fn refund_after_timeout(ctx: Context<RefundAfterTimeout>) -> Result<()> {
+        let accounts = &ctx.accounts;
+        require!(
+            Clock::get()?.current_unix_ts() >= UnixTs(accounts.escrow.expires_at),
+            ProfileError::EscrowNotExpired
+        );
+
+        pay_out_escrow(
+            &accounts.escrow,
+            &accounts.vault,
+            &accounts.maker_token,
+            &accounts.maker.to_account_info(),
+            &accounts.token_program,
+        )
+    }
+}
+
+/// Moves the whole vault to `destination` and closes it, refunding its rent
+/// to the maker. Both CPIs are signed by the escrow PDA with its stored bump.
+fn pay_out_escrow<'info>(
+    escrow: &Account<'info, Escrow>,
+    vault: &Account<'info, TokenAccount>,
+    destination: &Account<'info, TokenAccount>,
+    maker: &AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    let id = escrow.id.to_le_bytes();
+    let seeds: &[&[u8]] = &[
+        b"escrow",
+        escrow.maker.as_ref(),
+        escrow.recipient.as_ref(),
+        &id,
+        &[escrow.bump],
+    ];
+
+    let signer = &[seeds];
+
+    let cpi_accounts = Transfer {
+        from: vault.to_account_info(),
+        to: destination.to_account_info(),
+        authority: escrow.to_account_info(),
+    };
+    token::transfer(
+        CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer),
+        vault.amount,
+    )?;
+
+    let cpi_accounts = CloseAccount {
+        account: vault.to_account_info(),
+        destination: maker.clone(),
+        authority: escrow.to_account_info(),
+    };
+    token::close_account(CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer))
+}
+
+fn write_team(team: &mut Team, owner: Pubkey, org: String, name: String, bump: u8) -> Result<()> {
+    require!(
+        org.len() <= MAX_TEAM_NAME_LEN && name.len() <= MAX_TEAM_NAME_LEN,
+        ProfileError::NameTooLong
+    );
+    team.owner = owner;
+    team.org = org;
+    team.name = name;
+    team.bump = bump;
+    Ok(())
+}
+
+fn issue_badge(badge: &mut Badge, profile: &Account<Profile>, vault_key: Pubkey, vault: &Vault, bump: u8) -> Result<()> {
+    require!(vault.balance >= MIN_BADGE_BALANCE, ProfileError::InsufficientBalance);
+
+    badge.profile = profile.key();
+    badge.vault = vault_key;
+    badge.balance_at_issue = vault.balance;
+    badge.bump = bump;
+    Ok(())
+}
+
+/// NFKC-normalizes and lowercases `raw`, then allows only `[a-z0-9_]`.
+///
+/// NFKC folds compatibility forms such as full-width letters into their
+/// plain equivalents. Lookalikes from other scripts (Cyrillic "а" for Latin
+/// "a") survive any normalization, so they are rejected outright.
+pub fn normalize_username(raw: &str) -> Result<String> {
+    let normalized: String = raw.nfkc().flat_map(char::to_lowercase).collect();
+    require!(
+        (MIN_USERNAME_LEN..=MAX_USERNAME_LEN).contains(&normalized.len())
+            && normalized
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_'),
+        ProfileError::InvalidUsername
+    );
+    Ok(normalized)
 }
 
 #[derive(Accounts)]
@@ -71,3 +440,489 @@ pub struct Profile {
     pub username: String,
     pub bump: u8,
 }
+
+#[derive(Accounts)]
+pub struct InitializeRegistry<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Registry::INIT_SPACE,
+        seeds = [b"registry"],
+        bump
+    )]
+    pub registry: Account<'info, Registry>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(username: String)]
+pub struct ClaimUsername<'info> {
+    #[account(seeds = [b"registry"], bump = registry.bump)]
+    pub registry: Account<'info, Registry>,
+    #[account(seeds = [b"profile_secure", owner.key().as_ref()], bump = profile.bump)]
+    pub profile: Account<'info, Profile>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + UsernameClaim::INIT_SPACE,
+        // GOOD SEEDS: The normalized name, so every spelling of it maps here.
+        seeds = [b"username", normalize_username(&username)?.as_bytes()],
+        bump
+    )]
+    pub claim: Account<'info, UsernameClaim>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RenewUsername<'info> {
+    #[account(seeds = [b"registry"], bump = registry.bump)]
+    pub registry: Account<'info, Registry>,
+    #[account(
+        mut,
+        seeds = [b"username", claim.username.as_bytes()],
+        bump = claim.bump,
+        has_one = owner @ ProfileError::Unauthorized
+    )]
+    pub claim: Account<'info, UsernameClaim>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseExpiredUsername<'info> {
+    #[account(
+        mut,
+        seeds = [b"username", claim.username.as_bytes()],
+        bump = claim.bump,
+        has_one = owner,
+        close = owner
+    )]
+    pub claim: Account<'info, UsernameClaim>,
+    /// Receives the claim's rent.
+    #[account(mut)]
+    pub owner: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenDispute<'info> {
+    #[account(
+        seeds = [b"username", claim.username.as_bytes()],
+        bump = claim.bump,
+        constraint = claim.owner != challenger.key() @ ProfileError::InvalidDispute
+    )]
+    pub claim: Account<'info, UsernameClaim>,
+    #[account(
+        seeds = [b"profile_secure", challenger.key().as_ref()],
+        bump = challenger_profile.bump
+    )]
+    pub challenger_profile: Account<'info, Profile>,
+    #[account(
+        init,
+        payer = challenger,
+        space = 8 + UsernameDispute::INIT_SPACE,
+        seeds = [b"dispute", claim.key().as_ref()],
+        bump
+    )]
+    pub dispute: Account<'info, UsernameDispute>,
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump,
+        has_one = authority @ ProfileError::Unauthorized
+    )]
+    pub registry: Account<'info, Registry>,
+    #[account(
+        mut,
+        seeds = [b"username", claim.username.as_bytes()],
+        bump = claim.bump
+    )]
+    pub claim: Account<'info, UsernameClaim>,
+    #[account(
+        mut,
+        seeds = [b"dispute", claim.key().as_ref()],
+        bump = dispute.bump,
+        has_one = claim,
+        has_one = challenger,
+        close = challenger
+    )]
+    pub dispute: Account<'info, UsernameDispute>,
+    /// Receives the dispute's rent, whatever the outcome.
+    #[account(mut)]
+    pub challenger: SystemAccount<'info>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableReclaimProfile<'info> {
+    // BAD: No signer, no flag, no waiting period.
+    #[account(mut, seeds = [b"profile", profile.username.as_bytes()], bump = profile.bump)]
+    pub profile: Account<'info, Profile>,
+}
+
+#[derive(Accounts)]
+pub struct FlagSquatter<'info> {
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump,
+        has_one = authority @ ProfileError::Unauthorized
+    )]
+    pub registry: Account<'info, Registry>,
+    #[account(seeds = [b"profile", profile.username.as_bytes()], bump = profile.bump)]
+    pub profile: Account<'info, Profile>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + SquatFlag::INIT_SPACE,
+        seeds = [b"squat_flag", profile.key().as_ref()],
+        bump
+    )]
+    pub flag: Account<'info, SquatFlag>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSquatFlag<'info> {
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump,
+        has_one = authority @ ProfileError::Unauthorized
+    )]
+    pub registry: Account<'info, Registry>,
+    #[account(
+        mut,
+        seeds = [b"squat_flag", flag.profile.as_ref()],
+        bump = flag.bump,
+        close = authority
+    )]
+    pub flag: Account<'info, SquatFlag>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimProfile<'info> {
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump,
+        has_one = authority @ ProfileError::Unauthorized
+    )]
+    pub registry: Account<'info, Registry>,
+    #[account(mut, seeds = [b"profile", profile.username.as_bytes()], bump = profile.bump)]
+    pub profile: Account<'info, Profile>,
+    #[account(
+        mut,
+        seeds = [b"squat_flag", profile.key().as_ref()],
+        bump = flag.bump,
+        // The profile must still be held by the key that was flagged.
+        constraint = flag.squatter == profile.authority @ ProfileError::FlagOutdated,
+        close = authority
+    )]
+    pub flag: Account<'info, SquatFlag>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableClaimBadge<'info> {
+    #[account(seeds = [b"profile_secure", owner.key().as_ref()], bump = profile.bump)]
+    pub profile: Account<'info, Profile>,
+    /// CHECK: VULNERABLE - decoded as a Vault without checking its owner or address.
+    pub vault: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Badge::INIT_SPACE,
+        seeds = [b"badge", profile.key().as_ref()],
+        bump
+    )]
+    pub badge: Account<'info, Badge>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SecureClaimBadge<'info> {
+    #[account(seeds = [b"profile_secure", owner.key().as_ref()], bump = profile.bump)]
+    pub profile: Account<'info, Profile>,
+    #[account(
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        seeds::program = mock_vault::ID
+    )]
+    pub vault: Account<'info, Vault>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Badge::INIT_SPACE,
+        seeds = [b"badge", profile.key().as_ref()],
+        bump
+    )]
+    pub badge: Account<'info, Badge>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(org: String, name: String)]
+pub struct VulnerableCreateTeam<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Team::INIT_SPACE,
+        // BAD SEEDS: "ali" + "cedevs" == "alice" + "devs"
+        seeds = [b"team", org.as_bytes(), name.as_bytes()],
+        bump
+    )]
+    pub team: Account<'info, Team>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(org: String, name: String)]
+pub struct SecureCreateTeam<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Team::INIT_SPACE,
+        // GOOD SEEDS: sha256(len || org || len || name)
+        seeds = [b"team_secure".as_ref(), &derive_seed::hashed(&[&org, &name])],
+        bump
+    )]
+    pub team: Account<'info, Team>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64, recipient: Pubkey)]
+pub struct CreateEscrow<'info> {
+    #[account(
+        init,
+        payer = maker,
+        space = 8 + Escrow::INIT_SPACE,
+        seeds = [b"escrow", maker.key().as_ref(), recipient.as_ref(), id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+    // The escrow PDA is the vault's token authority
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"escrow_vault", escrow.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut, token::mint = mint, token::authority = maker)]
+    pub maker_token: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableRelease<'info> {
+    /// CHECK: VULNERABLE - whatever PDA the instruction data's seeds derive.
+    pub escrow: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+    pub caller: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseToRecipient<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow", escrow.maker.as_ref(), escrow.recipient.as_ref(), escrow.id.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+        has_one = maker @ ProfileError::Unauthorized,
+        close = maker
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut, seeds = [b"escrow_vault", escrow.key().as_ref()], bump = escrow.vault_bump)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = escrow.mint, token::authority = escrow.recipient)]
+    pub recipient_token: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RefundAfterTimeout<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow", escrow.maker.as_ref(), escrow.recipient.as_ref(), escrow.id.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+        has_one = maker,
+        close = maker
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut, seeds = [b"escrow_vault", escrow.key().as_ref()], bump = escrow.vault_bump)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = escrow.mint, token::authority = maker)]
+    pub maker_token: Account<'info, TokenAccount>,
+    /// Receives the refund's rent.
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Registry {
+    /// Resolves disputes and reclaims squatted profiles.
+    pub authority: Pubkey,
+    pub claim_duration_secs: i64,
+    pub recovery_delay_secs: i64,
+    pub bump: u8,
+}
+
+/// Reserves a normalized username for the profile it points at.
+#[account]
+#[derive(InitSpace)]
+pub struct UsernameClaim {
+    pub owner: Pubkey,
+    pub profile: Pubkey,
+    #[max_len(MAX_USERNAME_LEN)]
+    pub username: String,
+    pub claimed_at: i64,
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct UsernameDispute {
+    pub claim: Pubkey,
+    pub challenger: Pubkey,
+    pub challenger_profile: Pubkey,
+    pub opened_at: i64,
+    pub bump: u8,
+}
+
+/// A pending recovery of a squatted `[b"profile", username]` profile.
+#[account]
+#[derive(InitSpace)]
+pub struct SquatFlag {
+    pub profile: Pubkey,
+    /// The profile's authority when it was flagged.
+    pub squatter: Pubkey,
+    /// Who gets the profile, or `None` to tombstone it.
+    pub rightful_owner: Option<Pubkey>,
+    pub flagged_at: i64,
+    pub reclaimable_at: i64,
+    pub bump: u8,
+}
+
+/// Two-party escrow at `[b"escrow", maker, recipient, id]`, holding its
+/// tokens in the vault at `[b"escrow_vault", escrow]`.
+#[account]
+#[derive(InitSpace)]
+pub struct Escrow {
+    pub maker: Pubkey,
+    pub recipient: Pubkey,
+    pub mint: Pubkey,
+    pub id: u64,
+    pub amount: u64,
+    /// From this time the maker can be refunded.
+    pub expires_at: i64,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Team {
+    pub owner: Pubkey,
+    #[max_len(MAX_TEAM_NAME_LEN)]
+    pub org: String,
+    #[max_len(MAX_TEAM_NAME_LEN)]
+    pub name: String,
+    pub bump: u8,
+}
+
+/// Marks a profile whose owner held `MIN_BADGE_BALANCE` in the vault program.
+#[account]
+#[derive(InitSpace)]
+pub struct Badge {
+    pub profile: Pubkey,
+    /// The vault the balance was read from.
+    pub vault: Pubkey,
+    pub balance_at_issue: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum ProfileError {
+    #[msg("Username must be 3-32 characters of a-z, 0-9 or _ after normalization")]
+    InvalidUsername,
+    #[msg("Durations must be positive")]
+    InvalidDuration,
+    #[msg("Claim has expired")]
+    ClaimExpired,
+    #[msg("Claim has not expired yet")]
+    ClaimNotExpired,
+    #[msg("Cannot dispute your own claim")]
+    InvalidDispute,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Recovery waiting period has not passed")]
+    RecoveryNotReady,
+    #[msg("Profile has changed hands since it was flagged")]
+    FlagOutdated,
+    #[msg("Org or team name is too long")]
+    NameTooLong,
+    #[msg("Vault balance is below the badge minimum")]
+    InsufficientBalance,
+    #[msg("Amount must be positive")]
+    InvalidAmount,
+    #[msg("Escrow has not expired yet")]
+    EscrowNotExpired,
+    #[msg("Arithmetic error")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn case_and_width_fold_to_one_name() {
+        for spelling in ["alice", "Alice", "ALICE", "\u{ff21}\u{ff4c}\u{ff49}\u{ff43}\u{ff45}"] {
+            assert_eq!(normalize_username(spelling).unwrap(), "alice");
+        }
+    }
+
+    #[test]
+    fn rejects_lookalikes_and_bad_lengths() {
+        // Cyrillic "а" in place of the Latin "a".
+        assert!(normalize_username("\u{0430}lice").is_err());
+        assert!(normalize_username("ali ce").is_err());
+        assert!(normalize_username("al").is_err());
+        assert!(normalize_username(&"a".repeat(MAX_USERNAME_LEN)).is_ok());
+        assert!(normalize_username(&"a".repeat(MAX_USERNAME_LEN + 1)).is_err());
+    }
+}
@@ -0,0 +1,269 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use security_common::time::{ClockExt, Slot};
+
+declare_id!("Gwjr9Gf8dx5HyAKiERxJojjFccpdxW5p3baSGF2H89xb");
+
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+#[program]
+pub mod missing_slippage_protection {
+    use super::*;
+
+    pub fn initialize_pool(ctx: Context<InitializePool>, fee_bps: u16) -> Result<()> {
+        require!(fee_bps <= MAX_FEE_BPS, SwapError::InvalidFee);
+
+        let pool = &mut ctx.accounts.pool;
+        pool.authority = ctx.accounts.authority.key();
+        pool.mint_a = ctx.accounts.mint_a.key();
+        pool.mint_b = ctx.accounts.mint_b.key();
+        pool.vault_a = ctx.accounts.vault_a.key();
+        pool.vault_b = ctx.accounts.vault_b.key();
+        pool.fee_bps = fee_bps;
+        pool.bump = ctx.bumps.pool;
+        Ok(())
+    }
+
+    pub fn add_liquidity(ctx: Context<AddLiquidity>, amount_a: u64, amount_b: u64) -> Result<()> {
+        let token_program = ctx.accounts.token_program.to_account_info();
+
+        token::transfer(
+            CpiContext::new(
+                token_program.clone(),
+                Transfer {
+                    from: ctx.accounts.provider_a.to_account_info(),
+                    to: ctx.accounts.vault_a.to_account_info(),
+                    authority: ctx.accounts.provider.to_account_info(),
+                },
+            ),
+            amount_a,
+        )?;
+        token::transfer(
+            CpiContext::new(
+                token_program,
+                Transfer {
+                    from: ctx.accounts.provider_b.to_account_info(),
+                    to: ctx.accounts.vault_b.to_account_info(),
+                    authority: ctx.accounts.provider.to_account_info(),
+                },
+            ),
+            amount_b,
+        )?;
+        Ok(())
+    }
+
+    // VULNERABLE: No Slippage Protection
+    // 1. The user only states how much they put in, never how much they expect out.
+    // 2. The output is priced against whatever reserves exist when the tx lands.
+    // 3. No deadline, so a delayed transaction executes at any future price.
+    pub fn vulnerable_swap(ctx: Context<Swap>, amount_in: u64, a_to_b: bool) -> Result<()> {
+        let amount_out = execute_swap(ctx.accounts, amount_in, a_to_b)?;
+
+        // The user accepts ANY amount_out, even 1 token, because nothing is compared.
+        msg!("Vulnerable swap: {} in -> {} out", amount_in, amount_out);
+        Ok(())
+    }
+
+    // SECURE: Slippage Limit + Deadline
+    // 1. `min_amount_out` bounds the worst price the user will accept.
+    // 2. `deadline_slot` stops a held-back transaction from landing later.
+    // 3. The check happens BEFORE any tokens move.
+    pub fn secure_swap(
+        ctx: Context<Swap>,
+        amount_in: u64,
+        min_amount_out: u64,
+        deadline_slot: u64,
+        a_to_b: bool,
+    ) -> Result<()> {
+        let current_slot = Clock::get()?.current_slot();
+        require!(current_slot <= Slot(deadline_slot), SwapError::DeadlineExceeded);
+
+        let (reserve_in, reserve_out) = reserves(&ctx.accounts.vault_a, &ctx.accounts.vault_b, a_to_b);
+        let quoted = get_amount_out(amount_in, reserve_in, reserve_out, ctx.accounts.pool.fee_bps)?;
+        require!(quoted >= min_amount_out, SwapError::SlippageExceeded);
+
+        let amount_out = execute_swap(ctx.accounts, amount_in, a_to_b)?;
+
+        msg!("Secure swap: {} in -> {} out (min {})", amount_in, amount_out, min_amount_out);
+        Ok(())
+    }
+}
+
+/// Constant-product output for `amount_in`, after the pool fee.
+///
+/// out = (in * (10_000 - fee) * reserve_out) / (reserve_in * 10_000 + in * (10_000 - fee))
+pub fn get_amount_out(amount_in: u64, reserve_in: u64, reserve_out: u64, fee_bps: u16) -> Result<u64> {
+    require!(amount_in > 0, SwapError::ZeroAmount);
+    require!(reserve_in > 0 && reserve_out > 0, SwapError::EmptyPool);
+
+    let in_after_fee = (amount_in as u128)
+        .checked_mul(10_000u128 - fee_bps as u128)
+        .ok_or(SwapError::MathOverflow)?;
+    let numerator = in_after_fee
+        .checked_mul(reserve_out as u128)
+        .ok_or(SwapError::MathOverflow)?;
+    let denominator = (reserve_in as u128)
+        .checked_mul(10_000)
+        .and_then(|v| v.checked_add(in_after_fee))
+        .ok_or(SwapError::MathOverflow)?;
+
+    u64::try_from(numerator / denominator).map_err(|_| SwapError::MathOverflow.into())
+}
+
+fn reserves(vault_a: &TokenAccount, vault_b: &TokenAccount, a_to_b: bool) -> (u64, u64) {
+    if a_to_b {
+        (vault_a.amount, vault_b.amount)
+    } else {
+        (vault_b.amount, vault_a.amount)
+    }
+}
+
+fn execute_swap(accounts: &Swap, amount_in: u64, a_to_b: bool) -> Result<u64> {
+    let (reserve_in, reserve_out) = reserves(&accounts.vault_a, &accounts.vault_b, a_to_b);
+    let amount_out = get_amount_out(amount_in, reserve_in, reserve_out, accounts.pool.fee_bps)?;
+
+    let (user_in, vault_in, vault_out, user_out) = if a_to_b {
+        (&accounts.user_a, &accounts.vault_a, &accounts.vault_b, &accounts.user_b)
+    } else {
+        (&accounts.user_b, &accounts.vault_b, &accounts.vault_a, &accounts.user_a)
+    };
+
+    token::transfer(
+        CpiContext::new(
+            accounts.token_program.to_account_info(),
+            Transfer {
+                from: user_in.to_account_info(),
+                to: vault_in.to_account_info(),
+                authority: accounts.user.to_account_info(),
+            },
+        ),
+        amount_in,
+    )?;
+
+    let mint_a = accounts.pool.mint_a;
+    let mint_b = accounts.pool.mint_b;
+    let seeds = &[
+        b"pool",
+        mint_a.as_ref(),
+        mint_b.as_ref(),
+        &[accounts.pool.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            Transfer {
+                from: vault_out.to_account_info(),
+                to: user_out.to_account_info(),
+                authority: accounts.pool.to_account_info(),
+            },
+            signer,
+        ),
+        amount_out,
+    )?;
+
+    Ok(amount_out)
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Pool::INIT_SPACE,
+        seeds = [b"pool", mint_a.key().as_ref(), mint_b.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    pub mint_a: Account<'info, Mint>,
+    pub mint_b: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = authority,
+        token::mint = mint_a,
+        token::authority = pool,
+        seeds = [b"vault_a", pool.key().as_ref()],
+        bump
+    )]
+    pub vault_a: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = authority,
+        token::mint = mint_b,
+        token::authority = pool,
+        seeds = [b"vault_b", pool.key().as_ref()],
+        bump
+    )]
+    pub vault_b: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct AddLiquidity<'info> {
+    #[account(has_one = vault_a, has_one = vault_b)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub vault_a: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub vault_b: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = pool.mint_a)]
+    pub provider_a: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = pool.mint_b)]
+    pub provider_b: Account<'info, TokenAccount>,
+    pub provider: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    #[account(
+        seeds = [b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        bump = pool.bump,
+        has_one = vault_a,
+        has_one = vault_b
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub vault_a: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub vault_b: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = pool.mint_a, token::authority = user)]
+    pub user_a: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = pool.mint_b, token::authority = user)]
+    pub user_b: Account<'info, TokenAccount>,
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub authority: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    pub fee_bps: u16,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum SwapError {
+    #[msg("Fee exceeds maximum")]
+    InvalidFee,
+    #[msg("Swap amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("Pool has no liquidity")]
+    EmptyPool,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Output below minimum amount (slippage exceeded)")]
+    SlippageExceeded,
+    #[msg("Transaction landed after its deadline slot")]
+    DeadlineExceeded,
+}
@@ -0,0 +1,277 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+    Keypair,
+    LAMPORTS_PER_SOL,
+    PublicKey,
+    SystemProgram,
+    SYSVAR_RENT_PUBKEY,
+} from "@solana/web3.js";
+import {
+    TOKEN_PROGRAM_ID,
+    createMint,
+    createAccount,
+    mintTo,
+    getAccount,
+} from "@solana/spl-token";
+import { expect } from "chai";
+
+describe("06: Missing Slippage Protection", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.MissingSlippageProtection as Program<any>;
+
+    let lp: Keypair;
+    let victim: Keypair;
+    let attacker: Keypair;
+
+    let mintA: PublicKey;
+    let mintB: PublicKey;
+    let pool: PublicKey;
+    let vaultA: PublicKey;
+    let vaultB: PublicKey;
+
+    const accountsFor: Record<string, { a: PublicKey; b: PublicKey }> = {};
+
+    const FEE_BPS = 30;
+    const POOL_LIQUIDITY = 1_000_000;
+    const VICTIM_SWAP = 10_000;
+    const ATTACKER_SWAP = 200_000;
+
+    // Mirrors the on-chain get_amount_out
+    function quote(amountIn: number, reserveIn: number, reserveOut: number): number {
+        const inAfterFee = BigInt(amountIn) * BigInt(10_000 - FEE_BPS);
+        const numerator = inAfterFee * BigInt(reserveOut);
+        const denominator = BigInt(reserveIn) * 10_000n + inAfterFee;
+        return Number(numerator / denominator);
+    }
+
+    async function reserves(): Promise<{ a: number; b: number }> {
+        const a = await getAccount(provider.connection, vaultA);
+        const b = await getAccount(provider.connection, vaultB);
+        return { a: Number(a.amount), b: Number(b.amount) };
+    }
+
+    async function balanceOf(account: PublicKey): Promise<number> {
+        return Number((await getAccount(provider.connection, account)).amount);
+    }
+
+    function swapAccounts(user: Keypair) {
+        return {
+            pool,
+            vaultA,
+            vaultB,
+            userA: accountsFor[user.publicKey.toBase58()].a,
+            userB: accountsFor[user.publicKey.toBase58()].b,
+            user: user.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+        };
+    }
+
+    before(async () => {
+        lp = Keypair.generate();
+        victim = Keypair.generate();
+        attacker = Keypair.generate();
+
+        for (const kp of [lp, victim, attacker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+
+        mintA = await createMint(provider.connection, lp, lp.publicKey, null, 0);
+        mintB = await createMint(provider.connection, lp, lp.publicKey, null, 0);
+
+        for (const kp of [lp, victim, attacker]) {
+            const a = await createAccount(provider.connection, kp, mintA, kp.publicKey);
+            const b = await createAccount(provider.connection, kp, mintB, kp.publicKey);
+            await mintTo(provider.connection, lp, mintA, a, lp, 10_000_000);
+            await mintTo(provider.connection, lp, mintB, b, lp, 10_000_000);
+            accountsFor[kp.publicKey.toBase58()] = { a, b };
+        }
+
+        [pool] = PublicKey.findProgramAddressSync(
+            [Buffer.from("pool"), mintA.toBuffer(), mintB.toBuffer()],
+            program.programId
+        );
+        [vaultA] = PublicKey.findProgramAddressSync(
+            [Buffer.from("vault_a"), pool.toBuffer()],
+            program.programId
+        );
+        [vaultB] = PublicKey.findProgramAddressSync(
+            [Buffer.from("vault_b"), pool.toBuffer()],
+            program.programId
+        );
+    });
+
+    describe("Setup: LP creates and funds pool", () => {
+        it("initializes pool", async () => {
+            await program.methods
+                .initializePool(FEE_BPS)
+                .accounts({
+                    pool,
+                    mintA,
+                    mintB,
+                    vaultA,
+                    vaultB,
+                    authority: lp.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                    rent: SYSVAR_RENT_PUBKEY,
+                })
+                .signers([lp])
+                .rpc();
+        });
+
+        it("adds liquidity at a 1:1 price", async () => {
+            await program.methods
+                .addLiquidity(new anchor.BN(POOL_LIQUIDITY), new anchor.BN(POOL_LIQUIDITY))
+                .accounts({
+                    pool,
+                    vaultA,
+                    vaultB,
+                    providerA: accountsFor[lp.publicKey.toBase58()].a,
+                    providerB: accountsFor[lp.publicKey.toBase58()].b,
+                    provider: lp.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([lp])
+                .rpc();
+
+            const r = await reserves();
+            expect(r.a).to.equal(POOL_LIQUIDITY);
+            expect(r.b).to.equal(POOL_LIQUIDITY);
+        });
+    });
+
+    describe("Exploit: sandwich against vulnerable_swap", () => {
+        it("attacker front-runs, victim swaps at a worse price, attacker back-runs", async () => {
+            const start = await reserves();
+            const fairQuote = quote(VICTIM_SWAP, start.a, start.b);
+            const attackerA = accountsFor[attacker.publicKey.toBase58()].a;
+            const attackerB = accountsFor[attacker.publicKey.toBase58()].b;
+            const attackerABefore = await balanceOf(attackerA);
+            const victimBBefore = await balanceOf(accountsFor[victim.publicKey.toBase58()].b);
+
+            // 1. Front-run: attacker buys B with A, pushing the A->B price up
+            await program.methods
+                .vulnerableSwap(new anchor.BN(ATTACKER_SWAP), true)
+                .accounts(swapAccounts(attacker))
+                .signers([attacker])
+                .rpc();
+            const attackerBReceived = (await balanceOf(attackerB)) - 10_000_000;
+
+            // 2. Victim's pending swap lands with no minimum output
+            await program.methods
+                .vulnerableSwap(new anchor.BN(VICTIM_SWAP), true)
+                .accounts(swapAccounts(victim))
+                .signers([victim])
+                .rpc();
+            const victimReceived =
+                (await balanceOf(accountsFor[victim.publicKey.toBase58()].b)) - victimBBefore;
+
+            // 3. Back-run: attacker sells the B they bought
+            await program.methods
+                .vulnerableSwap(new anchor.BN(attackerBReceived), false)
+                .accounts(swapAccounts(attacker))
+                .signers([attacker])
+                .rpc();
+            const attackerProfit = (await balanceOf(attackerA)) - attackerABefore;
+
+            console.log(`  Fair quote for victim:  ${fairQuote} B`);
+            console.log(`  Victim actually got:    ${victimReceived} B`);
+            console.log(`  Attacker profit:        ${attackerProfit} A`);
+
+            expect(victimReceived).to.be.lessThan(fairQuote);
+            expect(attackerProfit).to.be.greaterThan(0);
+        });
+    });
+
+    describe("Secure: secure_swap enforces min_amount_out and deadline", () => {
+        it("rejects the sandwiched swap when output drops below the minimum", async () => {
+            const start = await reserves();
+            // Victim tolerates 1% slippage from the quote they saw
+            const minOut = Math.floor(quote(VICTIM_SWAP, start.a, start.b) * 0.99);
+            const slot = await provider.connection.getSlot();
+
+            // Attacker front-runs again
+            await program.methods
+                .vulnerableSwap(new anchor.BN(ATTACKER_SWAP), true)
+                .accounts(swapAccounts(attacker))
+                .signers([attacker])
+                .rpc();
+
+            try {
+                await program.methods
+                    .secureSwap(
+                        new anchor.BN(VICTIM_SWAP),
+                        new anchor.BN(minOut),
+                        new anchor.BN(slot + 100),
+                        true
+                    )
+                    .accounts(swapAccounts(victim))
+                    .signers([victim])
+                    .rpc();
+
+                expect.fail("Should have thrown - slippage exceeded");
+            } catch (error: any) {
+                expect(error.message).to.include("SlippageExceeded");
+            }
+        });
+
+        it("rejects a swap that lands after its deadline slot", async () => {
+            const slot = await provider.connection.getSlot();
+
+            try {
+                await program.methods
+                    .secureSwap(new anchor.BN(VICTIM_SWAP), new anchor.BN(0), new anchor.BN(slot - 1), true)
+                    .accounts(swapAccounts(victim))
+                    .signers([victim])
+                    .rpc();
+
+                expect.fail("Should have thrown - deadline exceeded");
+            } catch (error: any) {
+                expect(error.message).to.include("DeadlineExceeded");
+            }
+        });
+
+        it("allows a swap within slippage tolerance", async () => {
+            const start = await reserves();
+            const expected = quote(VICTIM_SWAP, start.a, start.b);
+            const slot = await provider.connection.getSlot();
+            const victimB = accountsFor[victim.publicKey.toBase58()].b;
+            const before = await balanceOf(victimB);
+
+            await program.methods
+                .secureSwap(
+                    new anchor.BN(VICTIM_SWAP),
+                    new anchor.BN(Math.floor(expected * 0.99)),
+                    new anchor.BN(slot + 100),
+                    true
+                )
+                .accounts(swapAccounts(victim))
+                .signers([victim])
+                .rpc();
+
+            expect((await balanceOf(victimB)) - before).to.equal(expected);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Slippage Protection Security ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  vulnerable_swap(amount_in, a_to_b)");
+            console.log("  Risk: output is whatever the reserves give at execution time");
+            console.log("  Attack: front-run to move price, back-run to pocket the difference\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  secure_swap(amount_in, min_amount_out, deadline_slot, a_to_b)");
+            console.log("  Protection: tx reverts if price moved beyond tolerance or landed late\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  The user must state the WORST price they accept, not just the input.");
+        });
+    });
+});
@@ -0,0 +1,212 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use security_common::time::{ClockExt, Epoch, UnixTs};
+
+declare_id!("FAxar6FCTCqLVF46eyYUSxYcxwN4g7nTnMrzunJ7G2jv");
+
+#[program]
+pub mod clock_unit_confusion {
+    use super::*;
+
+    pub fn create_lockup(
+        ctx: Context<CreateLockup>,
+        lockup_id: u64,
+        lamports: u64,
+        lock_epochs: u64,
+    ) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.owner.to_account_info(),
+            to: ctx.accounts.lockup.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+        transfer(cpi_ctx, lamports)?;
+
+        let clock = Clock::get()?;
+        let lockup = &mut ctx.accounts.lockup;
+        lockup.owner = ctx.accounts.owner.key();
+        lockup.lockup_id = lockup_id;
+        lockup.amount = lamports;
+        // Denominated in EPOCHS
+        lockup.unlock_epoch = clock
+            .current_epoch()
+            .checked_add_epochs(lock_epochs)
+            .ok_or(ClockError::ArithmeticError)?
+            .0;
+        lockup.bump = ctx.bumps.lockup;
+        Ok(())
+    }
+
+    // VULNERABLE: Epoch Compared Against Unix Timestamp
+    // `unlock_epoch` is a few hundred; `unix_timestamp` is ~1.7 billion.
+    // The lock is satisfied the instant it is created.
+    pub fn vulnerable_withdraw(ctx: Context<Withdraw>) -> Result<()> {
+        let clock = Clock::get()?;
+        let lockup = &ctx.accounts.lockup;
+
+        // Both sides are bare integers, so this compiles without complaint.
+        require!(
+            clock.unix_timestamp as u64 >= lockup.unlock_epoch,
+            ClockError::StillLocked
+        );
+
+        msg!("Vulnerable withdraw: {} lamports released", lockup.amount);
+        Ok(())
+    }
+
+    // SECURE: Typed Epoch Comparison
+    // `current_epoch()` returns `Epoch`; comparing it to a `UnixTs` would not compile.
+    pub fn secure_withdraw(ctx: Context<Withdraw>) -> Result<()> {
+        let clock = Clock::get()?;
+        let lockup = &ctx.accounts.lockup;
+
+        require!(
+            clock.current_epoch() >= Epoch(lockup.unlock_epoch),
+            ClockError::StillLocked
+        );
+
+        msg!("Secure withdraw: {} lamports released", lockup.amount);
+        Ok(())
+    }
+
+    pub fn create_offer(ctx: Context<CreateOffer>, offer_id: u64, ttl_seconds: i64) -> Result<()> {
+        require!(ttl_seconds > 0, ClockError::InvalidDuration);
+
+        let clock = Clock::get()?;
+        let offer = &mut ctx.accounts.offer;
+        offer.maker = ctx.accounts.maker.key();
+        offer.offer_id = offer_id;
+        offer.taker = None;
+        // Denominated in SECONDS
+        offer.expires_at = clock
+            .current_unix_ts()
+            .checked_add_secs(ttl_seconds)
+            .ok_or(ClockError::ArithmeticError)?
+            .0;
+        offer.bump = ctx.bumps.offer;
+        Ok(())
+    }
+
+    // VULNERABLE: Slot Compared Against Seconds
+    // Slots count up far slower than a Unix timestamp, so `slot <= expires_at`
+    // holds for decades. The offer never expires.
+    pub fn vulnerable_accept_offer(ctx: Context<AcceptOffer>) -> Result<()> {
+        let clock = Clock::get()?;
+        let offer = &mut ctx.accounts.offer;
+
+        require!(offer.taker.is_none(), ClockError::AlreadyAccepted);
+        require!(
+            clock.slot as i64 <= offer.expires_at,
+            ClockError::OfferExpired
+        );
+
+        offer.taker = Some(ctx.accounts.taker.key());
+        Ok(())
+    }
+
+    // SECURE: Typed Timestamp Comparison
+    pub fn secure_accept_offer(ctx: Context<AcceptOffer>) -> Result<()> {
+        let clock = Clock::get()?;
+        let offer = &mut ctx.accounts.offer;
+
+        require!(offer.taker.is_none(), ClockError::AlreadyAccepted);
+        require!(
+            clock.current_unix_ts() <= UnixTs(offer.expires_at),
+            ClockError::OfferExpired
+        );
+
+        offer.taker = Some(ctx.accounts.taker.key());
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(lockup_id: u64)]
+pub struct CreateLockup<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Lockup::INIT_SPACE,
+        seeds = [b"lockup", owner.key().as_ref(), &lockup_id.to_le_bytes()],
+        bump
+    )]
+    pub lockup: Account<'info, Lockup>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    // Both paths close the lockup to the owner; only the time check differs.
+    #[account(
+        mut,
+        seeds = [b"lockup", owner.key().as_ref(), &lockup.lockup_id.to_le_bytes()],
+        bump = lockup.bump,
+        has_one = owner,
+        close = owner
+    )]
+    pub lockup: Account<'info, Lockup>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(offer_id: u64)]
+pub struct CreateOffer<'info> {
+    #[account(
+        init,
+        payer = maker,
+        space = 8 + Offer::INIT_SPACE,
+        seeds = [b"offer", maker.key().as_ref(), &offer_id.to_le_bytes()],
+        bump
+    )]
+    pub offer: Account<'info, Offer>,
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptOffer<'info> {
+    #[account(
+        mut,
+        seeds = [b"offer", offer.maker.as_ref(), &offer.offer_id.to_le_bytes()],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, Offer>,
+    pub taker: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Lockup {
+    pub owner: Pubkey,
+    pub lockup_id: u64,
+    pub amount: u64,
+    pub unlock_epoch: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Offer {
+    pub maker: Pubkey,
+    pub offer_id: u64,
+    pub taker: Option<Pubkey>,
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum ClockError {
+    #[msg("Lockup has not reached its unlock epoch")]
+    StillLocked,
+    #[msg("Offer has expired")]
+    OfferExpired,
+    #[msg("Offer was already accepted")]
+    AlreadyAccepted,
+    #[msg("Duration must be positive")]
+    InvalidDuration,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
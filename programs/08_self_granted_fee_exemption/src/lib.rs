@@ -0,0 +1,193 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("Gs66cu9W2tihGZmAHXYZ74dp18cyD4JWQcsX7TpziNYA");
+
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+#[program]
+pub mod self_granted_fee_exemption {
+    use super::*;
+
+    pub fn initialize_config(ctx: Context<InitializeConfig>, fee_bps: u16) -> Result<()> {
+        require!(fee_bps <= MAX_FEE_BPS, FeeError::InvalidFee);
+
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.fee_bps = fee_bps;
+        config.bump = ctx.bumps.config;
+        Ok(())
+    }
+
+    pub fn create_profile(ctx: Context<CreateProfile>) -> Result<()> {
+        let profile = &mut ctx.accounts.profile;
+        profile.user = ctx.accounts.user.key();
+        profile.fee_exempt = false;
+        profile.bump = ctx.bumps.profile;
+        Ok(())
+    }
+
+    // VULNERABLE: Wrong Authority
+    // The accounts struct authorizes against `profile.user` - the very person
+    // who benefits from the exemption. Owning a profile is not the same as
+    // being allowed to decide its fee status.
+    pub fn vulnerable_set_fee_exempt(ctx: Context<VulnerableSetFeeExempt>, exempt: bool) -> Result<()> {
+        ctx.accounts.profile.fee_exempt = exempt;
+        Ok(())
+    }
+
+    // SECURE: Admin-Gated + Audited
+    // 1. `has_one = admin` on the config ties the signer to the protocol admin.
+    // 2. The profile is located by seeds, so any user's profile can be targeted
+    //    by the admin but no user can target their own.
+    // 3. Every change emits an event for off-chain audit.
+    pub fn secure_set_fee_exempt(ctx: Context<SecureSetFeeExempt>, exempt: bool) -> Result<()> {
+        let profile = &mut ctx.accounts.profile;
+        let previous = profile.fee_exempt;
+        profile.fee_exempt = exempt;
+
+        emit!(FeeExemptionChanged {
+            user: profile.user,
+            admin: ctx.accounts.admin.key(),
+            previous,
+            exempt,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+
+    pub fn pay_with_fee(ctx: Context<PayWithFee>, amount: u64) -> Result<()> {
+        let fee = if ctx.accounts.profile.fee_exempt {
+            0
+        } else {
+            amount
+                .checked_mul(ctx.accounts.config.fee_bps as u64)
+                .ok_or(FeeError::ArithmeticError)?
+                / 10_000
+        };
+
+        if fee > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.user.to_account_info(),
+                to: ctx.accounts.config.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+            transfer(cpi_ctx, fee)?;
+        }
+
+        msg!("Payment of {} charged fee {}", amount, fee);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProfile<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = 8 + Profile::INIT_SPACE,
+        seeds = [b"profile", user.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, Profile>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableSetFeeExempt<'info> {
+    // VULNERABLE: The constraint is correct for "edit my profile",
+    // but this instruction edits a PRIVILEGED field.
+    #[account(
+        mut,
+        seeds = [b"profile", user.key().as_ref()],
+        bump = profile.bump,
+        has_one = user
+    )]
+    pub profile: Account<'info, Profile>,
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SecureSetFeeExempt<'info> {
+    // SECURE: Authority is the protocol admin, not the profile owner
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ FeeError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"profile", profile.user.as_ref()],
+        bump = profile.bump
+    )]
+    pub profile: Account<'info, Profile>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PayWithFee<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        seeds = [b"profile", user.key().as_ref()],
+        bump = profile.bump,
+        has_one = user
+    )]
+    pub profile: Account<'info, Profile>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    pub fee_bps: u16,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Profile {
+    pub user: Pubkey,
+    pub fee_exempt: bool,
+    pub bump: u8,
+}
+
+#[event]
+pub struct FeeExemptionChanged {
+    pub user: Pubkey,
+    pub admin: Pubkey,
+    pub previous: bool,
+    pub exempt: bool,
+    pub slot: u64,
+}
+
+#[error_code]
+pub enum FeeError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Fee exceeds maximum")]
+    InvalidFee,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
@@ -0,0 +1,164 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { sleep } from "../../../tests/utils";
+
+describe("08: Self-Granted Fee Exemption", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.SelfGrantedFeeExemption as Program<any>;
+
+    let admin: Keypair;
+    let user: Keypair;
+    let config: PublicKey;
+    let profile: PublicKey;
+
+    const FEE_BPS = 100; // 1%
+    const PAYMENT = 1 * LAMPORTS_PER_SOL;
+
+    async function pay(): Promise<number> {
+        const before = await provider.connection.getBalance(config);
+        await program.methods
+            .payWithFee(new anchor.BN(PAYMENT))
+            .accounts({
+                config,
+                profile,
+                user: user.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([user])
+            .rpc();
+        return (await provider.connection.getBalance(config)) - before;
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        user = Keypair.generate();
+
+        for (const kp of [admin, user]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+
+        [config] = PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId);
+        [profile] = PublicKey.findProgramAddressSync(
+            [Buffer.from("profile"), user.publicKey.toBuffer()],
+            program.programId
+        );
+    });
+
+    describe("Setup: admin configures fees, user creates profile", () => {
+        it("initializes config and profile", async () => {
+            await program.methods
+                .initializeConfig(FEE_BPS)
+                .accounts({ config, admin: admin.publicKey, systemProgram: SystemProgram.programId })
+                .signers([admin])
+                .rpc();
+
+            await program.methods
+                .createProfile()
+                .accounts({ profile, user: user.publicKey, systemProgram: SystemProgram.programId })
+                .signers([user])
+                .rpc();
+
+            const state = await program.account.profile.fetch(profile);
+            expect(state.feeExempt).to.equal(false);
+        });
+
+        it("charges the fee to a non-exempt user", async () => {
+            const fee = await pay();
+            expect(fee).to.equal((PAYMENT * FEE_BPS) / 10_000);
+        });
+    });
+
+    describe("Exploit: user exempts themselves", () => {
+        it("vulnerable_set_fee_exempt accepts the profile owner as authority", async () => {
+            await program.methods
+                .vulnerableSetFeeExempt(true)
+                .accounts({ profile, user: user.publicKey })
+                .signers([user])
+                .rpc();
+
+            const state = await program.account.profile.fetch(profile);
+            expect(state.feeExempt).to.equal(true);
+        });
+
+        it("user now pays zero fees", async () => {
+            const fee = await pay();
+            console.log(`  Fee charged after self-exemption: ${fee} lamports`);
+            expect(fee).to.equal(0);
+        });
+    });
+
+    describe("Secure: only the admin can change exemptions", () => {
+        it("admin revokes the self-granted exemption and an audit event is emitted", async () => {
+            let captured: any = null;
+            const listener = program.addEventListener("FeeExemptionChanged", (event) => {
+                captured = event;
+            });
+
+            await program.methods
+                .secureSetFeeExempt(false)
+                .accounts({ config, profile, admin: admin.publicKey })
+                .signers([admin])
+                .rpc();
+
+            await sleep(1);
+            await program.removeEventListener(listener);
+
+            const state = await program.account.profile.fetch(profile);
+            expect(state.feeExempt).to.equal(false);
+            expect(captured).to.not.be.null;
+            expect(captured.user.toString()).to.equal(user.publicKey.toString());
+            expect(captured.admin.toString()).to.equal(admin.publicKey.toString());
+            expect(captured.previous).to.equal(true);
+            expect(captured.exempt).to.equal(false);
+        });
+
+        it("rejects the user signing as admin", async () => {
+            try {
+                await program.methods
+                    .secureSetFeeExempt(true)
+                    .accounts({ config, profile, admin: user.publicKey })
+                    .signers([user])
+                    .rpc();
+
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("Unauthorized");
+            }
+
+            const state = await program.account.profile.fetch(profile);
+            expect(state.feeExempt).to.equal(false);
+        });
+
+        it("admin can legitimately grant an exemption", async () => {
+            await program.methods
+                .secureSetFeeExempt(true)
+                .accounts({ config, profile, admin: admin.publicKey })
+                .signers([admin])
+                .rpc();
+
+            expect(await pay()).to.equal(0);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Fee Exemption Authority ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  #[account(mut, has_one = user)] profile  +  user: Signer");
+            console.log("  Risk: the beneficiary authorizes their own privilege\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  #[account(has_one = admin)] config  +  admin: Signer");
+            console.log("  Protection: privileged fields are written only by the admin, with audit events\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Who owns the account is not the same as who may change every field in it.");
+        });
+    });
+});
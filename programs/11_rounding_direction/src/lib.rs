@@ -0,0 +1,270 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use security_common::math::{mul_div_ceil, mul_div_floor};
+
+declare_id!("AqoKx21H3cK4AsCBaauqszQzNwagfy53T5Wmiob7fxWV");
+
+pub const MAX_FEE_BPS: u64 = 1_000;
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+#[program]
+pub mod rounding_direction {
+    use super::*;
+
+    pub fn initialize_pool(ctx: Context<InitializePool>, fee_bps: u64) -> Result<()> {
+        require!(fee_bps <= MAX_FEE_BPS, RoundingError::InvalidFee);
+
+        let pool = &mut ctx.accounts.pool;
+        pool.admin = ctx.accounts.admin.key();
+        pool.fee_bps = fee_bps;
+        pool.total_assets = 0;
+        pool.total_shares = 0;
+        pool.fees_collected = 0;
+        pool.bump = ctx.bumps.pool;
+        Ok(())
+    }
+
+    pub fn create_position(ctx: Context<CreatePosition>) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.owner = ctx.accounts.owner.key();
+        position.shares = 0;
+        position.bump = ctx.bumps.position;
+        Ok(())
+    }
+
+    /// Admin adds yield to the pool, raising the value of every share.
+    pub fn accrue_interest(ctx: Context<AccrueInterest>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.admin.to_account_info(),
+            to: ctx.accounts.pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+        transfer(cpi_ctx, amount)?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.total_assets = pool
+            .total_assets
+            .checked_add(amount)
+            .ok_or(RoundingError::ArithmeticError)?;
+        Ok(())
+    }
+
+    // VULNERABLE: Rounds Shares Minted UP
+    // The depositor receives a fraction of a share for free on every deposit.
+    pub fn vulnerable_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let pool = &ctx.accounts.pool;
+        let shares = if pool.total_shares == 0 {
+            amount
+        } else {
+            mul_div_ceil(amount, pool.total_shares, pool.total_assets).ok_or(RoundingError::ArithmeticError)?
+        };
+
+        apply_deposit(ctx, amount, shares)
+    }
+
+    // VULNERABLE: Rounds Payout UP and Fee DOWN
+    // 1. `gross` rounds up: the withdrawer receives a fraction of a lamport extra.
+    // 2. `fee` rounds down: any withdrawal under 10_000 / fee_bps lamports pays no fee.
+    // Each is at most 1 lamport - and both are repeatable without limit.
+    pub fn vulnerable_withdraw(ctx: Context<Withdraw>, shares: u64) -> Result<()> {
+        let pool = &ctx.accounts.pool;
+        let gross = mul_div_ceil(shares, pool.total_assets, pool.total_shares)
+            .ok_or(RoundingError::ArithmeticError)?;
+        let fee = mul_div_floor(gross, pool.fee_bps, BPS_DENOMINATOR).ok_or(RoundingError::ArithmeticError)?;
+
+        apply_withdraw(ctx, shares, gross, fee)
+    }
+
+    // SECURE: Shares Minted Round DOWN (protocol pays out shares)
+    pub fn secure_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let pool = &ctx.accounts.pool;
+        let shares = if pool.total_shares == 0 {
+            amount
+        } else {
+            mul_div_floor(amount, pool.total_shares, pool.total_assets).ok_or(RoundingError::ArithmeticError)?
+        };
+        require!(shares > 0, RoundingError::ZeroShares);
+
+        apply_deposit(ctx, amount, shares)
+    }
+
+    // SECURE: Payout Rounds DOWN, Fee Rounds UP
+    // 1. `gross` is assets the protocol pays out -> floor.
+    // 2. `fee` is owed to the protocol -> ceil, so no withdrawal is fee-free.
+    pub fn secure_withdraw(ctx: Context<Withdraw>, shares: u64) -> Result<()> {
+        let pool = &ctx.accounts.pool;
+        let gross = mul_div_floor(shares, pool.total_assets, pool.total_shares)
+            .ok_or(RoundingError::ArithmeticError)?;
+        let fee = mul_div_ceil(gross, pool.fee_bps, BPS_DENOMINATOR).ok_or(RoundingError::ArithmeticError)?;
+
+        apply_withdraw(ctx, shares, gross, fee)
+    }
+}
+
+fn apply_deposit(ctx: Context<Deposit>, amount: u64, shares: u64) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.owner.to_account_info(),
+        to: ctx.accounts.pool.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+    transfer(cpi_ctx, amount)?;
+
+    let pool = &mut ctx.accounts.pool;
+    pool.total_assets = pool
+        .total_assets
+        .checked_add(amount)
+        .ok_or(RoundingError::ArithmeticError)?;
+    pool.total_shares = pool
+        .total_shares
+        .checked_add(shares)
+        .ok_or(RoundingError::ArithmeticError)?;
+
+    let position = &mut ctx.accounts.position;
+    position.shares = position
+        .shares
+        .checked_add(shares)
+        .ok_or(RoundingError::ArithmeticError)?;
+
+    msg!("Deposit: {} lamports -> {} shares", amount, shares);
+    Ok(())
+}
+
+fn apply_withdraw(ctx: Context<Withdraw>, shares: u64, gross: u64, fee: u64) -> Result<()> {
+    let net = gross.checked_sub(fee).ok_or(RoundingError::ArithmeticError)?;
+
+    let position = &mut ctx.accounts.position;
+    position.shares = position
+        .shares
+        .checked_sub(shares)
+        .ok_or(RoundingError::InsufficientShares)?;
+
+    let pool = &mut ctx.accounts.pool;
+    pool.total_shares = pool
+        .total_shares
+        .checked_sub(shares)
+        .ok_or(RoundingError::ArithmeticError)?;
+    pool.total_assets = pool
+        .total_assets
+        .checked_sub(gross)
+        .ok_or(RoundingError::ArithmeticError)?;
+    pool.fees_collected = pool
+        .fees_collected
+        .checked_add(fee)
+        .ok_or(RoundingError::ArithmeticError)?;
+
+    let pool_info = pool.to_account_info();
+    let owner_info = ctx.accounts.owner.to_account_info();
+    **pool_info.try_borrow_mut_lamports()? = pool_info
+        .lamports()
+        .checked_sub(net)
+        .ok_or(RoundingError::ArithmeticError)?;
+    **owner_info.try_borrow_mut_lamports()? = owner_info
+        .lamports()
+        .checked_add(net)
+        .ok_or(RoundingError::ArithmeticError)?;
+
+    msg!("Withdraw: {} shares -> {} gross, {} fee, {} net", shares, gross, fee, net);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Pool::INIT_SPACE,
+        seeds = [b"pool"],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreatePosition<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [b"position", owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AccrueInterest<'info> {
+    #[account(mut, seeds = [b"pool"], bump = pool.bump, has_one = admin)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, seeds = [b"pool"], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mut,
+        seeds = [b"position", owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut, seeds = [b"pool"], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mut,
+        seeds = [b"position", owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub admin: Pubkey,
+    pub fee_bps: u64,
+    /// Lamports backing outstanding shares (excludes collected fees).
+    pub total_assets: u64,
+    pub total_shares: u64,
+    pub fees_collected: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub owner: Pubkey,
+    pub shares: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum RoundingError {
+    #[msg("Fee exceeds maximum")]
+    InvalidFee,
+    #[msg("Deposit would mint zero shares")]
+    ZeroShares,
+    #[msg("Position does not hold enough shares")]
+    InsufficientShares,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
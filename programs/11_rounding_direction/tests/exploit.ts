@@ -0,0 +1,197 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { expect } from "chai";
+
+describe("11: Rounding Direction", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.RoundingDirection as Program<any>;
+
+    let admin: Keypair;
+    let attacker: Keypair;
+    let pool: PublicKey;
+
+    const FEE_BPS = 100; // 1%
+    const INITIAL_DEPOSIT = 1_000;
+    const INTEREST = 500; // share price becomes 1.5 lamports
+    const ROUND_TRIPS = 10;
+
+    function positionPda(owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("position"), owner.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function depositAccounts(owner: Keypair) {
+        return {
+            pool,
+            position: positionPda(owner.publicKey),
+            owner: owner.publicKey,
+            systemProgram: SystemProgram.programId,
+        };
+    }
+
+    function withdrawAccounts(owner: Keypair) {
+        return { pool, position: positionPda(owner.publicKey), owner: owner.publicKey };
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        attacker = Keypair.generate();
+
+        for (const kp of [admin, attacker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+
+        [pool] = PublicKey.findProgramAddressSync([Buffer.from("pool")], program.programId);
+    });
+
+    describe("Setup: pool with a non-integer share price", () => {
+        it("admin seeds the pool and accrues interest", async () => {
+            await program.methods
+                .initializePool(new anchor.BN(FEE_BPS))
+                .accounts({ pool, admin: admin.publicKey, systemProgram: SystemProgram.programId })
+                .signers([admin])
+                .rpc();
+
+            for (const kp of [admin, attacker]) {
+                await program.methods
+                    .createPosition()
+                    .accounts({
+                        position: positionPda(kp.publicKey),
+                        owner: kp.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([kp])
+                    .rpc();
+            }
+
+            await program.methods
+                .secureDeposit(new anchor.BN(INITIAL_DEPOSIT))
+                .accounts(depositAccounts(admin))
+                .signers([admin])
+                .rpc();
+
+            await program.methods
+                .accrueInterest(new anchor.BN(INTEREST))
+                .accounts({ pool, admin: admin.publicKey, systemProgram: SystemProgram.programId })
+                .signers([admin])
+                .rpc();
+
+            const state = await program.account.pool.fetch(pool);
+            expect(state.totalAssets.toNumber()).to.equal(1_500);
+            expect(state.totalShares.toNumber()).to.equal(1_000);
+        });
+    });
+
+    describe("Exploit: dust extraction via user-favorable rounding", () => {
+        it("each 1-lamport deposit/withdraw round trip extracts 1 lamport", async () => {
+            const before = await program.account.pool.fetch(pool);
+            const poolLamportsBefore = await provider.connection.getBalance(pool);
+
+            const tx = new Transaction();
+            for (let i = 0; i < ROUND_TRIPS; i++) {
+                // ceil(1 * S / A) = 1 share for 1 lamport (worth 1.5)
+                tx.add(
+                    await program.methods
+                        .vulnerableDeposit(new anchor.BN(1))
+                        .accounts(depositAccounts(attacker))
+                        .instruction()
+                );
+                // ceil(1 * A / S) = 2 lamports gross, floor(2 * 1%) = 0 fee
+                tx.add(
+                    await program.methods
+                        .vulnerableWithdraw(new anchor.BN(1))
+                        .accounts(withdrawAccounts(attacker))
+                        .instruction()
+                );
+            }
+            await provider.sendAndConfirm(tx, [attacker]);
+
+            const after = await program.account.pool.fetch(pool);
+            const extracted = poolLamportsBefore - (await provider.connection.getBalance(pool));
+
+            console.log(`  Round trips: ${ROUND_TRIPS}`);
+            console.log(`  Lamports extracted from pool: ${extracted}`);
+            console.log(`  Fees collected: ${after.feesCollected.toNumber()}`);
+
+            expect(extracted).to.equal(ROUND_TRIPS);
+            expect(after.feesCollected.toNumber()).to.equal(before.feesCollected.toNumber());
+        });
+
+        it("honest LP's shares are now worth less", async () => {
+            const state = await program.account.pool.fetch(pool);
+            const adminShares = (await program.account.position.fetch(positionPda(admin.publicKey))).shares;
+            const adminValue = adminShares.mul(state.totalAssets).div(state.totalShares).toNumber();
+
+            console.log(`  Admin's ${adminShares} shares now worth ${adminValue} (was 1500)`);
+            expect(adminValue).to.equal(1_500 - ROUND_TRIPS);
+        });
+    });
+
+    describe("Secure: rounding always favors the pool", () => {
+        it("rejects a deposit too small to mint a whole share", async () => {
+            try {
+                await program.methods
+                    .secureDeposit(new anchor.BN(1))
+                    .accounts(depositAccounts(attacker))
+                    .signers([attacker])
+                    .rpc();
+
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("ZeroShares");
+            }
+        });
+
+        it("round trips lose value to the pool instead of extracting it", async () => {
+            const before = await program.account.pool.fetch(pool);
+            const poolLamportsBefore = await provider.connection.getBalance(pool);
+
+            const tx = new Transaction();
+            for (let i = 0; i < ROUND_TRIPS; i++) {
+                tx.add(
+                    await program.methods
+                        .secureDeposit(new anchor.BN(2))
+                        .accounts(depositAccounts(attacker))
+                        .instruction()
+                );
+                tx.add(
+                    await program.methods
+                        .secureWithdraw(new anchor.BN(1))
+                        .accounts(withdrawAccounts(attacker))
+                        .instruction()
+                );
+            }
+            await provider.sendAndConfirm(tx, [attacker]);
+
+            const after = await program.account.pool.fetch(pool);
+            const netToPool = (await provider.connection.getBalance(pool)) - poolLamportsBefore;
+
+            console.log(`  Net lamports gained by pool: ${netToPool}`);
+            expect(netToPool).to.be.greaterThan(0);
+            expect(after.feesCollected.toNumber()).to.be.greaterThan(before.feesCollected.toNumber());
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Rounding Direction ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  shares = ceil(...)  payout = ceil(...)  fee = floor(...)");
+            console.log("  Risk: every rounding step favors the user; repeatable dust extraction\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  shares = floor(...) payout = floor(...) fee = ceil(...)");
+            console.log("  Protection: protocol never pays out more than it holds per share\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Round against the caller: floor what you pay, ceil what you collect.");
+        });
+    });
+});
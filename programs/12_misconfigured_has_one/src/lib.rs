@@ -0,0 +1,174 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("DWiNbsjox3buZP9qkzFGYEvbyxSUH5r1ZKaNyKSDA9Hb");
+
+pub const FEE_BPS: u64 = 100;
+
+#[program]
+pub mod misconfigured_has_one {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>, fee_collector: Pubkey) -> Result<()> {
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.admin = ctx.accounts.admin.key();
+        // Since the fee refactor, `authority` means "authority over fees".
+        treasury.authority = fee_collector;
+        treasury.fees_accrued = 0;
+        treasury.bump = ctx.bumps.treasury;
+        Ok(())
+    }
+
+    pub fn fund_treasury(ctx: Context<FundTreasury>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.funder.to_account_info(),
+            to: ctx.accounts.treasury.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+        transfer(cpi_ctx, amount)?;
+
+        let fee = amount
+            .checked_mul(FEE_BPS)
+            .ok_or(TreasuryError::ArithmeticError)?
+            / 10_000;
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.fees_accrued = treasury
+            .fees_accrued
+            .checked_add(fee)
+            .ok_or(TreasuryError::ArithmeticError)?;
+        Ok(())
+    }
+
+    /// Fee collector sweeps accrued fees. `has_one = authority` is correct HERE.
+    pub fn collect_fees(ctx: Context<CollectFees>) -> Result<()> {
+        let amount = ctx.accounts.treasury.fees_accrued;
+        ctx.accounts.treasury.fees_accrued = 0;
+        move_lamports(
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            amount,
+        )
+    }
+
+    // VULNERABLE: has_one Checks the Wrong Relationship
+    // Written when `authority` still meant "admin". After the refactor it holds
+    // the fee collector, so the fee collector can drain the whole treasury.
+    pub fn vulnerable_withdraw_treasury(ctx: Context<VulnerableWithdrawTreasury>, amount: u64) -> Result<()> {
+        move_lamports(
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            amount,
+        )
+    }
+
+    // SECURE: Explicit Address Constraint
+    // `address = treasury.admin` names the exact field being compared, so the
+    // constraint cannot silently follow a rename or repurpose.
+    pub fn secure_withdraw_treasury(ctx: Context<SecureWithdrawTreasury>, amount: u64) -> Result<()> {
+        move_lamports(
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.admin.to_account_info(),
+            amount,
+        )
+    }
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    let rent_floor = Rent::get()?.minimum_balance(from.data_len());
+    let remaining = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(TreasuryError::InsufficientFunds)?;
+    require!(remaining >= rent_floor, TreasuryError::InsufficientFunds);
+
+    **from.try_borrow_mut_lamports()? = remaining;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(TreasuryError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Treasury::INIT_SPACE,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundTreasury<'info> {
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CollectFees<'info> {
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+        has_one = authority @ TreasuryError::Unauthorized
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableWithdrawTreasury<'info> {
+    // VULNERABLE: Looks like an admin check. It is a fee-collector check.
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+        has_one = authority @ TreasuryError::Unauthorized
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SecureWithdrawTreasury<'info> {
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    // SECURE: Compared against the field that actually holds the admin
+    #[account(mut, address = treasury.admin @ TreasuryError::NotAdmin)]
+    pub admin: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Treasury {
+    /// Protocol admin. May withdraw principal.
+    pub admin: Pubkey,
+    /// Fee collector. Named `authority` for layout compatibility with v1,
+    /// where it held the admin.
+    pub authority: Pubkey,
+    pub fees_accrued: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum TreasuryError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Signer is not the treasury admin")]
+    NotAdmin,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
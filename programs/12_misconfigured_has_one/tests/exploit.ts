@@ -0,0 +1,141 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+
+describe("12: Misconfigured has_one", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.MisconfiguredHasOne as Program<any>;
+
+    let admin: Keypair;
+    let feeCollector: Keypair;
+    let treasury: PublicKey;
+
+    const FUNDING = 4 * LAMPORTS_PER_SOL;
+
+    before(async () => {
+        admin = Keypair.generate();
+        feeCollector = Keypair.generate();
+
+        for (const kp of [admin, feeCollector]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+
+        [treasury] = PublicKey.findProgramAddressSync([Buffer.from("treasury")], program.programId);
+
+        await program.methods
+            .initialize(feeCollector.publicKey)
+            .accounts({ treasury, admin: admin.publicKey, systemProgram: SystemProgram.programId })
+            .signers([admin])
+            .rpc();
+
+        await program.methods
+            .fundTreasury(new anchor.BN(FUNDING))
+            .accounts({ treasury, funder: admin.publicKey, systemProgram: SystemProgram.programId })
+            .signers([admin])
+            .rpc();
+    });
+
+    describe("Schema: field semantics", () => {
+        it("admin field holds the admin, authority field holds the fee collector", async () => {
+            const state = await program.account.treasury.fetch(treasury);
+
+            // If a refactor changes what a field means, this test must change with it -
+            // and so must every constraint that references the field.
+            expect(state.admin.toString()).to.equal(admin.publicKey.toString());
+            expect(state.authority.toString()).to.equal(feeCollector.publicKey.toString());
+            expect(state.authority.toString()).to.not.equal(state.admin.toString());
+        });
+
+        it("fee collector can collect fees (the legitimate use of has_one = authority)", async () => {
+            const before = await provider.connection.getBalance(feeCollector.publicKey);
+
+            await program.methods
+                .collectFees()
+                .accounts({ treasury, authority: feeCollector.publicKey })
+                .signers([feeCollector])
+                .rpc();
+
+            const after = await provider.connection.getBalance(feeCollector.publicKey);
+            expect(after).to.be.greaterThan(before);
+        });
+    });
+
+    describe("Exploit: fee collector passes the 'admin' check", () => {
+        it("vulnerable_withdraw_treasury lets the fee collector drain principal", async () => {
+            const before = await provider.connection.getBalance(feeCollector.publicKey);
+
+            await program.methods
+                .vulnerableWithdrawTreasury(new anchor.BN(1 * LAMPORTS_PER_SOL))
+                .accounts({ treasury, authority: feeCollector.publicKey })
+                .signers([feeCollector])
+                .rpc();
+
+            const gained = (await provider.connection.getBalance(feeCollector.publicKey)) - before;
+            console.log(`  Fee collector withdrew ${gained / LAMPORTS_PER_SOL} SOL of principal`);
+            expect(gained).to.be.greaterThan(0.99 * LAMPORTS_PER_SOL);
+        });
+
+        it("the real admin is rejected by the same check", async () => {
+            try {
+                await program.methods
+                    .vulnerableWithdrawTreasury(new anchor.BN(1 * LAMPORTS_PER_SOL))
+                    .accounts({ treasury, authority: admin.publicKey })
+                    .signers([admin])
+                    .rpc();
+
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("Unauthorized");
+            }
+        });
+    });
+
+    describe("Secure: address = treasury.admin", () => {
+        it("rejects the fee collector", async () => {
+            try {
+                await program.methods
+                    .secureWithdrawTreasury(new anchor.BN(1 * LAMPORTS_PER_SOL))
+                    .accounts({ treasury, admin: feeCollector.publicKey })
+                    .signers([feeCollector])
+                    .rpc();
+
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("NotAdmin");
+            }
+        });
+
+        it("allows the admin", async () => {
+            const before = await provider.connection.getBalance(admin.publicKey);
+
+            await program.methods
+                .secureWithdrawTreasury(new anchor.BN(1 * LAMPORTS_PER_SOL))
+                .accounts({ treasury, admin: admin.publicKey })
+                .signers([admin])
+                .rpc();
+
+            expect(await provider.connection.getBalance(admin.publicKey)).to.be.greaterThan(before);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== has_one Target Drift ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  #[account(has_one = authority)] treasury  // authority now = fee collector");
+            console.log("  Risk: constraint still compiles, still passes - for the wrong key\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  #[account(address = treasury.admin @ TreasuryError::NotAdmin)] admin");
+            console.log("  Protection: the compared field is named at the use site; schema test pins semantics\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  has_one checks a field NAME. Make sure the name still means what you think.");
+        });
+    });
+});
@@ -0,0 +1,331 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::sysvar::slot_hashes;
+use anchor_lang::system_program::{transfer, Transfer};
+use security_common::time::{ClockExt, Slot};
+
+declare_id!("EbajVFW9fogVpuJMYFT4ofgmVDjohe1RfN72ML6PVw3e");
+
+pub const MAX_ENTRIES: usize = 16;
+
+#[program]
+pub mod weak_randomness {
+    use super::*;
+
+    pub fn create_raffle(
+        ctx: Context<CreateRaffle>,
+        raffle_id: u64,
+        ticket_price: u64,
+        close_slot: u64,
+        commitment: [u8; 32],
+        vrf_authority: Pubkey,
+    ) -> Result<()> {
+        let raffle = &mut ctx.accounts.raffle;
+        raffle.creator = ctx.accounts.creator.key();
+        raffle.raffle_id = raffle_id;
+        raffle.ticket_price = ticket_price;
+        raffle.close_slot = close_slot;
+        raffle.commitment = commitment;
+        raffle.vrf_authority = vrf_authority;
+        raffle.vrf_requested = false;
+        raffle.entries = Vec::new();
+        raffle.pot = 0;
+        raffle.winner = None;
+        raffle.bump = ctx.bumps.raffle;
+        Ok(())
+    }
+
+    pub fn buy_ticket(ctx: Context<BuyTicket>) -> Result<()> {
+        let raffle = &ctx.accounts.raffle;
+        require!(
+            Clock::get()?.current_slot() < Slot(raffle.close_slot),
+            RaffleError::SalesClosed
+        );
+        require!(raffle.entries.len() < MAX_ENTRIES, RaffleError::RaffleFull);
+        // The creator knows the committed secret and could keep buying
+        // tickets until the entry set hashes in their favor.
+        require_keys_neq!(ctx.accounts.player.key(), raffle.creator, RaffleError::CreatorCannotEnter);
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.player.to_account_info(),
+            to: ctx.accounts.raffle.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+        transfer(cpi_ctx, raffle.ticket_price)?;
+
+        let raffle = &mut ctx.accounts.raffle;
+        raffle.pot = raffle
+            .pot
+            .checked_add(raffle.ticket_price)
+            .ok_or(RaffleError::ArithmeticError)?;
+        raffle.entries.push(ctx.accounts.player.key());
+        Ok(())
+    }
+
+    // VULNERABLE: Randomness From Slot and Recent Blockhash
+    // 1. Both inputs are public before the draw transaction lands
+    // 2. Anyone can simulate the draw and only submit when they win
+    // 3. The slot leader can reorder or drop the draw to pick the outcome
+    pub fn vulnerable_draw_winner(ctx: Context<VulnerableDrawWinner>) -> Result<()> {
+        let clock = Clock::get()?;
+        require!(ctx.accounts.raffle.winner.is_none(), RaffleError::AlreadyDrawn);
+        require!(clock.current_slot() >= Slot(ctx.accounts.raffle.close_slot), RaffleError::SalesOpen);
+
+        // SlotHashes layout: u64 count, then (u64 slot, [u8; 32] hash) newest first.
+        let data = ctx.accounts.recent_slothashes.try_borrow_data()?;
+        let mut recent_slot_hash = [0u8; 32];
+        recent_slot_hash.copy_from_slice(&data[16..48]);
+        drop(data);
+
+        let randomness = hashv(&[&clock.slot.to_le_bytes(), &recent_slot_hash]).to_bytes();
+        let winner = select_winner(&mut ctx.accounts.raffle, randomness)?;
+
+        emit!(PredictableDraw {
+            raffle: ctx.accounts.raffle.key(),
+            slot: clock.slot,
+            recent_slot_hash,
+            winner,
+        });
+        Ok(())
+    }
+
+    // SECURE: Commit-Reveal
+    // 1. The creator committed to hash(secret) before any ticket was sold
+    // 2. The entry set is frozen at close_slot, before the secret is revealed
+    // 3. The outcome mixes the secret with every entry, so neither side alone
+    //    controls it
+    pub fn secure_reveal(ctx: Context<SecureReveal>, secret: [u8; 32]) -> Result<()> {
+        let raffle = &ctx.accounts.raffle;
+        require!(raffle.winner.is_none(), RaffleError::AlreadyDrawn);
+        require!(
+            Clock::get()?.current_slot() >= Slot(raffle.close_slot),
+            RaffleError::SalesOpen
+        );
+        require!(
+            hashv(&[&secret]).to_bytes() == raffle.commitment,
+            RaffleError::CommitmentMismatch
+        );
+
+        let mut inputs: Vec<&[u8]> = vec![&secret];
+        inputs.extend(raffle.entries.iter().map(|entry| entry.as_ref()));
+        let randomness = hashv(&inputs).to_bytes();
+
+        select_winner(&mut ctx.accounts.raffle, randomness)?;
+        Ok(())
+    }
+
+    // SECURE: VRF Integration Point
+    // Anyone may request randomness once sales close. The oracle answers by
+    // calling `consume_randomness`, which only accepts the configured authority.
+    pub fn request_randomness(ctx: Context<RequestRandomness>) -> Result<()> {
+        let raffle = &mut ctx.accounts.raffle;
+        require!(raffle.winner.is_none(), RaffleError::AlreadyDrawn);
+        require!(
+            Clock::get()?.current_slot() >= Slot(raffle.close_slot),
+            RaffleError::SalesOpen
+        );
+        raffle.vrf_requested = true;
+
+        emit!(RandomnessRequested {
+            raffle: raffle.key(),
+            vrf_authority: raffle.vrf_authority,
+        });
+        Ok(())
+    }
+
+    /// VRF callback. In production `vrf_authority` is the oracle's signing PDA
+    /// and the proof is verified by the oracle program before this is invoked.
+    pub fn consume_randomness(ctx: Context<ConsumeRandomness>, randomness: [u8; 32]) -> Result<()> {
+        let raffle = &mut ctx.accounts.raffle;
+        require!(raffle.vrf_requested, RaffleError::RandomnessNotRequested);
+        require!(raffle.winner.is_none(), RaffleError::AlreadyDrawn);
+
+        raffle.vrf_requested = false;
+        select_winner(raffle, randomness)?;
+        Ok(())
+    }
+
+    pub fn claim_prize(ctx: Context<ClaimPrize>) -> Result<()> {
+        require!(
+            ctx.accounts.raffle.winner == Some(ctx.accounts.winner.key()),
+            RaffleError::NotWinner
+        );
+
+        let amount = ctx.accounts.raffle.pot;
+        ctx.accounts.raffle.pot = 0;
+
+        let raffle_info = ctx.accounts.raffle.to_account_info();
+        let winner_info = ctx.accounts.winner.to_account_info();
+        **raffle_info.try_borrow_mut_lamports()? = raffle_info
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(RaffleError::ArithmeticError)?;
+        **winner_info.try_borrow_mut_lamports()? = winner_info
+            .lamports()
+            .checked_add(amount)
+            .ok_or(RaffleError::ArithmeticError)?;
+        Ok(())
+    }
+}
+
+fn select_winner(raffle: &mut Raffle, randomness: [u8; 32]) -> Result<Pubkey> {
+    require!(!raffle.entries.is_empty(), RaffleError::NoEntries);
+
+    let mut head = [0u8; 8];
+    head.copy_from_slice(&randomness[..8]);
+    let index = (u64::from_le_bytes(head) % raffle.entries.len() as u64) as usize;
+
+    let winner = raffle.entries[index];
+    raffle.winner = Some(winner);
+    Ok(winner)
+}
+
+#[derive(Accounts)]
+#[instruction(raffle_id: u64)]
+pub struct CreateRaffle<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Raffle::INIT_SPACE,
+        seeds = [b"raffle", creator.key().as_ref(), &raffle_id.to_le_bytes()],
+        bump
+    )]
+    pub raffle: Account<'info, Raffle>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BuyTicket<'info> {
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle.creator.as_ref(), &raffle.raffle_id.to_le_bytes()],
+        bump = raffle.bump
+    )]
+    pub raffle: Account<'info, Raffle>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableDrawWinner<'info> {
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle.creator.as_ref(), &raffle.raffle_id.to_le_bytes()],
+        bump = raffle.bump
+    )]
+    pub raffle: Account<'info, Raffle>,
+    /// CHECK: Address-checked SlotHashes sysvar
+    #[account(address = slot_hashes::ID)]
+    pub recent_slothashes: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SecureReveal<'info> {
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle.creator.as_ref(), &raffle.raffle_id.to_le_bytes()],
+        bump = raffle.bump,
+        has_one = creator @ RaffleError::Unauthorized
+    )]
+    pub raffle: Account<'info, Raffle>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RequestRandomness<'info> {
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle.creator.as_ref(), &raffle.raffle_id.to_le_bytes()],
+        bump = raffle.bump
+    )]
+    pub raffle: Account<'info, Raffle>,
+    pub requester: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConsumeRandomness<'info> {
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle.creator.as_ref(), &raffle.raffle_id.to_le_bytes()],
+        bump = raffle.bump
+    )]
+    pub raffle: Account<'info, Raffle>,
+    #[account(address = raffle.vrf_authority @ RaffleError::NotVrfAuthority)]
+    pub vrf_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPrize<'info> {
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle.creator.as_ref(), &raffle.raffle_id.to_le_bytes()],
+        bump = raffle.bump
+    )]
+    pub raffle: Account<'info, Raffle>,
+    #[account(mut)]
+    pub winner: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Raffle {
+    pub creator: Pubkey,
+    pub raffle_id: u64,
+    pub ticket_price: u64,
+    /// Ticket sales stop at this slot; draws are only allowed from it onwards.
+    pub close_slot: u64,
+    /// `sha256(secret)`, committed by the creator before sales open.
+    pub commitment: [u8; 32],
+    pub vrf_authority: Pubkey,
+    pub vrf_requested: bool,
+    #[max_len(MAX_ENTRIES)]
+    pub entries: Vec<Pubkey>,
+    pub pot: u64,
+    pub winner: Option<Pubkey>,
+    pub bump: u8,
+}
+
+#[event]
+pub struct PredictableDraw {
+    pub raffle: Pubkey,
+    pub slot: u64,
+    pub recent_slot_hash: [u8; 32],
+    pub winner: Pubkey,
+}
+
+#[event]
+pub struct RandomnessRequested {
+    pub raffle: Pubkey,
+    pub vrf_authority: Pubkey,
+}
+
+#[error_code]
+pub enum RaffleError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Ticket sales have closed")]
+    SalesClosed,
+    #[msg("Ticket sales are still open")]
+    SalesOpen,
+    #[msg("Raffle is full")]
+    RaffleFull,
+    #[msg("Raffle creator cannot buy tickets")]
+    CreatorCannotEnter,
+    #[msg("Raffle has no entries")]
+    NoEntries,
+    #[msg("Winner has already been drawn")]
+    AlreadyDrawn,
+    #[msg("Revealed secret does not match commitment")]
+    CommitmentMismatch,
+    #[msg("Randomness has not been requested")]
+    RandomnessNotRequested,
+    #[msg("Signer is not the VRF authority")]
+    NotVrfAuthority,
+    #[msg("Signer is not the winner")]
+    NotWinner,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
@@ -0,0 +1,191 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use security_common::math::mul_div_floor;
+use security_common::time::{ClockExt, Slot, UnixTs};
+
+declare_id!("BTYaufP7qUkvdX4ZTdv5rvWAkNyDiP7vSL8bK9yaBjCt");
+
+/// Upper bound on how far `unix_timestamp` may advance per slot. Mainnet
+/// averages ~0.4s per slot; 1s leaves headroom for slow leaders.
+pub const MAX_SECS_PER_SLOT: u64 = 1;
+/// Extra allowance on top of the per-slot bound, for validator timestamp drift.
+pub const SKEW_TOLERANCE_SECS: u64 = 60;
+
+#[program]
+pub mod timestamp_manipulation {
+    use super::*;
+
+    pub fn create_vesting(
+        ctx: Context<CreateVesting>,
+        vesting_id: u64,
+        total_amount: u64,
+        start_ts: i64,
+        duration_secs: i64,
+    ) -> Result<()> {
+        require!(duration_secs > 0, VestingError::InvalidDuration);
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.grantor.to_account_info(),
+            to: ctx.accounts.vesting.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+        transfer(cpi_ctx, total_amount)?;
+
+        let clock = Clock::get()?;
+        let vesting = &mut ctx.accounts.vesting;
+        vesting.grantor = ctx.accounts.grantor.key();
+        vesting.beneficiary = ctx.accounts.beneficiary.key();
+        vesting.vesting_id = vesting_id;
+        vesting.total_amount = total_amount;
+        vesting.released = 0;
+        vesting.start_ts = start_ts;
+        vesting.duration_secs = duration_secs;
+        vesting.checkpoint_ts = clock.unix_timestamp;
+        vesting.checkpoint_slot = clock.slot;
+        vesting.bump = ctx.bumps.vesting;
+        Ok(())
+    }
+
+    // VULNERABLE: Caller-Supplied Time and Wrapping Elapsed
+    // 1. `now` is an instruction argument - the caller picks the time
+    // 2. `now - start` is negative before the start; `as u64` wraps it to ~2^64
+    // 3. A wrapped elapsed time reads as "fully vested"
+    pub fn vulnerable_release(ctx: Context<Release>, now: i64) -> Result<()> {
+        let vesting = &ctx.accounts.vesting;
+
+        let elapsed = now.wrapping_sub(vesting.start_ts) as u64;
+        let vested = if elapsed >= vesting.duration_secs as u64 {
+            vesting.total_amount
+        } else {
+            mul_div_floor(vesting.total_amount, elapsed, vesting.duration_secs as u64)
+                .ok_or(VestingError::ArithmeticError)?
+        };
+
+        release(ctx, vested)
+    }
+
+    // SECURE: Sysvar Time, Monotonic Checkpoint, Clamped Elapsed
+    // 1. Time comes from the Clock sysvar, never from the caller
+    // 2. Time must not run backwards relative to the stored checkpoint
+    // 3. Time must not run ahead of what the elapsed slots allow
+    // 4. Elapsed time is clamped to [0, duration]
+    pub fn secure_release(ctx: Context<Release>) -> Result<()> {
+        let clock = Clock::get()?;
+        let now = clock.current_unix_ts();
+        let vesting = &ctx.accounts.vesting;
+
+        let secs = now
+            .secs_since(UnixTs(vesting.checkpoint_ts))
+            .ok_or(VestingError::ClockWentBackwards)?;
+        let slots = clock
+            .current_slot()
+            .slots_since(Slot(vesting.checkpoint_slot))
+            .ok_or(VestingError::ClockWentBackwards)?;
+        let max_secs = slots
+            .checked_mul(MAX_SECS_PER_SLOT)
+            .and_then(|s| s.checked_add(SKEW_TOLERANCE_SECS))
+            .ok_or(VestingError::ArithmeticError)?;
+        require!(secs as u64 <= max_secs, VestingError::ClockSkew);
+
+        // Before the start, elapsed is zero - not negative, not wrapped.
+        let elapsed = now
+            .secs_since(UnixTs(vesting.start_ts))
+            .unwrap_or(0)
+            .min(vesting.duration_secs);
+        let vested = mul_div_floor(vesting.total_amount, elapsed as u64, vesting.duration_secs as u64)
+            .ok_or(VestingError::ArithmeticError)?;
+
+        let vesting = &mut ctx.accounts.vesting;
+        vesting.checkpoint_ts = now.0;
+        vesting.checkpoint_slot = clock.slot;
+
+        release(ctx, vested)
+    }
+}
+
+fn release(ctx: Context<Release>, vested: u64) -> Result<()> {
+    let vesting = &mut ctx.accounts.vesting;
+    let claimable = vested
+        .checked_sub(vesting.released)
+        .ok_or(VestingError::ArithmeticError)?;
+    require!(claimable > 0, VestingError::NothingToRelease);
+
+    vesting.released = vested;
+
+    let vesting_info = vesting.to_account_info();
+    let beneficiary_info = ctx.accounts.beneficiary.to_account_info();
+    **vesting_info.try_borrow_mut_lamports()? = vesting_info
+        .lamports()
+        .checked_sub(claimable)
+        .ok_or(VestingError::ArithmeticError)?;
+    **beneficiary_info.try_borrow_mut_lamports()? = beneficiary_info
+        .lamports()
+        .checked_add(claimable)
+        .ok_or(VestingError::ArithmeticError)?;
+
+    msg!("Released {} lamports ({} of {} vested)", claimable, vested, vesting.total_amount);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(vesting_id: u64)]
+pub struct CreateVesting<'info> {
+    #[account(
+        init,
+        payer = grantor,
+        space = 8 + Vesting::INIT_SPACE,
+        seeds = [b"vesting", beneficiary.key().as_ref(), &vesting_id.to_le_bytes()],
+        bump
+    )]
+    pub vesting: Account<'info, Vesting>,
+    #[account(mut)]
+    pub grantor: Signer<'info>,
+    /// CHECK: Only used as a seed and recorded as the beneficiary
+    pub beneficiary: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Release<'info> {
+    #[account(
+        mut,
+        seeds = [b"vesting", beneficiary.key().as_ref(), &vesting.vesting_id.to_le_bytes()],
+        bump = vesting.bump,
+        has_one = beneficiary @ VestingError::Unauthorized
+    )]
+    pub vesting: Account<'info, Vesting>,
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vesting {
+    pub grantor: Pubkey,
+    pub beneficiary: Pubkey,
+    pub vesting_id: u64,
+    pub total_amount: u64,
+    pub released: u64,
+    pub start_ts: i64,
+    pub duration_secs: i64,
+    /// Clock reading at the last secure release (or creation).
+    pub checkpoint_ts: i64,
+    pub checkpoint_slot: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum VestingError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Vesting duration must be positive")]
+    InvalidDuration,
+    #[msg("Nothing to release")]
+    NothingToRelease,
+    #[msg("Clock moved backwards since the last checkpoint")]
+    ClockWentBackwards,
+    #[msg("Clock advanced further than elapsed slots allow")]
+    ClockSkew,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
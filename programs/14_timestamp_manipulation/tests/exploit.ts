@@ -0,0 +1,166 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { sleep } from "../../../tests/utils";
+
+describe("14: Timestamp Manipulation", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.TimestampManipulation as Program<any>;
+
+    let grantor: Keypair;
+    let beneficiary: Keypair;
+
+    const TOTAL = 2 * LAMPORTS_PER_SOL;
+    const ONE_YEAR = 365 * 24 * 60 * 60;
+
+    const VULNERABLE_ID = 1;
+    const SECURE_ID = 2;
+    const FUTURE_START_ID = 3;
+
+    function vestingPda(id: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("vesting"), beneficiary.publicKey.toBuffer(), new anchor.BN(id).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        )[0];
+    }
+
+    async function chainTime(): Promise<number> {
+        const blockTime = await provider.connection.getBlockTime(await provider.connection.getSlot());
+        return blockTime ?? Math.floor(Date.now() / 1000);
+    }
+
+    async function createVesting(id: number, startTs: number) {
+        await program.methods
+            .createVesting(new anchor.BN(id), new anchor.BN(TOTAL), new anchor.BN(startTs), new anchor.BN(ONE_YEAR))
+            .accounts({
+                vesting: vestingPda(id),
+                grantor: grantor.publicKey,
+                beneficiary: beneficiary.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([grantor])
+            .rpc();
+    }
+
+    function releaseAccounts(id: number) {
+        return { vesting: vestingPda(id), beneficiary: beneficiary.publicKey };
+    }
+
+    before(async () => {
+        grantor = Keypair.generate();
+        beneficiary = Keypair.generate();
+
+        for (const kp of [grantor, beneficiary]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup: one-year linear vesting schedules", () => {
+        it("grantor funds three schedules", async () => {
+            const now = await chainTime();
+            await createVesting(VULNERABLE_ID, now);
+            await createVesting(SECURE_ID, now);
+            await createVesting(FUTURE_START_ID, now + 3600);
+
+            const state = await program.account.vesting.fetch(vestingPda(SECURE_ID));
+            expect(state.totalAmount.toNumber()).to.equal(TOTAL);
+            expect(state.released.toNumber()).to.equal(0);
+        });
+    });
+
+    describe("Exploit: caller-supplied timestamp with wrapping elapsed time", () => {
+        it("passing now = 0 wraps elapsed time and releases everything", async () => {
+            const before = await provider.connection.getBalance(beneficiary.publicKey);
+
+            // 0 - start_ts is negative; `as u64` turns it into ~1.8e19 seconds.
+            await program.methods
+                .vulnerableRelease(new anchor.BN(0))
+                .accounts(releaseAccounts(VULNERABLE_ID))
+                .signers([beneficiary])
+                .rpc();
+
+            const gained = (await provider.connection.getBalance(beneficiary.publicKey)) - before;
+            const state = await program.account.vesting.fetch(vestingPda(VULNERABLE_ID));
+
+            console.log(`  Released on day 0: ${gained / LAMPORTS_PER_SOL} SOL of ${TOTAL / LAMPORTS_PER_SOL}`);
+            expect(state.released.toNumber()).to.equal(TOTAL);
+        });
+
+        it("even an honest timestamp drains a schedule that has not started", async () => {
+            const now = await chainTime();
+
+            await program.methods
+                .vulnerableRelease(new anchor.BN(now))
+                .accounts(releaseAccounts(FUTURE_START_ID))
+                .signers([beneficiary])
+                .rpc();
+
+            const state = await program.account.vesting.fetch(vestingPda(FUTURE_START_ID));
+            expect(state.released.toNumber()).to.equal(TOTAL);
+        });
+    });
+
+    describe("Secure: sysvar time, checkpoint, clamped elapsed", () => {
+        it("releases only what real elapsed time has vested", async () => {
+            await sleep(3);
+
+            await program.methods
+                .secureRelease()
+                .accounts(releaseAccounts(SECURE_ID))
+                .signers([beneficiary])
+                .rpc();
+
+            const state = await program.account.vesting.fetch(vestingPda(SECURE_ID));
+            console.log(`  Released after a few seconds: ${state.released.toNumber()} lamports`);
+
+            expect(state.released.toNumber()).to.be.greaterThan(0);
+            expect(state.released.toNumber()).to.be.lessThan(TOTAL / 1000);
+        });
+
+        it("advances the stored checkpoint", async () => {
+            const state = await program.account.vesting.fetch(vestingPda(SECURE_ID));
+            expect(state.checkpointTs.toNumber()).to.be.greaterThan(state.startTs.toNumber() - 1);
+            expect(state.checkpointSlot.toNumber()).to.be.greaterThan(0);
+        });
+
+        it("clamps negative elapsed time to zero", async () => {
+            const now = await chainTime();
+            await createVesting(4, now + 3600);
+
+            try {
+                await program.methods
+                    .secureRelease()
+                    .accounts(releaseAccounts(4))
+                    .signers([beneficiary])
+                    .rpc();
+
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("NothingToRelease");
+            }
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Timestamp Manipulation ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  fn release(now: i64) { let elapsed = (now - start) as u64; ... }");
+            console.log("  Risk: caller picks the time; negative elapsed wraps to fully vested\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  now = Clock::get()?.current_unix_ts()");
+            console.log("  require!(now >= checkpoint && now - checkpoint <= slots * MAX + TOLERANCE)");
+            console.log("  elapsed = now.secs_since(start).unwrap_or(0).min(duration)");
+            console.log("  Protection: time is sysvar-sourced, monotonic, bounded and clamped\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Time is an input like any other: source it, bound it, clamp it.");
+        });
+    });
+});
@@ -0,0 +1,412 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use security_common::circuit_breaker::{Operation, PauseLevel};
+use security_common::time::{ClockExt, Slot};
+
+declare_id!("CxPnTKYoBgNWQsaLRzr1RXuqKeD6TUqfBCqhjzWU319");
+
+/// Fixed-point scale for `acc_reward_per_share`.
+pub const ACC_PRECISION: u128 = 1_000_000_000_000;
+
+#[program]
+pub mod pause_state_gap {
+    use super::*;
+
+    pub fn initialize_pool(ctx: Context<InitializePool>, pool_id: u64, reward_rate_per_slot: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.admin = ctx.accounts.admin.key();
+        pool.pool_id = pool_id;
+        pool.reward_rate_per_slot = reward_rate_per_slot;
+        pool.acc_reward_per_share = 0;
+        pool.last_update_slot = Clock::get()?.slot;
+        pool.total_staked = 0;
+        pool.reward_reserve = 0;
+        pool.paused = false;
+        pool.pause_level = PauseLevel::Active;
+        pool.bump = ctx.bumps.pool;
+        Ok(())
+    }
+
+    pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.funder.to_account_info(),
+            to: ctx.accounts.pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+        transfer(cpi_ctx, amount)?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.reward_reserve = pool
+            .reward_reserve
+            .checked_add(amount)
+            .ok_or(StakeError::ArithmeticError)?;
+        Ok(())
+    }
+
+    pub fn set_reward_rate(ctx: Context<AdminAction>, reward_rate_per_slot: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        // Settle the old rate up to now, unless accrual is frozen.
+        if pool.pause_level.allows(Operation::Accrue) {
+            accrue(pool, Clock::get()?.current_slot())?;
+        }
+        pool.reward_rate_per_slot = reward_rate_per_slot;
+        Ok(())
+    }
+
+    pub fn create_position(ctx: Context<CreatePosition>) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.owner = ctx.accounts.owner.key();
+        position.pool = ctx.accounts.pool.key();
+        position.amount = 0;
+        position.reward_debt = 0;
+        position.bump = ctx.bumps.position;
+        Ok(())
+    }
+
+    // VULNERABLE: Pause as a Single Boolean
+    // Each instruction decides for itself whether to honour `paused`.
+    // Deposit and claim do; withdraw and accrue do not.
+    pub fn vulnerable_set_paused(ctx: Context<AdminAction>, paused: bool) -> Result<()> {
+        ctx.accounts.pool.paused = paused;
+        Ok(())
+    }
+
+    pub fn vulnerable_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        require!(!ctx.accounts.pool.paused, StakeError::Paused);
+        process_deposit(ctx, amount)
+    }
+
+    pub fn vulnerable_claim(ctx: Context<Claim>) -> Result<()> {
+        require!(!ctx.accounts.pool.paused, StakeError::Paused);
+        process_claim(ctx)
+    }
+
+    // VULNERABLE: Withdraw Ignores Pause
+    // "Users must always be able to exit" - but withdraw also settles rewards,
+    // so during an incident it pays out whatever the broken accumulator says.
+    pub fn vulnerable_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        process_withdraw(ctx, amount)
+    }
+
+    // VULNERABLE: Accrual Ignores Pause
+    // Anyone can keep advancing the accumulator while the admin investigates.
+    pub fn vulnerable_accrue(ctx: Context<Accrue>) -> Result<()> {
+        accrue(&mut ctx.accounts.pool, Clock::get()?.current_slot())
+    }
+
+    // SECURE: Pause Level With a Single Matrix
+    // 1. Entering Frozen settles accrual up to the pause, then stops it
+    // 2. Leaving Frozen restarts accrual from now - frozen slots earn nothing
+    // 3. Every gated instruction asks `pause_level.allows(op)`
+    pub fn secure_set_pause_level(ctx: Context<AdminAction>, level: PauseLevel) -> Result<()> {
+        let current_slot = Clock::get()?.current_slot();
+        let pool = &mut ctx.accounts.pool;
+
+        let was_accruing = pool.pause_level.allows(Operation::Accrue);
+        let will_accrue = level.allows(Operation::Accrue);
+        if was_accruing && !will_accrue {
+            accrue(pool, current_slot)?;
+        } else if !was_accruing && will_accrue {
+            pool.last_update_slot = current_slot.0;
+        }
+
+        pool.pause_level = level;
+        Ok(())
+    }
+
+    pub fn secure_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        require_allowed(&ctx.accounts.pool, Operation::Deposit)?;
+        process_deposit(ctx, amount)
+    }
+
+    pub fn secure_claim(ctx: Context<Claim>) -> Result<()> {
+        require_allowed(&ctx.accounts.pool, Operation::Claim)?;
+        process_claim(ctx)
+    }
+
+    pub fn secure_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        require_allowed(&ctx.accounts.pool, Operation::Withdraw)?;
+        process_withdraw(ctx, amount)
+    }
+
+    pub fn secure_accrue(ctx: Context<Accrue>) -> Result<()> {
+        require_allowed(&ctx.accounts.pool, Operation::Accrue)?;
+        accrue(&mut ctx.accounts.pool, Clock::get()?.current_slot())
+    }
+}
+
+fn require_allowed(pool: &Pool, op: Operation) -> Result<()> {
+    require!(pool.pause_level.allows(op), StakeError::OperationPaused);
+    Ok(())
+}
+
+fn accrue(pool: &mut Pool, current_slot: Slot) -> Result<()> {
+    let slots = current_slot
+        .slots_since(Slot(pool.last_update_slot))
+        .ok_or(StakeError::ArithmeticError)?;
+
+    if slots > 0 && pool.total_staked > 0 {
+        let increment = (slots as u128)
+            .checked_mul(pool.reward_rate_per_slot as u128)
+            .and_then(|r| r.checked_mul(ACC_PRECISION))
+            .ok_or(StakeError::ArithmeticError)?
+            / pool.total_staked as u128;
+        pool.acc_reward_per_share = pool
+            .acc_reward_per_share
+            .checked_add(increment)
+            .ok_or(StakeError::ArithmeticError)?;
+    }
+
+    pool.last_update_slot = current_slot.0;
+    Ok(())
+}
+
+fn accumulated(pool: &Pool, amount: u64) -> Result<u128> {
+    Ok((amount as u128)
+        .checked_mul(pool.acc_reward_per_share)
+        .ok_or(StakeError::ArithmeticError)?
+        / ACC_PRECISION)
+}
+
+/// Accrues, then books the position's pending rewards against the reserve.
+/// Returns the lamports owed to the owner; the caller moves them.
+fn settle(pool: &mut Pool, position: &Position) -> Result<u64> {
+    accrue(pool, Clock::get()?.current_slot())?;
+
+    let pending = accumulated(pool, position.amount)?
+        .checked_sub(position.reward_debt)
+        .ok_or(StakeError::ArithmeticError)?;
+    let paid = u64::try_from(pending)
+        .unwrap_or(u64::MAX)
+        .min(pool.reward_reserve);
+    pool.reward_reserve -= paid;
+    Ok(paid)
+}
+
+fn pay_out(pool: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **pool.try_borrow_mut_lamports()? = pool
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(StakeError::ArithmeticError)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(StakeError::ArithmeticError)?;
+    Ok(())
+}
+
+fn process_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+    let rewards = settle(&mut ctx.accounts.pool, &ctx.accounts.position)?;
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.owner.to_account_info(),
+        to: ctx.accounts.pool.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+    transfer(cpi_ctx, amount)?;
+
+    let pool = &mut ctx.accounts.pool;
+    let position = &mut ctx.accounts.position;
+    position.amount = position
+        .amount
+        .checked_add(amount)
+        .ok_or(StakeError::ArithmeticError)?;
+    position.reward_debt = accumulated(pool, position.amount)?;
+    pool.total_staked = pool
+        .total_staked
+        .checked_add(amount)
+        .ok_or(StakeError::ArithmeticError)?;
+
+    pay_out(
+        &ctx.accounts.pool.to_account_info(),
+        &ctx.accounts.owner.to_account_info(),
+        rewards,
+    )
+}
+
+fn process_claim(ctx: Context<Claim>) -> Result<()> {
+    let rewards = settle(&mut ctx.accounts.pool, &ctx.accounts.position)?;
+    ctx.accounts.position.reward_debt = accumulated(&ctx.accounts.pool, ctx.accounts.position.amount)?;
+
+    pay_out(
+        &ctx.accounts.pool.to_account_info(),
+        &ctx.accounts.owner.to_account_info(),
+        rewards,
+    )
+}
+
+fn process_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+    let rewards = settle(&mut ctx.accounts.pool, &ctx.accounts.position)?;
+
+    let pool = &mut ctx.accounts.pool;
+    let position = &mut ctx.accounts.position;
+    position.amount = position
+        .amount
+        .checked_sub(amount)
+        .ok_or(StakeError::InsufficientStake)?;
+    position.reward_debt = accumulated(pool, position.amount)?;
+    pool.total_staked = pool
+        .total_staked
+        .checked_sub(amount)
+        .ok_or(StakeError::ArithmeticError)?;
+
+    let total = amount.checked_add(rewards).ok_or(StakeError::ArithmeticError)?;
+    msg!("Withdraw: {} principal + {} rewards", amount, rewards);
+    pay_out(
+        &ctx.accounts.pool.to_account_info(),
+        &ctx.accounts.owner.to_account_info(),
+        total,
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u64)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Pool::INIT_SPACE,
+        seeds = [b"pool".as_ref(), &pool_id.to_le_bytes()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundRewards<'info> {
+    #[account(mut, seeds = [b"pool".as_ref(), &pool.pool_id.to_le_bytes()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AdminAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool".as_ref(), &pool.pool_id.to_le_bytes()],
+        bump = pool.bump,
+        has_one = admin @ StakeError::Unauthorized
+    )]
+    pub pool: Account<'info, Pool>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreatePosition<'info> {
+    #[account(seeds = [b"pool".as_ref(), &pool.pool_id.to_le_bytes()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [b"position", pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, seeds = [b"pool".as_ref(), &pool.pool_id.to_le_bytes()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mut,
+        seeds = [b"position", pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner @ StakeError::Unauthorized,
+        has_one = pool @ StakeError::Unauthorized
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(mut, seeds = [b"pool".as_ref(), &pool.pool_id.to_le_bytes()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mut,
+        seeds = [b"position", pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner @ StakeError::Unauthorized,
+        has_one = pool @ StakeError::Unauthorized
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut, seeds = [b"pool".as_ref(), &pool.pool_id.to_le_bytes()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mut,
+        seeds = [b"position", pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner @ StakeError::Unauthorized,
+        has_one = pool @ StakeError::Unauthorized
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Accrue<'info> {
+    #[account(mut, seeds = [b"pool".as_ref(), &pool.pool_id.to_le_bytes()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub admin: Pubkey,
+    pub pool_id: u64,
+    pub reward_rate_per_slot: u64,
+    /// Rewards per staked lamport, scaled by `ACC_PRECISION`.
+    pub acc_reward_per_share: u128,
+    pub last_update_slot: u64,
+    pub total_staked: u64,
+    /// Lamports set aside for rewards, separate from staked principal.
+    pub reward_reserve: u64,
+    /// Vulnerable pause switch.
+    pub paused: bool,
+    /// Secure pause switch.
+    pub pause_level: PauseLevel,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub amount: u64,
+    pub reward_debt: u128,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum StakeError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Pool is paused")]
+    Paused,
+    #[msg("Operation not allowed at the current pause level")]
+    OperationPaused,
+    #[msg("Insufficient stake")]
+    InsufficientStake,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
@@ -0,0 +1,304 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { sleep } from "../../../tests/utils";
+
+describe("15: Pause State Gap", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.PauseStateGap as Program<any>;
+
+    let admin: Keypair;
+    let attacker: Keypair;
+
+    const VULNERABLE_POOL = 1;
+    const SECURE_POOL = 2;
+    const MATRIX_POOL = 3;
+
+    const NORMAL_RATE = new anchor.BN(1_000);
+    // Fat-fingered: 0.1 SOL per slot instead of 1000 lamports
+    const BROKEN_RATE = new anchor.BN(LAMPORTS_PER_SOL / 10);
+    const STAKE = new anchor.BN(LAMPORTS_PER_SOL);
+    const RESERVE = new anchor.BN(3 * LAMPORTS_PER_SOL);
+
+    function poolPda(id: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("pool"), new anchor.BN(id).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        )[0];
+    }
+
+    function positionPda(pool: PublicKey, owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("position"), pool.toBuffer(), owner.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function userAccounts(id: number) {
+        const pool = poolPda(id);
+        return {
+            pool,
+            position: positionPda(pool, attacker.publicKey),
+            owner: attacker.publicKey,
+            systemProgram: SystemProgram.programId,
+        };
+    }
+
+    function adminAccounts(id: number) {
+        return { pool: poolPda(id), admin: admin.publicKey };
+    }
+
+    async function setupPool(id: number, deposit: (id: number) => Promise<string>) {
+        await program.methods
+            .initializePool(new anchor.BN(id), NORMAL_RATE)
+            .accounts({ pool: poolPda(id), admin: admin.publicKey, systemProgram: SystemProgram.programId })
+            .signers([admin])
+            .rpc();
+
+        await program.methods
+            .fundRewards(RESERVE)
+            .accounts({ pool: poolPda(id), funder: admin.publicKey, systemProgram: SystemProgram.programId })
+            .signers([admin])
+            .rpc();
+
+        const { pool, position, owner, systemProgram } = userAccounts(id);
+        await program.methods
+            .createPosition()
+            .accounts({ pool, position, owner, systemProgram })
+            .signers([attacker])
+            .rpc();
+
+        await deposit(id);
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        attacker = Keypair.generate();
+
+        for (const kp of [admin, attacker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 20 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Exploit: pause stops deposits and claims, not withdraw or accrue", () => {
+        let accAtPause: anchor.BN;
+
+        it("incident: reward rate misconfigured, admin pauses", async () => {
+            await setupPool(VULNERABLE_POOL, (id) =>
+                program.methods.vulnerableDeposit(STAKE).accounts(userAccounts(id)).signers([attacker]).rpc()
+            );
+
+            await program.methods
+                .setRewardRate(BROKEN_RATE)
+                .accounts(adminAccounts(VULNERABLE_POOL))
+                .signers([admin])
+                .rpc();
+
+            await program.methods
+                .vulnerableSetPaused(true)
+                .accounts(adminAccounts(VULNERABLE_POOL))
+                .signers([admin])
+                .rpc();
+
+            accAtPause = (await program.account.pool.fetch(poolPda(VULNERABLE_POOL))).accRewardPerShare;
+        });
+
+        it("claim is blocked by the pause", async () => {
+            try {
+                await program.methods
+                    .vulnerableClaim()
+                    .accounts(userAccounts(VULNERABLE_POOL))
+                    .signers([attacker])
+                    .rpc();
+
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("Paused");
+            }
+        });
+
+        it("accrue keeps running while paused", async () => {
+            await sleep(3);
+
+            await program.methods.vulnerableAccrue().accounts({ pool: poolPda(VULNERABLE_POOL) }).rpc();
+
+            const acc = (await program.account.pool.fetch(poolPda(VULNERABLE_POOL))).accRewardPerShare;
+            expect(acc.gt(accAtPause)).to.equal(true);
+        });
+
+        it("withdraw auto-settles the inflated rewards while paused", async () => {
+            const before = await provider.connection.getBalance(attacker.publicKey);
+
+            await program.methods
+                .vulnerableWithdraw(STAKE)
+                .accounts(userAccounts(VULNERABLE_POOL))
+                .signers([attacker])
+                .rpc();
+
+            const gained = (await provider.connection.getBalance(attacker.publicKey)) - before;
+            const rewards = gained - STAKE.toNumber();
+            console.log(`  Rewards extracted during the pause: ${rewards / LAMPORTS_PER_SOL} SOL`);
+            expect(rewards).to.be.greaterThan(0.5 * LAMPORTS_PER_SOL);
+        });
+    });
+
+    describe("Secure: Frozen level blocks every value-moving operation", () => {
+        let accAtFreeze: anchor.BN;
+
+        it("incident: reward rate misconfigured, admin freezes", async () => {
+            await setupPool(SECURE_POOL, (id) =>
+                program.methods.secureDeposit(STAKE).accounts(userAccounts(id)).signers([attacker]).rpc()
+            );
+
+            await program.methods
+                .setRewardRate(BROKEN_RATE)
+                .accounts(adminAccounts(SECURE_POOL))
+                .signers([admin])
+                .rpc();
+
+            await program.methods
+                .secureSetPauseLevel({ frozen: {} })
+                .accounts(adminAccounts(SECURE_POOL))
+                .signers([admin])
+                .rpc();
+
+            accAtFreeze = (await program.account.pool.fetch(poolPda(SECURE_POOL))).accRewardPerShare;
+        });
+
+        it("withdraw and accrue are rejected; the accumulator does not move", async () => {
+            await sleep(3);
+
+            for (const attempt of [
+                () => program.methods.secureAccrue().accounts({ pool: poolPda(SECURE_POOL) }).rpc(),
+                () =>
+                    program.methods
+                        .secureWithdraw(STAKE)
+                        .accounts(userAccounts(SECURE_POOL))
+                        .signers([attacker])
+                        .rpc(),
+            ]) {
+                try {
+                    await attempt();
+                    expect.fail("Should have thrown");
+                } catch (error: any) {
+                    expect(error.message).to.include("OperationPaused");
+                }
+            }
+
+            const acc = (await program.account.pool.fetch(poolPda(SECURE_POOL))).accRewardPerShare;
+            expect(acc.eq(accAtFreeze)).to.equal(true);
+        });
+
+        it("admin fixes the rate and unfreezes; frozen slots earn nothing", async () => {
+            await program.methods
+                .setRewardRate(NORMAL_RATE)
+                .accounts(adminAccounts(SECURE_POOL))
+                .signers([admin])
+                .rpc();
+
+            await program.methods
+                .secureSetPauseLevel({ active: {} })
+                .accounts(adminAccounts(SECURE_POOL))
+                .signers([admin])
+                .rpc();
+
+            const state = await program.account.pool.fetch(poolPda(SECURE_POOL));
+            expect(state.accRewardPerShare.eq(accAtFreeze)).to.equal(true);
+
+            const before = await provider.connection.getBalance(attacker.publicKey);
+            await program.methods
+                .secureWithdraw(STAKE)
+                .accounts(userAccounts(SECURE_POOL))
+                .signers([attacker])
+                .rpc();
+
+            const rewards = (await provider.connection.getBalance(attacker.publicKey)) - before - STAKE.toNumber();
+            console.log(`  Rewards paid after incident response: ${rewards / LAMPORTS_PER_SOL} SOL`);
+        });
+    });
+
+    describe("Secure: pause matrix, exhaustive per level", () => {
+        const SMALL = new anchor.BN(1_000);
+
+        const operations: Record<string, () => Promise<string>> = {
+            deposit: () =>
+                program.methods.secureDeposit(SMALL).accounts(userAccounts(MATRIX_POOL)).signers([attacker]).rpc(),
+            claim: () =>
+                program.methods
+                    .secureClaim()
+                    .accounts(userAccounts(MATRIX_POOL))
+                    .signers([attacker])
+                    .rpc(),
+            withdraw: () =>
+                program.methods
+                    .secureWithdraw(SMALL)
+                    .accounts(userAccounts(MATRIX_POOL))
+                    .signers([attacker])
+                    .rpc(),
+            accrue: () => program.methods.secureAccrue().accounts({ pool: poolPda(MATRIX_POOL) }).rpc(),
+            admin: () =>
+                program.methods.setRewardRate(NORMAL_RATE).accounts(adminAccounts(MATRIX_POOL)).signers([admin]).rpc(),
+        };
+
+        const matrix: Record<string, Record<string, boolean>> = {
+            active: { deposit: true, claim: true, withdraw: true, accrue: true, admin: true },
+            windDown: { deposit: false, claim: true, withdraw: true, accrue: true, admin: true },
+            frozen: { deposit: false, claim: false, withdraw: false, accrue: false, admin: true },
+        };
+
+        before(async () => {
+            await setupPool(MATRIX_POOL, (id) =>
+                program.methods
+                    .secureDeposit(new anchor.BN(LAMPORTS_PER_SOL / 10))
+                    .accounts(userAccounts(id))
+                    .signers([attacker])
+                    .rpc()
+            );
+        });
+
+        for (const [level, allowed] of Object.entries(matrix)) {
+            for (const [op, expected] of Object.entries(allowed)) {
+                it(`${level}: ${op} is ${expected ? "allowed" : "blocked"}`, async () => {
+                    await program.methods
+                        .secureSetPauseLevel({ [level]: {} })
+                        .accounts(adminAccounts(MATRIX_POOL))
+                        .signers([admin])
+                        .rpc();
+
+                    if (expected) {
+                        await operations[op]();
+                        return;
+                    }
+
+                    try {
+                        await operations[op]();
+                        expect.fail("Should have thrown");
+                    } catch (error: any) {
+                        expect(error.message).to.include("OperationPaused");
+                    }
+                });
+            }
+        }
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Pause State Gap ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  pool.paused: bool, checked by deposit and claim only");
+            console.log("  Risk: withdraw settles rewards and accrue advances state during the incident\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  pool.pause_level.allows(Operation::X) on every gated instruction");
+            console.log("  Protection: one table decides; Frozen stops accrual and restarts it from now\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  A pause is only as strong as the least-gated instruction that moves value.");
+        });
+    });
+});
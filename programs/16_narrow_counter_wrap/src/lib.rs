@@ -0,0 +1,233 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("2tx7XN5mwtAah9jmWQpF3N794Rgb4qJiQxSUzKLecQaY");
+
+#[program]
+pub mod narrow_counter_wrap {
+    use super::*;
+
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        reward_per_claim: u64,
+        max_total_per_user: u64,
+        max_claims_per_user: u64,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.reward_per_claim = reward_per_claim;
+        config.max_total_per_user = max_total_per_user;
+        config.max_claims_per_user = max_claims_per_user;
+        config.bump = ctx.bumps.config;
+        Ok(())
+    }
+
+    pub fn fund(ctx: Context<Fund>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.funder.to_account_info(),
+            to: ctx.accounts.config.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+        transfer(cpi_ctx, amount)
+    }
+
+    pub fn register(ctx: Context<Register>) -> Result<()> {
+        let rewards = &mut ctx.accounts.rewards;
+        rewards.user = ctx.accounts.user.key();
+        rewards.narrow_claim_count = 0;
+        rewards.narrow_total_claimed = 0;
+        rewards.claim_count = 0;
+        rewards.total_claimed = 0;
+        rewards.bump = ctx.bumps.rewards;
+        Ok(())
+    }
+
+    // VULNERABLE: Caps Tracked in Narrow Integers
+    // 1. `narrow_total_claimed` is a u32 of lamports - it wraps at ~4.29 SOL,
+    //    below the 5 SOL cap, so the cap can never be reached
+    // 2. `narrow_claim_count` is a u16 - it wraps at 65,535, below a
+    //    100,000 claim cap
+    // 3. wrapping_add makes the reset silent even with overflow-checks on
+    pub fn vulnerable_claim(ctx: Context<Claim>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let reward = config.reward_per_claim;
+        let rewards = &mut ctx.accounts.rewards;
+
+        require!(
+            (rewards.narrow_claim_count as u64) < config.max_claims_per_user,
+            RewardError::ClaimCapReached
+        );
+        require!(
+            rewards.narrow_total_claimed as u64 + reward <= config.max_total_per_user,
+            RewardError::ClaimCapReached
+        );
+
+        rewards.narrow_claim_count = rewards.narrow_claim_count.wrapping_add(1);
+        rewards.narrow_total_claimed = rewards.narrow_total_claimed.wrapping_add(reward as u32);
+
+        pay_reward(&ctx.accounts.config.to_account_info(), &ctx.accounts.user.to_account_info(), reward)
+    }
+
+    // SECURE: u64 Counters With Checked Arithmetic
+    // 1. u64 cannot wrap within any realistic number of claims or lamports
+    // 2. checked_add turns the impossible case into an error, not a reset
+    // 3. The cap is compared against the post-claim value
+    pub fn secure_claim(ctx: Context<Claim>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let reward = config.reward_per_claim;
+        let rewards = &mut ctx.accounts.rewards;
+
+        let claim_count = rewards
+            .claim_count
+            .checked_add(1)
+            .ok_or(RewardError::ArithmeticError)?;
+        let total_claimed = rewards
+            .total_claimed
+            .checked_add(reward)
+            .ok_or(RewardError::ArithmeticError)?;
+        require!(claim_count <= config.max_claims_per_user, RewardError::ClaimCapReached);
+        require!(total_claimed <= config.max_total_per_user, RewardError::ClaimCapReached);
+
+        rewards.claim_count = claim_count;
+        rewards.total_claimed = total_claimed;
+
+        pay_reward(&ctx.accounts.config.to_account_info(), &ctx.accounts.user.to_account_info(), reward)
+    }
+
+    /// Debug state-injection hook: writes a user's counters directly so tests
+    /// can reach boundaries (e.g. `u16::MAX`) without 65,535 transactions.
+    /// Always fails unless the program is built with `--features debug-hooks`.
+    pub fn debug_inject_claim_state(
+        ctx: Context<DebugInjectClaimState>,
+        claim_count: u64,
+        total_claimed: u64,
+    ) -> Result<()> {
+        // `#[program]` cannot cfg out a single instruction, so the hook is
+        // always in the IDL and compiled down to an error instead.
+        require!(cfg!(feature = "debug-hooks"), RewardError::DebugHooksDisabled);
+
+        let rewards = &mut ctx.accounts.rewards;
+        // Narrow fields get what an `as` cast would store.
+        rewards.narrow_claim_count = claim_count as u16;
+        rewards.narrow_total_claimed = total_claimed as u32;
+        rewards.claim_count = claim_count;
+        rewards.total_claimed = total_claimed;
+        Ok(())
+    }
+}
+
+fn pay_reward(config: &AccountInfo, user: &AccountInfo, amount: u64) -> Result<()> {
+    let rent_floor = Rent::get()?.minimum_balance(config.data_len());
+    let remaining = config
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(RewardError::InsufficientFunds)?;
+    require!(remaining >= rent_floor, RewardError::InsufficientFunds);
+
+    **config.try_borrow_mut_lamports()? = remaining;
+    **user.try_borrow_mut_lamports()? = user
+        .lamports()
+        .checked_add(amount)
+        .ok_or(RewardError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Fund<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Register<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = 8 + UserRewards::INIT_SPACE,
+        seeds = [b"rewards", user.key().as_ref()],
+        bump
+    )]
+    pub rewards: Account<'info, UserRewards>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"rewards", user.key().as_ref()],
+        bump = rewards.bump,
+        has_one = user @ RewardError::Unauthorized
+    )]
+    pub rewards: Account<'info, UserRewards>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DebugInjectClaimState<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, has_one = admin @ RewardError::Unauthorized)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [b"rewards", rewards.user.as_ref()], bump = rewards.bump)]
+    pub rewards: Account<'info, UserRewards>,
+    pub admin: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    pub reward_per_claim: u64,
+    pub max_total_per_user: u64,
+    pub max_claims_per_user: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct UserRewards {
+    pub user: Pubkey,
+    /// Vulnerable counters - "u16/u32 is plenty and saves space".
+    pub narrow_claim_count: u16,
+    pub narrow_total_claimed: u32,
+    /// Secure counters.
+    pub claim_count: u64,
+    pub total_claimed: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum RewardError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Per-user claim cap reached")]
+    ClaimCapReached,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+    #[msg("Program was built without debug hooks")]
+    DebugHooksDisabled,
+}
@@ -0,0 +1,210 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+
+describe("16: Narrow Counter Wrap", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.NarrowCounterWrap as Program<any>;
+
+    let admin: Keypair;
+    let attacker: Keypair;
+    let honestUser: Keypair;
+    let boundaryUser: Keypair;
+    let config: PublicKey;
+
+    const REWARD = LAMPORTS_PER_SOL / 2;
+    const MAX_TOTAL = 5 * LAMPORTS_PER_SOL; // above u32::MAX lamports (~4.29 SOL)
+    const MAX_CLAIMS = 100_000; // above u16::MAX
+    const U16_MAX = 65_535;
+
+    function rewardsPda(user: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("rewards"), user.toBuffer()], program.programId)[0];
+    }
+
+    function claimAccounts(user: Keypair) {
+        return { config, rewards: rewardsPda(user.publicKey), user: user.publicKey };
+    }
+
+    async function inject(user: Keypair, claimCount: number, totalClaimed: number) {
+        await program.methods
+            .debugInjectClaimState(new anchor.BN(claimCount), new anchor.BN(totalClaimed))
+            .accounts({ config, rewards: rewardsPda(user.publicKey), admin: admin.publicKey })
+            .signers([admin])
+            .rpc();
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        attacker = Keypair.generate();
+        honestUser = Keypair.generate();
+        boundaryUser = Keypair.generate();
+
+        for (const kp of [admin, attacker, honestUser, boundaryUser]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 25 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+
+        [config] = PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId);
+    });
+
+    describe("Setup", () => {
+        it("admin configures a 5 SOL / 100,000 claim per-user cap", async () => {
+            await program.methods
+                .initialize(new anchor.BN(REWARD), new anchor.BN(MAX_TOTAL), new anchor.BN(MAX_CLAIMS))
+                .accounts({ config, admin: admin.publicKey, systemProgram: SystemProgram.programId })
+                .signers([admin])
+                .rpc();
+
+            await program.methods
+                .fund(new anchor.BN(20 * LAMPORTS_PER_SOL))
+                .accounts({ config, funder: admin.publicKey, systemProgram: SystemProgram.programId })
+                .signers([admin])
+                .rpc();
+
+            for (const user of [attacker, honestUser, boundaryUser]) {
+                await program.methods
+                    .register()
+                    .accounts({
+                        rewards: rewardsPda(user.publicKey),
+                        user: user.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([user])
+                    .rpc();
+            }
+        });
+    });
+
+    describe("Exploit: u32 lamport total wraps below the cap", () => {
+        it("claims past 5 SOL because the stored total resets at ~4.29 SOL", async () => {
+            const CLAIMS = 12; // 6 SOL
+            const before = await provider.connection.getBalance(attacker.publicKey);
+
+            for (let i = 0; i < CLAIMS; i++) {
+                await program.methods.vulnerableClaim().accounts(claimAccounts(attacker)).signers([attacker]).rpc();
+            }
+
+            const received = (await provider.connection.getBalance(attacker.publicKey)) - before;
+            const state = await program.account.userRewards.fetch(rewardsPda(attacker.publicKey));
+
+            console.log(`  Received: ${received / LAMPORTS_PER_SOL} SOL (cap ${MAX_TOTAL / LAMPORTS_PER_SOL} SOL)`);
+            console.log(`  Stored narrow total: ${state.narrowTotalClaimed / LAMPORTS_PER_SOL} SOL`);
+
+            expect(received).to.be.greaterThan(MAX_TOTAL);
+            expect(state.narrowTotalClaimed).to.equal((CLAIMS * REWARD) % 2 ** 32);
+        });
+    });
+
+    describe("Secure: u64 counters with checked arithmetic", () => {
+        it("stops at exactly 5 SOL", async () => {
+            for (let i = 0; i < MAX_TOTAL / REWARD; i++) {
+                await program.methods.secureClaim().accounts(claimAccounts(honestUser)).signers([honestUser]).rpc();
+            }
+
+            try {
+                await program.methods.secureClaim().accounts(claimAccounts(honestUser)).signers([honestUser]).rpc();
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("ClaimCapReached");
+            }
+
+            const state = await program.account.userRewards.fetch(rewardsPda(honestUser.publicKey));
+            expect(state.totalClaimed.toNumber()).to.equal(MAX_TOTAL);
+        });
+    });
+
+    describe("Regression: looping past u16::MAX via debug hooks", () => {
+        before(async function () {
+            try {
+                await inject(boundaryUser, 0, 0);
+            } catch (error: any) {
+                if (error.message.includes("DebugHooksDisabled")) {
+                    console.log("  Skipping: build with `--features debug-hooks` (yarn test:16)");
+                    this.skip();
+                }
+                throw error;
+            }
+        });
+
+        it("narrow claim counter wraps to zero after u16::MAX", async () => {
+            await inject(boundaryUser, U16_MAX - 1, 0);
+
+            for (let i = 0; i < 4; i++) {
+                await program.methods
+                    .vulnerableClaim()
+                    .accounts(claimAccounts(boundaryUser))
+                    .signers([boundaryUser])
+                    .rpc();
+            }
+
+            const state = await program.account.userRewards.fetch(rewardsPda(boundaryUser.publicKey));
+            console.log(`  Narrow count after ${U16_MAX - 1} + 4 claims: ${state.narrowClaimCount}`);
+            expect(state.narrowClaimCount).to.equal((U16_MAX - 1 + 4) % (U16_MAX + 1));
+        });
+
+        it("u64 claim counter keeps counting past u16::MAX", async () => {
+            await inject(boundaryUser, U16_MAX - 1, 0);
+
+            for (let i = 0; i < 4; i++) {
+                await program.methods
+                    .secureClaim()
+                    .accounts(claimAccounts(boundaryUser))
+                    .signers([boundaryUser])
+                    .rpc();
+            }
+
+            const state = await program.account.userRewards.fetch(rewardsPda(boundaryUser.publicKey));
+            expect(state.claimCount.toNumber()).to.equal(U16_MAX - 1 + 4);
+        });
+
+        it("u64 claim counter still enforces the claim cap", async () => {
+            await inject(boundaryUser, MAX_CLAIMS - 1, 0);
+
+            await program.methods.secureClaim().accounts(claimAccounts(boundaryUser)).signers([boundaryUser]).rpc();
+
+            try {
+                await program.methods
+                    .secureClaim()
+                    .accounts(claimAccounts(boundaryUser))
+                    .signers([boundaryUser])
+                    .rpc();
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("ClaimCapReached");
+            }
+        });
+
+        it("rejects injection from anyone but the admin", async () => {
+            try {
+                await program.methods
+                    .debugInjectClaimState(new anchor.BN(0), new anchor.BN(0))
+                    .accounts({ config, rewards: rewardsPda(attacker.publicKey), admin: attacker.publicKey })
+                    .signers([attacker])
+                    .rpc();
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("Unauthorized");
+            }
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Narrow Counter Wrap ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  narrow_total_claimed: u32 (lamports), narrow_claim_count: u16");
+            console.log("  Risk: counters wrap below the configured cap; the cap silently resets\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  total_claimed: u64, claim_count: u64, checked_add before comparing");
+            console.log("  Protection: the counter cannot reset; overflow is an error\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  A counter's type must hold every value the cap allows, plus one.");
+        });
+    });
+});
@@ -0,0 +1,163 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use security_common::pda::hashed_seed;
+
+declare_id!("6V9VVXRySeEj9u4YCHgJcZWpz1x9E4JmvWi37SMrGigM");
+
+pub const MAX_NAME_LEN: usize = 32;
+
+#[program]
+pub mod seed_concat_collision {
+    use super::*;
+
+    // VULNERABLE: Raw Strings as Adjacent Seeds
+    // The runtime hashes seeds back to back, so ("abc", "def") and
+    // ("ab", "cdef") derive the same address. Whoever registers first owns
+    // every split of the same bytes.
+    pub fn vulnerable_register(
+        ctx: Context<VulnerableRegister>,
+        parent: String,
+        label: String,
+        target: Pubkey,
+    ) -> Result<()> {
+        write_record(&mut ctx.accounts.record, ctx.accounts.owner.key(), parent, label, target, ctx.bumps.record)
+    }
+
+    pub fn vulnerable_pay(ctx: Context<VulnerablePay>, _parent: String, _label: String, amount: u64) -> Result<()> {
+        pay_target(&ctx.accounts.payer, &ctx.accounts.target, &ctx.accounts.system_program, amount)
+    }
+
+    // SECURE: Length-Prefixed, Hashed Seed
+    // 1. Each part is prefixed with its length, so boundaries are part of the input
+    // 2. The result is always 32 bytes, regardless of name length
+    pub fn secure_register(
+        ctx: Context<SecureRegister>,
+        parent: String,
+        label: String,
+        target: Pubkey,
+    ) -> Result<()> {
+        write_record(&mut ctx.accounts.record, ctx.accounts.owner.key(), parent, label, target, ctx.bumps.record)
+    }
+
+    pub fn secure_pay(ctx: Context<SecurePay>, _parent: String, _label: String, amount: u64) -> Result<()> {
+        pay_target(&ctx.accounts.payer, &ctx.accounts.target, &ctx.accounts.system_program, amount)
+    }
+}
+
+fn write_record(
+    record: &mut Record,
+    owner: Pubkey,
+    parent: String,
+    label: String,
+    target: Pubkey,
+    bump: u8,
+) -> Result<()> {
+    require!(
+        parent.len() <= MAX_NAME_LEN && label.len() <= MAX_NAME_LEN,
+        RegistryError::NameTooLong
+    );
+    record.owner = owner;
+    record.target = target;
+    record.parent = parent;
+    record.label = label;
+    record.bump = bump;
+    Ok(())
+}
+
+fn pay_target<'info>(
+    payer: &Signer<'info>,
+    target: &UncheckedAccount<'info>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: payer.to_account_info(),
+        to: target.to_account_info(),
+    };
+    transfer(CpiContext::new(system_program.to_account_info(), cpi_accounts), amount)
+}
+
+pub fn record_seed(parent: &str, label: &str) -> [u8; 32] {
+    hashed_seed(&[parent.as_bytes(), label.as_bytes()])
+}
+
+#[derive(Accounts)]
+#[instruction(parent: String, label: String)]
+pub struct VulnerableRegister<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Record::INIT_SPACE,
+        // BAD SEEDS: "abc" + "def" == "ab" + "cdef"
+        seeds = [b"record", parent.as_bytes(), label.as_bytes()],
+        bump
+    )]
+    pub record: Account<'info, Record>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(parent: String, label: String)]
+pub struct VulnerablePay<'info> {
+    #[account(seeds = [b"record", parent.as_bytes(), label.as_bytes()], bump = record.bump)]
+    pub record: Account<'info, Record>,
+    /// CHECK: Must match the record's target
+    #[account(mut, address = record.target @ RegistryError::TargetMismatch)]
+    pub target: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(parent: String, label: String)]
+pub struct SecureRegister<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Record::INIT_SPACE,
+        // GOOD SEEDS: sha256(len || "abc" || len || "def")
+        seeds = [b"secure_record".as_ref(), &record_seed(&parent, &label)],
+        bump
+    )]
+    pub record: Account<'info, Record>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(parent: String, label: String)]
+pub struct SecurePay<'info> {
+    #[account(seeds = [b"secure_record".as_ref(), &record_seed(&parent, &label)], bump = record.bump)]
+    pub record: Account<'info, Record>,
+    /// CHECK: Must match the record's target
+    #[account(mut, address = record.target @ RegistryError::TargetMismatch)]
+    pub target: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Record {
+    pub owner: Pubkey,
+    /// Where payments to `label.parent` are sent.
+    pub target: Pubkey,
+    #[max_len(MAX_NAME_LEN)]
+    pub parent: String,
+    #[max_len(MAX_NAME_LEN)]
+    pub label: String,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum RegistryError {
+    #[msg("Name exceeds maximum length")]
+    NameTooLong,
+    #[msg("Target does not match record")]
+    TargetMismatch,
+}
@@ -0,0 +1,196 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { createHash } from "crypto";
+
+describe("17: Seed Concatenation Collision", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.SeedConcatCollision as Program<any>;
+
+    let victim: Keypair;
+    let attacker: Keypair;
+    let payer: Keypair;
+
+    // The victim wants "def" under "abc"; the attacker registers "cdef" under "ab".
+    const VICTIM = { parent: "abc", label: "def" };
+    const ATTACKER = { parent: "ab", label: "cdef" };
+    const PAYMENT = LAMPORTS_PER_SOL / 10;
+
+    function vulnerablePda(parent: string, label: string): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("record"), Buffer.from(parent), Buffer.from(label)],
+            program.programId
+        )[0];
+    }
+
+    function lengthPrefixed(part: string): Buffer {
+        const len = Buffer.alloc(4);
+        len.writeUInt32LE(Buffer.byteLength(part));
+        return Buffer.concat([len, Buffer.from(part)]);
+    }
+
+    function securePda(parent: string, label: string): PublicKey {
+        const seed = createHash("sha256")
+            .update(lengthPrefixed(parent))
+            .update(lengthPrefixed(label))
+            .digest();
+        return PublicKey.findProgramAddressSync([Buffer.from("secure_record"), seed], program.programId)[0];
+    }
+
+    before(async () => {
+        victim = Keypair.generate();
+        attacker = Keypair.generate();
+        payer = Keypair.generate();
+
+        for (const kp of [victim, attacker, payer]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Exploit: adjacent string seeds collide", () => {
+        it("(\"abc\", \"def\") and (\"ab\", \"cdef\") derive the same address", () => {
+            const a = vulnerablePda(VICTIM.parent, VICTIM.label);
+            const b = vulnerablePda(ATTACKER.parent, ATTACKER.label);
+
+            console.log(`  abc|def  -> ${a.toString()}`);
+            console.log(`  ab|cdef  -> ${b.toString()}`);
+            expect(a.toString()).to.equal(b.toString());
+        });
+
+        it("attacker registers the colliding name first", async () => {
+            await program.methods
+                .vulnerableRegister(ATTACKER.parent, ATTACKER.label, attacker.publicKey)
+                .accounts({
+                    record: vulnerablePda(ATTACKER.parent, ATTACKER.label),
+                    owner: attacker.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([attacker])
+                .rpc();
+        });
+
+        it("victim can no longer register their own name", async () => {
+            try {
+                await program.methods
+                    .vulnerableRegister(VICTIM.parent, VICTIM.label, victim.publicKey)
+                    .accounts({
+                        record: vulnerablePda(VICTIM.parent, VICTIM.label),
+                        owner: victim.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([victim])
+                    .rpc();
+
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("already in use");
+            }
+        });
+
+        it("payments to \"abc\"/\"def\" resolve to the attacker", async () => {
+            const before = await provider.connection.getBalance(attacker.publicKey);
+
+            await program.methods
+                .vulnerablePay(VICTIM.parent, VICTIM.label, new anchor.BN(PAYMENT))
+                .accounts({
+                    record: vulnerablePda(VICTIM.parent, VICTIM.label),
+                    target: attacker.publicKey,
+                    payer: payer.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([payer])
+                .rpc();
+
+            const gained = (await provider.connection.getBalance(attacker.publicKey)) - before;
+            console.log(`  Attacker received ${gained / LAMPORTS_PER_SOL} SOL meant for abc/def`);
+            expect(gained).to.equal(PAYMENT);
+        });
+    });
+
+    describe("Secure: length-prefixed hashed seed", () => {
+        it("the two splits derive different addresses", () => {
+            expect(securePda(VICTIM.parent, VICTIM.label).toString()).to.not.equal(
+                securePda(ATTACKER.parent, ATTACKER.label).toString()
+            );
+        });
+
+        it("attacker's registration does not block the victim", async () => {
+            await program.methods
+                .secureRegister(ATTACKER.parent, ATTACKER.label, attacker.publicKey)
+                .accounts({
+                    record: securePda(ATTACKER.parent, ATTACKER.label),
+                    owner: attacker.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([attacker])
+                .rpc();
+
+            await program.methods
+                .secureRegister(VICTIM.parent, VICTIM.label, victim.publicKey)
+                .accounts({
+                    record: securePda(VICTIM.parent, VICTIM.label),
+                    owner: victim.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([victim])
+                .rpc();
+
+            const record = await program.account.record.fetch(securePda(VICTIM.parent, VICTIM.label));
+            expect(record.owner.toString()).to.equal(victim.publicKey.toString());
+        });
+
+        it("payments to \"abc\"/\"def\" reach the victim and cannot be redirected", async () => {
+            try {
+                await program.methods
+                    .securePay(VICTIM.parent, VICTIM.label, new anchor.BN(PAYMENT))
+                    .accounts({
+                        record: securePda(VICTIM.parent, VICTIM.label),
+                        target: attacker.publicKey,
+                        payer: payer.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([payer])
+                    .rpc();
+
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("TargetMismatch");
+            }
+
+            const before = await provider.connection.getBalance(victim.publicKey);
+            await program.methods
+                .securePay(VICTIM.parent, VICTIM.label, new anchor.BN(PAYMENT))
+                .accounts({
+                    record: securePda(VICTIM.parent, VICTIM.label),
+                    target: victim.publicKey,
+                    payer: payer.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([payer])
+                .rpc();
+
+            expect((await provider.connection.getBalance(victim.publicKey)) - before).to.equal(PAYMENT);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Seed Concatenation Collision ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  seeds = [b\"record\", parent.as_bytes(), label.as_bytes()]");
+            console.log("  Risk: the runtime concatenates seeds; \"abc\"+\"def\" == \"ab\"+\"cdef\"\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  seeds = [b\"secure_record\", &hashed_seed(&[parent, label])]");
+            console.log("  Protection: length prefixes fix the boundaries; hash fixes the size\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Separate seeds are not separate fields. Encode the boundaries yourself.");
+        });
+    });
+});
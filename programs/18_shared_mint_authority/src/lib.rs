@@ -0,0 +1,188 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
+
+declare_id!("CYNPS6istk3o5F5cfDTu4Ws4NjaERKLf5UhxjKYspivF");
+
+/// Points awarded per claim.
+pub const POINTS_PER_CLAIM: u64 = 100;
+
+#[program]
+pub mod shared_mint_authority {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.points_mint = ctx.accounts.points_mint.key();
+        config.governance_mint = ctx.accounts.governance_mint.key();
+        config.bump = ctx.bumps.config;
+        Ok(())
+    }
+
+    // VULNERABLE: One Authority, Unpinned Mint
+    // 1. `[b"mint_authority"]` is the mint authority of BOTH mints
+    // 2. The mint account is whatever the caller passes
+    // 3. Pass the governance mint and the "points" path mints governance tokens
+    pub fn vulnerable_claim_points(ctx: Context<VulnerableClaimPoints>) -> Result<()> {
+        let seeds: &[&[u8]] = &[b"mint_authority", &[ctx.bumps.mint_authority]];
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+        token::mint_to(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, &[seeds]),
+            POINTS_PER_CLAIM,
+        )
+    }
+
+    pub fn vulnerable_mint_governance(ctx: Context<VulnerableMintGovernance>, amount: u64) -> Result<()> {
+        let seeds: &[&[u8]] = &[b"mint_authority", &[ctx.bumps.mint_authority]];
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.governance_mint.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+        token::mint_to(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, &[seeds]),
+            amount,
+        )
+    }
+
+    // SECURE: One Authority Per Mint, Pairing Validated
+    // 1. Each mint's authority is `[b"mint_authority", mint]`
+    // 2. The points path pins `mint` to `config.points_mint`
+    // 3. Even without (2), it can only ever sign as the points authority
+    pub fn secure_claim_points(ctx: Context<SecureClaimPoints>) -> Result<()> {
+        let points_mint = ctx.accounts.config.points_mint;
+        let seeds: &[&[u8]] = &[b"mint_authority", points_mint.as_ref(), &[ctx.bumps.mint_authority]];
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.points_mint.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+        token::mint_to(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, &[seeds]),
+            POINTS_PER_CLAIM,
+        )
+    }
+
+    pub fn secure_mint_governance(ctx: Context<SecureMintGovernance>, amount: u64) -> Result<()> {
+        let governance_mint = ctx.accounts.config.governance_mint;
+        let seeds: &[&[u8]] = &[b"mint_authority", governance_mint.as_ref(), &[ctx.bumps.mint_authority]];
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.governance_mint.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+        token::mint_to(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, &[seeds]),
+            amount,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [b"config", points_mint.key().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    pub points_mint: Account<'info, Mint>,
+    pub governance_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableClaimPoints<'info> {
+    #[account(seeds = [b"config", config.points_mint.as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    // VULNERABLE: No `address = config.points_mint`
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+    #[account(mut, token::mint = mint, token::authority = user)]
+    pub destination: Account<'info, TokenAccount>,
+    /// CHECK: PDA signer shared by every mint the program controls
+    #[account(seeds = [b"mint_authority"], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableMintGovernance<'info> {
+    #[account(
+        seeds = [b"config", config.points_mint.as_ref()],
+        bump = config.bump,
+        has_one = admin @ MintError::Unauthorized,
+        has_one = governance_mint @ MintError::WrongMint
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub governance_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = governance_mint)]
+    pub destination: Account<'info, TokenAccount>,
+    /// CHECK: PDA signer shared by every mint the program controls
+    #[account(seeds = [b"mint_authority"], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SecureClaimPoints<'info> {
+    #[account(seeds = [b"config", config.points_mint.as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, address = config.points_mint @ MintError::WrongMint)]
+    pub points_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = points_mint, token::authority = user)]
+    pub destination: Account<'info, TokenAccount>,
+    /// CHECK: PDA signer for the points mint only
+    #[account(seeds = [b"mint_authority", config.points_mint.as_ref()], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SecureMintGovernance<'info> {
+    #[account(
+        seeds = [b"config", config.points_mint.as_ref()],
+        bump = config.bump,
+        has_one = admin @ MintError::Unauthorized,
+        has_one = governance_mint @ MintError::WrongMint
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub governance_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = governance_mint)]
+    pub destination: Account<'info, TokenAccount>,
+    /// CHECK: PDA signer for the governance mint only
+    #[account(seeds = [b"mint_authority", config.governance_mint.as_ref()], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    pub points_mint: Pubkey,
+    pub governance_mint: Pubkey,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum MintError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Mint does not match configuration")]
+    WrongMint,
+}
@@ -0,0 +1,232 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, createMint, createAccount, getAccount } from "@solana/spl-token";
+import { expect } from "chai";
+
+describe("18: Shared Mint Authority", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.SharedMintAuthority as Program<any>;
+
+    let admin: Keypair;
+    let attacker: Keypair;
+
+    const POINTS_PER_CLAIM = 100;
+
+    interface Setup {
+        config: PublicKey;
+        pointsMint: PublicKey;
+        governanceMint: PublicKey;
+        attackerPoints: PublicKey;
+        attackerGovernance: PublicKey;
+    }
+
+    let vulnerable: Setup;
+    let secure: Setup;
+
+    function pda(seeds: Buffer[]): PublicKey {
+        return PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+    }
+
+    const sharedAuthority = () => pda([Buffer.from("mint_authority")]);
+    const perMintAuthority = (mint: PublicKey) => pda([Buffer.from("mint_authority"), mint.toBuffer()]);
+
+    async function setup(
+        pointsAuthority: (mint: PublicKey) => PublicKey,
+        governanceAuthority: (mint: PublicKey) => PublicKey
+    ): Promise<Setup> {
+        // Mint addresses are needed to derive per-mint authorities, so create
+        // the keypairs first and pass them to createMint.
+        const pointsKp = Keypair.generate();
+        const governanceKp = Keypair.generate();
+
+        const pointsMint = await createMint(
+            provider.connection, admin, pointsAuthority(pointsKp.publicKey), null, 0, pointsKp
+        );
+        const governanceMint = await createMint(
+            provider.connection, admin, governanceAuthority(governanceKp.publicKey), null, 0, governanceKp
+        );
+
+        const config = pda([Buffer.from("config"), pointsMint.toBuffer()]);
+        await program.methods
+            .initialize()
+            .accounts({
+                config,
+                pointsMint,
+                governanceMint,
+                admin: admin.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([admin])
+            .rpc();
+
+        return {
+            config,
+            pointsMint,
+            governanceMint,
+            attackerPoints: await createAccount(provider.connection, attacker, pointsMint, attacker.publicKey),
+            attackerGovernance: await createAccount(provider.connection, attacker, governanceMint, attacker.publicKey),
+        };
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        attacker = Keypair.generate();
+
+        for (const kp of [admin, attacker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+
+        vulnerable = await setup(sharedAuthority, sharedAuthority);
+        secure = await setup(perMintAuthority, perMintAuthority);
+    });
+
+    describe("Exploit: points path mints governance tokens", () => {
+        it("claiming points works as intended", async () => {
+            await program.methods
+                .vulnerableClaimPoints()
+                .accounts({
+                    config: vulnerable.config,
+                    mint: vulnerable.pointsMint,
+                    destination: vulnerable.attackerPoints,
+                    mintAuthority: sharedAuthority(),
+                    user: attacker.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([attacker])
+                .rpc();
+
+            const points = await getAccount(provider.connection, vulnerable.attackerPoints);
+            expect(Number(points.amount)).to.equal(POINTS_PER_CLAIM);
+        });
+
+        it("passing the governance mint mints governance tokens", async () => {
+            const CLAIMS = 5;
+            for (let i = 0; i < CLAIMS; i++) {
+                await program.methods
+                    .vulnerableClaimPoints()
+                    .accounts({
+                        config: vulnerable.config,
+                        mint: vulnerable.governanceMint,
+                        destination: vulnerable.attackerGovernance,
+                        mintAuthority: sharedAuthority(),
+                        user: attacker.publicKey,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .signers([attacker])
+                    .rpc();
+            }
+
+            const governance = await getAccount(provider.connection, vulnerable.attackerGovernance);
+            console.log(`  Attacker minted ${governance.amount} governance tokens via the points path`);
+            expect(Number(governance.amount)).to.equal(CLAIMS * POINTS_PER_CLAIM);
+        });
+    });
+
+    describe("Secure: per-mint authority, pinned mint", () => {
+        it("points path still works for points", async () => {
+            await program.methods
+                .secureClaimPoints()
+                .accounts({
+                    config: secure.config,
+                    pointsMint: secure.pointsMint,
+                    destination: secure.attackerPoints,
+                    mintAuthority: perMintAuthority(secure.pointsMint),
+                    user: attacker.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([attacker])
+                .rpc();
+
+            const points = await getAccount(provider.connection, secure.attackerPoints);
+            expect(Number(points.amount)).to.equal(POINTS_PER_CLAIM);
+        });
+
+        it("rejects the governance mint on the points path", async () => {
+            try {
+                await program.methods
+                    .secureClaimPoints()
+                    .accounts({
+                        config: secure.config,
+                        pointsMint: secure.governanceMint,
+                        destination: secure.attackerGovernance,
+                        mintAuthority: perMintAuthority(secure.pointsMint),
+                        user: attacker.publicKey,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .signers([attacker])
+                    .rpc();
+
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("WrongMint");
+            }
+        });
+
+        it("cannot borrow the governance authority either", async () => {
+            try {
+                await program.methods
+                    .secureClaimPoints()
+                    .accounts({
+                        config: secure.config,
+                        pointsMint: secure.pointsMint,
+                        destination: secure.attackerPoints,
+                        mintAuthority: perMintAuthority(secure.governanceMint),
+                        user: attacker.publicKey,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .signers([attacker])
+                    .rpc();
+
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("ConstraintSeeds");
+            }
+
+            const governance = await getAccount(provider.connection, secure.attackerGovernance);
+            expect(Number(governance.amount)).to.equal(0);
+        });
+
+        it("admin mints governance through its own authority", async () => {
+            const adminGovernance = await createAccount(
+                provider.connection, admin, secure.governanceMint, admin.publicKey
+            );
+
+            await program.methods
+                .secureMintGovernance(new anchor.BN(1_000))
+                .accounts({
+                    config: secure.config,
+                    governanceMint: secure.governanceMint,
+                    destination: adminGovernance,
+                    mintAuthority: perMintAuthority(secure.governanceMint),
+                    admin: admin.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([admin])
+                .rpc();
+
+            const balance = await getAccount(provider.connection, adminGovernance);
+            expect(Number(balance.amount)).to.equal(1_000);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Shared Mint Authority ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  mint_authority = PDA([\"mint_authority\"]) for points AND governance");
+            console.log("  Risk: any path that can sign for one mint can sign for all of them\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  mint_authority = PDA([\"mint_authority\", mint]); mint = config.points_mint");
+            console.log("  Protection: each signer controls one mint; the pairing is validated\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  A PDA signer's power is everything it is the authority of. Keep it to one thing.");
+        });
+    });
+});
@@ -0,0 +1,215 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Ea3vsdXrLYFjtYCgg3ojnJ4tCHyKvNEbvCKNxvVcyfJn");
+
+/// Balance credited to every new ledger.
+pub const INITIAL_BALANCE: u64 = 1_000;
+
+/// Byte offset of `PackedLedger::frozen`: discriminator, authority, owner,
+/// bump (padded to 8), balance.
+///
+/// VULNERABLE: `PackedLedger` is `repr(packed)`, so `bump` is NOT padded and
+/// `frozen` actually lives at offset 81. Offset 88 is inside `reserved`.
+pub const PACKED_FROZEN_OFFSET: usize = 8 + 32 + 32 + 8 + 8;
+
+#[program]
+pub mod zero_copy_aliasing {
+    use super::*;
+
+    pub fn init_packed_ledger(ctx: Context<InitPackedLedger>, owner: Pubkey) -> Result<()> {
+        let mut ledger = ctx.accounts.ledger.load_init()?;
+        ledger.authority = ctx.accounts.authority.key();
+        ledger.owner = owner;
+        ledger.bump = ctx.bumps.ledger;
+        ledger.balance = INITIAL_BALANCE;
+        Ok(())
+    }
+
+    pub fn freeze_packed_ledger(ctx: Context<FreezePackedLedger>) -> Result<()> {
+        let mut ledger = ctx.accounts.ledger.load_mut()?;
+        // Copy out of the packed struct; references to its fields are unaligned.
+        let authority = ledger.authority;
+        require_keys_eq!(authority, ctx.accounts.authority.key(), LedgerError::Unauthorized);
+        ledger.frozen = 1;
+        Ok(())
+    }
+
+    // VULNERABLE: Aliased Loads + Hand-Computed Packed Offsets
+    // 1. The frozen check reads raw bytes at an offset that assumes padding
+    //    `repr(packed)` does not have — it reads a zero from `reserved`
+    // 2. Both balances are copied out before either is written
+    // 3. If `from == to`, the second write clobbers the first: the debit is
+    //    lost and the credit is kept, so a self-transfer mints `amount`
+    pub fn vulnerable_transfer(ctx: Context<VulnerableTransfer>, amount: u64) -> Result<()> {
+        {
+            let from_info = ctx.accounts.from.to_account_info();
+            let data = from_info.try_borrow_data()?;
+            require!(data[PACKED_FROZEN_OFFSET] == 0, LedgerError::LedgerFrozen);
+        }
+
+        let from_balance = {
+            let from = ctx.accounts.from.load()?;
+            let owner = from.owner;
+            require_keys_eq!(owner, ctx.accounts.owner.key(), LedgerError::Unauthorized);
+            from.balance
+        };
+        let to_balance = ctx.accounts.to.load()?.balance;
+
+        require!(from_balance >= amount, LedgerError::InsufficientBalance);
+
+        ctx.accounts.from.load_mut()?.balance = from_balance - amount;
+        ctx.accounts.to.load_mut()?.balance = to_balance
+            .checked_add(amount)
+            .ok_or(LedgerError::ArithmeticError)?;
+
+        Ok(())
+    }
+
+    pub fn init_ledger(ctx: Context<InitLedger>, owner: Pubkey) -> Result<()> {
+        let mut ledger = ctx.accounts.ledger.load_init()?;
+        ledger.authority = ctx.accounts.authority.key();
+        ledger.owner = owner;
+        ledger.bump = ctx.bumps.ledger;
+        ledger.balance = INITIAL_BALANCE;
+        Ok(())
+    }
+
+    pub fn freeze_ledger(ctx: Context<FreezeLedger>) -> Result<()> {
+        let mut ledger = ctx.accounts.ledger.load_mut()?;
+        require_keys_eq!(ledger.authority, ctx.accounts.authority.key(), LedgerError::Unauthorized);
+        ledger.frozen = 1;
+        Ok(())
+    }
+
+    // SECURE: Distinct Accounts, Aligned Layout, Field Access Only
+    // 1. `from` and `to` must be different accounts
+    // 2. Both are borrowed mutably for the whole update; writes go through
+    //    the live borrows, never through stale copies
+    // 3. `Ledger` is `repr(C)` with explicit padding, checked at compile time,
+    //    and fields are only ever read by name
+    pub fn secure_transfer(ctx: Context<SecureTransfer>, amount: u64) -> Result<()> {
+        let mut from = ctx.accounts.from.load_mut()?;
+        let mut to = ctx.accounts.to.load_mut()?;
+
+        require_keys_eq!(from.owner, ctx.accounts.owner.key(), LedgerError::Unauthorized);
+        require!(from.frozen == 0 && to.frozen == 0, LedgerError::LedgerFrozen);
+
+        from.balance = from
+            .balance
+            .checked_sub(amount)
+            .ok_or(LedgerError::InsufficientBalance)?;
+        to.balance = to
+            .balance
+            .checked_add(amount)
+            .ok_or(LedgerError::ArithmeticError)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct InitPackedLedger<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<PackedLedger>(),
+        seeds = [b"packed_ledger", owner.as_ref()],
+        bump
+    )]
+    pub ledger: AccountLoader<'info, PackedLedger>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FreezePackedLedger<'info> {
+    #[account(mut)]
+    pub ledger: AccountLoader<'info, PackedLedger>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableTransfer<'info> {
+    // VULNERABLE: Nothing stops `to` from being the same account as `from`
+    #[account(mut)]
+    pub from: AccountLoader<'info, PackedLedger>,
+    #[account(mut)]
+    pub to: AccountLoader<'info, PackedLedger>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct InitLedger<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<Ledger>(),
+        seeds = [b"ledger", owner.as_ref()],
+        bump
+    )]
+    pub ledger: AccountLoader<'info, Ledger>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FreezeLedger<'info> {
+    #[account(mut)]
+    pub ledger: AccountLoader<'info, Ledger>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SecureTransfer<'info> {
+    #[account(mut)]
+    pub from: AccountLoader<'info, Ledger>,
+    #[account(mut, constraint = to.key() != from.key() @ LedgerError::SameAccount)]
+    pub to: AccountLoader<'info, Ledger>,
+    pub owner: Signer<'info>,
+}
+
+/// `repr(packed)`: no padding anywhere, so `balance` sits at an odd offset and
+/// every field after it is shifted relative to a naturally aligned layout.
+#[account(zero_copy(unsafe))]
+#[repr(C, packed)]
+pub struct PackedLedger {
+    pub authority: Pubkey,
+    pub owner: Pubkey,
+    pub bump: u8,
+    pub balance: u64,
+    pub frozen: u8,
+    pub reserved: [u8; 15],
+}
+
+/// `repr(C)`: widest fields first, padding spelled out. `bytemuck::Pod`
+/// refuses to derive if any implicit padding remains.
+#[account(zero_copy)]
+pub struct Ledger {
+    pub authority: Pubkey,
+    pub owner: Pubkey,
+    pub balance: u64,
+    pub frozen: u8,
+    pub bump: u8,
+    pub padding: [u8; 6],
+}
+
+const _: () = assert!(std::mem::size_of::<Ledger>() == 80);
+const _: () = assert!(std::mem::align_of::<Ledger>() == 8);
+
+#[error_code]
+pub enum LedgerError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Ledger is frozen")]
+    LedgerFrozen,
+    #[msg("Insufficient balance")]
+    InsufficientBalance,
+    #[msg("Source and destination must be different accounts")]
+    SameAccount,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
@@ -0,0 +1,213 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+
+describe("19: Zero-Copy Aliasing", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ZeroCopyAliasing as Program<any>;
+
+    let issuer: Keypair;
+    let attacker: Keypair;
+    let accomplice: Keypair;
+
+    const INITIAL_BALANCE = 1_000;
+    // Where the vulnerable program looks for `frozen`, and where it really is.
+    const ASSUMED_FROZEN_OFFSET = 8 + 32 + 32 + 8 + 8;
+    const ACTUAL_FROZEN_OFFSET = 8 + 32 + 32 + 1 + 8;
+
+    function ledgerPda(prefix: string, owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from(prefix), owner.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    const packedLedger = (owner: PublicKey) => ledgerPda("packed_ledger", owner);
+    const ledger = (owner: PublicKey) => ledgerPda("ledger", owner);
+
+    before(async () => {
+        issuer = Keypair.generate();
+        attacker = Keypair.generate();
+        accomplice = Keypair.generate();
+
+        for (const kp of [issuer, attacker, accomplice]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Issuer creates packed and aligned ledgers", async () => {
+            for (const owner of [attacker.publicKey, accomplice.publicKey]) {
+                await program.methods
+                    .initPackedLedger(owner)
+                    .accounts({
+                        ledger: packedLedger(owner),
+                        authority: issuer.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([issuer])
+                    .rpc();
+
+                await program.methods
+                    .initLedger(owner)
+                    .accounts({
+                        ledger: ledger(owner),
+                        authority: issuer.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([issuer])
+                    .rpc();
+            }
+
+            const packed = await program.account.packedLedger.fetch(packedLedger(attacker.publicKey));
+            expect(packed.balance.toNumber()).to.equal(INITIAL_BALANCE);
+        });
+    });
+
+    describe("Exploit: aliased from/to mints balance", () => {
+        it("Self-transfer increases the balance instead of leaving it unchanged", async () => {
+            const account = packedLedger(attacker.publicKey);
+
+            for (let i = 0; i < 3; i++) {
+                await program.methods
+                    .vulnerableTransfer(new anchor.BN(INITIAL_BALANCE * 2 ** i))
+                    .accounts({ from: account, to: account, owner: attacker.publicKey })
+                    .signers([attacker])
+                    .rpc();
+            }
+
+            const after = await program.account.packedLedger.fetch(account);
+            console.log(`  Balance after 3 self-transfers: ${after.balance.toString()} (started at ${INITIAL_BALANCE})`);
+            expect(after.balance.toNumber()).to.equal(INITIAL_BALANCE * 8);
+        });
+    });
+
+    describe("Exploit: hand-computed offset misses the packed flag", () => {
+        it("Issuer freezes the attacker's packed ledger", async () => {
+            await program.methods
+                .freezePackedLedger()
+                .accounts({ ledger: packedLedger(attacker.publicKey), authority: issuer.publicKey })
+                .signers([issuer])
+                .rpc();
+
+            const info = await provider.connection.getAccountInfo(packedLedger(attacker.publicKey));
+            console.log(`  data[${ACTUAL_FROZEN_OFFSET}] (real frozen)    = ${info!.data[ACTUAL_FROZEN_OFFSET]}`);
+            console.log(`  data[${ASSUMED_FROZEN_OFFSET}] (checked offset) = ${info!.data[ASSUMED_FROZEN_OFFSET]}`);
+            expect(info!.data[ACTUAL_FROZEN_OFFSET]).to.equal(1);
+            expect(info!.data[ASSUMED_FROZEN_OFFSET]).to.equal(0);
+        });
+
+        it("Frozen ledger still sends funds", async () => {
+            await program.methods
+                .vulnerableTransfer(new anchor.BN(INITIAL_BALANCE))
+                .accounts({
+                    from: packedLedger(attacker.publicKey),
+                    to: packedLedger(accomplice.publicKey),
+                    owner: attacker.publicKey,
+                })
+                .signers([attacker])
+                .rpc();
+
+            const accomplicePacked = await program.account.packedLedger.fetch(packedLedger(accomplice.publicKey));
+            expect(accomplicePacked.balance.toNumber()).to.equal(INITIAL_BALANCE * 2);
+        });
+    });
+
+    describe("Secure: distinct accounts, aligned layout", () => {
+        it("Rejects from == to", async () => {
+            const account = ledger(attacker.publicKey);
+
+            try {
+                await program.methods
+                    .secureTransfer(new anchor.BN(INITIAL_BALANCE))
+                    .accounts({ from: account, to: account, owner: attacker.publicKey })
+                    .signers([attacker])
+                    .rpc();
+
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("SameAccount");
+            }
+
+            const after = await program.account.ledger.fetch(account);
+            expect(after.balance.toNumber()).to.equal(INITIAL_BALANCE);
+        });
+
+        it("Moves funds between distinct ledgers", async () => {
+            await program.methods
+                .secureTransfer(new anchor.BN(400))
+                .accounts({
+                    from: ledger(attacker.publicKey),
+                    to: ledger(accomplice.publicKey),
+                    owner: attacker.publicKey,
+                })
+                .signers([attacker])
+                .rpc();
+
+            const from = await program.account.ledger.fetch(ledger(attacker.publicKey));
+            const to = await program.account.ledger.fetch(ledger(accomplice.publicKey));
+            expect(from.balance.toNumber()).to.equal(INITIAL_BALANCE - 400);
+            expect(to.balance.toNumber()).to.equal(INITIAL_BALANCE + 400);
+        });
+
+        it("Honours the frozen flag", async () => {
+            await program.methods
+                .freezeLedger()
+                .accounts({ ledger: ledger(attacker.publicKey), authority: issuer.publicKey })
+                .signers([issuer])
+                .rpc();
+
+            try {
+                await program.methods
+                    .secureTransfer(new anchor.BN(1))
+                    .accounts({
+                        from: ledger(attacker.publicKey),
+                        to: ledger(accomplice.publicKey),
+                        owner: attacker.publicKey,
+                    })
+                    .signers([attacker])
+                    .rpc();
+
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("LedgerFrozen");
+            }
+        });
+
+        it("Only the issuer can freeze", async () => {
+            try {
+                await program.methods
+                    .freezeLedger()
+                    .accounts({ ledger: ledger(accomplice.publicKey), authority: attacker.publicKey })
+                    .signers([attacker])
+                    .rpc();
+
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("Unauthorized");
+            }
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Zero-Copy Aliasing ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  let a = from.load()?.balance; let b = to.load()?.balance;");
+            console.log("  from.load_mut()?.balance = a - x; to.load_mut()?.balance = b + x;");
+            console.log("  Risk: from == to keeps only the credit; raw offsets ignore repr(packed)\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  constraint = to.key() != from.key(); hold both load_mut() borrows");
+            console.log("  Protection: no stale copies; repr(C) with explicit padding, fields by name\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Zero-copy gives you raw memory. Aliasing and layout are now your problem.");
+        });
+    });
+});
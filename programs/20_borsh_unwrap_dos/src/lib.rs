@@ -0,0 +1,243 @@
+use anchor_lang::prelude::*;
+
+declare_id!("3wT9xUCgqorXxKMxCZ7n1pkMhsuBrd8SwsordMQton5R");
+
+/// Largest serialized `Action` a task may carry.
+pub const MAX_PAYLOAD_LEN: usize = 64;
+
+#[program]
+pub mod borsh_unwrap_dos {
+    use super::*;
+
+    pub fn init_queue(ctx: Context<InitQueue>, queue_id: u64) -> Result<()> {
+        let queue = &mut ctx.accounts.queue;
+        queue.queue_id = queue_id;
+        queue.head = 0;
+        queue.tail = 0;
+        queue.value = 0;
+        queue.processed = 0;
+        queue.failed = 0;
+        queue.bump = ctx.bumps.queue;
+        Ok(())
+    }
+
+    /// Anyone may enqueue a task. The payload is stored as raw bytes and only
+    /// decoded into an `Action` when the crank executes it.
+    pub fn submit_task(ctx: Context<SubmitTask>, payload: Vec<u8>) -> Result<()> {
+        require!(payload.len() <= MAX_PAYLOAD_LEN, QueueError::PayloadTooLarge);
+
+        let queue = &mut ctx.accounts.queue;
+        let task = &mut ctx.accounts.task;
+        task.queue = queue.key();
+        task.id = queue.tail;
+        task.submitter = ctx.accounts.submitter.key();
+        task.status = TaskStatus::Pending;
+        task.payload = payload;
+        task.bump = ctx.bumps.task;
+
+        queue.tail = queue.tail.checked_add(1).ok_or(QueueError::ArithmeticError)?;
+        Ok(())
+    }
+
+    // VULNERABLE: Panicking Decode Inside a Batch
+    // 1. Tasks must be processed in FIFO order, starting at `queue.head`
+    // 2. `try_from_slice(..).unwrap()` panics on a malformed payload
+    // 3. A panic aborts the whole transaction, so `head` never advances past
+    //    the poisoned task and every future crank fails the same way
+    pub fn vulnerable_crank<'info>(ctx: Context<'_, '_, 'info, 'info, Crank<'info>>) -> Result<()> {
+        let queue_key = ctx.accounts.queue.key();
+        let queue = &mut ctx.accounts.queue;
+
+        for info in ctx.remaining_accounts.iter() {
+            let mut task = Account::<Task>::try_from(info)?;
+            require_keys_eq!(task.queue, queue_key, QueueError::WrongQueue);
+            require!(task.id == queue.head, QueueError::OutOfOrder);
+
+            let action = Action::try_from_slice(&task.payload).unwrap();
+            queue.value = action.apply(queue.value).ok_or(QueueError::ArithmeticError)?;
+
+            task.status = TaskStatus::Done;
+            task.exit(&crate::ID)?;
+            queue.head += 1;
+            queue.processed += 1;
+        }
+
+        Ok(())
+    }
+
+    // SECURE: Skip-and-Report
+    // 1. Same FIFO ordering
+    // 2. Decoding and execution are fallible; a bad task is marked `Failed`
+    //    and reported with an event instead of aborting the batch
+    // 3. `head` always advances, so one poisoned task costs one slot in the
+    //    queue, not the queue
+    pub fn secure_crank<'info>(ctx: Context<'_, '_, 'info, 'info, Crank<'info>>) -> Result<()> {
+        let queue_key = ctx.accounts.queue.key();
+        let queue = &mut ctx.accounts.queue;
+
+        for info in ctx.remaining_accounts.iter() {
+            // The task itself was written by this program; ordering errors are
+            // the keeper's mistake and still fail loudly.
+            let mut task = Account::<Task>::try_from(info)?;
+            require_keys_eq!(task.queue, queue_key, QueueError::WrongQueue);
+            require!(task.id == queue.head, QueueError::OutOfOrder);
+
+            let outcome = match Action::try_from_slice(&task.payload) {
+                Ok(action) => action.apply(queue.value).ok_or(SkipReason::ExecutionFailed),
+                Err(_) => Err(SkipReason::MalformedPayload),
+            };
+
+            match outcome {
+                Ok(value) => {
+                    queue.value = value;
+                    task.status = TaskStatus::Done;
+                    queue.processed += 1;
+                }
+                Err(reason) => {
+                    task.status = TaskStatus::Failed;
+                    queue.failed += 1;
+                    emit!(TaskSkipped {
+                        queue: queue_key,
+                        task: task.key(),
+                        id: task.id,
+                        reason,
+                    });
+                }
+            }
+
+            task.exit(&crate::ID)?;
+            queue.head += 1;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(queue_id: u64)]
+pub struct InitQueue<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Queue::INIT_SPACE,
+        seeds = [b"queue", queue_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub queue: Account<'info, Queue>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitTask<'info> {
+    #[account(
+        mut,
+        seeds = [b"queue", queue.queue_id.to_le_bytes().as_ref()],
+        bump = queue.bump
+    )]
+    pub queue: Account<'info, Queue>,
+    #[account(
+        init,
+        payer = submitter,
+        space = 8 + Task::INIT_SPACE,
+        seeds = [b"task", queue.key().as_ref(), queue.tail.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub task: Account<'info, Task>,
+    #[account(mut)]
+    pub submitter: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Tasks are passed as writable `remaining_accounts`, in queue order.
+#[derive(Accounts)]
+pub struct Crank<'info> {
+    #[account(
+        mut,
+        seeds = [b"queue", queue.queue_id.to_le_bytes().as_ref()],
+        bump = queue.bump
+    )]
+    pub queue: Account<'info, Queue>,
+    pub keeper: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Queue {
+    pub queue_id: u64,
+    /// Next task id to execute.
+    pub head: u64,
+    /// Next task id to assign.
+    pub tail: u64,
+    /// State the actions operate on.
+    pub value: u64,
+    pub processed: u64,
+    pub failed: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Task {
+    pub queue: Pubkey,
+    pub id: u64,
+    pub submitter: Pubkey,
+    pub status: TaskStatus,
+    /// Borsh-encoded `Action`, as supplied by the submitter.
+    #[max_len(MAX_PAYLOAD_LEN)]
+    pub payload: Vec<u8>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum TaskStatus {
+    Pending,
+    Done,
+    Failed,
+}
+
+/// What a task asks the crank to do. Never stored typed; decoded from
+/// `Task::payload` at execution time.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub enum Action {
+    Add { amount: u64 },
+    Sub { amount: u64 },
+    Reset,
+}
+
+impl Action {
+    pub fn apply(self, value: u64) -> Option<u64> {
+        match self {
+            Action::Add { amount } => value.checked_add(amount),
+            Action::Sub { amount } => value.checked_sub(amount),
+            Action::Reset => Some(0),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    MalformedPayload,
+    ExecutionFailed,
+}
+
+#[event]
+pub struct TaskSkipped {
+    pub queue: Pubkey,
+    pub task: Pubkey,
+    pub id: u64,
+    pub reason: SkipReason,
+}
+
+#[error_code]
+pub enum QueueError {
+    #[msg("Payload exceeds maximum length")]
+    PayloadTooLarge,
+    #[msg("Task belongs to a different queue")]
+    WrongQueue,
+    #[msg("Task is not next in the queue")]
+    OutOfOrder,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
@@ -0,0 +1,231 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { sleep } from "../../../tests/utils";
+
+describe("20: Borsh Unwrap DoS", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.BorshUnwrapDos as Program<any>;
+
+    let user: Keypair;
+    let attacker: Keypair;
+    let keeper: Keypair;
+
+    const VULNERABLE_QUEUE_ID = 1;
+    const SECURE_QUEUE_ID = 2;
+
+    // Borsh encodings of `Action`: a one-byte variant tag, then the fields.
+    function add(amount: number): Buffer {
+        const buf = Buffer.alloc(9);
+        buf.writeUInt8(0, 0);
+        buf.writeBigUInt64LE(BigInt(amount), 1);
+        return buf;
+    }
+
+    function sub(amount: number): Buffer {
+        const buf = add(amount);
+        buf.writeUInt8(1, 0);
+        return buf;
+    }
+
+    // Variant 9 does not exist; `Action::try_from_slice` returns an error.
+    const POISON = Buffer.from([9]);
+
+    function queuePda(queueId: number): PublicKey {
+        const id = Buffer.alloc(8);
+        id.writeBigUInt64LE(BigInt(queueId));
+        return PublicKey.findProgramAddressSync([Buffer.from("queue"), id], program.programId)[0];
+    }
+
+    function taskPda(queue: PublicKey, taskId: number): PublicKey {
+        const id = Buffer.alloc(8);
+        id.writeBigUInt64LE(BigInt(taskId));
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("task"), queue.toBuffer(), id],
+            program.programId
+        )[0];
+    }
+
+    async function initQueue(queueId: number) {
+        await program.methods
+            .initQueue(new anchor.BN(queueId))
+            .accounts({
+                queue: queuePda(queueId),
+                payer: keeper.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([keeper])
+            .rpc();
+    }
+
+    async function submit(queueId: number, submitter: Keypair, payload: Buffer) {
+        const queue = queuePda(queueId);
+        const { tail } = await program.account.queue.fetch(queue);
+
+        await program.methods
+            .submitTask(payload)
+            .accounts({
+                queue,
+                task: taskPda(queue, tail.toNumber()),
+                submitter: submitter.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([submitter])
+            .rpc();
+    }
+
+    function crank(method: "vulnerableCrank" | "secureCrank", queueId: number, taskIds: number[]) {
+        const queue = queuePda(queueId);
+        return program.methods[method]()
+            .accounts({ queue, keeper: keeper.publicKey })
+            .remainingAccounts(
+                taskIds.map((id) => ({ pubkey: taskPda(queue, id), isWritable: true, isSigner: false }))
+            )
+            .signers([keeper])
+            .rpc();
+    }
+
+    function failureText(error: any): string {
+        return `${error.message}\n${(error.logs ?? []).join("\n")}`;
+    }
+
+    before(async () => {
+        user = Keypair.generate();
+        attacker = Keypair.generate();
+        keeper = Keypair.generate();
+
+        for (const kp of [user, attacker, keeper]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates both queues and enqueues add(5), POISON, add(7)", async () => {
+            for (const queueId of [VULNERABLE_QUEUE_ID, SECURE_QUEUE_ID]) {
+                await initQueue(queueId);
+                await submit(queueId, user, add(5));
+                await submit(queueId, attacker, POISON);
+                await submit(queueId, user, add(7));
+            }
+
+            const queue = await program.account.queue.fetch(queuePda(VULNERABLE_QUEUE_ID));
+            expect(queue.tail.toNumber()).to.equal(3);
+        });
+    });
+
+    describe("Exploit: one malformed payload halts the crank", () => {
+        it("The full batch panics", async () => {
+            try {
+                await crank("vulnerableCrank", VULNERABLE_QUEUE_ID, [0, 1, 2]);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                console.log("  Crank aborted on the poisoned task");
+                expect(failureText(error)).to.match(/panicked|Program failed to complete/);
+            }
+
+            const queue = await program.account.queue.fetch(queuePda(VULNERABLE_QUEUE_ID));
+            expect(queue.head.toNumber()).to.equal(0);
+            expect(queue.value.toNumber()).to.equal(0);
+        });
+
+        it("Tasks before the poison can be cranked on their own", async () => {
+            await crank("vulnerableCrank", VULNERABLE_QUEUE_ID, [0]);
+
+            const queue = await program.account.queue.fetch(queuePda(VULNERABLE_QUEUE_ID));
+            expect(queue.head.toNumber()).to.equal(1);
+            expect(queue.value.toNumber()).to.equal(5);
+        });
+
+        it("Nothing after it can ever run", async () => {
+            try {
+                await crank("vulnerableCrank", VULNERABLE_QUEUE_ID, [1, 2]);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(failureText(error)).to.match(/panicked|Program failed to complete/);
+            }
+
+            try {
+                await crank("vulnerableCrank", VULNERABLE_QUEUE_ID, [2]);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("OutOfOrder");
+            }
+
+            const queue = await program.account.queue.fetch(queuePda(VULNERABLE_QUEUE_ID));
+            console.log(`  Queue stuck at head ${queue.head.toNumber()} of ${queue.tail.toNumber()}`);
+            expect(queue.head.toNumber()).to.equal(1);
+        });
+    });
+
+    describe("Secure: skip-and-report", () => {
+        it("Processes the batch and reports the poisoned task", async () => {
+            const skipped: any[] = [];
+            const listener = program.addEventListener("TaskSkipped", (event: any) => {
+                skipped.push(event);
+            });
+
+            await crank("secureCrank", SECURE_QUEUE_ID, [0, 1, 2]);
+            await sleep(1);
+            await program.removeEventListener(listener);
+
+            const queue = await program.account.queue.fetch(queuePda(SECURE_QUEUE_ID));
+            expect(queue.head.toNumber()).to.equal(3);
+            expect(queue.value.toNumber()).to.equal(12);
+            expect(queue.processed.toNumber()).to.equal(2);
+            expect(queue.failed.toNumber()).to.equal(1);
+
+            const poisoned = await program.account.task.fetch(taskPda(queuePda(SECURE_QUEUE_ID), 1));
+            expect(poisoned.status).to.have.property("failed");
+
+            expect(skipped).to.have.length(1);
+            expect(skipped[0].id.toNumber()).to.equal(1);
+            expect(skipped[0].reason).to.have.property("malformedPayload");
+        });
+
+        it("Execution failures are skipped too", async () => {
+            // 12 - 1000 underflows; with `?` this would halt the crank just like the panic.
+            await submit(SECURE_QUEUE_ID, attacker, sub(1_000));
+            await submit(SECURE_QUEUE_ID, user, sub(2));
+
+            await crank("secureCrank", SECURE_QUEUE_ID, [3, 4]);
+
+            const queue = await program.account.queue.fetch(queuePda(SECURE_QUEUE_ID));
+            expect(queue.head.toNumber()).to.equal(5);
+            expect(queue.value.toNumber()).to.equal(10);
+            expect(queue.failed.toNumber()).to.equal(2);
+        });
+
+        it("Ordering is still enforced", async () => {
+            await submit(SECURE_QUEUE_ID, user, add(1));
+            await submit(SECURE_QUEUE_ID, user, add(1));
+
+            try {
+                await crank("secureCrank", SECURE_QUEUE_ID, [6]);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("OutOfOrder");
+            }
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Borsh Unwrap DoS ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  let action = Action::try_from_slice(&task.payload).unwrap();");
+            console.log("  Risk: one malformed payload panics every batch that includes it\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  match Action::try_from_slice(..) { Ok(a) => run(a), Err(_) => mark Failed + emit }");
+            console.log("  Protection: bad entries cost one slot; the batch and the queue keep moving\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  In batch code, per-item errors must be per-item. A panic is never per-item.");
+        });
+    });
+});
@@ -0,0 +1,311 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use security_common::time::{ClockExt, UnixTs};
+
+declare_id!("HgiLCGWsfy6T2MzLvQVFeshXGeeHJBj4AHESa3utcmVS");
+
+/// Length of one lock period. Short so the tests can wait one out.
+pub const PERIOD_SECS: i64 = 2;
+/// Early-exit penalty per second of lock remaining, in lamports.
+pub const PENALTY_PER_SEC: u64 = 1_000_000;
+
+#[program]
+pub mod cast_truncation {
+    use super::*;
+
+    pub fn initialize_vault(ctx: Context<InitializeVault>, vault_id: u64) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.vault_id = vault_id;
+        vault.penalties = 0;
+        vault.bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    pub fn open_position(ctx: Context<OpenPosition>) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.vault = ctx.accounts.vault.key();
+        position.owner = ctx.accounts.owner.key();
+        position.balance = 0;
+        position.boost = 0;
+        position.lock_periods = 0;
+        position.unlock_ts = 0;
+        position.bump = ctx.bumps.position;
+        Ok(())
+    }
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let position = &mut ctx.accounts.position;
+        position.balance = position.balance.checked_add(amount).ok_or(CastError::ArithmeticError)?;
+        Ok(())
+    }
+
+    // VULNERABLE: u64 -> u32 With `as`
+    // 1. The ledger helper takes `u32` (withdrawals are capped at u32::MAX)
+    // 2. `amount as u32` keeps only the low 32 bits
+    // 3. 2^32 + 1_000_000 debits 1_000_000 but pays out the full u64
+    pub fn vulnerable_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let position = &mut ctx.accounts.position;
+        require!(now >= position.unlock_ts, CastError::StillLocked);
+
+        position.balance = debit(position.balance, amount as u32)?;
+        move_lamports(&ctx.accounts.vault.to_account_info(), &ctx.accounts.owner.to_account_info(), amount)?;
+
+        msg!("Vulnerable withdraw: paid {}", amount);
+        Ok(())
+    }
+
+    // VULNERABLE: u64 -> u8 With `as`
+    // 1. The boost is computed from the full `periods`
+    // 2. The lock length is stored as `periods as u8`
+    // 3. 256 periods earns a 256x boost and a lock of zero periods
+    pub fn vulnerable_lock(ctx: Context<Lock>, periods: u64) -> Result<()> {
+        require!(periods > 0, CastError::InvalidLock);
+        let now = Clock::get()?.unix_timestamp;
+        let position = &mut ctx.accounts.position;
+
+        let earned = position.balance.checked_mul(periods).ok_or(CastError::ArithmeticError)?;
+        position.boost = position.boost.checked_add(earned).ok_or(CastError::ArithmeticError)?;
+        position.lock_periods = periods as u8;
+        position.unlock_ts = now + position.lock_periods as i64 * PERIOD_SECS;
+
+        msg!("Vulnerable lock: boost +{}, locked {} periods", earned, position.lock_periods);
+        Ok(())
+    }
+
+    // VULNERABLE: i64 -> u64 With `as`
+    // 1. Time remaining is `unlock_ts - now`, which goes negative once the lock expires
+    // 2. `as u64` turns -1 into 18_446_744_073_709_551_615
+    // 3. An honest user exiting AFTER their lock pays the maximum penalty
+    pub fn vulnerable_exit(ctx: Context<Withdraw>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let position = &mut ctx.accounts.position;
+
+        let remaining = (position.unlock_ts - now) as u64;
+        let penalty = remaining.saturating_mul(PENALTY_PER_SEC).min(position.balance);
+
+        settle_exit(position, &mut ctx.accounts.vault, &ctx.accounts.owner, penalty)
+    }
+
+    // SECURE: `try_into()` With Explicit Errors
+    // 1. Narrowing fails with `AmountTooLarge` instead of dropping bits
+    // 2. The checked value is the one used for both the debit and the payout
+    pub fn secure_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let position = &mut ctx.accounts.position;
+        require!(now >= position.unlock_ts, CastError::StillLocked);
+
+        let amount_u32: u32 = amount.try_into().map_err(|_| CastError::AmountTooLarge)?;
+        position.balance = debit(position.balance, amount_u32)?;
+        move_lamports(
+            &ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.owner.to_account_info(),
+            u64::from(amount_u32),
+        )?;
+
+        msg!("Secure withdraw: paid {}", amount_u32);
+        Ok(())
+    }
+
+    // SECURE: Narrow Once, Use the Narrow Value Everywhere
+    // 1. `periods` is converted to `u8` up front, failing with `LockTooLong`
+    // 2. Boost and lock length both derive from the converted value
+    pub fn secure_lock(ctx: Context<Lock>, periods: u64) -> Result<()> {
+        let periods: u8 = periods.try_into().map_err(|_| CastError::LockTooLong)?;
+        require!(periods > 0, CastError::InvalidLock);
+        let now = Clock::get()?.unix_timestamp;
+        let position = &mut ctx.accounts.position;
+
+        let earned = position
+            .balance
+            .checked_mul(u64::from(periods))
+            .ok_or(CastError::ArithmeticError)?;
+        position.boost = position.boost.checked_add(earned).ok_or(CastError::ArithmeticError)?;
+        position.lock_periods = periods;
+        position.unlock_ts = now
+            .checked_add(i64::from(periods) * PERIOD_SECS)
+            .ok_or(CastError::ArithmeticError)?;
+
+        msg!("Secure lock: boost +{}, locked {} periods", earned, periods);
+        Ok(())
+    }
+
+    // SECURE: Clamp the Sign, Then Convert
+    // 1. `secs_since` returns `None` once the lock has expired: no time remaining
+    // 2. The non-negative `i64` is converted with `try_into()`, never `as`
+    pub fn secure_exit(ctx: Context<Withdraw>) -> Result<()> {
+        let now = Clock::get()?.current_unix_ts();
+        let position = &mut ctx.accounts.position;
+
+        let remaining = UnixTs(position.unlock_ts).secs_since(now).unwrap_or(0);
+        let remaining: u64 = remaining.try_into().map_err(|_| CastError::NegativeDuration)?;
+        let penalty = remaining.saturating_mul(PENALTY_PER_SEC).min(position.balance);
+
+        settle_exit(position, &mut ctx.accounts.vault, &ctx.accounts.owner, penalty)
+    }
+}
+
+/// Subtracts a withdrawal from a balance. Withdrawals are capped at
+/// `u32::MAX` lamports per call, hence the narrow argument.
+fn debit(balance: u64, amount: u32) -> Result<u64> {
+    balance
+        .checked_sub(u64::from(amount))
+        .ok_or_else(|| CastError::InsufficientBalance.into())
+}
+
+/// Pays out `balance - penalty`, keeps the penalty in the vault and closes
+/// out the position's balance and boost.
+fn settle_exit(
+    position: &mut Account<Position>,
+    vault: &mut Account<Vault>,
+    owner: &Signer,
+    penalty: u64,
+) -> Result<()> {
+    let payout = position.balance - penalty;
+    vault.penalties = vault.penalties.checked_add(penalty).ok_or(CastError::ArithmeticError)?;
+    position.balance = 0;
+    position.boost = 0;
+
+    move_lamports(&vault.to_account_info(), &owner.to_account_info(), payout)?;
+
+    msg!("Exit: paid {}, penalty {}", payout, penalty);
+    Ok(())
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(CastError::InsufficientBalance)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(CastError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(vault_id: u64)]
+pub struct InitializeVault<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", vault_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(seeds = [b"vault", vault.vault_id.to_le_bytes().as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [b"position", vault.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, seeds = [b"vault", vault.vault_id.to_le_bytes().as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    #[account(
+        mut,
+        seeds = [b"position", vault.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut, seeds = [b"vault", vault.vault_id.to_le_bytes().as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    #[account(
+        mut,
+        seeds = [b"position", vault.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Lock<'info> {
+    #[account(seeds = [b"vault", vault.vault_id.to_le_bytes().as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    #[account(
+        mut,
+        seeds = [b"position", vault.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    pub owner: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub vault_id: u64,
+    /// Early-exit penalties retained by the vault.
+    pub penalties: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub balance: u64,
+    /// Reward weight earned by locking: balance x periods.
+    pub boost: u64,
+    pub lock_periods: u8,
+    pub unlock_ts: i64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum CastError {
+    #[msg("Amount does not fit in u32")]
+    AmountTooLarge,
+    #[msg("Lock period does not fit in u8")]
+    LockTooLong,
+    #[msg("Duration is negative")]
+    NegativeDuration,
+    #[msg("Lock must be at least one period")]
+    InvalidLock,
+    #[msg("Position is still locked")]
+    StillLocked,
+    #[msg("Insufficient balance")]
+    InsufficientBalance,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
@@ -0,0 +1,238 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { sleep } from "../../../tests/utils";
+
+describe("21: Numeric Cast Truncation", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.CastTruncation as Program<any>;
+
+    let victim: Keypair;
+    let attacker: Keypair;
+
+    const VULNERABLE_VAULT_ID = 1;
+    const SECURE_VAULT_ID = 2;
+
+    const VICTIM_DEPOSIT = 5 * LAMPORTS_PER_SOL;
+    const ATTACKER_DEPOSIT = 10_000_000;
+    // Low 32 bits are 1_000_000; the full value is ~4.3 SOL.
+    const TRUNCATED_AMOUNT = new anchor.BN(2).pow(new anchor.BN(32)).addn(1_000_000);
+    const PERIOD_SECS = 2;
+
+    function vaultPda(vaultId: number): PublicKey {
+        const id = Buffer.alloc(8);
+        id.writeBigUInt64LE(BigInt(vaultId));
+        return PublicKey.findProgramAddressSync([Buffer.from("vault"), id], program.programId)[0];
+    }
+
+    function positionPda(vault: PublicKey, owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("position"), vault.toBuffer(), owner.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function accounts(vaultId: number, owner: Keypair) {
+        const vault = vaultPda(vaultId);
+        return {
+            vault,
+            position: positionPda(vault, owner.publicKey),
+            owner: owner.publicKey,
+            systemProgram: SystemProgram.programId,
+        };
+    }
+
+    async function fund(vaultId: number, owner: Keypair, amount: number) {
+        await program.methods.openPosition().accounts(accounts(vaultId, owner)).signers([owner]).rpc();
+        await program.methods
+            .deposit(new anchor.BN(amount))
+            .accounts(accounts(vaultId, owner))
+            .signers([owner])
+            .rpc();
+    }
+
+    before(async () => {
+        victim = Keypair.generate();
+        attacker = Keypair.generate();
+
+        for (const kp of [victim, attacker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 20 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates two vaults and funds them", async () => {
+            for (const vaultId of [VULNERABLE_VAULT_ID, SECURE_VAULT_ID]) {
+                await program.methods
+                    .initializeVault(new anchor.BN(vaultId))
+                    .accounts({
+                        vault: vaultPda(vaultId),
+                        payer: victim.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([victim])
+                    .rpc();
+
+                await fund(vaultId, victim, VICTIM_DEPOSIT);
+                await fund(vaultId, attacker, ATTACKER_DEPOSIT);
+            }
+        });
+    });
+
+    describe("Exploit: u64 -> u32 withdraw", () => {
+        it("Debits the low 32 bits, pays the full amount", async () => {
+            const { position } = accounts(VULNERABLE_VAULT_ID, attacker);
+            const before = await provider.connection.getBalance(attacker.publicKey);
+
+            await program.methods
+                .vulnerableWithdraw(TRUNCATED_AMOUNT)
+                .accounts(accounts(VULNERABLE_VAULT_ID, attacker))
+                .signers([attacker])
+                .rpc();
+
+            const gained = (await provider.connection.getBalance(attacker.publicKey)) - before;
+            const state = await program.account.position.fetch(position);
+
+            console.log(`  Debited:  ${ATTACKER_DEPOSIT - state.balance.toNumber()} lamports`);
+            console.log(`  Received: ${gained / LAMPORTS_PER_SOL} SOL`);
+            expect(state.balance.toNumber()).to.equal(ATTACKER_DEPOSIT - 1_000_000);
+            expect(gained).to.equal(TRUNCATED_AMOUNT.toNumber());
+        });
+    });
+
+    describe("Exploit: u64 -> u8 lock", () => {
+        it("256 periods earns a 256x boost and no lock", async () => {
+            const { position } = accounts(VULNERABLE_VAULT_ID, attacker);
+            const { balance } = await program.account.position.fetch(position);
+
+            await program.methods
+                .vulnerableLock(new anchor.BN(256))
+                .accounts(accounts(VULNERABLE_VAULT_ID, attacker))
+                .signers([attacker])
+                .rpc();
+
+            const state = await program.account.position.fetch(position);
+            console.log(`  Boost: ${state.boost.toString()}, lock_periods: ${state.lockPeriods}`);
+            expect(state.boost.toString()).to.equal(balance.muln(256).toString());
+            expect(state.lockPeriods).to.equal(0);
+
+            // Unlocked immediately.
+            await program.methods
+                .vulnerableWithdraw(balance)
+                .accounts(accounts(VULNERABLE_VAULT_ID, attacker))
+                .signers([attacker])
+                .rpc();
+        });
+    });
+
+    describe("Exploit: i64 -> u64 remaining time", () => {
+        it("Exiting after the lock expires charges the maximum penalty", async () => {
+            await program.methods
+                .vulnerableLock(new anchor.BN(1))
+                .accounts(accounts(VULNERABLE_VAULT_ID, victim))
+                .signers([victim])
+                .rpc();
+
+            await sleep(PERIOD_SECS + 2);
+
+            const before = await provider.connection.getBalance(victim.publicKey);
+            await program.methods
+                .vulnerableExit()
+                .accounts(accounts(VULNERABLE_VAULT_ID, victim))
+                .signers([victim])
+                .rpc();
+
+            const received = (await provider.connection.getBalance(victim.publicKey)) - before;
+            const vault = await program.account.vault.fetch(vaultPda(VULNERABLE_VAULT_ID));
+            console.log(`  Victim received ${received} lamports; penalty ${vault.penalties.toString()}`);
+            expect(received).to.equal(0);
+            expect(vault.penalties.toNumber()).to.equal(VICTIM_DEPOSIT);
+        });
+    });
+
+    describe("Secure: try_into() with explicit errors", () => {
+        it("Rejects an amount that does not fit in u32", async () => {
+            try {
+                await program.methods
+                    .secureWithdraw(TRUNCATED_AMOUNT)
+                    .accounts(accounts(SECURE_VAULT_ID, attacker))
+                    .signers([attacker])
+                    .rpc();
+
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("AmountTooLarge");
+            }
+        });
+
+        it("Still pays in-range withdrawals", async () => {
+            const before = await provider.connection.getBalance(attacker.publicKey);
+
+            await program.methods
+                .secureWithdraw(new anchor.BN(1_000_000))
+                .accounts(accounts(SECURE_VAULT_ID, attacker))
+                .signers([attacker])
+                .rpc();
+
+            expect((await provider.connection.getBalance(attacker.publicKey)) - before).to.equal(1_000_000);
+        });
+
+        it("Rejects a lock that does not fit in u8", async () => {
+            try {
+                await program.methods
+                    .secureLock(new anchor.BN(256))
+                    .accounts(accounts(SECURE_VAULT_ID, attacker))
+                    .signers([attacker])
+                    .rpc();
+
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("LockTooLong");
+            }
+        });
+
+        it("Exiting after the lock expires charges nothing", async () => {
+            await program.methods
+                .secureLock(new anchor.BN(1))
+                .accounts(accounts(SECURE_VAULT_ID, victim))
+                .signers([victim])
+                .rpc();
+
+            await sleep(PERIOD_SECS + 2);
+
+            const before = await provider.connection.getBalance(victim.publicKey);
+            await program.methods
+                .secureExit()
+                .accounts(accounts(SECURE_VAULT_ID, victim))
+                .signers([victim])
+                .rpc();
+
+            const received = (await provider.connection.getBalance(victim.publicKey)) - before;
+            const vault = await program.account.vault.fetch(vaultPda(SECURE_VAULT_ID));
+            expect(received).to.equal(VICTIM_DEPOSIT);
+            expect(vault.penalties.toNumber()).to.equal(0);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Numeric Cast Truncation ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  debit(balance, amount as u32); lock_periods = periods as u8;");
+            console.log("  remaining = (unlock_ts - now) as u64;");
+            console.log("  Risk: `as` drops high bits and reinterprets sign without an error\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  let amount: u32 = amount.try_into().map_err(|_| CastError::AmountTooLarge)?;");
+            console.log("  Protection: out-of-range values fail; the checked value is the one used\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Checked arithmetic is not enough if the conversion in front of it is unchecked.");
+        });
+    });
+});
@@ -0,0 +1,256 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("6imGXiMuRcDsCdv9iXEQ5pRw4FBTaSsGUTnpSFv3b2XP");
+
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+/// Fee tiers as seed strings. `fee_zero` exists for protocol-owned flows and
+/// was only ever meant to be created by the team.
+pub fn fee_bps_for_tier(tier: &str) -> Result<u16> {
+    match tier {
+        "fee_high" => Ok(100),
+        "fee_low" => Ok(30),
+        "fee_zero" => Ok(0),
+        _ => err!(PoolError::UnknownTier),
+    }
+}
+
+#[program]
+pub mod seed_encoded_params {
+    use super::*;
+
+    pub fn initialize_protocol(ctx: Context<InitializeProtocol>) -> Result<()> {
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.admin = ctx.accounts.admin.key();
+        protocol.fees_collected = 0;
+        protocol.bump = ctx.bumps.protocol;
+        Ok(())
+    }
+
+    // VULNERABLE: The Tier Lives in the Address
+    // 1. Anyone can create `[b"pool", tier]` for any known tier string
+    // 2. The pool stores no fee; the fee is looked up from the seed string
+    // 3. Whoever creates `fee_zero` gets a zero-fee venue
+    pub fn vulnerable_create_pool(ctx: Context<VulnerableCreatePool>, tier: String) -> Result<()> {
+        fee_bps_for_tier(&tier)?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.creator = ctx.accounts.creator.key();
+        pool.volume = 0;
+        pool.bump = ctx.bumps.pool;
+
+        msg!("Vulnerable pool created for tier {}", tier);
+        Ok(())
+    }
+
+    // VULNERABLE: Trusts Which PDA Was Passed
+    // The seeds prove the pool matches `tier`. They say nothing about
+    // whether `tier` was ever authorized.
+    pub fn vulnerable_pay(ctx: Context<VulnerablePay>, tier: String, amount: u64) -> Result<()> {
+        let fee_bps = fee_bps_for_tier(&tier)?;
+        let fee = charge_fee(
+            &mut ctx.accounts.protocol,
+            &ctx.accounts.user,
+            &ctx.accounts.system_program,
+            amount,
+            fee_bps,
+        )?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.volume = pool.volume.checked_add(amount).ok_or(PoolError::ArithmeticError)?;
+
+        msg!("Vulnerable payment of {} via {} charged fee {}", amount, tier, fee);
+        Ok(())
+    }
+
+    // SECURE: Tier Is State, Set by the Admin
+    // 1. Pools are created by the admin only (`has_one = admin`)
+    // 2. `fee_bps` is stored in the pool and bounded by `MAX_FEE_BPS`
+    // 3. The address is an opaque id; it carries no meaning
+    pub fn secure_create_pool(ctx: Context<SecureCreatePool>, pool_id: u64, fee_bps: u16) -> Result<()> {
+        require!(fee_bps <= MAX_FEE_BPS, PoolError::InvalidFee);
+
+        let pool = &mut ctx.accounts.pool;
+        pool.pool_id = pool_id;
+        pool.fee_bps = fee_bps;
+        pool.volume = 0;
+        pool.bump = ctx.bumps.pool;
+        Ok(())
+    }
+
+    pub fn secure_set_fee(ctx: Context<SecureSetFee>, fee_bps: u16) -> Result<()> {
+        require!(fee_bps <= MAX_FEE_BPS, PoolError::InvalidFee);
+        ctx.accounts.pool.fee_bps = fee_bps;
+        Ok(())
+    }
+
+    // SECURE: Fee Read From Validated State
+    // The pool is found by its stored id and the fee comes from the field
+    // the admin set, so passing a different pool can only select another
+    // admin-approved fee.
+    pub fn secure_pay(ctx: Context<SecurePay>, amount: u64) -> Result<()> {
+        let fee_bps = ctx.accounts.pool.fee_bps;
+        require!(fee_bps <= MAX_FEE_BPS, PoolError::InvalidFee);
+
+        let fee = charge_fee(
+            &mut ctx.accounts.protocol,
+            &ctx.accounts.user,
+            &ctx.accounts.system_program,
+            amount,
+            fee_bps,
+        )?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.volume = pool.volume.checked_add(amount).ok_or(PoolError::ArithmeticError)?;
+
+        msg!("Secure payment of {} via pool {} charged fee {}", amount, pool.pool_id, fee);
+        Ok(())
+    }
+}
+
+fn charge_fee<'info>(
+    protocol: &mut Account<'info, Protocol>,
+    user: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+    fee_bps: u16,
+) -> Result<u64> {
+    let fee = amount
+        .checked_mul(fee_bps as u64)
+        .ok_or(PoolError::ArithmeticError)?
+        / 10_000;
+
+    if fee > 0 {
+        let cpi_accounts = Transfer {
+            from: user.to_account_info(),
+            to: protocol.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(system_program.to_account_info(), cpi_accounts);
+        transfer(cpi_ctx, fee)?;
+    }
+
+    protocol.fees_collected = protocol.fees_collected.checked_add(fee).ok_or(PoolError::ArithmeticError)?;
+    Ok(fee)
+}
+
+#[derive(Accounts)]
+pub struct InitializeProtocol<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Protocol::INIT_SPACE,
+        seeds = [b"protocol"],
+        bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(tier: String)]
+pub struct VulnerableCreatePool<'info> {
+    // VULNERABLE: Permissionless init, privileged meaning in the seed
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + TierPool::INIT_SPACE,
+        seeds = [b"pool", tier.as_bytes()],
+        bump
+    )]
+    pub pool: Account<'info, TierPool>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(tier: String)]
+pub struct VulnerablePay<'info> {
+    #[account(mut, seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    #[account(mut, seeds = [b"pool", tier.as_bytes()], bump = pool.bump)]
+    pub pool: Account<'info, TierPool>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u64)]
+pub struct SecureCreatePool<'info> {
+    #[account(seeds = [b"protocol"], bump = protocol.bump, has_one = admin @ PoolError::Unauthorized)]
+    pub protocol: Account<'info, Protocol>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Pool::INIT_SPACE,
+        seeds = [b"secure_pool", pool_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SecureSetFee<'info> {
+    #[account(seeds = [b"protocol"], bump = protocol.bump, has_one = admin @ PoolError::Unauthorized)]
+    pub protocol: Account<'info, Protocol>,
+    #[account(mut, seeds = [b"secure_pool", pool.pool_id.to_le_bytes().as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SecurePay<'info> {
+    #[account(mut, seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    #[account(mut, seeds = [b"secure_pool", pool.pool_id.to_le_bytes().as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Protocol {
+    pub admin: Pubkey,
+    pub fees_collected: u64,
+    pub bump: u8,
+}
+
+/// Vulnerable pool: the fee tier is only in the address.
+#[account]
+#[derive(InitSpace)]
+pub struct TierPool {
+    pub creator: Pubkey,
+    pub volume: u64,
+    pub bump: u8,
+}
+
+/// Secure pool: the fee tier is a field only the admin can write.
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub pool_id: u64,
+    pub fee_bps: u16,
+    pub volume: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum PoolError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Unknown fee tier")]
+    UnknownTier,
+    #[msg("Fee exceeds maximum")]
+    InvalidFee,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
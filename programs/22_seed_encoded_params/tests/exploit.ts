@@ -0,0 +1,228 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+
+describe("22: Seed-Encoded Parameters", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.SeedEncodedParams as Program<any>;
+
+    let admin: Keypair;
+    let user: Keypair;
+    let attacker: Keypair;
+
+    const PAYMENT = LAMPORTS_PER_SOL;
+    const SECURE_POOL_ID = 1;
+    const SECURE_FEE_BPS = 30;
+
+    const protocolPda = () =>
+        PublicKey.findProgramAddressSync([Buffer.from("protocol")], program.programId)[0];
+
+    const tierPoolPda = (tier: string) =>
+        PublicKey.findProgramAddressSync([Buffer.from("pool"), Buffer.from(tier)], program.programId)[0];
+
+    function securePoolPda(poolId: number): PublicKey {
+        const id = Buffer.alloc(8);
+        id.writeBigUInt64LE(BigInt(poolId));
+        return PublicKey.findProgramAddressSync([Buffer.from("secure_pool"), id], program.programId)[0];
+    }
+
+    async function feesCollected(): Promise<number> {
+        const protocol = await program.account.protocol.fetch(protocolPda());
+        return protocol.feesCollected.toNumber();
+    }
+
+    async function vulnerablePay(payer: Keypair, tier: string) {
+        await program.methods
+            .vulnerablePay(tier, new anchor.BN(PAYMENT))
+            .accounts({
+                protocol: protocolPda(),
+                pool: tierPoolPda(tier),
+                user: payer.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([payer])
+            .rpc();
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        user = Keypair.generate();
+        attacker = Keypair.generate();
+
+        for (const kp of [admin, user, attacker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Initializes the protocol and the public tiers", async () => {
+            await program.methods
+                .initializeProtocol()
+                .accounts({
+                    protocol: protocolPda(),
+                    admin: admin.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([admin])
+                .rpc();
+
+            for (const tier of ["fee_low", "fee_high"]) {
+                await program.methods
+                    .vulnerableCreatePool(tier)
+                    .accounts({
+                        pool: tierPoolPda(tier),
+                        creator: admin.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([admin])
+                    .rpc();
+            }
+        });
+
+        it("Normal payments through fee_low pay 30 bps", async () => {
+            const before = await feesCollected();
+            await vulnerablePay(user, "fee_low");
+            expect((await feesCollected()) - before).to.equal(PAYMENT * 30 / 10_000);
+        });
+    });
+
+    describe("Exploit: create and use the fee_zero tier", () => {
+        it("Attacker creates [\"pool\", \"fee_zero\"]", async () => {
+            await program.methods
+                .vulnerableCreatePool("fee_zero")
+                .accounts({
+                    pool: tierPoolPda("fee_zero"),
+                    creator: attacker.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([attacker])
+                .rpc();
+
+            const pool = await program.account.tierPool.fetch(tierPoolPda("fee_zero"));
+            expect(pool.creator.toString()).to.equal(attacker.publicKey.toString());
+        });
+
+        it("Payments through it pay no fee", async () => {
+            const before = await feesCollected();
+
+            for (let i = 0; i < 3; i++) {
+                await vulnerablePay(attacker, "fee_zero");
+            }
+
+            const charged = (await feesCollected()) - before;
+            console.log(`  3 SOL routed through fee_zero; protocol collected ${charged} lamports`);
+            expect(charged).to.equal(0);
+        });
+    });
+
+    describe("Secure: tier stored in admin-set state", () => {
+        it("Non-admin cannot create a pool", async () => {
+            try {
+                await program.methods
+                    .secureCreatePool(new anchor.BN(99), 0)
+                    .accounts({
+                        protocol: protocolPda(),
+                        pool: securePoolPda(99),
+                        admin: attacker.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([attacker])
+                    .rpc();
+
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("Unauthorized");
+            }
+        });
+
+        it("Admin creates a pool with a stored fee", async () => {
+            await program.methods
+                .secureCreatePool(new anchor.BN(SECURE_POOL_ID), SECURE_FEE_BPS)
+                .accounts({
+                    protocol: protocolPda(),
+                    pool: securePoolPda(SECURE_POOL_ID),
+                    admin: admin.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([admin])
+                .rpc();
+
+            const pool = await program.account.pool.fetch(securePoolPda(SECURE_POOL_ID));
+            expect(pool.feeBps).to.equal(SECURE_FEE_BPS);
+        });
+
+        it("Non-admin cannot lower the fee", async () => {
+            try {
+                await program.methods
+                    .secureSetFee(0)
+                    .accounts({
+                        protocol: protocolPda(),
+                        pool: securePoolPda(SECURE_POOL_ID),
+                        admin: attacker.publicKey,
+                    })
+                    .signers([attacker])
+                    .rpc();
+
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("Unauthorized");
+            }
+        });
+
+        it("Admin cannot exceed MAX_FEE_BPS", async () => {
+            try {
+                await program.methods
+                    .secureSetFee(1_001)
+                    .accounts({
+                        protocol: protocolPda(),
+                        pool: securePoolPda(SECURE_POOL_ID),
+                        admin: admin.publicKey,
+                    })
+                    .signers([admin])
+                    .rpc();
+
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("InvalidFee");
+            }
+        });
+
+        it("Payments are charged the stored fee", async () => {
+            const before = await feesCollected();
+
+            await program.methods
+                .securePay(new anchor.BN(PAYMENT))
+                .accounts({
+                    protocol: protocolPda(),
+                    pool: securePoolPda(SECURE_POOL_ID),
+                    user: attacker.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([attacker])
+                .rpc();
+
+            expect((await feesCollected()) - before).to.equal(PAYMENT * SECURE_FEE_BPS / 10_000);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Seed-Encoded Parameters ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  seeds = [b\"pool\", tier.as_bytes()]; fee = fee_bps_for_tier(&tier)");
+            console.log("  Risk: anyone who can create the PDA chooses the parameter\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  pool.fee_bps set by has_one = admin; fee = pool.fee_bps");
+            console.log("  Protection: addresses identify accounts; state carries privileges\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  A PDA proves how an address was derived, not who approved the seeds.");
+        });
+    });
+});
@@ -0,0 +1,210 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::get_return_data;
+use mock_vault::cpi::accounts::Withdraw as VaultWithdraw;
+use mock_vault::program::MockVault;
+use mock_vault::Vault;
+
+declare_id!("7v1oek6HjopKuy4tGHE1MGjvdzoDEu5H7mzevGRzSC1Y");
+
+#[program]
+pub mod unchecked_cpi_result {
+    use super::*;
+
+    pub fn initialize_router(ctx: Context<InitializeRouter>, router_id: u64, min_reserve: u64) -> Result<()> {
+        let router = &mut ctx.accounts.router;
+        router.router_id = router_id;
+        router.vault = ctx.accounts.vault.key();
+        router.min_reserve = min_reserve;
+        router.total_credits = 0;
+        router.bump = ctx.bumps.router;
+        Ok(())
+    }
+
+    pub fn open_credit(ctx: Context<OpenCredit>) -> Result<()> {
+        let credit = &mut ctx.accounts.credit;
+        credit.router = ctx.accounts.router.key();
+        credit.owner = ctx.accounts.owner.key();
+        credit.amount = 0;
+        credit.bump = ctx.bumps.credit;
+        Ok(())
+    }
+
+    // VULNERABLE: Acts on Assumed Success
+    // 1. `let _ =` discards the CPI's `Result`
+    // 2. `get_return_data()` is never inspected, so a partial fill looks
+    //    like a full one: the caller credits `amount`, not what was withdrawn
+    // 3. The reserve check reads `vault` as deserialized BEFORE the CPI
+    pub fn vulnerable_pull(ctx: Context<Pull>, amount: u64) -> Result<()> {
+        let router_info = ctx.accounts.router.to_account_info();
+        let router_id = ctx.accounts.router.router_id.to_le_bytes();
+        let seeds: &[&[u8]] = &[b"router", router_id.as_ref(), &[ctx.accounts.router.bump]];
+        let signer = &[seeds];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.vault_program.to_account_info(),
+            VaultWithdraw {
+                vault: ctx.accounts.vault.to_account_info(),
+                authority: router_info,
+            },
+            signer,
+        );
+        let _ = mock_vault::cpi::withdraw(cpi_ctx, amount);
+        let _ = get_return_data();
+
+        require!(
+            ctx.accounts.vault.balance >= ctx.accounts.router.min_reserve,
+            RouterError::ReserveBreached
+        );
+
+        credit(&mut ctx.accounts.router, &mut ctx.accounts.credit, amount)?;
+        msg!("Vulnerable pull: credited {}", amount);
+        Ok(())
+    }
+
+    // SECURE: Propagate, Verify, Reload
+    // 1. `?` propagates any CPI error
+    // 2. Return data must come from the vault program and be exactly a `u64`
+    // 3. Only the amount actually withdrawn is credited
+    // 4. `vault.reload()` before any check that depends on post-CPI state
+    pub fn secure_pull(ctx: Context<Pull>, amount: u64) -> Result<()> {
+        let router_info = ctx.accounts.router.to_account_info();
+        let router_id = ctx.accounts.router.router_id.to_le_bytes();
+        let seeds: &[&[u8]] = &[b"router", router_id.as_ref(), &[ctx.accounts.router.bump]];
+        let signer = &[seeds];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.vault_program.to_account_info(),
+            VaultWithdraw {
+                vault: ctx.accounts.vault.to_account_info(),
+                authority: router_info,
+            },
+            signer,
+        );
+        mock_vault::cpi::withdraw(cpi_ctx, amount)?;
+        let withdrawn = withdrawn_from_return_data()?;
+
+        ctx.accounts.vault.reload()?;
+        require!(
+            ctx.accounts.vault.balance >= ctx.accounts.router.min_reserve,
+            RouterError::ReserveBreached
+        );
+
+        credit(&mut ctx.accounts.router, &mut ctx.accounts.credit, withdrawn)?;
+        msg!("Secure pull: requested {}, credited {}", amount, withdrawn);
+        Ok(())
+    }
+}
+
+/// Reads the `u64` the vault's `withdraw` returned, rejecting return data
+/// that is missing, from another program, or the wrong size.
+fn withdrawn_from_return_data() -> Result<u64> {
+    let (program_id, data) = get_return_data().ok_or(RouterError::MissingReturnData)?;
+    require_keys_eq!(program_id, mock_vault::ID, RouterError::UnexpectedReturnProgram);
+    let bytes: [u8; 8] = data
+        .as_slice()
+        .try_into()
+        .map_err(|_| RouterError::MalformedReturnData)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn credit(router: &mut Account<Router>, credit: &mut Account<Credit>, amount: u64) -> Result<()> {
+    credit.amount = credit.amount.checked_add(amount).ok_or(RouterError::ArithmeticError)?;
+    router.total_credits = router
+        .total_credits
+        .checked_add(amount)
+        .ok_or(RouterError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(router_id: u64)]
+pub struct InitializeRouter<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Router::INIT_SPACE,
+        seeds = [b"router", router_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub router: Account<'info, Router>,
+    /// The vault this router pulls from; the router PDA must be its authority.
+    #[account(constraint = vault.authority == router.key() @ RouterError::Unauthorized)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenCredit<'info> {
+    #[account(seeds = [b"router", router.router_id.to_le_bytes().as_ref()], bump = router.bump)]
+    pub router: Account<'info, Router>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Credit::INIT_SPACE,
+        seeds = [b"credit", router.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub credit: Account<'info, Credit>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Pull<'info> {
+    #[account(
+        mut,
+        seeds = [b"router", router.router_id.to_le_bytes().as_ref()],
+        bump = router.bump,
+        has_one = vault
+    )]
+    pub router: Account<'info, Router>,
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    #[account(
+        mut,
+        seeds = [b"credit", router.key().as_ref(), owner.key().as_ref()],
+        bump = credit.bump
+    )]
+    pub credit: Account<'info, Credit>,
+    pub owner: Signer<'info>,
+    pub vault_program: Program<'info, MockVault>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Router {
+    pub router_id: u64,
+    pub vault: Pubkey,
+    /// The vault must keep at least this much after every pull.
+    pub min_reserve: u64,
+    pub total_credits: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Credit {
+    pub router: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum RouterError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Vault would fall below its minimum reserve")]
+    ReserveBreached,
+    #[msg("CPI returned no data")]
+    MissingReturnData,
+    #[msg("Return data was set by an unexpected program")]
+    UnexpectedReturnProgram,
+    #[msg("Return data has the wrong length")]
+    MalformedReturnData,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
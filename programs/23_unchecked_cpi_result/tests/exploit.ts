@@ -0,0 +1,189 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+
+describe("23: Unchecked CPI Result", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.UncheckedCpiResult as Program<any>;
+    const vaultProgram = anchor.workspace.MockVault as Program<any>;
+
+    let admin: Keypair;
+    let attacker: Keypair;
+
+    const VAULT_BALANCE = 100;
+    const MIN_RESERVE = 20;
+
+    const VULNERABLE_ROUTER_ID = 1;
+    const SECURE_ROUTER_ID = 2;
+    // No reserve, so partial fills are visible on their own.
+    const PARTIAL_FILL_ROUTER_ID = 3;
+
+    function routerPda(routerId: number): PublicKey {
+        const id = Buffer.alloc(8);
+        id.writeBigUInt64LE(BigInt(routerId));
+        return PublicKey.findProgramAddressSync([Buffer.from("router"), id], program.programId)[0];
+    }
+
+    function vaultPda(routerId: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("vault"), routerPda(routerId).toBuffer()],
+            vaultProgram.programId
+        )[0];
+    }
+
+    function creditPda(routerId: number, owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("credit"), routerPda(routerId).toBuffer(), owner.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    async function setupRouter(routerId: number, minReserve: number, balance: number) {
+        await vaultProgram.methods
+            .initialize(routerPda(routerId))
+            .accounts({
+                vault: vaultPda(routerId),
+                payer: admin.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([admin])
+            .rpc();
+
+        await vaultProgram.methods
+            .deposit(new anchor.BN(balance))
+            .accounts({ vault: vaultPda(routerId), depositor: admin.publicKey })
+            .signers([admin])
+            .rpc();
+
+        await program.methods
+            .initializeRouter(new anchor.BN(routerId), new anchor.BN(minReserve))
+            .accounts({
+                router: routerPda(routerId),
+                vault: vaultPda(routerId),
+                payer: admin.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([admin])
+            .rpc();
+
+        await program.methods
+            .openCredit()
+            .accounts({
+                router: routerPda(routerId),
+                credit: creditPda(routerId, attacker.publicKey),
+                owner: attacker.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([attacker])
+            .rpc();
+    }
+
+    function pull(method: "vulnerablePull" | "securePull", routerId: number, amount: number) {
+        return program.methods[method](new anchor.BN(amount))
+            .accounts({
+                router: routerPda(routerId),
+                vault: vaultPda(routerId),
+                credit: creditPda(routerId, attacker.publicKey),
+                owner: attacker.publicKey,
+                vaultProgram: vaultProgram.programId,
+            })
+            .signers([attacker])
+            .rpc();
+    }
+
+    async function state(routerId: number) {
+        const vault = await vaultProgram.account.vault.fetch(vaultPda(routerId));
+        const credit = await program.account.credit.fetch(creditPda(routerId, attacker.publicKey));
+        return { vault: vault.balance.toNumber(), credit: credit.amount.toNumber() };
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        attacker = Keypair.generate();
+
+        for (const kp of [admin, attacker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates routers backed by mock vaults", async () => {
+            await setupRouter(VULNERABLE_ROUTER_ID, MIN_RESERVE, VAULT_BALANCE);
+            await setupRouter(SECURE_ROUTER_ID, MIN_RESERVE, VAULT_BALANCE);
+            await setupRouter(PARTIAL_FILL_ROUTER_ID, 0, 30);
+
+            expect((await state(VULNERABLE_ROUTER_ID)).vault).to.equal(VAULT_BALANCE);
+        });
+    });
+
+    describe("Exploit: assumed success", () => {
+        it("Credits the requested amount after a partial fill", async () => {
+            await pull("vulnerablePull", VULNERABLE_ROUTER_ID, 500);
+
+            const { vault, credit } = await state(VULNERABLE_ROUTER_ID);
+            console.log(`  Vault paid out ${VAULT_BALANCE - vault}, attacker credited ${credit}`);
+            expect(credit).to.equal(500);
+            expect(VAULT_BALANCE - vault).to.equal(VAULT_BALANCE);
+        });
+
+        it("Stale reserve check let the vault drain below its minimum", async () => {
+            const { vault } = await state(VULNERABLE_ROUTER_ID);
+            console.log(`  Vault balance ${vault} < reserve ${MIN_RESERVE}`);
+            expect(vault).to.be.lessThan(MIN_RESERVE);
+        });
+    });
+
+    describe("Secure: propagate, verify return data, reload", () => {
+        it("Credits only what was actually withdrawn", async () => {
+            await pull("securePull", PARTIAL_FILL_ROUTER_ID, 100);
+
+            const { vault, credit } = await state(PARTIAL_FILL_ROUTER_ID);
+            expect(credit).to.equal(30);
+            expect(vault).to.equal(0);
+        });
+
+        it("Reloaded reserve check rejects a drain", async () => {
+            try {
+                await pull("securePull", SECURE_ROUTER_ID, 500);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("ReserveBreached");
+            }
+
+            expect(await state(SECURE_ROUTER_ID)).to.deep.equal({ vault: VAULT_BALANCE, credit: 0 });
+        });
+
+        it("Allows pulls that respect the reserve", async () => {
+            await pull("securePull", SECURE_ROUTER_ID, 50);
+            expect(await state(SECURE_ROUTER_ID)).to.deep.equal({ vault: 50, credit: 50 });
+
+            try {
+                await pull("securePull", SECURE_ROUTER_ID, 40);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("ReserveBreached");
+            }
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Unchecked CPI Result ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  let _ = cpi::withdraw(ctx, amount); let _ = get_return_data(); credit(amount);");
+            console.log("  Risk: success is assumed, partial fills over-credit, post-CPI state is stale\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  cpi::withdraw(ctx, amount)?; check return program id + length; vault.reload()?;");
+            console.log("  Protection: act on what the callee reports and what the account now holds\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  A CPI that did not fail is not a CPI that did what you asked.");
+        });
+    });
+});
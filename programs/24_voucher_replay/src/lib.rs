@@ -0,0 +1,248 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    self as instructions_sysvar, load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("4JiFQZhZYitA4zcuHmeLxjbM6dfiWVbTcRGB5Cbb8TiQ");
+
+#[program]
+pub mod voucher_replay {
+    use super::*;
+
+    pub fn initialize_treasury(ctx: Context<InitializeTreasury>, funding: u64) -> Result<()> {
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.issuer = ctx.accounts.issuer.key();
+        treasury.bump = ctx.bumps.treasury;
+
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.issuer.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                },
+            ),
+            funding,
+        )
+    }
+
+    // VULNERABLE: Bearer Voucher, No Replay Protection
+    // 1. The issuer's ed25519 signature over the voucher is verified correctly
+    // 2. Nothing about the voucher is recorded after it is paid
+    // 3. The same signed bytes can be wrapped in a new transaction forever
+    pub fn vulnerable_redeem(ctx: Context<VulnerableRedeem>, voucher: BearerVoucher) -> Result<()> {
+        verify_issuer_signature(
+            &ctx.accounts.instructions,
+            &ctx.accounts.treasury.issuer,
+            &voucher.try_to_vec()?,
+        )?;
+        require_keys_eq!(voucher.recipient, ctx.accounts.recipient.key(), VoucherError::WrongRecipient);
+
+        move_lamports(
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.recipient.to_account_info(),
+            voucher.amount,
+        )?;
+
+        msg!("Vulnerable redeem: paid {}", voucher.amount);
+        Ok(())
+    }
+
+    pub fn init_nonce(ctx: Context<InitNonce>) -> Result<()> {
+        let nonce = &mut ctx.accounts.nonce;
+        nonce.treasury = ctx.accounts.treasury.key();
+        nonce.user = ctx.accounts.user.key();
+        nonce.next = 0;
+        nonce.bump = ctx.bumps.nonce;
+        Ok(())
+    }
+
+    // SECURE: Voucher Bound to a Per-User Nonce
+    // 1. The signed bytes include the treasury, so a voucher for one
+    //    deployment or treasury is meaningless to another
+    // 2. `voucher.nonce` must equal the user's `next` nonce
+    // 3. `next` is incremented in the same instruction that pays, so a
+    //    voucher is spent exactly once
+    pub fn secure_redeem(ctx: Context<SecureRedeem>, voucher: Voucher) -> Result<()> {
+        verify_issuer_signature(
+            &ctx.accounts.instructions,
+            &ctx.accounts.treasury.issuer,
+            &voucher.try_to_vec()?,
+        )?;
+        require_keys_eq!(voucher.treasury, ctx.accounts.treasury.key(), VoucherError::WrongTreasury);
+        require_keys_eq!(voucher.recipient, ctx.accounts.recipient.key(), VoucherError::WrongRecipient);
+
+        let nonce = &mut ctx.accounts.nonce;
+        require!(voucher.nonce == nonce.next, VoucherError::InvalidNonce);
+        nonce.next = nonce.next.checked_add(1).ok_or(VoucherError::ArithmeticError)?;
+
+        move_lamports(
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.recipient.to_account_info(),
+            voucher.amount,
+        )?;
+
+        msg!("Secure redeem: paid {} (nonce {})", voucher.amount, voucher.nonce);
+        Ok(())
+    }
+}
+
+/// Requires the instruction immediately before this one to be an ed25519
+/// program instruction in which `issuer` signed exactly `message`.
+fn verify_issuer_signature(instructions: &AccountInfo, issuer: &Pubkey, message: &[u8]) -> Result<()> {
+    let current = load_current_index_checked(instructions)?;
+    require!(current > 0, VoucherError::MissingSignature);
+    let ix = load_instruction_at_checked(current as usize - 1, instructions)?;
+    require_keys_eq!(ix.program_id, ed25519_program::ID, VoucherError::MissingSignature);
+
+    // Header: count (u8), padding (u8), then one 14-byte offsets record.
+    let data = &ix.data;
+    require!(data.len() >= 16 && data[0] == 1, VoucherError::MalformedSignature);
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+
+    // Signature, key and message must all live in that same instruction.
+    let this_ix = u16::MAX as usize;
+    require!(
+        read_u16(4) == this_ix && read_u16(8) == this_ix && read_u16(14) == this_ix,
+        VoucherError::MalformedSignature
+    );
+
+    let key_at = read_u16(6);
+    let msg_at = read_u16(10);
+    let msg_len = read_u16(12);
+    let signer = data.get(key_at..key_at + 32).ok_or(VoucherError::MalformedSignature)?;
+    let signed = data.get(msg_at..msg_at + msg_len).ok_or(VoucherError::MalformedSignature)?;
+
+    require!(signer == issuer.as_ref(), VoucherError::WrongIssuer);
+    require!(signed == message, VoucherError::MessageMismatch);
+    Ok(())
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(VoucherError::InsufficientFunds)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(VoucherError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeTreasury<'info> {
+    #[account(
+        init,
+        payer = issuer,
+        space = 8 + Treasury::INIT_SPACE,
+        seeds = [b"treasury", issuer.key().as_ref()],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableRedeem<'info> {
+    #[account(mut, seeds = [b"treasury", treasury.issuer.as_ref()], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+    /// CHECK: Instructions sysvar, checked by address
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitNonce<'info> {
+    #[account(seeds = [b"treasury", treasury.issuer.as_ref()], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        init,
+        payer = user,
+        space = 8 + UserNonce::INIT_SPACE,
+        seeds = [b"nonce", treasury.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub nonce: Account<'info, UserNonce>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SecureRedeem<'info> {
+    #[account(mut, seeds = [b"treasury", treasury.issuer.as_ref()], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        mut,
+        seeds = [b"nonce", treasury.key().as_ref(), recipient.key().as_ref()],
+        bump = nonce.bump
+    )]
+    pub nonce: Account<'info, UserNonce>,
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+    /// CHECK: Instructions sysvar, checked by address
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Treasury {
+    pub issuer: Pubkey,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct UserNonce {
+    pub treasury: Pubkey,
+    pub user: Pubkey,
+    /// The only nonce the next voucher for this user may carry.
+    pub next: u64,
+    pub bump: u8,
+}
+
+/// Signed off-chain by the issuer. Valid for as long as the issuer's key is.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BearerVoucher {
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+/// Signed off-chain by the issuer. Valid once, for one treasury.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Voucher {
+    pub treasury: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub nonce: u64,
+}
+
+#[error_code]
+pub enum VoucherError {
+    #[msg("Missing ed25519 signature instruction")]
+    MissingSignature,
+    #[msg("Malformed ed25519 signature instruction")]
+    MalformedSignature,
+    #[msg("Voucher was not signed by the issuer")]
+    WrongIssuer,
+    #[msg("Signed message does not match the voucher")]
+    MessageMismatch,
+    #[msg("Voucher is for a different recipient")]
+    WrongRecipient,
+    #[msg("Voucher is for a different treasury")]
+    WrongTreasury,
+    #[msg("Voucher nonce is not the next expected nonce")]
+    InvalidNonce,
+    #[msg("Treasury has insufficient funds")]
+    InsufficientFunds,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
@@ -0,0 +1,223 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+    ComputeBudgetProgram,
+    Ed25519Program,
+    Keypair,
+    LAMPORTS_PER_SOL,
+    PublicKey,
+    SystemProgram,
+    SYSVAR_INSTRUCTIONS_PUBKEY,
+} from "@solana/web3.js";
+import { expect } from "chai";
+
+describe("24: Voucher Replay", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.VoucherReplay as Program<any>;
+
+    let issuer: Keypair;
+    let attacker: Keypair;
+    let otherUser: Keypair;
+    let treasury: PublicKey;
+
+    const VOUCHER_AMOUNT = LAMPORTS_PER_SOL / 10;
+    const REPLAYS = 5;
+
+    function u64(value: number): Buffer {
+        const buf = Buffer.alloc(8);
+        buf.writeBigUInt64LE(BigInt(value));
+        return buf;
+    }
+
+    // Borsh layout of `BearerVoucher { recipient, amount }`.
+    function bearerVoucherBytes(recipient: PublicKey, amount: number): Buffer {
+        return Buffer.concat([recipient.toBuffer(), u64(amount)]);
+    }
+
+    // Borsh layout of `Voucher { treasury, recipient, amount, nonce }`.
+    function voucherBytes(recipient: PublicKey, amount: number, nonce: number): Buffer {
+        return Buffer.concat([treasury.toBuffer(), recipient.toBuffer(), u64(amount), u64(nonce)]);
+    }
+
+    function signIx(signer: Keypair, message: Buffer) {
+        return Ed25519Program.createInstructionWithPrivateKey({ privateKey: signer.secretKey, message });
+    }
+
+    // Each replay gets a distinct compute limit so the runtime does not
+    // reject it as a duplicate of an identical, already-processed transaction.
+    function uniquifier(i: number) {
+        return ComputeBudgetProgram.setComputeUnitLimit({ units: 200_000 + i });
+    }
+
+    function noncePda(user: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("nonce"), treasury.toBuffer(), user.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function vulnerableRedeem(recipient: Keypair, amount: number, signer: Keypair, i = 0) {
+        const voucher = { recipient: recipient.publicKey, amount: new anchor.BN(amount) };
+        return program.methods
+            .vulnerableRedeem(voucher)
+            .accounts({
+                treasury,
+                recipient: recipient.publicKey,
+                instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+            })
+            .preInstructions([uniquifier(i), signIx(signer, bearerVoucherBytes(recipient.publicKey, amount))])
+            .signers([recipient])
+            .rpc();
+    }
+
+    function secureRedeem(
+        redeemer: Keypair,
+        voucherFor: PublicKey,
+        amount: number,
+        nonce: number,
+        signer: Keypair,
+        i = 0
+    ) {
+        const voucher = {
+            treasury,
+            recipient: voucherFor,
+            amount: new anchor.BN(amount),
+            nonce: new anchor.BN(nonce),
+        };
+        return program.methods
+            .secureRedeem(voucher)
+            .accounts({
+                treasury,
+                nonce: noncePda(redeemer.publicKey),
+                recipient: redeemer.publicKey,
+                instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+            })
+            .preInstructions([uniquifier(i), signIx(signer, voucherBytes(voucherFor, amount, nonce))])
+            .signers([redeemer])
+            .rpc();
+    }
+
+    async function initNonce(user: Keypair) {
+        await program.methods
+            .initNonce()
+            .accounts({
+                treasury,
+                nonce: noncePda(user.publicKey),
+                user: user.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([user])
+            .rpc();
+    }
+
+    before(async () => {
+        issuer = Keypair.generate();
+        attacker = Keypair.generate();
+        otherUser = Keypair.generate();
+
+        for (const kp of [issuer, attacker, otherUser]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+
+        treasury = PublicKey.findProgramAddressSync(
+            [Buffer.from("treasury"), issuer.publicKey.toBuffer()],
+            program.programId
+        )[0];
+    });
+
+    describe("Setup", () => {
+        it("Funds the issuer's treasury", async () => {
+            await program.methods
+                .initializeTreasury(new anchor.BN(2 * LAMPORTS_PER_SOL))
+                .accounts({ treasury, issuer: issuer.publicKey, systemProgram: SystemProgram.programId })
+                .signers([issuer])
+                .rpc();
+
+            const state = await program.account.treasury.fetch(treasury);
+            expect(state.issuer.toBase58()).to.equal(issuer.publicKey.toBase58());
+        });
+    });
+
+    describe("Exploit: bearer voucher replay", () => {
+        it("Redeems one signed voucher over and over", async () => {
+            const before = await provider.connection.getBalance(treasury);
+
+            for (let i = 0; i < REPLAYS; i++) {
+                await vulnerableRedeem(attacker, VOUCHER_AMOUNT, issuer, i);
+            }
+
+            const drained = before - (await provider.connection.getBalance(treasury));
+            console.log(`  One voucher for ${VOUCHER_AMOUNT} lamports paid out ${drained}`);
+            expect(drained).to.equal(REPLAYS * VOUCHER_AMOUNT);
+        });
+    });
+
+    describe("Secure: per-user nonce", () => {
+        it("Initializes nonce accounts", async () => {
+            await initNonce(attacker);
+            await initNonce(otherUser);
+
+            const nonce = await program.account.userNonce.fetch(noncePda(attacker.publicKey));
+            expect(nonce.next.toNumber()).to.equal(0);
+        });
+
+        it("Redeems a voucher exactly once", async () => {
+            await secureRedeem(attacker, attacker.publicKey, VOUCHER_AMOUNT, 0, issuer);
+
+            try {
+                await secureRedeem(attacker, attacker.publicKey, VOUCHER_AMOUNT, 0, issuer, 1);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("InvalidNonce");
+            }
+
+            const nonce = await program.account.userNonce.fetch(noncePda(attacker.publicKey));
+            expect(nonce.next.toNumber()).to.equal(1);
+        });
+
+        it("Accepts the next voucher in sequence", async () => {
+            await secureRedeem(attacker, attacker.publicKey, VOUCHER_AMOUNT, 1, issuer);
+
+            const nonce = await program.account.userNonce.fetch(noncePda(attacker.publicKey));
+            expect(nonce.next.toNumber()).to.equal(2);
+        });
+
+        it("Rejects another user's voucher", async () => {
+            try {
+                await secureRedeem(attacker, otherUser.publicKey, VOUCHER_AMOUNT, 2, issuer);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("WrongRecipient");
+            }
+        });
+
+        it("Rejects a voucher not signed by the issuer", async () => {
+            try {
+                await secureRedeem(attacker, attacker.publicKey, VOUCHER_AMOUNT, 2, attacker);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("WrongIssuer");
+            }
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Voucher Replay ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  verify issuer signed { recipient, amount }; pay amount");
+            console.log("  Risk: the same signed bytes are valid in every future transaction\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  signed { treasury, recipient, amount, nonce }; require nonce == next; next += 1");
+            console.log("  Protection: each voucher is consumed by the instruction that pays it\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  A valid signature proves who authorized it, not that it hasn't been used.");
+        });
+    });
+});
@@ -0,0 +1,288 @@
+use anchor_lang::prelude::*;
+
+declare_id!("CswSUXcEEVGNT9WBEDjhFMn9eXtXPCb5QFxyT5Zb9QyP");
+
+#[program]
+pub mod self_transfer_ledger {
+    use super::*;
+
+    pub fn initialize_ledger(ctx: Context<InitializeLedger>, ledger_id: u64) -> Result<()> {
+        let ledger = &mut ctx.accounts.ledger;
+        ledger.ledger_id = ledger_id;
+        ledger.authority = ctx.accounts.authority.key();
+        ledger.total_supply = 0;
+        ledger.bump = ctx.bumps.ledger;
+        Ok(())
+    }
+
+    pub fn open_entry(ctx: Context<OpenEntry>) -> Result<()> {
+        let entry = &mut ctx.accounts.entry;
+        entry.ledger = ctx.accounts.ledger.key();
+        entry.owner = ctx.accounts.owner.key();
+        entry.balance = 0;
+        entry.bump = ctx.bumps.entry;
+        Ok(())
+    }
+
+    pub fn mint(ctx: Context<Mint>, amount: u64) -> Result<()> {
+        let ledger = &mut ctx.accounts.ledger;
+        let entry = &mut ctx.accounts.entry;
+        ledger.total_supply = ledger
+            .total_supply
+            .checked_add(amount)
+            .ok_or(LedgerError::ArithmeticError)?;
+        entry.balance = entry.balance.checked_add(amount).ok_or(LedgerError::ArithmeticError)?;
+        Ok(())
+    }
+
+    // VULNERABLE: Source and Destination May Be the Same Entry
+    // 1. Nothing stops `from` and `to` from being the same account
+    // 2. Each `Account<Entry>` is its own deserialized copy, so when they
+    //    alias, the credit and the debit are applied to different copies
+    // 3. On exit Anchor writes `from` and then `to`: the credited copy is
+    //    written last and the debit is lost, minting `amount`
+    pub fn vulnerable_transfer(ctx: Context<VulnerableTransfer>, amount: u64) -> Result<()> {
+        let to = &mut ctx.accounts.to;
+        to.balance = to.balance.checked_add(amount).ok_or(LedgerError::ArithmeticError)?;
+
+        let from = &mut ctx.accounts.from;
+        from.balance = from
+            .balance
+            .checked_sub(amount)
+            .ok_or(LedgerError::InsufficientBalance)?;
+
+        msg!("Vulnerable transfer: {}", amount);
+        Ok(())
+    }
+
+    // SECURE: Distinct Entries, One Atomic Update
+    // 1. `from != to` is enforced in the accounts struct
+    // 2. Both new balances are computed with checked math before either is
+    //    written, so a failed debit leaves nothing half-applied
+    pub fn secure_transfer(ctx: Context<SecureTransfer>, amount: u64) -> Result<()> {
+        let (from_balance, to_balance) =
+            transfer_balances(ctx.accounts.from.balance, ctx.accounts.to.balance, amount)?;
+
+        ctx.accounts.from.balance = from_balance;
+        ctx.accounts.to.balance = to_balance;
+
+        msg!("Secure transfer: {}", amount);
+        Ok(())
+    }
+}
+
+/// Balances of two distinct entries after moving `amount` from the first to
+/// the second.
+pub fn transfer_balances(from: u64, to: u64, amount: u64) -> Result<(u64, u64)> {
+    let from = from.checked_sub(amount).ok_or(LedgerError::InsufficientBalance)?;
+    let to = to.checked_add(amount).ok_or(LedgerError::ArithmeticError)?;
+    Ok((from, to))
+}
+
+#[derive(Accounts)]
+#[instruction(ledger_id: u64)]
+pub struct InitializeLedger<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Ledger::INIT_SPACE,
+        seeds = [b"ledger", ledger_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub ledger: Account<'info, Ledger>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenEntry<'info> {
+    #[account(seeds = [b"ledger", ledger.ledger_id.to_le_bytes().as_ref()], bump = ledger.bump)]
+    pub ledger: Account<'info, Ledger>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Entry::INIT_SPACE,
+        seeds = [b"entry", ledger.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub entry: Account<'info, Entry>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Mint<'info> {
+    #[account(
+        mut,
+        seeds = [b"ledger", ledger.ledger_id.to_le_bytes().as_ref()],
+        bump = ledger.bump,
+        has_one = authority @ LedgerError::Unauthorized
+    )]
+    pub ledger: Account<'info, Ledger>,
+    #[account(mut, has_one = ledger)]
+    pub entry: Account<'info, Entry>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"entry", from.ledger.as_ref(), owner.key().as_ref()],
+        bump = from.bump,
+        has_one = owner @ LedgerError::Unauthorized
+    )]
+    pub from: Account<'info, Entry>,
+    // VULNERABLE: may be `from` itself
+    #[account(mut, constraint = to.ledger == from.ledger @ LedgerError::LedgerMismatch)]
+    pub to: Account<'info, Entry>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SecureTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"entry", from.ledger.as_ref(), owner.key().as_ref()],
+        bump = from.bump,
+        has_one = owner @ LedgerError::Unauthorized
+    )]
+    pub from: Account<'info, Entry>,
+    #[account(
+        mut,
+        constraint = to.key() != from.key() @ LedgerError::SameAccount,
+        constraint = to.ledger == from.ledger @ LedgerError::LedgerMismatch
+    )]
+    pub to: Account<'info, Entry>,
+    pub owner: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Ledger {
+    pub ledger_id: u64,
+    pub authority: Pubkey,
+    /// Sum of every entry's balance; only `mint` may change it.
+    pub total_supply: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Entry {
+    pub ledger: Pubkey,
+    pub owner: Pubkey,
+    pub balance: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum LedgerError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Source and destination must be different entries")]
+    SameAccount,
+    #[msg("Entries belong to different ledgers")]
+    LedgerMismatch,
+    #[msg("Insufficient balance")]
+    InsufficientBalance,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const ENTRIES: usize = 4;
+
+    /// Mirrors `secure_transfer` on an in-memory ledger.
+    fn secure_step(balances: &mut [u64], from: usize, to: usize, amount: u64) -> bool {
+        if from == to {
+            return false;
+        }
+        match transfer_balances(balances[from], balances[to], amount) {
+            Ok((f, t)) => {
+                balances[from] = f;
+                balances[to] = t;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Mirrors `vulnerable_transfer`: each account is a separate copy, and
+    /// copies are written back in field order (`from`, then `to`).
+    fn vulnerable_step(balances: &mut [u64], from: usize, to: usize, amount: u64) -> bool {
+        let (mut from_copy, mut to_copy) = (balances[from], balances[to]);
+        let Some(credited) = to_copy.checked_add(amount) else { return false };
+        to_copy = credited;
+        let Some(debited) = from_copy.checked_sub(amount) else { return false };
+        from_copy = debited;
+        balances[from] = from_copy;
+        balances[to] = to_copy;
+        true
+    }
+
+    fn supply(balances: &[u64]) -> u128 {
+        balances.iter().map(|&b| b as u128).sum()
+    }
+
+    fn transfers() -> impl Strategy<Value = Vec<(usize, usize, u64)>> {
+        prop::collection::vec((0..ENTRIES, 0..ENTRIES, 0..2_000u64), 0..64)
+    }
+
+    proptest! {
+        #[test]
+        fn secure_transfers_conserve_supply(
+            initial in prop::array::uniform4(0..1_000u64),
+            ops in transfers(),
+        ) {
+            let mut balances = initial;
+            let start = supply(&balances);
+            for (from, to, amount) in ops {
+                let before = balances;
+                let applied = secure_step(&mut balances, from, to, amount);
+                prop_assert_eq!(supply(&balances), start);
+                if !applied {
+                    prop_assert_eq!(balances, before);
+                }
+            }
+        }
+
+        #[test]
+        fn secure_rejects_self_transfer(balance in any::<u64>(), amount in any::<u64>()) {
+            let mut balances = [balance; ENTRIES];
+            prop_assert!(!secure_step(&mut balances, 1, 1, amount));
+            prop_assert_eq!(balances, [balance; ENTRIES]);
+        }
+
+        #[test]
+        fn vulnerable_self_transfer_mints(balance in 0..u64::MAX / 2, amount in 1..u64::MAX / 2) {
+            prop_assume!(amount <= balance);
+            let mut balances = [balance; ENTRIES];
+            let start = supply(&balances);
+            prop_assert!(vulnerable_step(&mut balances, 2, 2, amount));
+            prop_assert_eq!(supply(&balances), start + amount as u128);
+        }
+
+        #[test]
+        fn vulnerable_matches_secure_for_distinct_entries(
+            initial in prop::array::uniform4(0..1_000u64),
+            ops in transfers(),
+        ) {
+            let mut secure = initial;
+            let mut vulnerable = initial;
+            for (from, to, amount) in ops.into_iter().filter(|(f, t, _)| f != t) {
+                prop_assert_eq!(
+                    secure_step(&mut secure, from, to, amount),
+                    vulnerable_step(&mut vulnerable, from, to, amount)
+                );
+                prop_assert_eq!(secure, vulnerable);
+            }
+        }
+    }
+}
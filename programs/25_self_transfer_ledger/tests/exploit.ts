@@ -0,0 +1,157 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+
+describe("25: Self-Transfer Ledger", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.SelfTransferLedger as Program<any>;
+
+    let authority: Keypair;
+    let attacker: Keypair;
+    let victim: Keypair;
+
+    const LEDGER_ID = 1;
+    const INITIAL_BALANCE = 100;
+
+    let ledger: PublicKey;
+
+    function entryPda(owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("entry"), ledger.toBuffer(), owner.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    async function balance(owner: PublicKey): Promise<number> {
+        return (await program.account.entry.fetch(entryPda(owner))).balance.toNumber();
+    }
+
+    function transfer(
+        method: "vulnerableTransfer" | "secureTransfer",
+        owner: Keypair,
+        to: PublicKey,
+        amount: number
+    ) {
+        return program.methods[method](new anchor.BN(amount))
+            .accounts({ from: entryPda(owner.publicKey), to, owner: owner.publicKey })
+            .signers([owner])
+            .rpc();
+    }
+
+    before(async () => {
+        authority = Keypair.generate();
+        attacker = Keypair.generate();
+        victim = Keypair.generate();
+
+        for (const kp of [authority, attacker, victim]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 2 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+
+        const id = Buffer.alloc(8);
+        id.writeBigUInt64LE(BigInt(LEDGER_ID));
+        ledger = PublicKey.findProgramAddressSync([Buffer.from("ledger"), id], program.programId)[0];
+    });
+
+    describe("Setup", () => {
+        it("Creates a ledger with two funded entries", async () => {
+            await program.methods
+                .initializeLedger(new anchor.BN(LEDGER_ID))
+                .accounts({ ledger, authority: authority.publicKey, systemProgram: SystemProgram.programId })
+                .signers([authority])
+                .rpc();
+
+            for (const owner of [attacker, victim]) {
+                await program.methods
+                    .openEntry()
+                    .accounts({
+                        ledger,
+                        entry: entryPda(owner.publicKey),
+                        owner: owner.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([owner])
+                    .rpc();
+
+                await program.methods
+                    .mint(new anchor.BN(INITIAL_BALANCE))
+                    .accounts({ ledger, entry: entryPda(owner.publicKey), authority: authority.publicKey })
+                    .signers([authority])
+                    .rpc();
+            }
+
+            expect(await balance(attacker.publicKey)).to.equal(INITIAL_BALANCE);
+        });
+    });
+
+    describe("Exploit: transfer to self", () => {
+        it("Mints the transferred amount on every self-transfer", async () => {
+            const self = entryPda(attacker.publicKey);
+            await transfer("vulnerableTransfer", attacker, self, 100);
+            await transfer("vulnerableTransfer", attacker, self, 200);
+            await transfer("vulnerableTransfer", attacker, self, 400);
+
+            const after = await balance(attacker.publicKey);
+            const supply = (await program.account.ledger.fetch(ledger)).totalSupply.toNumber();
+            console.log(`  Attacker balance ${after}, recorded supply ${supply}`);
+            expect(after).to.equal(INITIAL_BALANCE * 8);
+            expect(after).to.be.greaterThan(supply);
+        });
+
+        it("Spends the minted balance on a real transfer", async () => {
+            await transfer("vulnerableTransfer", attacker, entryPda(victim.publicKey), 500);
+            expect(await balance(victim.publicKey)).to.equal(INITIAL_BALANCE + 500);
+        });
+    });
+
+    describe("Secure: distinct entries, atomic update", () => {
+        it("Rejects a self-transfer", async () => {
+            const before = await balance(victim.publicKey);
+            try {
+                await transfer("secureTransfer", victim, entryPda(victim.publicKey), 50);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("SameAccount");
+            }
+            expect(await balance(victim.publicKey)).to.equal(before);
+        });
+
+        it("Rejects an overdraft without touching either entry", async () => {
+            const before = [await balance(victim.publicKey), await balance(attacker.publicKey)];
+            try {
+                await transfer("secureTransfer", victim, entryPda(attacker.publicKey), 10_000);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("InsufficientBalance");
+            }
+            expect([await balance(victim.publicKey), await balance(attacker.publicKey)]).to.deep.equal(before);
+        });
+
+        it("Conserves the sum across a real transfer", async () => {
+            const before = (await balance(victim.publicKey)) + (await balance(attacker.publicKey));
+            await transfer("secureTransfer", victim, entryPda(attacker.publicKey), 50);
+            const after = (await balance(victim.publicKey)) + (await balance(attacker.publicKey));
+            expect(after).to.equal(before);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Self-Transfer Ledger ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  to.balance += amount; from.balance -= amount;  // from may equal to");
+            console.log("  Risk: aliased copies, last write wins, the debit disappears\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  constraint = to.key() != from.key(); compute both balances, then write");
+            console.log("  Protection: no aliasing, no half-applied updates\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Two account slots are not two accounts until you check the keys.");
+        });
+    });
+});
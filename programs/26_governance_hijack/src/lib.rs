@@ -0,0 +1,333 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use security_common::time::{ClockExt, UnixTs};
+
+declare_id!("4YqcYUkn3SvMyn4bFrz6zWTi8Lt1TVk8vaSNWxNSaeR7");
+
+#[program]
+pub mod governance_hijack {
+    use super::*;
+
+    pub fn initialize_realm(
+        ctx: Context<InitializeRealm>,
+        realm_id: u64,
+        quorum_votes: u64,
+        voting_period_secs: i64,
+        treasury_funding: u64,
+    ) -> Result<()> {
+        require!(voting_period_secs > 0, GovernanceError::InvalidVotingPeriod);
+
+        let realm = &mut ctx.accounts.realm;
+        realm.realm_id = realm_id;
+        realm.authority = ctx.accounts.authority.key();
+        realm.quorum_votes = quorum_votes;
+        realm.voting_period_secs = voting_period_secs;
+        realm.proposal_count = 0;
+        realm.bump = ctx.bumps.realm;
+
+        // The realm PDA's own lamports are the treasury proposals spend.
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.realm.to_account_info(),
+                },
+            ),
+            treasury_funding,
+        )
+    }
+
+    pub fn add_member(ctx: Context<AddMember>, wallet: Pubkey, weight: u64) -> Result<()> {
+        let member = &mut ctx.accounts.member;
+        member.realm = ctx.accounts.realm.key();
+        member.wallet = wallet;
+        member.weight = weight;
+        member.bump = ctx.bumps.member;
+        Ok(())
+    }
+
+    pub fn create_proposal(ctx: Context<CreateProposal>, recipient: Pubkey, amount: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let realm = &mut ctx.accounts.realm;
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.realm = realm.key();
+        proposal.proposal_id = realm.proposal_count;
+        proposal.proposer = ctx.accounts.proposer.key();
+        proposal.recipient = recipient;
+        proposal.amount = amount;
+        proposal.yes_votes = 0;
+        proposal.no_votes = 0;
+        proposal.voting_ends_at = now
+            .checked_add(realm.voting_period_secs)
+            .ok_or(GovernanceError::ArithmeticError)?;
+        proposal.state = ProposalState::Voting;
+        proposal.bump = ctx.bumps.proposal;
+
+        realm.proposal_count = realm
+            .proposal_count
+            .checked_add(1)
+            .ok_or(GovernanceError::ArithmeticError)?;
+        Ok(())
+    }
+
+    pub fn cast_vote(ctx: Context<CastVote>, approve: bool) -> Result<()> {
+        let now = Clock::get()?.current_unix_ts();
+        let proposal = &mut ctx.accounts.proposal;
+        require!(proposal.state == ProposalState::Voting, GovernanceError::NotVoting);
+        require!(now < UnixTs(proposal.voting_ends_at), GovernanceError::VotingEnded);
+
+        let weight = ctx.accounts.member.weight;
+        let tally = if approve { &mut proposal.yes_votes } else { &mut proposal.no_votes };
+        *tally = tally.checked_add(weight).ok_or(GovernanceError::ArithmeticError)?;
+
+        ctx.accounts.vote_record.bump = ctx.bumps.vote_record;
+        Ok(())
+    }
+
+    // VULNERABLE: Execute Trusts the Proposal Account
+    // 1. The proposal is never tied to this realm, so a proposal passed in
+    //    an attacker-controlled realm can spend this realm's treasury
+    // 2. Quorum is not checked: one yes vote against zero no votes passes
+    // 3. The voting period is not checked: execution can follow the first
+    //    vote in the same slot, before anyone else can vote no
+    pub fn vulnerable_execute(ctx: Context<VulnerableExecute>) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        require!(proposal.state == ProposalState::Voting, GovernanceError::NotVoting);
+        require!(proposal.yes_votes > proposal.no_votes, GovernanceError::ProposalDefeated);
+
+        proposal.state = ProposalState::Executed;
+        let amount = proposal.amount;
+        move_lamports(
+            &ctx.accounts.realm.to_account_info(),
+            &ctx.accounts.recipient.to_account_info(),
+            amount,
+        )?;
+
+        msg!("Vulnerable execute: paid {}", amount);
+        Ok(())
+    }
+
+    // SECURE: Full State-Machine Validation
+    // 1. `has_one = realm`: only this realm's proposals can spend its treasury
+    // 2. The proposal must still be in `Voting`
+    // 3. The voting period must have ended
+    // 4. Yes votes must reach quorum and beat no votes
+    // 5. State moves to `Executed` before funds move
+    pub fn secure_execute(ctx: Context<SecureExecute>) -> Result<()> {
+        let now = Clock::get()?.current_unix_ts();
+        let quorum = ctx.accounts.realm.quorum_votes;
+        let proposal = &mut ctx.accounts.proposal;
+
+        require!(proposal.state == ProposalState::Voting, GovernanceError::NotVoting);
+        require!(now >= UnixTs(proposal.voting_ends_at), GovernanceError::VotingNotEnded);
+        require!(proposal.yes_votes >= quorum, GovernanceError::QuorumNotReached);
+        require!(proposal.yes_votes > proposal.no_votes, GovernanceError::ProposalDefeated);
+
+        proposal.state = ProposalState::Executed;
+        let amount = proposal.amount;
+        move_lamports(
+            &ctx.accounts.realm.to_account_info(),
+            &ctx.accounts.recipient.to_account_info(),
+            amount,
+        )?;
+
+        msg!("Secure execute: paid {}", amount);
+        Ok(())
+    }
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(GovernanceError::InsufficientTreasury)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(GovernanceError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(realm_id: u64)]
+pub struct InitializeRealm<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Realm::INIT_SPACE,
+        seeds = [b"realm", realm_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub realm: Account<'info, Realm>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct AddMember<'info> {
+    #[account(
+        seeds = [b"realm", realm.realm_id.to_le_bytes().as_ref()],
+        bump = realm.bump,
+        has_one = authority @ GovernanceError::Unauthorized
+    )]
+    pub realm: Account<'info, Realm>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Member::INIT_SPACE,
+        seeds = [b"member", realm.key().as_ref(), wallet.as_ref()],
+        bump
+    )]
+    pub member: Account<'info, Member>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(mut, seeds = [b"realm", realm.realm_id.to_le_bytes().as_ref()], bump = realm.bump)]
+    pub realm: Account<'info, Realm>,
+    #[account(
+        seeds = [b"member", realm.key().as_ref(), proposer.key().as_ref()],
+        bump = member.bump
+    )]
+    pub member: Account<'info, Member>,
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + Proposal::INIT_SPACE,
+        seeds = [b"proposal", realm.key().as_ref(), realm.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+    #[account(
+        seeds = [b"member", proposal.realm.as_ref(), voter.key().as_ref()],
+        bump = member.bump
+    )]
+    pub member: Account<'info, Member>,
+    /// One vote per member per proposal: a second `init` fails.
+    #[account(
+        init,
+        payer = voter,
+        space = 8 + VoteRecord::INIT_SPACE,
+        seeds = [b"vote", proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableExecute<'info> {
+    #[account(mut, seeds = [b"realm", realm.realm_id.to_le_bytes().as_ref()], bump = realm.bump)]
+    pub realm: Account<'info, Realm>,
+    // VULNERABLE: any proposal owned by this program, from any realm
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+    /// CHECK: Must match the proposal's recipient
+    #[account(mut, address = proposal.recipient @ GovernanceError::WrongRecipient)]
+    pub recipient: UncheckedAccount<'info>,
+    pub executor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SecureExecute<'info> {
+    #[account(mut, seeds = [b"realm", realm.realm_id.to_le_bytes().as_ref()], bump = realm.bump)]
+    pub realm: Account<'info, Realm>,
+    #[account(mut, has_one = realm @ GovernanceError::RealmMismatch)]
+    pub proposal: Account<'info, Proposal>,
+    /// CHECK: Must match the proposal's recipient
+    #[account(mut, address = proposal.recipient @ GovernanceError::WrongRecipient)]
+    pub recipient: UncheckedAccount<'info>,
+    pub executor: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Realm {
+    pub realm_id: u64,
+    pub authority: Pubkey,
+    /// Minimum yes-vote weight for a proposal to pass.
+    pub quorum_votes: u64,
+    pub voting_period_secs: i64,
+    pub proposal_count: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Member {
+    pub realm: Pubkey,
+    pub wallet: Pubkey,
+    pub weight: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Proposal {
+    pub realm: Pubkey,
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+    pub voting_ends_at: i64,
+    pub state: ProposalState,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct VoteRecord {
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ProposalState {
+    Voting,
+    Executed,
+}
+
+#[error_code]
+pub enum GovernanceError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Voting period must be positive")]
+    InvalidVotingPeriod,
+    #[msg("Proposal is not in the voting state")]
+    NotVoting,
+    #[msg("Voting period has ended")]
+    VotingEnded,
+    #[msg("Voting period has not ended")]
+    VotingNotEnded,
+    #[msg("Proposal did not reach quorum")]
+    QuorumNotReached,
+    #[msg("Proposal was defeated")]
+    ProposalDefeated,
+    #[msg("Proposal belongs to a different realm")]
+    RealmMismatch,
+    #[msg("Recipient does not match the proposal")]
+    WrongRecipient,
+    #[msg("Treasury has insufficient funds")]
+    InsufficientTreasury,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
@@ -0,0 +1,242 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { sleep } from "../../../tests/utils";
+
+describe("26: Governance Proposal Hijack", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.GovernanceHijack as Program<any>;
+
+    let daoAdmin: Keypair;
+    let whale: Keypair;
+    let attacker: Keypair;
+
+    const DAO_REALM_ID = 1;
+    const ATTACKER_REALM_ID = 2;
+    const QUORUM = 100;
+    const VOTING_PERIOD_SECS = 2;
+    const TREASURY = 2 * LAMPORTS_PER_SOL;
+    const PAYOUT = LAMPORTS_PER_SOL / 5;
+
+    function u64(value: number): Buffer {
+        const buf = Buffer.alloc(8);
+        buf.writeBigUInt64LE(BigInt(value));
+        return buf;
+    }
+
+    function realmPda(realmId: number): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("realm"), u64(realmId)], program.programId)[0];
+    }
+
+    function memberPda(realm: PublicKey, wallet: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("member"), realm.toBuffer(), wallet.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    async function initRealm(realmId: number, authority: Keypair, quorum: number, funding: number) {
+        await program.methods
+            .initializeRealm(
+                new anchor.BN(realmId),
+                new anchor.BN(quorum),
+                new anchor.BN(VOTING_PERIOD_SECS),
+                new anchor.BN(funding)
+            )
+            .accounts({
+                realm: realmPda(realmId),
+                authority: authority.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([authority])
+            .rpc();
+    }
+
+    async function addMember(realmId: number, authority: Keypair, wallet: PublicKey, weight: number) {
+        const realm = realmPda(realmId);
+        await program.methods
+            .addMember(wallet, new anchor.BN(weight))
+            .accounts({
+                realm,
+                member: memberPda(realm, wallet),
+                authority: authority.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([authority])
+            .rpc();
+    }
+
+    async function propose(realmId: number, proposer: Keypair, recipient: PublicKey, amount: number) {
+        const realm = realmPda(realmId);
+        const count = (await program.account.realm.fetch(realm)).proposalCount.toNumber();
+        const proposal = PublicKey.findProgramAddressSync(
+            [Buffer.from("proposal"), realm.toBuffer(), u64(count)],
+            program.programId
+        )[0];
+
+        await program.methods
+            .createProposal(recipient, new anchor.BN(amount))
+            .accounts({
+                realm,
+                member: memberPda(realm, proposer.publicKey),
+                proposal,
+                proposer: proposer.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([proposer])
+            .rpc();
+        return proposal;
+    }
+
+    async function vote(proposal: PublicKey, voter: Keypair, approve: boolean) {
+        const { realm } = await program.account.proposal.fetch(proposal);
+        const voteRecord = PublicKey.findProgramAddressSync(
+            [Buffer.from("vote"), proposal.toBuffer(), voter.publicKey.toBuffer()],
+            program.programId
+        )[0];
+
+        await program.methods
+            .castVote(approve)
+            .accounts({
+                proposal,
+                member: memberPda(realm, voter.publicKey),
+                voteRecord,
+                voter: voter.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([voter])
+            .rpc();
+    }
+
+    async function execute(method: "vulnerableExecute" | "secureExecute", realmId: number, proposal: PublicKey) {
+        const { recipient } = await program.account.proposal.fetch(proposal);
+        await program.methods[method]()
+            .accounts({ realm: realmPda(realmId), proposal, recipient, executor: attacker.publicKey })
+            .signers([attacker])
+            .rpc();
+    }
+
+    function treasury(): Promise<number> {
+        return provider.connection.getBalance(realmPda(DAO_REALM_ID));
+    }
+
+    before(async () => {
+        daoAdmin = Keypair.generate();
+        whale = Keypair.generate();
+        attacker = Keypair.generate();
+
+        for (const kp of [daoAdmin, whale, attacker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates the DAO realm and the attacker's own realm", async () => {
+            await initRealm(DAO_REALM_ID, daoAdmin, QUORUM, TREASURY);
+            await addMember(DAO_REALM_ID, daoAdmin, whale.publicKey, QUORUM);
+            await addMember(DAO_REALM_ID, daoAdmin, attacker.publicKey, 1);
+
+            // Anyone can create a realm and give themselves unlimited weight.
+            await initRealm(ATTACKER_REALM_ID, attacker, 1, 0);
+            await addMember(ATTACKER_REALM_ID, attacker, attacker.publicKey, 1_000_000);
+
+            const realm = await program.account.realm.fetch(realmPda(DAO_REALM_ID));
+            expect(realm.quorumVotes.toNumber()).to.equal(QUORUM);
+        });
+    });
+
+    describe("Exploit: execute without state-machine checks", () => {
+        it("Executes a 1-vote proposal immediately, below quorum", async () => {
+            const before = await treasury();
+            const proposal = await propose(DAO_REALM_ID, attacker, attacker.publicKey, PAYOUT);
+            await vote(proposal, attacker, true);
+            await execute("vulnerableExecute", DAO_REALM_ID, proposal);
+
+            console.log(`  Paid ${before - (await treasury())} lamports on 1 of ${QUORUM} quorum votes`);
+            expect(before - (await treasury())).to.equal(PAYOUT);
+        });
+
+        it("Spends the DAO treasury with a proposal passed in another realm", async () => {
+            const before = await treasury();
+            const foreign = await propose(ATTACKER_REALM_ID, attacker, attacker.publicKey, PAYOUT);
+            await vote(foreign, attacker, true);
+            await execute("vulnerableExecute", DAO_REALM_ID, foreign);
+
+            console.log(`  Foreign proposal paid ${before - (await treasury())} from the DAO treasury`);
+            expect(before - (await treasury())).to.equal(PAYOUT);
+        });
+    });
+
+    describe("Secure: full state-machine validation", () => {
+        it("Rejects a proposal from another realm", async () => {
+            const foreign = await propose(ATTACKER_REALM_ID, attacker, attacker.publicKey, PAYOUT);
+            await vote(foreign, attacker, true);
+
+            try {
+                await execute("secureExecute", DAO_REALM_ID, foreign);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("RealmMismatch");
+            }
+        });
+
+        it("Rejects execution before voting ends, then below quorum", async () => {
+            const proposal = await propose(DAO_REALM_ID, attacker, attacker.publicKey, PAYOUT);
+            await vote(proposal, attacker, true);
+
+            try {
+                await execute("secureExecute", DAO_REALM_ID, proposal);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("VotingNotEnded");
+            }
+
+            await sleep(VOTING_PERIOD_SECS + 1);
+
+            try {
+                await execute("secureExecute", DAO_REALM_ID, proposal);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("QuorumNotReached");
+            }
+        });
+
+        it("Executes a passed proposal exactly once", async () => {
+            const proposal = await propose(DAO_REALM_ID, whale, whale.publicKey, PAYOUT);
+            await vote(proposal, whale, true);
+            await sleep(VOTING_PERIOD_SECS + 1);
+
+            const before = await treasury();
+            await execute("secureExecute", DAO_REALM_ID, proposal);
+            expect(before - (await treasury())).to.equal(PAYOUT);
+
+            try {
+                await execute("secureExecute", DAO_REALM_ID, proposal);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("NotVoting");
+            }
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Governance Proposal Hijack ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  execute(realm, proposal): require!(yes > no); pay proposal.amount");
+            console.log("  Risk: foreign proposals, no quorum, execution before anyone can vote no\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  has_one = realm; state == Voting; now >= voting_ends_at; yes >= quorum; yes > no");
+            console.log("  Protection: only a proposal that completed this realm's process can execute\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Execute is where governance is enforced; every stage before it is advisory.");
+        });
+    });
+});
@@ -0,0 +1,306 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("H8zYzKjaihEN7iTAij6Lbd3e6M19rxzwwwNxts5QWm1K");
+
+/// Bytes of bitmap per page; a literal so the IDL can size the array.
+const PAGE_BYTES: usize = 128;
+/// Work items tracked by one bitmap page.
+pub const ITEMS_PER_PAGE: u64 = PAGE_BYTES as u64 * 8;
+
+#[program]
+pub mod replayable_crank {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>, network_id: u64, reward: u64, funding: u64) -> Result<()> {
+        let network = &mut ctx.accounts.network;
+        network.network_id = network_id;
+        network.authority = ctx.accounts.authority.key();
+        network.reward = reward;
+        network.posted_items = 0;
+        network.last_observation = 0;
+        network.bump = ctx.bumps.network;
+
+        // Rewards are paid from the network PDA's own lamports.
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.network.to_account_info(),
+                },
+            ),
+            funding,
+        )
+    }
+
+    /// Makes the next `count` work items available to keepers.
+    pub fn post_work(ctx: Context<PostWork>, count: u64) -> Result<()> {
+        let network = &mut ctx.accounts.network;
+        network.posted_items = network
+            .posted_items
+            .checked_add(count)
+            .ok_or(CrankError::ArithmeticError)?;
+        Ok(())
+    }
+
+    pub fn init_bitmap_page(ctx: Context<InitBitmapPage>, page: u64) -> Result<()> {
+        let bitmap = &mut ctx.accounts.bitmap;
+        bitmap.network = ctx.accounts.network.key();
+        bitmap.page = page;
+        bitmap.bits = [0; PAGE_BYTES];
+        bitmap.bump = ctx.bumps.bitmap;
+        Ok(())
+    }
+
+    // VULNERABLE: Payout Not Tied to Consumed Work
+    // 1. The item only has to exist; nothing records that it was cranked
+    // 2. A keeper re-submits the same (item, observation) and is paid again
+    // 3. The treasury pays out once per call, not once per unit of work
+    pub fn vulnerable_crank(ctx: Context<VulnerableCrank>, item_id: u64, observation: u64) -> Result<()> {
+        let network = &mut ctx.accounts.network;
+        require!(item_id < network.posted_items, CrankError::UnknownItem);
+        network.last_observation = observation;
+
+        let reward = network.reward;
+        move_lamports(
+            &ctx.accounts.network.to_account_info(),
+            &ctx.accounts.keeper.to_account_info(),
+            reward,
+        )?;
+
+        msg!("Vulnerable crank: item {} paid {}", item_id, reward);
+        Ok(())
+    }
+
+    // SECURE: Work Items Consumed in a PDA Bitmap
+    // 1. The bitmap page is derived from `item_id`, so each item has exactly
+    //    one bit in exactly one account
+    // 2. The bit must be clear; it is set before the reward moves
+    // 3. A replay finds the bit set and is rejected
+    pub fn secure_crank(ctx: Context<SecureCrank>, item_id: u64, observation: u64) -> Result<()> {
+        require!(item_id < ctx.accounts.network.posted_items, CrankError::UnknownItem);
+        ctx.accounts.bitmap.consume(item_id)?;
+
+        let network = &mut ctx.accounts.network;
+        network.last_observation = observation;
+
+        let reward = network.reward;
+        move_lamports(
+            &ctx.accounts.network.to_account_info(),
+            &ctx.accounts.keeper.to_account_info(),
+            reward,
+        )?;
+
+        msg!("Secure crank: item {} paid {}", item_id, reward);
+        Ok(())
+    }
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(CrankError::InsufficientFunds)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(CrankError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(network_id: u64)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Network::INIT_SPACE,
+        seeds = [b"network", network_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub network: Account<'info, Network>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PostWork<'info> {
+    #[account(
+        mut,
+        seeds = [b"network", network.network_id.to_le_bytes().as_ref()],
+        bump = network.bump,
+        has_one = authority @ CrankError::Unauthorized
+    )]
+    pub network: Account<'info, Network>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(page: u64)]
+pub struct InitBitmapPage<'info> {
+    #[account(seeds = [b"network", network.network_id.to_le_bytes().as_ref()], bump = network.bump)]
+    pub network: Account<'info, Network>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + WorkBitmap::INIT_SPACE,
+        seeds = [b"bitmap", network.key().as_ref(), page.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub bitmap: Account<'info, WorkBitmap>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableCrank<'info> {
+    #[account(
+        mut,
+        seeds = [b"network", network.network_id.to_le_bytes().as_ref()],
+        bump = network.bump
+    )]
+    pub network: Account<'info, Network>,
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(item_id: u64)]
+pub struct SecureCrank<'info> {
+    #[account(
+        mut,
+        seeds = [b"network", network.network_id.to_le_bytes().as_ref()],
+        bump = network.bump
+    )]
+    pub network: Account<'info, Network>,
+    #[account(
+        mut,
+        seeds = [
+            b"bitmap",
+            network.key().as_ref(),
+            (item_id / ITEMS_PER_PAGE).to_le_bytes().as_ref()
+        ],
+        bump = bitmap.bump
+    )]
+    pub bitmap: Account<'info, WorkBitmap>,
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Network {
+    pub network_id: u64,
+    pub authority: Pubkey,
+    /// Lamports paid per cranked work item.
+    pub reward: u64,
+    /// Items `0..posted_items` may be cranked.
+    pub posted_items: u64,
+    pub last_observation: u64,
+    pub bump: u8,
+}
+
+/// One bit per work item for items
+/// `page * ITEMS_PER_PAGE..(page + 1) * ITEMS_PER_PAGE`.
+#[account]
+#[derive(InitSpace)]
+pub struct WorkBitmap {
+    pub network: Pubkey,
+    pub page: u64,
+    pub bits: [u8; PAGE_BYTES],
+    pub bump: u8,
+}
+
+impl WorkBitmap {
+    fn position(&self, item_id: u64) -> Result<(usize, u8)> {
+        require!(item_id / ITEMS_PER_PAGE == self.page, CrankError::ItemOutOfRange);
+        let offset = item_id % ITEMS_PER_PAGE;
+        Ok(((offset / 8) as usize, 1 << (offset % 8)))
+    }
+
+    pub fn is_consumed(&self, item_id: u64) -> Result<bool> {
+        let (byte, mask) = self.position(item_id)?;
+        Ok(self.bits[byte] & mask != 0)
+    }
+
+    /// Marks `item_id` consumed, failing if it already was.
+    pub fn consume(&mut self, item_id: u64) -> Result<()> {
+        let (byte, mask) = self.position(item_id)?;
+        require!(self.bits[byte] & mask == 0, CrankError::AlreadyConsumed);
+        self.bits[byte] |= mask;
+        Ok(())
+    }
+}
+
+#[error_code]
+pub enum CrankError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Work item has not been posted")]
+    UnknownItem,
+    #[msg("Work item is not tracked by this bitmap page")]
+    ItemOutOfRange,
+    #[msg("Work item has already been cranked")]
+    AlreadyConsumed,
+    #[msg("Network has insufficient funds")]
+    InsufficientFunds,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(page: u64) -> WorkBitmap {
+        WorkBitmap {
+            network: Pubkey::default(),
+            page,
+            bits: [0; PAGE_BYTES],
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn page_holds_exactly_items_per_page() {
+        let mut bitmap = page(3);
+        let first = 3 * ITEMS_PER_PAGE;
+        for item in first..first + ITEMS_PER_PAGE {
+            bitmap.consume(item).unwrap();
+        }
+        assert!(bitmap.bits.iter().all(|b| *b == u8::MAX));
+        assert!(bitmap.consume(first - 1).is_err());
+        assert!(bitmap.consume(first + ITEMS_PER_PAGE).is_err());
+    }
+
+    #[test]
+    fn each_item_consumes_once() {
+        let mut bitmap = page(0);
+        for item in [0, 7, 8, ITEMS_PER_PAGE - 1] {
+            assert!(!bitmap.is_consumed(item).unwrap());
+            bitmap.consume(item).unwrap();
+            assert!(bitmap.is_consumed(item).unwrap());
+            assert!(bitmap.consume(item).is_err());
+        }
+    }
+
+    #[test]
+    fn items_do_not_share_bits() {
+        let mut bitmap = page(0);
+        bitmap.consume(9).unwrap();
+        for item in (0..ITEMS_PER_PAGE).filter(|i| *i != 9) {
+            assert!(!bitmap.is_consumed(item).unwrap(), "item {item}");
+        }
+    }
+
+    #[test]
+    fn last_page_is_addressable() {
+        let last = u64::MAX / ITEMS_PER_PAGE;
+        let mut bitmap = page(last);
+        bitmap.consume(u64::MAX).unwrap();
+        assert!(bitmap.is_consumed(u64::MAX).unwrap());
+    }
+}
@@ -0,0 +1,189 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ComputeBudgetProgram, Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+
+describe("27: Replayable Crank Rewards", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ReplayableCrank as Program<any>;
+
+    let authority: Keypair;
+    let keeper: Keypair;
+    let network: PublicKey;
+
+    const NETWORK_ID = 1;
+    const REWARD = LAMPORTS_PER_SOL / 100;
+    const ITEMS_PER_PAGE = 1024;
+    const POSTED_ITEMS = ITEMS_PER_PAGE + 100;
+    const REPLAYS = 5;
+
+    function u64(value: number): Buffer {
+        const buf = Buffer.alloc(8);
+        buf.writeBigUInt64LE(BigInt(value));
+        return buf;
+    }
+
+    function bitmapPda(page: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("bitmap"), network.toBuffer(), u64(page)],
+            program.programId
+        )[0];
+    }
+
+    // Identical transactions are deduplicated by the runtime; vary the
+    // compute limit so each replay is a distinct transaction.
+    function uniquifier(i: number) {
+        return ComputeBudgetProgram.setComputeUnitLimit({ units: 200_000 + i });
+    }
+
+    function vulnerableCrank(itemId: number, observation: number, i = 0) {
+        return program.methods
+            .vulnerableCrank(new anchor.BN(itemId), new anchor.BN(observation))
+            .accounts({ network, keeper: keeper.publicKey })
+            .preInstructions([uniquifier(i)])
+            .signers([keeper])
+            .rpc();
+    }
+
+    function secureCrank(itemId: number, observation: number, page = Math.floor(itemId / ITEMS_PER_PAGE), i = 0) {
+        return program.methods
+            .secureCrank(new anchor.BN(itemId), new anchor.BN(observation))
+            .accounts({ network, bitmap: bitmapPda(page), keeper: keeper.publicKey })
+            .preInstructions([uniquifier(i)])
+            .signers([keeper])
+            .rpc();
+    }
+
+    async function initPage(page: number) {
+        await program.methods
+            .initBitmapPage(new anchor.BN(page))
+            .accounts({
+                network,
+                bitmap: bitmapPda(page),
+                payer: authority.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([authority])
+            .rpc();
+    }
+
+    function treasury(): Promise<number> {
+        return provider.connection.getBalance(network);
+    }
+
+    before(async () => {
+        authority = Keypair.generate();
+        keeper = Keypair.generate();
+
+        for (const kp of [authority, keeper]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+
+        network = PublicKey.findProgramAddressSync([Buffer.from("network"), u64(NETWORK_ID)], program.programId)[0];
+    });
+
+    describe("Setup", () => {
+        it("Funds a keeper network and posts work", async () => {
+            await program.methods
+                .initialize(new anchor.BN(NETWORK_ID), new anchor.BN(REWARD), new anchor.BN(LAMPORTS_PER_SOL))
+                .accounts({ network, authority: authority.publicKey, systemProgram: SystemProgram.programId })
+                .signers([authority])
+                .rpc();
+
+            await program.methods
+                .postWork(new anchor.BN(POSTED_ITEMS))
+                .accounts({ network, authority: authority.publicKey })
+                .signers([authority])
+                .rpc();
+
+            const state = await program.account.network.fetch(network);
+            expect(state.postedItems.toNumber()).to.equal(POSTED_ITEMS);
+        });
+    });
+
+    describe("Exploit: resubmit the same work", () => {
+        it("Pays a keeper repeatedly for one work item", async () => {
+            const before = await treasury();
+
+            for (let i = 0; i < REPLAYS; i++) {
+                await vulnerableCrank(0, 42, i);
+            }
+
+            const paid = before - (await treasury());
+            console.log(`  One work item paid ${paid / REWARD} rewards`);
+            expect(paid).to.equal(REPLAYS * REWARD);
+        });
+    });
+
+    describe("Secure: consumed-work bitmap", () => {
+        it("Pays once per work item", async () => {
+            await initPage(0);
+            const before = await treasury();
+
+            await secureCrank(0, 42);
+            try {
+                await secureCrank(0, 42, 0, 1);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("AlreadyConsumed");
+            }
+
+            expect(before - (await treasury())).to.equal(REWARD);
+        });
+
+        it("Tracks the last item of a page", async () => {
+            await secureCrank(ITEMS_PER_PAGE - 1, 7);
+
+            const bitmap = await program.account.workBitmap.fetch(bitmapPda(0));
+            expect(bitmap.bits[0]).to.equal(0b0000_0001);
+            expect(bitmap.bits[ITEMS_PER_PAGE / 8 - 1]).to.equal(0b1000_0000);
+        });
+
+        it("Rejects an item outside the supplied page", async () => {
+            try {
+                await secureCrank(ITEMS_PER_PAGE, 7, 0);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("ConstraintSeeds");
+            }
+        });
+
+        it("Continues on the next page", async () => {
+            await initPage(1);
+            await secureCrank(ITEMS_PER_PAGE, 7);
+
+            const bitmap = await program.account.workBitmap.fetch(bitmapPda(1));
+            expect(bitmap.page.toNumber()).to.equal(1);
+            expect(bitmap.bits[0]).to.equal(0b0000_0001);
+        });
+
+        it("Rejects work that was never posted", async () => {
+            try {
+                await secureCrank(POSTED_ITEMS, 7);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("UnknownItem");
+            }
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Replayable Crank Rewards ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  require!(item_id < posted_items); pay(reward)");
+            console.log("  Risk: the same observation is paid every time it is submitted\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  bitmap page = item_id / 1024; require!(bit clear); set bit; pay(reward)");
+            console.log("  Protection: one bit per work item, set before payout\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Pay for work consumed, not for calls made.");
+        });
+    });
+});
@@ -0,0 +1,222 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("9gThzguhxTKYbiKP2WECeEWwZPvHqHMKZX9rL9qXTn9w");
+
+#[program]
+pub mod account_label_swap {
+    use super::*;
+
+    /// Maker escrows `total` tokens: `taker_payout` is the taker's fee on
+    /// settlement, the rest is refunded to the maker.
+    pub fn open_deal(
+        ctx: Context<OpenDeal>,
+        deal_id: u64,
+        taker: Pubkey,
+        total: u64,
+        taker_payout: u64,
+    ) -> Result<()> {
+        let maker_refund = total.checked_sub(taker_payout).ok_or(DealError::PayoutExceedsTotal)?;
+
+        let deal = &mut ctx.accounts.deal;
+        deal.deal_id = deal_id;
+        deal.maker = ctx.accounts.maker.key();
+        deal.taker = taker;
+        deal.mint = ctx.accounts.mint.key();
+        deal.maker_refund = maker_refund;
+        deal.taker_payout = taker_payout;
+        deal.settled = false;
+        deal.bump = ctx.bumps.deal;
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.maker_token_account.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.maker.to_account_info(),
+                },
+            ),
+            total,
+        )
+    }
+
+    // VULNERABLE: Accounts Distinguished Only by Their Labels
+    // 1. `maker_receive_account` and `taker_receive_account` have identical
+    //    constraints: "a token account of the deal's mint"
+    // 2. Which is which comes from the position in the instruction's account
+    //    list, which the caller controls
+    // 3. A taker who builds the instruction by hand swaps the two and
+    //    collects the maker's refund instead of their own payout
+    pub fn vulnerable_settle(ctx: Context<VulnerableSettle>) -> Result<()> {
+        let deal = &ctx.accounts.deal;
+        require!(!deal.settled, DealError::AlreadySettled);
+
+        let (maker_refund, taker_payout) = (deal.maker_refund, deal.taker_payout);
+        pay_out(
+            &ctx.accounts.deal,
+            &ctx.accounts.vault,
+            &ctx.accounts.maker_receive_account,
+            &ctx.accounts.token_program,
+            maker_refund,
+        )?;
+        pay_out(
+            &ctx.accounts.deal,
+            &ctx.accounts.vault,
+            &ctx.accounts.taker_receive_account,
+            &ctx.accounts.token_program,
+            taker_payout,
+        )?;
+
+        ctx.accounts.deal.settled = true;
+        msg!("Vulnerable settle: refund {}, payout {}", maker_refund, taker_payout);
+        Ok(())
+    }
+
+    // SECURE: Each Account Bound to Its Party
+    // 1. `maker_receive_account` must be owned by `deal.maker`
+    // 2. `taker_receive_account` must be owned by `deal.taker`
+    // 3. Swapping them fails the owner constraint regardless of how the
+    //    instruction was built
+    pub fn secure_settle(ctx: Context<SecureSettle>) -> Result<()> {
+        let deal = &ctx.accounts.deal;
+        require!(!deal.settled, DealError::AlreadySettled);
+
+        let (maker_refund, taker_payout) = (deal.maker_refund, deal.taker_payout);
+        pay_out(
+            &ctx.accounts.deal,
+            &ctx.accounts.vault,
+            &ctx.accounts.maker_receive_account,
+            &ctx.accounts.token_program,
+            maker_refund,
+        )?;
+        pay_out(
+            &ctx.accounts.deal,
+            &ctx.accounts.vault,
+            &ctx.accounts.taker_receive_account,
+            &ctx.accounts.token_program,
+            taker_payout,
+        )?;
+
+        ctx.accounts.deal.settled = true;
+        msg!("Secure settle: refund {}, payout {}", maker_refund, taker_payout);
+        Ok(())
+    }
+}
+
+fn pay_out<'info>(
+    deal: &Account<'info, Deal>,
+    vault: &Account<'info, TokenAccount>,
+    to: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let deal_id = deal.deal_id.to_le_bytes();
+    let seeds: &[&[u8]] = &[b"deal", deal.maker.as_ref(), deal_id.as_ref(), &[deal.bump]];
+    let signer = &[seeds];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: vault.to_account_info(),
+                to: to.to_account_info(),
+                authority: deal.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(deal_id: u64)]
+pub struct OpenDeal<'info> {
+    #[account(
+        init,
+        payer = maker,
+        space = 8 + Deal::INIT_SPACE,
+        seeds = [b"deal", maker.key().as_ref(), deal_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub deal: Account<'info, Deal>,
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"vault", deal.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = deal
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut, token::mint = mint, token::authority = maker)]
+    pub maker_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableSettle<'info> {
+    #[account(
+        mut,
+        seeds = [b"deal", deal.maker.as_ref(), deal.deal_id.to_le_bytes().as_ref()],
+        bump = deal.bump,
+        has_one = taker @ DealError::Unauthorized
+    )]
+    pub deal: Account<'info, Deal>,
+    #[account(mut, seeds = [b"vault", deal.key().as_ref()], bump)]
+    pub vault: Account<'info, TokenAccount>,
+    // VULNERABLE: indistinguishable from `taker_receive_account`
+    #[account(mut, token::mint = deal.mint)]
+    pub maker_receive_account: Account<'info, TokenAccount>,
+    // VULNERABLE: indistinguishable from `maker_receive_account`
+    #[account(mut, token::mint = deal.mint)]
+    pub taker_receive_account: Account<'info, TokenAccount>,
+    pub taker: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SecureSettle<'info> {
+    #[account(
+        mut,
+        seeds = [b"deal", deal.maker.as_ref(), deal.deal_id.to_le_bytes().as_ref()],
+        bump = deal.bump,
+        has_one = taker @ DealError::Unauthorized
+    )]
+    pub deal: Account<'info, Deal>,
+    #[account(mut, seeds = [b"vault", deal.key().as_ref()], bump)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = deal.mint, token::authority = deal.maker)]
+    pub maker_receive_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = deal.mint, token::authority = deal.taker)]
+    pub taker_receive_account: Account<'info, TokenAccount>,
+    pub taker: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Deal {
+    pub deal_id: u64,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub mint: Pubkey,
+    pub maker_refund: u64,
+    pub taker_payout: u64,
+    pub settled: bool,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum DealError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Taker payout exceeds the escrowed total")]
+    PayoutExceedsTotal,
+    #[msg("Deal has already been settled")]
+    AlreadySettled,
+}
@@ -0,0 +1,175 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+    Keypair,
+    LAMPORTS_PER_SOL,
+    PublicKey,
+    SystemProgram,
+    Transaction,
+    TransactionInstruction,
+} from "@solana/web3.js";
+import { createMint, createAccount, mintTo, getAccount, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+
+describe("28: Account Label Swap", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.AccountLabelSwap as Program<any>;
+
+    let maker: Keypair;
+    let taker: Keypair;
+
+    let mint: PublicKey;
+    let makerTokenAccount: PublicKey;
+    let takerTokenAccount: PublicKey;
+
+    const VULNERABLE_DEAL_ID = 1;
+    const SECURE_DEAL_ID = 2;
+    const TOTAL = 1_000;
+    const TAKER_PAYOUT = 100;
+    const MAKER_REFUND = TOTAL - TAKER_PAYOUT;
+
+    function dealPda(dealId: number): PublicKey {
+        const id = Buffer.alloc(8);
+        id.writeBigUInt64LE(BigInt(dealId));
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("deal"), maker.publicKey.toBuffer(), id],
+            program.programId
+        )[0];
+    }
+
+    function vaultPda(dealId: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("vault"), dealPda(dealId).toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    async function openDeal(dealId: number) {
+        await program.methods
+            .openDeal(new anchor.BN(dealId), taker.publicKey, new anchor.BN(TOTAL), new anchor.BN(TAKER_PAYOUT))
+            .accounts({
+                deal: dealPda(dealId),
+                vault: vaultPda(dealId),
+                mint,
+                makerTokenAccount,
+                maker: maker.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([maker])
+            .rpc();
+    }
+
+    // Built by hand, the way any client that skips Anchor's named-accounts
+    // builder would: only the position of each key says what it is.
+    function settleIx(
+        method: "vulnerableSettle" | "secureSettle",
+        dealId: number,
+        makerReceive: PublicKey,
+        takerReceive: PublicKey
+    ): TransactionInstruction {
+        return new TransactionInstruction({
+            programId: program.programId,
+            keys: [
+                { pubkey: dealPda(dealId), isSigner: false, isWritable: true },
+                { pubkey: vaultPda(dealId), isSigner: false, isWritable: true },
+                { pubkey: makerReceive, isSigner: false, isWritable: true },
+                { pubkey: takerReceive, isSigner: false, isWritable: true },
+                { pubkey: taker.publicKey, isSigner: true, isWritable: false },
+                { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+            ],
+            data: program.coder.instruction.encode(method, {}),
+        });
+    }
+
+    function send(ix: TransactionInstruction) {
+        return provider.sendAndConfirm(new Transaction().add(ix), [taker]);
+    }
+
+    async function balance(account: PublicKey): Promise<number> {
+        return Number((await getAccount(provider.connection, account)).amount);
+    }
+
+    before(async () => {
+        maker = Keypair.generate();
+        taker = Keypair.generate();
+
+        for (const kp of [maker, taker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+
+        mint = await createMint(provider.connection, maker, maker.publicKey, null, 6);
+        makerTokenAccount = await createAccount(provider.connection, maker, mint, maker.publicKey);
+        takerTokenAccount = await createAccount(provider.connection, taker, mint, taker.publicKey);
+        await mintTo(provider.connection, maker, mint, makerTokenAccount, maker, 2 * TOTAL);
+    });
+
+    describe("Setup", () => {
+        it("Maker escrows two deals with the taker", async () => {
+            await openDeal(VULNERABLE_DEAL_ID);
+            await openDeal(SECURE_DEAL_ID);
+
+            expect(await balance(vaultPda(VULNERABLE_DEAL_ID))).to.equal(TOTAL);
+            expect(await balance(makerTokenAccount)).to.equal(0);
+        });
+    });
+
+    describe("Exploit: swapped receive accounts", () => {
+        it("Taker collects the maker's refund", async () => {
+            // maker_receive_account slot <- taker's account, and vice versa
+            await send(settleIx("vulnerableSettle", VULNERABLE_DEAL_ID, takerTokenAccount, makerTokenAccount));
+
+            const takerGot = await balance(takerTokenAccount);
+            const makerGot = await balance(makerTokenAccount);
+            console.log(`  Taker received ${takerGot} (owed ${TAKER_PAYOUT}), maker received ${makerGot} (owed ${MAKER_REFUND})`);
+            expect(takerGot).to.equal(MAKER_REFUND);
+            expect(makerGot).to.equal(TAKER_PAYOUT);
+        });
+    });
+
+    describe("Secure: receive accounts bound to their owners", () => {
+        it("Rejects the swapped accounts", async () => {
+            try {
+                await send(settleIx("secureSettle", SECURE_DEAL_ID, takerTokenAccount, makerTokenAccount));
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.include("ConstraintTokenOwner");
+            }
+
+            expect(await balance(vaultPda(SECURE_DEAL_ID))).to.equal(TOTAL);
+        });
+
+        it("Settles correctly with the accounts in their places", async () => {
+            const makerBefore = await balance(makerTokenAccount);
+            const takerBefore = await balance(takerTokenAccount);
+
+            await send(settleIx("secureSettle", SECURE_DEAL_ID, makerTokenAccount, takerTokenAccount));
+
+            expect((await balance(makerTokenAccount)) - makerBefore).to.equal(MAKER_REFUND);
+            expect((await balance(takerTokenAccount)) - takerBefore).to.equal(TAKER_PAYOUT);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Account Label Swap ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  maker_receive_account: token::mint = deal.mint");
+            console.log("  taker_receive_account: token::mint = deal.mint");
+            console.log("  Risk: the field name is a client-side label; the caller picks the order\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  maker_receive_account: token::authority = deal.maker");
+            console.log("  taker_receive_account: token::authority = deal.taker");
+            console.log("  Protection: each slot only accepts its party's account\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  If two accounts have the same constraints, the program cannot tell them apart.");
+        });
+    });
+});
@@ -0,0 +1,239 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+declare_id!("FeUYQ6KrZdNHeuauKPsk2opFzTx3YvHarMq5RyiUA5Rz");
+
+#[program]
+pub mod transfer_fee_accounting {
+    use super::*;
+
+    pub fn initialize_pool(ctx: Context<InitializePool>, pool_id: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.pool_id = pool_id;
+        pool.mint = ctx.accounts.mint.key();
+        pool.vault = ctx.accounts.vault.key();
+        pool.total_credits = 0;
+        pool.bump = ctx.bumps.pool;
+        Ok(())
+    }
+
+    pub fn open_balance(ctx: Context<OpenBalance>) -> Result<()> {
+        let balance = &mut ctx.accounts.balance;
+        balance.pool = ctx.accounts.pool.key();
+        balance.owner = ctx.accounts.owner.key();
+        balance.amount = 0;
+        balance.bump = ctx.bumps.balance;
+        Ok(())
+    }
+
+    // VULNERABLE: Credits the Requested Amount
+    // 1. Token-2022's transfer-fee extension withholds part of every
+    //    transfer in the destination account
+    // 2. The vault receives `amount - fee`, but the user is credited `amount`
+    // 3. Credits exceed vault holdings by the fee on every deposit; the
+    //    last users to withdraw find the vault short
+    pub fn vulnerable_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        transfer_in(&ctx, amount)?;
+
+        credit(&mut ctx.accounts.pool, &mut ctx.accounts.balance, amount)?;
+        msg!("Vulnerable deposit: sent {}, credited {}", amount, amount);
+        Ok(())
+    }
+
+    // SECURE: Credits What Actually Arrived
+    // 1. Read the vault balance before the transfer
+    // 2. `reload()` after the CPI and credit the delta
+    // 3. Correct for any fee schedule, including one changed after deploy
+    pub fn secure_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let before = ctx.accounts.vault.amount;
+        transfer_in(&ctx, amount)?;
+        ctx.accounts.vault.reload()?;
+        let received = ctx
+            .accounts
+            .vault
+            .amount
+            .checked_sub(before)
+            .ok_or(PoolError::ArithmeticError)?;
+
+        credit(&mut ctx.accounts.pool, &mut ctx.accounts.balance, received)?;
+        msg!("Secure deposit: sent {}, credited {}", amount, received);
+        Ok(())
+    }
+
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        let balance = &mut ctx.accounts.balance;
+        balance.amount = balance.amount.checked_sub(amount).ok_or(PoolError::InsufficientBalance)?;
+        let pool = &mut ctx.accounts.pool;
+        pool.total_credits = pool
+            .total_credits
+            .checked_sub(amount)
+            .ok_or(PoolError::ArithmeticError)?;
+
+        let pool_id = pool.pool_id.to_le_bytes();
+        let seeds: &[&[u8]] = &[b"pool", pool_id.as_ref(), &[pool.bump]];
+        let signer = &[seeds];
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+            ctx.accounts.mint.decimals,
+        )
+    }
+}
+
+fn transfer_in(ctx: &Context<Deposit>, amount: u64) -> Result<()> {
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.user_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )
+}
+
+fn credit(pool: &mut Account<Pool>, balance: &mut Account<Balance>, amount: u64) -> Result<()> {
+    balance.amount = balance.amount.checked_add(amount).ok_or(PoolError::ArithmeticError)?;
+    pool.total_credits = pool
+        .total_credits
+        .checked_add(amount)
+        .ok_or(PoolError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u64)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Pool::INIT_SPACE,
+        seeds = [b"pool", pool_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = pool,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenBalance<'info> {
+    #[account(seeds = [b"pool", pool.pool_id.to_le_bytes().as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Balance::INIT_SPACE,
+        seeds = [b"balance", pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub balance: Account<'info, Balance>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.pool_id.to_le_bytes().as_ref()],
+        bump = pool.bump,
+        has_one = mint,
+        has_one = vault
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"balance", pool.key().as_ref(), owner.key().as_ref()],
+        bump = balance.bump
+    )]
+    pub balance: Account<'info, Balance>,
+    #[account(mut, token::mint = mint, token::authority = owner)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.pool_id.to_le_bytes().as_ref()],
+        bump = pool.bump,
+        has_one = mint,
+        has_one = vault
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"balance", pool.key().as_ref(), owner.key().as_ref()],
+        bump = balance.bump
+    )]
+    pub balance: Account<'info, Balance>,
+    #[account(mut, token::mint = mint, token::authority = owner)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub pool_id: u64,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    /// Sum of every `Balance::amount`; must never exceed `vault.amount`.
+    pub total_credits: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Balance {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum PoolError {
+    #[msg("Insufficient balance")]
+    InsufficientBalance,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
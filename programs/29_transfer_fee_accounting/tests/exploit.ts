@@ -0,0 +1,251 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+    Keypair,
+    LAMPORTS_PER_SOL,
+    PublicKey,
+    SystemProgram,
+    Transaction,
+    sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import {
+    ExtensionType,
+    TOKEN_2022_PROGRAM_ID,
+    createAccount,
+    createInitializeMintInstruction,
+    createInitializeTransferFeeConfigInstruction,
+    getAccount,
+    getMintLen,
+    mintTo,
+} from "@solana/spl-token";
+import { expect } from "chai";
+
+describe("29: Token-2022 Transfer Fee Accounting", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.TransferFeeAccounting as Program<any>;
+
+    let admin: Keypair;
+    let victim: Keypair;
+    let attacker: Keypair;
+
+    let mint: PublicKey;
+    const tokenAccounts = new Map<string, PublicKey>();
+
+    const VULNERABLE_POOL_ID = 1;
+    const SECURE_POOL_ID = 2;
+    const DECIMALS = 6;
+    const FEE_BPS = 100; // 1%
+    const DEPOSIT = 10_000;
+    const FEE = (DEPOSIT * FEE_BPS) / 10_000;
+
+    function poolPda(poolId: number): PublicKey {
+        const id = Buffer.alloc(8);
+        id.writeBigUInt64LE(BigInt(poolId));
+        return PublicKey.findProgramAddressSync([Buffer.from("pool"), id], program.programId)[0];
+    }
+
+    function vaultPda(poolId: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("vault"), poolPda(poolId).toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function balancePda(poolId: number, owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("balance"), poolPda(poolId).toBuffer(), owner.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function userAccounts(poolId: number, user: Keypair) {
+        return {
+            pool: poolPda(poolId),
+            vault: vaultPda(poolId),
+            mint,
+            balance: balancePda(poolId, user.publicKey),
+            userTokenAccount: tokenAccounts.get(user.publicKey.toBase58()),
+            owner: user.publicKey,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+        };
+    }
+
+    async function setupPool(poolId: number) {
+        await program.methods
+            .initializePool(new anchor.BN(poolId))
+            .accounts({
+                pool: poolPda(poolId),
+                vault: vaultPda(poolId),
+                mint,
+                payer: admin.publicKey,
+                tokenProgram: TOKEN_2022_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([admin])
+            .rpc();
+
+        for (const user of [victim, attacker]) {
+            await program.methods
+                .openBalance()
+                .accounts({
+                    pool: poolPda(poolId),
+                    balance: balancePda(poolId, user.publicKey),
+                    owner: user.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([user])
+                .rpc();
+        }
+    }
+
+    function deposit(method: "vulnerableDeposit" | "secureDeposit", poolId: number, user: Keypair, amount: number) {
+        return program.methods[method](new anchor.BN(amount))
+            .accounts(userAccounts(poolId, user))
+            .signers([user])
+            .rpc();
+    }
+
+    function withdraw(poolId: number, user: Keypair, amount: number) {
+        return program.methods
+            .withdraw(new anchor.BN(amount))
+            .accounts(userAccounts(poolId, user))
+            .signers([user])
+            .rpc();
+    }
+
+    async function vaultAmount(poolId: number): Promise<number> {
+        return Number((await getAccount(provider.connection, vaultPda(poolId), undefined, TOKEN_2022_PROGRAM_ID)).amount);
+    }
+
+    async function credited(poolId: number, user: Keypair): Promise<number> {
+        return (await program.account.balance.fetch(balancePda(poolId, user.publicKey))).amount.toNumber();
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        victim = Keypair.generate();
+        attacker = Keypair.generate();
+
+        for (const kp of [admin, victim, attacker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+
+        const mintKeypair = Keypair.generate();
+        mint = mintKeypair.publicKey;
+        const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+        const rent = await provider.connection.getMinimumBalanceForRentExemption(mintLen);
+
+        await sendAndConfirmTransaction(
+            provider.connection,
+            new Transaction().add(
+                SystemProgram.createAccount({
+                    fromPubkey: admin.publicKey,
+                    newAccountPubkey: mint,
+                    space: mintLen,
+                    lamports: rent,
+                    programId: TOKEN_2022_PROGRAM_ID,
+                }),
+                createInitializeTransferFeeConfigInstruction(
+                    mint,
+                    admin.publicKey,
+                    admin.publicKey,
+                    FEE_BPS,
+                    BigInt(1_000_000_000),
+                    TOKEN_2022_PROGRAM_ID
+                ),
+                createInitializeMintInstruction(mint, DECIMALS, admin.publicKey, null, TOKEN_2022_PROGRAM_ID)
+            ),
+            [admin, mintKeypair]
+        );
+
+        for (const user of [victim, attacker]) {
+            const account = await createAccount(
+                provider.connection,
+                user,
+                mint,
+                user.publicKey,
+                undefined,
+                undefined,
+                TOKEN_2022_PROGRAM_ID
+            );
+            await mintTo(provider.connection, admin, mint, account, admin, 10 * DEPOSIT, [], undefined, TOKEN_2022_PROGRAM_ID);
+            tokenAccounts.set(user.publicKey.toBase58(), account);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates pools for a 1% transfer-fee mint", async () => {
+            await setupPool(VULNERABLE_POOL_ID);
+            await setupPool(SECURE_POOL_ID);
+
+            const pool = await program.account.pool.fetch(poolPda(VULNERABLE_POOL_ID));
+            expect(pool.mint.toBase58()).to.equal(mint.toBase58());
+        });
+    });
+
+    describe("Exploit: credit the gross amount", () => {
+        it("Credits more than the vault received", async () => {
+            await deposit("vulnerableDeposit", VULNERABLE_POOL_ID, victim, DEPOSIT);
+            await deposit("vulnerableDeposit", VULNERABLE_POOL_ID, attacker, DEPOSIT);
+
+            const pool = await program.account.pool.fetch(poolPda(VULNERABLE_POOL_ID));
+            const vault = await vaultAmount(VULNERABLE_POOL_ID);
+            console.log(`  Credits ${pool.totalCredits.toNumber()}, vault holds ${vault}`);
+            expect(pool.totalCredits.toNumber()).to.equal(2 * DEPOSIT);
+            expect(vault).to.equal(2 * (DEPOSIT - FEE));
+        });
+
+        it("First withdrawer is made whole out of the victim's deposit", async () => {
+            await withdraw(VULNERABLE_POOL_ID, attacker, DEPOSIT);
+
+            try {
+                await withdraw(VULNERABLE_POOL_ID, victim, DEPOSIT);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                // Token-2022 rejects the vault transfer: credits exceed holdings.
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.include("insufficient funds");
+            }
+
+            console.log(`  Victim credited ${await credited(VULNERABLE_POOL_ID, victim)}, vault holds ${await vaultAmount(VULNERABLE_POOL_ID)}`);
+        });
+    });
+
+    describe("Secure: credit the measured delta", () => {
+        it("Credits only what arrived in the vault", async () => {
+            await deposit("secureDeposit", SECURE_POOL_ID, victim, DEPOSIT);
+            await deposit("secureDeposit", SECURE_POOL_ID, attacker, DEPOSIT);
+
+            expect(await credited(SECURE_POOL_ID, victim)).to.equal(DEPOSIT - FEE);
+            const pool = await program.account.pool.fetch(poolPda(SECURE_POOL_ID));
+            expect(pool.totalCredits.toNumber()).to.equal(await vaultAmount(SECURE_POOL_ID));
+        });
+
+        it("Every depositor can withdraw their full credit", async () => {
+            await withdraw(SECURE_POOL_ID, attacker, DEPOSIT - FEE);
+            await withdraw(SECURE_POOL_ID, victim, DEPOSIT - FEE);
+
+            expect(await vaultAmount(SECURE_POOL_ID)).to.equal(0);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Token-2022 Transfer Fee Accounting ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  transfer_checked(user -> vault, amount); credit(amount)");
+            console.log("  Risk: the fee is withheld from the vault, credits exceed holdings\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  before = vault.amount; transfer_checked(...); vault.reload()?; credit(vault.amount - before)");
+            console.log("  Protection: credit what arrived, whatever the fee schedule\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  With Token-2022, the amount you send is not the amount that arrives.");
+        });
+    });
+});
@@ -0,0 +1,235 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("6tkoq5XWorhH7XEbTjwkL3UmidirnrS9JNhxEgbUhQfH");
+
+/// Version of the newest `process_*` instruction.
+pub const CURRENT_VERSION: u8 = 2;
+
+#[program]
+pub mod version_downgrade {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>, max_withdrawal: u64) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.min_version = 1;
+        config.max_withdrawal = max_withdrawal;
+        config.bump = ctx.bumps.config;
+        Ok(())
+    }
+
+    pub fn open_position(ctx: Context<OpenPosition>) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.owner = ctx.accounts.owner.key();
+        position.amount = 0;
+        position.frozen = false;
+        position.bump = ctx.bumps.position;
+        Ok(())
+    }
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: ctx.accounts.config.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let position = &mut ctx.accounts.position;
+        position.amount = position.amount.checked_add(amount).ok_or(VersionError::ArithmeticError)?;
+        Ok(())
+    }
+
+    pub fn set_frozen(ctx: Context<AdminPosition>, frozen: bool) -> Result<()> {
+        ctx.accounts.position.frozen = frozen;
+        Ok(())
+    }
+
+    /// Retires every `process_*` instruction older than `version`. One-way.
+    pub fn set_min_version(ctx: Context<AdminConfig>, version: u8) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(version >= config.min_version, VersionError::VersionRollback);
+        require!(version <= CURRENT_VERSION, VersionError::UnknownVersion);
+        config.min_version = version;
+        Ok(())
+    }
+
+    /// Current withdrawal path: checks the freeze flag and the per-call cap
+    /// that were added when v1 was superseded.
+    pub fn process_v2(ctx: Context<Process>, amount: u64) -> Result<()> {
+        require!(!ctx.accounts.position.frozen, VersionError::PositionFrozen);
+        require!(
+            amount <= ctx.accounts.config.max_withdrawal,
+            VersionError::WithdrawalTooLarge
+        );
+        withdraw(ctx.accounts, amount)?;
+        msg!("process_v2: withdrew {}", amount);
+        Ok(())
+    }
+
+    // VULNERABLE: Legacy Path Never Retired
+    // 1. v1 predates the freeze flag and the withdrawal cap
+    // 2. It stays callable forever; updating the official client to v2
+    //    does nothing to stop anyone building a v1 instruction
+    // 3. Every check added in v2 is bypassed by calling v1
+    pub fn vulnerable_process_v1(ctx: Context<Process>, amount: u64) -> Result<()> {
+        withdraw(ctx.accounts, amount)?;
+        msg!("vulnerable_process_v1: withdrew {}", amount);
+        Ok(())
+    }
+
+    // SECURE: Legacy Path Gated on `min_version`
+    // 1. Still available while `config.min_version <= 1`, so clients can
+    //    migrate on their own schedule
+    // 2. Once the admin raises `min_version`, v1 returns `Deprecated`
+    // 3. `set_min_version` is one-way, so v1 cannot be quietly re-enabled
+    pub fn secure_process_v1(ctx: Context<Process>, amount: u64) -> Result<()> {
+        require!(ctx.accounts.config.min_version <= 1, VersionError::Deprecated);
+        withdraw(ctx.accounts, amount)?;
+        msg!("secure_process_v1: withdrew {}", amount);
+        Ok(())
+    }
+}
+
+fn withdraw(accounts: &mut Process, amount: u64) -> Result<()> {
+    let position = &mut accounts.position;
+    position.amount = position
+        .amount
+        .checked_sub(amount)
+        .ok_or(VersionError::InsufficientBalance)?;
+
+    let config = accounts.config.to_account_info();
+    let owner = accounts.owner.to_account_info();
+    **config.try_borrow_mut_lamports()? = config
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(VersionError::InsufficientBalance)?;
+    **owner.try_borrow_mut_lamports()? = owner
+        .lamports()
+        .checked_add(amount)
+        .ok_or(VersionError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [b"position", owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"position", owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner @ VersionError::Unauthorized
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AdminConfig<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump, has_one = admin @ VersionError::Unauthorized)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AdminPosition<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, has_one = admin @ VersionError::Unauthorized)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [b"position", position.owner.as_ref()], bump = position.bump)]
+    pub position: Account<'info, Position>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Process<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"position", owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner @ VersionError::Unauthorized
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    /// Oldest `process_*` version still accepted.
+    pub min_version: u8,
+    /// Per-call withdrawal cap, introduced with v2.
+    pub max_withdrawal: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub owner: Pubkey,
+    pub amount: u64,
+    /// Set by the admin; introduced with v2.
+    pub frozen: bool,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum VersionError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("This instruction version is deprecated")]
+    Deprecated,
+    #[msg("Minimum version cannot be lowered")]
+    VersionRollback,
+    #[msg("Unknown instruction version")]
+    UnknownVersion,
+    #[msg("Position is frozen")]
+    PositionFrozen,
+    #[msg("Withdrawal exceeds the per-call maximum")]
+    WithdrawalTooLarge,
+    #[msg("Insufficient balance")]
+    InsufficientBalance,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
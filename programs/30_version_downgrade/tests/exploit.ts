@@ -0,0 +1,172 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+
+describe("30: Versioned Instruction Downgrade", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.VersionDowngrade as Program<any>;
+
+    let admin: Keypair;
+    let alice: Keypair;
+    let attacker: Keypair;
+    let config: PublicKey;
+
+    const DEPOSIT = LAMPORTS_PER_SOL;
+    const MAX_WITHDRAWAL = LAMPORTS_PER_SOL / 4;
+
+    function positionPda(owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("position"), owner.toBuffer()], program.programId)[0];
+    }
+
+    function process(
+        method: "processV2" | "vulnerableProcessV1" | "secureProcessV1",
+        owner: Keypair,
+        amount: number
+    ) {
+        return program.methods[method](new anchor.BN(amount))
+            .accounts({ config, position: positionPda(owner.publicKey), owner: owner.publicKey })
+            .signers([owner])
+            .rpc();
+    }
+
+    function setMinVersion(version: number) {
+        return program.methods
+            .setMinVersion(version)
+            .accounts({ config, admin: admin.publicKey })
+            .signers([admin])
+            .rpc();
+    }
+
+    async function position(owner: Keypair) {
+        return program.account.position.fetch(positionPda(owner.publicKey));
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        alice = Keypair.generate();
+        attacker = Keypair.generate();
+
+        for (const kp of [admin, alice, attacker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+
+        config = PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId)[0];
+    });
+
+    describe("Setup", () => {
+        it("Initializes with both versions live and funds two positions", async () => {
+            await program.methods
+                .initialize(new anchor.BN(MAX_WITHDRAWAL))
+                .accounts({ config, admin: admin.publicKey, systemProgram: SystemProgram.programId })
+                .signers([admin])
+                .rpc();
+
+            for (const user of [alice, attacker]) {
+                await program.methods
+                    .openPosition()
+                    .accounts({
+                        position: positionPda(user.publicKey),
+                        owner: user.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([user])
+                    .rpc();
+
+                await program.methods
+                    .deposit(new anchor.BN(DEPOSIT))
+                    .accounts({
+                        config,
+                        position: positionPda(user.publicKey),
+                        owner: user.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([user])
+                    .rpc();
+            }
+
+            expect((await program.account.config.fetch(config)).minVersion).to.equal(1);
+        });
+    });
+
+    describe("Exploit: call the old version", () => {
+        it("v2 enforces the freeze and the cap", async () => {
+            await program.methods
+                .setFrozen(true)
+                .accounts({ config, position: positionPda(attacker.publicKey), admin: admin.publicKey })
+                .signers([admin])
+                .rpc();
+
+            try {
+                await process("processV2", attacker, MAX_WITHDRAWAL);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("PositionFrozen");
+            }
+        });
+
+        it("A frozen user withdraws everything through v1", async () => {
+            await process("vulnerableProcessV1", attacker, DEPOSIT);
+
+            const pos = await position(attacker);
+            console.log(`  Frozen position withdrew ${DEPOSIT} (cap ${MAX_WITHDRAWAL}) via v1`);
+            expect(pos.frozen).to.equal(true);
+            expect(pos.amount.toNumber()).to.equal(0);
+        });
+    });
+
+    describe("Secure: legacy path gated on min_version", () => {
+        it("Serves v1 until it is retired", async () => {
+            await process("secureProcessV1", alice, DEPOSIT / 10);
+            expect((await position(alice)).amount.toNumber()).to.equal(DEPOSIT - DEPOSIT / 10);
+        });
+
+        it("Returns Deprecated once min_version is raised", async () => {
+            await setMinVersion(2);
+
+            try {
+                await process("secureProcessV1", alice, DEPOSIT / 10);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("Deprecated");
+            }
+
+            await process("processV2", alice, DEPOSIT / 10);
+            expect((await position(alice)).amount.toNumber()).to.equal(DEPOSIT - (2 * DEPOSIT) / 10);
+        });
+
+        it("Cannot re-enable a retired version", async () => {
+            try {
+                await setMinVersion(1);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("VersionRollback");
+            }
+        });
+
+        it("The ungated v1 ignores min_version entirely", async () => {
+            await process("vulnerableProcessV1", alice, DEPOSIT / 2);
+            expect((await program.account.config.fetch(config)).minVersion).to.equal(2);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Versioned Instruction Downgrade ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  process_v1 (no freeze, no cap) and process_v2 both live forever");
+            console.log("  Risk: every check added in v2 is skipped by calling v1\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  require!(config.min_version <= 1, Deprecated); min_version only goes up");
+            console.log("  Protection: the program, not the client, decides which versions run\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Shipping v2 does not retire v1. Only an on-chain check does.");
+        });
+    });
+});
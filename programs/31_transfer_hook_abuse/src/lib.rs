@@ -0,0 +1,340 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{transfer_hook, StateWithExtensions},
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+declare_id!("Fwt25R8y3NuMHFuq2xCAokXMoKPHgZgawZpSt2xZmxWW");
+
+/// Maximum number of hook programs the allowlist can hold.
+pub const MAX_HOOK_PROGRAMS: usize = 8;
+
+#[program]
+pub mod transfer_hook_abuse {
+    use super::*;
+
+    pub fn initialize_allowlist(ctx: Context<InitializeAllowlist>) -> Result<()> {
+        let allowlist = &mut ctx.accounts.hook_allowlist;
+        allowlist.admin = ctx.accounts.admin.key();
+        allowlist.programs = Vec::new();
+        allowlist.bump = ctx.bumps.hook_allowlist;
+        Ok(())
+    }
+
+    pub fn add_hook_program(ctx: Context<AddHookProgram>, program_id: Pubkey) -> Result<()> {
+        let allowlist = &mut ctx.accounts.hook_allowlist;
+        require!(
+            !allowlist.programs.contains(&program_id),
+            HookPoolError::AlreadyAllowed
+        );
+        require!(
+            allowlist.programs.len() < MAX_HOOK_PROGRAMS,
+            HookPoolError::AllowlistFull
+        );
+        allowlist.programs.push(program_id);
+        Ok(())
+    }
+
+    pub fn initialize_pool(ctx: Context<InitializePool>, pool_id: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.pool_id = pool_id;
+        pool.mint = ctx.accounts.mint.key();
+        pool.vault = ctx.accounts.vault.key();
+        pool.locked = false;
+        pool.bump = ctx.bumps.pool;
+        Ok(())
+    }
+
+    pub fn open_balance(ctx: Context<OpenBalance>) -> Result<()> {
+        let balance = &mut ctx.accounts.balance;
+        balance.pool = ctx.accounts.pool.key();
+        balance.owner = ctx.accounts.owner.key();
+        balance.amount = 0;
+        balance.bump = ctx.bumps.balance;
+        Ok(())
+    }
+
+    // VULNERABLE: Any Mint, Any Hook
+    // 1. The pool accepts every Token-2022 mint, including ones whose
+    //    transfer hook points at a program the mint creator controls
+    // 2. Every deposit and withdrawal CPIs `transfer_checked`, which runs
+    //    that hook with the caller-supplied extra accounts
+    // 3. The hook can fail selectively: deposits succeed, withdrawals
+    //    signed by the pool revert, and the vault is frozen at the
+    //    creator's discretion
+    pub fn vulnerable_deposit<'info>(
+        ctx: Context<'_, '_, '_, 'info, PoolTransfer<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        credit(&mut ctx.accounts.balance, amount)?;
+        transfer_in(&ctx, amount)?;
+        msg!("Vulnerable deposit: {}", amount);
+        Ok(())
+    }
+
+    pub fn vulnerable_withdraw<'info>(
+        ctx: Context<'_, '_, '_, 'info, PoolTransfer<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        debit(&mut ctx.accounts.balance, amount)?;
+        transfer_out(&ctx, amount)?;
+        msg!("Vulnerable withdraw: {}", amount);
+        Ok(())
+    }
+
+    // SECURE: Allowlisted Hooks and a Reentrancy Flag
+    // 1. Read the mint's TransferHook extension on every transfer, since
+    //    the hook authority can repoint it after the pool is created
+    // 2. Reject the transfer unless the hook program is on the
+    //    admin-managed allowlist (mints without a hook pass)
+    // 3. Persist `pool.locked = true` before the CPI and clear it after,
+    //    so no path back into the pool can observe half-applied state
+    pub fn secure_deposit<'info>(
+        ctx: Context<'_, '_, '_, 'info, PoolTransfer<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        check_hook(&ctx.accounts.mint, &ctx.accounts.hook_allowlist)?;
+        lock(&mut ctx.accounts.pool)?;
+
+        credit(&mut ctx.accounts.balance, amount)?;
+        transfer_in(&ctx, amount)?;
+
+        ctx.accounts.pool.locked = false;
+        msg!("Secure deposit: {}", amount);
+        Ok(())
+    }
+
+    pub fn secure_withdraw<'info>(
+        ctx: Context<'_, '_, '_, 'info, PoolTransfer<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        check_hook(&ctx.accounts.mint, &ctx.accounts.hook_allowlist)?;
+        lock(&mut ctx.accounts.pool)?;
+
+        debit(&mut ctx.accounts.balance, amount)?;
+        transfer_out(&ctx, amount)?;
+
+        ctx.accounts.pool.locked = false;
+        msg!("Secure withdraw: {}", amount);
+        Ok(())
+    }
+}
+
+/// Fails unless the mint has no transfer hook or its hook program is allowlisted.
+fn check_hook(mint: &InterfaceAccount<Mint>, allowlist: &HookAllowlist) -> Result<()> {
+    let info = mint.to_account_info();
+    let data = info.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    if let Some(program_id) = transfer_hook::get_program_id(&state) {
+        require!(
+            allowlist.programs.contains(&program_id),
+            HookPoolError::HookNotAllowed
+        );
+    }
+    Ok(())
+}
+
+/// Sets the reentrancy flag and writes it to the account before any CPI.
+/// Anchor only serializes accounts when the instruction returns, so without
+/// the explicit `exit` a nested call would still read `locked == false`.
+fn lock(pool: &mut Account<Pool>) -> Result<()> {
+    require!(!pool.locked, HookPoolError::Reentrancy);
+    pool.locked = true;
+    pool.exit(&crate::ID)
+}
+
+fn transfer_in<'info>(ctx: &Context<'_, '_, '_, 'info, PoolTransfer<'info>>, amount: u64) -> Result<()> {
+    spl_token_2022::onchain::invoke_transfer_checked(
+        ctx.accounts.token_program.key,
+        ctx.accounts.user_token_account.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.owner.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+        ctx.accounts.mint.decimals,
+        &[],
+    )
+    .map_err(Into::into)
+}
+
+fn transfer_out<'info>(ctx: &Context<'_, '_, '_, 'info, PoolTransfer<'info>>, amount: u64) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let pool_id = pool.pool_id.to_le_bytes();
+    let seeds: &[&[u8]] = &[b"pool", pool_id.as_ref(), &[pool.bump]];
+    let signer = &[seeds];
+
+    spl_token_2022::onchain::invoke_transfer_checked(
+        ctx.accounts.token_program.key,
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.user_token_account.to_account_info(),
+        ctx.accounts.pool.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+        ctx.accounts.mint.decimals,
+        signer,
+    )
+    .map_err(Into::into)
+}
+
+fn credit(balance: &mut Account<Balance>, amount: u64) -> Result<()> {
+    balance.amount = balance.amount.checked_add(amount).ok_or(HookPoolError::ArithmeticError)?;
+    Ok(())
+}
+
+fn debit(balance: &mut Account<Balance>, amount: u64) -> Result<()> {
+    balance.amount = balance.amount.checked_sub(amount).ok_or(HookPoolError::InsufficientBalance)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeAllowlist<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + HookAllowlist::INIT_SPACE,
+        seeds = [b"hook_allowlist"],
+        bump
+    )]
+    pub hook_allowlist: Account<'info, HookAllowlist>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddHookProgram<'info> {
+    #[account(
+        mut,
+        seeds = [b"hook_allowlist"],
+        bump = hook_allowlist.bump,
+        has_one = admin @ HookPoolError::Unauthorized
+    )]
+    pub hook_allowlist: Account<'info, HookAllowlist>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u64)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Pool::INIT_SPACE,
+        seeds = [b"pool", pool_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = pool,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenBalance<'info> {
+    #[account(seeds = [b"pool", pool.pool_id.to_le_bytes().as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Balance::INIT_SPACE,
+        seeds = [b"balance", pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub balance: Account<'info, Balance>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Shared by deposits and withdrawals. Hook extra accounts (the
+/// extra-account-metas PDA, the hook program and whatever the list names)
+/// are passed as remaining accounts.
+#[derive(Accounts)]
+pub struct PoolTransfer<'info> {
+    #[account(seeds = [b"hook_allowlist"], bump = hook_allowlist.bump)]
+    pub hook_allowlist: Account<'info, HookAllowlist>,
+    #[account(
+        mut,
+        seeds = [b"pool", pool.pool_id.to_le_bytes().as_ref()],
+        bump = pool.bump,
+        has_one = mint,
+        has_one = vault
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"balance", pool.key().as_ref(), owner.key().as_ref()],
+        bump = balance.bump
+    )]
+    pub balance: Account<'info, Balance>,
+    #[account(mut, token::mint = mint, token::authority = owner)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct HookAllowlist {
+    pub admin: Pubkey,
+    /// Transfer hook programs the secure path will run.
+    #[max_len(MAX_HOOK_PROGRAMS)]
+    pub programs: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub pool_id: u64,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    /// Set for the duration of a secure transfer's CPI.
+    pub locked: bool,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Balance {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum HookPoolError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Mint's transfer hook program is not allowlisted")]
+    HookNotAllowed,
+    #[msg("Hook program is already allowlisted")]
+    AlreadyAllowed,
+    #[msg("Hook allowlist is full")]
+    AllowlistFull,
+    #[msg("Pool is already mid-transfer")]
+    Reentrancy,
+    #[msg("Insufficient balance")]
+    InsufficientBalance,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
@@ -0,0 +1,338 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+    AccountMeta,
+    Keypair,
+    LAMPORTS_PER_SOL,
+    PublicKey,
+    SystemProgram,
+    Transaction,
+    sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import {
+    ExtensionType,
+    TOKEN_2022_PROGRAM_ID,
+    createAccount,
+    createInitializeMintInstruction,
+    createInitializeTransferHookInstruction,
+    createMint,
+    getAccount,
+    getMintLen,
+    mintTo,
+} from "@solana/spl-token";
+import { expect } from "chai";
+
+describe("31: Token-2022 Transfer Hook Abuse", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.TransferHookAbuse as Program<any>;
+    const hookProgram = anchor.workspace.MockTransferHook as Program<any>;
+
+    let admin: Keypair;
+    let victim: Keypair;
+    let attacker: Keypair;
+
+    let hookMint: PublicKey;
+    let plainMint: PublicKey;
+    let hookAllowlist: PublicKey;
+    let hookConfig: PublicKey;
+    let extraAccountMetas: PublicKey;
+    const tokenAccounts = new Map<string, PublicKey>();
+
+    const VULNERABLE_POOL_ID = 1;
+    const SECURE_POOL_ID = 2;
+    const PLAIN_POOL_ID = 3;
+    const DECIMALS = 6;
+    const DEPOSIT = 10_000;
+
+    function poolPda(poolId: number): PublicKey {
+        const id = Buffer.alloc(8);
+        id.writeBigUInt64LE(BigInt(poolId));
+        return PublicKey.findProgramAddressSync([Buffer.from("pool"), id], program.programId)[0];
+    }
+
+    function vaultPda(poolId: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("vault"), poolPda(poolId).toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function balancePda(poolId: number, owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("balance"), poolPda(poolId).toBuffer(), owner.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function tokenAccountKey(mint: PublicKey, owner: PublicKey): string {
+        return `${mint.toBase58()}:${owner.toBase58()}`;
+    }
+
+    // Accounts Token-2022 needs to run the hook: the extra-account-metas PDA,
+    // the hook program, and the hook config the list resolves to.
+    function hookAccounts(mint: PublicKey): AccountMeta[] {
+        if (!mint.equals(hookMint)) return [];
+        return [
+            { pubkey: extraAccountMetas, isSigner: false, isWritable: false },
+            { pubkey: hookProgram.programId, isSigner: false, isWritable: false },
+            { pubkey: hookConfig, isSigner: false, isWritable: false },
+        ];
+    }
+
+    async function setupPool(poolId: number, mint: PublicKey, users: Keypair[]) {
+        await program.methods
+            .initializePool(new anchor.BN(poolId))
+            .accounts({
+                pool: poolPda(poolId),
+                vault: vaultPda(poolId),
+                mint,
+                payer: admin.publicKey,
+                tokenProgram: TOKEN_2022_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([admin])
+            .rpc();
+
+        for (const user of users) {
+            await program.methods
+                .openBalance()
+                .accounts({
+                    pool: poolPda(poolId),
+                    balance: balancePda(poolId, user.publicKey),
+                    owner: user.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([user])
+                .rpc();
+        }
+    }
+
+    function poolTransfer(
+        method: "vulnerableDeposit" | "vulnerableWithdraw" | "secureDeposit" | "secureWithdraw",
+        poolId: number,
+        mint: PublicKey,
+        user: Keypair,
+        amount: number
+    ) {
+        return program.methods[method](new anchor.BN(amount))
+            .accounts({
+                hookAllowlist,
+                pool: poolPda(poolId),
+                vault: vaultPda(poolId),
+                mint,
+                balance: balancePda(poolId, user.publicKey),
+                userTokenAccount: tokenAccounts.get(tokenAccountKey(mint, user.publicKey)),
+                owner: user.publicKey,
+                tokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .remainingAccounts(hookAccounts(mint))
+            .signers([user])
+            .rpc();
+    }
+
+    async function vaultAmount(poolId: number): Promise<number> {
+        return Number((await getAccount(provider.connection, vaultPda(poolId), undefined, TOKEN_2022_PROGRAM_ID)).amount);
+    }
+
+    async function fundUser(mint: PublicKey, mintAuthority: Keypair, user: Keypair) {
+        const account = await createAccount(
+            provider.connection,
+            user,
+            mint,
+            user.publicKey,
+            undefined,
+            undefined,
+            TOKEN_2022_PROGRAM_ID
+        );
+        await mintTo(provider.connection, mintAuthority, mint, account, mintAuthority, 10 * DEPOSIT, [], undefined, TOKEN_2022_PROGRAM_ID);
+        tokenAccounts.set(tokenAccountKey(mint, user.publicKey), account);
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        victim = Keypair.generate();
+        attacker = Keypair.generate();
+
+        for (const kp of [admin, victim, attacker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+
+        hookAllowlist = PublicKey.findProgramAddressSync([Buffer.from("hook_allowlist")], program.programId)[0];
+
+        // The attacker creates a mint whose transfer hook they control.
+        const mintKeypair = Keypair.generate();
+        hookMint = mintKeypair.publicKey;
+        const mintLen = getMintLen([ExtensionType.TransferHook]);
+        const rent = await provider.connection.getMinimumBalanceForRentExemption(mintLen);
+
+        await sendAndConfirmTransaction(
+            provider.connection,
+            new Transaction().add(
+                SystemProgram.createAccount({
+                    fromPubkey: attacker.publicKey,
+                    newAccountPubkey: hookMint,
+                    space: mintLen,
+                    lamports: rent,
+                    programId: TOKEN_2022_PROGRAM_ID,
+                }),
+                createInitializeTransferHookInstruction(
+                    hookMint,
+                    attacker.publicKey,
+                    hookProgram.programId,
+                    TOKEN_2022_PROGRAM_ID
+                ),
+                createInitializeMintInstruction(hookMint, DECIMALS, attacker.publicKey, null, TOKEN_2022_PROGRAM_ID)
+            ),
+            [attacker, mintKeypair]
+        );
+
+        hookConfig = PublicKey.findProgramAddressSync(
+            [Buffer.from("hook_config"), hookMint.toBuffer()],
+            hookProgram.programId
+        )[0];
+        extraAccountMetas = PublicKey.findProgramAddressSync(
+            [Buffer.from("extra-account-metas"), hookMint.toBuffer()],
+            hookProgram.programId
+        )[0];
+
+        await hookProgram.methods
+            .initialize()
+            .accounts({
+                hookConfig,
+                extraAccountMetaList: extraAccountMetas,
+                mint: hookMint,
+                authority: attacker.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([attacker])
+            .rpc();
+
+        plainMint = await createMint(
+            provider.connection,
+            admin,
+            admin.publicKey,
+            null,
+            DECIMALS,
+            undefined,
+            undefined,
+            TOKEN_2022_PROGRAM_ID
+        );
+
+        for (const user of [victim, attacker]) {
+            await fundUser(hookMint, attacker, user);
+        }
+        await fundUser(plainMint, admin, victim);
+    });
+
+    describe("Setup", () => {
+        it("Creates an empty hook allowlist and three pools", async () => {
+            await program.methods
+                .initializeAllowlist()
+                .accounts({ hookAllowlist, admin: admin.publicKey, systemProgram: SystemProgram.programId })
+                .signers([admin])
+                .rpc();
+
+            await setupPool(VULNERABLE_POOL_ID, hookMint, [victim, attacker]);
+            await setupPool(SECURE_POOL_ID, hookMint, [victim]);
+            await setupPool(PLAIN_POOL_ID, plainMint, [victim]);
+
+            const allowlist = await program.account.hookAllowlist.fetch(hookAllowlist);
+            expect(allowlist.programs).to.have.length(0);
+        });
+    });
+
+    describe("Exploit: the mint creator's hook decides who can withdraw", () => {
+        it("Victim deposits into a pool for the attacker's mint", async () => {
+            await poolTransfer("vulnerableDeposit", VULNERABLE_POOL_ID, hookMint, victim, DEPOSIT);
+            expect(await vaultAmount(VULNERABLE_POOL_ID)).to.equal(DEPOSIT);
+        });
+
+        it("Attacker blocks transfers signed by the pool", async () => {
+            await hookProgram.methods
+                .setBlocked(poolPda(VULNERABLE_POOL_ID))
+                .accounts({ hookConfig, authority: attacker.publicKey })
+                .signers([attacker])
+                .rpc();
+
+            try {
+                await poolTransfer("vulnerableWithdraw", VULNERABLE_POOL_ID, hookMint, victim, DEPOSIT);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.include("TransferBlocked");
+            }
+        });
+
+        it("Deposits still go through: the failure is selective", async () => {
+            await poolTransfer("vulnerableDeposit", VULNERABLE_POOL_ID, hookMint, attacker, DEPOSIT);
+
+            const vault = await vaultAmount(VULNERABLE_POOL_ID);
+            console.log(`  Vault holds ${vault}; every withdrawal reverts inside the attacker's hook`);
+            expect(vault).to.equal(2 * DEPOSIT);
+        });
+    });
+
+    describe("Secure: hook program checked against an allowlist", () => {
+        it("Rejects a mint whose hook is not allowlisted", async () => {
+            try {
+                await poolTransfer("secureDeposit", SECURE_POOL_ID, hookMint, victim, DEPOSIT);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("HookNotAllowed");
+            }
+        });
+
+        it("Accepts a mint with no hook and leaves the pool unlocked", async () => {
+            await poolTransfer("secureDeposit", PLAIN_POOL_ID, plainMint, victim, DEPOSIT);
+            await poolTransfer("secureWithdraw", PLAIN_POOL_ID, plainMint, victim, DEPOSIT);
+
+            expect(await vaultAmount(PLAIN_POOL_ID)).to.equal(0);
+            expect((await program.account.pool.fetch(poolPda(PLAIN_POOL_ID))).locked).to.equal(false);
+        });
+
+        it("Only the admin can allowlist a hook program", async () => {
+            try {
+                await program.methods
+                    .addHookProgram(hookProgram.programId)
+                    .accounts({ hookAllowlist, admin: attacker.publicKey })
+                    .signers([attacker])
+                    .rpc();
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("Unauthorized");
+            }
+        });
+
+        it("Runs the hook once the admin has allowlisted it", async () => {
+            await program.methods
+                .addHookProgram(hookProgram.programId)
+                .accounts({ hookAllowlist, admin: admin.publicKey })
+                .signers([admin])
+                .rpc();
+
+            await poolTransfer("secureDeposit", SECURE_POOL_ID, hookMint, victim, DEPOSIT);
+            await poolTransfer("secureWithdraw", SECURE_POOL_ID, hookMint, victim, DEPOSIT);
+            expect(await vaultAmount(SECURE_POOL_ID)).to.equal(0);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Token-2022 Transfer Hook Abuse ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  Accept any mint; transfer_checked runs whatever hook the mint names");
+            console.log("  Risk: the mint creator's program decides which transfers succeed\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  require!(allowlist.contains(hook_program)); lock pool around the CPI");
+            console.log("  Protection: only reviewed hook programs run inside pool transfers\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  A Token-2022 mint can attach code to every transfer. Accepting the mint means running that code.");
+        });
+    });
+});
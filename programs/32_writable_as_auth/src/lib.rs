@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("6p4X9iAww74Psb6xbS5jXNgJqN2udW15BLTnaYoQCobJ");
+
+#[program]
+pub mod writable_as_auth {
+    use super::*;
+
+    pub fn open_vault(ctx: Context<OpenVault>, amount: u64) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.owner = ctx.accounts.owner.key();
+        vault.bump = ctx.bumps.vault;
+
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                },
+            ),
+            amount,
+        )
+    }
+
+    // VULNERABLE: Writability Treated as Consent
+    // 1. The owner's key is compared against `vault.owner`, which only
+    //    proves the right pubkey was passed, not who sent the transaction
+    // 2. `is_writable` is then taken as proof the owner approved, on the
+    //    theory that only the fee payer's own wallet is ever writable
+    // 3. The writable flag is set by whoever builds the transaction: any
+    //    account can be writable without signing
+    pub fn vulnerable_withdraw(ctx: Context<VulnerableWithdraw>, amount: u64) -> Result<()> {
+        let owner = &ctx.accounts.owner;
+        require_keys_eq!(owner.key(), ctx.accounts.vault.owner, VaultError::Unauthorized);
+        require!(owner.is_writable, VaultError::NotApproved);
+
+        move_lamports(
+            &ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.destination.to_account_info(),
+            amount,
+        )?;
+        msg!("Vulnerable withdraw: {} to {}", amount, ctx.accounts.destination.key());
+        Ok(())
+    }
+
+    // SECURE: Signer Check for Authority, `mut` for Writes
+    // 1. `owner` is a `Signer`, so the runtime has verified a signature
+    // 2. `has_one = owner` ties that signer to the vault
+    // 3. Writability is requested only where lamports or data change and
+    //    is never read as an authorization signal
+    pub fn secure_withdraw(ctx: Context<SecureWithdraw>, amount: u64) -> Result<()> {
+        move_lamports(
+            &ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.destination.to_account_info(),
+            amount,
+        )?;
+        msg!("Secure withdraw: {} to {}", amount, ctx.accounts.destination.key());
+        Ok(())
+    }
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    let remaining = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(VaultError::InsufficientFunds)?;
+    require!(
+        remaining >= Rent::get()?.minimum_balance(from.data_len()),
+        VaultError::InsufficientFunds
+    );
+    **from.try_borrow_mut_lamports()? = remaining;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(VaultError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct OpenVault<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableWithdraw<'info> {
+    #[account(mut, seeds = [b"vault", vault.owner.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    /// CHECK: VULNERABLE - only its key and `is_writable` flag are checked.
+    pub owner: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub destination: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SecureWithdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::Unauthorized
+    )]
+    pub vault: Account<'info, Vault>,
+    pub owner: Signer<'info>,
+    #[account(mut)]
+    pub destination: SystemAccount<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub owner: Pubkey,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum VaultError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Owner account was not passed as writable")]
+    NotApproved,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
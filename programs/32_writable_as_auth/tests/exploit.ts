@@ -0,0 +1,149 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+    Keypair,
+    LAMPORTS_PER_SOL,
+    PublicKey,
+    SystemProgram,
+    Transaction,
+    TransactionInstruction,
+} from "@solana/web3.js";
+import { expect } from "chai";
+
+describe("32: Writable Flag as Authorization", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.WritableAsAuth as Program<any>;
+
+    let alice: Keypair;
+    let attacker: Keypair;
+
+    const DEPOSIT = LAMPORTS_PER_SOL;
+
+    function vaultPda(owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("vault"), owner.toBuffer()], program.programId)[0];
+    }
+
+    // Builds the withdraw through Anchor, then overrides the owner's account
+    // meta. The flags are whatever the transaction builder says they are.
+    async function withdrawIx(
+        method: "vulnerableWithdraw" | "secureWithdraw",
+        owner: PublicKey,
+        amount: number,
+        flags: { isSigner: boolean; isWritable: boolean },
+        destination: PublicKey = attacker.publicKey
+    ): Promise<TransactionInstruction> {
+        const ix = await program.methods[method](new anchor.BN(amount))
+            .accounts({ vault: vaultPda(owner), owner, destination })
+            .instruction();
+        ix.keys[1] = { ...ix.keys[1], ...flags }; // vault, owner, destination
+        return ix;
+    }
+
+    function send(ix: TransactionInstruction, signers: Keypair[] = [attacker]) {
+        return provider.sendAndConfirm(new Transaction().add(ix), signers);
+    }
+
+    async function vaultLamports(owner: PublicKey): Promise<number> {
+        return provider.connection.getBalance(vaultPda(owner));
+    }
+
+    before(async () => {
+        alice = Keypair.generate();
+        attacker = Keypair.generate();
+
+        for (const kp of [alice, attacker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Alice opens a vault with 1 SOL", async () => {
+            await program.methods
+                .openVault(new anchor.BN(DEPOSIT))
+                .accounts({ vault: vaultPda(alice.publicKey), owner: alice.publicKey, systemProgram: SystemProgram.programId })
+                .signers([alice])
+                .rpc();
+
+            const vault = await program.account.vault.fetch(vaultPda(alice.publicKey));
+            expect(vault.owner.toBase58()).to.equal(alice.publicKey.toBase58());
+        });
+    });
+
+    describe("Exploit: mark the owner writable", () => {
+        it("Read-only owner is rejected, which looks like a working check", async () => {
+            try {
+                await send(await withdrawIx("vulnerableWithdraw", alice.publicKey, DEPOSIT / 2, { isSigner: false, isWritable: false }));
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.include("NotApproved");
+            }
+        });
+
+        it("Attacker flips isWritable on Alice's key and drains Alice's vault", async () => {
+            const before = await vaultLamports(alice.publicKey);
+
+            // Alice does not sign. Writable non-signer accounts are legal.
+            await send(await withdrawIx("vulnerableWithdraw", alice.publicKey, DEPOSIT / 2, { isSigner: false, isWritable: true }));
+
+            const taken = before - (await vaultLamports(alice.publicKey));
+            console.log(`  Attacker withdrew ${taken} lamports without Alice's signature`);
+            expect(taken).to.equal(DEPOSIT / 2);
+        });
+    });
+
+    describe("Secure: signer check, writability only for writes", () => {
+        it("Writable but unsigned owner is rejected", async () => {
+            try {
+                await send(await withdrawIx("secureWithdraw", alice.publicKey, DEPOSIT / 4, { isSigner: false, isWritable: true }));
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.include("AccountNotSigner");
+            }
+        });
+
+        it("Attacker signing for their own key is not the vault owner", async () => {
+            const ix = await program.methods
+                .secureWithdraw(new anchor.BN(DEPOSIT / 4))
+                .accounts({ vault: vaultPda(alice.publicKey), owner: attacker.publicKey, destination: attacker.publicKey })
+                .instruction();
+
+            try {
+                await send(ix);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                // The vault's seeds are derived from `owner`, so a different
+                // signer fails the seeds check before `has_one` runs.
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.include("ConstraintSeeds");
+            }
+        });
+
+        it("Alice's signature authorizes a withdrawal to Alice's wallet", async () => {
+            const before = await vaultLamports(alice.publicKey);
+            await send(await withdrawIx("secureWithdraw", alice.publicKey, DEPOSIT / 4, { isSigner: true, isWritable: false }, alice.publicKey), [alice]);
+            expect(before - (await vaultLamports(alice.publicKey))).to.equal(DEPOSIT / 4);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Writable Flag as Authorization ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  require_keys_eq!(owner.key(), vault.owner); require!(owner.is_writable)");
+            console.log("  Risk: any transaction can mark any account writable without its signature\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  pub owner: Signer<'info> + has_one = owner");
+            console.log("  Protection: only a signature proves the owner approved\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  is_writable says what the transaction may change, not who approved it.");
+        });
+    });
+});
@@ -0,0 +1,311 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+declare_id!("AEux2vbHXPd6Nr5pSmFFFCPUUYGCKVSzMdaisFh5r9XT");
+
+#[program]
+pub mod rebasing_token_staking {
+    use super::*;
+
+    pub fn initialize_pool(ctx: Context<InitializePool>, pool_id: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.pool_id = pool_id;
+        pool.mint = ctx.accounts.mint.key();
+        pool.vault = ctx.accounts.vault.key();
+        pool.total_staked = 0;
+        pool.total_shares = 0;
+        pool.bump = ctx.bumps.pool;
+        Ok(())
+    }
+
+    pub fn open_stake(ctx: Context<OpenStake>) -> Result<()> {
+        let stake = &mut ctx.accounts.stake;
+        stake.pool = ctx.accounts.pool.key();
+        stake.owner = ctx.accounts.owner.key();
+        stake.staked = 0;
+        stake.shares = 0;
+        stake.bump = ctx.bumps.stake;
+        Ok(())
+    }
+
+    // VULNERABLE: Records the Instructed Amount
+    // 1. A transfer fee means the vault receives less than `amount`
+    // 2. A rebase (supply adjustment, permanent-delegate burn) changes the
+    //    vault balance without any instruction of this program running
+    // 3. `staked` is a fixed token amount, so the pool promises more than
+    //    it holds and early unstakers are paid from later stakers' tokens
+    pub fn vulnerable_stake(ctx: Context<StakeTokens>, amount: u64) -> Result<()> {
+        transfer_in(&ctx, amount)?;
+
+        let stake = &mut ctx.accounts.stake;
+        stake.staked = stake.staked.checked_add(amount).ok_or(StakeError::ArithmeticError)?;
+        let pool = &mut ctx.accounts.pool;
+        pool.total_staked = pool
+            .total_staked
+            .checked_add(amount)
+            .ok_or(StakeError::ArithmeticError)?;
+        msg!("Vulnerable stake: sent {}, recorded {}", amount, amount);
+        Ok(())
+    }
+
+    pub fn vulnerable_unstake(ctx: Context<StakeTokens>, amount: u64) -> Result<()> {
+        let stake = &mut ctx.accounts.stake;
+        stake.staked = stake.staked.checked_sub(amount).ok_or(StakeError::InsufficientStake)?;
+        let pool = &mut ctx.accounts.pool;
+        pool.total_staked = pool
+            .total_staked
+            .checked_sub(amount)
+            .ok_or(StakeError::ArithmeticError)?;
+
+        transfer_out(&ctx, amount)?;
+        msg!("Vulnerable unstake: {}", amount);
+        Ok(())
+    }
+
+    // SECURE: Credits the Measured Delta as Shares
+    // 1. Snapshot the vault before the CPI, `reload()` after, and treat
+    //    the difference as the amount received
+    // 2. Convert it to shares of the vault, so each stake is a fraction
+    //    of whatever the vault holds rather than a fixed token amount
+    // 3. Unstaking pays `shares / total_shares` of the current vault
+    //    balance: fees and rebases are shared pro rata
+    pub fn secure_stake(ctx: Context<StakeTokens>, amount: u64) -> Result<()> {
+        let before = ctx.accounts.vault.amount;
+        transfer_in(&ctx, amount)?;
+        ctx.accounts.vault.reload()?;
+        let received = ctx
+            .accounts
+            .vault
+            .amount
+            .checked_sub(before)
+            .ok_or(StakeError::ArithmeticError)?;
+
+        let pool = &mut ctx.accounts.pool;
+        let shares = shares_for_deposit(received, pool.total_shares, before)?;
+        pool.total_shares = pool
+            .total_shares
+            .checked_add(shares)
+            .ok_or(StakeError::ArithmeticError)?;
+        let stake = &mut ctx.accounts.stake;
+        stake.shares = stake.shares.checked_add(shares).ok_or(StakeError::ArithmeticError)?;
+        msg!("Secure stake: sent {}, received {}, minted {} shares", amount, received, shares);
+        Ok(())
+    }
+
+    pub fn secure_unstake(ctx: Context<StakeTokens>, shares: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let amount = amount_for_shares(shares, pool.total_shares, ctx.accounts.vault.amount)?;
+
+        let stake = &mut ctx.accounts.stake;
+        stake.shares = stake.shares.checked_sub(shares).ok_or(StakeError::InsufficientStake)?;
+        pool.total_shares = pool
+            .total_shares
+            .checked_sub(shares)
+            .ok_or(StakeError::ArithmeticError)?;
+
+        transfer_out(&ctx, amount)?;
+        msg!("Secure unstake: {} shares for {}", shares, amount);
+        Ok(())
+    }
+}
+
+/// Shares minted for `received` tokens arriving in a vault that held
+/// `vault_before`. The first stake mints one share per token.
+pub fn shares_for_deposit(received: u64, total_shares: u64, vault_before: u64) -> Result<u64> {
+    let shares = if total_shares == 0 {
+        received
+    } else {
+        require!(vault_before > 0, StakeError::EmptyVault);
+        let shares = (received as u128)
+            .checked_mul(total_shares as u128)
+            .ok_or(StakeError::ArithmeticError)?
+            / vault_before as u128;
+        u64::try_from(shares).map_err(|_| StakeError::ArithmeticError)?
+    };
+    require!(shares > 0, StakeError::ZeroShares);
+    Ok(shares)
+}
+
+/// Tokens owed for `shares` out of `total_shares`, rounded down so the
+/// vault always covers the remaining shares.
+pub fn amount_for_shares(shares: u64, total_shares: u64, vault_balance: u64) -> Result<u64> {
+    require!(
+        shares > 0 && shares <= total_shares,
+        StakeError::InsufficientStake
+    );
+    let amount = (shares as u128)
+        .checked_mul(vault_balance as u128)
+        .ok_or(StakeError::ArithmeticError)?
+        / total_shares as u128;
+    u64::try_from(amount).map_err(|_| error!(StakeError::ArithmeticError))
+}
+
+fn transfer_in(ctx: &Context<StakeTokens>, amount: u64) -> Result<()> {
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.user_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )
+}
+
+fn transfer_out(ctx: &Context<StakeTokens>, amount: u64) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let pool_id = pool.pool_id.to_le_bytes();
+    let seeds: &[&[u8]] = &[b"pool", pool_id.as_ref(), &[pool.bump]];
+    let signer = &[seeds];
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.user_token_account.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u64)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + StakePool::INIT_SPACE,
+        seeds = [b"pool", pool_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, StakePool>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = pool,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenStake<'info> {
+    #[account(seeds = [b"pool", pool.pool_id.to_le_bytes().as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, StakePool>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Stake::INIT_SPACE,
+        seeds = [b"stake", pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub stake: Account<'info, Stake>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct StakeTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.pool_id.to_le_bytes().as_ref()],
+        bump = pool.bump,
+        has_one = mint,
+        has_one = vault
+    )]
+    pub pool: Account<'info, StakePool>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"stake", pool.key().as_ref(), owner.key().as_ref()],
+        bump = stake.bump
+    )]
+    pub stake: Account<'info, Stake>,
+    #[account(mut, token::mint = mint, token::authority = owner)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct StakePool {
+    pub pool_id: u64,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    /// Sum of `Stake::staked`; used by the vulnerable instructions.
+    pub total_staked: u64,
+    /// Sum of `Stake::shares`; used by the secure instructions.
+    pub total_shares: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Stake {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    /// Fixed token amount owed (vulnerable accounting).
+    pub staked: u64,
+    /// Fraction of the vault owned (secure accounting).
+    pub shares: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum StakeError {
+    #[msg("Insufficient stake")]
+    InsufficientStake,
+    #[msg("Vault is empty but shares are outstanding")]
+    EmptyVault,
+    #[msg("Deposit too small to mint a share")]
+    ZeroShares,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_stake_mints_one_share_per_token() {
+        assert_eq!(shares_for_deposit(9_900, 0, 0).unwrap(), 9_900);
+    }
+
+    #[test]
+    fn shares_track_a_shrunken_vault() {
+        // 10,000 shares backed by 9,000 tokens after a 10% negative rebase:
+        // 900 new tokens buy 1,000 shares, worth exactly 900 on the way out.
+        let shares = shares_for_deposit(900, 10_000, 9_000).unwrap();
+        assert_eq!(shares, 1_000);
+        assert_eq!(amount_for_shares(shares, 11_000, 9_900).unwrap(), 900);
+    }
+
+    #[test]
+    fn payout_rounds_down() {
+        assert_eq!(amount_for_shares(1, 3, 10).unwrap(), 3);
+        assert!(amount_for_shares(4, 3, 10).is_err());
+    }
+}
@@ -0,0 +1,256 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+    Keypair,
+    LAMPORTS_PER_SOL,
+    PublicKey,
+    SystemProgram,
+    Transaction,
+    sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import {
+    ExtensionType,
+    TOKEN_2022_PROGRAM_ID,
+    burn,
+    createAccount,
+    createInitializeMintInstruction,
+    createInitializePermanentDelegateInstruction,
+    createInitializeTransferFeeConfigInstruction,
+    getAccount,
+    getMintLen,
+    mintTo,
+} from "@solana/spl-token";
+import { expect } from "chai";
+
+describe("33: Fee-on-Transfer and Rebasing Token Staking", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.RebasingTokenStaking as Program<any>;
+
+    let admin: Keypair;
+    let victim: Keypair;
+    let attacker: Keypair;
+
+    let mint: PublicKey;
+    const tokenAccounts = new Map<string, PublicKey>();
+
+    const VULNERABLE_POOL_ID = 1;
+    const SECURE_POOL_ID = 2;
+    const DECIMALS = 6;
+    const FEE_BPS = 100; // 1%
+    const DEPOSIT = 10_000;
+    const RECEIVED = DEPOSIT - (DEPOSIT * FEE_BPS) / 10_000;
+    const REBASE_BPS = 1_000; // -10%
+
+    function poolPda(poolId: number): PublicKey {
+        const id = Buffer.alloc(8);
+        id.writeBigUInt64LE(BigInt(poolId));
+        return PublicKey.findProgramAddressSync([Buffer.from("pool"), id], program.programId)[0];
+    }
+
+    function vaultPda(poolId: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("vault"), poolPda(poolId).toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function stakePda(poolId: number, owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("stake"), poolPda(poolId).toBuffer(), owner.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    async function setupPool(poolId: number) {
+        await program.methods
+            .initializePool(new anchor.BN(poolId))
+            .accounts({
+                pool: poolPda(poolId),
+                vault: vaultPda(poolId),
+                mint,
+                payer: admin.publicKey,
+                tokenProgram: TOKEN_2022_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([admin])
+            .rpc();
+
+        for (const user of [victim, attacker]) {
+            await program.methods
+                .openStake()
+                .accounts({
+                    pool: poolPda(poolId),
+                    stake: stakePda(poolId, user.publicKey),
+                    owner: user.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([user])
+                .rpc();
+        }
+    }
+
+    function call(
+        method: "vulnerableStake" | "vulnerableUnstake" | "secureStake" | "secureUnstake",
+        poolId: number,
+        user: Keypair,
+        amount: number
+    ) {
+        return program.methods[method](new anchor.BN(amount))
+            .accounts({
+                pool: poolPda(poolId),
+                vault: vaultPda(poolId),
+                mint,
+                stake: stakePda(poolId, user.publicKey),
+                userTokenAccount: tokenAccounts.get(user.publicKey.toBase58()),
+                owner: user.publicKey,
+                tokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .signers([user])
+            .rpc();
+    }
+
+    async function vaultAmount(poolId: number): Promise<number> {
+        return Number((await getAccount(provider.connection, vaultPda(poolId), undefined, TOKEN_2022_PROGRAM_ID)).amount);
+    }
+
+    // Negative rebase: the permanent delegate burns a share of the vault.
+    // Nothing in the staking program runs, yet its backing shrinks.
+    async function rebase(poolId: number): Promise<number> {
+        const cut = Math.floor(((await vaultAmount(poolId)) * REBASE_BPS) / 10_000);
+        await burn(provider.connection, admin, vaultPda(poolId), mint, admin, cut, [], undefined, TOKEN_2022_PROGRAM_ID);
+        return cut;
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        victim = Keypair.generate();
+        attacker = Keypair.generate();
+
+        for (const kp of [admin, victim, attacker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+
+        const mintKeypair = Keypair.generate();
+        mint = mintKeypair.publicKey;
+        const mintLen = getMintLen([ExtensionType.TransferFeeConfig, ExtensionType.PermanentDelegate]);
+        const rent = await provider.connection.getMinimumBalanceForRentExemption(mintLen);
+
+        await sendAndConfirmTransaction(
+            provider.connection,
+            new Transaction().add(
+                SystemProgram.createAccount({
+                    fromPubkey: admin.publicKey,
+                    newAccountPubkey: mint,
+                    space: mintLen,
+                    lamports: rent,
+                    programId: TOKEN_2022_PROGRAM_ID,
+                }),
+                createInitializeTransferFeeConfigInstruction(
+                    mint,
+                    admin.publicKey,
+                    admin.publicKey,
+                    FEE_BPS,
+                    BigInt(1_000_000_000),
+                    TOKEN_2022_PROGRAM_ID
+                ),
+                createInitializePermanentDelegateInstruction(mint, admin.publicKey, TOKEN_2022_PROGRAM_ID),
+                createInitializeMintInstruction(mint, DECIMALS, admin.publicKey, null, TOKEN_2022_PROGRAM_ID)
+            ),
+            [admin, mintKeypair]
+        );
+
+        for (const user of [victim, attacker]) {
+            const account = await createAccount(
+                provider.connection,
+                user,
+                mint,
+                user.publicKey,
+                undefined,
+                undefined,
+                TOKEN_2022_PROGRAM_ID
+            );
+            await mintTo(provider.connection, admin, mint, account, admin, 10 * DEPOSIT, [], undefined, TOKEN_2022_PROGRAM_ID);
+            tokenAccounts.set(user.publicKey.toBase58(), account);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates staking pools for a 1% fee, rebasing mint", async () => {
+            await setupPool(VULNERABLE_POOL_ID);
+            await setupPool(SECURE_POOL_ID);
+
+            const pool = await program.account.stakePool.fetch(poolPda(SECURE_POOL_ID));
+            expect(pool.mint.toBase58()).to.equal(mint.toBase58());
+        });
+    });
+
+    describe("Exploit: record the instructed amount", () => {
+        it("Records 10,000 per stake while the vault receives 9,900", async () => {
+            await call("vulnerableStake", VULNERABLE_POOL_ID, victim, DEPOSIT);
+            await call("vulnerableStake", VULNERABLE_POOL_ID, attacker, DEPOSIT);
+
+            const pool = await program.account.stakePool.fetch(poolPda(VULNERABLE_POOL_ID));
+            expect(pool.totalStaked.toNumber()).to.equal(2 * DEPOSIT);
+            expect(await vaultAmount(VULNERABLE_POOL_ID)).to.equal(2 * RECEIVED);
+        });
+
+        it("A rebase widens the gap; the first unstaker takes the victim's tokens", async () => {
+            const cut = await rebase(VULNERABLE_POOL_ID);
+            console.log(`  Rebase burned ${cut}; vault ${await vaultAmount(VULNERABLE_POOL_ID)} vs recorded ${2 * DEPOSIT}`);
+
+            await call("vulnerableUnstake", VULNERABLE_POOL_ID, attacker, DEPOSIT);
+
+            try {
+                await call("vulnerableUnstake", VULNERABLE_POOL_ID, victim, DEPOSIT);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.include("insufficient funds");
+            }
+        });
+    });
+
+    describe("Secure: shares of the measured delta", () => {
+        it("Mints shares for what arrived, not what was sent", async () => {
+            await call("secureStake", SECURE_POOL_ID, victim, DEPOSIT);
+            await call("secureStake", SECURE_POOL_ID, attacker, DEPOSIT);
+
+            const stake = await program.account.stake.fetch(stakePda(SECURE_POOL_ID, victim.publicKey));
+            const pool = await program.account.stakePool.fetch(poolPda(SECURE_POOL_ID));
+            expect(stake.shares.toNumber()).to.equal(RECEIVED);
+            expect(pool.totalShares.toNumber()).to.equal(await vaultAmount(SECURE_POOL_ID));
+        });
+
+        it("Both stakers bear the rebase equally and the vault empties exactly", async () => {
+            await rebase(SECURE_POOL_ID);
+            const perStaker = (await vaultAmount(SECURE_POOL_ID)) / 2;
+
+            const before = await vaultAmount(SECURE_POOL_ID);
+            await call("secureUnstake", SECURE_POOL_ID, attacker, RECEIVED);
+            expect(before - (await vaultAmount(SECURE_POOL_ID))).to.equal(perStaker);
+
+            await call("secureUnstake", SECURE_POOL_ID, victim, RECEIVED);
+            expect(await vaultAmount(SECURE_POOL_ID)).to.equal(0);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Fee-on-Transfer and Rebasing Token Staking ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  transfer_checked(amount); stake.staked += amount");
+            console.log("  Risk: fees and rebases leave the vault short of what is recorded\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  received = vault_after - vault_before; shares = received * total_shares / vault_before");
+            console.log("  Protection: stakes are fractions of the vault, so every change is shared pro rata\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Record what the vault holds, not what the instruction said.");
+        });
+    });
+});
@@ -0,0 +1,203 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("FEJLjJm23DTLzME6Y58VkASyXaovSypv6qJ5XEiyGugd");
+
+#[program]
+pub mod pda_existence_assumption {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>, claim_amount: u64, funding: u64) -> Result<()> {
+        let airdrop = &mut ctx.accounts.airdrop;
+        airdrop.admin = ctx.accounts.admin.key();
+        airdrop.claim_amount = claim_amount;
+        airdrop.bump = ctx.bumps.airdrop;
+
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: ctx.accounts.airdrop.to_account_info(),
+                },
+            ),
+            funding,
+        )
+    }
+
+    /// Admin-only: creates the user's `Registration` PDA.
+    pub fn register(ctx: Context<Register>, user: Pubkey) -> Result<()> {
+        let registration = &mut ctx.accounts.registration;
+        registration.user = user;
+        registration.bump = ctx.bumps.registration;
+        Ok(())
+    }
+
+    // VULNERABLE: Existence at the PDA Treated as Registration
+    // 1. The address is derived correctly, so the code assumes only this
+    //    program could have put an account there
+    // 2. Anyone can send lamports to any address, PDAs included; the
+    //    result is a system-owned account with no data
+    // 3. "Has lamports" is not "was created by us": an attacker funds their
+    //    own registration PDA and claims without ever being registered
+    pub fn vulnerable_claim(ctx: Context<VulnerableClaim>) -> Result<()> {
+        let registration = &ctx.accounts.registration;
+        require!(registration.lamports() > 0, AirdropError::NotRegistered);
+
+        ctx.accounts.receipt.claimant = ctx.accounts.claimant.key();
+        pay_claim(&ctx.accounts.airdrop, &ctx.accounts.claimant.to_account_info())?;
+        msg!("Vulnerable claim: registration owned by {}", registration.owner);
+        Ok(())
+    }
+
+    // SECURE: Owner and Discriminator Checked Before Trusting Contents
+    // 1. `owner == crate::ID`: only this program can have written the data
+    // 2. `try_deserialize` checks the `Registration` discriminator, so a
+    //    different account type of this program is rejected too
+    // 3. The stored `user` must match the claimant
+    pub fn secure_claim(ctx: Context<SecureClaim>) -> Result<()> {
+        let info = &ctx.accounts.registration;
+        require_keys_eq!(*info.owner, crate::ID, AirdropError::InvalidOwner);
+        let registration = Registration::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require_keys_eq!(
+            registration.user,
+            ctx.accounts.claimant.key(),
+            AirdropError::Unauthorized
+        );
+
+        ctx.accounts.receipt.claimant = ctx.accounts.claimant.key();
+        pay_claim(&ctx.accounts.airdrop, &ctx.accounts.claimant.to_account_info())?;
+        msg!("Secure claim: {}", registration.user);
+        Ok(())
+    }
+}
+
+fn pay_claim(airdrop: &Account<Airdrop>, claimant: &AccountInfo) -> Result<()> {
+    let from = airdrop.to_account_info();
+    let amount = airdrop.claim_amount;
+    let remaining = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(AirdropError::InsufficientFunds)?;
+    require!(
+        remaining >= Rent::get()?.minimum_balance(from.data_len()),
+        AirdropError::InsufficientFunds
+    );
+    **from.try_borrow_mut_lamports()? = remaining;
+    **claimant.try_borrow_mut_lamports()? = claimant
+        .lamports()
+        .checked_add(amount)
+        .ok_or(AirdropError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Airdrop::INIT_SPACE,
+        seeds = [b"airdrop"],
+        bump
+    )]
+    pub airdrop: Account<'info, Airdrop>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct Register<'info> {
+    #[account(seeds = [b"airdrop"], bump = airdrop.bump, has_one = admin @ AirdropError::Unauthorized)]
+    pub airdrop: Account<'info, Airdrop>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Registration::INIT_SPACE,
+        seeds = [b"registration", user.as_ref()],
+        bump
+    )]
+    pub registration: Account<'info, Registration>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableClaim<'info> {
+    #[account(mut, seeds = [b"airdrop"], bump = airdrop.bump)]
+    pub airdrop: Account<'info, Airdrop>,
+    /// CHECK: VULNERABLE - address is derived, but owner and data are never checked.
+    #[account(seeds = [b"registration", claimant.key().as_ref()], bump)]
+    pub registration: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = claimant,
+        space = 8 + ClaimReceipt::INIT_SPACE,
+        seeds = [b"receipt", claimant.key().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, ClaimReceipt>,
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SecureClaim<'info> {
+    #[account(mut, seeds = [b"airdrop"], bump = airdrop.bump)]
+    pub airdrop: Account<'info, Airdrop>,
+    /// CHECK: Owner and discriminator are checked in the handler; in most
+    /// code `Account<'info, Registration>` does both implicitly.
+    #[account(seeds = [b"registration", claimant.key().as_ref()], bump)]
+    pub registration: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = claimant,
+        space = 8 + ClaimReceipt::INIT_SPACE,
+        seeds = [b"receipt", claimant.key().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, ClaimReceipt>,
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Airdrop {
+    pub admin: Pubkey,
+    /// Lamports paid per claim, out of this account's own balance.
+    pub claim_amount: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Registration {
+    pub user: Pubkey,
+    pub bump: u8,
+}
+
+/// Created on claim; its `init` makes each user's claim one-shot.
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimReceipt {
+    pub claimant: Pubkey,
+}
+
+#[error_code]
+pub enum AirdropError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("User is not registered")]
+    NotRegistered,
+    #[msg("Registration is not owned by this program")]
+    InvalidOwner,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
@@ -0,0 +1,156 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { expect } from "chai";
+
+describe("34: PDA Existence Assumption", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.PdaExistenceAssumption as Program<any>;
+
+    let admin: Keypair;
+    let alice: Keypair;
+    let attacker: Keypair;
+    let secondAttacker: Keypair;
+    let airdrop: PublicKey;
+
+    const CLAIM_AMOUNT = LAMPORTS_PER_SOL / 10;
+    const FUNDING = 2 * LAMPORTS_PER_SOL;
+    const SQUAT_LAMPORTS = 1_000_000; // enough to be rent-exempt with no data
+
+    function registrationPda(user: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("registration"), user.toBuffer()], program.programId)[0];
+    }
+
+    function receiptPda(user: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("receipt"), user.toBuffer()], program.programId)[0];
+    }
+
+    function claim(method: "vulnerableClaim" | "secureClaim", claimant: Keypair) {
+        return program.methods[method]()
+            .accounts({
+                airdrop,
+                registration: registrationPda(claimant.publicKey),
+                receipt: receiptPda(claimant.publicKey),
+                claimant: claimant.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([claimant])
+            .rpc();
+    }
+
+    // A plain system transfer to the PDA address. No signature from the
+    // program is needed to send lamports somewhere.
+    async function squat(user: Keypair) {
+        await provider.sendAndConfirm(
+            new Transaction().add(
+                SystemProgram.transfer({
+                    fromPubkey: user.publicKey,
+                    toPubkey: registrationPda(user.publicKey),
+                    lamports: SQUAT_LAMPORTS,
+                })
+            ),
+            [user]
+        );
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        alice = Keypair.generate();
+        attacker = Keypair.generate();
+        secondAttacker = Keypair.generate();
+
+        for (const kp of [admin, alice, attacker, secondAttacker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+
+        airdrop = PublicKey.findProgramAddressSync([Buffer.from("airdrop")], program.programId)[0];
+    });
+
+    describe("Setup", () => {
+        it("Funds the airdrop and registers Alice", async () => {
+            await program.methods
+                .initialize(new anchor.BN(CLAIM_AMOUNT), new anchor.BN(FUNDING))
+                .accounts({ airdrop, admin: admin.publicKey, systemProgram: SystemProgram.programId })
+                .signers([admin])
+                .rpc();
+
+            await program.methods
+                .register(alice.publicKey)
+                .accounts({
+                    airdrop,
+                    registration: registrationPda(alice.publicKey),
+                    admin: admin.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([admin])
+                .rpc();
+
+            const info = await provider.connection.getAccountInfo(registrationPda(alice.publicKey));
+            expect(info!.owner.toBase58()).to.equal(program.programId.toBase58());
+        });
+    });
+
+    describe("Exploit: fund your own registration PDA", () => {
+        it("An unregistered user is rejected while the PDA is empty", async () => {
+            try {
+                await claim("vulnerableClaim", attacker);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("NotRegistered");
+            }
+        });
+
+        it("After a system transfer to the PDA, the claim pays out", async () => {
+            await squat(attacker);
+            const info = await provider.connection.getAccountInfo(registrationPda(attacker.publicKey));
+            expect(info!.owner.toBase58()).to.equal(SystemProgram.programId.toBase58());
+            expect(info!.data.length).to.equal(0);
+
+            const before = await provider.connection.getBalance(airdrop);
+            await claim("vulnerableClaim", attacker);
+            const paid = before - (await provider.connection.getBalance(airdrop));
+
+            console.log(`  Attacker spent ${SQUAT_LAMPORTS} lamports, claimed ${paid} without registration`);
+            expect(paid).to.equal(CLAIM_AMOUNT);
+        });
+    });
+
+    describe("Secure: owner and discriminator checked", () => {
+        it("Rejects a system-owned account at the registration address", async () => {
+            await squat(secondAttacker);
+
+            try {
+                await claim("secureClaim", secondAttacker);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.message).to.include("InvalidOwner");
+            }
+        });
+
+        it("Pays a registered user", async () => {
+            const before = await provider.connection.getBalance(airdrop);
+            await claim("secureClaim", alice);
+            expect(before - (await provider.connection.getBalance(airdrop))).to.equal(CLAIM_AMOUNT);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== PDA Existence Assumption ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  seeds check on an UncheckedAccount, then require!(registration.lamports() > 0)");
+            console.log("  Risk: anyone can fund any address; a system account passes the check\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  require_keys_eq!(*info.owner, crate::ID); Registration::try_deserialize(...)");
+            console.log("  Protection: only data this program wrote, of the right type, is trusted\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  A PDA address is reserved for your signature, not for your accounts.");
+        });
+    });
+});
@@ -0,0 +1,265 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("AbKECSdRrU96bQjvZwhyAsJnvbXgRSWdMpup3tbGKB2g");
+
+/// Share of a liquidated position paid to the liquidator.
+pub const LIQUIDATION_BONUS_BPS: u64 = 500;
+
+#[program]
+pub mod retroactive_limit_update {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>, max_deposit: u64) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.max_deposit = max_deposit;
+        config.version = 1;
+        config.bump = ctx.bumps.config;
+        Ok(())
+    }
+
+    /// Changes the limit and bumps `version`. Existing positions keep the
+    /// limit they were opened under until their owner migrates.
+    pub fn set_max_deposit(ctx: Context<AdminConfig>, max_deposit: u64) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.max_deposit = max_deposit;
+        config.version = config.version.checked_add(1).ok_or(LimitError::ArithmeticError)?;
+        msg!("Config v{}: max_deposit = {}", config.version, max_deposit);
+        Ok(())
+    }
+
+    pub fn open_position(ctx: Context<OpenPosition>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let position = &mut ctx.accounts.position;
+        position.owner = ctx.accounts.owner.key();
+        position.amount = 0;
+        position.max_deposit = config.max_deposit;
+        position.config_version = config.version;
+        position.bump = ctx.bumps.position;
+        Ok(())
+    }
+
+    /// Owner opts an existing position into the current limit, e.g. to
+    /// use a raised cap. Only allowed if the position already complies.
+    pub fn migrate_position(ctx: Context<OwnerPosition>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let position = &mut ctx.accounts.position;
+        require!(
+            position.amount <= config.max_deposit,
+            LimitError::ExceedsCurrentLimit
+        );
+        position.max_deposit = config.max_deposit;
+        position.config_version = config.version;
+        Ok(())
+    }
+
+    // VULNERABLE: Limit Read Live From Config
+    // 1. The cap for an existing position is whatever `config.max_deposit`
+    //    says right now, not what the owner agreed to when opening
+    // 2. Raising the limit instantly raises every existing position's cap,
+    //    including caps the protocol sized risk against earlier
+    pub fn vulnerable_deposit(ctx: Context<OwnerPosition>, amount: u64) -> Result<()> {
+        let limit = ctx.accounts.config.max_deposit;
+        deposit(ctx.accounts, amount, limit)?;
+        msg!("Vulnerable deposit: {} (live limit {})", amount, limit);
+        Ok(())
+    }
+
+    // VULNERABLE: Liquidation Against the Live Limit
+    // 1. A position is "over limit" if it exceeds today's `max_deposit`
+    // 2. Lowering the limit turns every compliant position above the new
+    //    value into a liquidation target at once; the owner did nothing
+    pub fn vulnerable_liquidate(ctx: Context<Liquidate>) -> Result<()> {
+        let limit = ctx.accounts.config.max_deposit;
+        require!(ctx.accounts.position.amount > limit, LimitError::PositionCompliant);
+        let bonus = liquidate(ctx.accounts)?;
+        msg!("Vulnerable liquidate: over live limit {}, bonus {}", limit, bonus);
+        Ok(())
+    }
+
+    // SECURE: Limits Grandfathered per Position
+    // 1. Each position stores the `max_deposit` and config version it was
+    //    opened under
+    // 2. Deposits are checked against that stored limit, so raises apply
+    //    to new (or explicitly migrated) positions only
+    pub fn secure_deposit(ctx: Context<OwnerPosition>, amount: u64) -> Result<()> {
+        let limit = ctx.accounts.position.max_deposit;
+        deposit(ctx.accounts, amount, limit)?;
+        msg!(
+            "Secure deposit: {} (v{} limit {})",
+            amount,
+            ctx.accounts.position.config_version,
+            limit
+        );
+        Ok(())
+    }
+
+    // SECURE: Liquidation Against the Grandfathered Limit
+    // 1. Only positions over the limit they opened under can be liquidated
+    // 2. Lowering `max_deposit` restricts new positions; it never turns an
+    //    existing compliant position into a target
+    pub fn secure_liquidate(ctx: Context<Liquidate>) -> Result<()> {
+        let limit = ctx.accounts.position.max_deposit;
+        require!(ctx.accounts.position.amount > limit, LimitError::PositionCompliant);
+        let bonus = liquidate(ctx.accounts)?;
+        msg!("Secure liquidate: over grandfathered limit {}, bonus {}", limit, bonus);
+        Ok(())
+    }
+}
+
+fn deposit(accounts: &mut OwnerPosition, amount: u64, limit: u64) -> Result<()> {
+    let position = &mut accounts.position;
+    let new_amount = position.amount.checked_add(amount).ok_or(LimitError::ArithmeticError)?;
+    require!(new_amount <= limit, LimitError::ExceedsLimit);
+    position.amount = new_amount;
+
+    transfer(
+        CpiContext::new(
+            accounts.system_program.to_account_info(),
+            Transfer {
+                from: accounts.owner.to_account_info(),
+                to: accounts.config.to_account_info(),
+            },
+        ),
+        amount,
+    )
+}
+
+/// Pays the liquidator `LIQUIDATION_BONUS_BPS` of the position and returns
+/// the rest to the owner. Returns the bonus.
+fn liquidate(accounts: &mut Liquidate) -> Result<u64> {
+    let amount = accounts.position.amount;
+    let bonus = amount
+        .checked_mul(LIQUIDATION_BONUS_BPS)
+        .ok_or(LimitError::ArithmeticError)?
+        / 10_000;
+    let refund = amount - bonus;
+    accounts.position.amount = 0;
+
+    let config = accounts.config.to_account_info();
+    move_lamports(&config, &accounts.liquidator.to_account_info(), bonus)?;
+    move_lamports(&config, &accounts.owner.to_account_info(), refund)?;
+    Ok(bonus)
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(LimitError::ArithmeticError)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(LimitError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AdminConfig<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump, has_one = admin @ LimitError::Unauthorized)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [b"position", owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OwnerPosition<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"position", owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner @ LimitError::Unauthorized
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Liquidate<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"position", position.owner.as_ref()],
+        bump = position.bump,
+        has_one = owner
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: SystemAccount<'info>,
+    #[account(mut)]
+    pub liquidator: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    /// Limit for positions opened (or migrated) from now on.
+    pub max_deposit: u64,
+    /// Bumped on every limit change.
+    pub version: u32,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub owner: Pubkey,
+    pub amount: u64,
+    /// `Config::max_deposit` when the position was opened or last migrated.
+    pub max_deposit: u64,
+    /// `Config::version` when the position was opened or last migrated.
+    pub config_version: u32,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum LimitError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Deposit would exceed the position's limit")]
+    ExceedsLimit,
+    #[msg("Position exceeds the current limit and cannot migrate")]
+    ExceedsCurrentLimit,
+    #[msg("Position is within its limit")]
+    PositionCompliant,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
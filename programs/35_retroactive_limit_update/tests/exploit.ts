@@ -0,0 +1,185 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("35: Retroactive Limit Update", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.RetroactiveLimitUpdate as Program<any>;
+
+    let admin: Keypair;
+    let alice: Keypair; // uses the vulnerable instructions
+    let bob: Keypair; // uses the secure instructions
+    let dave: Keypair; // opens after the limit is cut
+    let liquidator: Keypair;
+    let config: PublicKey;
+
+    const V1_LIMIT = LAMPORTS_PER_SOL;
+    const V2_LIMIT = LAMPORTS_PER_SOL / 2;
+    const V3_LIMIT = 3 * LAMPORTS_PER_SOL;
+    const BONUS_BPS = 500;
+
+    function positionPda(owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("position"), owner.toBuffer()], program.programId)[0];
+    }
+
+    function setLimit(limit: number) {
+        return program.methods
+            .setMaxDeposit(new anchor.BN(limit))
+            .accounts({ config, admin: admin.publicKey })
+            .signers([admin])
+            .rpc();
+    }
+
+    function openPosition(owner: Keypair) {
+        return program.methods
+            .openPosition()
+            .accounts({ config, position: positionPda(owner.publicKey), owner: owner.publicKey, systemProgram: SystemProgram.programId })
+            .signers([owner])
+            .rpc();
+    }
+
+    function deposit(method: "vulnerableDeposit" | "secureDeposit", owner: Keypair, amount: number) {
+        return program.methods[method](new anchor.BN(amount))
+            .accounts({ config, position: positionPda(owner.publicKey), owner: owner.publicKey, systemProgram: SystemProgram.programId })
+            .signers([owner])
+            .rpc();
+    }
+
+    function migrate(owner: Keypair) {
+        return program.methods
+            .migratePosition()
+            .accounts({ config, position: positionPda(owner.publicKey), owner: owner.publicKey, systemProgram: SystemProgram.programId })
+            .signers([owner])
+            .rpc();
+    }
+
+    function liquidate(method: "vulnerableLiquidate" | "secureLiquidate", owner: Keypair) {
+        return program.methods[method]()
+            .accounts({ config, position: positionPda(owner.publicKey), owner: owner.publicKey, liquidator: liquidator.publicKey })
+            .signers([liquidator])
+            .rpc();
+    }
+
+    async function position(owner: Keypair) {
+        return program.account.position.fetch(positionPda(owner.publicKey));
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        alice = Keypair.generate();
+        bob = Keypair.generate();
+        dave = Keypair.generate();
+        liquidator = Keypair.generate();
+
+        for (const kp of [admin, alice, bob, dave, liquidator]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+
+        config = PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId)[0];
+    });
+
+    describe("Setup", () => {
+        it("Opens two positions under v1 and fills both exactly to the limit", async () => {
+            await program.methods
+                .initialize(new anchor.BN(V1_LIMIT))
+                .accounts({ config, admin: admin.publicKey, systemProgram: SystemProgram.programId })
+                .signers([admin])
+                .rpc();
+
+            await openPosition(alice);
+            await openPosition(bob);
+            await deposit("vulnerableDeposit", alice, V1_LIMIT);
+            await deposit("secureDeposit", bob, V1_LIMIT);
+
+            // One lamport over the boundary fails on both paths while the limit is unchanged.
+            await expectError(deposit("vulnerableDeposit", alice, 1), "ExceedsLimit");
+            await expectError(deposit("secureDeposit", bob, 1), "ExceedsLimit");
+
+            const pos = await position(bob);
+            expect(pos.configVersion).to.equal(1);
+            expect(pos.maxDeposit.toNumber()).to.equal(V1_LIMIT);
+        });
+    });
+
+    describe("Exploit: lower the limit under existing positions", () => {
+        it("A compliant position becomes liquidatable the moment the limit drops", async () => {
+            await setLimit(V2_LIMIT);
+
+            const before = await provider.connection.getBalance(liquidator.publicKey);
+            await liquidate("vulnerableLiquidate", alice);
+            const earned = (await provider.connection.getBalance(liquidator.publicKey)) - before;
+
+            console.log(`  Liquidator earned ${earned} lamports from a position that never broke a rule`);
+            expect((await position(alice)).amount.toNumber()).to.equal(0);
+        });
+    });
+
+    describe("Secure: grandfathered limits survive a cut", () => {
+        it("The v1 position stays compliant under its own limit", async () => {
+            await expectError(liquidate("secureLiquidate", bob), "PositionCompliant");
+            expect((await position(bob)).amount.toNumber()).to.equal(V1_LIMIT);
+        });
+
+        it("New positions get the v2 limit, boundary included", async () => {
+            await openPosition(dave);
+            await deposit("secureDeposit", dave, V2_LIMIT);
+            await expectError(deposit("secureDeposit", dave, 1), "ExceedsLimit");
+
+            const pos = await position(dave);
+            expect(pos.configVersion).to.equal(2);
+            expect(pos.maxDeposit.toNumber()).to.equal(V2_LIMIT);
+        });
+
+        it("A position above the current limit cannot migrate onto it", async () => {
+            await expectError(migrate(bob), "ExceedsCurrentLimit");
+        });
+    });
+
+    describe("Exploit: raise the limit under existing caps", () => {
+        it("A v1 position grows past the cap it was opened with", async () => {
+            await setLimit(V3_LIMIT);
+
+            await deposit("vulnerableDeposit", alice, 2 * V1_LIMIT);
+            const pos = await position(alice);
+            console.log(`  v1 position (cap ${V1_LIMIT}) now holds ${pos.amount.toNumber()}`);
+            expect(pos.amount.toNumber()).to.be.greaterThan(pos.maxDeposit.toNumber());
+        });
+    });
+
+    describe("Secure: raises apply to new or migrated positions", () => {
+        it("The v1 position keeps its v1 cap", async () => {
+            await expectError(deposit("secureDeposit", bob, 1), "ExceedsLimit");
+        });
+
+        it("After an explicit migration the owner gets the v3 cap", async () => {
+            await migrate(bob);
+            await deposit("secureDeposit", bob, V3_LIMIT - V1_LIMIT);
+
+            const pos = await position(bob);
+            expect(pos.configVersion).to.equal(3);
+            expect(pos.amount.toNumber()).to.equal(V3_LIMIT);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Retroactive Limit Update ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  require!(position.amount <= config.max_deposit) on every deposit and liquidation");
+            console.log(`  Risk: a cut makes compliant positions liquidatable (${BONUS_BPS / 100}% bonus); a raise lifts old caps\n`);
+
+            console.log("SECURE PATTERN:");
+            console.log("  position.max_deposit / config_version snapshotted at open; owner opts in via migrate");
+            console.log("  Protection: limit changes apply to new positions only\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  A parameter change is a new rule. Existing positions were opened under the old one.");
+        });
+    });
+});
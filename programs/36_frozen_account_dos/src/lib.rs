@@ -0,0 +1,228 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("8N94VEvpp3NnP4tgXmLq5uKwEX2EnGUB3qQiL68zEDAo");
+
+/// Maximum number of recipients in one distribution.
+pub const MAX_RECIPIENTS: usize = 8;
+
+#[program]
+pub mod frozen_account_dos {
+    use super::*;
+
+    /// Creates a distribution paying `amount_per_recipient` to each listed
+    /// wallet. The vault is funded separately.
+    pub fn create_distribution(
+        ctx: Context<CreateDistribution>,
+        distribution_id: u64,
+        amount_per_recipient: u64,
+        recipients: Vec<Pubkey>,
+    ) -> Result<()> {
+        require!(
+            recipients.len() <= MAX_RECIPIENTS,
+            DistributionError::TooManyRecipients
+        );
+
+        let distribution = &mut ctx.accounts.distribution;
+        distribution.distribution_id = distribution_id;
+        distribution.admin = ctx.accounts.admin.key();
+        distribution.mint = ctx.accounts.mint.key();
+        distribution.amount_per_recipient = amount_per_recipient;
+        distribution.recipients = recipients;
+        distribution.settled = false;
+        distribution.bump = ctx.bumps.distribution;
+        distribution.vault_bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    // VULNERABLE: Push Payouts in One Batch
+    // 1. The admin pushes every payout in a single instruction, one
+    //    transfer per recipient token account
+    // 2. A token account can be frozen by the mint's freeze authority (or
+    //    closed by its owner); a transfer into it fails
+    // 3. One failing transfer reverts the whole instruction: nobody is
+    //    paid, and retrying fails the same way until the list changes
+    pub fn vulnerable_settle<'info>(ctx: Context<'_, '_, 'info, 'info, Settle<'info>>) -> Result<()> {
+        let distribution = &ctx.accounts.distribution;
+        require!(!distribution.settled, DistributionError::AlreadySettled);
+        require!(
+            ctx.remaining_accounts.len() == distribution.recipients.len(),
+            DistributionError::RecipientMismatch
+        );
+
+        for (info, recipient) in ctx.remaining_accounts.iter().zip(&distribution.recipients) {
+            let destination = Account::<TokenAccount>::try_from(info)?;
+            require_keys_eq!(destination.owner, *recipient, DistributionError::RecipientMismatch);
+            require_keys_eq!(destination.mint, distribution.mint, DistributionError::RecipientMismatch);
+
+            pay_out(
+                distribution,
+                &ctx.accounts.vault,
+                info.clone(),
+                &ctx.accounts.token_program,
+            )?;
+        }
+
+        ctx.accounts.distribution.settled = true;
+        msg!("Vulnerable settle: paid {} recipients", ctx.remaining_accounts.len());
+        Ok(())
+    }
+
+    // SECURE: Pull Payments
+    // 1. Each recipient claims their own payout in their own transaction
+    // 2. A `Claim` PDA per (distribution, recipient) makes claims one-shot
+    // 3. A frozen account only fails its own claim, and the recipient can
+    //    claim to any other token account they own
+    pub fn secure_claim(ctx: Context<SecureClaim>) -> Result<()> {
+        let distribution = &ctx.accounts.distribution;
+        let recipient = ctx.accounts.recipient.key();
+        require!(
+            distribution.recipients.contains(&recipient),
+            DistributionError::NotARecipient
+        );
+
+        let claim = &mut ctx.accounts.claim;
+        claim.distribution = distribution.key();
+        claim.recipient = recipient;
+
+        pay_out(
+            distribution,
+            &ctx.accounts.vault,
+            ctx.accounts.destination.to_account_info(),
+            &ctx.accounts.token_program,
+        )?;
+        msg!("Secure claim: {} paid {}", recipient, distribution.amount_per_recipient);
+        Ok(())
+    }
+}
+
+fn pay_out<'info>(
+    distribution: &Account<'info, Distribution>,
+    vault: &Account<'info, TokenAccount>,
+    destination: AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    let distribution_id = distribution.distribution_id.to_le_bytes();
+    let seeds: &[&[u8]] = &[b"distribution", distribution_id.as_ref(), &[distribution.bump]];
+    let signer = &[seeds];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: vault.to_account_info(),
+                to: destination,
+                authority: distribution.to_account_info(),
+            },
+            signer,
+        ),
+        distribution.amount_per_recipient,
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(distribution_id: u64, amount_per_recipient: u64, recipients: Vec<Pubkey>)]
+pub struct CreateDistribution<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Distribution::INIT_SPACE,
+        seeds = [b"distribution", distribution_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub distribution: Account<'info, Distribution>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"vault", distribution.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = distribution
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Recipient token accounts are passed as remaining accounts, in the
+/// order of `distribution.recipients`.
+#[derive(Accounts)]
+pub struct Settle<'info> {
+    #[account(
+        mut,
+        seeds = [b"distribution", distribution.distribution_id.to_le_bytes().as_ref()],
+        bump = distribution.bump,
+        has_one = admin @ DistributionError::Unauthorized
+    )]
+    pub distribution: Account<'info, Distribution>,
+    #[account(mut, seeds = [b"vault", distribution.key().as_ref()], bump = distribution.vault_bump)]
+    pub vault: Account<'info, TokenAccount>,
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SecureClaim<'info> {
+    #[account(
+        seeds = [b"distribution", distribution.distribution_id.to_le_bytes().as_ref()],
+        bump = distribution.bump
+    )]
+    pub distribution: Account<'info, Distribution>,
+    #[account(mut, seeds = [b"vault", distribution.key().as_ref()], bump = distribution.vault_bump)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = recipient,
+        space = 8 + Claim::INIT_SPACE,
+        seeds = [b"claim", distribution.key().as_ref(), recipient.key().as_ref()],
+        bump
+    )]
+    pub claim: Account<'info, Claim>,
+    #[account(mut, token::mint = distribution.mint, token::authority = recipient)]
+    pub destination: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Distribution {
+    pub distribution_id: u64,
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub amount_per_recipient: u64,
+    /// Recipient wallets; payouts go to token accounts they own.
+    #[max_len(MAX_RECIPIENTS)]
+    pub recipients: Vec<Pubkey>,
+    /// Set by `vulnerable_settle` once every payout has been pushed.
+    pub settled: bool,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+/// Exists once a recipient has claimed.
+#[account]
+#[derive(InitSpace)]
+pub struct Claim {
+    pub distribution: Pubkey,
+    pub recipient: Pubkey,
+}
+
+#[error_code]
+pub enum DistributionError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Too many recipients")]
+    TooManyRecipients,
+    #[msg("Recipient accounts do not match the distribution")]
+    RecipientMismatch,
+    #[msg("Signer is not a recipient of this distribution")]
+    NotARecipient,
+    #[msg("Distribution already settled")]
+    AlreadySettled,
+}
@@ -0,0 +1,213 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import {
+    TOKEN_PROGRAM_ID,
+    createAccount,
+    createMint,
+    freezeAccount,
+    getAccount,
+    mintTo,
+} from "@solana/spl-token";
+import { expect } from "chai";
+
+describe("36: Frozen Token Account DoS", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.FrozenAccountDos as Program<any>;
+
+    let admin: Keypair;
+    let alice: Keypair;
+    let bob: Keypair;
+    let carol: Keypair;
+
+    let mint: PublicKey;
+    const tokenAccounts = new Map<string, PublicKey>();
+
+    const VULNERABLE_ID = 1;
+    const SECURE_ID = 2;
+    const AMOUNT = 1_000;
+
+    function distributionPda(id: number): PublicKey {
+        const buf = Buffer.alloc(8);
+        buf.writeBigUInt64LE(BigInt(id));
+        return PublicKey.findProgramAddressSync([Buffer.from("distribution"), buf], program.programId)[0];
+    }
+
+    function vaultPda(id: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("vault"), distributionPda(id).toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function claimPda(id: number, recipient: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("claim"), distributionPda(id).toBuffer(), recipient.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function recipients(): Keypair[] {
+        return [alice, bob, carol];
+    }
+
+    async function createDistribution(id: number) {
+        await program.methods
+            .createDistribution(new anchor.BN(id), new anchor.BN(AMOUNT), recipients().map((kp) => kp.publicKey))
+            .accounts({
+                distribution: distributionPda(id),
+                vault: vaultPda(id),
+                mint,
+                admin: admin.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([admin])
+            .rpc();
+
+        await mintTo(provider.connection, admin, mint, vaultPda(id), admin, recipients().length * AMOUNT);
+    }
+
+    function claim(recipient: Keypair, destination: PublicKey) {
+        return program.methods
+            .secureClaim()
+            .accounts({
+                distribution: distributionPda(SECURE_ID),
+                vault: vaultPda(SECURE_ID),
+                claim: claimPda(SECURE_ID, recipient.publicKey),
+                destination,
+                recipient: recipient.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([recipient])
+            .rpc();
+    }
+
+    async function balance(account: PublicKey): Promise<number> {
+        return Number((await getAccount(provider.connection, account)).amount);
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        alice = Keypair.generate();
+        bob = Keypair.generate();
+        carol = Keypair.generate();
+
+        for (const kp of [admin, alice, bob, carol]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+
+        // The mint has a freeze authority, as most regulated stablecoins do.
+        mint = await createMint(provider.connection, admin, admin.publicKey, admin.publicKey, 6);
+
+        for (const kp of recipients()) {
+            tokenAccounts.set(kp.publicKey.toBase58(), await createAccount(provider.connection, kp, mint, kp.publicKey));
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates and funds two distributions for three recipients", async () => {
+            await createDistribution(VULNERABLE_ID);
+            await createDistribution(SECURE_ID);
+
+            expect(await balance(vaultPda(VULNERABLE_ID))).to.equal(3 * AMOUNT);
+            expect(await balance(vaultPda(SECURE_ID))).to.equal(3 * AMOUNT);
+        });
+    });
+
+    describe("Exploit: one frozen account bricks the batch", () => {
+        it("Freezes Carol's token account", async () => {
+            const carolAccount = tokenAccounts.get(carol.publicKey.toBase58())!;
+            await freezeAccount(provider.connection, admin, carolAccount, mint, admin);
+            expect((await getAccount(provider.connection, carolAccount)).isFrozen).to.equal(true);
+        });
+
+        it("Push settlement reverts and nobody is paid", async () => {
+            try {
+                await program.methods
+                    .vulnerableSettle()
+                    .accounts({
+                        distribution: distributionPda(VULNERABLE_ID),
+                        vault: vaultPda(VULNERABLE_ID),
+                        admin: admin.publicKey,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .remainingAccounts(
+                        recipients().map((kp) => ({
+                            pubkey: tokenAccounts.get(kp.publicKey.toBase58())!,
+                            isSigner: false,
+                            isWritable: true,
+                        }))
+                    )
+                    .signers([admin])
+                    .rpc();
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.include("frozen");
+            }
+
+            const alicePaid = await balance(tokenAccounts.get(alice.publicKey.toBase58())!);
+            console.log(`  Alice received ${alicePaid}; vault still holds ${await balance(vaultPda(VULNERABLE_ID))}`);
+            expect(alicePaid).to.equal(0);
+        });
+    });
+
+    describe("Secure: pull payments", () => {
+        it("Alice and Bob claim regardless of Carol", async () => {
+            await claim(alice, tokenAccounts.get(alice.publicKey.toBase58())!);
+            await claim(bob, tokenAccounts.get(bob.publicKey.toBase58())!);
+
+            expect(await balance(tokenAccounts.get(bob.publicKey.toBase58())!)).to.equal(AMOUNT);
+        });
+
+        it("Carol's frozen account blocks only Carol's claim", async () => {
+            try {
+                await claim(carol, tokenAccounts.get(carol.publicKey.toBase58())!);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.include("frozen");
+            }
+        });
+
+        it("Carol can claim to another token account", async () => {
+            const fresh = await createAccount(provider.connection, carol, mint, carol.publicKey, Keypair.generate());
+            await claim(carol, fresh);
+
+            expect(await balance(fresh)).to.equal(AMOUNT);
+            expect(await balance(vaultPda(SECURE_ID))).to.equal(0);
+        });
+
+        it("Each recipient claims once", async () => {
+            try {
+                await claim(alice, tokenAccounts.get(alice.publicKey.toBase58())!);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.include("already in use");
+            }
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Frozen Token Account DoS ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  for each recipient { transfer(vault -> recipient_account) } in one instruction");
+            console.log("  Risk: one frozen or closed account reverts every payout\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  secure_claim: recipient pulls their own payout; Claim PDA makes it one-shot");
+            console.log("  Protection: a failing account blocks only its owner, who can use another\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Never let one recipient's account state decide whether everyone else gets paid.");
+        });
+    });
+});
@@ -0,0 +1,325 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("EondsxaBS7pxE6ZH5NPTd7Cii6M4JhqV8SRXKgdLUq5K");
+
+/// Metaplex Token Metadata program.
+pub mod mpl_token_metadata {
+    anchor_lang::declare_id!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+}
+
+#[program]
+pub mod nft_metadata_verification {
+    use super::*;
+
+    /// Creates a lending market that lends `loan_amount` lamports against
+    /// any NFT from `collection` with `creator` as a verified creator.
+    pub fn initialize_market(
+        ctx: Context<InitializeMarket>,
+        collection: Pubkey,
+        creator: Pubkey,
+        loan_amount: u64,
+        funding: u64,
+    ) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        market.admin = ctx.accounts.admin.key();
+        market.collection = collection;
+        market.creator = creator;
+        market.loan_amount = loan_amount;
+        market.bump = ctx.bumps.market;
+
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: ctx.accounts.market.to_account_info(),
+                },
+            ),
+            funding,
+        )
+    }
+
+    // VULNERABLE: Caller-Supplied Metadata Trusted
+    // 1. `metadata` is any account the caller passes; its address is not
+    //    derived from `mint` and its owner is not checked
+    // 2. The collection and creator fields are read and compared, so the
+    //    check looks thorough
+    // 3. An attacker passes the metadata of a genuine NFT from the
+    //    collection alongside a worthless mint of their own, and borrows
+    pub fn vulnerable_borrow(ctx: Context<VulnerableBorrow>) -> Result<()> {
+        let metadata = MetadataPrefix::parse(&ctx.accounts.metadata)?;
+        check_collection_and_creator(&metadata, &ctx.accounts.market)?;
+
+        lock_and_lend(
+            &ctx.accounts.market,
+            &mut ctx.accounts.loan,
+            &ctx.accounts.mint,
+            &ctx.accounts.borrower_nft,
+            &ctx.accounts.vault,
+            &ctx.accounts.borrower,
+            &ctx.accounts.token_program,
+        )?;
+        msg!("Vulnerable borrow against mint {}", ctx.accounts.mint.key());
+        Ok(())
+    }
+
+    // SECURE: Metadata Derived From the Mint and Fully Verified
+    // 1. `metadata` must be the Metaplex PDA for `mint` and owned by the
+    //    Token Metadata program
+    // 2. The parsed metadata's `mint` must equal the collateral mint
+    // 3. The collection must match and be `verified`, and the market's
+    //    creator must appear as a `verified` creator
+    pub fn secure_borrow(ctx: Context<SecureBorrow>) -> Result<()> {
+        let metadata = MetadataPrefix::parse(&ctx.accounts.metadata)?;
+        require_keys_eq!(metadata.mint, ctx.accounts.mint.key(), NftError::MetadataMintMismatch);
+        check_collection_and_creator(&metadata, &ctx.accounts.market)?;
+
+        lock_and_lend(
+            &ctx.accounts.market,
+            &mut ctx.accounts.loan,
+            &ctx.accounts.mint,
+            &ctx.accounts.borrower_nft,
+            &ctx.accounts.vault,
+            &ctx.accounts.borrower,
+            &ctx.accounts.token_program,
+        )?;
+        msg!("Secure borrow against mint {}", ctx.accounts.mint.key());
+        Ok(())
+    }
+}
+
+/// Both paths run this; the vulnerable one simply runs it on an account
+/// that may describe a different NFT.
+fn check_collection_and_creator(metadata: &MetadataPrefix, market: &Market) -> Result<()> {
+    let collection = metadata.collection.as_ref().ok_or(NftError::WrongCollection)?;
+    require_keys_eq!(collection.key, market.collection, NftError::WrongCollection);
+    require!(collection.verified, NftError::UnverifiedCollection);
+
+    let creators = metadata.data.creators.as_deref().unwrap_or_default();
+    require!(
+        creators.iter().any(|c| c.address == market.creator && c.verified),
+        NftError::UnverifiedCreator
+    );
+    Ok(())
+}
+
+fn lock_and_lend<'info>(
+    market: &Account<'info, Market>,
+    loan: &mut Account<'info, Loan>,
+    mint: &Account<'info, Mint>,
+    borrower_nft: &Account<'info, TokenAccount>,
+    vault: &Account<'info, TokenAccount>,
+    borrower: &Signer<'info>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    require!(mint.decimals == 0 && mint.supply == 1, NftError::NotAnNft);
+
+    token::transfer(
+        CpiContext::new(
+            token_program.to_account_info(),
+            Transfer {
+                from: borrower_nft.to_account_info(),
+                to: vault.to_account_info(),
+                authority: borrower.to_account_info(),
+            },
+        ),
+        1,
+    )?;
+
+    loan.borrower = borrower.key();
+    loan.mint = mint.key();
+    loan.amount = market.loan_amount;
+
+    let from = market.to_account_info();
+    let to = borrower.to_account_info();
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(market.loan_amount)
+        .ok_or(NftError::InsufficientFunds)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(market.loan_amount)
+        .ok_or(NftError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeMarket<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Market::INIT_SPACE,
+        seeds = [b"market"],
+        bump
+    )]
+    pub market: Account<'info, Market>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableBorrow<'info> {
+    #[account(mut, seeds = [b"market"], bump = market.bump)]
+    pub market: Account<'info, Market>,
+    /// CHECK: VULNERABLE - neither derived from `mint` nor owner-checked.
+    pub metadata: UncheckedAccount<'info>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = borrower,
+        space = 8 + Loan::INIT_SPACE,
+        seeds = [b"loan", mint.key().as_ref()],
+        bump
+    )]
+    pub loan: Account<'info, Loan>,
+    #[account(mut, token::mint = mint, token::authority = borrower)]
+    pub borrower_nft: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = borrower,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = market
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SecureBorrow<'info> {
+    #[account(mut, seeds = [b"market"], bump = market.bump)]
+    pub market: Account<'info, Market>,
+    /// CHECK: Address derived from `mint` under the Token Metadata program,
+    /// which must also own it; contents are parsed in the handler.
+    #[account(
+        seeds = [b"metadata", mpl_token_metadata::ID.as_ref(), mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump,
+        owner = mpl_token_metadata::ID @ NftError::InvalidMetadataOwner
+    )]
+    pub metadata: UncheckedAccount<'info>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = borrower,
+        space = 8 + Loan::INIT_SPACE,
+        seeds = [b"loan", mint.key().as_ref()],
+        bump
+    )]
+    pub loan: Account<'info, Loan>,
+    #[account(mut, token::mint = mint, token::authority = borrower)]
+    pub borrower_nft: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = borrower,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = market
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Market {
+    pub admin: Pubkey,
+    /// Collection mint accepted as collateral.
+    pub collection: Pubkey,
+    /// Creator that must have verified the NFT.
+    pub creator: Pubkey,
+    /// Lamports lent per NFT, out of this account's own balance.
+    pub loan_amount: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Loan {
+    pub borrower: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+/// Metaplex `Key::MetadataV1`.
+const METADATA_V1_KEY: u8 = 4;
+
+/// Leading fields of Metaplex's `Metadata` account, in Borsh order, up to
+/// and including `collection`. Parsed by hand so the program does not pull
+/// in `mpl-token-metadata`; Borsh stops after the last field it needs.
+#[derive(AnchorDeserialize)]
+pub struct MetadataPrefix {
+    pub key: u8,
+    pub update_authority: Pubkey,
+    pub mint: Pubkey,
+    pub data: MetadataData,
+    pub primary_sale_happened: bool,
+    pub is_mutable: bool,
+    pub edition_nonce: Option<u8>,
+    pub token_standard: Option<u8>,
+    pub collection: Option<MetadataCollection>,
+}
+
+#[derive(AnchorDeserialize)]
+pub struct MetadataData {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub creators: Option<Vec<MetadataCreator>>,
+}
+
+#[derive(AnchorDeserialize)]
+pub struct MetadataCreator {
+    pub address: Pubkey,
+    pub verified: bool,
+    pub share: u8,
+}
+
+#[derive(AnchorDeserialize)]
+pub struct MetadataCollection {
+    pub verified: bool,
+    pub key: Pubkey,
+}
+
+impl MetadataPrefix {
+    pub fn parse(info: &AccountInfo) -> Result<Self> {
+        let data = info.try_borrow_data()?;
+        let metadata =
+            Self::deserialize(&mut &data[..]).map_err(|_| error!(NftError::InvalidMetadata))?;
+        require!(metadata.key == METADATA_V1_KEY, NftError::InvalidMetadata);
+        Ok(metadata)
+    }
+}
+
+#[error_code]
+pub enum NftError {
+    #[msg("Account is not Metaplex metadata")]
+    InvalidMetadata,
+    #[msg("Metadata is not owned by the Token Metadata program")]
+    InvalidMetadataOwner,
+    #[msg("Metadata describes a different mint")]
+    MetadataMintMismatch,
+    #[msg("NFT is not from the accepted collection")]
+    WrongCollection,
+    #[msg("Collection is not verified")]
+    UnverifiedCollection,
+    #[msg("Required creator has not verified this NFT")]
+    UnverifiedCreator,
+    #[msg("Mint is not an NFT")]
+    NotAnNft,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
@@ -0,0 +1,246 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import {
+    TOKEN_PROGRAM_ID,
+    createAccount,
+    createMint,
+    getAccount,
+    mintTo,
+    transfer,
+} from "@solana/spl-token";
+import {
+    PROGRAM_ID as METADATA_PROGRAM_ID,
+    createCreateMasterEditionV3Instruction,
+    createCreateMetadataAccountV3Instruction,
+    createVerifyCollectionInstruction,
+} from "@metaplex-foundation/mpl-token-metadata";
+import { expect } from "chai";
+
+describe("37: NFT Metadata Verification", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.NftMetadataVerification as Program<any>;
+
+    let admin: Keypair;
+    let alice: Keypair;
+    let attacker: Keypair;
+
+    let collectionMint: PublicKey;
+    let aliceMint: PublicKey;
+    let aliceNft: PublicKey;
+
+    const LOAN = 1 * LAMPORTS_PER_SOL;
+
+    const [marketPda] = PublicKey.findProgramAddressSync([Buffer.from("market")], program.programId);
+
+    function metadataPda(mint: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("metadata"), METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+            METADATA_PROGRAM_ID
+        )[0];
+    }
+
+    function editionPda(mint: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("metadata"), METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer(), Buffer.from("edition")],
+            METADATA_PROGRAM_ID
+        )[0];
+    }
+
+    function loanPda(mint: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("loan"), mint.toBuffer()], program.programId)[0];
+    }
+
+    function vaultPda(mint: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("vault"), mint.toBuffer()], program.programId)[0];
+    }
+
+    // Mints a 1-of-1 token to `owner` and creates its Metaplex metadata with
+    // admin as creator. Metaplex only lets the flag start verified when the
+    // creator is the signing update authority.
+    async function createNft(
+        authority: Keypair,
+        owner: Keypair,
+        name: string,
+        collection: PublicKey | null
+    ): Promise<{ mint: PublicKey; tokenAccount: PublicKey }> {
+        const mint = await createMint(provider.connection, authority, authority.publicKey, null, 0);
+        const tokenAccount = await createAccount(provider.connection, owner, mint, owner.publicKey);
+        await mintTo(provider.connection, authority, mint, tokenAccount, authority, 1);
+
+        const tx = new Transaction().add(
+            createCreateMetadataAccountV3Instruction(
+                {
+                    metadata: metadataPda(mint),
+                    mint,
+                    mintAuthority: authority.publicKey,
+                    payer: authority.publicKey,
+                    updateAuthority: authority.publicKey,
+                },
+                {
+                    createMetadataAccountArgsV3: {
+                        data: {
+                            name,
+                            symbol: "SSP",
+                            uri: "",
+                            sellerFeeBasisPoints: 0,
+                            creators: [{ address: admin.publicKey, verified: authority.publicKey.equals(admin.publicKey), share: 100 }],
+                            collection: collection ? { key: collection, verified: false } : null,
+                            uses: null,
+                        },
+                        isMutable: true,
+                        collectionDetails: null,
+                    },
+                }
+            )
+        );
+        await provider.sendAndConfirm(tx, [authority]);
+        return { mint, tokenAccount };
+    }
+
+    function borrow(method: "vulnerableBorrow" | "secureBorrow", borrower: Keypair, mint: PublicKey, metadata: PublicKey, nft: PublicKey) {
+        return program.methods[method]()
+            .accounts({
+                market: marketPda,
+                metadata,
+                mint,
+                loan: loanPda(mint),
+                borrowerNft: nft,
+                vault: vaultPda(mint),
+                borrower: borrower.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([borrower])
+            .rpc();
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        alice = Keypair.generate();
+        attacker = Keypair.generate();
+
+        for (const kp of [admin, alice, attacker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates a verified collection and an NFT in it for Alice", async () => {
+            const collection = await createNft(admin, admin, "Collection", null);
+            collectionMint = collection.mint;
+            await provider.sendAndConfirm(
+                new Transaction().add(
+                    createCreateMasterEditionV3Instruction(
+                        {
+                            edition: editionPda(collectionMint),
+                            mint: collectionMint,
+                            updateAuthority: admin.publicKey,
+                            mintAuthority: admin.publicKey,
+                            payer: admin.publicKey,
+                            metadata: metadataPda(collectionMint),
+                        },
+                        { createMasterEditionArgs: { maxSupply: 0 } }
+                    )
+                ),
+                [admin]
+            );
+
+            const item = await createNft(admin, admin, "Item #1", collectionMint);
+            aliceMint = item.mint;
+            await provider.sendAndConfirm(
+                new Transaction().add(
+                    createVerifyCollectionInstruction({
+                        metadata: metadataPda(aliceMint),
+                        collectionAuthority: admin.publicKey,
+                        payer: admin.publicKey,
+                        collectionMint,
+                        collection: metadataPda(collectionMint),
+                        collectionMasterEditionAccount: editionPda(collectionMint),
+                    })
+                ),
+                [admin]
+            );
+
+            aliceNft = await createAccount(provider.connection, alice, aliceMint, alice.publicKey);
+            await transfer(provider.connection, admin, item.tokenAccount, aliceNft, admin, 1);
+        });
+
+        it("Opens a market lending 1 SOL per collection NFT", async () => {
+            await program.methods
+                .initializeMarket(collectionMint, admin.publicKey, new anchor.BN(LOAN), new anchor.BN(5 * LOAN))
+                .accounts({ market: marketPda, admin: admin.publicKey, systemProgram: SystemProgram.programId })
+                .signers([admin])
+                .rpc();
+        });
+    });
+
+    describe("Exploit: borrow against someone else's metadata", () => {
+        it("Attacker pairs Alice's metadata with a worthless mint", async () => {
+            const worthless = await createMint(provider.connection, attacker, attacker.publicKey, null, 0);
+            const attackerNft = await createAccount(provider.connection, attacker, worthless, attacker.publicKey);
+            await mintTo(provider.connection, attacker, worthless, attackerNft, attacker, 1);
+
+            const before = await provider.connection.getBalance(attacker.publicKey);
+            await borrow("vulnerableBorrow", attacker, worthless, metadataPda(aliceMint), attackerNft);
+            const after = await provider.connection.getBalance(attacker.publicKey);
+
+            console.log(`  Attacker borrowed ~${((after - before) / LAMPORTS_PER_SOL).toFixed(2)} SOL against a worthless token`);
+            expect(after - before).to.be.greaterThan(LOAN * 0.9);
+        });
+    });
+
+    describe("Secure: metadata derived from the mint", () => {
+        it("Rejects metadata that is not the mint's PDA", async () => {
+            const worthless = await createMint(provider.connection, attacker, attacker.publicKey, null, 0);
+            const attackerNft = await createAccount(provider.connection, attacker, worthless, attacker.publicKey);
+            await mintTo(provider.connection, attacker, worthless, attackerNft, attacker, 1);
+
+            try {
+                await borrow("secureBorrow", attacker, worthless, metadataPda(aliceMint), attackerNft);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("ConstraintSeeds");
+            }
+        });
+
+        it("Rejects real metadata claiming an unverified collection", async () => {
+            const fake = await createNft(attacker, attacker, "Item #1", collectionMint);
+
+            try {
+                await borrow("secureBorrow", attacker, fake.mint, metadataPda(fake.mint), fake.tokenAccount);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("UnverifiedCollection");
+            }
+        });
+
+        it("Lends against Alice's genuine NFT", async () => {
+            await borrow("secureBorrow", alice, aliceMint, metadataPda(aliceMint), aliceNft);
+
+            const loan = await program.account.loan.fetch(loanPda(aliceMint));
+            expect(loan.borrower.toBase58()).to.equal(alice.publicKey.toBase58());
+            expect(Number((await getAccount(provider.connection, vaultPda(aliceMint))).amount)).to.equal(1);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== NFT Metadata Verification ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  metadata: UncheckedAccount; check metadata.collection and creators");
+            console.log("  Risk: any genuine NFT's metadata vouches for an unrelated mint\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  seeds = [\"metadata\", metadata_program, mint], owner = metadata_program");
+            console.log("  Protection: metadata.mint == mint, verified collection, verified creator\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Metadata fields prove nothing until the account is tied to the mint and the verified flags are set.");
+        });
+    });
+});
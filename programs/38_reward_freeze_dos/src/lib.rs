@@ -0,0 +1,319 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("FHGuyz9hTP75tcFrqwY6nEAmAekRXBELg8dpVgEAd9sd");
+
+/// Reward paid on withdrawal, as a share of the principal.
+pub const REWARD_BPS: u64 = 1_000;
+
+#[program]
+pub mod reward_freeze_dos {
+    use super::*;
+
+    pub fn initialize_pool(ctx: Context<InitializePool>, pool_id: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.pool_id = pool_id;
+        pool.stake_mint = ctx.accounts.stake_mint.key();
+        pool.reward_mint = ctx.accounts.reward_mint.key();
+        pool.bump = ctx.bumps.pool;
+        pool.stake_vault_bump = ctx.bumps.stake_vault;
+        pool.reward_vault_bump = ctx.bumps.reward_vault;
+        Ok(())
+    }
+
+    pub fn open_position(ctx: Context<OpenPosition>) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.pool = ctx.accounts.pool.key();
+        position.owner = ctx.accounts.owner.key();
+        position.amount = 0;
+        position.unclaimed_rewards = 0;
+        position.bump = ctx.bumps.position;
+        Ok(())
+    }
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_stake_account.to_account_info(),
+                    to: ctx.accounts.stake_vault.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let position = &mut ctx.accounts.position;
+        position.amount = position.amount.checked_add(amount).ok_or(RewardError::ArithmeticError)?;
+        Ok(())
+    }
+
+    // VULNERABLE: Reward and Principal Paid Together
+    // 1. Withdrawal pays the reward, then returns the principal, in one
+    //    instruction
+    // 2. The reward token is a separate asset with its own issuer; if that
+    //    issuer freezes (or blocklists) the user's reward account, the
+    //    reward transfer fails
+    // 3. The failure reverts the principal transfer with it: the user's
+    //    own deposit is locked by a third party's decision on another token
+    pub fn vulnerable_withdraw(ctx: Context<Withdraw>) -> Result<()> {
+        let amount = ctx.accounts.position.amount;
+        let reward = reward_for(amount)?;
+        ctx.accounts.position.amount = 0;
+
+        pay_from_vault(
+            &ctx.accounts.pool,
+            &ctx.accounts.reward_vault,
+            &ctx.accounts.user_reward_account,
+            &ctx.accounts.token_program,
+            reward,
+        )?;
+        pay_from_vault(
+            &ctx.accounts.pool,
+            &ctx.accounts.stake_vault,
+            &ctx.accounts.user_stake_account,
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+        msg!("Vulnerable withdraw: principal {}, reward {}", amount, reward);
+        Ok(())
+    }
+
+    // SECURE: Principal Decoupled From Rewards
+    // 1. Withdrawal returns only the principal and records the reward as
+    //    `unclaimed_rewards` on the position
+    // 2. Rewards are paid by a separate `claim_rewards`, to any reward
+    //    account the owner picks
+    // 3. A frozen reward account can delay rewards, never principal
+    pub fn secure_withdraw(ctx: Context<SecureWithdraw>) -> Result<()> {
+        let amount = ctx.accounts.position.amount;
+        let reward = reward_for(amount)?;
+        let position = &mut ctx.accounts.position;
+        position.amount = 0;
+        position.unclaimed_rewards = position
+            .unclaimed_rewards
+            .checked_add(reward)
+            .ok_or(RewardError::ArithmeticError)?;
+
+        pay_from_vault(
+            &ctx.accounts.pool,
+            &ctx.accounts.stake_vault,
+            &ctx.accounts.user_stake_account,
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+        msg!("Secure withdraw: principal {}, reward {} left to claim", amount, reward);
+        Ok(())
+    }
+
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        let reward = ctx.accounts.position.unclaimed_rewards;
+        require!(reward > 0, RewardError::NothingToClaim);
+        ctx.accounts.position.unclaimed_rewards = 0;
+
+        pay_from_vault(
+            &ctx.accounts.pool,
+            &ctx.accounts.reward_vault,
+            &ctx.accounts.user_reward_account,
+            &ctx.accounts.token_program,
+            reward,
+        )?;
+        msg!("Claimed reward {}", reward);
+        Ok(())
+    }
+}
+
+fn reward_for(amount: u64) -> Result<u64> {
+    require!(amount > 0, RewardError::NothingToWithdraw);
+    Ok(amount.checked_mul(REWARD_BPS).ok_or(RewardError::ArithmeticError)? / 10_000)
+}
+
+fn pay_from_vault<'info>(
+    pool: &Account<'info, Pool>,
+    vault: &Account<'info, TokenAccount>,
+    destination: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let pool_id = pool.pool_id.to_le_bytes();
+    let seeds: &[&[u8]] = &[b"pool", pool_id.as_ref(), &[pool.bump]];
+    let signer = &[seeds];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: vault.to_account_info(),
+                to: destination.to_account_info(),
+                authority: pool.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u64)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Pool::INIT_SPACE,
+        seeds = [b"pool", pool_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"stake_vault", pool.key().as_ref()],
+        bump,
+        token::mint = stake_mint,
+        token::authority = pool
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"reward_vault", pool.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = pool
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    pub stake_mint: Account<'info, Mint>,
+    pub reward_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(seeds = [b"pool", pool.pool_id.to_le_bytes().as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [b"position", pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(seeds = [b"pool", pool.pool_id.to_le_bytes().as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut, seeds = [b"stake_vault", pool.key().as_ref()], bump = pool.stake_vault_bump)]
+    pub stake_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"position", pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut, token::mint = pool.stake_mint, token::authority = owner)]
+    pub user_stake_account: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(seeds = [b"pool", pool.pool_id.to_le_bytes().as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut, seeds = [b"stake_vault", pool.key().as_ref()], bump = pool.stake_vault_bump)]
+    pub stake_vault: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [b"reward_vault", pool.key().as_ref()], bump = pool.reward_vault_bump)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"position", pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut, token::mint = pool.stake_mint, token::authority = owner)]
+    pub user_stake_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = pool.reward_mint, token::authority = owner)]
+    pub user_reward_account: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// No reward accounts: nothing the reward issuer does can block this.
+#[derive(Accounts)]
+pub struct SecureWithdraw<'info> {
+    #[account(seeds = [b"pool", pool.pool_id.to_le_bytes().as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut, seeds = [b"stake_vault", pool.key().as_ref()], bump = pool.stake_vault_bump)]
+    pub stake_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"position", pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut, token::mint = pool.stake_mint, token::authority = owner)]
+    pub user_stake_account: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    #[account(seeds = [b"pool", pool.pool_id.to_le_bytes().as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut, seeds = [b"reward_vault", pool.key().as_ref()], bump = pool.reward_vault_bump)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"position", pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut, token::mint = pool.reward_mint, token::authority = owner)]
+    pub user_reward_account: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub pool_id: u64,
+    pub stake_mint: Pubkey,
+    /// Issued by a third party, which may freeze holders' accounts.
+    pub reward_mint: Pubkey,
+    pub bump: u8,
+    pub stake_vault_bump: u8,
+    pub reward_vault_bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    /// Rewards earned but not yet paid (secure path only).
+    pub unclaimed_rewards: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum RewardError {
+    #[msg("Nothing to withdraw")]
+    NothingToWithdraw,
+    #[msg("No rewards to claim")]
+    NothingToClaim,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
@@ -0,0 +1,231 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import {
+    TOKEN_PROGRAM_ID,
+    createAccount,
+    createMint,
+    freezeAccount,
+    getAccount,
+    mintTo,
+} from "@solana/spl-token";
+import { expect } from "chai";
+
+describe("38: Reward Freeze Withdrawal DoS", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.RewardFreezeDos as Program<any>;
+
+    let admin: Keypair;
+    let rewardIssuer: Keypair;
+    let alice: Keypair;
+    let bob: Keypair;
+
+    let stakeMint: PublicKey;
+    let rewardMint: PublicKey;
+    const stakeAccounts = new Map<string, PublicKey>();
+    const rewardAccounts = new Map<string, PublicKey>();
+
+    const POOL_ID = 1;
+    const DEPOSIT = 10_000;
+    const REWARD = 1_000; // 10% of DEPOSIT
+
+    function poolPda(): PublicKey {
+        const buf = Buffer.alloc(8);
+        buf.writeBigUInt64LE(BigInt(POOL_ID));
+        return PublicKey.findProgramAddressSync([Buffer.from("pool"), buf], program.programId)[0];
+    }
+
+    function pda(label: string, ...keys: PublicKey[]): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from(label), ...keys.map((k) => k.toBuffer())],
+            program.programId
+        )[0];
+    }
+
+    const stakeVault = () => pda("stake_vault", poolPda());
+    const rewardVault = () => pda("reward_vault", poolPda());
+    const positionPda = (owner: PublicKey) => pda("position", poolPda(), owner);
+
+    async function balance(account: PublicKey): Promise<number> {
+        return Number((await getAccount(provider.connection, account)).amount);
+    }
+
+    async function openAndDeposit(user: Keypair) {
+        await program.methods
+            .openPosition()
+            .accounts({
+                pool: poolPda(),
+                position: positionPda(user.publicKey),
+                owner: user.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([user])
+            .rpc();
+
+        await program.methods
+            .deposit(new anchor.BN(DEPOSIT))
+            .accounts({
+                pool: poolPda(),
+                stakeVault: stakeVault(),
+                position: positionPda(user.publicKey),
+                userStakeAccount: stakeAccounts.get(user.publicKey.toBase58())!,
+                owner: user.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([user])
+            .rpc();
+    }
+
+    async function freezeRewards(user: Keypair) {
+        await freezeAccount(
+            provider.connection,
+            rewardIssuer,
+            rewardAccounts.get(user.publicKey.toBase58())!,
+            rewardMint,
+            rewardIssuer
+        );
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        rewardIssuer = Keypair.generate();
+        alice = Keypair.generate();
+        bob = Keypair.generate();
+
+        for (const kp of [admin, rewardIssuer, alice, bob]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+
+        stakeMint = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+        // The reward token belongs to someone else, and they keep a freeze authority.
+        rewardMint = await createMint(provider.connection, rewardIssuer, rewardIssuer.publicKey, rewardIssuer.publicKey, 6);
+
+        for (const kp of [alice, bob]) {
+            const stakeAccount = await createAccount(provider.connection, kp, stakeMint, kp.publicKey);
+            await mintTo(provider.connection, admin, stakeMint, stakeAccount, admin, DEPOSIT);
+            stakeAccounts.set(kp.publicKey.toBase58(), stakeAccount);
+            rewardAccounts.set(kp.publicKey.toBase58(), await createAccount(provider.connection, kp, rewardMint, kp.publicKey));
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates the pool, funds rewards, and takes deposits from Alice and Bob", async () => {
+            await program.methods
+                .initializePool(new anchor.BN(POOL_ID))
+                .accounts({
+                    pool: poolPda(),
+                    stakeVault: stakeVault(),
+                    rewardVault: rewardVault(),
+                    stakeMint,
+                    rewardMint,
+                    payer: admin.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([admin])
+                .rpc();
+            await mintTo(provider.connection, rewardIssuer, rewardMint, rewardVault(), rewardIssuer, 2 * REWARD);
+
+            await openAndDeposit(alice);
+            await openAndDeposit(bob);
+            expect(await balance(stakeVault())).to.equal(2 * DEPOSIT);
+        });
+    });
+
+    describe("Exploit: frozen reward account locks principal", () => {
+        it("Reward issuer freezes Alice's reward account", async () => {
+            await freezeRewards(alice);
+            const account = await getAccount(provider.connection, rewardAccounts.get(alice.publicKey.toBase58())!);
+            expect(account.isFrozen).to.equal(true);
+        });
+
+        it("Alice cannot withdraw the principal", async () => {
+            try {
+                await program.methods
+                    .vulnerableWithdraw()
+                    .accounts({
+                        pool: poolPda(),
+                        stakeVault: stakeVault(),
+                        rewardVault: rewardVault(),
+                        position: positionPda(alice.publicKey),
+                        userStakeAccount: stakeAccounts.get(alice.publicKey.toBase58())!,
+                        userRewardAccount: rewardAccounts.get(alice.publicKey.toBase58())!,
+                        owner: alice.publicKey,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .signers([alice])
+                    .rpc();
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.include("frozen");
+            }
+
+            const principal = await balance(stakeAccounts.get(alice.publicKey.toBase58())!);
+            console.log(`  Alice's stake-token balance: ${principal}; ${DEPOSIT} stuck in the vault`);
+            expect(principal).to.equal(0);
+        });
+    });
+
+    describe("Secure: principal decoupled from rewards", () => {
+        it("Bob's reward account is frozen too, but Bob still gets the principal back", async () => {
+            await freezeRewards(bob);
+
+            await program.methods
+                .secureWithdraw()
+                .accounts({
+                    pool: poolPda(),
+                    stakeVault: stakeVault(),
+                    position: positionPda(bob.publicKey),
+                    userStakeAccount: stakeAccounts.get(bob.publicKey.toBase58())!,
+                    owner: bob.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([bob])
+                .rpc();
+
+            expect(await balance(stakeAccounts.get(bob.publicKey.toBase58())!)).to.equal(DEPOSIT);
+            const position = await program.account.position.fetch(positionPda(bob.publicKey));
+            expect(position.unclaimedRewards.toNumber()).to.equal(REWARD);
+        });
+
+        it("Bob claims the reward later to an unfrozen account", async () => {
+            const fresh = await createAccount(provider.connection, bob, rewardMint, bob.publicKey, Keypair.generate());
+
+            await program.methods
+                .claimRewards()
+                .accounts({
+                    pool: poolPda(),
+                    rewardVault: rewardVault(),
+                    position: positionPda(bob.publicKey),
+                    userRewardAccount: fresh,
+                    owner: bob.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([bob])
+                .rpc();
+
+            expect(await balance(fresh)).to.equal(REWARD);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Reward Freeze Withdrawal DoS ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  withdraw: transfer(reward) then transfer(principal) in one instruction");
+            console.log("  Risk: the reward issuer freezes one account and the principal is stuck\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  secure_withdraw returns principal only; claim_rewards pays rewards separately");
+            console.log("  Protection: a failing reward transfer can delay rewards, never principal\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Never make getting your own funds back depend on a transfer of someone else's token.");
+        });
+    });
+});
@@ -0,0 +1,186 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+declare_id!("3exciy4dHkEYf41HNFNsVQYB5Xd5S4WFKwtoXbk8J9gp");
+
+/// Storage reserved per queue. Keeps the account under the 10 KiB limit
+/// for accounts created through CPI.
+pub const QUEUE_SPACE: usize = 1_024;
+/// Most items the secure path accepts in one queue.
+pub const MAX_QUEUE_LEN: usize = 64;
+/// Most items the secure path accepts per `secure_enqueue`.
+pub const MAX_BATCH: usize = 16;
+/// Most items the secure path processes per `secure_process`.
+pub const PAGE_SIZE: usize = 16;
+/// Hash rounds per item, standing in for per-item verification work.
+pub const HASH_ROUNDS: usize = 16;
+
+#[program]
+pub mod compute_exhaustion_dos {
+    use super::*;
+
+    pub fn initialize_queue(ctx: Context<InitializeQueue>, queue_id: u64) -> Result<()> {
+        let queue = &mut ctx.accounts.queue;
+        queue.queue_id = queue_id;
+        queue.items = Vec::new();
+        queue.bump = ctx.bumps.queue;
+
+        let cursor = &mut ctx.accounts.cursor;
+        cursor.queue = queue.key();
+        cursor.next_index = 0;
+        cursor.processed_total = 0;
+        cursor.checksum = [0; 32];
+        cursor.bump = ctx.bumps.cursor;
+        Ok(())
+    }
+
+    // VULNERABLE: Unbounded Input Appended to Shared State
+    // 1. Anyone can append any number of items; only transaction size
+    //    limits one call, and nothing limits repeated calls
+    pub fn vulnerable_enqueue(ctx: Context<Enqueue>, items: Vec<u64>) -> Result<()> {
+        ctx.accounts.queue.items.extend(items);
+        msg!("Vulnerable enqueue: queue length {}", ctx.accounts.queue.items.len());
+        Ok(())
+    }
+
+    // VULNERABLE: Whole Queue Processed in One Instruction
+    // 1. The loop runs over every stored item, so its cost grows with
+    //    whatever users have appended
+    // 2. Once the queue costs more than 1.4M CU (the per-transaction
+    //    maximum) to process, no transaction can ever complete it
+    // 3. The crank is bricked permanently and honest items are stuck
+    //    behind the attacker's
+    pub fn vulnerable_process_all(ctx: Context<Process>) -> Result<()> {
+        let queue = &ctx.accounts.queue;
+        let cursor = &mut ctx.accounts.cursor;
+        for item in &queue.items {
+            process_item(cursor, *item)?;
+        }
+        cursor.next_index = queue.items.len() as u64;
+        msg!("Vulnerable process: {} items", queue.items.len());
+        Ok(())
+    }
+
+    // SECURE: Capped Input
+    // 1. At most `MAX_BATCH` items per call and `MAX_QUEUE_LEN` in total,
+    //    so the queue's worst-case processing cost is known in advance
+    pub fn secure_enqueue(ctx: Context<Enqueue>, items: Vec<u64>) -> Result<()> {
+        require!(items.len() <= MAX_BATCH, QueueError::BatchTooLarge);
+        let queue = &mut ctx.accounts.queue;
+        require!(
+            queue.items.len() + items.len() <= MAX_QUEUE_LEN,
+            QueueError::QueueFull
+        );
+        queue.items.extend(items);
+        msg!("Secure enqueue: queue length {}", queue.items.len());
+        Ok(())
+    }
+
+    // SECURE: Paginated Processing With a Cursor
+    // 1. Each call processes at most `PAGE_SIZE` items from
+    //    `cursor.next_index`, so its cost is bounded regardless of queue
+    //    length
+    // 2. The cursor persists between calls; any number of transactions
+    //    drain the queue page by page
+    pub fn secure_process(ctx: Context<Process>, limit: u8) -> Result<()> {
+        let queue = &ctx.accounts.queue;
+        let cursor = &mut ctx.accounts.cursor;
+        let start = cursor.next_index as usize;
+        require!(start < queue.items.len(), QueueError::NothingToProcess);
+
+        let end = queue.items.len().min(start + (limit as usize).min(PAGE_SIZE));
+        for item in &queue.items[start..end] {
+            process_item(cursor, *item)?;
+        }
+        cursor.next_index = end as u64;
+        msg!("Secure process: items {}..{} of {}", start, end, queue.items.len());
+        Ok(())
+    }
+}
+
+fn process_item(cursor: &mut Cursor, item: u64) -> Result<()> {
+    let mut digest = cursor.checksum;
+    for _ in 0..HASH_ROUNDS {
+        digest = hashv(&[&digest, &item.to_le_bytes()]).to_bytes();
+    }
+    cursor.checksum = digest;
+    cursor.processed_total = cursor
+        .processed_total
+        .checked_add(item)
+        .ok_or(QueueError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(queue_id: u64)]
+pub struct InitializeQueue<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Queue::INIT_SPACE,
+        seeds = [b"queue", queue_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub queue: Account<'info, Queue>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Cursor::INIT_SPACE,
+        seeds = [b"cursor", queue.key().as_ref()],
+        bump
+    )]
+    pub cursor: Account<'info, Cursor>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Enqueue<'info> {
+    #[account(mut, seeds = [b"queue", queue.queue_id.to_le_bytes().as_ref()], bump = queue.bump)]
+    pub queue: Account<'info, Queue>,
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Process<'info> {
+    #[account(seeds = [b"queue", queue.queue_id.to_le_bytes().as_ref()], bump = queue.bump)]
+    pub queue: Account<'info, Queue>,
+    #[account(mut, seeds = [b"cursor", queue.key().as_ref()], bump = cursor.bump)]
+    pub cursor: Account<'info, Cursor>,
+    pub cranker: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Queue {
+    pub queue_id: u64,
+    #[max_len(QUEUE_SPACE)]
+    pub items: Vec<u64>,
+    pub bump: u8,
+}
+
+/// Progress through a `Queue`.
+#[account]
+#[derive(InitSpace)]
+pub struct Cursor {
+    pub queue: Pubkey,
+    /// Index of the first item not yet processed.
+    pub next_index: u64,
+    pub processed_total: u64,
+    /// Running hash over processed items.
+    pub checksum: [u8; 32],
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum QueueError {
+    #[msg("Too many items in one call")]
+    BatchTooLarge,
+    #[msg("Queue is full")]
+    QueueFull,
+    #[msg("Nothing left to process")]
+    NothingToProcess,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
@@ -0,0 +1,200 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ComputeBudgetProgram, Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+
+describe("39: Compute Exhaustion DoS", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ComputeExhaustionDos as Program<any>;
+
+    let alice: Keypair;
+    let attacker: Keypair;
+    let cranker: Keypair;
+
+    const VULNERABLE_ID = 1;
+    const SECURE_ID = 2;
+    const MAX_TX_COMPUTE = 1_400_000;
+    const DEFAULT_COMPUTE = 200_000;
+    const MAX_BATCH = 16;
+    const MAX_QUEUE_LEN = 64;
+
+    function queuePda(id: number): PublicKey {
+        const buf = Buffer.alloc(8);
+        buf.writeBigUInt64LE(BigInt(id));
+        return PublicKey.findProgramAddressSync([Buffer.from("queue"), buf], program.programId)[0];
+    }
+
+    function cursorPda(id: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("cursor"), queuePda(id).toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function items(count: number, start = 1): anchor.BN[] {
+        return Array.from({ length: count }, (_, i) => new anchor.BN(start + i));
+    }
+
+    async function computeUnits(signature: string): Promise<number> {
+        const tx = await provider.connection.getTransaction(signature, {
+            commitment: "confirmed",
+            maxSupportedTransactionVersion: 0,
+        });
+        return tx!.meta!.computeUnitsConsumed!;
+    }
+
+    function enqueue(method: "vulnerableEnqueue" | "secureEnqueue", id: number, user: Keypair, values: anchor.BN[]) {
+        return program.methods[method](values)
+            .accounts({ queue: queuePda(id), user: user.publicKey })
+            .signers([user])
+            .rpc();
+    }
+
+    before(async () => {
+        alice = Keypair.generate();
+        attacker = Keypair.generate();
+        cranker = Keypair.generate();
+
+        for (const kp of [alice, attacker, cranker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates a queue and cursor for each path", async () => {
+            for (const id of [VULNERABLE_ID, SECURE_ID]) {
+                await program.methods
+                    .initializeQueue(new anchor.BN(id))
+                    .accounts({
+                        queue: queuePda(id),
+                        cursor: cursorPda(id),
+                        payer: cranker.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([cranker])
+                    .rpc();
+            }
+        });
+    });
+
+    describe("Exploit: flood the queue past the compute limit", () => {
+        let perItem: number;
+
+        it("Measures the cost of processing 8 items from Alice", async () => {
+            await enqueue("vulnerableEnqueue", VULNERABLE_ID, alice, items(8));
+
+            const sig = await program.methods
+                .vulnerableProcessAll()
+                .accounts({ queue: queuePda(VULNERABLE_ID), cursor: cursorPda(VULNERABLE_ID), cranker: cranker.publicKey })
+                .signers([cranker])
+                .rpc({ commitment: "confirmed" });
+            const used = await computeUnits(sig);
+            perItem = Math.ceil(used / 8);
+            console.log(`  8 items: ${used} CU (~${perItem} CU per item)`);
+            console.log(`  Items that fit in ${MAX_TX_COMPUTE} CU: ~${Math.floor(MAX_TX_COMPUTE / perItem)}`);
+        });
+
+        it("Attacker appends junk until one transaction cannot process the queue", async () => {
+            // ~1,000 items, well past what 1.4M CU covers; the attacker only
+            // pays transaction fees for this.
+            for (let added = 0; added < 1_000; added += 100) {
+                await enqueue("vulnerableEnqueue", VULNERABLE_ID, attacker, items(100, 1_000 + added));
+            }
+            const queue = await program.account.queue.fetch(queuePda(VULNERABLE_ID));
+            console.log(`  Queue length: ${queue.items.length} (~${queue.items.length * perItem} CU to process)`);
+        });
+
+        it("Processing fails even at the 1.4M CU maximum; the queue is bricked", async () => {
+            try {
+                await program.methods
+                    .vulnerableProcessAll()
+                    .accounts({ queue: queuePda(VULNERABLE_ID), cursor: cursorPda(VULNERABLE_ID), cranker: cranker.publicKey })
+                    .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: MAX_TX_COMPUTE })])
+                    .signers([cranker])
+                    .rpc();
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.match(/exceeded CUs meter|Computational budget exceeded/);
+            }
+
+            const cursor = await program.account.cursor.fetch(cursorPda(VULNERABLE_ID));
+            const queue = await program.account.queue.fetch(queuePda(VULNERABLE_ID));
+            console.log(`  Cursor stuck at ${cursor.nextIndex.toNumber()} of ${queue.items.length}`);
+            expect(cursor.nextIndex.toNumber()).to.equal(8);
+        });
+    });
+
+    describe("Secure: caps and paginated processing", () => {
+        it("Rejects an oversized batch", async () => {
+            try {
+                await enqueue("secureEnqueue", SECURE_ID, attacker, items(MAX_BATCH + 1));
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("BatchTooLarge");
+            }
+        });
+
+        it("Caps the total queue length", async () => {
+            await enqueue("secureEnqueue", SECURE_ID, alice, items(8));
+            for (let added = 8; added < MAX_QUEUE_LEN; added += 8) {
+                await enqueue("secureEnqueue", SECURE_ID, attacker, items(8, 1_000 + added));
+            }
+
+            try {
+                await enqueue("secureEnqueue", SECURE_ID, attacker, items(1));
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("QueueFull");
+            }
+        });
+
+        it("Drains the full queue in bounded pages", async () => {
+            let page = 0;
+            while (true) {
+                const cursor = await program.account.cursor.fetch(cursorPda(SECURE_ID));
+                if (cursor.nextIndex.toNumber() >= MAX_QUEUE_LEN) break;
+
+                const sig = await program.methods
+                    .secureProcess(255)
+                    .accounts({ queue: queuePda(SECURE_ID), cursor: cursorPda(SECURE_ID), cranker: cranker.publicKey })
+                    .signers([cranker])
+                    .rpc({ commitment: "confirmed" });
+                const used = await computeUnits(sig);
+                console.log(`  Page ${page++}: ${used} CU`);
+                expect(used).to.be.lessThan(DEFAULT_COMPUTE);
+            }
+
+            try {
+                await program.methods
+                    .secureProcess(255)
+                    .accounts({ queue: queuePda(SECURE_ID), cursor: cursorPda(SECURE_ID), cranker: cranker.publicKey })
+                    .signers([cranker])
+                    .rpc();
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("NothingToProcess");
+            }
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Compute Exhaustion DoS ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  enqueue(items: Vec<u64>) with no cap; process_all loops over every item");
+            console.log("  Risk: once the loop costs more than 1.4M CU, no transaction can finish it\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  MAX_BATCH per call, MAX_QUEUE_LEN total, PAGE_SIZE per crank with a cursor");
+            console.log("  Protection: every instruction's cost is bounded regardless of input\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Every loop over user-controlled data needs a bound that fits in one transaction.");
+        });
+    });
+});
@@ -0,0 +1,183 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("5UvKu9Urs3hyzA3GaRGgn1nwsLfjjE9HA6gXmVqGddgG");
+
+#[program]
+pub mod rent_exemption_floor {
+    use super::*;
+
+    /// The protocol opens (and pays rent for) a vault on the owner's behalf.
+    pub fn open_vault(ctx: Context<OpenVault>, owner: Pubkey) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.owner = owner;
+        vault.rent_payer = ctx.accounts.rent_payer.key();
+        vault.total_deposited = 0;
+        vault.bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let vault = &mut ctx.accounts.vault;
+        vault.total_deposited = vault
+            .total_deposited
+            .checked_add(amount)
+            .ok_or(VaultError::ArithmeticError)?;
+        Ok(())
+    }
+
+    // VULNERABLE: Whole Lamport Balance Treated as Withdrawable
+    // 1. Part of a data account's balance is the rent-exempt reserve, not
+    //    user funds; here it was even paid by the protocol
+    // 2. Withdrawing into the reserve without emptying the account leaves
+    //    it rent-paying, which the runtime rejects: withdrawals fail for
+    //    reasons the program never checks
+    // 3. Withdrawing everything takes the reserve too, and a zero-lamport
+    //    account is garbage-collected at the end of the transaction: the
+    //    vault's data is gone and its PDA can be initialized again
+    pub fn vulnerable_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        let vault = ctx.accounts.vault.to_account_info();
+        require!(amount <= vault.lamports(), VaultError::InsufficientFunds);
+
+        move_lamports(&vault, &ctx.accounts.owner.to_account_info(), amount)?;
+        msg!("Vulnerable withdraw: {} (vault left with {})", amount, vault.lamports());
+        Ok(())
+    }
+
+    // SECURE: Rent-Exempt Floor Enforced
+    // 1. `Rent::minimum_balance(data_len)` is computed for the vault's
+    //    actual size
+    // 2. Only lamports above that floor can be withdrawn, so the account
+    //    stays rent-exempt and its data is never evicted
+    // 3. Recovering the reserve is an explicit `close_vault` that returns
+    //    it to whoever paid it
+    pub fn secure_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        let vault = ctx.accounts.vault.to_account_info();
+        let available = withdrawable(&vault)?;
+        require!(amount <= available, VaultError::BelowRentFloor);
+
+        move_lamports(&vault, &ctx.accounts.owner.to_account_info(), amount)?;
+        msg!("Secure withdraw: {} (floor kept, vault left with {})", amount, vault.lamports());
+        Ok(())
+    }
+
+    /// Closes an emptied vault and returns the rent reserve to its payer.
+    pub fn close_vault(ctx: Context<CloseVault>) -> Result<()> {
+        let vault = ctx.accounts.vault.to_account_info();
+        require!(withdrawable(&vault)? == 0, VaultError::VaultNotEmpty);
+        Ok(())
+    }
+}
+
+/// Lamports above the rent-exempt minimum for the account's current size.
+fn withdrawable(account: &AccountInfo) -> Result<u64> {
+    let floor = Rent::get()?.minimum_balance(account.data_len());
+    Ok(account.lamports().saturating_sub(floor))
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(VaultError::ArithmeticError)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(VaultError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct OpenVault<'info> {
+    #[account(
+        init,
+        payer = rent_payer,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", owner.as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub rent_payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::Unauthorized
+    )]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::Unauthorized
+    )]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseVault<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::Unauthorized,
+        has_one = rent_payer @ VaultError::Unauthorized,
+        close = rent_payer
+    )]
+    pub vault: Account<'info, Vault>,
+    pub owner: Signer<'info>,
+    /// CHECK: Receives the rent reserve; matched against `vault.rent_payer`.
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub owner: Pubkey,
+    /// Paid the rent-exempt reserve and gets it back on close.
+    pub rent_payer: Pubkey,
+    pub total_deposited: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum VaultError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Withdrawal would take the vault below its rent-exempt minimum")]
+    BelowRentFloor,
+    #[msg("Withdraw the remaining balance before closing")]
+    VaultNotEmpty,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
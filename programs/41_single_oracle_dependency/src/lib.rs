@@ -0,0 +1,415 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_lang::system_program::{transfer, Transfer};
+use mock_oracle::PriceFeed;
+
+declare_id!("BP11jamLog7xkpXGhvUnPHJ1fLU9aw3mcWDrn8KynQyY");
+
+/// Debt may be at most this share of collateral value when borrowing or
+/// withdrawing.
+pub const MAX_LTV_BPS: u64 = 8_000;
+/// Positions whose debt exceeds this share of collateral value can be
+/// liquidated.
+pub const LIQUIDATION_BPS: u64 = 8_500;
+/// Two fresh sources further apart than this no longer corroborate each other.
+pub const MAX_DEVIATION_BPS: u64 = 200;
+/// Discount applied to collateral while the oracle is degraded.
+pub const DEGRADED_HAIRCUT_BPS: u64 = 1_000;
+
+const BPS: u64 = 10_000;
+
+#[program]
+pub mod single_oracle_dependency {
+    use super::*;
+
+    /// `feeds[0]` is the primary feed the vulnerable paths read; the secure
+    /// paths read all three.
+    pub fn initialize_market(ctx: Context<InitializeMarket>, feeds: [Pubkey; 3], max_staleness: i64) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        market.admin = ctx.accounts.admin.key();
+        market.feeds = feeds;
+        market.last_price = 0;
+        market.max_staleness = max_staleness;
+        market.bump = ctx.bumps.market;
+        Ok(())
+    }
+
+    /// Deposits `collateral` lamports and records `debt` (USD, 6 decimals)
+    /// against them. Paying out the borrowed asset is out of scope.
+    pub fn open_position(ctx: Context<OpenPosition>, collateral: u64, debt: u64) -> Result<()> {
+        if debt > 0 {
+            let price = match ctx.accounts.feeds_status()? {
+                OracleStatus::Normal(price) => price,
+                OracleStatus::Degraded(_) => return err!(OracleError::OracleDegraded),
+                OracleStatus::Unavailable => return err!(OracleError::OracleUnavailable),
+            };
+            require!(within_limit(collateral, debt, price, MAX_LTV_BPS)?, OracleError::Undercollateralized);
+        }
+
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: ctx.accounts.position.to_account_info(),
+                },
+            ),
+            collateral,
+        )?;
+
+        let position = &mut ctx.accounts.position;
+        position.owner = ctx.accounts.owner.key();
+        position.collateral = collateral;
+        position.debt = debt;
+        position.bump = ctx.bumps.position;
+        Ok(())
+    }
+
+    // VULNERABLE: Withdrawals Hard-Depend on One Feed
+    // 1. Only the primary feed is read, and a stale primary fails the
+    //    instruction outright
+    // 2. That includes positions with no debt, which need no price at all:
+    //    while the feed is down every user's collateral is stuck
+    // 3. Every fresh read is cached in `market.last_price`, which the
+    //    liquidation path below falls back to
+    pub fn vulnerable_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let market = &mut ctx.accounts.market;
+        let price = fresh_price(&ctx.accounts.feed_a, now, market.max_staleness).ok_or(OracleError::StalePrice)?;
+        market.last_price = price;
+
+        let position = &mut ctx.accounts.position;
+        let remaining = position.collateral.checked_sub(amount).ok_or(OracleError::InsufficientCollateral)?;
+        require!(
+            within_limit(remaining, position.debt, price, MAX_LTV_BPS)?,
+            OracleError::Undercollateralized
+        );
+        position.collateral = remaining;
+
+        move_lamports(&position.to_account_info(), &ctx.accounts.owner.to_account_info(), amount)?;
+        msg!("Vulnerable withdraw: {} lamports at primary price {}", amount, price);
+        Ok(())
+    }
+
+    // VULNERABLE: Silent Fallback to the Last Cached Price
+    // 1. When the primary feed is stale, liquidation quietly uses whatever
+    //    price was cached last, however old
+    // 2. An attacker caches a momentary wick, waits for the feed to stall,
+    //    and liquidates positions that are healthy at the real price
+    // 3. The other sources that disagree are never consulted
+    pub fn vulnerable_liquidate(ctx: Context<Liquidate>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let market = &mut ctx.accounts.market;
+        let price = match fresh_price(&ctx.accounts.feed_a, now, market.max_staleness) {
+            Some(price) => {
+                market.last_price = price;
+                price
+            }
+            None => market.last_price,
+        };
+        require!(price > 0, OracleError::StalePrice);
+
+        let seized = seize(&mut ctx.accounts.position, &ctx.accounts.liquidator, price)?;
+        msg!("Vulnerable liquidation: seized {} lamports at price {}", seized, price);
+        Ok(())
+    }
+
+    // SECURE: Multi-Source Withdrawals with Graceful Degradation
+    // 1. A position with no debt needs no price and can always withdraw
+    // 2. All three feeds are read; stale ones are ignored and the rest are
+    //    aggregated (median of three, or two that agree)
+    // 3. When too few sources are fresh or they disagree, the oracle is
+    //    degraded: withdrawals still work, against a haircut price
+    // 4. Only with no fresh source at all are indebted withdrawals refused
+    pub fn secure_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        let debt = ctx.accounts.position.debt;
+        let remaining = ctx
+            .accounts
+            .position
+            .collateral
+            .checked_sub(amount)
+            .ok_or(OracleError::InsufficientCollateral)?;
+
+        if debt > 0 {
+            let price = match ctx.accounts.feeds_status()? {
+                OracleStatus::Normal(price) => price,
+                OracleStatus::Degraded(price) => apply_bps(price, BPS - DEGRADED_HAIRCUT_BPS)?,
+                OracleStatus::Unavailable => return err!(OracleError::OracleUnavailable),
+            };
+            require!(within_limit(remaining, debt, price, MAX_LTV_BPS)?, OracleError::Undercollateralized);
+        }
+
+        let position = &mut ctx.accounts.position;
+        position.collateral = remaining;
+        move_lamports(&position.to_account_info(), &ctx.accounts.owner.to_account_info(), amount)?;
+        msg!("Secure withdraw: {} lamports", amount);
+        Ok(())
+    }
+
+    // SECURE: Liquidate Only on a Corroborated Price
+    // 1. The same staleness-aware aggregation as withdrawals
+    // 2. Liquidation is irreversible, so it requires the normal mode: a
+    //    degraded or unavailable oracle pauses it instead of guessing
+    // 3. There is no cached fallback price to exploit
+    pub fn secure_liquidate(ctx: Context<Liquidate>) -> Result<()> {
+        let price = match ctx.accounts.feeds_status()? {
+            OracleStatus::Normal(price) => price,
+            OracleStatus::Degraded(_) => return err!(OracleError::OracleDegraded),
+            OracleStatus::Unavailable => return err!(OracleError::OracleUnavailable),
+        };
+
+        let seized = seize(&mut ctx.accounts.position, &ctx.accounts.liquidator, price)?;
+        msg!("Secure liquidation: seized {} lamports at price {}", seized, price);
+        Ok(())
+    }
+}
+
+/// Aggregated view of the market's feeds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OracleStatus {
+    /// Three fresh sources (median) or two that agree (mean).
+    Normal(u64),
+    /// One fresh source, or two that disagree (the lower one).
+    Degraded(u64),
+    /// No fresh source.
+    Unavailable,
+}
+
+/// Combines the fresh prices among `prices`; `None` marks a stale feed.
+pub fn aggregate(prices: &[Option<u64>]) -> OracleStatus {
+    let mut fresh: Vec<u64> = prices.iter().flatten().copied().collect();
+    fresh.sort_unstable();
+    match fresh.as_slice() {
+        [] => OracleStatus::Unavailable,
+        [only] => OracleStatus::Degraded(*only),
+        [low, high] => {
+            let spread = (*high as u128 - *low as u128) * BPS as u128;
+            if spread > *low as u128 * MAX_DEVIATION_BPS as u128 {
+                OracleStatus::Degraded(*low)
+            } else {
+                OracleStatus::Normal(((*low as u128 + *high as u128) / 2) as u64)
+            }
+        }
+        sorted => OracleStatus::Normal(sorted[sorted.len() / 2]),
+    }
+}
+
+/// The feed's price, or `None` if it is older than `max_staleness` seconds.
+fn fresh_price(feed: &PriceFeed, now: i64, max_staleness: i64) -> Option<u64> {
+    (now.saturating_sub(feed.publish_time) <= max_staleness).then_some(feed.price)
+}
+
+/// Whether `debt` is at most `limit_bps` of the collateral's USD value.
+/// Prices are USD per SOL with 6 decimals, like the debt.
+fn within_limit(collateral: u64, debt: u64, price: u64, limit_bps: u64) -> Result<bool> {
+    let value = (collateral as u128)
+        .checked_mul(price as u128)
+        .ok_or(OracleError::ArithmeticError)?
+        / LAMPORTS_PER_SOL as u128;
+    Ok(debt as u128 * BPS as u128 <= value * limit_bps as u128)
+}
+
+fn apply_bps(amount: u64, bps: u64) -> Result<u64> {
+    let scaled = (amount as u128 * bps as u128) / BPS as u128;
+    u64::try_from(scaled).map_err(|_| error!(OracleError::ArithmeticError))
+}
+
+/// Hands an unhealthy position's collateral to the liquidator, who takes
+/// over its debt. Repaying that debt is out of scope.
+fn seize<'info>(position: &mut Account<'info, Position>, liquidator: &Signer<'info>, price: u64) -> Result<u64> {
+    require!(
+        !within_limit(position.collateral, position.debt, price, LIQUIDATION_BPS)?,
+        OracleError::PositionHealthy
+    );
+    let seized = position.collateral;
+    position.collateral = 0;
+    position.debt = 0;
+    move_lamports(&position.to_account_info(), &liquidator.to_account_info(), seized)?;
+    Ok(seized)
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(OracleError::ArithmeticError)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(OracleError::ArithmeticError)?;
+    Ok(())
+}
+
+fn status(market: &Market, feeds: [&PriceFeed; 3]) -> Result<OracleStatus> {
+    let now = Clock::get()?.unix_timestamp;
+    Ok(aggregate(&feeds.map(|feed| fresh_price(feed, now, market.max_staleness))))
+}
+
+#[derive(Accounts)]
+pub struct InitializeMarket<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Market::INIT_SPACE,
+        seeds = [b"market"],
+        bump
+    )]
+    pub market: Account<'info, Market>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(seeds = [b"market"], bump = market.bump)]
+    pub market: Account<'info, Market>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [b"position", owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(address = market.feeds[0] @ OracleError::WrongFeed)]
+    pub feed_a: Account<'info, PriceFeed>,
+    #[account(address = market.feeds[1] @ OracleError::WrongFeed)]
+    pub feed_b: Account<'info, PriceFeed>,
+    #[account(address = market.feeds[2] @ OracleError::WrongFeed)]
+    pub feed_c: Account<'info, PriceFeed>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl OpenPosition<'_> {
+    fn feeds_status(&self) -> Result<OracleStatus> {
+        status(&self.market, [&self.feed_a, &self.feed_b, &self.feed_c])
+    }
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut, seeds = [b"market"], bump = market.bump)]
+    pub market: Account<'info, Market>,
+    #[account(
+        mut,
+        seeds = [b"position", owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner @ OracleError::Unauthorized
+    )]
+    pub position: Account<'info, Position>,
+    #[account(address = market.feeds[0] @ OracleError::WrongFeed)]
+    pub feed_a: Account<'info, PriceFeed>,
+    #[account(address = market.feeds[1] @ OracleError::WrongFeed)]
+    pub feed_b: Account<'info, PriceFeed>,
+    #[account(address = market.feeds[2] @ OracleError::WrongFeed)]
+    pub feed_c: Account<'info, PriceFeed>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+impl Withdraw<'_> {
+    fn feeds_status(&self) -> Result<OracleStatus> {
+        status(&self.market, [&self.feed_a, &self.feed_b, &self.feed_c])
+    }
+}
+
+#[derive(Accounts)]
+pub struct Liquidate<'info> {
+    #[account(mut, seeds = [b"market"], bump = market.bump)]
+    pub market: Account<'info, Market>,
+    #[account(
+        mut,
+        seeds = [b"position", position.owner.as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(address = market.feeds[0] @ OracleError::WrongFeed)]
+    pub feed_a: Account<'info, PriceFeed>,
+    #[account(address = market.feeds[1] @ OracleError::WrongFeed)]
+    pub feed_b: Account<'info, PriceFeed>,
+    #[account(address = market.feeds[2] @ OracleError::WrongFeed)]
+    pub feed_c: Account<'info, PriceFeed>,
+    #[account(mut)]
+    pub liquidator: Signer<'info>,
+}
+
+impl Liquidate<'_> {
+    fn feeds_status(&self) -> Result<OracleStatus> {
+        status(&self.market, [&self.feed_a, &self.feed_b, &self.feed_c])
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Market {
+    pub admin: Pubkey,
+    /// Price feeds; the first is the primary.
+    pub feeds: [Pubkey; 3],
+    /// Last fresh primary price seen by the vulnerable paths.
+    pub last_price: u64,
+    /// Feeds older than this many seconds are stale.
+    pub max_staleness: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub owner: Pubkey,
+    /// Collateral in lamports.
+    pub collateral: u64,
+    /// Debt in USD with 6 decimals.
+    pub debt: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum OracleError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Account is not one of the market's price feeds")]
+    WrongFeed,
+    #[msg("Price feed is stale")]
+    StalePrice,
+    #[msg("Oracle is degraded: too few fresh sources or they disagree")]
+    OracleDegraded,
+    #[msg("No fresh price source is available")]
+    OracleUnavailable,
+    #[msg("Position would exceed the maximum loan-to-value")]
+    Undercollateralized,
+    #[msg("Position is healthy and cannot be liquidated")]
+    PositionHealthy,
+    #[msg("Insufficient collateral")]
+    InsufficientCollateral,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_fresh_source_is_unavailable() {
+        assert_eq!(aggregate(&[None, None, None]), OracleStatus::Unavailable);
+    }
+
+    #[test]
+    fn a_single_source_is_degraded() {
+        assert_eq!(aggregate(&[None, Some(100), None]), OracleStatus::Degraded(100));
+    }
+
+    #[test]
+    fn two_sources_must_agree() {
+        assert_eq!(aggregate(&[Some(100), Some(101), None]), OracleStatus::Normal(100));
+        assert_eq!(aggregate(&[None, Some(100), Some(90)]), OracleStatus::Degraded(90));
+    }
+
+    #[test]
+    fn median_ignores_one_outlier() {
+        assert_eq!(aggregate(&[Some(50), Some(100), Some(101)]), OracleStatus::Normal(100));
+    }
+}
@@ -0,0 +1,243 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("41: Single Oracle Dependency", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.SingleOracleDependency as Program<any>;
+    const oracle = anchor.workspace.MockOracle as Program<any>;
+
+    let oracleAuthority: Keypair;
+    let admin: Keypair;
+    let alice: Keypair;
+    let bob: Keypair;
+    let carol: Keypair;
+    let attacker: Keypair;
+
+    const FEED_IDS = [1, 2, 3];
+    const MAX_STALENESS = 60;
+    const usd = (dollars: number) => new anchor.BN(dollars * 1_000_000);
+
+    // 10 SOL against $700 of debt: healthy at $100, liquidatable below ~$82.
+    const COLLATERAL = 10 * LAMPORTS_PER_SOL;
+    const DEBT = 700;
+
+    const marketPda = PublicKey.findProgramAddressSync([Buffer.from("market")], program.programId)[0];
+
+    function feedPda(id: number): PublicKey {
+        const buf = Buffer.alloc(8);
+        buf.writeBigUInt64LE(BigInt(id));
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("feed"), oracleAuthority.publicKey.toBuffer(), buf],
+            oracle.programId
+        )[0];
+    }
+
+    function positionPda(owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("position"), owner.toBuffer()], program.programId)[0];
+    }
+
+    function feeds() {
+        return { feedA: feedPda(1), feedB: feedPda(2), feedC: feedPda(3) };
+    }
+
+    /** `null` publishes a stale price, an hour old. */
+    async function setPrices(...prices: (number | null)[]) {
+        const stale = Math.floor(Date.now() / 1000) - 3_600;
+        for (const [i, price] of prices.entries()) {
+            await oracle.methods
+                .setPrice(usd(price ?? 100), price === null ? new anchor.BN(stale) : null)
+                .accounts({ feed: feedPda(FEED_IDS[i]), authority: oracleAuthority.publicKey })
+                .signers([oracleAuthority])
+                .rpc();
+        }
+    }
+
+    async function openPosition(owner: Keypair, collateral: number, debt: number) {
+        await program.methods
+            .openPosition(new anchor.BN(collateral), usd(debt))
+            .accounts({
+                market: marketPda,
+                position: positionPda(owner.publicKey),
+                ...feeds(),
+                owner: owner.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc();
+    }
+
+    function withdraw(method: "vulnerableWithdraw" | "secureWithdraw", owner: Keypair, amount: number) {
+        return program.methods[method](new anchor.BN(amount))
+            .accounts({
+                market: marketPda,
+                position: positionPda(owner.publicKey),
+                ...feeds(),
+                owner: owner.publicKey,
+            })
+            .signers([owner])
+            .rpc();
+    }
+
+    function liquidate(method: "vulnerableLiquidate" | "secureLiquidate", victim: Keypair) {
+        return program.methods[method]()
+            .accounts({
+                market: marketPda,
+                position: positionPda(victim.publicKey),
+                ...feeds(),
+                liquidator: attacker.publicKey,
+            })
+            .signers([attacker])
+            .rpc();
+    }
+
+    before(async () => {
+        oracleAuthority = Keypair.generate();
+        admin = Keypair.generate();
+        alice = Keypair.generate();
+        bob = Keypair.generate();
+        carol = Keypair.generate();
+        attacker = Keypair.generate();
+
+        for (const kp of [oracleAuthority, admin, alice, bob, carol, attacker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 20 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates three feeds at $100 and a market with the first as primary", async () => {
+            for (const id of FEED_IDS) {
+                await oracle.methods
+                    .initializeFeed(new anchor.BN(id), usd(100))
+                    .accounts({
+                        feed: feedPda(id),
+                        authority: oracleAuthority.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([oracleAuthority])
+                    .rpc();
+            }
+
+            await program.methods
+                .initializeMarket(
+                    FEED_IDS.map((id) => feedPda(id)),
+                    new anchor.BN(MAX_STALENESS)
+                )
+                .accounts({ market: marketPda, admin: admin.publicKey, systemProgram: SystemProgram.programId })
+                .signers([admin])
+                .rpc();
+        });
+
+        it("Alice and Carol borrow $700 against 10 SOL; Bob and the attacker hold collateral only", async () => {
+            await openPosition(alice, COLLATERAL, DEBT);
+            await openPosition(carol, COLLATERAL, DEBT);
+            await openPosition(bob, 2 * LAMPORTS_PER_SOL, 0);
+            await openPosition(attacker, LAMPORTS_PER_SOL, 0);
+        });
+    });
+
+    describe("Exploit: primary feed goes stale", () => {
+        it("Bob cannot withdraw, although a debt-free position needs no price", async () => {
+            await setPrices(null, 100, 100);
+
+            await expectError(withdraw("vulnerableWithdraw", bob, LAMPORTS_PER_SOL), "StalePrice");
+            console.log("  Bob's collateral is stuck until the primary feed recovers");
+        });
+    });
+
+    describe("Exploit: silent fallback to a cached wick", () => {
+        it("The attacker caches a momentary $50 print from the primary feed", async () => {
+            await setPrices(50, 100, 100);
+            await withdraw("vulnerableWithdraw", attacker, 0);
+
+            const market = await program.account.market.fetch(marketPda);
+            expect(market.lastPrice.toNumber()).to.equal(usd(50).toNumber());
+        });
+
+        it("The primary stalls and Alice's healthy position is liquidated at the cached price", async () => {
+            await setPrices(null, 100, 100);
+            const before = await provider.connection.getBalance(attacker.publicKey);
+
+            await liquidate("vulnerableLiquidate", alice);
+
+            const gained = (await provider.connection.getBalance(attacker.publicKey)) - before;
+            console.log(`  Attacker seized ${gained / LAMPORTS_PER_SOL} SOL while two live feeds read $100`);
+            expect(gained).to.be.greaterThan(COLLATERAL - LAMPORTS_PER_SOL / 100);
+
+            const position = await program.account.position.fetch(positionPda(alice.publicKey));
+            expect(position.collateral.toNumber()).to.equal(0);
+        });
+    });
+
+    describe("Secure: multi-source median with degradation modes", () => {
+        it("Normal: a stale primary is outvoted by two fresh feeds that agree", async () => {
+            await setPrices(null, 100, 100);
+            await expectError(liquidate("secureLiquidate", carol), "PositionHealthy");
+        });
+
+        it("Normal: the median ignores one manipulated source", async () => {
+            await setPrices(50, 100, 100);
+            await expectError(liquidate("secureLiquidate", carol), "PositionHealthy");
+        });
+
+        it("Degraded (one fresh source): liquidation pauses, withdrawals continue at a haircut", async () => {
+            await setPrices(null, 100, null);
+
+            await expectError(liquidate("secureLiquidate", carol), "OracleDegraded");
+
+            // $90 after the 10% haircut: 9.9 SOL still covers $700 at 80% LTV.
+            await withdraw("secureWithdraw", carol, LAMPORTS_PER_SOL / 10);
+            // 9 SOL at $90 does not.
+            await expectError(withdraw("secureWithdraw", carol, LAMPORTS_PER_SOL), "Undercollateralized");
+        });
+
+        it("Degraded (two sources disagree): liquidation pauses", async () => {
+            await setPrices(null, 100, 80);
+            await expectError(liquidate("secureLiquidate", carol), "OracleDegraded");
+        });
+
+        it("Unavailable: indebted withdrawals stop, debt-free ones do not", async () => {
+            await setPrices(null, null, null);
+
+            await expectError(withdraw("secureWithdraw", carol, 1), "OracleUnavailable");
+            await expectError(liquidate("secureLiquidate", carol), "OracleUnavailable");
+
+            await withdraw("secureWithdraw", bob, LAMPORTS_PER_SOL);
+            const position = await program.account.position.fetch(positionPda(bob.publicKey));
+            expect(position.collateral.toNumber()).to.equal(LAMPORTS_PER_SOL);
+        });
+
+        it("Liquidation resumes when every source confirms a real drop", async () => {
+            await setPrices(70, 70, 70);
+
+            await liquidate("secureLiquidate", carol);
+
+            const position = await program.account.position.fetch(positionPda(carol.publicKey));
+            expect(position.collateral.toNumber()).to.equal(0);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Single Oracle Dependency ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  price = fresh(primary).ok_or(StalePrice)?   // withdrawals halt");
+            console.log("  price = fresh(primary).unwrap_or(last_price) // liquidations use stale data");
+            console.log("  Risk: funds stuck during outages, or liquidation at a cached wick\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  match aggregate([fresh(a), fresh(b), fresh(c)]) { Normal | Degraded | Unavailable }");
+            console.log("  Protection: median of fresh sources; degraded mode pauses liquidations and");
+            console.log("  haircuts withdrawals; debt-free positions never need a price\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Decide up front what each instruction does when the oracle is stale, instead of halting or guessing.");
+        });
+    });
+});
@@ -0,0 +1,153 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{
+    allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer,
+};
+
+declare_id!("7dgS3xVrdBY79s2yNL1LZ6PMyWpWGRTZwbD6DKJsY6bS");
+
+#[program]
+pub mod pda_prefunding_griefing {
+    use super::*;
+
+    // VULNERABLE: Account Creation Assumes an Empty Address
+    // 1. The profile PDA is derived from the owner alone, so anyone can
+    //    compute it before the owner creates it
+    // 2. The account is created with `system_program::create_account`,
+    //    which refuses any address that already holds lamports
+    // 3. A plain transfer to the PDA therefore blocks creation, and since
+    //    nobody can sign for the PDA to move the lamports out, the block
+    //    is permanent
+    pub fn vulnerable_create_profile(ctx: Context<CreateProfile>) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+        let bump = ctx.bumps.profile;
+        let seeds: &[&[u8]] = &[b"profile", owner.as_ref(), &[bump]];
+        let signer = &[seeds];
+
+        let space = 8 + Profile::INIT_SPACE;
+        create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                CreateAccount {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: ctx.accounts.profile.to_account_info(),
+                },
+                signer,
+            ),
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            &crate::ID,
+        )?;
+
+        write_profile(&ctx.accounts.profile, owner, bump)?;
+        msg!("Vulnerable create: profile created for {}", owner);
+        Ok(())
+    }
+
+    // SECURE: Lamport-Tolerant Initialization
+    // 1. An address that already belongs to this program is initialized
+    //    and is rejected
+    // 2. An empty address is created with `create_account` as before
+    // 3. A pre-funded address is still a System account with no data, so
+    //    it is topped up to the rent-exempt minimum, then allocated and
+    //    assigned with the PDA's signature; the griefer's lamports simply
+    //    stay in the profile
+    // Anchor's `init` constraint performs these same steps.
+    pub fn secure_create_profile(ctx: Context<CreateProfile>) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+        let bump = ctx.bumps.profile;
+        let seeds: &[&[u8]] = &[b"profile", owner.as_ref(), &[bump]];
+        let signer = &[seeds];
+
+        let profile = ctx.accounts.profile.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        require_keys_eq!(*profile.owner, System::id(), ProfileError::AlreadyInitialized);
+
+        let space = 8 + Profile::INIT_SPACE;
+        let required = Rent::get()?.minimum_balance(space);
+        let current = profile.lamports();
+
+        if current == 0 {
+            create_account(
+                CpiContext::new_with_signer(
+                    system_program,
+                    CreateAccount {
+                        from: ctx.accounts.owner.to_account_info(),
+                        to: profile.clone(),
+                    },
+                    signer,
+                ),
+                required,
+                space as u64,
+                &crate::ID,
+            )?;
+        } else {
+            let shortfall = required.saturating_sub(current);
+            if shortfall > 0 {
+                transfer(
+                    CpiContext::new(
+                        system_program.clone(),
+                        Transfer {
+                            from: ctx.accounts.owner.to_account_info(),
+                            to: profile.clone(),
+                        },
+                    ),
+                    shortfall,
+                )?;
+            }
+            allocate(
+                CpiContext::new_with_signer(
+                    system_program.clone(),
+                    Allocate {
+                        account_to_allocate: profile.clone(),
+                    },
+                    signer,
+                ),
+                space as u64,
+            )?;
+            assign(
+                CpiContext::new_with_signer(
+                    system_program,
+                    Assign {
+                        account_to_assign: profile.clone(),
+                    },
+                    signer,
+                ),
+                &crate::ID,
+            )?;
+            msg!("Secure create: absorbed {} pre-funded lamports", current);
+        }
+
+        write_profile(&ctx.accounts.profile, owner, bump)?;
+        Ok(())
+    }
+}
+
+fn write_profile(account: &AccountInfo, owner: Pubkey, bump: u8) -> Result<()> {
+    let profile = Profile { owner, bump };
+    let mut data = account.try_borrow_mut_data()?;
+    profile.try_serialize(&mut &mut data[..])
+}
+
+#[derive(Accounts)]
+pub struct CreateProfile<'info> {
+    /// CHECK: Address is checked by the seeds; the instruction creates the
+    /// account itself.
+    #[account(mut, seeds = [b"profile", owner.key().as_ref()], bump)]
+    pub profile: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Profile {
+    pub owner: Pubkey,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum ProfileError {
+    #[msg("Profile is already initialized")]
+    AlreadyInitialized,
+}
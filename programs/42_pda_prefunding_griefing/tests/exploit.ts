@@ -0,0 +1,146 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { expect } from "chai";
+
+describe("42: PDA Pre-Funding Griefing", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.PdaPrefundingGriefing as Program<any>;
+
+    let attacker: Keypair;
+    let alice: Keypair;
+    let bob: Keypair;
+    let carol: Keypair;
+
+    function profilePda(owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("profile"), owner.toBuffer()], program.programId)[0];
+    }
+
+    function createProfile(method: "vulnerableCreateProfile" | "secureCreateProfile", owner: Keypair) {
+        return program.methods[method]()
+            .accounts({
+                profile: profilePda(owner.publicKey),
+                owner: owner.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc();
+    }
+
+    /** The cheapest transfer that creates a System account at `target`. */
+    async function prefund(target: PublicKey): Promise<number> {
+        const lamports = await provider.connection.getMinimumBalanceForRentExemption(0);
+        const tx = new Transaction().add(
+            SystemProgram.transfer({ fromPubkey: attacker.publicKey, toPubkey: target, lamports })
+        );
+        await provider.sendAndConfirm(tx, [attacker]);
+        return lamports;
+    }
+
+    before(async () => {
+        attacker = Keypair.generate();
+        alice = Keypair.generate();
+        bob = Keypair.generate();
+        carol = Keypair.generate();
+
+        for (const kp of [attacker, alice, bob, carol]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 2 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Profile addresses are predictable from the owner's key", () => {
+            console.log(`  Alice's profile will live at ${profilePda(alice.publicKey).toBase58()}`);
+        });
+    });
+
+    describe("Exploit: pre-funding the PDA", () => {
+        it("The attacker sends rent-exempt dust to Alice's future profile", async () => {
+            const spent = await prefund(profilePda(alice.publicKey));
+            console.log(`  Attack cost: ${spent} lamports`);
+
+            const info = await provider.connection.getAccountInfo(profilePda(alice.publicKey));
+            expect(info!.owner.toBase58()).to.equal(SystemProgram.programId.toBase58());
+        });
+
+        it("Alice can no longer create a profile", async () => {
+            try {
+                await createProfile("vulnerableCreateProfile", alice);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.match(/already in use/i);
+            }
+        });
+
+        it("Retrying does not help: nobody can move the lamports back out", async () => {
+            try {
+                await createProfile("vulnerableCreateProfile", alice);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.match(/already in use/i);
+            }
+            console.log("  Alice's profile address is blocked for good");
+        });
+    });
+
+    describe("Secure: lamport-tolerant initialization", () => {
+        it("Creates Bob's profile despite the pre-funding", async () => {
+            const dust = await prefund(profilePda(bob.publicKey));
+
+            await createProfile("secureCreateProfile", bob);
+
+            const profile = await program.account.profile.fetch(profilePda(bob.publicKey));
+            expect(profile.owner.toBase58()).to.equal(bob.publicKey.toBase58());
+
+            const info = await provider.connection.getAccountInfo(profilePda(bob.publicKey));
+            const rent = await provider.connection.getMinimumBalanceForRentExemption(info!.data.length);
+            console.log(`  Profile holds ${info!.lamports} lamports (${dust} of them from the attacker)`);
+            expect(info!.lamports).to.equal(rent);
+        });
+
+        it("Unblocks Alice's profile too", async () => {
+            await createProfile("secureCreateProfile", alice);
+
+            const profile = await program.account.profile.fetch(profilePda(alice.publicKey));
+            expect(profile.owner.toBase58()).to.equal(alice.publicKey.toBase58());
+        });
+
+        it("Creates a profile at an untouched address as usual", async () => {
+            await createProfile("secureCreateProfile", carol);
+
+            const profile = await program.account.profile.fetch(profilePda(carol.publicKey));
+            expect(profile.owner.toBase58()).to.equal(carol.publicKey.toBase58());
+        });
+
+        it("Refuses to initialize an existing profile again", async () => {
+            try {
+                await createProfile("secureCreateProfile", carol);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("AlreadyInitialized");
+            }
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== PDA Pre-Funding Griefing ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  system_program::create_account(payer -> pda, rent, space, program_id)");
+            console.log("  Risk: any lamports at the predictable address make creation fail forever\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  lamports == 0 ? create_account : transfer(shortfall) + allocate + assign");
+            console.log("  Protection: pre-funded addresses are initialized anyway (as Anchor's `init` does)\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Anyone can send lamports to any address. Never assume a PDA is empty before you create it.");
+        });
+    });
+});
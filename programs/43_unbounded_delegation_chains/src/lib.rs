@@ -0,0 +1,312 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("9tz31rdmf6oP85CwTn6NrcRyidMrHJ2xm5mhhiXon2jj");
+
+/// Longest chain the secure paths accept; a direct grant has depth 1.
+pub const MAX_DEPTH: u8 = 3;
+
+#[program]
+pub mod unbounded_delegation_chains {
+    use super::*;
+
+    pub fn initialize_treasury(ctx: Context<InitializeTreasury>, deposit: u64) -> Result<()> {
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.owner = ctx.accounts.owner.key();
+        treasury.next_generation = 1;
+        treasury.bump = ctx.bumps.treasury;
+
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                },
+            ),
+            deposit,
+        )
+    }
+
+    /// The owner grants spending authority directly (depth 1).
+    pub fn grant(ctx: Context<Grant>) -> Result<()> {
+        let generation = ctx.accounts.treasury.issue_generation()?;
+        let key = ctx.accounts.delegation.key();
+
+        let delegation = &mut ctx.accounts.delegation;
+        delegation.treasury = ctx.accounts.treasury.key();
+        delegation.grantor = ctx.accounts.owner.key();
+        delegation.delegate = ctx.accounts.delegate.key();
+        delegation.parent = Pubkey::default();
+        delegation.root = key;
+        delegation.depth = 1;
+        delegation.generation = generation;
+        delegation.parent_generation = 0;
+        delegation.bump = ctx.bumps.delegation;
+        Ok(())
+    }
+
+    /// The grantor closes a delegation it issued.
+    pub fn revoke(_ctx: Context<Revoke>) -> Result<()> {
+        Ok(())
+    }
+
+    // VULNERABLE: Unbounded Re-Delegation
+    // 1. Any delegate can delegate again, to any depth
+    // 2. The child records nothing that ties it to its ancestors, so it
+    //    stays valid on its own
+    // 3. A compromised delegate that fans out sub-delegations before being
+    //    revoked keeps spending through them
+    pub fn vulnerable_delegate(ctx: Context<Delegate>) -> Result<()> {
+        let parent = &ctx.accounts.parent;
+        let delegation = &mut ctx.accounts.delegation;
+        delegation.treasury = parent.treasury;
+        delegation.grantor = ctx.accounts.grantor.key();
+        delegation.delegate = ctx.accounts.delegate.key();
+        delegation.parent = parent.key();
+        delegation.depth = parent.depth.saturating_add(1);
+        delegation.bump = ctx.bumps.delegation;
+        msg!("Vulnerable delegate: depth {}", delegation.depth);
+        Ok(())
+    }
+
+    // VULNERABLE: Spending Checks Only the Last Link
+    // 1. The delegation account existing is taken as proof of authority
+    // 2. Revoking (closing) an ancestor has no effect on its descendants
+    pub fn vulnerable_spend(ctx: Context<Spend>, amount: u64) -> Result<()> {
+        move_lamports(
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.delegate.to_account_info(),
+            amount,
+        )?;
+        msg!("Vulnerable spend: {} lamports at depth {}", amount, ctx.accounts.delegation.depth);
+        Ok(())
+    }
+
+    // SECURE: Depth-Limited Delegation Bound to Its Ancestors
+    // 1. The parent's own chain must be valid before it can delegate
+    // 2. Depth is capped at `MAX_DEPTH`
+    // 3. The child stores the branch root and the parent's generation; every
+    //    delegation gets a fresh generation from the treasury, so a
+    //    re-granted parent never matches children issued before
+    pub fn secure_delegate<'info>(ctx: Context<'_, '_, 'info, 'info, Delegate<'info>>) -> Result<()> {
+        let parent = &ctx.accounts.parent;
+        verify_chain(parent, ctx.remaining_accounts)?;
+
+        let depth = parent.depth.checked_add(1).ok_or(DelegationError::ArithmeticError)?;
+        require!(depth <= MAX_DEPTH, DelegationError::DepthExceeded);
+
+        let (parent_key, root, parent_generation) = (parent.key(), parent.root, parent.generation);
+        let generation = ctx.accounts.treasury.issue_generation()?;
+
+        let delegation = &mut ctx.accounts.delegation;
+        delegation.treasury = ctx.accounts.treasury.key();
+        delegation.grantor = ctx.accounts.grantor.key();
+        delegation.delegate = ctx.accounts.delegate.key();
+        delegation.parent = parent_key;
+        delegation.root = root;
+        delegation.depth = depth;
+        delegation.generation = generation;
+        delegation.parent_generation = parent_generation;
+        delegation.bump = ctx.bumps.delegation;
+        Ok(())
+    }
+
+    // SECURE: Whole Chain Checked on Use
+    // 1. The caller passes every ancestor, parent first, as remaining
+    //    accounts; there are at most `MAX_DEPTH - 1` of them
+    // 2. Each ancestor must still exist and carry the generation its child
+    //    recorded, up to a direct grant from the owner
+    // 3. Revoking any link therefore cuts off everything below it
+    pub fn secure_spend<'info>(ctx: Context<'_, '_, 'info, 'info, Spend<'info>>, amount: u64) -> Result<()> {
+        verify_chain(&ctx.accounts.delegation, ctx.remaining_accounts)?;
+
+        move_lamports(
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.delegate.to_account_info(),
+            amount,
+        )?;
+        msg!("Secure spend: {} lamports at depth {}", amount, ctx.accounts.delegation.depth);
+        Ok(())
+    }
+}
+
+/// Walks from `delegation` up to its direct grant. `ancestors` must hold
+/// every ancestor, parent first.
+fn verify_chain<'info>(delegation: &Account<'info, Delegation>, ancestors: &'info [AccountInfo<'info>]) -> Result<()> {
+    require!(delegation.depth <= MAX_DEPTH, DelegationError::DepthExceeded);
+    require!(
+        ancestors.len() + 1 == delegation.depth as usize,
+        DelegationError::BrokenChain
+    );
+
+    let mut parent_key = delegation.parent;
+    let mut parent_generation = delegation.parent_generation;
+    for info in ancestors {
+        require_keys_eq!(info.key(), parent_key, DelegationError::BrokenChain);
+        // A revoked ancestor has been closed and no longer deserializes.
+        let parent = Account::<Delegation>::try_from(info).map_err(|_| error!(DelegationError::Revoked))?;
+        require_keys_eq!(parent.treasury, delegation.treasury, DelegationError::BrokenChain);
+        require_keys_eq!(parent.root, delegation.root, DelegationError::BrokenChain);
+        require!(parent.generation == parent_generation, DelegationError::StaleDelegation);
+
+        parent_key = parent.parent;
+        parent_generation = parent.parent_generation;
+    }
+
+    // The top of the chain is a direct grant from the owner.
+    require_keys_eq!(parent_key, Pubkey::default(), DelegationError::BrokenChain);
+    Ok(())
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(DelegationError::InsufficientFunds)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(DelegationError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeTreasury<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Treasury::INIT_SPACE,
+        seeds = [b"treasury", owner.key().as_ref()],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Grant<'info> {
+    #[account(
+        mut,
+        seeds = [b"treasury", owner.key().as_ref()],
+        bump = treasury.bump,
+        has_one = owner @ DelegationError::Unauthorized
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Delegation::INIT_SPACE,
+        seeds = [b"delegation", treasury.key().as_ref(), delegate.key().as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+    /// CHECK: Any wallet can receive authority.
+    pub delegate: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Delegate<'info> {
+    #[account(mut, seeds = [b"treasury", treasury.owner.as_ref()], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    /// The grantor's own delegation.
+    #[account(
+        seeds = [b"delegation", treasury.key().as_ref(), grantor.key().as_ref()],
+        bump = parent.bump,
+        has_one = treasury @ DelegationError::BrokenChain
+    )]
+    pub parent: Account<'info, Delegation>,
+    #[account(
+        init,
+        payer = grantor,
+        space = 8 + Delegation::INIT_SPACE,
+        seeds = [b"delegation", treasury.key().as_ref(), delegate.key().as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+    /// CHECK: Any wallet can receive authority.
+    pub delegate: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub grantor: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Revoke<'info> {
+    #[account(mut, has_one = grantor @ DelegationError::Unauthorized, close = grantor)]
+    pub delegation: Account<'info, Delegation>,
+    #[account(mut)]
+    pub grantor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Spend<'info> {
+    #[account(mut, seeds = [b"treasury", treasury.owner.as_ref()], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        seeds = [b"delegation", treasury.key().as_ref(), delegate.key().as_ref()],
+        bump = delegation.bump,
+        has_one = treasury @ DelegationError::BrokenChain
+    )]
+    pub delegation: Account<'info, Delegation>,
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Treasury {
+    pub owner: Pubkey,
+    /// Next generation to hand out; never reused.
+    pub next_generation: u64,
+    pub bump: u8,
+}
+
+impl Treasury {
+    fn issue_generation(&mut self) -> Result<u64> {
+        let generation = self.next_generation;
+        self.next_generation = generation.checked_add(1).ok_or(DelegationError::ArithmeticError)?;
+        Ok(generation)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Delegation {
+    pub treasury: Pubkey,
+    /// Issued this delegation and can revoke it.
+    pub grantor: Pubkey,
+    pub delegate: Pubkey,
+    /// Parent delegation, or the default key for a direct grant.
+    pub parent: Pubkey,
+    /// Direct grant at the top of this branch.
+    pub root: Pubkey,
+    pub depth: u8,
+    pub generation: u64,
+    /// The parent's generation when this delegation was issued.
+    pub parent_generation: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum DelegationError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Delegation chain is too deep")]
+    DepthExceeded,
+    #[msg("Ancestors do not form a chain up to a direct grant")]
+    BrokenChain,
+    #[msg("An ancestor delegation has been revoked")]
+    Revoked,
+    #[msg("An ancestor delegation was re-issued after this one was granted")]
+    StaleDelegation,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
@@ -0,0 +1,222 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("43: Unbounded Delegation Chains", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.UnboundedDelegationChains as Program<any>;
+
+    let alice: Keypair;
+    let bob: Keypair;
+    let mallory: Keypair;
+    let sybils: Keypair[];
+
+    const SPEND = LAMPORTS_PER_SOL / 10;
+
+    function treasuryPda(owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("treasury"), owner.toBuffer()], program.programId)[0];
+    }
+
+    function delegationPda(owner: PublicKey, delegate: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("delegation"), treasuryPda(owner).toBuffer(), delegate.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function readonly(keys: PublicKey[]) {
+        return keys.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }));
+    }
+
+    async function grant(owner: Keypair, delegate: PublicKey) {
+        await program.methods
+            .grant()
+            .accounts({
+                treasury: treasuryPda(owner.publicKey),
+                delegation: delegationPda(owner.publicKey, delegate),
+                delegate,
+                owner: owner.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc();
+    }
+
+    async function revoke(owner: Keypair, grantor: Keypair, delegate: PublicKey) {
+        await program.methods
+            .revoke()
+            .accounts({ delegation: delegationPda(owner.publicKey, delegate), grantor: grantor.publicKey })
+            .signers([grantor])
+            .rpc();
+    }
+
+    function delegate(
+        method: "vulnerableDelegate" | "secureDelegate",
+        owner: Keypair,
+        grantor: Keypair,
+        delegate: PublicKey,
+        ancestors: PublicKey[] = []
+    ) {
+        return program.methods[method]()
+            .accounts({
+                treasury: treasuryPda(owner.publicKey),
+                parent: delegationPda(owner.publicKey, grantor.publicKey),
+                delegation: delegationPda(owner.publicKey, delegate),
+                delegate,
+                grantor: grantor.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .remainingAccounts(readonly(ancestors))
+            .signers([grantor])
+            .rpc();
+    }
+
+    function spend(
+        method: "vulnerableSpend" | "secureSpend",
+        owner: Keypair,
+        delegate: Keypair,
+        ancestors: PublicKey[] = []
+    ) {
+        return program.methods[method](new anchor.BN(SPEND))
+            .accounts({
+                treasury: treasuryPda(owner.publicKey),
+                delegation: delegationPda(owner.publicKey, delegate.publicKey),
+                delegate: delegate.publicKey,
+            })
+            .remainingAccounts(readonly(ancestors))
+            .signers([delegate])
+            .rpc();
+    }
+
+    before(async () => {
+        alice = Keypair.generate();
+        bob = Keypair.generate();
+        mallory = Keypair.generate();
+        sybils = Array.from({ length: 4 }, () => Keypair.generate());
+
+        for (const kp of [alice, bob, mallory, ...sybils]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Alice and Bob each fund a treasury and delegate spending to Mallory's hot wallet", async () => {
+            for (const owner of [alice, bob]) {
+                await program.methods
+                    .initializeTreasury(new anchor.BN(5 * LAMPORTS_PER_SOL))
+                    .accounts({
+                        treasury: treasuryPda(owner.publicKey),
+                        owner: owner.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([owner])
+                    .rpc();
+                await grant(owner, mallory.publicKey);
+            }
+        });
+    });
+
+    describe("Exploit: sub-delegations survive revocation", () => {
+        it("The compromised delegate fans out a chain of any depth", async () => {
+            let grantor = mallory;
+            for (const sybil of sybils) {
+                await delegate("vulnerableDelegate", alice, grantor, sybil.publicKey);
+                grantor = sybil;
+            }
+
+            const deepest = await program.account.delegation.fetch(delegationPda(alice.publicKey, sybils[3].publicKey));
+            console.log(`  Deepest delegation: depth ${deepest.depth}`);
+            expect(deepest.depth).to.equal(5);
+        });
+
+        it("Alice revokes Mallory, which cuts off only Mallory", async () => {
+            await revoke(alice, alice, mallory.publicKey);
+
+            try {
+                await spend("vulnerableSpend", alice, mallory);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("AccountNotInitialized");
+            }
+        });
+
+        it("The grandchildren keep spending", async () => {
+            const before = await provider.connection.getBalance(sybils[3].publicKey);
+
+            await spend("vulnerableSpend", alice, sybils[3]);
+
+            const gained = (await provider.connection.getBalance(sybils[3].publicKey)) - before;
+            console.log(`  Depth-5 delegate drained ${gained} lamports after the revocation`);
+            expect(gained).to.be.greaterThan(0);
+        });
+    });
+
+    describe("Secure: bounded chains with cascading revocation", () => {
+        const chain = () => ({
+            mallory: delegationPda(bob.publicKey, mallory.publicKey),
+            first: delegationPda(bob.publicKey, sybils[0].publicKey),
+            second: delegationPda(bob.publicKey, sybils[1].publicKey),
+        });
+
+        it("Allows re-delegation up to the depth limit", async () => {
+            await delegate("secureDelegate", bob, mallory, sybils[0].publicKey);
+            await delegate("secureDelegate", bob, sybils[0], sybils[1].publicKey, [chain().mallory]);
+
+            const second = await program.account.delegation.fetch(chain().second);
+            expect(second.depth).to.equal(3);
+            expect(second.root.toBase58()).to.equal(chain().mallory.toBase58());
+        });
+
+        it("Rejects a delegation past the depth limit", async () => {
+            await expectError(
+                delegate("secureDelegate", bob, sybils[1], sybils[2].publicKey, [chain().first, chain().mallory]),
+                "DepthExceeded"
+            );
+        });
+
+        it("Spending requires the full chain of ancestors", async () => {
+            await expectError(spend("secureSpend", bob, sybils[1], [chain().first]), "BrokenChain");
+            await spend("secureSpend", bob, sybils[1], [chain().first, chain().mallory]);
+        });
+
+        it("Revoking Mallory cuts off every descendant", async () => {
+            await revoke(bob, bob, mallory.publicKey);
+
+            await expectError(spend("secureSpend", bob, sybils[1], [chain().first, chain().mallory]), "Revoked");
+            await expectError(spend("secureSpend", bob, sybils[0], [chain().mallory]), "Revoked");
+        });
+
+        it("Re-granting Mallory later does not revive the old sub-delegations", async () => {
+            await grant(bob, mallory.publicKey);
+
+            await expectError(
+                spend("secureSpend", bob, sybils[1], [chain().first, chain().mallory]),
+                "StaleDelegation"
+            );
+            await spend("secureSpend", bob, mallory);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Unbounded Delegation Chains ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  delegation exists => authorized; delegates re-delegate without limit");
+            console.log("  Risk: revoking a compromised delegate leaves its sub-delegations live\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  depth <= MAX_DEPTH; child stores root and parent generation;");
+            console.log("  every ancestor is passed and checked on use");
+            console.log("  Protection: revoking any link revokes the whole subtree\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Authority derived from a grant must end when the grant ends. Check the chain, not the last link.");
+        });
+    });
+});
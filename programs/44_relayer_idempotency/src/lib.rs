@@ -0,0 +1,169 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("4McqV1vcvf2naLUxTL5DxCim3uGk5LbW8u8oNcTgEW8q");
+
+#[program]
+pub mod relayer_idempotency {
+    use super::*;
+
+    pub fn open_wallet(ctx: Context<OpenWallet>, deposit: u64) -> Result<()> {
+        let wallet = &mut ctx.accounts.wallet;
+        wallet.owner = ctx.accounts.owner.key();
+        wallet.bump = ctx.bumps.wallet;
+
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: ctx.accounts.wallet.to_account_info(),
+                },
+            ),
+            deposit,
+        )
+    }
+
+    // VULNERABLE: Payment With No Notion of an Intent
+    // 1. The owner authorizes a payment; a relayer submits it and pays fees
+    // 2. When confirmation is slow, the relayer rebuilds the transaction
+    //    (new blockhash, higher priority fee) and submits again
+    // 3. The runtime only drops transactions with identical signatures, so
+    //    both copies land and the owner pays twice
+    pub fn vulnerable_pay(ctx: Context<VulnerablePay>, amount: u64) -> Result<()> {
+        move_lamports(
+            &ctx.accounts.wallet.to_account_info(),
+            &ctx.accounts.recipient.to_account_info(),
+            amount,
+        )?;
+        msg!("Vulnerable pay: {} lamports", amount);
+        Ok(())
+    }
+
+    // SECURE: Intent Id Recorded on First Execution
+    // 1. The client generates a random `intent_id` once per user intent and
+    //    reuses it on every retry
+    // 2. The intent PDA is derived from the wallet and that id and created
+    //    with `init`, which fails if it already exists
+    // 3. Whichever copy lands first executes; every later copy fails
+    //    before moving any funds
+    pub fn secure_pay(ctx: Context<SecurePay>, intent_id: [u8; 16], amount: u64) -> Result<()> {
+        move_lamports(
+            &ctx.accounts.wallet.to_account_info(),
+            &ctx.accounts.recipient.to_account_info(),
+            amount,
+        )?;
+
+        let intent = &mut ctx.accounts.intent;
+        intent.wallet = ctx.accounts.wallet.key();
+        intent.intent_id = intent_id;
+        intent.recipient = ctx.accounts.recipient.key();
+        intent.amount = amount;
+        intent.executed_slot = Clock::get()?.slot;
+        intent.bump = ctx.bumps.intent;
+        msg!("Secure pay: {} lamports", amount);
+        Ok(())
+    }
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(RelayerError::InsufficientFunds)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(RelayerError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct OpenWallet<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Wallet::INIT_SPACE,
+        seeds = [b"wallet", owner.key().as_ref()],
+        bump
+    )]
+    pub wallet: Account<'info, Wallet>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerablePay<'info> {
+    #[account(
+        mut,
+        seeds = [b"wallet", owner.key().as_ref()],
+        bump = wallet.bump,
+        has_one = owner @ RelayerError::Unauthorized
+    )]
+    pub wallet: Account<'info, Wallet>,
+    pub owner: Signer<'info>,
+    /// CHECK: Any account can receive lamports.
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+    /// Submits the transaction and pays its fees.
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(intent_id: [u8; 16])]
+pub struct SecurePay<'info> {
+    #[account(
+        mut,
+        seeds = [b"wallet", owner.key().as_ref()],
+        bump = wallet.bump,
+        has_one = owner @ RelayerError::Unauthorized
+    )]
+    pub wallet: Account<'info, Wallet>,
+    #[account(
+        init,
+        payer = relayer,
+        space = 8 + Intent::INIT_SPACE,
+        seeds = [b"intent", wallet.key().as_ref(), intent_id.as_ref()],
+        bump
+    )]
+    pub intent: Account<'info, Intent>,
+    pub owner: Signer<'info>,
+    /// CHECK: Any account can receive lamports.
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+    /// Submits the transaction, pays its fees and the intent's rent.
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Wallet {
+    pub owner: Pubkey,
+    pub bump: u8,
+}
+
+/// Receipt of an executed intent; its existence is the dedupe.
+#[account]
+#[derive(InitSpace)]
+pub struct Intent {
+    pub wallet: Pubkey,
+    pub intent_id: [u8; 16],
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub executed_slot: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum RelayerError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
@@ -0,0 +1,166 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+    ComputeBudgetProgram,
+    Keypair,
+    LAMPORTS_PER_SOL,
+    PublicKey,
+    SystemProgram,
+    Transaction,
+    TransactionInstruction,
+    sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import { randomBytes } from "crypto";
+import { expect } from "chai";
+
+describe("44: Relayer Idempotency", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.RelayerIdempotency as Program<any>;
+
+    let relayer: Keypair;
+    let alice: Keypair;
+    let bob: Keypair;
+
+    const PAYMENT = LAMPORTS_PER_SOL / 2;
+
+    function walletPda(owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("wallet"), owner.toBuffer()], program.programId)[0];
+    }
+
+    function intentPda(owner: PublicKey, intentId: Buffer): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("intent"), walletPda(owner).toBuffer(), intentId],
+            program.programId
+        )[0];
+    }
+
+    /**
+     * The relayer submits `ix` for Alice. Each attempt is a new transaction:
+     * fresh blockhash and a priority fee that rises with `attempt`.
+     */
+    async function relay(ix: TransactionInstruction, attempt: number): Promise<string> {
+        const tx = new Transaction().add(ComputeBudgetProgram.setComputeUnitPrice({ microLamports: attempt }), ix);
+        tx.feePayer = relayer.publicKey;
+        return sendAndConfirmTransaction(provider.connection, tx, [relayer, alice]);
+    }
+
+    function vulnerablePayIx(): Promise<TransactionInstruction> {
+        return program.methods
+            .vulnerablePay(new anchor.BN(PAYMENT))
+            .accounts({
+                wallet: walletPda(alice.publicKey),
+                owner: alice.publicKey,
+                recipient: bob.publicKey,
+                relayer: relayer.publicKey,
+            })
+            .instruction();
+    }
+
+    function securePayIx(intentId: Buffer): Promise<TransactionInstruction> {
+        return program.methods
+            .securePay([...intentId], new anchor.BN(PAYMENT))
+            .accounts({
+                wallet: walletPda(alice.publicKey),
+                intent: intentPda(alice.publicKey, intentId),
+                owner: alice.publicKey,
+                recipient: bob.publicKey,
+                relayer: relayer.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .instruction();
+    }
+
+    async function balance(account: PublicKey): Promise<number> {
+        return provider.connection.getBalance(account);
+    }
+
+    before(async () => {
+        relayer = Keypair.generate();
+        alice = Keypair.generate();
+        bob = Keypair.generate();
+
+        for (const kp of [relayer, alice, bob]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Alice funds a wallet with 3 SOL", async () => {
+            await program.methods
+                .openWallet(new anchor.BN(3 * LAMPORTS_PER_SOL))
+                .accounts({
+                    wallet: walletPda(alice.publicKey),
+                    owner: alice.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([alice])
+                .rpc();
+        });
+    });
+
+    describe("Exploit: a retry applies the intent twice", () => {
+        it("Alice asks to pay Bob 0.5 SOL once; the relayer retries and both copies land", async () => {
+            const before = await balance(bob.publicKey);
+
+            const first = await relay(await vulnerablePayIx(), 1);
+            // No confirmation seen in time: rebuild with a higher fee and resend.
+            const retry = await relay(await vulnerablePayIx(), 2);
+            expect(first).to.not.equal(retry);
+
+            const received = (await balance(bob.publicKey)) - before;
+            console.log(`  One intent, two transactions: Bob received ${received / LAMPORTS_PER_SOL} SOL`);
+            expect(received).to.equal(2 * PAYMENT);
+        });
+    });
+
+    describe("Secure: intent id PDA dedupe", () => {
+        it("The first submission of an intent executes", async () => {
+            const intentId = randomBytes(16);
+            const before = await balance(bob.publicKey);
+
+            await relay(await securePayIx(intentId), 1);
+
+            expect((await balance(bob.publicKey)) - before).to.equal(PAYMENT);
+            const intent = await program.account.intent.fetch(intentPda(alice.publicKey, intentId));
+            expect(intent.amount.toNumber()).to.equal(PAYMENT);
+
+            // A retry of the same intent, as a new transaction, is rejected.
+            try {
+                await relay(await securePayIx(intentId), 2);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.match(/already in use/i);
+            }
+            expect((await balance(bob.publicKey)) - before).to.equal(PAYMENT);
+        });
+
+        it("A new intent with the same parameters still goes through", async () => {
+            const before = await balance(bob.publicKey);
+
+            await relay(await securePayIx(randomBytes(16)), 1);
+
+            expect((await balance(bob.publicKey)) - before).to.equal(PAYMENT);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Relayer Idempotency ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  pay(amount) // nothing identifies the user's intent");
+            console.log("  Risk: a rebuilt retry is a new transaction and executes again\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  pay(intent_id, amount) with #[account(init, seeds = [b\"intent\", wallet, intent_id])]");
+            console.log("  Protection: the first copy creates the intent PDA; later copies fail on init\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  The runtime dedupes signatures, not intents. Give each intent an id and record it on-chain.");
+        });
+    });
+});
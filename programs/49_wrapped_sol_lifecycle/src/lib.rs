@@ -0,0 +1,260 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, spl_token::native_mint, CloseAccount, Mint, SyncNative, Token, TokenAccount, Transfer};
+
+declare_id!("7Yx8SxgpitdxNT2UweuSkAUVycCRxieNzivtZ2MPp13i");
+
+#[program]
+pub mod wrapped_sol_lifecycle {
+    use super::*;
+
+    /// Creates the vault and its wrapped SOL token account.
+    pub fn initialize_vault(ctx: Context<InitializeVault>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.admin = ctx.accounts.admin.key();
+        vault.bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    pub fn open_balance(ctx: Context<OpenBalance>) -> Result<()> {
+        let balance = &mut ctx.accounts.balance;
+        balance.owner = ctx.accounts.owner.key();
+        balance.amount = 0;
+        balance.bump = ctx.bumps.balance;
+        Ok(())
+    }
+
+    // VULNERABLE: Wrap Without sync_native
+    // 1. Lamports are sent straight to the vault's wSOL account
+    // 2. The token program only counts them after `SyncNative`; until then
+    //    `vault_wsol.amount` is unchanged
+    // 3. The depositor is credited anyway, so the ledger claims more wSOL
+    //    than the account can transfer and withdrawals fail
+    pub fn vulnerable_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        wrap(&ctx, amount)?;
+        credit(&mut ctx.accounts.balance, amount)
+    }
+
+    // SECURE: Wrap Then sync_native
+    // The transfer and the sync happen in the same instruction, so the
+    // token amount matches the credited lamports before anything reads it.
+    pub fn secure_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        wrap(&ctx, amount)?;
+        token::sync_native(CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            SyncNative {
+                account: ctx.accounts.vault_wsol.to_account_info(),
+            },
+        ))?;
+        credit(&mut ctx.accounts.balance, amount)
+    }
+
+    // VULNERABLE: Temp Account Closed to the Rent Payer
+    // 1. A temporary wSOL account is created, paid for by `payer` (a relayer
+    //    sponsoring the withdrawal), and filled from the vault
+    // 2. It is closed to `payer` "to refund the rent"
+    // 3. Closing a native account sends *all* its lamports: the rent and the
+    //    unwrapped SOL. The relayer receives the owner's withdrawal
+    pub fn vulnerable_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        ctx.accounts.fill_temp(amount)?;
+        let payer = ctx.accounts.payer.to_account_info();
+        ctx.accounts.close_temp(payer)?;
+        msg!("Vulnerable withdraw: {} lamports unwrapped to the rent payer", amount);
+        Ok(())
+    }
+
+    // SECURE: Create, Fill, Close to Owner, Refund Rent
+    // 1. The temporary account is closed to the owner, who receives the
+    //    unwrapped SOL and the rent
+    // 2. The rent, measured before the close, is then paid back to `payer`
+    // 3. Each party ends with exactly what they are owed
+    pub fn secure_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        ctx.accounts.fill_temp(amount)?;
+
+        let rent = ctx
+            .accounts
+            .temp
+            .to_account_info()
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(WsolError::ArithmeticError)?;
+        let owner = ctx.accounts.owner.to_account_info();
+        ctx.accounts.close_temp(owner)?;
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: ctx.accounts.payer.to_account_info(),
+                },
+            ),
+            rent,
+        )?;
+        msg!("Secure withdraw: {} lamports to the owner, {} rent back to the payer", amount, rent);
+        Ok(())
+    }
+}
+
+/// Sends `amount` lamports from the owner to the vault's wSOL account.
+fn wrap(ctx: &Context<Deposit>, amount: u64) -> Result<()> {
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.owner.to_account_info(),
+                to: ctx.accounts.vault_wsol.to_account_info(),
+            },
+        ),
+        amount,
+    )
+}
+
+fn credit(balance: &mut Balance, amount: u64) -> Result<()> {
+    balance.amount = balance.amount.checked_add(amount).ok_or(WsolError::ArithmeticError)?;
+    Ok(())
+}
+
+impl<'info> Withdraw<'info> {
+    /// Debits the owner's balance and moves `amount` wSOL from the vault
+    /// into the temporary account.
+    fn fill_temp(&mut self, amount: u64) -> Result<()> {
+        self.balance.amount = self
+            .balance
+            .amount
+            .checked_sub(amount)
+            .ok_or(WsolError::InsufficientBalance)?;
+
+        let seeds: &[&[u8]] = &[b"vault", &[self.vault.bump]];
+        let signer = &[seeds];
+        token::transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.vault_wsol.to_account_info(),
+                    to: self.temp.to_account_info(),
+                    authority: self.vault.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )
+    }
+
+    /// Closes the temporary wSOL account, sending every lamport in it to
+    /// `destination`.
+    fn close_temp(&self, destination: AccountInfo<'info>) -> Result<()> {
+        let seeds: &[&[u8]] = &[b"vault", &[self.vault.bump]];
+        let signer = &[seeds];
+        token::close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.temp.to_account_info(),
+                destination,
+                authority: self.vault.to_account_info(),
+            },
+            signer,
+        ))
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeVault<'info> {
+    #[account(init, payer = admin, space = 8 + Vault::INIT_SPACE, seeds = [b"vault"], bump)]
+    pub vault: Account<'info, Vault>,
+    #[account(address = native_mint::ID)]
+    pub native_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"vault_wsol"],
+        bump,
+        token::mint = native_mint,
+        token::authority = vault
+    )]
+    pub vault_wsol: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenBalance<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Balance::INIT_SPACE,
+        seeds = [b"balance", owner.key().as_ref()],
+        bump
+    )]
+    pub balance: Account<'info, Balance>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, seeds = [b"vault_wsol"], bump)]
+    pub vault_wsol: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [b"balance", owner.key().as_ref()], bump = balance.bump, has_one = owner)]
+    pub balance: Account<'info, Balance>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut, seeds = [b"vault_wsol"], bump)]
+    pub vault_wsol: Account<'info, TokenAccount>,
+    #[account(address = native_mint::ID)]
+    pub native_mint: Account<'info, Mint>,
+    #[account(mut, seeds = [b"balance", owner.key().as_ref()], bump = balance.bump, has_one = owner)]
+    pub balance: Account<'info, Balance>,
+    /// Created and closed within the instruction.
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"temp", owner.key().as_ref()],
+        bump,
+        token::mint = native_mint,
+        token::authority = vault
+    )]
+    pub temp: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    /// Pays the temporary account's rent; may be a relayer.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub admin: Pubkey,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Balance {
+    pub owner: Pubkey,
+    /// Lamports of wSOL credited to the owner.
+    pub amount: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum WsolError {
+    #[msg("Insufficient balance")]
+    InsufficientBalance,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
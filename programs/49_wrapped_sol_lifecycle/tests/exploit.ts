@@ -0,0 +1,178 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { NATIVE_MINT, TOKEN_PROGRAM_ID, createSyncNativeInstruction, getAccount } from "@solana/spl-token";
+import { expect } from "chai";
+
+describe("49: Wrapped SOL Lifecycle", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.WrappedSolLifecycle as Program<any>;
+
+    let admin: Keypair;
+    let alice: Keypair;
+    let relayer: Keypair;
+
+    const AMOUNT = LAMPORTS_PER_SOL;
+
+    const vaultPda = PublicKey.findProgramAddressSync([Buffer.from("vault")], program.programId)[0];
+    const vaultWsolPda = PublicKey.findProgramAddressSync([Buffer.from("vault_wsol")], program.programId)[0];
+
+    function balancePda(owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("balance"), owner.toBuffer()], program.programId)[0];
+    }
+
+    function tempPda(owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("temp"), owner.toBuffer()], program.programId)[0];
+    }
+
+    function deposit(method: "vulnerableDeposit" | "secureDeposit", owner: Keypair, amount: number) {
+        return program.methods[method](new anchor.BN(amount))
+            .accounts({
+                vaultWsol: vaultWsolPda,
+                balance: balancePda(owner.publicKey),
+                owner: owner.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc();
+    }
+
+    /** `payer` sponsors the temporary account's rent; the owner signs too. */
+    function withdraw(method: "vulnerableWithdraw" | "secureWithdraw", owner: Keypair, payer: Keypair, amount: number) {
+        return program.methods[method](new anchor.BN(amount))
+            .accounts({
+                vault: vaultPda,
+                vaultWsol: vaultWsolPda,
+                nativeMint: NATIVE_MINT,
+                balance: balancePda(owner.publicKey),
+                temp: tempPda(owner.publicKey),
+                owner: owner.publicKey,
+                payer: payer.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers(owner === payer ? [owner] : [owner, payer])
+            .rpc();
+    }
+
+    async function wrapped(): Promise<number> {
+        return Number((await getAccount(provider.connection, vaultWsolPda)).amount);
+    }
+
+    async function credited(owner: PublicKey): Promise<number> {
+        return (await program.account.balance.fetch(balancePda(owner))).amount.toNumber();
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        alice = Keypair.generate();
+        relayer = Keypair.generate();
+
+        for (const kp of [admin, alice, relayer]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates the vault, its wSOL account, and Alice's balance", async () => {
+            await program.methods
+                .initializeVault()
+                .accounts({
+                    vault: vaultPda,
+                    nativeMint: NATIVE_MINT,
+                    vaultWsol: vaultWsolPda,
+                    admin: admin.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([admin])
+                .rpc();
+
+            await program.methods
+                .openBalance()
+                .accounts({ balance: balancePda(alice.publicKey), owner: alice.publicKey, systemProgram: SystemProgram.programId })
+                .signers([alice])
+                .rpc();
+        });
+    });
+
+    describe("Exploit: unsynced deposits and a misdirected close", () => {
+        it("Credits Alice 1 SOL that the wSOL account does not count", async () => {
+            const lamportsBefore = await provider.connection.getBalance(vaultWsolPda);
+            await deposit("vulnerableDeposit", alice, AMOUNT);
+
+            expect(await credited(alice.publicKey)).to.equal(AMOUNT);
+            expect(await provider.connection.getBalance(vaultWsolPda)).to.equal(lamportsBefore + AMOUNT);
+            expect(await wrapped()).to.equal(0);
+            console.log("  Lamports arrived, token amount still 0: no SyncNative");
+        });
+
+        it("Alice's withdrawal fails until someone syncs the account", async () => {
+            try {
+                await withdraw("secureWithdraw", alice, alice, AMOUNT);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.match(/insufficient funds/i);
+            }
+
+            await provider.sendAndConfirm(new Transaction().add(createSyncNativeInstruction(vaultWsolPda)));
+            expect(await wrapped()).to.equal(AMOUNT);
+        });
+
+        it("A relayer sponsoring the temp account's rent receives Alice's withdrawal", async () => {
+            const aliceBefore = await provider.connection.getBalance(alice.publicKey);
+            const relayerBefore = await provider.connection.getBalance(relayer.publicKey);
+
+            await withdraw("vulnerableWithdraw", alice, relayer, AMOUNT);
+
+            const aliceGain = (await provider.connection.getBalance(alice.publicKey)) - aliceBefore;
+            const relayerGain = (await provider.connection.getBalance(relayer.publicKey)) - relayerBefore;
+            console.log(`  Alice received ${aliceGain / LAMPORTS_PER_SOL} SOL, the relayer ${relayerGain / LAMPORTS_PER_SOL} SOL`);
+            expect(aliceGain).to.equal(0);
+            expect(relayerGain).to.equal(AMOUNT);
+            expect(await credited(alice.publicKey)).to.equal(0);
+        });
+    });
+
+    describe("Secure: create, wrap, use, close", () => {
+        it("Syncs the wSOL account in the same instruction as the deposit", async () => {
+            await deposit("secureDeposit", alice, AMOUNT);
+            expect(await wrapped()).to.equal(AMOUNT);
+            expect(await credited(alice.publicKey)).to.equal(AMOUNT);
+        });
+
+        it("Closes the temp account to Alice and refunds only the rent to the relayer", async () => {
+            const aliceBefore = await provider.connection.getBalance(alice.publicKey);
+            const relayerBefore = await provider.connection.getBalance(relayer.publicKey);
+
+            await withdraw("secureWithdraw", alice, relayer, AMOUNT);
+
+            expect((await provider.connection.getBalance(alice.publicKey)) - aliceBefore).to.equal(AMOUNT);
+            expect(await provider.connection.getBalance(relayer.publicKey)).to.equal(relayerBefore);
+            expect(await provider.connection.getAccountInfo(tempPda(alice.publicKey))).to.be.null;
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Wrapped SOL Lifecycle ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  system transfer into a wSOL account, no sync_native; close temp account to the rent payer");
+            console.log("  Risk: ledger and token amount disagree; the unwrapped SOL goes to whoever paid rent\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  transfer + sync_native together; close to the owner, then refund measured rent to the payer");
+            console.log("  Protection: token amount always matches credits; each party gets exactly what it is owed\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  A native token account's lamports are its balance. Sync after wrapping, and remember that");
+            console.log("  closing it pays out everything, not just the rent.");
+        });
+    });
+});
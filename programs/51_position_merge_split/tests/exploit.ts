@@ -0,0 +1,180 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+
+describe("51: Position Merge/Split Arithmetic", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.PositionMergeSplit as Program<any>;
+
+    let admin: Keypair;
+    let alice: Keypair;
+    let attacker: Keypair;
+
+    // 1 lamport per unit.
+    const MARK = new anchor.BN(1_000_000_000);
+    const TEN_SOL_OF_UNITS = new anchor.BN(10 * LAMPORTS_PER_SOL);
+    const ODD_SIZE = new anchor.BN(1_000_000_001);
+
+    const marketPda = PublicKey.findProgramAddressSync([Buffer.from("market")], program.programId)[0];
+
+    function positionPda(owner: PublicKey, id: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("position"), owner.toBuffer(), new anchor.BN(id).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        )[0];
+    }
+
+    function open(owner: Keypair, id: number, size: anchor.BN) {
+        return program.methods
+            .openPosition(new anchor.BN(id), size)
+            .accounts({
+                market: marketPda,
+                position: positionPda(owner.publicKey, id),
+                owner: owner.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc();
+    }
+
+    function merge(method: "vulnerableMerge" | "secureMerge", owner: Keypair, destination: number, source: number) {
+        return program.methods[method]()
+            .accounts({
+                destination: positionPda(owner.publicKey, destination),
+                source: positionPda(owner.publicKey, source),
+                owner: owner.publicKey,
+            })
+            .signers([owner])
+            .rpc();
+    }
+
+    function split(method: "vulnerableSplit" | "secureSplit", owner: Keypair, source: number, newId: number, shareBps: number) {
+        return program.methods[method](new anchor.BN(newId), new anchor.BN(shareBps))
+            .accounts({
+                source: positionPda(owner.publicKey, source),
+                part: positionPda(owner.publicKey, newId),
+                owner: owner.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc();
+    }
+
+    /** Closes a position and returns the lamports the market paid for it. */
+    async function close(owner: Keypair, id: number): Promise<number> {
+        const before = await provider.connection.getBalance(marketPda);
+        await program.methods
+            .closePosition()
+            .accounts({ market: marketPda, position: positionPda(owner.publicKey, id), owner: owner.publicKey })
+            .signers([owner])
+            .rpc();
+        return before - (await provider.connection.getBalance(marketPda));
+    }
+
+    async function position(owner: PublicKey, id: number) {
+        const p = await program.account.position.fetch(positionPda(owner, id));
+        return { size: p.size.toString(), entryPrice: p.entryPrice.toNumber(), collateral: p.collateral.toNumber() };
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        alice = Keypair.generate();
+        attacker = Keypair.generate();
+
+        for (const kp of [admin, alice, attacker]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 50 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates a market at 1 lamport per unit with 30 SOL of house liquidity", async () => {
+            await program.methods
+                .initializeMarket(MARK)
+                .accounts({ market: marketPda, admin: admin.publicKey, systemProgram: SystemProgram.programId })
+                .signers([admin])
+                .rpc();
+
+            await program.methods
+                .fund(new anchor.BN(30 * LAMPORTS_PER_SOL))
+                .accounts({ market: marketPda, funder: admin.publicKey, systemProgram: SystemProgram.programId })
+                .signers([admin])
+                .rpc();
+        });
+    });
+
+    describe("Exploit: overflow in merge, rounding in split", () => {
+        it("Merging two 10 SOL positions wraps the notional and collapses the entry price", async () => {
+            await open(attacker, 1, TEN_SOL_OF_UNITS);
+            await open(attacker, 2, TEN_SOL_OF_UNITS);
+
+            await merge("vulnerableMerge", attacker, 1, 2);
+
+            const merged = await position(attacker.publicKey, 1);
+            console.log(`  Merged entry price: ${merged.entryPrice} (mark ${MARK.toNumber()})`);
+            expect(merged.entryPrice).to.be.lessThan(MARK.toNumber() / 10);
+
+            const paid = await close(attacker, 1);
+            console.log(`  Deposited 20 SOL at the mark price, closed at the same price for ${paid / LAMPORTS_PER_SOL} SOL`);
+            expect(paid).to.be.greaterThan(38 * LAMPORTS_PER_SOL);
+        });
+
+        it("Splitting an odd position in half rounds both halves up", async () => {
+            await open(attacker, 3, ODD_SIZE);
+            await split("vulnerableSplit", attacker, 3, 4, 5_000);
+
+            const a = await position(attacker.publicKey, 3);
+            const b = await position(attacker.publicKey, 4);
+            console.log(`  ${ODD_SIZE.toString()} lamports of collateral split into ${a.collateral} + ${b.collateral}`);
+            expect(a.collateral + b.collateral).to.equal(ODD_SIZE.toNumber() + 1);
+
+            expect((await close(attacker, 3)) + (await close(attacker, 4))).to.equal(ODD_SIZE.toNumber() + 1);
+        });
+    });
+
+    describe("Secure: u128 math and conservation checks", () => {
+        it("Merging the same two positions keeps the entry at the mark price", async () => {
+            await open(alice, 1, TEN_SOL_OF_UNITS);
+            await open(alice, 2, TEN_SOL_OF_UNITS);
+
+            await merge("secureMerge", alice, 1, 2);
+
+            const merged = await position(alice.publicKey, 1);
+            expect(merged.entryPrice).to.equal(MARK.toNumber());
+            expect(merged.size).to.equal(TEN_SOL_OF_UNITS.muln(2).toString());
+            expect(await close(alice, 1)).to.equal(20 * LAMPORTS_PER_SOL);
+        });
+
+        it("Splitting an odd position gives the remainder to one side", async () => {
+            await open(alice, 3, ODD_SIZE);
+            await split("secureSplit", alice, 3, 4, 5_000);
+
+            const a = await position(alice.publicKey, 3);
+            const b = await position(alice.publicKey, 4);
+            expect(a.collateral + b.collateral).to.equal(ODD_SIZE.toNumber());
+            expect((await close(alice, 3)) + (await close(alice, 4))).to.equal(ODD_SIZE.toNumber());
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Position Merge/Split Arithmetic ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  entry = (a.size * a.entry).wrapping_add(b.size * b.entry) / size   // u64");
+            console.log("  part = ceil(x * share / BPS); rest = ceil(x * (BPS - share) / BPS)");
+            console.log("  Risk: overflow collapses the entry price; rounding mints collateral\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  u128 notional, checked sums, entry rounded up;");
+            console.log("  part = floor(x * share / BPS); rest = x - part; require!(part + rest == x)");
+            console.log("  Protection: merges and splits conserve size and collateral exactly\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Position algebra must conserve value. Compute wide, derive the remainder, and assert the sum.");
+        });
+    });
+});
@@ -0,0 +1,232 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke;
+use anchor_lang::solana_program::{bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable};
+
+declare_id!("8YVk3KrfXNAMzvRyzW9hqBAxfrkpmGx6o8TePs2nbmZ9");
+
+/// Maximum number of approved callback programs.
+pub const MAX_ALLOWED: usize = 4;
+
+/// Programs that own deployed, invocable programs.
+const LOADERS: [Pubkey; 3] = [bpf_loader_upgradeable::ID, bpf_loader::ID, bpf_loader_deprecated::ID];
+
+#[program]
+pub mod missing_executable_check {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.allowlist = Vec::new();
+        config.bump = ctx.bumps.config;
+        Ok(())
+    }
+
+    /// Adds a deployed program to the callback allowlist.
+    pub fn allow_callback(ctx: Context<AllowCallback>) -> Result<()> {
+        let program = ctx.accounts.callback_program.key();
+        let config = &mut ctx.accounts.config;
+        require!(config.allowlist.len() < MAX_ALLOWED, CallbackError::AllowlistFull);
+        require!(!config.allowlist.contains(&program), CallbackError::AlreadyAllowed);
+        config.allowlist.push(program);
+        Ok(())
+    }
+
+    // VULNERABLE: Callback Registered Without Checks
+    // Any pubkey is stored as the subscriber's callback program: a wallet,
+    // a data account, an address with nothing deployed yet.
+    pub fn vulnerable_register(ctx: Context<VulnerableRegister>, callback_program: Pubkey) -> Result<()> {
+        let subscription = &mut ctx.accounts.subscription;
+        subscription.owner = ctx.accounts.owner.key();
+        subscription.callback_program = callback_program;
+        subscription.bump = ctx.bumps.subscription;
+        Ok(())
+    }
+
+    // SECURE: Callback Must Be a Deployed, Approved Program
+    // 1. `executable` must be set on the account
+    // 2. It must be owned by a BPF loader, not a native or data account
+    // 3. Its address must be on the admin-managed allowlist
+    pub fn secure_register(ctx: Context<SecureRegister>) -> Result<()> {
+        if let Some(error) = callback_error(&ctx.accounts.config, &ctx.accounts.callback_program) {
+            return Err(error.into());
+        }
+
+        let subscription = &mut ctx.accounts.subscription;
+        subscription.owner = ctx.accounts.owner.key();
+        subscription.callback_program = ctx.accounts.callback_program.key();
+        subscription.bump = ctx.bumps.subscription;
+        Ok(())
+    }
+
+    // VULNERABLE: Invoke Without an Executable Check
+    // 1. The keeper notifies every subscriber in one batch, passing
+    //    (subscription, callback program) pairs as remaining accounts
+    // 2. Each stored callback is invoked as-is
+    // 3. Invoking a non-executable account fails, and the failure reverts
+    //    the whole batch: one poisoned subscription blocks every
+    //    notification, on every retry
+    pub fn vulnerable_notify<'info>(ctx: Context<'_, '_, 'info, 'info, Notify<'info>>, event_id: u64) -> Result<()> {
+        for pair in ctx.remaining_accounts.chunks(2) {
+            let (subscription, callback) = load_pair(pair)?;
+            invoke_callback(&subscription, callback, event_id)?;
+        }
+        Ok(())
+    }
+
+    // SECURE: Check Before Every Invoke
+    // 1. The same executable, loader and allowlist checks run again before
+    //    each invoke, covering subscriptions registered before the checks
+    //    existed and programs removed from the allowlist since
+    // 2. A callback that fails them is skipped and logged; the rest of the
+    //    batch is still delivered
+    pub fn secure_notify<'info>(ctx: Context<'_, '_, 'info, 'info, Notify<'info>>, event_id: u64) -> Result<()> {
+        for pair in ctx.remaining_accounts.chunks(2) {
+            let (subscription, callback) = load_pair(pair)?;
+            if let Some(error) = callback_error(&ctx.accounts.config, callback) {
+                msg!("Skipping {}: {}", subscription.owner, error);
+                continue;
+            }
+            invoke_callback(&subscription, callback, event_id)?;
+        }
+        Ok(())
+    }
+}
+
+/// Why `program` cannot be used as a callback, if it cannot.
+fn callback_error(config: &Config, program: &AccountInfo) -> Option<CallbackError> {
+    if !program.executable {
+        Some(CallbackError::NotExecutable)
+    } else if !LOADERS.contains(program.owner) {
+        Some(CallbackError::NotLoaderOwned)
+    } else if !config.allowlist.contains(program.key) {
+        Some(CallbackError::CallbackNotAllowed)
+    } else {
+        None
+    }
+}
+
+/// Reads a `[subscription, callback_program]` pair from remaining accounts.
+fn load_pair<'info>(
+    pair: &'info [AccountInfo<'info>],
+) -> Result<(Account<'info, Subscription>, &'info AccountInfo<'info>)> {
+    let [subscription, callback] = pair else {
+        return err!(CallbackError::IncompletePair);
+    };
+    let subscription = Account::<Subscription>::try_from(subscription)?;
+    require_keys_eq!(callback.key(), subscription.callback_program, CallbackError::CallbackMismatch);
+    Ok((subscription, callback))
+}
+
+/// Sends the event to the callback program as UTF-8 instruction data.
+fn invoke_callback(subscription: &Subscription, callback: &AccountInfo, event_id: u64) -> Result<()> {
+    let ix = Instruction {
+        program_id: callback.key(),
+        accounts: vec![],
+        data: format!("event {} for {}", event_id, subscription.owner).into_bytes(),
+    };
+    invoke(&ix, std::slice::from_ref(callback))?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = admin, space = 8 + Config::INIT_SPACE, seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AllowCallback<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump, has_one = admin @ CallbackError::Unauthorized)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+    /// CHECK: only its address is stored; it must be a deployed program.
+    #[account(executable)]
+    pub callback_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableRegister<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Subscription::INIT_SPACE,
+        seeds = [b"subscription", owner.key().as_ref()],
+        bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SecureRegister<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Subscription::INIT_SPACE,
+        seeds = [b"subscription", owner.key().as_ref()],
+        bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+    /// CHECK: validated in the handler by `callback_error`.
+    pub callback_program: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Subscriptions are passed as `remaining_accounts` in
+/// `[subscription, callback_program]` pairs.
+#[derive(Accounts)]
+pub struct Notify<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    pub keeper: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    /// Programs subscribers may register as callbacks.
+    #[max_len(MAX_ALLOWED)]
+    pub allowlist: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Subscription {
+    pub owner: Pubkey,
+    /// Program invoked with each event.
+    pub callback_program: Pubkey,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum CallbackError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Callback account is not executable")]
+    NotExecutable,
+    #[msg("Callback account is not owned by a BPF loader")]
+    NotLoaderOwned,
+    #[msg("Callback program is not on the allowlist")]
+    CallbackNotAllowed,
+    #[msg("Callback allowlist is full")]
+    AllowlistFull,
+    #[msg("Callback program is already allowed")]
+    AlreadyAllowed,
+    #[msg("Callback account does not match the subscription")]
+    CallbackMismatch,
+    #[msg("Subscriptions must be passed as (subscription, callback) pairs")]
+    IncompletePair,
+}
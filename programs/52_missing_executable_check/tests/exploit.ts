@@ -0,0 +1,171 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { AccountMeta, Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("52: Missing Executable Check", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.MissingExecutableCheck as Program<any>;
+
+    // Accepts any UTF-8 instruction data, so it can receive notifications.
+    const MEMO_PROGRAM_ID = new PublicKey("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+    let admin: Keypair;
+    let alice: Keypair;
+    let bob: Keypair;
+    let attacker: Keypair;
+    let mallory: Keypair;
+
+    const configPda = PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId)[0];
+
+    function subscriptionPda(owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("subscription"), owner.toBuffer()], program.programId)[0];
+    }
+
+    function secureRegister(owner: Keypair, callbackProgram: PublicKey) {
+        return program.methods
+            .secureRegister()
+            .accounts({
+                config: configPda,
+                subscription: subscriptionPda(owner.publicKey),
+                callbackProgram,
+                owner: owner.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc();
+    }
+
+    function notify(method: "vulnerableNotify" | "secureNotify", subscribers: { owner: PublicKey; callback: PublicKey }[]) {
+        const remaining: AccountMeta[] = subscribers.flatMap(({ owner, callback }) => [
+            { pubkey: subscriptionPda(owner), isSigner: false, isWritable: false },
+            { pubkey: callback, isSigner: false, isWritable: false },
+        ]);
+        return program.methods[method](new anchor.BN(1))
+            .accounts({ config: configPda, keeper: admin.publicKey })
+            .remainingAccounts(remaining)
+            .signers([admin])
+            .rpc();
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        alice = Keypair.generate();
+        bob = Keypair.generate();
+        attacker = Keypair.generate();
+        mallory = Keypair.generate();
+
+        for (const kp of [admin, alice, bob, attacker, mallory]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 2 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates the config and allowlists the memo program as a callback", async () => {
+            await program.methods
+                .initialize()
+                .accounts({ config: configPda, admin: admin.publicKey, systemProgram: SystemProgram.programId })
+                .signers([admin])
+                .rpc();
+
+            await program.methods
+                .allowCallback()
+                .accounts({ config: configPda, admin: admin.publicKey, callbackProgram: MEMO_PROGRAM_ID })
+                .signers([admin])
+                .rpc();
+        });
+
+        it("Alice and Bob subscribe with the memo program", async () => {
+            await secureRegister(alice, MEMO_PROGRAM_ID);
+            await secureRegister(bob, MEMO_PROGRAM_ID);
+        });
+    });
+
+    describe("Exploit: a non-executable callback poisons the batch", () => {
+        it("The attacker registers their own wallet as a callback program", async () => {
+            await program.methods
+                .vulnerableRegister(attacker.publicKey)
+                .accounts({
+                    subscription: subscriptionPda(attacker.publicKey),
+                    owner: attacker.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([attacker])
+                .rpc();
+
+            const info = await provider.connection.getAccountInfo(attacker.publicKey);
+            console.log(`  Registered callback executable: ${info!.executable}, owner: ${info!.owner.toBase58()}`);
+        });
+
+        it("The keeper's batch reverts, so Alice and Bob are never notified", async () => {
+            const subscribers = [
+                { owner: alice.publicKey, callback: MEMO_PROGRAM_ID },
+                { owner: attacker.publicKey, callback: attacker.publicKey },
+                { owner: bob.publicKey, callback: MEMO_PROGRAM_ID },
+            ];
+
+            try {
+                await notify("vulnerableNotify", subscribers);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.not.include("Should have thrown");
+                console.log("  Batch reverted on the wallet 'program'; retrying fails the same way");
+            }
+        });
+    });
+
+    describe("Secure: executable, loader and allowlist checks", () => {
+        it("Rejects a wallet: not executable", async () => {
+            await expectError(secureRegister(mallory, mallory.publicKey), "NotExecutable");
+        });
+
+        it("Rejects a native program: not owned by a BPF loader", async () => {
+            await expectError(secureRegister(mallory, SystemProgram.programId), "NotLoaderOwned");
+        });
+
+        it("Rejects a deployed program that is not on the allowlist", async () => {
+            await expectError(secureRegister(mallory, TOKEN_PROGRAM_ID), "CallbackNotAllowed");
+        });
+
+        it("Skips the poisoned subscription and still notifies Alice and Bob", async () => {
+            const sig = await notify("secureNotify", [
+                { owner: alice.publicKey, callback: MEMO_PROGRAM_ID },
+                { owner: attacker.publicKey, callback: attacker.publicKey },
+                { owner: bob.publicKey, callback: MEMO_PROGRAM_ID },
+            ]);
+
+            await provider.connection.confirmTransaction(sig, "confirmed");
+            const tx = await provider.connection.getTransaction(sig, { commitment: "confirmed" });
+            const logs = tx!.meta!.logMessages!.join("\n");
+
+            expect(logs).to.include(`Skipping ${attacker.publicKey.toBase58()}`);
+            expect(logs).to.include(`event 1 for ${alice.publicKey.toBase58()}`);
+            expect(logs).to.include(`event 1 for ${bob.publicKey.toBase58()}`);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Missing Executable Check ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  subscription.callback_program = any_pubkey;");
+            console.log("  invoke(&Instruction { program_id: callback.key(), .. }, ..)?;");
+            console.log("  Risk: a wallet or data account as 'program' reverts every batch it is in\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  require!(callback.executable); require!(LOADERS.contains(callback.owner));");
+            console.log("  require!(config.allowlist.contains(callback.key));  // at register and before invoke");
+            console.log("  Protection: only deployed, approved programs are stored or invoked\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  A pubkey is not a program. Check it is executable, loader-owned and approved before you call it.");
+        });
+    });
+});
@@ -0,0 +1,420 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use security_common::time::{ClockExt, UnixTs};
+
+declare_id!("G7FXzrQ14PaJfF6W1u6o4JTEppJG53uSKT1Jfq8Pw6YX");
+
+/// Maximum number of guardians per wallet.
+pub const MAX_GUARDIANS: usize = 5;
+
+#[program]
+pub mod guardian_recovery {
+    use super::*;
+
+    /// Creates a wallet holding `funding` lamports, recoverable by
+    /// `threshold` of `guardians`. Proposals stay open for
+    /// `approval_window_secs`.
+    pub fn create_wallet(
+        ctx: Context<CreateWallet>,
+        wallet_id: u64,
+        guardians: Vec<Pubkey>,
+        threshold: u8,
+        approval_window_secs: i64,
+        funding: u64,
+    ) -> Result<()> {
+        validate_guardian_set(&guardians, threshold)?;
+        require!(approval_window_secs > 0, RecoveryError::InvalidWindow);
+
+        let wallet = &mut ctx.accounts.wallet;
+        wallet.wallet_id = wallet_id;
+        wallet.owner = ctx.accounts.owner.key();
+        wallet.guardians = guardians;
+        wallet.threshold = threshold;
+        wallet.guardian_set_version = 0;
+        wallet.approval_window_secs = approval_window_secs;
+        wallet.proposal_count = 0;
+        wallet.bump = ctx.bumps.wallet;
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.owner.to_account_info(),
+            to: ctx.accounts.wallet.to_account_info(),
+        };
+        transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts), funding)
+    }
+
+    /// Owner-only withdrawal. Whoever controls `owner` controls the funds,
+    /// which is what recovery hands over.
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        move_lamports(
+            &ctx.accounts.wallet.to_account_info(),
+            &ctx.accounts.owner.to_account_info(),
+            amount,
+        )
+    }
+
+    // VULNERABLE: Guardian Set Update Without a Threshold
+    // 1. Any single guardian can replace the whole guardian set and the
+    //    threshold in one call
+    // 2. A compromised or malicious guardian installs themselves as the
+    //    only guardian with a threshold of 1
+    // 3. Recovery below checks the threshold correctly, but against the
+    //    set the attacker just wrote
+    pub fn vulnerable_set_guardians(
+        ctx: Context<VulnerableSetGuardians>,
+        guardians: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        validate_guardian_set(&guardians, threshold)?;
+
+        let wallet = &mut ctx.accounts.wallet;
+        wallet.guardians = guardians;
+        wallet.threshold = threshold;
+        msg!("Vulnerable update: {} guardians, threshold {}", wallet.guardians.len(), threshold);
+        Ok(())
+    }
+
+    /// Hands the wallet to `new_owner` when `threshold` distinct guardians
+    /// sign, passed as signer `remaining_accounts`.
+    pub fn vulnerable_recover(ctx: Context<VulnerableRecover>, new_owner: Pubkey) -> Result<()> {
+        let wallet = &mut ctx.accounts.wallet;
+        let signers = count_guardian_signers(wallet, ctx.remaining_accounts);
+        require!(signers >= wallet.threshold as usize, RecoveryError::ThresholdNotMet);
+
+        wallet.owner = new_owner;
+        msg!("Vulnerable recover: {} guardian(s) moved the wallet to {}", signers, new_owner);
+        Ok(())
+    }
+
+    // SECURE: M-of-N Proposals With Expiry
+    // 1. Guardian set changes and recoveries are both proposals; nothing
+    //    changes the wallet without `threshold` approvals
+    // 2. Each guardian approves once, through an `Approval` PDA seeded by
+    //    (proposal, guardian)
+    // 3. Proposals expire after the wallet's approval window
+    // 4. Proposals are bound to the guardian set version they were created
+    //    under; executing a set change bumps the version, so approvals
+    //    collected under the old set cannot be used afterwards
+    pub fn secure_propose(ctx: Context<SecurePropose>, action: RecoveryAction) -> Result<()> {
+        if let RecoveryAction::ReplaceGuardians { guardians, threshold } = &action {
+            validate_guardian_set(guardians, *threshold)?;
+        }
+
+        let now = Clock::get()?.current_unix_ts();
+        let wallet = &mut ctx.accounts.wallet;
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.wallet = wallet.key();
+        proposal.proposal_id = wallet.proposal_count;
+        proposal.action = action;
+        proposal.guardian_set_version = wallet.guardian_set_version;
+        proposal.expires_at = now
+            .checked_add_secs(wallet.approval_window_secs)
+            .ok_or(RecoveryError::ArithmeticError)?
+            .0;
+        proposal.approvals = 0;
+        proposal.executed = false;
+        proposal.bump = ctx.bumps.proposal;
+
+        wallet.proposal_count = wallet
+            .proposal_count
+            .checked_add(1)
+            .ok_or(RecoveryError::ArithmeticError)?;
+        Ok(())
+    }
+
+    pub fn secure_approve(ctx: Context<SecureApprove>) -> Result<()> {
+        let now = Clock::get()?.current_unix_ts();
+        let wallet = &ctx.accounts.wallet;
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.check_open(wallet, now)?;
+
+        proposal.approvals = proposal.approvals.checked_add(1).ok_or(RecoveryError::ArithmeticError)?;
+
+        let approval = &mut ctx.accounts.approval;
+        approval.proposal = proposal.key();
+        approval.guardian = ctx.accounts.guardian.key();
+        approval.bump = ctx.bumps.approval;
+        Ok(())
+    }
+
+    pub fn secure_execute(ctx: Context<SecureExecute>) -> Result<()> {
+        let now = Clock::get()?.current_unix_ts();
+        let wallet = &mut ctx.accounts.wallet;
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.check_open(wallet, now)?;
+        require!(proposal.approvals >= wallet.threshold, RecoveryError::ThresholdNotMet);
+
+        proposal.executed = true;
+        match proposal.action.clone() {
+            RecoveryAction::ReplaceGuardians { guardians, threshold } => {
+                wallet.guardians = guardians;
+                wallet.threshold = threshold;
+                wallet.guardian_set_version = wallet
+                    .guardian_set_version
+                    .checked_add(1)
+                    .ok_or(RecoveryError::ArithmeticError)?;
+                msg!("Secure execute: guardian set replaced, threshold {}", threshold);
+            }
+            RecoveryAction::Recover { new_owner } => {
+                wallet.owner = new_owner;
+                msg!("Secure execute: wallet recovered to {}", new_owner);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// At most `MAX_GUARDIANS` distinct guardians and `1 <= threshold <= len`.
+fn validate_guardian_set(guardians: &[Pubkey], threshold: u8) -> Result<()> {
+    require!(guardians.len() <= MAX_GUARDIANS, RecoveryError::TooManyGuardians);
+    require!(
+        threshold > 0 && threshold as usize <= guardians.len(),
+        RecoveryError::InvalidThreshold
+    );
+    for (i, guardian) in guardians.iter().enumerate() {
+        require!(!guardians[..i].contains(guardian), RecoveryError::DuplicateGuardian);
+    }
+    Ok(())
+}
+
+/// Distinct current guardians among the signing `accounts`.
+fn count_guardian_signers(wallet: &RecoverableWallet, accounts: &[AccountInfo]) -> usize {
+    let mut seen: Vec<Pubkey> = Vec::new();
+    for info in accounts {
+        if info.is_signer && wallet.guardians.contains(info.key) && !seen.contains(info.key) {
+            seen.push(*info.key);
+        }
+    }
+    seen.len()
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(RecoveryError::InsufficientFunds)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(RecoveryError::ArithmeticError)?;
+    Ok(())
+}
+
+impl Proposal {
+    /// Not executed, not expired, and created under the current guardian set.
+    fn check_open(&self, wallet: &RecoverableWallet, now: UnixTs) -> Result<()> {
+        require!(!self.executed, RecoveryError::AlreadyExecuted);
+        require!(now < UnixTs(self.expires_at), RecoveryError::ProposalExpired);
+        require!(
+            self.guardian_set_version == wallet.guardian_set_version,
+            RecoveryError::GuardianSetChanged
+        );
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(wallet_id: u64)]
+pub struct CreateWallet<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + RecoverableWallet::INIT_SPACE,
+        seeds = [b"wallet", wallet_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub wallet: Account<'info, RecoverableWallet>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"wallet", wallet.wallet_id.to_le_bytes().as_ref()],
+        bump = wallet.bump,
+        has_one = owner @ RecoveryError::Unauthorized
+    )]
+    pub wallet: Account<'info, RecoverableWallet>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableSetGuardians<'info> {
+    #[account(
+        mut,
+        seeds = [b"wallet", wallet.wallet_id.to_le_bytes().as_ref()],
+        bump = wallet.bump,
+        constraint = wallet.guardians.contains(&guardian.key()) @ RecoveryError::NotAGuardian
+    )]
+    pub wallet: Account<'info, RecoverableWallet>,
+    pub guardian: Signer<'info>,
+}
+
+/// Guardian signatures are passed as signer `remaining_accounts`.
+#[derive(Accounts)]
+pub struct VulnerableRecover<'info> {
+    #[account(mut, seeds = [b"wallet", wallet.wallet_id.to_le_bytes().as_ref()], bump = wallet.bump)]
+    pub wallet: Account<'info, RecoverableWallet>,
+}
+
+#[derive(Accounts)]
+pub struct SecurePropose<'info> {
+    #[account(
+        mut,
+        seeds = [b"wallet", wallet.wallet_id.to_le_bytes().as_ref()],
+        bump = wallet.bump,
+        constraint = wallet.guardians.contains(&guardian.key()) @ RecoveryError::NotAGuardian
+    )]
+    pub wallet: Account<'info, RecoverableWallet>,
+    #[account(
+        init,
+        payer = guardian,
+        space = 8 + Proposal::INIT_SPACE,
+        seeds = [b"proposal", wallet.key().as_ref(), wallet.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(mut)]
+    pub guardian: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SecureApprove<'info> {
+    #[account(
+        seeds = [b"wallet", wallet.wallet_id.to_le_bytes().as_ref()],
+        bump = wallet.bump,
+        constraint = wallet.guardians.contains(&guardian.key()) @ RecoveryError::NotAGuardian
+    )]
+    pub wallet: Account<'info, RecoverableWallet>,
+    #[account(
+        mut,
+        seeds = [b"proposal", wallet.key().as_ref(), proposal.proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        has_one = wallet
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(
+        init,
+        payer = guardian,
+        space = 8 + Approval::INIT_SPACE,
+        seeds = [b"approval", proposal.key().as_ref(), guardian.key().as_ref()],
+        bump
+    )]
+    pub approval: Account<'info, Approval>,
+    #[account(mut)]
+    pub guardian: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SecureExecute<'info> {
+    #[account(mut, seeds = [b"wallet", wallet.wallet_id.to_le_bytes().as_ref()], bump = wallet.bump)]
+    pub wallet: Account<'info, RecoverableWallet>,
+    #[account(
+        mut,
+        seeds = [b"proposal", wallet.key().as_ref(), proposal.proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        has_one = wallet
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RecoverableWallet {
+    pub wallet_id: u64,
+    pub owner: Pubkey,
+    #[max_len(MAX_GUARDIANS)]
+    pub guardians: Vec<Pubkey>,
+    /// Guardian approvals needed to change the set or recover the wallet.
+    pub threshold: u8,
+    /// Incremented each time the guardian set is replaced.
+    pub guardian_set_version: u64,
+    pub approval_window_secs: i64,
+    pub proposal_count: u64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub enum RecoveryAction {
+    ReplaceGuardians {
+        #[max_len(MAX_GUARDIANS)]
+        guardians: Vec<Pubkey>,
+        threshold: u8,
+    },
+    Recover {
+        new_owner: Pubkey,
+    },
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Proposal {
+    pub wallet: Pubkey,
+    pub proposal_id: u64,
+    pub action: RecoveryAction,
+    /// `wallet.guardian_set_version` when the proposal was created.
+    pub guardian_set_version: u64,
+    /// Unix timestamp after which the proposal can no longer be approved or executed.
+    pub expires_at: i64,
+    pub approvals: u8,
+    pub executed: bool,
+    pub bump: u8,
+}
+
+/// One per (proposal, guardian); its existence is the approval.
+#[account]
+#[derive(InitSpace)]
+pub struct Approval {
+    pub proposal: Pubkey,
+    pub guardian: Pubkey,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum RecoveryError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Signer is not a guardian of this wallet")]
+    NotAGuardian,
+    #[msg("Too many guardians")]
+    TooManyGuardians,
+    #[msg("Threshold must be between 1 and the number of guardians")]
+    InvalidThreshold,
+    #[msg("Guardian listed twice")]
+    DuplicateGuardian,
+    #[msg("Approval window must be positive")]
+    InvalidWindow,
+    #[msg("Not enough guardian approvals")]
+    ThresholdNotMet,
+    #[msg("Proposal has expired")]
+    ProposalExpired,
+    #[msg("Guardian set changed since the proposal was created")]
+    GuardianSetChanged,
+    #[msg("Proposal already executed")]
+    AlreadyExecuted,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guardian_set_validation() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert!(validate_guardian_set(&[a, b], 2).is_ok());
+        assert!(validate_guardian_set(&[a, b], 0).is_err());
+        assert!(validate_guardian_set(&[a, b], 3).is_err());
+        assert!(validate_guardian_set(&[a, a], 1).is_err());
+        assert!(validate_guardian_set(&[Pubkey::new_unique(); MAX_GUARDIANS + 1], 1).is_err());
+    }
+}
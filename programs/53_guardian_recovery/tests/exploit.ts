@@ -0,0 +1,206 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { expectError, sleep } from "../../../tests/utils";
+
+describe("53: Guardian Recovery Takeover", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.GuardianRecovery as Program<any>;
+
+    let alice: Keypair;
+    let aliceNewKey: Keypair;
+    let guardian1: Keypair;
+    let guardian2: Keypair;
+    let mallory: Keypair;
+
+    const VULNERABLE_WALLET = 1;
+    const SECURE_WALLET = 2;
+    const WINDOW_SECS = 8;
+    const FUNDING = 5 * LAMPORTS_PER_SOL;
+
+    function walletPda(walletId: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("wallet"), new anchor.BN(walletId).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        )[0];
+    }
+
+    function proposalPda(wallet: PublicKey, proposalId: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("proposal"), wallet.toBuffer(), new anchor.BN(proposalId).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        )[0];
+    }
+
+    function approvalPda(proposal: PublicKey, guardian: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("approval"), proposal.toBuffer(), guardian.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function createWallet(walletId: number) {
+        return program.methods
+            .createWallet(
+                new anchor.BN(walletId),
+                [guardian1.publicKey, guardian2.publicKey, mallory.publicKey],
+                2,
+                new anchor.BN(WINDOW_SECS),
+                new anchor.BN(FUNDING)
+            )
+            .accounts({ wallet: walletPda(walletId), owner: alice.publicKey, systemProgram: SystemProgram.programId })
+            .signers([alice])
+            .rpc();
+    }
+
+    /** Creates a proposal on the secure wallet and returns its address. */
+    async function propose(guardian: Keypair, action: object): Promise<PublicKey> {
+        const wallet = walletPda(SECURE_WALLET);
+        const { proposalCount } = await program.account.recoverableWallet.fetch(wallet);
+        const proposal = proposalPda(wallet, proposalCount.toNumber());
+        await program.methods
+            .securePropose(action)
+            .accounts({ wallet, proposal, guardian: guardian.publicKey, systemProgram: SystemProgram.programId })
+            .signers([guardian])
+            .rpc();
+        return proposal;
+    }
+
+    function approve(guardian: Keypair, proposal: PublicKey) {
+        return program.methods
+            .secureApprove()
+            .accounts({
+                wallet: walletPda(SECURE_WALLET),
+                proposal,
+                approval: approvalPda(proposal, guardian.publicKey),
+                guardian: guardian.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([guardian])
+            .rpc();
+    }
+
+    function execute(proposal: PublicKey) {
+        return program.methods.secureExecute().accounts({ wallet: walletPda(SECURE_WALLET), proposal }).rpc();
+    }
+
+    function withdraw(walletId: number, owner: Keypair) {
+        return program.methods
+            .withdraw(new anchor.BN(FUNDING))
+            .accounts({ wallet: walletPda(walletId), owner: owner.publicKey })
+            .signers([owner])
+            .rpc();
+    }
+
+    before(async () => {
+        alice = Keypair.generate();
+        aliceNewKey = Keypair.generate();
+        guardian1 = Keypair.generate();
+        guardian2 = Keypair.generate();
+        mallory = Keypair.generate();
+
+        for (const kp of [alice, aliceNewKey, guardian1, guardian2, mallory]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 15 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Alice creates two 5 SOL wallets guarded 2-of-3 by two friends and Mallory", async () => {
+            await createWallet(VULNERABLE_WALLET);
+            await createWallet(SECURE_WALLET);
+        });
+    });
+
+    describe("Exploit: one guardian rewrites the guardian set", () => {
+        it("Mallory replaces all three guardians with just themselves, threshold 1", async () => {
+            await program.methods
+                .vulnerableSetGuardians([mallory.publicKey], 1)
+                .accounts({ wallet: walletPda(VULNERABLE_WALLET), guardian: mallory.publicKey })
+                .signers([mallory])
+                .rpc();
+
+            const wallet = await program.account.recoverableWallet.fetch(walletPda(VULNERABLE_WALLET));
+            expect(wallet.guardians.map((g: PublicKey) => g.toBase58())).to.deep.equal([mallory.publicKey.toBase58()]);
+            expect(wallet.threshold).to.equal(1);
+        });
+
+        it("Then 'recovers' the wallet alone and empties it", async () => {
+            await program.methods
+                .vulnerableRecover(mallory.publicKey)
+                .accounts({ wallet: walletPda(VULNERABLE_WALLET) })
+                .remainingAccounts([{ pubkey: mallory.publicKey, isSigner: true, isWritable: false }])
+                .signers([mallory])
+                .rpc();
+
+            const before = await provider.connection.getBalance(mallory.publicKey);
+            await withdraw(VULNERABLE_WALLET, mallory);
+            const stolen = (await provider.connection.getBalance(mallory.publicKey)) - before;
+
+            console.log(`  Mallory took ${stolen / LAMPORTS_PER_SOL} SOL with one of three guardian signatures`);
+            expect(stolen).to.be.greaterThan(FUNDING - 0.01 * LAMPORTS_PER_SOL);
+        });
+    });
+
+    describe("Secure: M-of-N approval PDAs with expiry", () => {
+        let malloryProposal: PublicKey;
+
+        it("A guardian set change proposed and approved by Mallory alone cannot execute", async () => {
+            malloryProposal = await propose(mallory, {
+                replaceGuardians: { guardians: [mallory.publicKey], threshold: 1 },
+            });
+            await approve(mallory, malloryProposal);
+
+            await expectError(execute(malloryProposal), "ThresholdNotMet");
+        });
+
+        it("Mallory cannot approve the same proposal twice", async () => {
+            try {
+                await approve(mallory, malloryProposal);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.include("already in use");
+            }
+        });
+
+        it("The proposal expires after the approval window", async () => {
+            await sleep(WINDOW_SECS + 1);
+            await expectError(execute(malloryProposal), "ProposalExpired");
+        });
+
+        it("Two honest guardians recover Alice's wallet to Alice's new key", async () => {
+            const proposal = await propose(guardian1, { recover: { newOwner: aliceNewKey.publicKey } });
+            await approve(guardian1, proposal);
+            await approve(guardian2, proposal);
+            await execute(proposal);
+
+            const wallet = await program.account.recoverableWallet.fetch(walletPda(SECURE_WALLET));
+            expect(wallet.owner.toBase58()).to.equal(aliceNewKey.publicKey.toBase58());
+
+            await withdraw(SECURE_WALLET, aliceNewKey);
+            await expectError(execute(proposal), "AlreadyExecuted");
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Guardian Recovery Takeover ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  set_guardians: require!(wallet.guardians.contains(signer)); wallet.guardians = new;");
+            console.log("  Risk: one guardian rewrites the set, then meets the threshold alone\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  propose -> approve (one Approval PDA per guardian) -> execute");
+            console.log("  require!(approvals >= threshold && now < expires_at && version == wallet.version)");
+            console.log("  Protection: every change to the set or owner needs M of N current guardians\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  The instruction that changes who may approve must itself need the approvals.");
+        });
+    });
+});
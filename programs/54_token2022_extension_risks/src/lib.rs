@@ -0,0 +1,265 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+declare_id!("CwrRzCHgqkciur4kVb81TY5MgtxVYVEQbm13AiQg4Wo4");
+
+#[program]
+pub mod token2022_extension_risks {
+    use super::*;
+
+    // VULNERABLE: Any Token-2022 Mint Accepted
+    // 1. A pool and vault are created for whatever mint is passed in
+    // 2. A mint with a `PermanentDelegate` lets its delegate transfer or
+    //    burn tokens out of *any* account of that mint, the pool's vault
+    //    included, without the pool's signature
+    // 3. A `ConfidentialTransferMint` lets balances move in encrypted form
+    //    the program cannot read; `vault.amount` no longer describes what
+    //    the vault holds
+    // 4. Depositors are credited against a vault someone else controls
+    pub fn vulnerable_register_mint(ctx: Context<RegisterMint>) -> Result<()> {
+        ctx.accounts.init_pool(ctx.bumps.pool);
+        Ok(())
+    }
+
+    // SECURE: Inspect the Mint's Extensions First
+    // 1. Unpack the mint with `StateWithExtensions` and list its extensions
+    // 2. Accept only extensions the pool's accounting handles. Transfer
+    //    fees are allowed because deposits credit what the vault received;
+    //    metadata and interest-bearing display are inert
+    // 3. Reject permanent delegates, confidential transfers and everything
+    //    else, including extension types added after this was written
+    pub fn secure_register_mint(ctx: Context<RegisterMint>) -> Result<()> {
+        check_mint_extensions(&ctx.accounts.mint)?;
+        ctx.accounts.init_pool(ctx.bumps.pool);
+        Ok(())
+    }
+
+    pub fn open_balance(ctx: Context<OpenBalance>) -> Result<()> {
+        let balance = &mut ctx.accounts.balance;
+        balance.pool = ctx.accounts.pool.key();
+        balance.owner = ctx.accounts.owner.key();
+        balance.amount = 0;
+        balance.bump = ctx.bumps.balance;
+        Ok(())
+    }
+
+    /// Credits the amount that reached the vault, net of any transfer fee.
+    pub fn deposit(ctx: Context<PoolTransfer>, amount: u64) -> Result<()> {
+        let before = ctx.accounts.vault.amount;
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+        ctx.accounts.vault.reload()?;
+        let received = ctx
+            .accounts
+            .vault
+            .amount
+            .checked_sub(before)
+            .ok_or(VaultError::ArithmeticError)?;
+
+        let balance = &mut ctx.accounts.balance;
+        balance.amount = balance.amount.checked_add(received).ok_or(VaultError::ArithmeticError)?;
+        msg!("Deposit: sent {}, credited {}", amount, received);
+        Ok(())
+    }
+
+    pub fn withdraw(ctx: Context<PoolTransfer>, amount: u64) -> Result<()> {
+        let balance = &mut ctx.accounts.balance;
+        balance.amount = balance.amount.checked_sub(amount).ok_or(VaultError::InsufficientBalance)?;
+
+        let mint_key = ctx.accounts.mint.key();
+        let seeds: &[&[u8]] = &[b"pool", mint_key.as_ref(), &[ctx.accounts.pool.bump]];
+        let signer = &[seeds];
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+            ctx.accounts.mint.decimals,
+        )
+    }
+}
+
+/// Fails on any mint extension the pool does not explicitly support.
+fn check_mint_extensions(mint: &InterfaceAccount<Mint>) -> Result<()> {
+    let info = mint.to_account_info();
+    if *info.owner != spl_token_2022::ID {
+        // Legacy SPL Token mints have no extensions.
+        return Ok(());
+    }
+    let data = info.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    for extension in state.get_extension_types()? {
+        if let Some(error) = extension_error(extension) {
+            msg!("Rejected mint extension: {:?}", extension);
+            return Err(error.into());
+        }
+    }
+    Ok(())
+}
+
+/// Why a mint with `extension` cannot back a pool, if it cannot.
+fn extension_error(extension: ExtensionType) -> Option<VaultError> {
+    match extension {
+        ExtensionType::TransferFeeConfig
+        | ExtensionType::MetadataPointer
+        | ExtensionType::TokenMetadata
+        | ExtensionType::InterestBearingConfig => None,
+        ExtensionType::PermanentDelegate => Some(VaultError::PermanentDelegate),
+        ExtensionType::ConfidentialTransferMint | ExtensionType::ConfidentialTransferFeeConfig => {
+            Some(VaultError::ConfidentialTransfers)
+        }
+        _ => Some(VaultError::UnsupportedExtension),
+    }
+}
+
+impl<'info> RegisterMint<'info> {
+    fn init_pool(&mut self, bump: u8) {
+        self.pool.mint = self.mint.key();
+        self.pool.vault = self.vault.key();
+        self.pool.bump = bump;
+    }
+}
+
+#[derive(Accounts)]
+pub struct RegisterMint<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Pool::INIT_SPACE,
+        seeds = [b"pool", mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = pool,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenBalance<'info> {
+    #[account(seeds = [b"pool", pool.mint.as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Balance::INIT_SPACE,
+        seeds = [b"balance", pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub balance: Account<'info, Balance>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PoolTransfer<'info> {
+    #[account(seeds = [b"pool", mint.key().as_ref()], bump = pool.bump, has_one = mint, has_one = vault)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"balance", pool.key().as_ref(), owner.key().as_ref()],
+        bump = balance.bump
+    )]
+    pub balance: Account<'info, Balance>,
+    #[account(mut, token::mint = mint, token::authority = owner)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Balance {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum VaultError {
+    #[msg("Mint has a permanent delegate that can move tokens out of the vault")]
+    PermanentDelegate,
+    #[msg("Mint supports confidential transfers the pool cannot account for")]
+    ConfidentialTransfers,
+    #[msg("Mint has an extension the pool does not support")]
+    UnsupportedExtension,
+    #[msg("Insufficient balance")]
+    InsufficientBalance,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_supported_extensions_pass() {
+        assert!(extension_error(ExtensionType::TransferFeeConfig).is_none());
+        assert!(extension_error(ExtensionType::TokenMetadata).is_none());
+        assert!(matches!(
+            extension_error(ExtensionType::PermanentDelegate),
+            Some(VaultError::PermanentDelegate)
+        ));
+        assert!(matches!(
+            extension_error(ExtensionType::ConfidentialTransferMint),
+            Some(VaultError::ConfidentialTransfers)
+        ));
+        for extension in [
+            ExtensionType::MintCloseAuthority,
+            ExtensionType::DefaultAccountState,
+            ExtensionType::NonTransferable,
+            ExtensionType::TransferHook,
+        ] {
+            assert!(matches!(extension_error(extension), Some(VaultError::UnsupportedExtension)));
+        }
+    }
+}
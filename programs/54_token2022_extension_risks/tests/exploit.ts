@@ -0,0 +1,266 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+    Keypair,
+    LAMPORTS_PER_SOL,
+    PublicKey,
+    SystemProgram,
+    Transaction,
+    TransactionInstruction,
+    sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import {
+    ExtensionType,
+    TOKEN_2022_PROGRAM_ID,
+    createAccount,
+    createInitializeMintInstruction,
+    createInitializePermanentDelegateInstruction,
+    getAccount,
+    getMintLen,
+    mintTo,
+    transferChecked,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("54: Token-2022 Extension Risks", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.Token2022ExtensionRisks as Program<any>;
+
+    let admin: Keypair;
+    let issuer: Keypair;
+    let alice: Keypair;
+
+    let delegateMint: PublicKey;
+    const tokenAccounts = new Map<string, PublicKey>();
+
+    const DECIMALS = 6;
+    const DEPOSIT = 1_000_000_000;
+
+    // Base account (165) + account type (1) + TLV header (4) + ConfidentialTransferMint (65).
+    const CONFIDENTIAL_MINT_LEN = 165 + 1 + 4 + 65;
+
+    function poolPda(mint: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("pool"), mint.toBuffer()], program.programId)[0];
+    }
+
+    function vaultPda(mint: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("vault"), poolPda(mint).toBuffer()], program.programId)[0];
+    }
+
+    function balancePda(mint: PublicKey, owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("balance"), poolPda(mint).toBuffer(), owner.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    /** Creates a Token-2022 mint with `extensionIxs` run before `InitializeMint`. */
+    async function createMint(space: number, extensionIxs: (mint: PublicKey) => TransactionInstruction[]) {
+        const mint = Keypair.generate();
+        const tx = new Transaction().add(
+            SystemProgram.createAccount({
+                fromPubkey: issuer.publicKey,
+                newAccountPubkey: mint.publicKey,
+                space,
+                lamports: await provider.connection.getMinimumBalanceForRentExemption(space),
+                programId: TOKEN_2022_PROGRAM_ID,
+            }),
+            ...extensionIxs(mint.publicKey),
+            createInitializeMintInstruction(mint.publicKey, DECIMALS, issuer.publicKey, null, TOKEN_2022_PROGRAM_ID)
+        );
+        await sendAndConfirmTransaction(provider.connection, tx, [issuer, mint]);
+        return mint.publicKey;
+    }
+
+    function createPermanentDelegateMint() {
+        return createMint(getMintLen([ExtensionType.PermanentDelegate]), (mint) => [
+            createInitializePermanentDelegateInstruction(mint, issuer.publicKey, TOKEN_2022_PROGRAM_ID),
+        ]);
+    }
+
+    // ConfidentialTransferExtension (27) / InitializeMint (0): no authority,
+    // auto-approve new accounts, no auditor.
+    function createConfidentialMint() {
+        return createMint(CONFIDENTIAL_MINT_LEN, (mint) => [
+            new TransactionInstruction({
+                programId: TOKEN_2022_PROGRAM_ID,
+                keys: [{ pubkey: mint, isSigner: false, isWritable: true }],
+                data: Buffer.concat([Buffer.from([27, 0]), Buffer.alloc(32), Buffer.from([1]), Buffer.alloc(32)]),
+            }),
+        ]);
+    }
+
+    async function tokenAccount(mint: PublicKey, owner: PublicKey): Promise<PublicKey> {
+        const key = `${mint.toBase58()}:${owner.toBase58()}`;
+        if (!tokenAccounts.has(key)) {
+            tokenAccounts.set(
+                key,
+                await createAccount(provider.connection, admin, mint, owner, undefined, undefined, TOKEN_2022_PROGRAM_ID)
+            );
+        }
+        return tokenAccounts.get(key)!;
+    }
+
+    function register(method: "vulnerableRegisterMint" | "secureRegisterMint", mint: PublicKey) {
+        return program.methods[method]()
+            .accounts({
+                pool: poolPda(mint),
+                vault: vaultPda(mint),
+                mint,
+                payer: admin.publicKey,
+                tokenProgram: TOKEN_2022_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([admin])
+            .rpc();
+    }
+
+    async function transfer(method: "deposit" | "withdraw", mint: PublicKey, owner: Keypair, amount: number) {
+        return program.methods[method](new anchor.BN(amount))
+            .accounts({
+                pool: poolPda(mint),
+                vault: vaultPda(mint),
+                mint,
+                balance: balancePda(mint, owner.publicKey),
+                userTokenAccount: await tokenAccount(mint, owner.publicKey),
+                owner: owner.publicKey,
+                tokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .signers([owner])
+            .rpc();
+    }
+
+    /** Opens Alice's balance in the pool for `mint` and deposits `DEPOSIT`. */
+    async function aliceDeposits(mint: PublicKey) {
+        await mintTo(
+            provider.connection,
+            issuer,
+            mint,
+            await tokenAccount(mint, alice.publicKey),
+            issuer,
+            DEPOSIT,
+            [],
+            undefined,
+            TOKEN_2022_PROGRAM_ID
+        );
+        await program.methods
+            .openBalance()
+            .accounts({
+                pool: poolPda(mint),
+                balance: balancePda(mint, alice.publicKey),
+                owner: alice.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([alice])
+            .rpc();
+        await transfer("deposit", mint, alice, DEPOSIT);
+    }
+
+    async function vaultAmount(mint: PublicKey): Promise<bigint> {
+        return (await getAccount(provider.connection, vaultPda(mint), undefined, TOKEN_2022_PROGRAM_ID)).amount;
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        issuer = Keypair.generate();
+        alice = Keypair.generate();
+
+        for (const kp of [admin, issuer, alice]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("The issuer creates a Token-2022 mint with themselves as permanent delegate", async () => {
+            delegateMint = await createPermanentDelegateMint();
+        });
+    });
+
+    describe("Exploit: the permanent delegate claws back the vault", () => {
+        it("The vulnerable pool accepts the mint and Alice deposits", async () => {
+            await register("vulnerableRegisterMint", delegateMint);
+            await aliceDeposits(delegateMint);
+            expect(await vaultAmount(delegateMint)).to.equal(BigInt(DEPOSIT));
+        });
+
+        it("The issuer moves the vault's tokens without the pool's signature", async () => {
+            await transferChecked(
+                provider.connection,
+                issuer,
+                vaultPda(delegateMint),
+                delegateMint,
+                await tokenAccount(delegateMint, issuer.publicKey),
+                issuer,
+                DEPOSIT,
+                DECIMALS,
+                [],
+                undefined,
+                TOKEN_2022_PROGRAM_ID
+            );
+
+            const balance = await program.account.balance.fetch(balancePda(delegateMint, alice.publicKey));
+            console.log(`  Alice's credit: ${balance.amount.toString()}, vault holds: ${await vaultAmount(delegateMint)}`);
+            expect(await vaultAmount(delegateMint)).to.equal(0n);
+        });
+
+        it("Alice's withdrawal fails: the pool is insolvent", async () => {
+            try {
+                await transfer("withdraw", delegateMint, alice, DEPOSIT);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.include("insufficient funds");
+            }
+        });
+    });
+
+    describe("Secure: mint extensions inspected at registration", () => {
+        it("Rejects a mint with a permanent delegate", async () => {
+            const mint = await createPermanentDelegateMint();
+            await expectError(register("secureRegisterMint", mint), "PermanentDelegate");
+        });
+
+        it("Rejects a mint with confidential transfers", async () => {
+            const mint = await createConfidentialMint();
+            await expectError(register("secureRegisterMint", mint), "ConfidentialTransfers");
+        });
+
+        it("Accepts a plain Token-2022 mint; deposits and withdrawals round-trip", async () => {
+            const mint = await createMint(getMintLen([]), () => []);
+            await register("secureRegisterMint", mint);
+            await aliceDeposits(mint);
+            await transfer("withdraw", mint, alice, DEPOSIT);
+
+            const account = await getAccount(
+                provider.connection,
+                await tokenAccount(mint, alice.publicKey),
+                undefined,
+                TOKEN_2022_PROGRAM_ID
+            );
+            expect(account.amount).to.equal(BigInt(DEPOSIT));
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Token-2022 Extension Risks ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  pub mint: InterfaceAccount<'info, Mint>,  // any Token-2022 mint");
+            console.log("  Risk: a permanent delegate moves vault tokens at will; confidential balances are unreadable\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  for ext in StateWithExtensions::<Mint>::unpack(&data)?.get_extension_types()? {");
+            console.log("      require!(SUPPORTED.contains(&ext));  // fees, metadata, interest display");
+            console.log("  }");
+            console.log("  Protection: only mints whose extensions the pool's accounting handles\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  A Token-2022 mint is not just a mint. Read its extensions before holding its tokens.");
+        });
+    });
+});
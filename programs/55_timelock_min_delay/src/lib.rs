@@ -0,0 +1,331 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use security_common::time::{ClockExt, UnixTs};
+
+declare_id!("8fNJgSjLX2o6eq74xaqW4gSHLLK9wWxs73Q6jhErPGWp");
+
+/// Shortest delay the secure path accepts. Short so the tests can wait one
+/// out; production timelocks use days.
+pub const MIN_DELAY_SECS: i64 = 4;
+/// Longest delay, so a compromised admin cannot freeze governance forever.
+pub const MAX_DELAY_SECS: i64 = 30 * 24 * 60 * 60;
+
+const BPS: u64 = 10_000;
+
+#[program]
+pub mod timelock_min_delay {
+    use super::*;
+
+    /// Creates a vault whose withdrawal fee can only change through the
+    /// timelock.
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        timelock_id: u64,
+        delay_secs: i64,
+        withdraw_fee_bps: u16,
+    ) -> Result<()> {
+        check_delay(delay_secs)?;
+        require!(withdraw_fee_bps as u64 <= BPS, TimelockError::InvalidFee);
+
+        let timelock = &mut ctx.accounts.timelock;
+        timelock.timelock_id = timelock_id;
+        timelock.admin = ctx.accounts.admin.key();
+        timelock.delay_secs = delay_secs;
+        timelock.withdraw_fee_bps = withdraw_fee_bps;
+        timelock.change_count = 0;
+        timelock.bump = ctx.bumps.timelock;
+        Ok(())
+    }
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.owner.to_account_info(),
+            to: ctx.accounts.timelock.to_account_info(),
+        };
+        transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts), amount)?;
+
+        let position = &mut ctx.accounts.position;
+        position.owner = ctx.accounts.owner.key();
+        position.amount = amount;
+        position.bump = ctx.bumps.position;
+        Ok(())
+    }
+
+    /// Withdraws everything at the current fee; the fee goes to the admin.
+    pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
+        let amount = ctx.accounts.position.amount;
+        let fee = (amount as u128 * ctx.accounts.timelock.withdraw_fee_bps as u128 / BPS as u128) as u64;
+        let timelock = ctx.accounts.timelock.to_account_info();
+        move_lamports(&timelock, &ctx.accounts.admin.to_account_info(), fee)?;
+        move_lamports(&timelock, &ctx.accounts.owner.to_account_info(), amount - fee)?;
+        msg!("Withdraw: {} lamports, fee {}", amount - fee, fee);
+        Ok(())
+    }
+
+    /// Queues a new withdrawal fee, executable after the current delay.
+    pub fn queue_fee_change(ctx: Context<QueueChange>, withdraw_fee_bps: u16) -> Result<()> {
+        require!(withdraw_fee_bps as u64 <= BPS, TimelockError::InvalidFee);
+        ctx.accounts.queue(TimelockAction::SetWithdrawFee { withdraw_fee_bps }, ctx.bumps.change)
+    }
+
+    // VULNERABLE: Delay Set Directly, With No Lower Bound
+    // 1. The admin sets `delay_secs` immediately, outside the timelock
+    // 2. Nothing stops a delay of 0
+    // 3. With the delay at 0, any change can be queued and executed in the
+    //    same transaction; users get no window to exit first
+    pub fn vulnerable_set_delay(ctx: Context<AdminAction>, delay_secs: i64) -> Result<()> {
+        require!(delay_secs >= 0, TimelockError::DelayTooShort);
+        ctx.accounts.timelock.delay_secs = delay_secs;
+        msg!("Vulnerable delay update: {}s", delay_secs);
+        Ok(())
+    }
+
+    // SECURE: Delay Changes Go Through the Timelock
+    // 1. The new delay must lie within `MIN_DELAY_SECS..=MAX_DELAY_SECS`,
+    //    hard-coded in the program rather than stored in config
+    // 2. The change is queued like any other and takes effect only after
+    //    the *current* delay, so shortening the delay is announced at
+    //    least one full delay in advance
+    pub fn secure_queue_delay_change(ctx: Context<QueueChange>, delay_secs: i64) -> Result<()> {
+        check_delay(delay_secs)?;
+        ctx.accounts.queue(TimelockAction::SetDelay { delay_secs }, ctx.bumps.change)
+    }
+
+    /// Applies a queued change once its ETA has passed. Permissionless.
+    pub fn execute_change(ctx: Context<ExecuteChange>) -> Result<()> {
+        let now = Clock::get()?.current_unix_ts();
+        let change = &mut ctx.accounts.change;
+        require!(!change.executed, TimelockError::AlreadyExecuted);
+        require!(now >= UnixTs(change.eta), TimelockError::NotReady);
+        change.executed = true;
+
+        let timelock = &mut ctx.accounts.timelock;
+        match change.action {
+            TimelockAction::SetWithdrawFee { withdraw_fee_bps } => timelock.withdraw_fee_bps = withdraw_fee_bps,
+            TimelockAction::SetDelay { delay_secs } => timelock.delay_secs = delay_secs,
+        }
+        msg!("Executed change {}: {:?}", change.change_id, change.action);
+        Ok(())
+    }
+}
+
+fn check_delay(delay_secs: i64) -> Result<()> {
+    require!(delay_secs >= MIN_DELAY_SECS, TimelockError::DelayTooShort);
+    require!(delay_secs <= MAX_DELAY_SECS, TimelockError::DelayTooLong);
+    Ok(())
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(TimelockError::InsufficientFunds)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(TimelockError::ArithmeticError)?;
+    Ok(())
+}
+
+impl<'info> QueueChange<'info> {
+    /// Records `action` with an ETA of now plus the timelock's current delay.
+    fn queue(&mut self, action: TimelockAction, bump: u8) -> Result<()> {
+        let now = Clock::get()?.current_unix_ts();
+        let timelock = &mut self.timelock;
+
+        let change = &mut self.change;
+        change.timelock = timelock.key();
+        change.change_id = timelock.change_count;
+        change.action = action;
+        change.eta = now
+            .checked_add_secs(timelock.delay_secs)
+            .ok_or(TimelockError::ArithmeticError)?
+            .0;
+        change.executed = false;
+        change.bump = bump;
+
+        timelock.change_count = timelock
+            .change_count
+            .checked_add(1)
+            .ok_or(TimelockError::ArithmeticError)?;
+        msg!("Queued change {}: {:?}, eta {}", change.change_id, change.action, change.eta);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(timelock_id: u64)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Timelock::INIT_SPACE,
+        seeds = [b"timelock", timelock_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub timelock: Account<'info, Timelock>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, seeds = [b"timelock", timelock.timelock_id.to_le_bytes().as_ref()], bump = timelock.bump)]
+    pub timelock: Account<'info, Timelock>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [b"position", timelock.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"timelock", timelock.timelock_id.to_le_bytes().as_ref()],
+        bump = timelock.bump,
+        has_one = admin
+    )]
+    pub timelock: Account<'info, Timelock>,
+    #[account(
+        mut,
+        seeds = [b"position", timelock.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner,
+        close = owner
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    /// CHECK: fee recipient, checked by `has_one` on the timelock.
+    #[account(mut)]
+    pub admin: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AdminAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"timelock", timelock.timelock_id.to_le_bytes().as_ref()],
+        bump = timelock.bump,
+        has_one = admin @ TimelockError::Unauthorized
+    )]
+    pub timelock: Account<'info, Timelock>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct QueueChange<'info> {
+    #[account(
+        mut,
+        seeds = [b"timelock", timelock.timelock_id.to_le_bytes().as_ref()],
+        bump = timelock.bump,
+        has_one = admin @ TimelockError::Unauthorized
+    )]
+    pub timelock: Account<'info, Timelock>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Change::INIT_SPACE,
+        seeds = [b"change", timelock.key().as_ref(), timelock.change_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub change: Account<'info, Change>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteChange<'info> {
+    #[account(mut, seeds = [b"timelock", timelock.timelock_id.to_le_bytes().as_ref()], bump = timelock.bump)]
+    pub timelock: Account<'info, Timelock>,
+    #[account(
+        mut,
+        seeds = [b"change", timelock.key().as_ref(), change.change_id.to_le_bytes().as_ref()],
+        bump = change.bump,
+        has_one = timelock
+    )]
+    pub change: Account<'info, Change>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Timelock {
+    pub timelock_id: u64,
+    pub admin: Pubkey,
+    /// Seconds between queueing a change and executing it.
+    pub delay_secs: i64,
+    /// Governed parameter: share of each withdrawal paid to the admin.
+    pub withdraw_fee_bps: u16,
+    pub change_count: u64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum TimelockAction {
+    SetWithdrawFee { withdraw_fee_bps: u16 },
+    SetDelay { delay_secs: i64 },
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Change {
+    pub timelock: Pubkey,
+    pub change_id: u64,
+    pub action: TimelockAction,
+    /// Unix timestamp from which the change can be executed.
+    pub eta: i64,
+    pub executed: bool,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum TimelockError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Delay is below the minimum")]
+    DelayTooShort,
+    #[msg("Delay is above the maximum")]
+    DelayTooLong,
+    #[msg("Fee cannot exceed 100%")]
+    InvalidFee,
+    #[msg("Change is not executable yet")]
+    NotReady,
+    #[msg("Change already executed")]
+    AlreadyExecuted,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_bounds_are_inclusive() {
+        assert!(check_delay(MIN_DELAY_SECS).is_ok());
+        assert!(check_delay(MAX_DELAY_SECS).is_ok());
+        assert!(check_delay(0).is_err());
+        assert!(check_delay(MIN_DELAY_SECS - 1).is_err());
+        assert!(check_delay(MAX_DELAY_SECS + 1).is_err());
+    }
+}
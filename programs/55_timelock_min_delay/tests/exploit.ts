@@ -0,0 +1,206 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { expect } from "chai";
+import { expectError, sleep } from "../../../tests/utils";
+
+describe("55: Timelock Minimum Delay", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.TimelockMinDelay as Program<any>;
+
+    let admin: Keypair;
+    let alice: Keypair;
+
+    const VULNERABLE_TIMELOCK = 1;
+    const SECURE_TIMELOCK = 2;
+    const DELAY_SECS = 6;
+    const MIN_DELAY_SECS = 4;
+    const FEE_BPS = 50;
+    const MAX_FEE_BPS = 10_000;
+    const DEPOSIT = 2 * LAMPORTS_PER_SOL;
+
+    function timelockPda(timelockId: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("timelock"), new anchor.BN(timelockId).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        )[0];
+    }
+
+    function changePda(timelockId: number, changeId: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("change"), timelockPda(timelockId).toBuffer(), new anchor.BN(changeId).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        )[0];
+    }
+
+    function positionPda(timelockId: number, owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("position"), timelockPda(timelockId).toBuffer(), owner.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    async function nextChange(timelockId: number): Promise<PublicKey> {
+        const { changeCount } = await program.account.timelock.fetch(timelockPda(timelockId));
+        return changePda(timelockId, changeCount.toNumber());
+    }
+
+    async function queueFeeChange(timelockId: number, feeBps: number) {
+        const change = await nextChange(timelockId);
+        return program.methods
+            .queueFeeChange(feeBps)
+            .accounts({
+                timelock: timelockPda(timelockId),
+                change,
+                admin: admin.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([admin]);
+    }
+
+    async function queueDelayChange(timelockId: number, delaySecs: number) {
+        const change = await nextChange(timelockId);
+        return program.methods
+            .secureQueueDelayChange(new anchor.BN(delaySecs))
+            .accounts({
+                timelock: timelockPda(timelockId),
+                change,
+                admin: admin.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([admin]);
+    }
+
+    function execute(timelockId: number, changeId: number) {
+        return program.methods
+            .executeChange()
+            .accounts({ timelock: timelockPda(timelockId), change: changePda(timelockId, changeId) });
+    }
+
+    async function setup(timelockId: number) {
+        await program.methods
+            .initialize(new anchor.BN(timelockId), new anchor.BN(DELAY_SECS), FEE_BPS)
+            .accounts({ timelock: timelockPda(timelockId), admin: admin.publicKey, systemProgram: SystemProgram.programId })
+            .signers([admin])
+            .rpc();
+
+        await program.methods
+            .deposit(new anchor.BN(DEPOSIT))
+            .accounts({
+                timelock: timelockPda(timelockId),
+                position: positionPda(timelockId, alice.publicKey),
+                owner: alice.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([alice])
+            .rpc();
+    }
+
+    /** Withdraws Alice's position and returns what reached Alice. */
+    async function aliceWithdraws(timelockId: number): Promise<number> {
+        const timelock = timelockPda(timelockId);
+        const before = await provider.connection.getBalance(timelock);
+        const feeBefore = await provider.connection.getBalance(admin.publicKey);
+        await program.methods
+            .withdraw()
+            .accounts({
+                timelock,
+                position: positionPda(timelockId, alice.publicKey),
+                owner: alice.publicKey,
+                admin: admin.publicKey,
+            })
+            .signers([alice])
+            .rpc();
+        const fee = (await provider.connection.getBalance(admin.publicKey)) - feeBefore;
+        return before - (await provider.connection.getBalance(timelock)) - fee;
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        alice = Keypair.generate();
+
+        for (const kp of [admin, alice]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Two vaults with a 6s timelock and a 0.5% withdrawal fee; Alice deposits 2 SOL in each", async () => {
+            await setup(VULNERABLE_TIMELOCK);
+            await setup(SECURE_TIMELOCK);
+        });
+    });
+
+    describe("Exploit: delay set to zero, change pushed in the same transaction", () => {
+        it("The admin zeroes the delay, queues a 100% fee and executes it atomically", async () => {
+            const tx = new Transaction().add(
+                await program.methods
+                    .vulnerableSetDelay(new anchor.BN(0))
+                    .accounts({ timelock: timelockPda(VULNERABLE_TIMELOCK), admin: admin.publicKey })
+                    .instruction(),
+                await (await queueFeeChange(VULNERABLE_TIMELOCK, MAX_FEE_BPS)).instruction(),
+                await execute(VULNERABLE_TIMELOCK, 0).instruction()
+            );
+            await provider.sendAndConfirm(tx, [admin]);
+
+            const timelock = await program.account.timelock.fetch(timelockPda(VULNERABLE_TIMELOCK));
+            expect(timelock.withdrawFeeBps).to.equal(MAX_FEE_BPS);
+        });
+
+        it("Alice never had a window to exit; the whole deposit goes to the admin", async () => {
+            const received = await aliceWithdraws(VULNERABLE_TIMELOCK);
+            console.log(`  Alice received ${received / LAMPORTS_PER_SOL} SOL of ${DEPOSIT / LAMPORTS_PER_SOL}`);
+            expect(received).to.equal(0);
+        });
+    });
+
+    describe("Secure: hard minimum, and delay changes wait out the current delay", () => {
+        it("Rejects a delay below the minimum", async () => {
+            await expectError((await queueDelayChange(SECURE_TIMELOCK, 0)).rpc(), "DelayTooShort");
+        });
+
+        it("Shortening the delay to the minimum is itself timelocked", async () => {
+            await (await queueDelayChange(SECURE_TIMELOCK, MIN_DELAY_SECS)).rpc();
+            await expectError(execute(SECURE_TIMELOCK, 0).rpc(), "NotReady");
+        });
+
+        it("A queued 100% fee cannot execute before Alice sees it and exits", async () => {
+            await (await queueFeeChange(SECURE_TIMELOCK, MAX_FEE_BPS)).rpc();
+            await expectError(execute(SECURE_TIMELOCK, 1).rpc(), "NotReady");
+
+            const received = await aliceWithdraws(SECURE_TIMELOCK);
+            expect(received).to.equal(DEPOSIT - (DEPOSIT * FEE_BPS) / MAX_FEE_BPS);
+        });
+
+        it("Both changes execute once the delay has passed", async () => {
+            await sleep(DELAY_SECS + 1);
+            await execute(SECURE_TIMELOCK, 0).rpc();
+            await execute(SECURE_TIMELOCK, 1).rpc();
+
+            const timelock = await program.account.timelock.fetch(timelockPda(SECURE_TIMELOCK));
+            expect(timelock.delaySecs.toNumber()).to.equal(MIN_DELAY_SECS);
+            expect(timelock.withdrawFeeBps).to.equal(MAX_FEE_BPS);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Timelock Minimum Delay ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  set_delay(0) applied immediately by the admin");
+            console.log("  Risk: set_delay(0) + queue + execute in one transaction; users cannot react\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  require!(MIN_DELAY_SECS <= delay && delay <= MAX_DELAY_SECS);  // constants, not config");
+            console.log("  delay changes are queued and wait out the current delay");
+            console.log("  Protection: every change, including to the delay, is visible for at least MIN_DELAY_SECS\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  A timelock whose delay can be changed instantly is not a timelock.");
+        });
+    });
+});
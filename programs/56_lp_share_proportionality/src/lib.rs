@@ -0,0 +1,405 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount, Transfer};
+use security_common::math::mul_div_floor;
+
+declare_id!("AwT8wodWrApTnQNKdzW8aupL87ghPg8AWZRu2qs2CyuA");
+
+pub const LP_DECIMALS: u8 = 6;
+
+#[program]
+pub mod lp_share_proportionality {
+    use super::*;
+
+    pub fn initialize_pool(ctx: Context<InitializePool>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.mint_a = ctx.accounts.mint_a.key();
+        pool.mint_b = ctx.accounts.mint_b.key();
+        pool.vault_a = ctx.accounts.vault_a.key();
+        pool.vault_b = ctx.accounts.vault_b.key();
+        pool.lp_mint = ctx.accounts.lp_mint.key();
+        pool.bump = ctx.bumps.pool;
+        Ok(())
+    }
+
+    /// Deposits both tokens and mints LP tokens for the smaller of the two
+    /// proportional shares.
+    pub fn add_liquidity(ctx: Context<AddLiquidity>, amount_a: u64, amount_b: u64) -> Result<()> {
+        require!(amount_a > 0 && amount_b > 0, LiquidityError::ZeroAmount);
+
+        let lp_amount = lp_for_deposit(
+            amount_a,
+            amount_b,
+            ctx.accounts.vault_a.amount,
+            ctx.accounts.vault_b.amount,
+            ctx.accounts.lp_mint.supply,
+        )
+        .ok_or(LiquidityError::EmptyPool)?;
+        require!(lp_amount > 0, LiquidityError::ZeroLiquidity);
+
+        let accounts = &ctx.accounts;
+        for (from, to, amount) in [
+            (&accounts.user_a, &accounts.vault_a, amount_a),
+            (&accounts.user_b, &accounts.vault_b, amount_b),
+        ] {
+            token::transfer(
+                CpiContext::new(
+                    accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: from.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: accounts.user.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+        }
+
+        let pool = &accounts.pool;
+        let seeds: &[&[u8]] = &[b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref(), &[pool.bump]];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: accounts.lp_mint.to_account_info(),
+                    to: accounts.user_lp.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                &[seeds],
+            ),
+            lp_amount,
+        )?;
+
+        msg!("Add liquidity: {} A + {} B -> {} LP", amount_a, amount_b, lp_amount);
+        Ok(())
+    }
+
+    // VULNERABLE: Withdrawal Amounts Chosen by the Caller
+    // 1. The caller names how much of each token they want back
+    // 2. The only bound is what the vaults hold, not what `lp_amount` is
+    //    worth
+    // 3. Burning a single LP token can withdraw both vaults in full
+    pub fn vulnerable_remove_liquidity(
+        ctx: Context<RemoveLiquidity>,
+        lp_amount: u64,
+        amount_a: u64,
+        amount_b: u64,
+    ) -> Result<()> {
+        require!(lp_amount > 0, LiquidityError::ZeroAmount);
+        require!(
+            amount_a <= ctx.accounts.vault_a.amount && amount_b <= ctx.accounts.vault_b.amount,
+            LiquidityError::InsufficientLiquidity
+        );
+
+        msg!("Vulnerable remove: {} LP -> {} A + {} B", lp_amount, amount_a, amount_b);
+        ctx.accounts.burn_and_pay(lp_amount, amount_a, amount_b)
+    }
+
+    // SECURE: Outputs Derived From the LP Share
+    // 1. The caller only says how many LP tokens to burn
+    // 2. Each output is `lp_amount / lp_supply` of that vault, read from
+    //    the accounts rather than from instruction data
+    // 3. Both round down, so the remaining LPs keep any dust and no
+    //    sequence of deposits and withdrawals pays out more than went in
+    pub fn secure_remove_liquidity(ctx: Context<RemoveLiquidity>, lp_amount: u64) -> Result<()> {
+        require!(lp_amount > 0, LiquidityError::ZeroAmount);
+
+        let (amount_a, amount_b) = withdrawal_amounts(
+            lp_amount,
+            ctx.accounts.vault_a.amount,
+            ctx.accounts.vault_b.amount,
+            ctx.accounts.lp_mint.supply,
+        )
+        .ok_or(LiquidityError::InsufficientLiquidity)?;
+
+        msg!("Secure remove: {} LP -> {} A + {} B", lp_amount, amount_a, amount_b);
+        ctx.accounts.burn_and_pay(lp_amount, amount_a, amount_b)
+    }
+}
+
+/// LP tokens minted for a deposit. The first deposit mints `amount_a`;
+/// later ones mint the smaller of the two proportional shares, so the
+/// excess of an unbalanced deposit is left to existing LPs.
+pub fn lp_for_deposit(amount_a: u64, amount_b: u64, reserve_a: u64, reserve_b: u64, lp_supply: u64) -> Option<u64> {
+    if lp_supply == 0 {
+        return Some(amount_a);
+    }
+    let by_a = mul_div_floor(amount_a, lp_supply, reserve_a)?;
+    let by_b = mul_div_floor(amount_b, lp_supply, reserve_b)?;
+    Some(by_a.min(by_b))
+}
+
+/// Tokens paid for burning `lp_amount`: that share of each vault, rounded
+/// down.
+pub fn withdrawal_amounts(lp_amount: u64, reserve_a: u64, reserve_b: u64, lp_supply: u64) -> Option<(u64, u64)> {
+    if lp_amount > lp_supply {
+        return None;
+    }
+    Some((
+        mul_div_floor(lp_amount, reserve_a, lp_supply)?,
+        mul_div_floor(lp_amount, reserve_b, lp_supply)?,
+    ))
+}
+
+impl<'info> RemoveLiquidity<'info> {
+    /// Burns the caller's LP tokens and pays out of both vaults.
+    fn burn_and_pay(&self, lp_amount: u64, amount_a: u64, amount_b: u64) -> Result<()> {
+        token::burn(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                Burn {
+                    mint: self.lp_mint.to_account_info(),
+                    from: self.user_lp.to_account_info(),
+                    authority: self.user.to_account_info(),
+                },
+            ),
+            lp_amount,
+        )?;
+
+        let pool = &self.pool;
+        let seeds: &[&[u8]] = &[b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref(), &[pool.bump]];
+        for (from, to, amount) in [(&self.vault_a, &self.user_a, amount_a), (&self.vault_b, &self.user_b, amount_b)] {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    Transfer {
+                        from: from.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: pool.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                amount,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Pool::INIT_SPACE,
+        seeds = [b"pool", mint_a.key().as_ref(), mint_b.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    pub mint_a: Account<'info, Mint>,
+    pub mint_b: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = payer,
+        token::mint = mint_a,
+        token::authority = pool,
+        seeds = [b"vault_a", pool.key().as_ref()],
+        bump
+    )]
+    pub vault_a: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = payer,
+        token::mint = mint_b,
+        token::authority = pool,
+        seeds = [b"vault_b", pool.key().as_ref()],
+        bump
+    )]
+    pub vault_b: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = LP_DECIMALS,
+        mint::authority = pool,
+        seeds = [b"lp_mint", pool.key().as_ref()],
+        bump
+    )]
+    pub lp_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct AddLiquidity<'info> {
+    #[account(
+        seeds = [b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        bump = pool.bump,
+        has_one = vault_a,
+        has_one = vault_b,
+        has_one = lp_mint
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub vault_a: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub vault_b: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub lp_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = pool.mint_a, token::authority = user)]
+    pub user_a: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = pool.mint_b, token::authority = user)]
+    pub user_b: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = lp_mint)]
+    pub user_lp: Account<'info, TokenAccount>,
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveLiquidity<'info> {
+    #[account(
+        seeds = [b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        bump = pool.bump,
+        has_one = vault_a,
+        has_one = vault_b,
+        has_one = lp_mint
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub vault_a: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub vault_b: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub lp_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = pool.mint_a)]
+    pub user_a: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = pool.mint_b)]
+    pub user_b: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = lp_mint, token::authority = user)]
+    pub user_lp: Account<'info, TokenAccount>,
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    pub lp_mint: Pubkey,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum LiquidityError {
+    #[msg("Amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("Deposit too small to mint any LP tokens")]
+    ZeroLiquidity,
+    #[msg("Pool has LP supply but an empty vault")]
+    EmptyPool,
+    #[msg("Withdrawal exceeds pool liquidity")]
+    InsufficientLiquidity,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Add { user: usize, amount_a: u64, amount_b: u64 },
+        Remove { user: usize, share_bps: u64 },
+    }
+
+    const USERS: usize = 3;
+
+    fn ops() -> impl Strategy<Value = Vec<Op>> {
+        let op = prop_oneof![
+            (0..USERS, 1..1_000_000_000_000u64, 1..1_000_000_000_000u64)
+                .prop_map(|(user, amount_a, amount_b)| Op::Add { user, amount_a, amount_b }),
+            (0..USERS, 1..=10_000u64).prop_map(|(user, share_bps)| Op::Remove { user, share_bps }),
+        ];
+        prop::collection::vec(op, 1..40)
+    }
+
+    #[test]
+    fn one_lp_token_is_worth_its_share() {
+        // 1,000 A / 1,000 B backing 1,000,000,001 LP tokens.
+        let supply = 1_000_000_001;
+        assert_eq!(withdrawal_amounts(1, 1_000_000_000, 1_000_000_000, supply), Some((0, 0)));
+        assert_eq!(withdrawal_amounts(supply, 7, 9, supply), Some((7, 9)));
+        assert_eq!(withdrawal_amounts(supply + 1, 7, 9, supply), None);
+    }
+
+    proptest! {
+        #[test]
+        fn random_add_remove_sequences_conserve(ops in ops()) {
+            let (mut reserve_a, mut reserve_b, mut supply) = (0u64, 0u64, 0u64);
+            let mut lp = [0u64; USERS];
+            let (mut deposited_a, mut deposited_b) = ([0u128; USERS], [0u128; USERS]);
+            let (mut withdrawn_a, mut withdrawn_b) = ([0u128; USERS], [0u128; USERS]);
+
+            for op in ops {
+                match op {
+                    Op::Add { user, amount_a, amount_b } => {
+                        let Some(minted) = lp_for_deposit(amount_a, amount_b, reserve_a, reserve_b, supply) else {
+                            continue;
+                        };
+                        if minted == 0 {
+                            continue;
+                        }
+                        reserve_a += amount_a;
+                        reserve_b += amount_b;
+                        supply += minted;
+                        lp[user] += minted;
+                        deposited_a[user] += amount_a as u128;
+                        deposited_b[user] += amount_b as u128;
+                    }
+                    Op::Remove { user, share_bps } => {
+                        let burned = (lp[user] as u128 * share_bps as u128 / 10_000) as u64;
+                        if burned == 0 {
+                            continue;
+                        }
+                        let (out_a, out_b) = withdrawal_amounts(burned, reserve_a, reserve_b, supply).unwrap();
+
+                        // Never more than the burned share of either vault.
+                        prop_assert!(out_a as u128 * supply as u128 <= burned as u128 * reserve_a as u128);
+                        prop_assert!(out_b as u128 * supply as u128 <= burned as u128 * reserve_b as u128);
+
+                        // Remaining LPs are never diluted by a withdrawal.
+                        let (new_a, new_b, new_supply) = (reserve_a - out_a, reserve_b - out_b, supply - burned);
+                        prop_assert!(new_a as u128 * supply as u128 >= reserve_a as u128 * new_supply as u128);
+                        prop_assert!(new_b as u128 * supply as u128 >= reserve_b as u128 * new_supply as u128);
+
+                        reserve_a = new_a;
+                        reserve_b = new_b;
+                        supply = new_supply;
+                        lp[user] -= burned;
+                        withdrawn_a[user] += out_a as u128;
+                        withdrawn_b[user] += out_b as u128;
+                    }
+                }
+
+                // Token conservation: the vaults hold exactly what went in and did not come out.
+                let in_a: u128 = deposited_a.iter().sum();
+                let out_a: u128 = withdrawn_a.iter().sum();
+                let in_b: u128 = deposited_b.iter().sum();
+                let out_b: u128 = withdrawn_b.iter().sum();
+                prop_assert_eq!(reserve_a as u128 + out_a, in_a);
+                prop_assert_eq!(reserve_b as u128 + out_b, in_b);
+                prop_assert_eq!(supply, lp.iter().sum::<u64>());
+            }
+        }
+
+        #[test]
+        fn add_then_remove_never_profits(
+            reserve_a in 1..1_000_000_000_000u64,
+            reserve_b in 1..1_000_000_000_000u64,
+            supply in 1..1_000_000_000_000u64,
+            amount_a in 1..1_000_000_000_000u64,
+            amount_b in 1..1_000_000_000_000u64,
+        ) {
+            let minted = lp_for_deposit(amount_a, amount_b, reserve_a, reserve_b, supply).unwrap();
+            let (out_a, out_b) =
+                withdrawal_amounts(minted, reserve_a + amount_a, reserve_b + amount_b, supply + minted).unwrap();
+            prop_assert!(out_a <= amount_a);
+            prop_assert!(out_b <= amount_b);
+        }
+    }
+}
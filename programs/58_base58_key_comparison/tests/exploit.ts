@@ -0,0 +1,147 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("58: Base58 Key Comparison", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.Base58KeyComparison as Program<any>;
+
+    let admin: Keypair;
+    let operator: Keypair;
+    let mallory: Keypair;
+
+    const DISPLAY_PREFIX_LEN = 2;
+    const MAX_GRIND_ATTEMPTS = 200_000;
+    const TREASURY_FUNDS = 5 * LAMPORTS_PER_SOL;
+
+    const [treasury] = PublicKey.findProgramAddressSync([Buffer.from("treasury")], program.programId);
+
+    // Mirrors the on-chain display_prefix_matches
+    function displayPrefixMatches(candidate: PublicKey, expected: PublicKey): boolean {
+        const prefix = expected.toBase58().toLowerCase().slice(0, DISPLAY_PREFIX_LEN);
+        return candidate.toBase58().toLowerCase().startsWith(prefix);
+    }
+
+    /** Generates keypairs until one passes the prefix check for `target`. */
+    function grind(target: PublicKey): { keypair: Keypair; attempts: number } {
+        for (let attempts = 1; attempts <= MAX_GRIND_ATTEMPTS; attempts++) {
+            const keypair = Keypair.generate();
+            if (!keypair.publicKey.equals(target) && displayPrefixMatches(keypair.publicKey, target)) {
+                return { keypair, attempts };
+            }
+        }
+        throw new Error("No match found");
+    }
+
+    function sweep(method: "vulnerableSweep" | "secureSweep", signer: Keypair, amount: number) {
+        return program.methods[method](new anchor.BN(amount))
+            .accounts({ treasury, operator: signer.publicKey })
+            .signers([signer])
+            .rpc({ commitment: "confirmed" });
+    }
+
+    async function computeUnits(signature: string): Promise<number> {
+        const tx = await provider.connection.getTransaction(signature, {
+            commitment: "confirmed",
+            maxSupportedTransactionVersion: 0,
+        });
+        return tx!.meta!.computeUnitsConsumed!;
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        operator = Keypair.generate();
+
+        for (const kp of [admin, operator]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates a treasury with a designated operator and funds it", async () => {
+            await program.methods
+                .initialize(operator.publicKey)
+                .accounts({ treasury, admin: admin.publicKey, systemProgram: SystemProgram.programId })
+                .signers([admin])
+                .rpc();
+
+            await program.methods
+                .fund(new anchor.BN(TREASURY_FUNDS))
+                .accounts({ treasury, funder: admin.publicKey, systemProgram: SystemProgram.programId })
+                .signers([admin])
+                .rpc();
+        });
+    });
+
+    describe("Exploit: a vanity-ground key passes the string check", () => {
+        it("Mallory grinds a key sharing the operator's displayed prefix", async () => {
+            const ground = grind(operator.publicKey);
+            mallory = ground.keypair;
+
+            console.log(`  Operator: ${operator.publicKey.toBase58()}`);
+            console.log(`  Mallory:  ${mallory.publicKey.toBase58()} (${ground.attempts} keypairs generated)`);
+
+            const sig = await provider.connection.requestAirdrop(mallory.publicKey, LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        });
+
+        it("The vulnerable sweep pays Mallory", async () => {
+            const before = await provider.connection.getBalance(mallory.publicKey);
+            await sweep("vulnerableSweep", mallory, LAMPORTS_PER_SOL);
+            const after = await provider.connection.getBalance(mallory.publicKey);
+            expect(after).to.be.greaterThan(before);
+        });
+    });
+
+    describe("Secure: all 32 bytes compared", () => {
+        it("Rejects Mallory's ground key", async () => {
+            await expectError(sweep("secureSweep", mallory, LAMPORTS_PER_SOL), "Unauthorized");
+        });
+
+        it("Accepts the real operator", async () => {
+            await sweep("secureSweep", operator, LAMPORTS_PER_SOL);
+        });
+    });
+
+    describe("Compute Cost", () => {
+        it("Benchmarks both checks on the same sweep", async () => {
+            const vulnerable = await computeUnits(await sweep("vulnerableSweep", operator, 1));
+            const secure = await computeUnits(await sweep("secureSweep", operator, 1));
+
+            const rows: [string, number][] = [
+                ["to_string().to_lowercase().starts_with", vulnerable],
+                ["Pubkey == Pubkey", secure],
+                ["Difference", vulnerable - secure],
+            ];
+            console.log("\n  | Check                                   | Compute units |");
+            console.log("  |-----------------------------------------|---------------|");
+            for (const [check, units] of rows) {
+                console.log(`  | ${check.padEnd(39)} | ${String(units).padStart(13)} |`);
+            }
+
+            expect(vulnerable).to.be.greaterThan(secure);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Base58 Key Comparison ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  signer.to_string().to_lowercase().starts_with(&operator_prefix)");
+            console.log("  Risk: a few characters of a lossy encoding; grinding finds a match\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  require_keys_eq!(signer.key(), treasury.operator);");
+            console.log("  Protection: every bit of the key is compared, with no allocation\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Base58 is for humans. Programs compare bytes.");
+        });
+    });
+});
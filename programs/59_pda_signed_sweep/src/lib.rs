@@ -0,0 +1,248 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke_signed, system_instruction};
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("AsPsTfJvbDTrSKsUCfcMmyjFyPU3DEQwMGkYoWsUUCJ6");
+
+pub const MAX_DESTINATIONS: usize = 4;
+
+#[program]
+pub mod pda_signed_sweep {
+    use super::*;
+
+    /// Creates a fee config. `sweeper` is the hot key a bot uses to move
+    /// collected fees; the admin key stays cold.
+    pub fn initialize(ctx: Context<Initialize>, config_id: u64, sweeper: Pubkey, max_sweep: u64) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.config_id = config_id;
+        config.admin = ctx.accounts.admin.key();
+        config.sweeper = sweeper;
+        config.allowlist = Vec::new();
+        config.max_sweep = max_sweep;
+        config.bump = ctx.bumps.config;
+        config.vault_bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    pub fn allow_destination(ctx: Context<AdminAction>, destination: Pubkey) -> Result<()> {
+        let allowlist = &mut ctx.accounts.config.allowlist;
+        require!(allowlist.len() < MAX_DESTINATIONS, SweepError::AllowlistFull);
+        if !allowlist.contains(&destination) {
+            allowlist.push(destination);
+        }
+        Ok(())
+    }
+
+    /// Pays fees into the config's SOL vault.
+    pub fn collect(ctx: Context<Collect>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+        };
+        transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts), amount)
+    }
+
+    // VULNERABLE: PDA Signs Whatever Transfer the Caller Describes
+    // 1. The vault PDA signs a system transfer with `invoke_signed`
+    // 2. Destination and amount come straight from the caller; nothing
+    //    compares them to the config
+    // 3. Whoever holds the sweeper's hot key, or any bug that reaches this
+    //    path, can send the whole vault anywhere in one call
+    pub fn vulnerable_admin_sweep(ctx: Context<Sweep>, amount: u64) -> Result<()> {
+        ctx.accounts.sweep(amount)
+    }
+
+    // SECURE: Allowlisted, Capped, Above the Rent Floor
+    // 1. The destination must be on the allowlist only the cold admin key
+    //    can edit
+    // 2. Each sweep is capped by `max_sweep`
+    // 3. The vault never drops below its rent-exempt minimum, so it stays
+    //    alive and later transfers keep working
+    pub fn secure_admin_sweep(ctx: Context<Sweep>, amount: u64) -> Result<()> {
+        let floor = Rent::get()?.minimum_balance(0);
+        if let Some(error) = sweep_error(
+            &ctx.accounts.config,
+            ctx.accounts.destination.key,
+            amount,
+            ctx.accounts.vault.lamports(),
+            floor,
+        ) {
+            return Err(error.into());
+        }
+
+        ctx.accounts.sweep(amount)
+    }
+}
+
+/// Why a sweep of `amount` from a vault holding `balance` to `destination`
+/// is refused, if it is.
+fn sweep_error(config: &Config, destination: &Pubkey, amount: u64, balance: u64, floor: u64) -> Option<SweepError> {
+    if !config.allowlist.contains(destination) {
+        return Some(SweepError::DestinationNotAllowed);
+    }
+    if amount > config.max_sweep {
+        return Some(SweepError::SweepCapExceeded);
+    }
+    match balance.checked_sub(amount) {
+        None => Some(SweepError::InsufficientFunds),
+        Some(remaining) if remaining < floor => Some(SweepError::BelowRentFloor),
+        Some(_) => None,
+    }
+}
+
+impl<'info> Sweep<'info> {
+    /// Transfers `amount` from the vault to `destination`, signed by the
+    /// vault PDA.
+    fn sweep(&self, amount: u64) -> Result<()> {
+        let config_key = self.config.key();
+        let seeds: &[&[u8]] = &[b"vault", config_key.as_ref(), &[self.config.vault_bump]];
+        invoke_signed(
+            &system_instruction::transfer(self.vault.key, self.destination.key, amount),
+            &[
+                self.vault.to_account_info(),
+                self.destination.to_account_info(),
+                self.system_program.to_account_info(),
+            ],
+            &[seeds],
+        )?;
+        msg!("Swept {} lamports to {}", amount, self.destination.key);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(config_id: u64)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [b"config", config_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: system-owned PDA holding the config's SOL; only its bump is stored.
+    #[account(seeds = [b"vault", config.key().as_ref()], bump)]
+    pub vault: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AdminAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", config.config_id.to_le_bytes().as_ref()],
+        bump = config.bump,
+        has_one = admin @ SweepError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Collect<'info> {
+    #[account(seeds = [b"config", config.config_id.to_le_bytes().as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// CHECK: system-owned PDA, checked by seeds.
+    #[account(mut, seeds = [b"vault", config.key().as_ref()], bump = config.vault_bump)]
+    pub vault: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Sweep<'info> {
+    #[account(
+        seeds = [b"config", config.config_id.to_le_bytes().as_ref()],
+        bump = config.bump,
+        has_one = sweeper @ SweepError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: system-owned PDA, checked by seeds.
+    #[account(mut, seeds = [b"vault", config.key().as_ref()], bump = config.vault_bump)]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: any account in the vulnerable path; allowlisted in the secure one.
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+    pub sweeper: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub config_id: u64,
+    /// Cold key: edits the allowlist.
+    pub admin: Pubkey,
+    /// Hot key: signs sweeps.
+    pub sweeper: Pubkey,
+    #[max_len(MAX_DESTINATIONS)]
+    pub allowlist: Vec<Pubkey>,
+    /// Largest amount a single sweep may move.
+    pub max_sweep: u64,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+#[error_code]
+pub enum SweepError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Allowlist is full")]
+    AllowlistFull,
+    #[msg("Destination is not on the allowlist")]
+    DestinationNotAllowed,
+    #[msg("Amount exceeds the per-sweep cap")]
+    SweepCapExceeded,
+    #[msg("Sweep would leave the vault below its rent-exempt minimum")]
+    BelowRentFloor,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLOOR: u64 = 890_880;
+
+    fn config(treasury: Pubkey) -> Config {
+        Config {
+            config_id: 1,
+            admin: Pubkey::new_unique(),
+            sweeper: Pubkey::new_unique(),
+            allowlist: vec![treasury],
+            max_sweep: 1_000_000_000,
+            bump: 255,
+            vault_bump: 255,
+        }
+    }
+
+    #[test]
+    fn sweeps_are_allowlisted_capped_and_floored() {
+        let treasury = Pubkey::new_unique();
+        let config = config(treasury);
+        let balance = 5_000_000_000;
+
+        assert!(sweep_error(&config, &treasury, 1_000_000_000, balance, FLOOR).is_none());
+        assert!(matches!(
+            sweep_error(&config, &Pubkey::new_unique(), 1, balance, FLOOR),
+            Some(SweepError::DestinationNotAllowed)
+        ));
+        assert!(matches!(
+            sweep_error(&config, &treasury, 1_000_000_001, balance, FLOOR),
+            Some(SweepError::SweepCapExceeded)
+        ));
+        assert!(matches!(
+            sweep_error(&config, &treasury, 1_000_000_000, 1_000_000_000, FLOOR),
+            Some(SweepError::BelowRentFloor)
+        ));
+        assert!(matches!(
+            sweep_error(&config, &treasury, 1_000_000_000, 1, FLOOR),
+            Some(SweepError::InsufficientFunds)
+        ));
+    }
+}
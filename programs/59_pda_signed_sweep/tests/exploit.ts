@@ -0,0 +1,160 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("59: PDA-Signed Sweep Drain", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.PdaSignedSweep as Program<any>;
+
+    let admin: Keypair;
+    let sweeper: Keypair;
+    let treasury: Keypair;
+    let mallory: Keypair;
+
+    const VULNERABLE_CONFIG = 1;
+    const SECURE_CONFIG = 2;
+    const COLLECTED = 5 * LAMPORTS_PER_SOL;
+    const MAX_SWEEP = LAMPORTS_PER_SOL;
+
+    function configPda(configId: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("config"), new anchor.BN(configId).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        )[0];
+    }
+
+    function vaultPda(configId: number): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("vault"), configPda(configId).toBuffer()], program.programId)[0];
+    }
+
+    function sweep(
+        method: "vulnerableAdminSweep" | "secureAdminSweep",
+        configId: number,
+        destination: PublicKey,
+        amount: number
+    ) {
+        return program.methods[method](new anchor.BN(amount))
+            .accounts({
+                config: configPda(configId),
+                vault: vaultPda(configId),
+                destination,
+                sweeper: sweeper.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([sweeper])
+            .rpc();
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        sweeper = Keypair.generate();
+        treasury = Keypair.generate();
+        mallory = Keypair.generate();
+
+        for (const kp of [admin, sweeper]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 20 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Two fee configs, each with 5 SOL collected; the treasury is allowlisted", async () => {
+            for (const configId of [VULNERABLE_CONFIG, SECURE_CONFIG]) {
+                await program.methods
+                    .initialize(new anchor.BN(configId), sweeper.publicKey, new anchor.BN(MAX_SWEEP))
+                    .accounts({
+                        config: configPda(configId),
+                        vault: vaultPda(configId),
+                        admin: admin.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([admin])
+                    .rpc();
+
+                await program.methods
+                    .allowDestination(treasury.publicKey)
+                    .accounts({ config: configPda(configId), admin: admin.publicKey })
+                    .signers([admin])
+                    .rpc();
+
+                await program.methods
+                    .collect(new anchor.BN(COLLECTED))
+                    .accounts({
+                        config: configPda(configId),
+                        vault: vaultPda(configId),
+                        payer: admin.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([admin])
+                    .rpc();
+            }
+        });
+    });
+
+    describe("Exploit: the leaked sweeper key drains the vault", () => {
+        it("Mallory, holding the sweeper key, sends the whole vault to Mallory's wallet", async () => {
+            const vault = await provider.connection.getBalance(vaultPda(VULNERABLE_CONFIG));
+            await sweep("vulnerableAdminSweep", VULNERABLE_CONFIG, mallory.publicKey, vault);
+
+            console.log(`  Mallory received ${(await provider.connection.getBalance(mallory.publicKey)) / LAMPORTS_PER_SOL} SOL`);
+            expect(await provider.connection.getBalance(mallory.publicKey)).to.equal(vault);
+            expect(await provider.connection.getBalance(vaultPda(VULNERABLE_CONFIG))).to.equal(0);
+        });
+    });
+
+    describe("Secure: allowlisted destination, capped amount, rent floor", () => {
+        it("Rejects a destination off the allowlist", async () => {
+            await expectError(
+                sweep("secureAdminSweep", SECURE_CONFIG, mallory.publicKey, MAX_SWEEP),
+                "DestinationNotAllowed"
+            );
+        });
+
+        it("Rejects a sweep above the per-sweep cap", async () => {
+            await expectError(
+                sweep("secureAdminSweep", SECURE_CONFIG, treasury.publicKey, MAX_SWEEP + 1),
+                "SweepCapExceeded"
+            );
+        });
+
+        it("Sweeps to the treasury until only the rent floor is left", async () => {
+            for (let i = 0; i < COLLECTED / MAX_SWEEP - 1; i++) {
+                await sweep("secureAdminSweep", SECURE_CONFIG, treasury.publicKey, MAX_SWEEP);
+            }
+
+            const floor = await provider.connection.getMinimumBalanceForRentExemption(0);
+            const vault = await provider.connection.getBalance(vaultPda(SECURE_CONFIG));
+            await expectError(
+                sweep("secureAdminSweep", SECURE_CONFIG, treasury.publicKey, vault),
+                "BelowRentFloor"
+            );
+
+            await sweep("secureAdminSweep", SECURE_CONFIG, treasury.publicKey, vault - floor);
+            expect(await provider.connection.getBalance(vaultPda(SECURE_CONFIG))).to.equal(floor);
+            expect(await provider.connection.getBalance(treasury.publicKey)).to.equal(COLLECTED - floor);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== PDA-Signed Sweep Drain ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  invoke_signed(&transfer(vault, destination, amount), .., &[vault_seeds])");
+            console.log("  Risk: destination and amount are the caller's; the PDA signs anything\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  require!(config.allowlist.contains(destination));");
+            console.log("  require!(amount <= config.max_sweep);");
+            console.log("  require!(vault.lamports() - amount >= rent.minimum_balance(0));");
+            console.log("  Protection: a leaked hot key moves at most max_sweep, only to the treasury\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  A PDA signature is the program's signature. Decide what it signs before you sign.");
+        });
+    });
+});
@@ -0,0 +1,394 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+declare_id!("4pSz83TD92rxciAojDaqMh5iSVDu4EJwKuBptgca2hkE");
+
+const BPS: u64 = 10_000;
+
+#[program]
+pub mod per_mint_risk_isolation {
+    use super::*;
+
+    /// Creates a market that lends SOL against listed SPL collateral.
+    pub fn initialize_market(ctx: Context<InitializeMarket>, market_id: u64) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        market.market_id = market_id;
+        market.admin = ctx.accounts.admin.key();
+        market.ltv_bps = 0;
+        market.liquidation_threshold_bps = 0;
+        market.bump = ctx.bumps.market;
+        Ok(())
+    }
+
+    /// Adds SOL for borrowers to draw.
+    pub fn fund(ctx: Context<Fund>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.funder.to_account_info(),
+            to: ctx.accounts.market.to_account_info(),
+        };
+        transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts), amount)
+    }
+
+    /// Lists `mint` as collateral at `price` lamports per base unit. Prices
+    /// are set by the admin; oracles are out of scope here.
+    pub fn list_mint(ctx: Context<ListMint>, price: u64) -> Result<()> {
+        let listing = &mut ctx.accounts.listing;
+        listing.market = ctx.accounts.market.key();
+        listing.mint = ctx.accounts.mint.key();
+        listing.vault = ctx.accounts.vault.key();
+        listing.price = price;
+        listing.bump = ctx.bumps.listing;
+        Ok(())
+    }
+
+    // VULNERABLE: One Set of Risk Parameters for Every Mint
+    // 1. LTV and liquidation threshold live on the market, not the mint
+    // 2. Listing a new mint means setting parameters for that mint, which
+    //    overwrites them for every mint already listed
+    // 3. Tuning the market for a stablecoin at 90% LTV lets a volatile
+    //    token listed at 25% borrow at 90% too
+    pub fn vulnerable_set_risk_params(
+        ctx: Context<SetGlobalRisk>,
+        ltv_bps: u64,
+        liquidation_threshold_bps: u64,
+    ) -> Result<()> {
+        check_risk_params(ltv_bps, liquidation_threshold_bps)?;
+        let market = &mut ctx.accounts.market;
+        market.ltv_bps = ltv_bps;
+        market.liquidation_threshold_bps = liquidation_threshold_bps;
+        msg!("Global risk params for every listed mint: LTV {} bps", ltv_bps);
+        Ok(())
+    }
+
+    // SECURE: Risk Config per Mint
+    // 1. Each mint's parameters live in a PDA derived from the market and
+    //    that mint
+    // 2. Creating a config for a new mint creates a new account; no other
+    //    mint's config is written
+    // 3. Borrowing re-derives the config from the listing's mint, so one
+    //    mint's config cannot be passed for another
+    pub fn secure_init_risk_config(
+        ctx: Context<InitRiskConfig>,
+        ltv_bps: u64,
+        liquidation_threshold_bps: u64,
+    ) -> Result<()> {
+        check_risk_params(ltv_bps, liquidation_threshold_bps)?;
+        let risk_config = &mut ctx.accounts.risk_config;
+        risk_config.market = ctx.accounts.market.key();
+        risk_config.mint = ctx.accounts.listing.mint;
+        risk_config.ltv_bps = ltv_bps;
+        risk_config.liquidation_threshold_bps = liquidation_threshold_bps;
+        risk_config.bump = ctx.bumps.risk_config;
+        msg!("Risk config for {}: LTV {} bps", risk_config.mint, ltv_bps);
+        Ok(())
+    }
+
+    pub fn deposit_collateral(ctx: Context<DepositCollateral>, amount: u64) -> Result<()> {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.owner_token_account.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let position = &mut ctx.accounts.position;
+        position.owner = ctx.accounts.owner.key();
+        position.listing = ctx.accounts.listing.key();
+        position.collateral = amount;
+        position.debt = 0;
+        position.bump = ctx.bumps.position;
+        Ok(())
+    }
+
+    pub fn vulnerable_borrow(ctx: Context<Borrow>, amount: u64) -> Result<()> {
+        let ltv_bps = ctx.accounts.market.ltv_bps;
+        ctx.accounts.draw(amount, ltv_bps)
+    }
+
+    pub fn secure_borrow(ctx: Context<SecureBorrow>, amount: u64) -> Result<()> {
+        let ltv_bps = ctx.accounts.risk_config.ltv_bps;
+        ctx.accounts.borrow.draw(amount, ltv_bps)
+    }
+}
+
+fn check_risk_params(ltv_bps: u64, liquidation_threshold_bps: u64) -> Result<()> {
+    require!(
+        ltv_bps < liquidation_threshold_bps && liquidation_threshold_bps <= BPS,
+        LendingError::InvalidRiskParams
+    );
+    Ok(())
+}
+
+/// Most SOL `collateral` base units at `price` lamports each can back.
+pub fn max_borrow(collateral: u64, price: u64, ltv_bps: u64) -> Option<u64> {
+    let value = (collateral as u128).checked_mul(price as u128)?;
+    u64::try_from(value.checked_mul(ltv_bps as u128)? / BPS as u128).ok()
+}
+
+impl<'info> Borrow<'info> {
+    fn draw(&mut self, amount: u64, ltv_bps: u64) -> Result<()> {
+        let position = &mut self.position;
+        let debt = position.debt.checked_add(amount).ok_or(LendingError::ArithmeticError)?;
+        let limit = max_borrow(position.collateral, self.listing.price, ltv_bps).ok_or(LendingError::ArithmeticError)?;
+        require!(debt <= limit, LendingError::ExceedsLtv);
+        position.debt = debt;
+
+        let market = self.market.to_account_info();
+        let owner = self.owner.to_account_info();
+        **market.try_borrow_mut_lamports()? = market
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(LendingError::InsufficientLiquidity)?;
+        **owner.try_borrow_mut_lamports()? = owner
+            .lamports()
+            .checked_add(amount)
+            .ok_or(LendingError::ArithmeticError)?;
+        msg!("Borrowed {} lamports: debt {} of limit {} at {} bps", amount, debt, limit, ltv_bps);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(market_id: u64)]
+pub struct InitializeMarket<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Market::INIT_SPACE,
+        seeds = [b"market", market_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub market: Account<'info, Market>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Fund<'info> {
+    #[account(mut, seeds = [b"market", market.market_id.to_le_bytes().as_ref()], bump = market.bump)]
+    pub market: Account<'info, Market>,
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ListMint<'info> {
+    #[account(
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+        has_one = admin @ LendingError::Unauthorized
+    )]
+    pub market: Account<'info, Market>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Listing::INIT_SPACE,
+        seeds = [b"listing", market.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = admin,
+        token::mint = mint,
+        token::authority = market,
+        seeds = [b"vault", listing.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct SetGlobalRisk<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+        has_one = admin @ LendingError::Unauthorized
+    )]
+    pub market: Account<'info, Market>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitRiskConfig<'info> {
+    #[account(
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+        has_one = admin @ LendingError::Unauthorized
+    )]
+    pub market: Account<'info, Market>,
+    #[account(
+        seeds = [b"listing", market.key().as_ref(), listing.mint.as_ref()],
+        bump = listing.bump,
+        has_one = market
+    )]
+    pub listing: Account<'info, Listing>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + RiskConfig::INIT_SPACE,
+        seeds = [b"risk", market.key().as_ref(), listing.mint.as_ref()],
+        bump
+    )]
+    pub risk_config: Account<'info, RiskConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositCollateral<'info> {
+    #[account(seeds = [b"market", market.market_id.to_le_bytes().as_ref()], bump = market.bump)]
+    pub market: Account<'info, Market>,
+    #[account(
+        seeds = [b"listing", market.key().as_ref(), listing.mint.as_ref()],
+        bump = listing.bump,
+        has_one = market,
+        has_one = vault
+    )]
+    pub listing: Account<'info, Listing>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [b"position", listing.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut, token::mint = listing.mint, token::authority = owner)]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Borrow<'info> {
+    #[account(mut, seeds = [b"market", market.market_id.to_le_bytes().as_ref()], bump = market.bump)]
+    pub market: Account<'info, Market>,
+    #[account(
+        seeds = [b"listing", market.key().as_ref(), listing.mint.as_ref()],
+        bump = listing.bump,
+        has_one = market
+    )]
+    pub listing: Account<'info, Listing>,
+    #[account(
+        mut,
+        seeds = [b"position", listing.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner,
+        has_one = listing
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SecureBorrow<'info> {
+    pub borrow: Borrow<'info>,
+    #[account(
+        seeds = [b"risk", borrow.market.key().as_ref(), borrow.listing.mint.as_ref()],
+        bump = risk_config.bump,
+        constraint = risk_config.market == borrow.market.key() @ LendingError::RiskConfigMismatch,
+        constraint = risk_config.mint == borrow.listing.mint @ LendingError::RiskConfigMismatch
+    )]
+    pub risk_config: Account<'info, RiskConfig>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Market {
+    pub market_id: u64,
+    pub admin: Pubkey,
+    /// Vulnerable path only: one LTV for every listed mint.
+    pub ltv_bps: u64,
+    /// Vulnerable path only: one liquidation threshold for every listed mint.
+    pub liquidation_threshold_bps: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Listing {
+    pub market: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    /// Lamports per base unit of `mint`.
+    pub price: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RiskConfig {
+    pub market: Pubkey,
+    pub mint: Pubkey,
+    pub ltv_bps: u64,
+    pub liquidation_threshold_bps: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub owner: Pubkey,
+    pub listing: Pubkey,
+    pub collateral: u64,
+    pub debt: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum LendingError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("LTV must be below the liquidation threshold, which must be at most 100%")]
+    InvalidRiskParams,
+    #[msg("Risk config belongs to a different market or mint")]
+    RiskConfigMismatch,
+    #[msg("Borrow exceeds the mint's LTV")]
+    ExceedsLtv,
+    #[msg("Insufficient liquidity")]
+    InsufficientLiquidity,
+    #[msg("Arithmetic operation failed (overflow/underflow)")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_borrow_scales_with_ltv() {
+        // 10 tokens (6 decimals) at 1,000 lamports per base unit = 10 SOL.
+        assert_eq!(max_borrow(10_000_000, 1_000, 2_500), Some(2_500_000_000));
+        assert_eq!(max_borrow(10_000_000, 1_000, 9_000), Some(9_000_000_000));
+        assert_eq!(max_borrow(u64::MAX, u64::MAX, BPS), None);
+    }
+
+    #[test]
+    fn risk_params_must_be_ordered() {
+        assert!(check_risk_params(2_500, 4_000).is_ok());
+        assert!(check_risk_params(4_000, 4_000).is_err());
+        assert!(check_risk_params(9_000, 10_001).is_err());
+    }
+}
@@ -0,0 +1,261 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, createAccount, createMint, mintTo } from "@solana/spl-token";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("60: Per-Mint Risk Isolation", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.PerMintRiskIsolation as Program<any>;
+
+    let admin: Keypair;
+    let mallory: Keypair;
+
+    let meme: PublicKey;
+    let stable: PublicKey;
+    let malloryMeme: PublicKey;
+
+    const VULNERABLE_MARKET = 1;
+    const SECURE_MARKET = 2;
+    const LIQUIDITY = 20 * LAMPORTS_PER_SOL;
+
+    // 1,000 lamports per base unit: 10 MEME (6 decimals) is worth 10 SOL.
+    const PRICE = 1_000;
+    const COLLATERAL = 10_000_000;
+    const COLLATERAL_VALUE = COLLATERAL * PRICE;
+
+    const MEME_RISK = { ltv: 2_500, threshold: 4_000 };
+    const STABLE_RISK = { ltv: 9_000, threshold: 9_500 };
+
+    function marketPda(marketId: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("market"), new anchor.BN(marketId).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        )[0];
+    }
+
+    function listingPda(marketId: number, mint: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("listing"), marketPda(marketId).toBuffer(), mint.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function vaultPda(marketId: number, mint: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("vault"), listingPda(marketId, mint).toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function riskConfigPda(marketId: number, mint: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("risk"), marketPda(marketId).toBuffer(), mint.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function positionPda(marketId: number, mint: PublicKey, owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("position"), listingPda(marketId, mint).toBuffer(), owner.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    async function listMint(marketId: number, mint: PublicKey) {
+        await program.methods
+            .listMint(new anchor.BN(PRICE))
+            .accounts({
+                market: marketPda(marketId),
+                listing: listingPda(marketId, mint),
+                mint,
+                vault: vaultPda(marketId, mint),
+                admin: admin.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                rent: SYSVAR_RENT_PUBKEY,
+            })
+            .signers([admin])
+            .rpc();
+    }
+
+    function setGlobalRisk(risk: { ltv: number; threshold: number }) {
+        return program.methods
+            .vulnerableSetRiskParams(new anchor.BN(risk.ltv), new anchor.BN(risk.threshold))
+            .accounts({ market: marketPda(VULNERABLE_MARKET), admin: admin.publicKey })
+            .signers([admin])
+            .rpc();
+    }
+
+    function initRiskConfig(mint: PublicKey, risk: { ltv: number; threshold: number }) {
+        return program.methods
+            .secureInitRiskConfig(new anchor.BN(risk.ltv), new anchor.BN(risk.threshold))
+            .accounts({
+                market: marketPda(SECURE_MARKET),
+                listing: listingPda(SECURE_MARKET, mint),
+                riskConfig: riskConfigPda(SECURE_MARKET, mint),
+                admin: admin.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([admin])
+            .rpc();
+    }
+
+    function depositMeme(marketId: number) {
+        return program.methods
+            .depositCollateral(new anchor.BN(COLLATERAL))
+            .accounts({
+                market: marketPda(marketId),
+                listing: listingPda(marketId, meme),
+                vault: vaultPda(marketId, meme),
+                position: positionPda(marketId, meme, mallory.publicKey),
+                ownerTokenAccount: malloryMeme,
+                owner: mallory.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([mallory])
+            .rpc();
+    }
+
+    function borrowAccounts(marketId: number) {
+        return {
+            market: marketPda(marketId),
+            listing: listingPda(marketId, meme),
+            position: positionPda(marketId, meme, mallory.publicKey),
+            owner: mallory.publicKey,
+        };
+    }
+
+    function vulnerableBorrow(amount: number) {
+        return program.methods
+            .vulnerableBorrow(new anchor.BN(amount))
+            .accounts(borrowAccounts(VULNERABLE_MARKET))
+            .signers([mallory])
+            .rpc();
+    }
+
+    function secureBorrow(amount: number, riskConfig = riskConfigPda(SECURE_MARKET, meme)) {
+        return program.methods
+            .secureBorrow(new anchor.BN(amount))
+            .accounts({ borrow: borrowAccounts(SECURE_MARKET), riskConfig })
+            .signers([mallory])
+            .rpc();
+    }
+
+    function ltvAmount(ltvBps: number): number {
+        return (COLLATERAL_VALUE * ltvBps) / 10_000;
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        mallory = Keypair.generate();
+
+        for (const kp of [admin, mallory]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 50 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Two funded markets list MEME, a volatile token, at 25% LTV", async () => {
+            meme = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+            stable = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+            malloryMeme = await createAccount(provider.connection, mallory, meme, mallory.publicKey);
+            await mintTo(provider.connection, admin, meme, malloryMeme, admin, 2 * COLLATERAL);
+
+            for (const marketId of [VULNERABLE_MARKET, SECURE_MARKET]) {
+                await program.methods
+                    .initializeMarket(new anchor.BN(marketId))
+                    .accounts({ market: marketPda(marketId), admin: admin.publicKey, systemProgram: SystemProgram.programId })
+                    .signers([admin])
+                    .rpc();
+                await program.methods
+                    .fund(new anchor.BN(LIQUIDITY))
+                    .accounts({ market: marketPda(marketId), funder: admin.publicKey, systemProgram: SystemProgram.programId })
+                    .signers([admin])
+                    .rpc();
+                await listMint(marketId, meme);
+            }
+
+            await setGlobalRisk(MEME_RISK);
+            await initRiskConfig(meme, MEME_RISK);
+        });
+
+        it("Mallory deposits 10 SOL worth of MEME in each market", async () => {
+            await depositMeme(VULNERABLE_MARKET);
+            await depositMeme(SECURE_MARKET);
+        });
+    });
+
+    describe("Exploit: listing a stablecoin loosens MEME's LTV", () => {
+        it("At 25% LTV, Mallory can borrow 2.5 SOL and no more", async () => {
+            await expectError(vulnerableBorrow(ltvAmount(MEME_RISK.ltv) + 1), "ExceedsLtv");
+            await vulnerableBorrow(ltvAmount(MEME_RISK.ltv));
+        });
+
+        it("The admin lists a stablecoin at 90% LTV, overwriting the global parameters", async () => {
+            await listMint(VULNERABLE_MARKET, stable);
+            await setGlobalRisk(STABLE_RISK);
+
+            const market = await program.account.market.fetch(marketPda(VULNERABLE_MARKET));
+            expect(market.ltvBps.toNumber()).to.equal(STABLE_RISK.ltv);
+        });
+
+        it("Mallory now borrows against MEME at 90%", async () => {
+            const extra = ltvAmount(STABLE_RISK.ltv) - ltvAmount(MEME_RISK.ltv);
+            await vulnerableBorrow(extra);
+
+            const position = await program.account.position.fetch(positionPda(VULNERABLE_MARKET, meme, mallory.publicKey));
+            console.log(`  Debt against 10 SOL of MEME: ${position.debt.toNumber() / LAMPORTS_PER_SOL} SOL`);
+            expect(position.debt.toNumber()).to.equal(ltvAmount(STABLE_RISK.ltv));
+        });
+    });
+
+    describe("Secure: each mint's risk config is its own PDA", () => {
+        it("At 25% LTV, Mallory can borrow 2.5 SOL and no more", async () => {
+            await expectError(secureBorrow(ltvAmount(MEME_RISK.ltv) + 1), "ExceedsLtv");
+            await secureBorrow(ltvAmount(MEME_RISK.ltv));
+        });
+
+        it("Listing a stablecoin at 90% leaves MEME's config untouched", async () => {
+            const before = await program.account.riskConfig.fetch(riskConfigPda(SECURE_MARKET, meme));
+
+            await listMint(SECURE_MARKET, stable);
+            await initRiskConfig(stable, STABLE_RISK);
+
+            const after = await program.account.riskConfig.fetch(riskConfigPda(SECURE_MARKET, meme));
+            expect(after.ltvBps.toNumber()).to.equal(before.ltvBps.toNumber());
+            expect(after.liquidationThresholdBps.toNumber()).to.equal(before.liquidationThresholdBps.toNumber());
+            expect(after.ltvBps.toNumber()).to.equal(MEME_RISK.ltv);
+        });
+
+        it("MEME still borrows at 25%", async () => {
+            await expectError(secureBorrow(1), "ExceedsLtv");
+        });
+
+        it("The stablecoin's config cannot be passed for MEME", async () => {
+            await expectError(secureBorrow(1, riskConfigPda(SECURE_MARKET, stable)), "ConstraintSeeds");
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Per-Mint Risk Isolation ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  market.ltv_bps  // one value for every listed mint");
+            console.log("  Risk: configuring a new listing rewrites the risk of every existing one\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  seeds = [b\"risk\", market, listing.mint]  // one RiskConfig PDA per mint");
+            console.log("  Protection: a new mint gets a new account; existing configs are never written\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Risk is a property of the asset. Store it where the asset is the key.");
+        });
+    });
+});
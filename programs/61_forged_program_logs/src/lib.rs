@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("4DUKgrc2aCo6y1Svijz2GruAUzX5kaFWvS9BATxEDpex");
+
+#[program]
+pub mod forged_program_logs {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.total_deposited = 0;
+        vault.bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    // VULNERABLE: Deposit Announced with msg!
+    // 1. The deposit is reported as a plain log line that an off-chain
+    //    indexer scans for and credits
+    // 2. `msg!` output is text in the transaction's log list; any program in
+    //    any transaction can print the same line
+    // 3. The log gives no verifiable link back to this program, so a forged
+    //    line from another program is indistinguishable to a text parser
+    pub fn vulnerable_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        ctx.accounts.deposit(amount)?;
+        msg!("Deposit: depositor={} amount={}", ctx.accounts.depositor.key, amount);
+        Ok(())
+    }
+
+    // SECURE: Deposit Recorded as a Self-CPI Event
+    // 1. `emit_cpi!` invokes this program with the event as instruction data,
+    //    signed by the `__event_authority` PDA
+    // 2. The event lands in the transaction's inner instructions, attributed
+    //    to this program id, instead of in the logs
+    // 3. Only this program can sign for its event authority, so an inner
+    //    instruction to this program carrying the event tag is proof of origin
+    pub fn secure_deposit(ctx: Context<SecureDeposit>, amount: u64) -> Result<()> {
+        ctx.accounts.deposit.deposit(amount)?;
+        emit_cpi!(DepositEvent {
+            depositor: ctx.accounts.deposit.depositor.key(),
+            amount,
+            total_deposited: ctx.accounts.deposit.vault.total_deposited,
+        });
+        Ok(())
+    }
+}
+
+impl<'info> Deposit<'info> {
+    /// Moves `amount` lamports from the depositor into the vault.
+    fn deposit(&mut self, amount: u64) -> Result<()> {
+        require!(amount > 0, LogError::ZeroAmount);
+
+        let cpi_accounts = Transfer {
+            from: self.depositor.to_account_info(),
+            to: self.vault.to_account_info(),
+        };
+        transfer(CpiContext::new(self.system_program.to_account_info(), cpi_accounts), amount)?;
+
+        self.vault.total_deposited = self
+            .vault
+            .total_deposited
+            .checked_add(amount)
+            .ok_or(LogError::ArithmeticError)?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = payer, space = 8 + Vault::INIT_SPACE, seeds = [b"vault"], bump)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SecureDeposit<'info> {
+    pub deposit: Deposit<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub total_deposited: u64,
+    pub bump: u8,
+}
+
+#[event]
+pub struct DepositEvent {
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub total_deposited: u64,
+}
+
+#[error_code]
+pub enum LogError {
+    #[msg("Amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("Arithmetic error")]
+    ArithmeticError,
+}
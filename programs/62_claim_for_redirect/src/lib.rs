@@ -0,0 +1,291 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use security_common::math::mul_div_floor;
+
+declare_id!("58WxiAaw7cPchg8rQBXibxFkmeMSjGc9TiXuz9QF2wh6");
+
+/// Largest tip a pool may pay a claim-for caller: 1% of the claim.
+pub const MAX_TIP_BPS: u16 = 100;
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+#[program]
+pub mod claim_for_redirect {
+    use super::*;
+
+    pub fn initialize_pool(ctx: Context<InitializePool>, tip_bps: u16) -> Result<()> {
+        require!(tip_bps <= MAX_TIP_BPS, ClaimError::InvalidTip);
+
+        let pool = &mut ctx.accounts.pool;
+        pool.admin = ctx.accounts.admin.key();
+        pool.reward_mint = ctx.accounts.reward_mint.key();
+        pool.vault = ctx.accounts.vault.key();
+        pool.tip_bps = tip_bps;
+        pool.bump = ctx.bumps.pool;
+        Ok(())
+    }
+
+    pub fn open_position(ctx: Context<OpenPosition>) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.owner = ctx.accounts.owner.key();
+        position.pool = ctx.accounts.pool.key();
+        position.pending_rewards = 0;
+        position.bump = ctx.bumps.position;
+        Ok(())
+    }
+
+    /// Funds the vault and credits `amount` of rewards to a position. Stands
+    /// in for whatever accrual the pool runs.
+    pub fn credit_rewards(ctx: Context<CreditRewards>, amount: u64) -> Result<()> {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.admin_token_account.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.admin.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let position = &mut ctx.accounts.position;
+        position.pending_rewards = position
+            .pending_rewards
+            .checked_add(amount)
+            .ok_or(ClaimError::ArithmeticError)?;
+        Ok(())
+    }
+
+    // VULNERABLE: Claim-For Pays the Caller
+    // 1. Anyone may claim a position's rewards, so compounding bots can
+    //    harvest for their users
+    // 2. The payout goes to a `destination` token account the caller picks;
+    //    only its mint is checked
+    // 3. Any caller claims any position into their own account
+    pub fn vulnerable_claim_for(ctx: Context<VulnerableClaimFor>) -> Result<()> {
+        let amount = ctx.accounts.claim.take_pending()?;
+        ctx.accounts
+            .claim
+            .pay(ctx.accounts.destination.to_account_info(), amount)
+    }
+
+    // SECURE: Claim-For Pays the Owner's ATA
+    // 1. Still permissionless to call
+    // 2. The payout goes to the position owner's associated token account,
+    //    derived from `position.owner` and the reward mint
+    // 3. A caller who passes their own token account receives a tip of
+    //    `pool.tip_bps`, capped at MAX_TIP_BPS, taken from the claim and
+    //    rounded down; the owner receives the rest
+    pub fn secure_claim_for(ctx: Context<SecureClaimFor>) -> Result<()> {
+        let pending = ctx.accounts.claim.take_pending()?;
+        let tip_bps = match ctx.accounts.tip_account {
+            Some(_) => ctx.accounts.claim.pool.tip_bps,
+            None => 0,
+        };
+        let (owner_amount, tip) = split_claim(pending, tip_bps).ok_or(ClaimError::ArithmeticError)?;
+
+        ctx.accounts
+            .claim
+            .pay(ctx.accounts.owner_token_account.to_account_info(), owner_amount)?;
+        if let Some(tip_account) = &ctx.accounts.tip_account {
+            ctx.accounts.claim.pay(tip_account.to_account_info(), tip)?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits a claim into the owner's share and the caller's tip. The tip rounds
+/// down, so the remainder stays with the owner.
+fn split_claim(pending: u64, tip_bps: u16) -> Option<(u64, u64)> {
+    if tip_bps > MAX_TIP_BPS {
+        return None;
+    }
+    let tip = mul_div_floor(pending, tip_bps as u64, BPS_DENOMINATOR)?;
+    Some((pending - tip, tip))
+}
+
+impl<'info> Claim<'info> {
+    /// Zeroes the position's pending rewards and returns them.
+    fn take_pending(&mut self) -> Result<u64> {
+        let pending = self.position.pending_rewards;
+        require!(pending > 0, ClaimError::NothingToClaim);
+        self.position.pending_rewards = 0;
+        Ok(pending)
+    }
+
+    /// Transfers `amount` from the vault to `to`, signed by the pool.
+    fn pay(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let reward_mint = self.pool.reward_mint;
+        let seeds: &[&[u8]] = &[b"pool", reward_mint.as_ref(), &[self.pool.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.vault.to_account_info(),
+                    to,
+                    authority: self.pool.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Pool::INIT_SPACE,
+        seeds = [b"pool", reward_mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    pub reward_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = admin,
+        token::mint = reward_mint,
+        token::authority = pool,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(seeds = [b"pool", pool.reward_mint.as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [b"position", pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreditRewards<'info> {
+    #[account(
+        seeds = [b"pool", pool.reward_mint.as_ref()],
+        bump = pool.bump,
+        has_one = admin @ ClaimError::Unauthorized,
+        has_one = vault
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(mut, has_one = pool)]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub admin_token_account: Account<'info, TokenAccount>,
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Accounts shared by both claim-for paths. `caller` is whoever sends the
+/// transaction, not necessarily the owner.
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(seeds = [b"pool", pool.reward_mint.as_ref()], bump = pool.bump, has_one = vault)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mut,
+        seeds = [b"position", pool.key().as_ref(), position.owner.as_ref()],
+        bump = position.bump,
+        has_one = pool
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    pub caller: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableClaimFor<'info> {
+    pub claim: Claim<'info>,
+    #[account(mut, token::mint = claim.pool.reward_mint)]
+    pub destination: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SecureClaimFor<'info> {
+    pub claim: Claim<'info>,
+    #[account(
+        mut,
+        associated_token::mint = claim.pool.reward_mint,
+        associated_token::authority = claim.position.owner
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    /// The caller's account for the tip; omit to pay the owner everything.
+    #[account(mut, token::mint = claim.pool.reward_mint, token::authority = claim.caller)]
+    pub tip_account: Option<Account<'info, TokenAccount>>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub admin: Pubkey,
+    pub reward_mint: Pubkey,
+    pub vault: Pubkey,
+    /// Share of each claim-for paid to the caller, in basis points.
+    pub tip_bps: u16,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub pending_rewards: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum ClaimError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Tip exceeds the maximum")]
+    InvalidTip,
+    #[msg("No rewards to claim")]
+    NothingToClaim,
+    #[msg("Arithmetic error")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tip_is_bounded_and_rounds_toward_the_owner() {
+        assert_eq!(split_claim(1_000_000, 100), Some((990_000, 10_000)));
+        assert_eq!(split_claim(1_000_000, 0), Some((1_000_000, 0)));
+        // 99 * 1% = 0.99, rounded down: the owner keeps the fraction.
+        assert_eq!(split_claim(99, 100), Some((99, 0)));
+        assert_eq!(split_claim(1_000_000, MAX_TIP_BPS + 1), None);
+
+        for pending in [1, 7, 10_001, u64::MAX] {
+            let (owner, tip) = split_claim(pending, MAX_TIP_BPS).unwrap();
+            assert_eq!(owner + tip, pending);
+            assert!(tip <= pending / 100);
+        }
+    }
+}
@@ -0,0 +1,194 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import {
+    TOKEN_PROGRAM_ID,
+    createAccount,
+    createAssociatedTokenAccount,
+    createMint,
+    getAccount,
+    mintTo,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("62: Claim-For Payout Redirect", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ClaimForRedirect as Program<any>;
+
+    let admin: Keypair;
+    let alice: Keypair;
+    let keeper: Keypair;
+    let mallory: Keypair;
+
+    let rewardMint: PublicKey;
+    let adminTokens: PublicKey;
+    let aliceAta: PublicKey;
+    let keeperTokens: PublicKey;
+    let malloryTokens: PublicKey;
+
+    let pool: PublicKey;
+    let vault: PublicKey;
+    let position: PublicKey;
+
+    const TIP_BPS = 100;
+    const REWARDS = 100_000_000;
+    const TIP = (REWARDS * TIP_BPS) / 10_000;
+
+    async function balance(account: PublicKey): Promise<number> {
+        return Number((await getAccount(provider.connection, account)).amount);
+    }
+
+    function creditAlice(amount: number) {
+        return program.methods
+            .creditRewards(new anchor.BN(amount))
+            .accounts({
+                pool,
+                position,
+                vault,
+                adminTokenAccount: adminTokens,
+                admin: admin.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([admin])
+            .rpc();
+    }
+
+    function claimAccounts(caller: Keypair) {
+        return { pool, position, vault, caller: caller.publicKey, tokenProgram: TOKEN_PROGRAM_ID };
+    }
+
+    function vulnerableClaimFor(caller: Keypair, destination: PublicKey) {
+        return program.methods
+            .vulnerableClaimFor()
+            .accounts({ claim: claimAccounts(caller), destination })
+            .signers([caller])
+            .rpc();
+    }
+
+    function secureClaimFor(caller: Keypair, ownerTokenAccount: PublicKey, tipAccount: PublicKey | null) {
+        return program.methods
+            .secureClaimFor()
+            .accounts({ claim: claimAccounts(caller), ownerTokenAccount, tipAccount })
+            .signers([caller])
+            .rpc();
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        alice = Keypair.generate();
+        keeper = Keypair.generate();
+        mallory = Keypair.generate();
+
+        for (const kp of [admin, alice, keeper, mallory]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Creates a reward pool with a 1% claim-for tip and a position for Alice", async () => {
+            rewardMint = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+            adminTokens = await createAccount(provider.connection, admin, rewardMint, admin.publicKey);
+            await mintTo(provider.connection, admin, rewardMint, adminTokens, admin, 10 * REWARDS);
+
+            aliceAta = await createAssociatedTokenAccount(provider.connection, alice, rewardMint, alice.publicKey);
+            keeperTokens = await createAccount(provider.connection, keeper, rewardMint, keeper.publicKey);
+            malloryTokens = await createAccount(provider.connection, mallory, rewardMint, mallory.publicKey);
+
+            [pool] = PublicKey.findProgramAddressSync([Buffer.from("pool"), rewardMint.toBuffer()], program.programId);
+            [vault] = PublicKey.findProgramAddressSync([Buffer.from("vault"), pool.toBuffer()], program.programId);
+            [position] = PublicKey.findProgramAddressSync(
+                [Buffer.from("position"), pool.toBuffer(), alice.publicKey.toBuffer()],
+                program.programId
+            );
+
+            await program.methods
+                .initializePool(TIP_BPS)
+                .accounts({
+                    pool,
+                    rewardMint,
+                    vault,
+                    admin: admin.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                    rent: SYSVAR_RENT_PUBKEY,
+                })
+                .signers([admin])
+                .rpc();
+
+            await program.methods
+                .openPosition()
+                .accounts({ pool, position, owner: alice.publicKey, systemProgram: SystemProgram.programId })
+                .signers([alice])
+                .rpc();
+        });
+    });
+
+    describe("Exploit: the caller names the payout account", () => {
+        it("Mallory claims Alice's 100 tokens into Mallory's own account", async () => {
+            await creditAlice(REWARDS);
+            await vulnerableClaimFor(mallory, malloryTokens);
+
+            console.log(`  Mallory received ${(await balance(malloryTokens)) / 1e6} tokens of Alice's rewards`);
+            expect(await balance(malloryTokens)).to.equal(REWARDS);
+            expect(await balance(aliceAta)).to.equal(0);
+
+            const state = await program.account.position.fetch(position);
+            expect(state.pendingRewards.toNumber()).to.equal(0);
+        });
+    });
+
+    describe("Secure: payout to the owner's ATA, bounded tip to the caller", () => {
+        it("Rejects Mallory's account as the owner's payout account", async () => {
+            await creditAlice(REWARDS);
+            await expectError(secureClaimFor(mallory, malloryTokens, malloryTokens), "ConstraintTokenOwner");
+        });
+
+        it("A keeper claims for Alice: 99 tokens to Alice, a 1-token tip to the keeper", async () => {
+            await secureClaimFor(keeper, aliceAta, keeperTokens);
+
+            expect(await balance(aliceAta)).to.equal(REWARDS - TIP);
+            expect(await balance(keeperTokens)).to.equal(TIP);
+        });
+
+        it("The tip rounds down: a 99-unit claim pays the keeper nothing", async () => {
+            const before = await balance(aliceAta);
+            await creditAlice(99);
+            await secureClaimFor(keeper, aliceAta, keeperTokens);
+
+            expect(await balance(aliceAta)).to.equal(before + 99);
+            expect(await balance(keeperTokens)).to.equal(TIP);
+        });
+
+        it("Without a tip account, Alice receives the full claim", async () => {
+            const before = await balance(aliceAta);
+            await creditAlice(REWARDS);
+            await secureClaimFor(keeper, aliceAta, null);
+
+            expect(await balance(aliceAta)).to.equal(before + REWARDS);
+            expect(await balance(keeperTokens)).to.equal(TIP);
+            expect(await balance(vault)).to.equal(0);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Claim-For Payout Redirect ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  #[account(mut, token::mint = reward_mint)] destination  // chosen by the caller");
+            console.log("  Risk: anyone can claim anyone's rewards into their own account\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  associated_token::authority = position.owner  // payout");
+            console.log("  tip = floor(claim * pool.tip_bps / 10_000), tip_bps <= MAX_TIP_BPS");
+            console.log("  Protection: the caller can only earn the tip; the owner always gets the rest\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Permissionless to call does not mean the caller chooses where the money goes.");
+        });
+    });
+});
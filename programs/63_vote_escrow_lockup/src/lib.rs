@@ -0,0 +1,391 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use security_common::math::mul_div_floor;
+use security_common::time::{ClockExt, UnixTs};
+
+declare_id!("GdgCWB2t799g2oQNKCKXGkcybJUerBb26mQQVb7FPi2i");
+
+/// Longest lock, four years. A lock this long gets one vote per token.
+pub const MAX_LOCK_SECS: i64 = 4 * 365 * 86_400;
+
+#[program]
+pub mod vote_escrow_lockup {
+    use super::*;
+
+    pub fn initialize_registrar(ctx: Context<InitializeRegistrar>, registrar_id: u64) -> Result<()> {
+        let registrar = &mut ctx.accounts.registrar;
+        registrar.registrar_id = registrar_id;
+        registrar.mint = ctx.accounts.mint.key();
+        registrar.vault = ctx.accounts.vault.key();
+        registrar.bump = ctx.bumps.registrar;
+        Ok(())
+    }
+
+    pub fn create_proposal(ctx: Context<CreateProposal>, proposal_id: u64) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.registrar = ctx.accounts.registrar.key();
+        proposal.proposal_id = proposal_id;
+        proposal.yes_votes = 0;
+        proposal.bump = ctx.bumps.proposal;
+        Ok(())
+    }
+
+    /// Locks `amount` tokens until `lock_end`. Voting power starts at
+    /// `amount * remaining / MAX_LOCK_SECS` and decays to zero at `lock_end`.
+    pub fn create_lock(ctx: Context<CreateLock>, amount: u64, lock_end: i64) -> Result<()> {
+        let now = Clock::get()?.current_unix_ts();
+        require!(amount > 0, EscrowError::ZeroAmount);
+        check_lock_end(lock_end, now)?;
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.owner_token_account.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let lock = &mut ctx.accounts.lock;
+        lock.owner = ctx.accounts.owner.key();
+        lock.registrar = ctx.accounts.registrar.key();
+        lock.amount = amount;
+        lock.lock_end = lock_end;
+        lock.bump = ctx.bumps.lock;
+        lock.checkpoint(now)
+    }
+
+    // VULNERABLE: Lock End Is Freely Editable
+    // 1. The owner can move `lock_end` anywhere, including earlier or to now
+    // 2. `voting_power` is not recomputed, so the power earned by the
+    //    original four-year lock stays on the account
+    // 3. Withdrawal only checks `lock_end`, so shortening the lock unlocks
+    //    the tokens early, and the stale power survives the withdrawal
+    pub fn vulnerable_set_lock_end(ctx: Context<ModifyLock>, new_end: i64) -> Result<()> {
+        ctx.accounts.lock.lock_end = new_end;
+        Ok(())
+    }
+
+    pub fn vulnerable_withdraw(ctx: Context<Withdraw>) -> Result<()> {
+        let now = Clock::get()?.current_unix_ts();
+        require!(now >= UnixTs(ctx.accounts.lock.lock_end), EscrowError::LockNotExpired);
+
+        let amount = ctx.accounts.lock.amount;
+        ctx.accounts.pay_out(amount)?;
+        ctx.accounts.lock.amount = 0;
+        Ok(())
+    }
+
+    /// Votes with the power stored on the lock.
+    pub fn vulnerable_vote(ctx: Context<Vote>) -> Result<()> {
+        let power = ctx.accounts.lock.voting_power;
+        ctx.accounts.record_vote(power)
+    }
+
+    // SECURE: Monotonic Extension and Checkpointed Power
+    // 1. A lock can only be extended, never shortened, and never past
+    //    MAX_LOCK_SECS from now
+    // 2. Every change to a lock writes a fresh checkpoint of its voting
+    //    power; withdrawal zeroes both the amount and the power
+    // 3. Votes use the power recomputed from amount and `lock_end` at the
+    //    moment of voting, so decay is always applied
+    pub fn secure_extend_lock(ctx: Context<ModifyLock>, new_end: i64) -> Result<()> {
+        let now = Clock::get()?.current_unix_ts();
+        let lock = &mut ctx.accounts.lock;
+        require!(new_end > lock.lock_end, EscrowError::LockNotExtended);
+        check_lock_end(new_end, now)?;
+
+        lock.lock_end = new_end;
+        lock.checkpoint(now)
+    }
+
+    pub fn secure_withdraw(ctx: Context<Withdraw>) -> Result<()> {
+        let now = Clock::get()?.current_unix_ts();
+        require!(now >= UnixTs(ctx.accounts.lock.lock_end), EscrowError::LockNotExpired);
+
+        let amount = ctx.accounts.lock.amount;
+        ctx.accounts.pay_out(amount)?;
+        let lock = &mut ctx.accounts.lock;
+        lock.amount = 0;
+        lock.checkpoint(now)
+    }
+
+    pub fn secure_vote(ctx: Context<Vote>) -> Result<()> {
+        let now = Clock::get()?.current_unix_ts();
+        ctx.accounts.lock.checkpoint(now)?;
+
+        let power = ctx.accounts.lock.voting_power;
+        require!(power > 0, EscrowError::NoVotingPower);
+        ctx.accounts.record_vote(power)
+    }
+}
+
+/// Voting power of `amount` tokens locked until `lock_end`, as of `now`.
+/// Linear in the remaining lock time; zero once the lock has ended.
+fn voting_power(amount: u64, lock_end: i64, now: UnixTs) -> Option<u64> {
+    let remaining = UnixTs(lock_end).secs_since(now).unwrap_or(0);
+    mul_div_floor(amount, remaining as u64, MAX_LOCK_SECS as u64)
+}
+
+fn check_lock_end(lock_end: i64, now: UnixTs) -> Result<()> {
+    let max_end = now.checked_add_secs(MAX_LOCK_SECS).ok_or(EscrowError::ArithmeticError)?;
+    require!(
+        UnixTs(lock_end) > now && UnixTs(lock_end) <= max_end,
+        EscrowError::InvalidLockEnd
+    );
+    Ok(())
+}
+
+impl Lock {
+    /// Recomputes `voting_power` as of `now` and records when.
+    fn checkpoint(&mut self, now: UnixTs) -> Result<()> {
+        self.voting_power = voting_power(self.amount, self.lock_end, now).ok_or(EscrowError::ArithmeticError)?;
+        self.checkpoint_ts = now.0;
+        Ok(())
+    }
+}
+
+impl<'info> Withdraw<'info> {
+    /// Returns `amount` locked tokens to the owner, signed by the registrar.
+    fn pay_out(&self, amount: u64) -> Result<()> {
+        let registrar_id = self.registrar.registrar_id.to_le_bytes();
+        let seeds: &[&[u8]] = &[b"registrar", registrar_id.as_ref(), &[self.registrar.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.vault.to_account_info(),
+                    to: self.owner_token_account.to_account_info(),
+                    authority: self.registrar.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+        )
+    }
+}
+
+impl<'info> Vote<'info> {
+    fn record_vote(&mut self, power: u64) -> Result<()> {
+        let proposal = &mut self.proposal;
+        proposal.yes_votes = proposal
+            .yes_votes
+            .checked_add(power)
+            .ok_or(EscrowError::ArithmeticError)?;
+
+        let record = &mut self.vote_record;
+        record.voter = self.owner.key();
+        record.power = power;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(registrar_id: u64)]
+pub struct InitializeRegistrar<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Registrar::INIT_SPACE,
+        seeds = [b"registrar", registrar_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub registrar: Account<'info, Registrar>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = payer,
+        token::mint = mint,
+        token::authority = registrar,
+        seeds = [b"vault", registrar.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_id: u64)]
+pub struct CreateProposal<'info> {
+    #[account(seeds = [b"registrar", registrar.registrar_id.to_le_bytes().as_ref()], bump = registrar.bump)]
+    pub registrar: Account<'info, Registrar>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Proposal::INIT_SPACE,
+        seeds = [b"proposal", registrar.key().as_ref(), proposal_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateLock<'info> {
+    #[account(
+        seeds = [b"registrar", registrar.registrar_id.to_le_bytes().as_ref()],
+        bump = registrar.bump,
+        has_one = vault
+    )]
+    pub registrar: Account<'info, Registrar>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Lock::INIT_SPACE,
+        seeds = [b"lock", registrar.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub lock: Account<'info, Lock>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = registrar.mint, token::authority = owner)]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ModifyLock<'info> {
+    #[account(seeds = [b"registrar", registrar.registrar_id.to_le_bytes().as_ref()], bump = registrar.bump)]
+    pub registrar: Account<'info, Registrar>,
+    #[account(
+        mut,
+        seeds = [b"lock", registrar.key().as_ref(), owner.key().as_ref()],
+        bump = lock.bump,
+        has_one = owner @ EscrowError::Unauthorized
+    )]
+    pub lock: Account<'info, Lock>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        seeds = [b"registrar", registrar.registrar_id.to_le_bytes().as_ref()],
+        bump = registrar.bump,
+        has_one = vault
+    )]
+    pub registrar: Account<'info, Registrar>,
+    #[account(
+        mut,
+        seeds = [b"lock", registrar.key().as_ref(), owner.key().as_ref()],
+        bump = lock.bump,
+        has_one = owner @ EscrowError::Unauthorized
+    )]
+    pub lock: Account<'info, Lock>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = registrar.mint)]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Vote<'info> {
+    #[account(seeds = [b"registrar", registrar.registrar_id.to_le_bytes().as_ref()], bump = registrar.bump)]
+    pub registrar: Account<'info, Registrar>,
+    #[account(
+        mut,
+        seeds = [b"lock", registrar.key().as_ref(), owner.key().as_ref()],
+        bump = lock.bump,
+        has_one = owner @ EscrowError::Unauthorized
+    )]
+    pub lock: Account<'info, Lock>,
+    #[account(mut, has_one = registrar)]
+    pub proposal: Account<'info, Proposal>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + VoteRecord::INIT_SPACE,
+        seeds = [b"vote", proposal.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Registrar {
+    pub registrar_id: u64,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Lock {
+    pub owner: Pubkey,
+    pub registrar: Pubkey,
+    pub amount: u64,
+    pub lock_end: i64,
+    /// Voting power as of `checkpoint_ts`.
+    pub voting_power: u64,
+    pub checkpoint_ts: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Proposal {
+    pub registrar: Pubkey,
+    pub proposal_id: u64,
+    pub yes_votes: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct VoteRecord {
+    pub voter: Pubkey,
+    pub power: u64,
+}
+
+#[error_code]
+pub enum EscrowError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("Lock end must be in the future and within the maximum lock")]
+    InvalidLockEnd,
+    #[msg("New lock end must be later than the current one")]
+    LockNotExtended,
+    #[msg("Lock has not expired")]
+    LockNotExpired,
+    #[msg("No voting power")]
+    NoVotingPower,
+    #[msg("Arithmetic error")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn voting_power_decays_linearly_to_zero() {
+        let now = UnixTs(1_700_000_000);
+        let amount = 1_000_000;
+
+        assert_eq!(voting_power(amount, now.0 + MAX_LOCK_SECS, now), Some(amount));
+        assert_eq!(voting_power(amount, now.0 + MAX_LOCK_SECS / 4, now), Some(amount / 4));
+        assert_eq!(voting_power(amount, now.0, now), Some(0));
+        assert_eq!(voting_power(amount, now.0 - 1, now), Some(0));
+    }
+}
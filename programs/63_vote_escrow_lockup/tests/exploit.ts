@@ -0,0 +1,266 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, createAccount, createMint, getAccount, mintTo } from "@solana/spl-token";
+import { expect } from "chai";
+import { expectError, sleep } from "../../../tests/utils";
+
+describe("63: Vote-Escrow Lockup Bypass", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.VoteEscrowLockup as Program<any>;
+
+    let alice: Keypair;
+    let mallory: Keypair;
+
+    let mint: PublicKey;
+    let aliceTokens: PublicKey;
+    let malloryTokens: PublicKey;
+
+    const VULNERABLE_REGISTRAR = 1;
+    const SECURE_REGISTRAR = 2;
+    const PROPOSAL_ID = 1;
+    const MAX_LOCK_SECS = 4 * 365 * 86_400;
+    // Keeps requested lock ends inside the maximum despite clock drift between fetch and execution.
+    const MARGIN_SECS = 60;
+
+    const LOCKED = 1_000_000_000;
+
+    async function chainNow(): Promise<number> {
+        const slot = await provider.connection.getSlot();
+        return (await provider.connection.getBlockTime(slot))!;
+    }
+
+    function registrarPda(registrarId: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("registrar"), new anchor.BN(registrarId).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        )[0];
+    }
+
+    function vaultPda(registrarId: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("vault"), registrarPda(registrarId).toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function lockPda(registrarId: number, owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("lock"), registrarPda(registrarId).toBuffer(), owner.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function proposalPda(registrarId: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [
+                Buffer.from("proposal"),
+                registrarPda(registrarId).toBuffer(),
+                new anchor.BN(PROPOSAL_ID).toArrayLike(Buffer, "le", 8),
+            ],
+            program.programId
+        )[0];
+    }
+
+    function voteRecordPda(registrarId: number, owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("vote"), proposalPda(registrarId).toBuffer(), owner.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function createLock(registrarId: number, owner: Keypair, ownerTokens: PublicKey, amount: number, lockEnd: number) {
+        return program.methods
+            .createLock(new anchor.BN(amount), new anchor.BN(lockEnd))
+            .accounts({
+                registrar: registrarPda(registrarId),
+                lock: lockPda(registrarId, owner.publicKey),
+                vault: vaultPda(registrarId),
+                ownerTokenAccount: ownerTokens,
+                owner: owner.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc();
+    }
+
+    function modifyLock(method: "vulnerableSetLockEnd" | "secureExtendLock", registrarId: number, owner: Keypair, newEnd: number) {
+        return program.methods[method](new anchor.BN(newEnd))
+            .accounts({
+                registrar: registrarPda(registrarId),
+                lock: lockPda(registrarId, owner.publicKey),
+                owner: owner.publicKey,
+            })
+            .signers([owner])
+            .rpc();
+    }
+
+    function withdraw(method: "vulnerableWithdraw" | "secureWithdraw", registrarId: number, owner: Keypair, ownerTokens: PublicKey) {
+        return program.methods[method]()
+            .accounts({
+                registrar: registrarPda(registrarId),
+                lock: lockPda(registrarId, owner.publicKey),
+                vault: vaultPda(registrarId),
+                ownerTokenAccount: ownerTokens,
+                owner: owner.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([owner])
+            .rpc();
+    }
+
+    function vote(method: "vulnerableVote" | "secureVote", registrarId: number, owner: Keypair) {
+        return program.methods[method]()
+            .accounts({
+                registrar: registrarPda(registrarId),
+                lock: lockPda(registrarId, owner.publicKey),
+                proposal: proposalPda(registrarId),
+                voteRecord: voteRecordPda(registrarId, owner.publicKey),
+                owner: owner.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc();
+    }
+
+    before(async () => {
+        alice = Keypair.generate();
+        mallory = Keypair.generate();
+
+        for (const kp of [alice, mallory]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Two registrars over the same governance token, each with an open proposal", async () => {
+            mint = await createMint(provider.connection, alice, alice.publicKey, null, 6);
+            aliceTokens = await createAccount(provider.connection, alice, mint, alice.publicKey);
+            malloryTokens = await createAccount(provider.connection, mallory, mint, mallory.publicKey);
+            await mintTo(provider.connection, alice, mint, aliceTokens, alice, LOCKED);
+            await mintTo(provider.connection, alice, mint, malloryTokens, alice, LOCKED);
+
+            for (const registrarId of [VULNERABLE_REGISTRAR, SECURE_REGISTRAR]) {
+                await program.methods
+                    .initializeRegistrar(new anchor.BN(registrarId))
+                    .accounts({
+                        registrar: registrarPda(registrarId),
+                        mint,
+                        vault: vaultPda(registrarId),
+                        payer: alice.publicKey,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                        systemProgram: SystemProgram.programId,
+                        rent: SYSVAR_RENT_PUBKEY,
+                    })
+                    .signers([alice])
+                    .rpc();
+
+                await program.methods
+                    .createProposal(new anchor.BN(PROPOSAL_ID))
+                    .accounts({
+                        registrar: registrarPda(registrarId),
+                        proposal: proposalPda(registrarId),
+                        payer: alice.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([alice])
+                    .rpc();
+            }
+        });
+    });
+
+    describe("Exploit: shorten the lock, withdraw, vote with stale power", () => {
+        it("Mallory locks 1,000 tokens for four years", async () => {
+            const now = await chainNow();
+            await createLock(VULNERABLE_REGISTRAR, mallory, malloryTokens, LOCKED, now + MAX_LOCK_SECS - MARGIN_SECS);
+
+            const lock = await program.account.lock.fetch(lockPda(VULNERABLE_REGISTRAR, mallory.publicKey));
+            expect(lock.votingPower.toNumber()).to.be.closeTo(LOCKED, LOCKED / 1_000);
+        });
+
+        it("Mallory moves the lock end to two seconds from now and withdraws", async () => {
+            await modifyLock("vulnerableSetLockEnd", VULNERABLE_REGISTRAR, mallory, (await chainNow()) + 2);
+            await sleep(3);
+            await withdraw("vulnerableWithdraw", VULNERABLE_REGISTRAR, mallory, malloryTokens);
+
+            const balance = await getAccount(provider.connection, malloryTokens);
+            expect(Number(balance.amount)).to.equal(LOCKED);
+        });
+
+        it("The empty lock still votes with four years of power", async () => {
+            await vote("vulnerableVote", VULNERABLE_REGISTRAR, mallory);
+
+            const lock = await program.account.lock.fetch(lockPda(VULNERABLE_REGISTRAR, mallory.publicKey));
+            const proposal = await program.account.proposal.fetch(proposalPda(VULNERABLE_REGISTRAR));
+            console.log(`  Locked: ${lock.amount.toNumber()}, votes cast: ${proposal.yesVotes.toNumber()}`);
+
+            expect(lock.amount.toNumber()).to.equal(0);
+            expect(proposal.yesVotes.toNumber()).to.be.closeTo(LOCKED, LOCKED / 1_000);
+        });
+    });
+
+    describe("Secure: locks only extend, power is checkpointed", () => {
+        it("Mallory locks 1,000 tokens for two years", async () => {
+            const now = await chainNow();
+            await createLock(SECURE_REGISTRAR, mallory, malloryTokens, LOCKED, now + MAX_LOCK_SECS / 2);
+
+            const lock = await program.account.lock.fetch(lockPda(SECURE_REGISTRAR, mallory.publicKey));
+            expect(lock.votingPower.toNumber()).to.be.closeTo(LOCKED / 2, LOCKED / 1_000);
+        });
+
+        it("Rejects moving the lock end earlier", async () => {
+            await expectError(
+                modifyLock("secureExtendLock", SECURE_REGISTRAR, mallory, (await chainNow()) + 2),
+                "LockNotExtended"
+            );
+        });
+
+        it("Rejects withdrawing before the lock ends", async () => {
+            await expectError(withdraw("secureWithdraw", SECURE_REGISTRAR, mallory, malloryTokens), "LockNotExpired");
+        });
+
+        it("Extending to four years rewrites the checkpoint with the larger power", async () => {
+            const now = await chainNow();
+            await modifyLock("secureExtendLock", SECURE_REGISTRAR, mallory, now + MAX_LOCK_SECS - MARGIN_SECS);
+
+            const lock = await program.account.lock.fetch(lockPda(SECURE_REGISTRAR, mallory.publicKey));
+            expect(lock.votingPower.toNumber()).to.be.closeTo(LOCKED, LOCKED / 1_000);
+            expect(lock.checkpointTs.toNumber()).to.be.closeTo(now, 5);
+        });
+
+        it("Alice's expired lock withdraws and then has no power to vote", async () => {
+            await createLock(SECURE_REGISTRAR, alice, aliceTokens, LOCKED, (await chainNow()) + 3);
+            await sleep(5);
+            await withdraw("secureWithdraw", SECURE_REGISTRAR, alice, aliceTokens);
+
+            const lock = await program.account.lock.fetch(lockPda(SECURE_REGISTRAR, alice.publicKey));
+            expect(lock.amount.toNumber()).to.equal(0);
+            expect(lock.votingPower.toNumber()).to.equal(0);
+
+            await expectError(vote("secureVote", SECURE_REGISTRAR, alice), "NoVotingPower");
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Vote-Escrow Lockup Bypass ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  lock.lock_end = new_end;            // earlier is fine");
+            console.log("  vote(lock.voting_power);            // computed once, at lock creation");
+            console.log("  Risk: lock for four years, shorten, withdraw, vote with the four-year power\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  require!(new_end > lock.lock_end);  // extend only");
+            console.log("  lock.checkpoint(now);               // on every change and every vote");
+            console.log("  Protection: power always reflects tokens still locked and time still remaining\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Voting power is a function of the lock, not a number stored once beside it.");
+        });
+    });
+});
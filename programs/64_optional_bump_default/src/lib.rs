@@ -0,0 +1,196 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke_signed, system_instruction};
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("H11btxRCPvwuvXgLcWSa7ueaLGQyJ7BBEcZBHjhH9kYn");
+
+#[program]
+pub mod optional_bump_default {
+    use super::*;
+
+    /// Current initializer: records the SOL vault's canonical bump.
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.owner = ctx.accounts.owner.key();
+        vault.sol_vault_bump = Some(ctx.bumps.sol_vault);
+        vault.bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    /// Stands in for the v1 initializer, which predates `sol_vault_bump`.
+    /// Its accounts were allocated with room to spare; the zeroed byte after
+    /// the v1 fields, where the new field now lives, reads back as `None`.
+    pub fn initialize_legacy(ctx: Context<Initialize>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.owner = ctx.accounts.owner.key();
+        vault.sol_vault_bump = None;
+        vault.bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    // VULNERABLE: Missing Bump Defaulted to 0
+    // 1. `sol_vault_bump.unwrap_or(0)` turns a missing bump into a real one
+    // 2. Deriving with bump 0 gives a different address from the canonical
+    //    vault, when it gives a valid address at all
+    // 3. Deposits from a legacy account go to that non-canonical address,
+    //    where every later path that uses the canonical bump will never look
+    // 4. Withdrawals from the canonical vault sign for the wrong address and
+    //    fail, so funds there are stuck
+    pub fn vulnerable_deposit(ctx: Context<SolVaultAction>, amount: u64) -> Result<()> {
+        let bump = ctx.accounts.vault.sol_vault_bump.unwrap_or(0);
+        ctx.accounts.deposit(bump, amount)
+    }
+
+    pub fn vulnerable_withdraw(ctx: Context<SolVaultAction>, amount: u64) -> Result<()> {
+        let bump = ctx.accounts.vault.sol_vault_bump.unwrap_or(0);
+        ctx.accounts.withdraw(bump, amount)
+    }
+
+    // SECURE: Bump Mandatory, Absence Is an Error
+    // 1. `initialize` always stores the canonical bump
+    // 2. Every use reads it with `ok_or(BumpMissing)`, so a legacy account
+    //    stops with a typed error instead of guessing
+    // 3. `secure_migrate` backfills the canonical bump once, after which the
+    //    account behaves like a current one
+    pub fn secure_deposit(ctx: Context<SolVaultAction>, amount: u64) -> Result<()> {
+        let bump = ctx.accounts.vault.sol_vault_bump.ok_or(VaultError::BumpMissing)?;
+        ctx.accounts.deposit(bump, amount)
+    }
+
+    pub fn secure_withdraw(ctx: Context<SolVaultAction>, amount: u64) -> Result<()> {
+        let bump = ctx.accounts.vault.sol_vault_bump.ok_or(VaultError::BumpMissing)?;
+        ctx.accounts.withdraw(bump, amount)
+    }
+
+    pub fn secure_migrate(ctx: Context<Migrate>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        require!(vault.sol_vault_bump.is_none(), VaultError::BumpAlreadySet);
+        vault.sol_vault_bump = Some(ctx.bumps.sol_vault);
+        Ok(())
+    }
+}
+
+/// The SOL vault address for `vault` under `bump`, or `None` if that bump
+/// lands on the curve.
+fn sol_vault_address(vault: &Pubkey, bump: u8) -> Option<Pubkey> {
+    Pubkey::create_program_address(&[b"sol_vault", vault.as_ref(), &[bump]], &crate::ID).ok()
+}
+
+impl<'info> SolVaultAction<'info> {
+    /// Pays `amount` into the SOL vault derived with `bump`.
+    fn deposit(&self, bump: u8, amount: u64) -> Result<()> {
+        let expected = sol_vault_address(&self.vault.key(), bump).ok_or(VaultError::InvalidVaultAddress)?;
+        require_keys_eq!(self.sol_vault.key(), expected, VaultError::InvalidVaultAddress);
+
+        let cpi_accounts = Transfer {
+            from: self.owner.to_account_info(),
+            to: self.sol_vault.to_account_info(),
+        };
+        transfer(CpiContext::new(self.system_program.to_account_info(), cpi_accounts), amount)
+    }
+
+    /// Pays `amount` out of the SOL vault, signing with `bump`.
+    fn withdraw(&self, bump: u8, amount: u64) -> Result<()> {
+        let vault_key = self.vault.key();
+        let seeds: &[&[u8]] = &[b"sol_vault", vault_key.as_ref(), &[bump]];
+        invoke_signed(
+            &system_instruction::transfer(self.sol_vault.key, self.owner.key, amount),
+            &[
+                self.sol_vault.to_account_info(),
+                self.owner.to_account_info(),
+                self.system_program.to_account_info(),
+            ],
+            &[seeds],
+        )?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+    /// CHECK: system-owned PDA holding the vault's SOL; only its bump is stored.
+    #[account(seeds = [b"sol_vault", vault.key().as_ref()], bump)]
+    pub sol_vault: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SolVaultAction<'info> {
+    #[account(
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::Unauthorized
+    )]
+    pub vault: Account<'info, Vault>,
+    /// CHECK: derived from the stored bump in the handler.
+    #[account(mut)]
+    pub sol_vault: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Migrate<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ VaultError::Unauthorized
+    )]
+    pub vault: Account<'info, Vault>,
+    /// CHECK: only its canonical bump is needed.
+    #[account(seeds = [b"sol_vault", vault.key().as_ref()], bump)]
+    pub sol_vault: UncheckedAccount<'info>,
+    pub owner: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub owner: Pubkey,
+    pub bump: u8,
+    /// Added in v2, after the v1 fields. `None` on accounts created by the
+    /// v1 initializer.
+    pub sol_vault_bump: Option<u8>,
+}
+
+#[error_code]
+pub enum VaultError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("SOL vault bump is missing; migrate the account first")]
+    BumpMissing,
+    #[msg("SOL vault bump is already set")]
+    BumpAlreadySet,
+    #[msg("SOL vault does not match the stored bump")]
+    InvalidVaultAddress,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaulted_bump_never_reaches_the_canonical_address() {
+        for _ in 0..64 {
+            let vault = Pubkey::new_unique();
+            let (canonical, bump) = Pubkey::find_program_address(&[b"sol_vault", vault.as_ref()], &crate::ID);
+
+            assert_eq!(sol_vault_address(&vault, bump), Some(canonical));
+            if bump != 0 {
+                assert_ne!(sol_vault_address(&vault, 0), Some(canonical));
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("64: Optional Bump Defaulting", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.OptionalBumpDefault as Program<any>;
+
+    let alice: Keypair;
+    let bob: Keypair;
+    let carol: Keypair;
+
+    const DEPOSIT = 2 * LAMPORTS_PER_SOL;
+    const PAYMENT = LAMPORTS_PER_SOL;
+
+    function vaultPda(owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("vault"), owner.toBuffer()], program.programId)[0];
+    }
+
+    /** The address the program derives from the stored bump; the canonical one. */
+    function solVaultPda(owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("sol_vault"), vaultPda(owner).toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    /** The address `unwrap_or(0)` derives, or null when bump 0 lands on the curve. */
+    function bumpZeroAddress(owner: PublicKey): PublicKey | null {
+        try {
+            return PublicKey.createProgramAddressSync(
+                [Buffer.from("sol_vault"), vaultPda(owner).toBuffer(), Buffer.from([0])],
+                program.programId
+            );
+        } catch {
+            return null;
+        }
+    }
+
+    /** An owner whose legacy vault reaches a valid, non-canonical address with bump 0. */
+    function ownerWithBumpZeroAddress(): Keypair {
+        for (;;) {
+            const owner = Keypair.generate();
+            const address = bumpZeroAddress(owner.publicKey);
+            if (address && !address.equals(solVaultPda(owner.publicKey))) {
+                return owner;
+            }
+        }
+    }
+
+    function initialize(method: "initialize" | "initializeLegacy", owner: Keypair) {
+        return program.methods[method]()
+            .accounts({
+                vault: vaultPda(owner.publicKey),
+                solVault: solVaultPda(owner.publicKey),
+                owner: owner.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc();
+    }
+
+    function solVaultAction(
+        method: "vulnerableDeposit" | "vulnerableWithdraw" | "secureDeposit" | "secureWithdraw",
+        owner: Keypair,
+        solVault: PublicKey,
+        amount: number
+    ) {
+        return program.methods[method](new anchor.BN(amount))
+            .accounts({
+                vault: vaultPda(owner.publicKey),
+                solVault,
+                owner: owner.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc();
+    }
+
+    function migrate(owner: Keypair) {
+        return program.methods
+            .secureMigrate()
+            .accounts({ vault: vaultPda(owner.publicKey), solVault: solVaultPda(owner.publicKey), owner: owner.publicKey })
+            .signers([owner])
+            .rpc();
+    }
+
+    async function pay(to: PublicKey, lamports: number) {
+        const tx = new Transaction().add(SystemProgram.transfer({ fromPubkey: bob.publicKey, toPubkey: to, lamports }));
+        await provider.sendAndConfirm(tx, [bob]);
+    }
+
+    async function expectSignerFailure(promise: Promise<unknown>) {
+        try {
+            await promise;
+            expect.fail("Should have thrown");
+        } catch (error: any) {
+            const output = [error.message, ...(error.logs ?? [])].join("\n");
+            expect(output).to.include("signer privilege escalated");
+        }
+    }
+
+    before(async () => {
+        alice = ownerWithBumpZeroAddress();
+        bob = Keypair.generate();
+        carol = Keypair.generate();
+
+        for (const kp of [alice, bob, carol]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Alice and Carol have v1 vaults with no stored bump; Bob has a current one", async () => {
+            await initialize("initializeLegacy", alice);
+            await initialize("initializeLegacy", carol);
+            await initialize("initialize", bob);
+
+            expect((await program.account.vault.fetch(vaultPda(alice.publicKey))).solVaultBump).to.equal(null);
+            expect((await program.account.vault.fetch(vaultPda(bob.publicKey))).solVaultBump).to.not.equal(null);
+        });
+    });
+
+    describe("Exploit: unwrap_or(0) picks a different address", () => {
+        it("Alice's deposit to the canonical vault is rejected; bump 0 points elsewhere", async () => {
+            await expectError(
+                solVaultAction("vulnerableDeposit", alice, solVaultPda(alice.publicKey), DEPOSIT),
+                "InvalidVaultAddress"
+            );
+        });
+
+        it("A client that follows the program's derivation deposits at the bump-0 address", async () => {
+            const stray = bumpZeroAddress(alice.publicKey)!;
+            await solVaultAction("vulnerableDeposit", alice, stray, DEPOSIT);
+
+            expect(await provider.connection.getBalance(stray)).to.equal(DEPOSIT);
+            expect(await provider.connection.getBalance(solVaultPda(alice.publicKey))).to.equal(0);
+        });
+
+        it("A payment to Alice's canonical vault cannot be withdrawn: the PDA signature is for the wrong address", async () => {
+            await pay(solVaultPda(alice.publicKey), PAYMENT);
+            await expectSignerFailure(solVaultAction("vulnerableWithdraw", alice, solVaultPda(alice.publicKey), PAYMENT));
+        });
+
+        it("Once the bump is backfilled, the deposit at the bump-0 address is stranded", async () => {
+            await migrate(alice);
+            await solVaultAction("secureWithdraw", alice, solVaultPda(alice.publicKey), PAYMENT);
+
+            const stray = bumpZeroAddress(alice.publicKey)!;
+            await expectSignerFailure(solVaultAction("secureWithdraw", alice, stray, DEPOSIT));
+            console.log(`  Stranded at the bump-0 address: ${(await provider.connection.getBalance(stray)) / LAMPORTS_PER_SOL} SOL`);
+        });
+    });
+
+    describe("Secure: a missing bump is a typed error", () => {
+        it("Carol's v1 vault refuses deposits and withdrawals until migrated", async () => {
+            await expectError(
+                solVaultAction("secureDeposit", carol, solVaultPda(carol.publicKey), DEPOSIT),
+                "BumpMissing"
+            );
+            await expectError(
+                solVaultAction("secureWithdraw", carol, solVaultPda(carol.publicKey), DEPOSIT),
+                "BumpMissing"
+            );
+        });
+
+        it("After migration, Carol's vault uses the canonical address", async () => {
+            await migrate(carol);
+            await solVaultAction("secureDeposit", carol, solVaultPda(carol.publicKey), DEPOSIT);
+            await solVaultAction("secureWithdraw", carol, solVaultPda(carol.publicKey), DEPOSIT);
+
+            expect(await provider.connection.getBalance(solVaultPda(carol.publicKey))).to.equal(0);
+        });
+
+        it("Migration runs once", async () => {
+            await expectError(migrate(carol), "BumpAlreadySet");
+        });
+
+        it("Bob's current vault works from the start", async () => {
+            await solVaultAction("secureDeposit", bob, solVaultPda(bob.publicKey), DEPOSIT);
+            await solVaultAction("secureWithdraw", bob, solVaultPda(bob.publicKey), DEPOSIT);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Optional Bump Defaulting ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  let bump = vault.sol_vault_bump.unwrap_or(0);");
+            console.log("  Risk: a missing value becomes a real bump; signatures fail or funds go to another PDA\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  let bump = vault.sol_vault_bump.ok_or(VaultError::BumpMissing)?;");
+            console.log("  Protection: old accounts stop with a typed error until migrated to the canonical bump\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  0 is a valid bump. A default for a seed is a different address.");
+        });
+    });
+});
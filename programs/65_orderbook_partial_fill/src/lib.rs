@@ -0,0 +1,400 @@
+use anchor_lang::prelude::*;
+use security_common::math::mul_div_floor;
+
+declare_id!("Ffx6DJwRfGhZ2GzDVNV1cTsgNjBh3imjks9Xfu18VkUX");
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+#[program]
+pub mod orderbook_partial_fill {
+    use super::*;
+
+    /// Creates a market. `band_bps` bounds how far a trade may print from
+    /// the last traded price in the secure matcher.
+    pub fn initialize_market(ctx: Context<InitializeMarket>, market_id: u64, initial_price: u64, band_bps: u64) -> Result<()> {
+        require!(initial_price > 0, BookError::InvalidPrice);
+        require!(band_bps <= BPS_DENOMINATOR, BookError::InvalidBand);
+
+        let market = &mut ctx.accounts.market;
+        market.market_id = market_id;
+        market.admin = ctx.accounts.admin.key();
+        market.last_price = initial_price;
+        market.band_bps = band_bps;
+        market.base_escrowed = 0;
+        market.quote_escrowed = 0;
+        market.next_order_id = 0;
+        market.bump = ctx.bumps.market;
+        Ok(())
+    }
+
+    pub fn open_trader(ctx: Context<OpenTrader>) -> Result<()> {
+        let trader = &mut ctx.accounts.trader;
+        trader.owner = ctx.accounts.owner.key();
+        trader.market = ctx.accounts.market.key();
+        trader.base_free = 0;
+        trader.quote_free = 0;
+        trader.bump = ctx.bumps.trader;
+        Ok(())
+    }
+
+    /// Credits free balances. Stands in for token deposits, which are not
+    /// what this pattern is about.
+    pub fn deposit(ctx: Context<Deposit>, base: u64, quote: u64) -> Result<()> {
+        let trader = &mut ctx.accounts.trader;
+        trader.base_free = trader.base_free.checked_add(base).ok_or(BookError::ArithmeticError)?;
+        trader.quote_free = trader.quote_free.checked_add(quote).ok_or(BookError::ArithmeticError)?;
+        Ok(())
+    }
+
+    /// Rests an order, moving what it could owe from free balance into escrow:
+    /// `quantity` base for an ask, `price * quantity` quote for a bid.
+    pub fn place_order(ctx: Context<PlaceOrder>, side: Side, price: u64, quantity: u64) -> Result<()> {
+        require!(price > 0, BookError::InvalidPrice);
+        require!(quantity > 0, BookError::InvalidQuantity);
+
+        let market = &mut ctx.accounts.market;
+        let trader = &mut ctx.accounts.trader;
+        match side {
+            Side::Ask => {
+                trader.base_free = trader.base_free.checked_sub(quantity).ok_or(BookError::InsufficientBalance)?;
+                market.base_escrowed = market.base_escrowed.checked_add(quantity).ok_or(BookError::ArithmeticError)?;
+            }
+            Side::Bid => {
+                let cost = price.checked_mul(quantity).ok_or(BookError::ArithmeticError)?;
+                trader.quote_free = trader.quote_free.checked_sub(cost).ok_or(BookError::InsufficientBalance)?;
+                market.quote_escrowed = market.quote_escrowed.checked_add(cost).ok_or(BookError::ArithmeticError)?;
+            }
+        }
+
+        let order = &mut ctx.accounts.order;
+        order.market = market.key();
+        order.order_id = market.next_order_id;
+        order.owner = ctx.accounts.owner.key();
+        order.side = side;
+        order.price = price;
+        order.quantity = quantity;
+        order.filled = 0;
+        order.bump = ctx.bumps.order;
+
+        market.next_order_id = market.next_order_id.checked_add(1).ok_or(BookError::ArithmeticError)?;
+        Ok(())
+    }
+
+    /// Returns an order's unfilled escrow to its owner and closes it.
+    pub fn cancel_order(ctx: Context<CancelOrder>) -> Result<()> {
+        let order = &ctx.accounts.order;
+        let remaining = order.quantity.checked_sub(order.filled).ok_or(BookError::ArithmeticError)?;
+
+        let market = &mut ctx.accounts.market;
+        let trader = &mut ctx.accounts.trader;
+        match order.side {
+            Side::Ask => {
+                market.base_escrowed = market.base_escrowed.checked_sub(remaining).ok_or(BookError::InsufficientEscrow)?;
+                trader.base_free = trader.base_free.checked_add(remaining).ok_or(BookError::ArithmeticError)?;
+            }
+            Side::Bid => {
+                let refund = order.price.checked_mul(remaining).ok_or(BookError::ArithmeticError)?;
+                market.quote_escrowed = market.quote_escrowed.checked_sub(refund).ok_or(BookError::InsufficientEscrow)?;
+                trader.quote_free = trader.quote_free.checked_add(refund).ok_or(BookError::ArithmeticError)?;
+            }
+        }
+        Ok(())
+    }
+
+    // VULNERABLE: Fills Sized From the Original Quantity, Self-Trades Allowed
+    // 1. The fill is `min(bid.quantity, ask.quantity)`: what each order was
+    //    placed for, not what is left, so a partially filled order is filled
+    //    again in full and settles more than it escrowed
+    // 2. The excess is paid out of escrow that belongs to other orders
+    // 3. Bid and ask may share an owner, and nothing bounds the price, so a
+    //    trader can print any last-traded price by matching against themself
+    pub fn vulnerable_match(ctx: Context<MatchOrders>) -> Result<()> {
+        let fill = ctx.accounts.bid.quantity.min(ctx.accounts.ask.quantity);
+        ctx.accounts.settle(fill)
+    }
+
+    // SECURE: Remaining Quantities, No Self-Trades, Price Band
+    // 1. The fill is `min` of each order's remaining quantity, and must be
+    //    non-zero; `filled` can never pass `quantity`
+    // 2. Bid and ask must have different owners
+    // 3. The execution price must be within `band_bps` of the last traded
+    //    price, so one trade cannot move the reference price arbitrarily
+    pub fn secure_match(ctx: Context<MatchOrders>) -> Result<()> {
+        let accounts = &ctx.accounts;
+        require_keys_neq!(accounts.bid.owner, accounts.ask.owner, BookError::SelfTrade);
+        require!(
+            within_band(accounts.ask.price, accounts.market.last_price, accounts.market.band_bps),
+            BookError::PriceOutOfBand
+        );
+
+        let fill = remaining_fill(&accounts.bid, &accounts.ask).ok_or(BookError::ArithmeticError)?;
+        require!(fill > 0, BookError::NothingToFill);
+        ctx.accounts.settle(fill)
+    }
+}
+
+/// The largest fill both orders can still take.
+fn remaining_fill(bid: &Order, ask: &Order) -> Option<u64> {
+    let bid_left = bid.quantity.checked_sub(bid.filled)?;
+    let ask_left = ask.quantity.checked_sub(ask.filled)?;
+    Some(bid_left.min(ask_left))
+}
+
+/// Whether `price` is within `band_bps` of `reference`, inclusive.
+fn within_band(price: u64, reference: u64, band_bps: u64) -> bool {
+    let Some(band) = mul_div_floor(reference, band_bps, BPS_DENOMINATOR) else {
+        return false;
+    };
+    price >= reference.saturating_sub(band) && price <= reference.saturating_add(band)
+}
+
+impl<'info> MatchOrders<'info> {
+    /// Trades `fill` base at the ask's price. The buyer gets the base and a
+    /// refund of any price improvement; the seller gets the quote.
+    fn settle(&mut self, fill: u64) -> Result<()> {
+        let price = self.ask.price;
+        require!(self.bid.price >= price, BookError::PricesDoNotCross);
+
+        let paid = price.checked_mul(fill).ok_or(BookError::ArithmeticError)?;
+        let reserved = self.bid.price.checked_mul(fill).ok_or(BookError::ArithmeticError)?;
+
+        let market = &mut self.market;
+        market.base_escrowed = market.base_escrowed.checked_sub(fill).ok_or(BookError::InsufficientEscrow)?;
+        market.quote_escrowed = market.quote_escrowed.checked_sub(reserved).ok_or(BookError::InsufficientEscrow)?;
+        market.last_price = price;
+
+        self.bid.filled = self.bid.filled.checked_add(fill).ok_or(BookError::ArithmeticError)?;
+        self.ask.filled = self.ask.filled.checked_add(fill).ok_or(BookError::ArithmeticError)?;
+
+        let refund = reserved - paid;
+        let buyer = &mut self.bid_trader;
+        buyer.base_free = buyer.base_free.checked_add(fill).ok_or(BookError::ArithmeticError)?;
+        buyer.quote_free = buyer.quote_free.checked_add(refund).ok_or(BookError::ArithmeticError)?;
+
+        // In a self-trade both are the same account, and `ask_trader` is
+        // written back last; carry the buyer's credit into it.
+        if self.ask_trader.key() == self.bid_trader.key() {
+            self.ask_trader.set_inner((*self.bid_trader).clone());
+        }
+
+        let seller = &mut self.ask_trader;
+        seller.quote_free = seller.quote_free.checked_add(paid).ok_or(BookError::ArithmeticError)?;
+
+        msg!("Filled {} at {}", fill, price);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(market_id: u64)]
+pub struct InitializeMarket<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Market::INIT_SPACE,
+        seeds = [b"market", market_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub market: Account<'info, Market>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenTrader<'info> {
+    #[account(seeds = [b"market", market.market_id.to_le_bytes().as_ref()], bump = market.bump)]
+    pub market: Account<'info, Market>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Trader::INIT_SPACE,
+        seeds = [b"trader", market.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub trader: Account<'info, Trader>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(
+        mut,
+        seeds = [b"trader", trader.market.as_ref(), owner.key().as_ref()],
+        bump = trader.bump,
+        has_one = owner @ BookError::Unauthorized
+    )]
+    pub trader: Account<'info, Trader>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceOrder<'info> {
+    #[account(mut, seeds = [b"market", market.market_id.to_le_bytes().as_ref()], bump = market.bump)]
+    pub market: Account<'info, Market>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Order::INIT_SPACE,
+        seeds = [b"order", market.key().as_ref(), market.next_order_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub order: Account<'info, Order>,
+    #[account(
+        mut,
+        seeds = [b"trader", market.key().as_ref(), owner.key().as_ref()],
+        bump = trader.bump,
+        has_one = owner @ BookError::Unauthorized
+    )]
+    pub trader: Account<'info, Trader>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelOrder<'info> {
+    #[account(mut, seeds = [b"market", market.market_id.to_le_bytes().as_ref()], bump = market.bump)]
+    pub market: Account<'info, Market>,
+    #[account(
+        mut,
+        close = owner,
+        has_one = market,
+        has_one = owner @ BookError::Unauthorized
+    )]
+    pub order: Account<'info, Order>,
+    #[account(
+        mut,
+        seeds = [b"trader", market.key().as_ref(), owner.key().as_ref()],
+        bump = trader.bump
+    )]
+    pub trader: Account<'info, Trader>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+/// Permissionless: anyone may crank a crossing bid and ask.
+#[derive(Accounts)]
+pub struct MatchOrders<'info> {
+    #[account(mut, seeds = [b"market", market.market_id.to_le_bytes().as_ref()], bump = market.bump)]
+    pub market: Account<'info, Market>,
+    #[account(mut, has_one = market, constraint = bid.side == Side::Bid @ BookError::WrongSide)]
+    pub bid: Account<'info, Order>,
+    #[account(mut, has_one = market, constraint = ask.side == Side::Ask @ BookError::WrongSide)]
+    pub ask: Account<'info, Order>,
+    #[account(mut, seeds = [b"trader", market.key().as_ref(), bid.owner.as_ref()], bump = bid_trader.bump)]
+    pub bid_trader: Account<'info, Trader>,
+    #[account(mut, seeds = [b"trader", market.key().as_ref(), ask.owner.as_ref()], bump = ask_trader.bump)]
+    pub ask_trader: Account<'info, Trader>,
+    pub matcher: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Market {
+    pub market_id: u64,
+    pub admin: Pubkey,
+    /// Quote per base unit of the most recent trade.
+    pub last_price: u64,
+    pub band_bps: u64,
+    /// Base held for resting asks.
+    pub base_escrowed: u64,
+    /// Quote held for resting bids.
+    pub quote_escrowed: u64,
+    pub next_order_id: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Trader {
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub base_free: u64,
+    pub quote_free: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Order {
+    pub market: Pubkey,
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub side: Side,
+    pub price: u64,
+    pub quantity: u64,
+    pub filled: u64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum Side {
+    Bid,
+    Ask,
+}
+
+#[error_code]
+pub enum BookError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Price must be greater than zero")]
+    InvalidPrice,
+    #[msg("Band must be at most 10,000 bps")]
+    InvalidBand,
+    #[msg("Quantity must be greater than zero")]
+    InvalidQuantity,
+    #[msg("Insufficient balance")]
+    InsufficientBalance,
+    #[msg("Escrow cannot cover this settlement")]
+    InsufficientEscrow,
+    #[msg("Order is on the wrong side")]
+    WrongSide,
+    #[msg("Bid is below the ask")]
+    PricesDoNotCross,
+    #[msg("Bid and ask have the same owner")]
+    SelfTrade,
+    #[msg("Price is outside the allowed band")]
+    PriceOutOfBand,
+    #[msg("Nothing left to fill")]
+    NothingToFill,
+    #[msg("Arithmetic error")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(side: Side, quantity: u64, filled: u64) -> Order {
+        Order {
+            market: Pubkey::new_unique(),
+            order_id: 0,
+            owner: Pubkey::new_unique(),
+            side,
+            price: 100,
+            quantity,
+            filled,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn fills_use_what_is_left() {
+        let ask = order(Side::Ask, 10, 4);
+        assert_eq!(remaining_fill(&order(Side::Bid, 10, 0), &ask), Some(6));
+        assert_eq!(remaining_fill(&order(Side::Bid, 3, 0), &ask), Some(3));
+        assert_eq!(remaining_fill(&order(Side::Bid, 10, 0), &order(Side::Ask, 10, 10)), Some(0));
+    }
+
+    #[test]
+    fn band_is_inclusive_around_the_reference() {
+        assert!(within_band(110, 100, 1_000));
+        assert!(within_band(90, 100, 1_000));
+        assert!(!within_band(111, 100, 1_000));
+        assert!(!within_band(89, 100, 1_000));
+        assert!(within_band(100, 100, 0));
+    }
+}
@@ -0,0 +1,258 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("65: Order Book Partial-Fill Accounting", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.OrderbookPartialFill as Program<any>;
+
+    let alice: Keypair;
+    let bob: Keypair;
+    let carol: Keypair;
+    let mallory: Keypair;
+
+    const VULNERABLE_MARKET = 1;
+    const SECURE_MARKET = 2;
+    const INITIAL_PRICE = 100;
+    const BAND_BPS = 1_000;
+    const MANIPULATED_PRICE = 100 * INITIAL_PRICE;
+
+    const BID = { bid: {} };
+    const ASK = { ask: {} };
+
+    function marketPda(marketId: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("market"), new anchor.BN(marketId).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        )[0];
+    }
+
+    function traderPda(marketId: number, owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("trader"), marketPda(marketId).toBuffer(), owner.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    function orderPda(marketId: number, orderId: anchor.BN): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("order"), marketPda(marketId).toBuffer(), orderId.toArrayLike(Buffer, "le", 8)],
+            program.programId
+        )[0];
+    }
+
+    async function fetchTrader(marketId: number, owner: Keypair) {
+        return program.account.trader.fetch(traderPda(marketId, owner.publicKey));
+    }
+
+    async function placeOrder(marketId: number, owner: Keypair, side: object, price: number, quantity: number) {
+        const market = await program.account.market.fetch(marketPda(marketId));
+        const order = orderPda(marketId, market.nextOrderId);
+        await program.methods
+            .placeOrder(side, new anchor.BN(price), new anchor.BN(quantity))
+            .accounts({
+                market: marketPda(marketId),
+                order,
+                trader: traderPda(marketId, owner.publicKey),
+                owner: owner.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc();
+        return { order, owner: owner.publicKey };
+    }
+
+    type Resting = { order: PublicKey; owner: PublicKey };
+
+    function match(method: "vulnerableMatch" | "secureMatch", marketId: number, bid: Resting, ask: Resting) {
+        return program.methods[method]()
+            .accounts({
+                market: marketPda(marketId),
+                bid: bid.order,
+                ask: ask.order,
+                bidTrader: traderPda(marketId, bid.owner),
+                askTrader: traderPda(marketId, ask.owner),
+                matcher: provider.wallet.publicKey,
+            })
+            .rpc();
+    }
+
+    function cancel(marketId: number, owner: Keypair, order: PublicKey) {
+        return program.methods
+            .cancelOrder()
+            .accounts({
+                market: marketPda(marketId),
+                order,
+                trader: traderPda(marketId, owner.publicKey),
+                owner: owner.publicKey,
+            })
+            .signers([owner])
+            .rpc();
+    }
+
+    before(async () => {
+        alice = Keypair.generate();
+        bob = Keypair.generate();
+        carol = Keypair.generate();
+        mallory = Keypair.generate();
+
+        for (const kp of [alice, bob, carol, mallory]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Two markets at a price of 100 with a 10% band; Alice and Bob sell, Carol buys", async () => {
+            for (const marketId of [VULNERABLE_MARKET, SECURE_MARKET]) {
+                await program.methods
+                    .initializeMarket(new anchor.BN(marketId), new anchor.BN(INITIAL_PRICE), new anchor.BN(BAND_BPS))
+                    .accounts({ market: marketPda(marketId), admin: alice.publicKey, systemProgram: SystemProgram.programId })
+                    .signers([alice])
+                    .rpc();
+
+                const deposits: [Keypair, number, number][] = [
+                    [alice, 10, 0],
+                    [bob, 10, 0],
+                    [carol, 0, 100_000],
+                    [mallory, 1, 100_000],
+                ];
+                for (const [owner, base, quote] of deposits) {
+                    await program.methods
+                        .openTrader()
+                        .accounts({
+                            market: marketPda(marketId),
+                            trader: traderPda(marketId, owner.publicKey),
+                            owner: owner.publicKey,
+                            systemProgram: SystemProgram.programId,
+                        })
+                        .signers([owner])
+                        .rpc();
+                    await program.methods
+                        .deposit(new anchor.BN(base), new anchor.BN(quote))
+                        .accounts({ trader: traderPda(marketId, owner.publicKey), owner: owner.publicKey })
+                        .signers([owner])
+                        .rpc();
+                }
+            }
+        });
+    });
+
+    describe("Exploit: partial fills counted twice, self-trades print any price", () => {
+        let aliceAsk: Resting;
+        let bobAsk: Resting;
+
+        it("Alice's ask for 10 is partially filled by Carol's bid for 4", async () => {
+            aliceAsk = await placeOrder(VULNERABLE_MARKET, alice, ASK, INITIAL_PRICE, 10);
+            bobAsk = await placeOrder(VULNERABLE_MARKET, bob, ASK, INITIAL_PRICE, 10);
+
+            const smallBid = await placeOrder(VULNERABLE_MARKET, carol, BID, INITIAL_PRICE, 4);
+            await match("vulnerableMatch", VULNERABLE_MARKET, smallBid, aliceAsk);
+        });
+
+        it("A bid for 10 fills Alice's ask for all 10 again", async () => {
+            const bigBid = await placeOrder(VULNERABLE_MARKET, carol, BID, INITIAL_PRICE, 10);
+            await match("vulnerableMatch", VULNERABLE_MARKET, bigBid, aliceAsk);
+
+            const ask = await program.account.order.fetch(aliceAsk.order);
+            const carolState = await fetchTrader(VULNERABLE_MARKET, carol);
+            console.log(`  Alice's ask: quantity ${ask.quantity.toNumber()}, filled ${ask.filled.toNumber()}`);
+
+            expect(ask.filled.toNumber()).to.equal(14);
+            expect(carolState.baseFree.toNumber()).to.equal(14);
+        });
+
+        it("The 4 extra units came out of Bob's escrow; Bob cannot cancel", async () => {
+            const market = await program.account.market.fetch(marketPda(VULNERABLE_MARKET));
+            expect(market.baseEscrowed.toNumber()).to.equal(6);
+
+            await expectError(cancel(VULNERABLE_MARKET, bob, bobAsk.order), "InsufficientEscrow");
+        });
+
+        it("Mallory matches Mallory's own orders at 100x the price", async () => {
+            const ask = await placeOrder(VULNERABLE_MARKET, mallory, ASK, MANIPULATED_PRICE, 1);
+            const bid = await placeOrder(VULNERABLE_MARKET, mallory, BID, MANIPULATED_PRICE, 1);
+            await match("vulnerableMatch", VULNERABLE_MARKET, bid, ask);
+
+            const market = await program.account.market.fetch(marketPda(VULNERABLE_MARKET));
+            console.log(`  Last traded price: ${market.lastPrice.toNumber()}`);
+            expect(market.lastPrice.toNumber()).to.equal(MANIPULATED_PRICE);
+        });
+    });
+
+    describe("Secure: remaining quantities, no self-trades, price band", () => {
+        let aliceAsk: Resting;
+        let bobAsk: Resting;
+        let bigBid: Resting;
+
+        it("After a fill of 4, a bid for 10 only takes the remaining 6", async () => {
+            aliceAsk = await placeOrder(SECURE_MARKET, alice, ASK, INITIAL_PRICE, 10);
+            bobAsk = await placeOrder(SECURE_MARKET, bob, ASK, INITIAL_PRICE, 10);
+
+            const smallBid = await placeOrder(SECURE_MARKET, carol, BID, INITIAL_PRICE, 4);
+            await match("secureMatch", SECURE_MARKET, smallBid, aliceAsk);
+
+            bigBid = await placeOrder(SECURE_MARKET, carol, BID, INITIAL_PRICE, 10);
+            await match("secureMatch", SECURE_MARKET, bigBid, aliceAsk);
+
+            const ask = await program.account.order.fetch(aliceAsk.order);
+            const bid = await program.account.order.fetch(bigBid.order);
+            expect(ask.filled.toNumber()).to.equal(10);
+            expect(bid.filled.toNumber()).to.equal(6);
+            expect((await fetchTrader(SECURE_MARKET, carol)).baseFree.toNumber()).to.equal(10);
+        });
+
+        it("A filled order cannot be matched again", async () => {
+            await expectError(match("secureMatch", SECURE_MARKET, bigBid, aliceAsk), "NothingToFill");
+        });
+
+        it("Bob's escrow is intact; Bob cancels and gets all 10 back", async () => {
+            await cancel(SECURE_MARKET, bob, bobAsk.order);
+            expect((await fetchTrader(SECURE_MARKET, bob)).baseFree.toNumber()).to.equal(10);
+        });
+
+        it("Rejects Mallory matching Mallory's own orders", async () => {
+            const ask = await placeOrder(SECURE_MARKET, mallory, ASK, INITIAL_PRICE, 1);
+            const bid = await placeOrder(SECURE_MARKET, mallory, BID, INITIAL_PRICE, 1);
+            await expectError(match("secureMatch", SECURE_MARKET, bid, ask), "SelfTrade");
+            await cancel(SECURE_MARKET, mallory, ask.order);
+        });
+
+        it("Rejects a trade outside the 10% band, even between different owners", async () => {
+            const ask = await placeOrder(SECURE_MARKET, mallory, ASK, MANIPULATED_PRICE, 1);
+            const bid = await placeOrder(SECURE_MARKET, carol, BID, MANIPULATED_PRICE, 1);
+            await expectError(match("secureMatch", SECURE_MARKET, bid, ask), "PriceOutOfBand");
+        });
+
+        it("A trade inside the band moves the price", async () => {
+            const ask = await placeOrder(SECURE_MARKET, bob, ASK, 105, 1);
+            const bid = await placeOrder(SECURE_MARKET, carol, BID, 105, 1);
+            await match("secureMatch", SECURE_MARKET, bid, ask);
+
+            const market = await program.account.market.fetch(marketPda(SECURE_MARKET));
+            expect(market.lastPrice.toNumber()).to.equal(105);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Order Book Partial-Fill Accounting ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  let fill = bid.quantity.min(ask.quantity);  // ignores `filled`");
+            console.log("  Risk: partial fills settle twice from other orders' escrow; self-trades set any price\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  let fill = (bid.quantity - bid.filled).min(ask.quantity - ask.filled);");
+            console.log("  require_keys_neq!(bid.owner, ask.owner); require!(within_band(price, last, band));");
+            console.log("  Protection: each unit settles once, and one trader cannot move the reference price\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  An order's size is what is left of it, and a trade needs two parties.");
+        });
+    });
+});
@@ -0,0 +1,271 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("Bp3FCi5mCFVagQba7HKYQwLZGHC2YMFWQR8YKo4sqhmx");
+
+/// Shared by both record types in the vulnerable paths.
+pub const ROLE_SEED: &[u8] = b"role";
+pub const MEMBER_SEED: &[u8] = b"member";
+pub const OPERATOR_SEED: &[u8] = b"operator";
+
+#[program]
+pub mod cross_type_seed_reuse {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.admin = ctx.accounts.admin.key();
+        treasury.bump = ctx.bumps.treasury;
+        Ok(())
+    }
+
+    pub fn fund(ctx: Context<Fund>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.funder.to_account_info(),
+            to: ctx.accounts.treasury.to_account_info(),
+        };
+        transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts), amount)
+    }
+
+    // VULNERABLE: One Seed Prefix for Two Record Types
+    // 1. Member records (anyone, for themselves) and operator records (admin
+    //    only) are both derived from `[b"role", user]`
+    // 2. The operator check re-derives the address from the record's stored
+    //    bump and skips the discriminator, on the assumption that an address
+    //    under this prefix can only be an operator record
+    // 3. A member record has the same layout and a valid bump for the same
+    //    seeds, so self-registering as a member passes as an operator
+    // 4. The shared address also means a user who registered as a member can
+    //    never be granted the operator role
+    pub fn vulnerable_register_member(ctx: Context<VulnerableRegisterMember>) -> Result<()> {
+        let record = &mut ctx.accounts.member_record;
+        record.user = ctx.accounts.member.key();
+        record.bump = ctx.bumps.member_record;
+        Ok(())
+    }
+
+    pub fn vulnerable_grant_operator(ctx: Context<VulnerableGrantOperator>, operator: Pubkey) -> Result<()> {
+        let record = &mut ctx.accounts.operator_record;
+        record.user = operator;
+        record.bump = ctx.bumps.operator_record;
+        Ok(())
+    }
+
+    pub fn vulnerable_operator_withdraw(ctx: Context<VulnerableOperatorWithdraw>, amount: u64) -> Result<()> {
+        let info = ctx.accounts.operator_record.to_account_info();
+        let record = OperatorRecord::try_deserialize_unchecked(&mut &info.data.borrow()[..])?;
+
+        let operator = ctx.accounts.operator.key();
+        let expected = role_address(ROLE_SEED, &operator, record.bump).ok_or(RoleError::InvalidRoleRecord)?;
+        require_keys_eq!(info.key(), expected, RoleError::InvalidRoleRecord);
+        require_keys_eq!(record.user, operator, RoleError::Unauthorized);
+
+        pay_from_treasury(&ctx.accounts.treasury, &ctx.accounts.operator.to_account_info(), amount)
+    }
+
+    // SECURE: A Seed Prefix and a Type per Record
+    // 1. Member records live under `b"member"`, operator records under
+    //    `b"operator"`, so the two can never share an address
+    // 2. The operator record is loaded as `Account<OperatorRecord>`: the
+    //    discriminator must match, and the seeds are re-derived with the
+    //    operator prefix and the bump stored on that type
+    // 3. Being a member no longer says anything about being an operator
+    pub fn secure_register_member(ctx: Context<SecureRegisterMember>) -> Result<()> {
+        let record = &mut ctx.accounts.member_record;
+        record.user = ctx.accounts.member.key();
+        record.bump = ctx.bumps.member_record;
+        Ok(())
+    }
+
+    pub fn secure_grant_operator(ctx: Context<SecureGrantOperator>, operator: Pubkey) -> Result<()> {
+        let record = &mut ctx.accounts.operator_record;
+        record.user = operator;
+        record.bump = ctx.bumps.operator_record;
+        Ok(())
+    }
+
+    pub fn secure_operator_withdraw(ctx: Context<SecureOperatorWithdraw>, amount: u64) -> Result<()> {
+        pay_from_treasury(&ctx.accounts.treasury, &ctx.accounts.operator.to_account_info(), amount)
+    }
+}
+
+/// The record address for `user` under `prefix` with `bump`, or `None` if
+/// that bump lands on the curve.
+fn role_address(prefix: &[u8], user: &Pubkey, bump: u8) -> Option<Pubkey> {
+    Pubkey::create_program_address(&[prefix, user.as_ref(), &[bump]], &crate::ID).ok()
+}
+
+/// Moves `amount` lamports from the treasury to `to`, keeping the treasury
+/// rent-exempt.
+fn pay_from_treasury(treasury: &Account<Treasury>, to: &AccountInfo, amount: u64) -> Result<()> {
+    let from = treasury.to_account_info();
+    let floor = Rent::get()?.minimum_balance(from.data_len());
+    let remaining = from.lamports().checked_sub(amount).ok_or(RoleError::InsufficientFunds)?;
+    require!(remaining >= floor, RoleError::InsufficientFunds);
+
+    **from.try_borrow_mut_lamports()? = remaining;
+    **to.try_borrow_mut_lamports()? = to.lamports().checked_add(amount).ok_or(RoleError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = admin, space = 8 + Treasury::INIT_SPACE, seeds = [b"treasury"], bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Fund<'info> {
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableRegisterMember<'info> {
+    #[account(
+        init,
+        payer = member,
+        space = 8 + MemberRecord::INIT_SPACE,
+        seeds = [ROLE_SEED, member.key().as_ref()],
+        bump
+    )]
+    pub member_record: Account<'info, MemberRecord>,
+    #[account(mut)]
+    pub member: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(operator: Pubkey)]
+pub struct VulnerableGrantOperator<'info> {
+    #[account(seeds = [b"treasury"], bump = treasury.bump, has_one = admin @ RoleError::Unauthorized)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + OperatorRecord::INIT_SPACE,
+        seeds = [ROLE_SEED, operator.as_ref()],
+        bump
+    )]
+    pub operator_record: Account<'info, OperatorRecord>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableOperatorWithdraw<'info> {
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    /// CHECK: owned by this program; address re-derived from its stored bump in the handler.
+    #[account(owner = crate::ID)]
+    pub operator_record: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub operator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SecureRegisterMember<'info> {
+    #[account(
+        init,
+        payer = member,
+        space = 8 + MemberRecord::INIT_SPACE,
+        seeds = [MEMBER_SEED, member.key().as_ref()],
+        bump
+    )]
+    pub member_record: Account<'info, MemberRecord>,
+    #[account(mut)]
+    pub member: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(operator: Pubkey)]
+pub struct SecureGrantOperator<'info> {
+    #[account(seeds = [b"treasury"], bump = treasury.bump, has_one = admin @ RoleError::Unauthorized)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + OperatorRecord::INIT_SPACE,
+        seeds = [OPERATOR_SEED, operator.as_ref()],
+        bump
+    )]
+    pub operator_record: Account<'info, OperatorRecord>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SecureOperatorWithdraw<'info> {
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        seeds = [OPERATOR_SEED, operator.key().as_ref()],
+        bump = operator_record.bump,
+        constraint = operator_record.user == operator.key() @ RoleError::Unauthorized
+    )]
+    pub operator_record: Account<'info, OperatorRecord>,
+    #[account(mut)]
+    pub operator: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Treasury {
+    pub admin: Pubkey,
+    pub bump: u8,
+}
+
+/// Self-registered; grants nothing on its own.
+#[account]
+#[derive(InitSpace)]
+pub struct MemberRecord {
+    pub user: Pubkey,
+    pub bump: u8,
+}
+
+/// Granted by the admin; allows treasury withdrawals.
+#[account]
+#[derive(InitSpace)]
+pub struct OperatorRecord {
+    pub user: Pubkey,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum RoleError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Role record does not match its seeds")]
+    InvalidRoleRecord,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Arithmetic error")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_prefix_puts_both_types_at_one_address() {
+        let user = Pubkey::new_unique();
+        let (member, bump) = Pubkey::find_program_address(&[ROLE_SEED, user.as_ref()], &crate::ID);
+
+        // A member's stored bump derives the address an operator check expects.
+        assert_eq!(role_address(ROLE_SEED, &user, bump), Some(member));
+
+        let (member, _) = Pubkey::find_program_address(&[MEMBER_SEED, user.as_ref()], &crate::ID);
+        let (operator, _) = Pubkey::find_program_address(&[OPERATOR_SEED, user.as_ref()], &crate::ID);
+        assert_ne!(member, operator);
+    }
+}
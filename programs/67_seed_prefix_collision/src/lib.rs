@@ -0,0 +1,285 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+pub mod seeds;
+
+declare_id!("4Gnu5gqsaxVoSXZr5SkHHxgSKCFsmLA5Ty7WgkmXbBrP");
+
+#[program]
+pub mod seed_prefix_collision {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.admin = ctx.accounts.admin.key();
+        pool.bump = ctx.bumps.pool;
+        Ok(())
+    }
+
+    pub fn fund(ctx: Context<Fund>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.funder.to_account_info(),
+            to: ctx.accounts.pool.to_account_info(),
+        };
+        transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts), amount)
+    }
+
+    // VULNERABLE: Copy-Pasted Seed Prefix Across Features
+    // 1. The preferences feature and the credit feature both derive their
+    //    per-user account from `[b"user", owner]`
+    // 2. Each feature named its account `UserAccount`; Anchor's discriminator
+    //    is the struct name only, so the two types are indistinguishable
+    // 3. A preferences account, which the user fills in freely, passes the
+    //    credit feature's seeds constraint and discriminator check
+    // 4. The user-chosen `alert_threshold` is read as `credit_limit`
+    pub fn vulnerable_open_preferences(ctx: Context<VulnerableOpenPreferences>, alert_threshold: u64) -> Result<()> {
+        let prefs = &mut ctx.accounts.preferences;
+        prefs.owner = ctx.accounts.owner.key();
+        prefs.bump = ctx.bumps.preferences;
+        prefs.alert_threshold = alert_threshold;
+        Ok(())
+    }
+
+    pub fn vulnerable_open_credit_line(
+        ctx: Context<VulnerableOpenCreditLine>,
+        owner: Pubkey,
+        credit_limit: u64,
+    ) -> Result<()> {
+        let line = &mut ctx.accounts.credit_line;
+        line.owner = owner;
+        line.bump = ctx.bumps.credit_line;
+        line.credit_limit = credit_limit;
+        Ok(())
+    }
+
+    pub fn vulnerable_borrow(ctx: Context<VulnerableBorrow>, amount: u64) -> Result<()> {
+        let line = &mut ctx.accounts.credit_line;
+        line.draw(amount)?;
+        pay_from_pool(&ctx.accounts.pool, &ctx.accounts.owner.to_account_info(), amount)
+    }
+
+    // SECURE: One Module Owns Every Seed Prefix
+    // 1. Prefixes live in `seeds.rs`, one constant per account kind
+    // 2. A unit test asserts no prefix equals or starts another, so no two
+    //    features can derive the same address
+    // 3. A preferences account now sits at a different address from any
+    //    credit line and fails the credit feature's seeds constraint
+    pub fn secure_open_preferences(ctx: Context<SecureOpenPreferences>, alert_threshold: u64) -> Result<()> {
+        let prefs = &mut ctx.accounts.preferences;
+        prefs.owner = ctx.accounts.owner.key();
+        prefs.bump = ctx.bumps.preferences;
+        prefs.alert_threshold = alert_threshold;
+        Ok(())
+    }
+
+    pub fn secure_open_credit_line(ctx: Context<SecureOpenCreditLine>, owner: Pubkey, credit_limit: u64) -> Result<()> {
+        let line = &mut ctx.accounts.credit_line;
+        line.owner = owner;
+        line.bump = ctx.bumps.credit_line;
+        line.credit_limit = credit_limit;
+        Ok(())
+    }
+
+    pub fn secure_borrow(ctx: Context<SecureBorrow>, amount: u64) -> Result<()> {
+        let line = &mut ctx.accounts.credit_line;
+        line.draw(amount)?;
+        pay_from_pool(&ctx.accounts.pool, &ctx.accounts.owner.to_account_info(), amount)
+    }
+}
+
+/// Account state owned by the preferences feature.
+pub mod preferences {
+    use super::*;
+
+    #[account]
+    #[derive(InitSpace)]
+    pub struct UserAccount {
+        pub owner: Pubkey,
+        pub bump: u8,
+        /// Lamport balance below which the user wants an alert; any value.
+        pub alert_threshold: u64,
+        pub muted_until: i64,
+    }
+}
+
+/// Account state owned by the credit feature.
+pub mod credit {
+    use super::*;
+
+    #[account]
+    #[derive(InitSpace)]
+    pub struct UserAccount {
+        pub owner: Pubkey,
+        pub bump: u8,
+        /// Set by the admin.
+        pub credit_limit: u64,
+        pub borrowed: u64,
+    }
+
+    impl UserAccount {
+        pub fn draw(&mut self, amount: u64) -> Result<()> {
+            let borrowed = self.borrowed.checked_add(amount).ok_or(CreditError::ArithmeticError)?;
+            require!(borrowed <= self.credit_limit, CreditError::CreditLimitExceeded);
+            self.borrowed = borrowed;
+            Ok(())
+        }
+    }
+}
+
+/// Moves `amount` lamports from the pool to `to`, keeping the pool
+/// rent-exempt.
+fn pay_from_pool(pool: &Account<Pool>, to: &AccountInfo, amount: u64) -> Result<()> {
+    let from = pool.to_account_info();
+    let floor = Rent::get()?.minimum_balance(from.data_len());
+    let remaining = from.lamports().checked_sub(amount).ok_or(CreditError::InsufficientFunds)?;
+    require!(remaining >= floor, CreditError::InsufficientFunds);
+
+    **from.try_borrow_mut_lamports()? = remaining;
+    **to.try_borrow_mut_lamports()? = to.lamports().checked_add(amount).ok_or(CreditError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = admin, space = 8 + Pool::INIT_SPACE, seeds = [seeds::POOL], bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Fund<'info> {
+    #[account(mut, seeds = [seeds::POOL], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableOpenPreferences<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + preferences::UserAccount::INIT_SPACE,
+        seeds = [b"user", owner.key().as_ref()],
+        bump
+    )]
+    pub preferences: Account<'info, preferences::UserAccount>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct VulnerableOpenCreditLine<'info> {
+    #[account(seeds = [seeds::POOL], bump = pool.bump, has_one = admin @ CreditError::Unauthorized)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + credit::UserAccount::INIT_SPACE,
+        seeds = [b"user", owner.as_ref()],
+        bump
+    )]
+    pub credit_line: Account<'info, credit::UserAccount>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableBorrow<'info> {
+    #[account(mut, seeds = [seeds::POOL], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mut,
+        seeds = [b"user", owner.key().as_ref()],
+        bump = credit_line.bump,
+        has_one = owner @ CreditError::Unauthorized
+    )]
+    pub credit_line: Account<'info, credit::UserAccount>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SecureOpenPreferences<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + preferences::UserAccount::INIT_SPACE,
+        seeds = [seeds::PREFERENCES, owner.key().as_ref()],
+        bump
+    )]
+    pub preferences: Account<'info, preferences::UserAccount>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct SecureOpenCreditLine<'info> {
+    #[account(seeds = [seeds::POOL], bump = pool.bump, has_one = admin @ CreditError::Unauthorized)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + credit::UserAccount::INIT_SPACE,
+        seeds = [seeds::CREDIT_LINE, owner.as_ref()],
+        bump
+    )]
+    pub credit_line: Account<'info, credit::UserAccount>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SecureBorrow<'info> {
+    #[account(mut, seeds = [seeds::POOL], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mut,
+        seeds = [seeds::CREDIT_LINE, owner.key().as_ref()],
+        bump = credit_line.bump,
+        has_one = owner @ CreditError::Unauthorized
+    )]
+    pub credit_line: Account<'info, credit::UserAccount>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub admin: Pubkey,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum CreditError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Credit limit exceeded")]
+    CreditLimitExceeded,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Arithmetic error")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+
+    #[test]
+    fn same_name_means_same_discriminator() {
+        assert_eq!(preferences::UserAccount::DISCRIMINATOR, credit::UserAccount::DISCRIMINATOR);
+        assert_eq!(preferences::UserAccount::INIT_SPACE, credit::UserAccount::INIT_SPACE);
+    }
+}
@@ -0,0 +1,33 @@
+//! Every seed prefix used by the secure features and the shared pool.
+//!
+//! A prefix is only unique if no other prefix starts with it: seeds are
+//! hashed as one concatenated byte string, so `[b"stake", b"_v2..."]` and
+//! `[b"stake_v2", ...]` can derive the same address.
+
+pub const POOL: &[u8] = b"pool";
+pub const PREFERENCES: &[u8] = b"preferences";
+pub const CREDIT_LINE: &[u8] = b"credit_line";
+
+/// Add new prefixes here so the uniqueness test covers them.
+pub const ALL: &[&[u8]] = &[POOL, PREFERENCES, CREDIT_LINE];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_prefix_starts_with_another() {
+        for (i, a) in ALL.iter().enumerate() {
+            for (j, b) in ALL.iter().enumerate() {
+                if i != j {
+                    assert!(
+                        !a.starts_with(b),
+                        "{:?} collides with {:?}",
+                        String::from_utf8_lossy(a),
+                        String::from_utf8_lossy(b)
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("67: Cross-Feature Seed Prefix Collision", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.SeedPrefixCollision as Program<any>;
+
+    let alice: Keypair;
+    let bob: Keypair;
+    let mallory: Keypair;
+
+    const POOL_FUNDS = 10 * LAMPORTS_PER_SOL;
+    const BOB_LIMIT = LAMPORTS_PER_SOL;
+    // An "alert me below 3 SOL" preference, read as a 3 SOL credit limit.
+    const MALLORY_THRESHOLD = 3 * LAMPORTS_PER_SOL;
+
+    const poolPda = PublicKey.findProgramAddressSync([Buffer.from("pool")], program.programId)[0];
+
+    function userPda(prefix: "user" | "preferences" | "credit_line", owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from(prefix), owner.toBuffer()], program.programId)[0];
+    }
+
+    function openPreferences(method: "vulnerableOpenPreferences" | "secureOpenPreferences", owner: Keypair) {
+        const prefix = method === "vulnerableOpenPreferences" ? "user" : "preferences";
+        return program.methods[method](new anchor.BN(MALLORY_THRESHOLD))
+            .accounts({
+                preferences: userPda(prefix, owner.publicKey),
+                owner: owner.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc();
+    }
+
+    function openCreditLine(method: "vulnerableOpenCreditLine" | "secureOpenCreditLine", owner: PublicKey) {
+        const prefix = method === "vulnerableOpenCreditLine" ? "user" : "credit_line";
+        return program.methods[method](owner, new anchor.BN(BOB_LIMIT))
+            .accounts({
+                pool: poolPda,
+                creditLine: userPda(prefix, owner),
+                admin: alice.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([alice])
+            .rpc();
+    }
+
+    function borrow(method: "vulnerableBorrow" | "secureBorrow", owner: Keypair, creditLine: PublicKey, amount: number) {
+        return program.methods[method](new anchor.BN(amount))
+            .accounts({ pool: poolPda, creditLine, owner: owner.publicKey })
+            .signers([owner])
+            .rpc();
+    }
+
+    before(async () => {
+        alice = Keypair.generate();
+        bob = Keypair.generate();
+        mallory = Keypair.generate();
+
+        for (const kp of [alice, bob, mallory]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 20 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Alice is the admin of a funded credit pool", async () => {
+            await program.methods
+                .initialize()
+                .accounts({ pool: poolPda, admin: alice.publicKey, systemProgram: SystemProgram.programId })
+                .signers([alice])
+                .rpc();
+            await program.methods
+                .fund(new anchor.BN(POOL_FUNDS))
+                .accounts({ pool: poolPda, funder: alice.publicKey, systemProgram: SystemProgram.programId })
+                .signers([alice])
+                .rpc();
+        });
+    });
+
+    describe("Exploit: both features derive [b\"user\", owner]", () => {
+        it("Bob is given a 1 SOL credit line and cannot borrow past it", async () => {
+            await openCreditLine("vulnerableOpenCreditLine", bob.publicKey);
+            await borrow("vulnerableBorrow", bob, userPda("user", bob.publicKey), BOB_LIMIT);
+            await expectError(borrow("vulnerableBorrow", bob, userPda("user", bob.publicKey), 1), "CreditLimitExceeded");
+        });
+
+        it("Mallory saves a 3 SOL alert threshold and borrows it as a credit limit", async () => {
+            await openPreferences("vulnerableOpenPreferences", mallory);
+
+            const before = await provider.connection.getBalance(mallory.publicKey);
+            await borrow("vulnerableBorrow", mallory, userPda("user", mallory.publicKey), MALLORY_THRESHOLD);
+            const after = await provider.connection.getBalance(mallory.publicKey);
+
+            console.log(`  Mallory borrowed ${(after - before) / LAMPORTS_PER_SOL} SOL with no credit line`);
+            expect(after).to.be.greaterThan(before);
+        });
+
+        it("Mallory can no longer be given a real credit line", async () => {
+            try {
+                await openCreditLine("vulnerableOpenCreditLine", mallory.publicKey);
+                expect.fail("Should have thrown");
+            } catch (error: any) {
+                const output = [error.message, ...(error.logs ?? [])].join("\n");
+                expect(output).to.include("already in use");
+            }
+        });
+    });
+
+    describe("Secure: prefixes from one seeds module", () => {
+        it("Mallory's preferences sit at a different address from any credit line", async () => {
+            await openPreferences("secureOpenPreferences", mallory);
+            expect(userPda("preferences", mallory.publicKey).equals(userPda("credit_line", mallory.publicKey))).to.equal(
+                false
+            );
+        });
+
+        it("Rejects borrowing against Mallory's preferences account", async () => {
+            await expectError(
+                borrow("secureBorrow", mallory, userPda("preferences", mallory.publicKey), MALLORY_THRESHOLD),
+                "ConstraintSeeds"
+            );
+        });
+
+        it("Bob borrows within a credit line opened under the credit prefix", async () => {
+            await openCreditLine("secureOpenCreditLine", bob.publicKey);
+            await borrow("secureBorrow", bob, userPda("credit_line", bob.publicKey), BOB_LIMIT);
+            await expectError(
+                borrow("secureBorrow", bob, userPda("credit_line", bob.publicKey), 1),
+                "CreditLimitExceeded"
+            );
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Cross-Feature Seed Prefix Collision ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log('  preferences: seeds = [b"user", owner]   credit: seeds = [b"user", owner]');
+            console.log("  Risk: one feature's account passes the other's constraints; user fields become limits\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  seeds = [seeds::PREFERENCES, owner] / [seeds::CREDIT_LINE, owner], checked unique in a test");
+            console.log("  Protection: every account kind has its own address space\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Seed prefixes are a program-wide namespace. Keep them in one place and test it.");
+        });
+    });
+});
@@ -0,0 +1,21 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VaultError {
+    /// The account already holds an initialized vault.
+    AlreadyInitialized,
+    /// The account has not been initialized.
+    UninitializedAccount,
+    /// The account's discriminator is not the vault's.
+    InvalidDiscriminator,
+    /// The signer is not the vault's authority.
+    Unauthorized,
+    /// The withdrawal would leave the vault below rent exemption.
+    InsufficientFunds,
+}
+
+impl From<VaultError> for ProgramError {
+    fn from(e: VaultError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,176 @@
+//! A native (non-Anchor) vault program that packs and unpacks its account
+//! data by hand. Each vault account holds its own lamports; anyone can
+//! deposit with a plain system transfer, and only the authority can
+//! withdraw.
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    declare_id,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+pub mod error;
+pub mod state;
+
+use error::VaultError;
+use state::Vault;
+
+declare_id!("5pxkDgwfns36bBwa3E2SgMDzzmGqYtCSqVFxVu1Y4kZC");
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// Instructions, encoded as a one-byte tag followed by any arguments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VaultInstruction {
+    /// Accounts: `[writable] vault`, `[signer] authority`.
+    VulnerableInitialize,
+    /// Accounts: `[writable] vault`, `[writable, signer] authority`.
+    VulnerableWithdraw { amount: u64 },
+    /// Accounts: `[writable] vault`, `[signer] authority`.
+    SecureInitialize,
+    /// Accounts: `[writable] vault`, `[writable, signer] authority`.
+    SecureWithdraw { amount: u64 },
+}
+
+impl VaultInstruction {
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let (&tag, rest) = data.split_first().ok_or(ProgramError::InvalidInstructionData)?;
+        Ok(match tag {
+            0 => Self::VulnerableInitialize,
+            1 => Self::VulnerableWithdraw { amount: unpack_amount(rest)? },
+            2 => Self::SecureInitialize,
+            3 => Self::SecureWithdraw { amount: unpack_amount(rest)? },
+            _ => return Err(ProgramError::InvalidInstructionData),
+        })
+    }
+}
+
+fn unpack_amount(rest: &[u8]) -> Result<u64, ProgramError> {
+    let bytes: [u8; 8] = rest.try_into().map_err(|_| ProgramError::InvalidInstructionData)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+pub fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    match VaultInstruction::unpack(data)? {
+        VaultInstruction::VulnerableInitialize => vulnerable_initialize(program_id, accounts),
+        VaultInstruction::VulnerableWithdraw { amount } => vulnerable_withdraw(program_id, accounts, amount),
+        VaultInstruction::SecureInitialize => secure_initialize(program_id, accounts),
+        VaultInstruction::SecureWithdraw { amount } => secure_withdraw(program_id, accounts, amount),
+    }
+}
+
+// VULNERABLE: Manual Layout Without Initialization or Type Checks
+// 1. `vulnerable_initialize` writes a fresh vault over whatever the account
+//    holds, without reading `is_initialized` first
+// 2. Anyone can call it on someone else's funded vault and become its
+//    authority
+// 3. `vulnerable_withdraw` reads the authority with `unpack_unchecked`:
+//    any program-owned account at least `LEN` bytes long parses, whatever
+//    its discriminator, length or initialization state
+fn vulnerable_initialize(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let vault_info = next_account_info(iter)?;
+    let authority = next_account_info(iter)?;
+    check_owner_and_signer(program_id, vault_info, authority)?;
+
+    let vault = Vault { is_initialized: true, authority: *authority.key };
+    vault.pack(&mut vault_info.try_borrow_mut_data()?)
+}
+
+fn vulnerable_withdraw(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let vault_info = next_account_info(iter)?;
+    let authority = next_account_info(iter)?;
+    check_owner_and_signer(program_id, vault_info, authority)?;
+
+    let vault = Vault::unpack_unchecked(&vault_info.try_borrow_data()?)?;
+    if vault.authority != *authority.key {
+        return Err(VaultError::Unauthorized.into());
+    }
+    pay_out(vault_info, authority, amount)
+}
+
+// SECURE: Exact Length, Discriminator and Initialization Flag
+// 1. `secure_initialize` only writes to blank space: exactly `LEN` zeroed
+//    bytes, so an existing vault (or any other data) is never overwritten
+// 2. `secure_withdraw` reads through `Vault::unpack`, which rejects the
+//    wrong length, a foreign discriminator and an unset `is_initialized`
+fn secure_initialize(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let vault_info = next_account_info(iter)?;
+    let authority = next_account_info(iter)?;
+    check_owner_and_signer(program_id, vault_info, authority)?;
+
+    let mut data = vault_info.try_borrow_mut_data()?;
+    if !Vault::is_blank(&data) {
+        return Err(VaultError::AlreadyInitialized.into());
+    }
+    if !Rent::get()?.is_exempt(vault_info.lamports(), data.len()) {
+        return Err(ProgramError::AccountNotRentExempt);
+    }
+
+    let vault = Vault { is_initialized: true, authority: *authority.key };
+    vault.pack(&mut data)
+}
+
+fn secure_withdraw(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let vault_info = next_account_info(iter)?;
+    let authority = next_account_info(iter)?;
+    check_owner_and_signer(program_id, vault_info, authority)?;
+
+    let vault = Vault::unpack(&vault_info.try_borrow_data()?)?;
+    if vault.authority != *authority.key {
+        return Err(VaultError::Unauthorized.into());
+    }
+    pay_out(vault_info, authority, amount)
+}
+
+fn check_owner_and_signer(program_id: &Pubkey, vault: &AccountInfo, authority: &AccountInfo) -> ProgramResult {
+    if vault.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+/// Moves `amount` lamports from the vault to `to`, keeping the vault
+/// rent-exempt.
+fn pay_out(vault: &AccountInfo, to: &AccountInfo, amount: u64) -> ProgramResult {
+    let floor = Rent::get()?.minimum_balance(vault.data_len());
+    let remaining = vault.lamports().checked_sub(amount).ok_or(VaultError::InsufficientFunds)?;
+    if remaining < floor {
+        return Err(VaultError::InsufficientFunds.into());
+    }
+
+    **vault.try_borrow_mut_lamports()? = remaining;
+    **to.try_borrow_mut_lamports()? = to.lamports().checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+    msg!("Withdrew {} lamports", amount);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instructions_decode_from_tag_and_amount() {
+        assert_eq!(VaultInstruction::unpack(&[0]), Ok(VaultInstruction::VulnerableInitialize));
+
+        let mut withdraw = vec![3];
+        withdraw.extend_from_slice(&5u64.to_le_bytes());
+        assert_eq!(VaultInstruction::unpack(&withdraw), Ok(VaultInstruction::SecureWithdraw { amount: 5 }));
+
+        assert_eq!(VaultInstruction::unpack(&[3, 1, 2]), Err(ProgramError::InvalidInstructionData));
+        assert_eq!(VaultInstruction::unpack(&[9]), Err(ProgramError::InvalidInstructionData));
+        assert_eq!(VaultInstruction::unpack(&[]), Err(ProgramError::InvalidInstructionData));
+    }
+}
@@ -0,0 +1,107 @@
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+use crate::error::VaultError;
+
+/// Hand-rolled account layout, in the style of native programs:
+///
+/// | Offset | Size | Field            |
+/// |--------|------|------------------|
+/// | 0      | 8    | discriminator    |
+/// | 8      | 1    | `is_initialized` |
+/// | 9      | 32   | `authority`      |
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Vault {
+    pub is_initialized: bool,
+    pub authority: Pubkey,
+}
+
+impl Vault {
+    pub const DISCRIMINATOR: [u8; 8] = *b"vault\0v1";
+    pub const LEN: usize = 8 + 1 + 32;
+
+    /// Writes the full layout, discriminator included, to the front of `dst`.
+    pub fn pack(&self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        if dst.len() < Self::LEN {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        dst[..8].copy_from_slice(&Self::DISCRIMINATOR);
+        dst[8] = self.is_initialized as u8;
+        dst[9..41].copy_from_slice(self.authority.as_ref());
+        Ok(())
+    }
+
+    /// Reads the fields at their offsets and nothing else: no length,
+    /// discriminator or initialization check.
+    pub fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        let bytes = src.get(..Self::LEN).ok_or(ProgramError::InvalidAccountData)?;
+        Ok(Self {
+            is_initialized: bytes[8] != 0,
+            authority: Pubkey::try_from(&bytes[9..41]).map_err(|_| ProgramError::InvalidAccountData)?,
+        })
+    }
+
+    /// Reads a vault only from exactly `LEN` bytes that carry the vault
+    /// discriminator and a set `is_initialized` flag.
+    pub fn unpack(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if src[..8] != Self::DISCRIMINATOR {
+            return Err(VaultError::InvalidDiscriminator.into());
+        }
+        if src[8] != 1 {
+            return Err(VaultError::UninitializedAccount.into());
+        }
+        Self::unpack_unchecked(src)
+    }
+
+    /// Whether `src` is fresh, zeroed space of exactly `LEN` bytes.
+    pub fn is_blank(src: &[u8]) -> bool {
+        src.len() == Self::LEN && src.iter().all(|b| *b == 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packed(vault: Vault, len: usize) -> Vec<u8> {
+        let mut data = vec![0; len];
+        vault.pack(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn unpack_accepts_only_initialized_vaults_of_exact_length() {
+        let vault = Vault { is_initialized: true, authority: Pubkey::new_unique() };
+        assert_eq!(Vault::unpack(&packed(vault, Vault::LEN)), Ok(vault));
+
+        assert_eq!(Vault::unpack(&packed(vault, Vault::LEN + 8)), Err(ProgramError::InvalidAccountData));
+        assert_eq!(Vault::unpack(&[0; Vault::LEN]), Err(VaultError::InvalidDiscriminator.into()));
+
+        let mut other_type = packed(vault, Vault::LEN);
+        other_type[..8].copy_from_slice(b"receipt1");
+        assert_eq!(Vault::unpack(&other_type), Err(VaultError::InvalidDiscriminator.into()));
+
+        let pending = Vault { is_initialized: false, ..vault };
+        assert_eq!(Vault::unpack(&packed(pending, Vault::LEN)), Err(VaultError::UninitializedAccount.into()));
+    }
+
+    #[test]
+    fn unpack_unchecked_reads_anything_long_enough() {
+        let mut other_type = vec![7; Vault::LEN + 8];
+        other_type[8] = 0;
+        let vault = Vault::unpack_unchecked(&other_type).unwrap();
+        assert!(!vault.is_initialized);
+        assert_eq!(vault.authority, Pubkey::new_from_array([7; 32]));
+    }
+
+    #[test]
+    fn only_zeroed_space_of_exact_length_is_blank() {
+        assert!(Vault::is_blank(&[0; Vault::LEN]));
+        assert!(!Vault::is_blank(&[0; Vault::LEN + 1]));
+
+        let vault = Vault { is_initialized: true, authority: Pubkey::new_unique() };
+        assert!(!Vault::is_blank(&packed(vault, Vault::LEN)));
+    }
+}
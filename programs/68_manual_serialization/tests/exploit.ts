@@ -0,0 +1,200 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+    Keypair,
+    LAMPORTS_PER_SOL,
+    PublicKey,
+    SystemProgram,
+    Transaction,
+    TransactionInstruction,
+} from "@solana/web3.js";
+import { expect } from "chai";
+
+// Native program: no IDL, so instructions are encoded by hand.
+const PROGRAM_ID = new PublicKey("5pxkDgwfns36bBwa3E2SgMDzzmGqYtCSqVFxVu1Y4kZC");
+const VAULT_LEN = 8 + 1 + 32;
+
+const Tag = { VulnerableInitialize: 0, VulnerableWithdraw: 1, SecureInitialize: 2, SecureWithdraw: 3 } as const;
+
+// `VaultError` variants, in declaration order.
+const VaultError = { AlreadyInitialized: 0, UninitializedAccount: 1, InvalidDiscriminator: 2, Unauthorized: 3 } as const;
+
+describe("68: Manual Serialization Without Initialization Checks", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+    const connection = provider.connection;
+
+    let alice: Keypair;
+    let mallory: Keypair;
+
+    const DEPOSIT = 5 * LAMPORTS_PER_SOL;
+
+    function initializeIx(tag: number, vault: PublicKey, authority: PublicKey) {
+        return new TransactionInstruction({
+            programId: PROGRAM_ID,
+            keys: [
+                { pubkey: vault, isSigner: false, isWritable: true },
+                { pubkey: authority, isSigner: true, isWritable: false },
+            ],
+            data: Buffer.from([tag]),
+        });
+    }
+
+    function withdrawIx(tag: number, vault: PublicKey, authority: PublicKey, amount: number) {
+        const data = Buffer.alloc(9);
+        data.writeUInt8(tag, 0);
+        data.writeBigUInt64LE(BigInt(amount), 1);
+        return new TransactionInstruction({
+            programId: PROGRAM_ID,
+            keys: [
+                { pubkey: vault, isSigner: false, isWritable: true },
+                { pubkey: authority, isSigner: true, isWritable: true },
+            ],
+            data,
+        });
+    }
+
+    /** Allocates a program-owned account of `space` bytes, optionally initializing it in the same transaction. */
+    async function createVault(payer: Keypair, space: number, initTag?: number): Promise<PublicKey> {
+        const vault = Keypair.generate();
+        const lamports = await connection.getMinimumBalanceForRentExemption(space);
+        const tx = new Transaction().add(
+            SystemProgram.createAccount({
+                fromPubkey: payer.publicKey,
+                newAccountPubkey: vault.publicKey,
+                lamports,
+                space,
+                programId: PROGRAM_ID,
+            })
+        );
+        if (initTag !== undefined) {
+            tx.add(initializeIx(initTag, vault.publicKey, payer.publicKey));
+        }
+        await provider.sendAndConfirm(tx, [payer, vault]);
+        return vault.publicKey;
+    }
+
+    async function send(ix: TransactionInstruction, signer: Keypair) {
+        return provider.sendAndConfirm(new Transaction().add(ix), [signer]);
+    }
+
+    async function deposit(from: Keypair, vault: PublicKey, lamports: number) {
+        const tx = new Transaction().add(SystemProgram.transfer({ fromPubkey: from.publicKey, toPubkey: vault, lamports }));
+        await provider.sendAndConfirm(tx, [from]);
+    }
+
+    async function expectFailure(promise: Promise<unknown>, fragment: string) {
+        try {
+            await promise;
+            expect.fail("Should have thrown");
+        } catch (error: any) {
+            const output = [error.message, ...(error.logs ?? [])].join("\n");
+            expect(output).to.include(fragment);
+        }
+    }
+
+    function customError(code: number) {
+        return `custom program error: 0x${code.toString(16)}`;
+    }
+
+    before(async () => {
+        alice = Keypair.generate();
+        mallory = Keypair.generate();
+
+        for (const kp of [alice, mallory]) {
+            const sig = await connection.requestAirdrop(kp.publicKey, 20 * LAMPORTS_PER_SOL);
+            await connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Exploit: initialize writes over a live vault", () => {
+        let vault: PublicKey;
+
+        it("Alice creates and funds a vault through the vulnerable initializer", async () => {
+            vault = await createVault(alice, VAULT_LEN, Tag.VulnerableInitialize);
+            await deposit(alice, vault, DEPOSIT);
+        });
+
+        it("Mallory re-initializes Alice's vault with Mallory as authority", async () => {
+            await send(initializeIx(Tag.VulnerableInitialize, vault, mallory.publicKey), mallory);
+
+            const data = (await connection.getAccountInfo(vault))!.data;
+            expect(new PublicKey(data.subarray(9, 41)).equals(mallory.publicKey)).to.equal(true);
+        });
+
+        it("Mallory withdraws Alice's deposit; Alice is locked out", async () => {
+            const before = await connection.getBalance(mallory.publicKey);
+            await send(withdrawIx(Tag.VulnerableWithdraw, vault, mallory.publicKey, DEPOSIT), mallory);
+            const after = await connection.getBalance(mallory.publicKey);
+
+            console.log(`  Mallory withdrew ${(after - before) / LAMPORTS_PER_SOL} SOL from Alice's vault`);
+            expect(after).to.be.greaterThan(before);
+
+            await expectFailure(
+                send(withdrawIx(Tag.VulnerableWithdraw, vault, alice.publicKey, 1), alice),
+                customError(VaultError.Unauthorized)
+            );
+        });
+    });
+
+    describe("Secure: exact length, discriminator, is_initialized", () => {
+        let vault: PublicKey;
+
+        it("Alice creates and funds a vault through the secure initializer", async () => {
+            vault = await createVault(alice, VAULT_LEN, Tag.SecureInitialize);
+            await deposit(alice, vault, DEPOSIT);
+        });
+
+        it("Rejects Mallory re-initializing Alice's vault", async () => {
+            await expectFailure(
+                send(initializeIx(Tag.SecureInitialize, vault, mallory.publicKey), mallory),
+                customError(VaultError.AlreadyInitialized)
+            );
+        });
+
+        it("Rejects Mallory withdrawing from Alice's vault", async () => {
+            await expectFailure(
+                send(withdrawIx(Tag.SecureWithdraw, vault, mallory.publicKey, DEPOSIT), mallory),
+                customError(VaultError.Unauthorized)
+            );
+        });
+
+        it("Rejects an account that was never initialized", async () => {
+            const blank = await createVault(mallory, VAULT_LEN);
+            await expectFailure(
+                send(withdrawIx(Tag.SecureWithdraw, blank, mallory.publicKey, 1), mallory),
+                customError(VaultError.InvalidDiscriminator)
+            );
+        });
+
+        it("Rejects an account of the wrong length, even when initialized by the vulnerable path", async () => {
+            const oversized = await createVault(mallory, VAULT_LEN + 32, Tag.VulnerableInitialize);
+            await expectFailure(
+                send(withdrawIx(Tag.SecureWithdraw, oversized, mallory.publicKey, 1), mallory),
+                "invalid account data"
+            );
+        });
+
+        it("Alice withdraws the deposit", async () => {
+            await send(withdrawIx(Tag.SecureWithdraw, vault, alice.publicKey, DEPOSIT), alice);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Manual Serialization Without Initialization Checks ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  vault.pack(&mut data);                    // no is_initialized check first");
+            console.log("  let vault = Vault::unpack_unchecked(&data)?;  // any layout that is long enough");
+            console.log("  Risk: anyone re-initializes a funded vault as its authority and drains it\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  if !Vault::is_blank(&data) { return Err(AlreadyInitialized) }");
+            console.log("  Vault::unpack: len == LEN, discriminator matches, is_initialized == 1");
+            console.log("  Protection: live accounts are never overwritten; only real vaults are read\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Without a framework, every check Anchor's `init` and `Account<T>` do is yours to write.");
+        });
+    });
+});
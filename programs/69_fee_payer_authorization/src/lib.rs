@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("Bm29VALedpCqMhyuoVaFYLDVWBn5w4vD1Uqad8TeoidZ");
+
+#[program]
+pub mod fee_payer_authorization {
+    use super::*;
+
+    /// Anyone can pay into any vault, e.g. an employer paying wages.
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.depositor.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+        };
+        transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts), amount)
+    }
+
+    // VULNERABLE: Fee Payer Treated as the Acting User
+    // 1. The program assumes whoever pays for the transaction is the user,
+    //    and takes a single `payer: Signer` for both rent and identity
+    // 2. Under a relayer or sponsored-transaction service, the payer is the
+    //    relayer, not the user who signed the request
+    // 3. A vault opened through a relayer is owned by the relayer, who can
+    //    withdraw everything later paid into it
+    // 4. A vault opened directly can never be withdrawn through a relayer:
+    //    the payer no longer matches the owner
+    pub fn vulnerable_open_vault(ctx: Context<VulnerableOpenVault>) -> Result<()> {
+        ctx.accounts.vault.owner = ctx.accounts.payer.key();
+        Ok(())
+    }
+
+    pub fn vulnerable_withdraw(ctx: Context<VulnerableWithdraw>, amount: u64) -> Result<()> {
+        pay_from_vault(&ctx.accounts.vault, &ctx.accounts.payer.to_account_info(), amount)
+    }
+
+    // SECURE: Explicit User Signer, Separate from the Payer
+    // 1. `user: Signer` is the identity; `payer: Signer` only funds rent
+    // 2. Ownership and withdrawal checks compare against `user`, never the
+    //    payer, and payouts go to `user`
+    // 3. Direct and relayed transactions behave the same, and the relayer
+    //    gains no authority over what it sponsors
+    pub fn secure_open_vault(ctx: Context<SecureOpenVault>) -> Result<()> {
+        ctx.accounts.vault.owner = ctx.accounts.user.key();
+        Ok(())
+    }
+
+    pub fn secure_withdraw(ctx: Context<SecureWithdraw>, amount: u64) -> Result<()> {
+        pay_from_vault(&ctx.accounts.vault, &ctx.accounts.user.to_account_info(), amount)
+    }
+}
+
+/// Moves `amount` lamports from the vault to `to`, keeping the vault
+/// rent-exempt.
+fn pay_from_vault(vault: &Account<Vault>, to: &AccountInfo, amount: u64) -> Result<()> {
+    let from = vault.to_account_info();
+    let floor = Rent::get()?.minimum_balance(from.data_len());
+    let remaining = from.lamports().checked_sub(amount).ok_or(VaultError::InsufficientFunds)?;
+    require!(remaining >= floor, VaultError::InsufficientFunds);
+
+    **from.try_borrow_mut_lamports()? = remaining;
+    **to.try_borrow_mut_lamports()? = to.lamports().checked_add(amount).ok_or(VaultError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableOpenVault<'info> {
+    #[account(init, payer = payer, space = 8 + Vault::INIT_SPACE)]
+    pub vault: Account<'info, Vault>,
+    /// Pays rent and is taken to be the user.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableWithdraw<'info> {
+    #[account(mut, constraint = vault.owner == payer.key() @ VaultError::Unauthorized)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SecureOpenVault<'info> {
+    #[account(init, payer = payer, space = 8 + Vault::INIT_SPACE)]
+    pub vault: Account<'info, Vault>,
+    pub user: Signer<'info>,
+    /// Pays rent only; may be a relayer.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SecureWithdraw<'info> {
+    #[account(mut, constraint = vault.owner == user.key() @ VaultError::Unauthorized)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub owner: Pubkey,
+}
+
+#[error_code]
+pub enum VaultError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Arithmetic error")]
+    ArithmeticError,
+}
@@ -0,0 +1,168 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction, sendAndConfirmTransaction } from "@solana/web3.js";
+import { expect } from "chai";
+
+describe("69: Fee Payer Authorization", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.FeePayerAuthorization as Program<any>;
+
+    // Alice holds no SOL and sends every transaction through a relayer.
+    let alice: Keypair;
+    // Bob pays Alice's wages.
+    let bob: Keypair;
+    // Mallory runs the relayer: pays fees and rent for users' transactions.
+    let mallory: Keypair;
+
+    const WAGES = 3 * LAMPORTS_PER_SOL;
+
+    /** Sends `tx` with `feePayer` paying the fee, as a relayer would. */
+    async function relay(tx: Transaction, feePayer: Keypair, signers: Keypair[]) {
+        tx.feePayer = feePayer.publicKey;
+        return sendAndConfirmTransaction(provider.connection, tx, [feePayer, ...signers]);
+    }
+
+    async function payWages(vault: PublicKey) {
+        await program.methods
+            .deposit(new anchor.BN(WAGES))
+            .accounts({ vault, depositor: bob.publicKey, systemProgram: SystemProgram.programId })
+            .signers([bob])
+            .rpc();
+    }
+
+    async function expectFailure(promise: Promise<unknown>, fragment: string) {
+        try {
+            await promise;
+            expect.fail("Should have thrown");
+        } catch (error: any) {
+            const output = [error.message, ...(error.logs ?? [])].join("\n");
+            expect(output).to.include(fragment);
+        }
+    }
+
+    before(async () => {
+        alice = Keypair.generate();
+        bob = Keypair.generate();
+        mallory = Keypair.generate();
+
+        for (const kp of [bob, mallory]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Exploit: the relayer pays, so the relayer is the user", () => {
+        const vault = Keypair.generate();
+
+        it("Alice opens a vault through Mallory's relayer; Mallory becomes the owner", async () => {
+            // Alice has no SOL for rent, so the relayer must be `payer`.
+            const tx = await program.methods
+                .vulnerableOpenVault()
+                .accounts({ vault: vault.publicKey, payer: mallory.publicKey, systemProgram: SystemProgram.programId })
+                .transaction();
+            await relay(tx, mallory, [vault]);
+
+            const state = await program.account.vault.fetch(vault.publicKey);
+            expect(state.owner.equals(mallory.publicKey)).to.equal(true);
+        });
+
+        it("Bob pays Alice's wages into the vault", async () => {
+            await payWages(vault.publicKey);
+        });
+
+        it("Alice cannot withdraw", async () => {
+            const tx = await program.methods
+                .vulnerableWithdraw(new anchor.BN(WAGES))
+                .accounts({ vault: vault.publicKey, payer: alice.publicKey })
+                .transaction();
+            await expectFailure(relay(tx, mallory, [alice]), "Unauthorized");
+        });
+
+        it("Mallory withdraws Alice's wages", async () => {
+            const before = await provider.connection.getBalance(mallory.publicKey);
+            await program.methods
+                .vulnerableWithdraw(new anchor.BN(WAGES))
+                .accounts({ vault: vault.publicKey, payer: mallory.publicKey })
+                .signers([mallory])
+                .rpc();
+            const after = await provider.connection.getBalance(mallory.publicKey);
+
+            console.log(`  Mallory took ${(after - before) / LAMPORTS_PER_SOL} SOL of Alice's wages`);
+            expect(after - before).to.equal(WAGES);
+        });
+    });
+
+    describe("Secure: the user signs as the user, the relayer only pays", () => {
+        const vault = Keypair.generate();
+
+        it("Alice opens a vault through Mallory's relayer; Alice is the owner", async () => {
+            const tx = await program.methods
+                .secureOpenVault()
+                .accounts({
+                    vault: vault.publicKey,
+                    user: alice.publicKey,
+                    payer: mallory.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .transaction();
+            await relay(tx, mallory, [alice, vault]);
+
+            const state = await program.account.vault.fetch(vault.publicKey);
+            expect(state.owner.equals(alice.publicKey)).to.equal(true);
+
+            await payWages(vault.publicKey);
+        });
+
+        it("Rejects Mallory withdrawing from Alice's vault", async () => {
+            await expectFailure(
+                program.methods
+                    .secureWithdraw(new anchor.BN(WAGES))
+                    .accounts({ vault: vault.publicKey, user: mallory.publicKey })
+                    .signers([mallory])
+                    .rpc(),
+                "Unauthorized"
+            );
+        });
+
+        it("Alice withdraws through the relayer; Mallory pays only the fee", async () => {
+            const tx = await program.methods
+                .secureWithdraw(new anchor.BN(WAGES))
+                .accounts({ vault: vault.publicKey, user: alice.publicKey })
+                .transaction();
+            await relay(tx, mallory, [alice]);
+
+            expect(await provider.connection.getBalance(alice.publicKey)).to.equal(WAGES);
+        });
+
+        it("The same instruction works without a relayer", async () => {
+            await payWages(vault.publicKey);
+
+            const tx = await program.methods
+                .secureWithdraw(new anchor.BN(WAGES))
+                .accounts({ vault: vault.publicKey, user: alice.publicKey })
+                .transaction();
+            await relay(tx, alice, []);
+
+            expect(await provider.connection.getBalance(alice.publicKey)).to.be.greaterThan(WAGES);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Fee Payer Authorization ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  vault.owner = ctx.accounts.payer.key();  // payer assumed to be the user");
+            console.log("  Risk: under a relayer the payer is a third party, who then owns the user's accounts\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  pub user: Signer<'info>,  pub payer: Signer<'info>,  // owner = user");
+            console.log("  Protection: identity comes from the user's signature; paying grants nothing\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Who pays is not who acts. Authorize the signer that represents the user.");
+        });
+    });
+});
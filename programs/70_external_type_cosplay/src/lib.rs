@@ -0,0 +1,150 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("5niKiBRQDympKrrVeZCtfVaQ8uj2Jk2YPb65QqcS9CDS");
+
+#[program]
+pub mod external_type_cosplay {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>, treasury: Pubkey, fee_bps: u16) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.treasury = treasury;
+        config.fee_bps = fee_bps;
+        config.bump = ctx.bumps.config;
+        ctx.accounts.fee_vault.bump = ctx.bumps.fee_vault;
+        Ok(())
+    }
+
+    /// Stands in for trading: fees accrue in the fee vault.
+    pub fn pay_fee(ctx: Context<PayFee>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: ctx.accounts.fee_vault.to_account_info(),
+        };
+        transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts), amount)
+    }
+
+    // VULNERABLE: Discriminator Taken as Proof of Origin
+    // 1. The config is an `UncheckedAccount` decoded with
+    //    `Config::try_deserialize`, which checks only the discriminator
+    // 2. The discriminator is `sha256("account:Config")[..8]`: any Anchor
+    //    program with a `Config` struct produces the same 8 bytes
+    // 3. A permissionless launchpad lets anyone create a `Config` naming
+    //    themselves admin and treasury; it decodes here field for field
+    // 4. The attacker signs as that admin and sweeps this program's fees
+    pub fn vulnerable_sweep_fees(ctx: Context<SweepFees>) -> Result<()> {
+        let config = ctx.accounts.load_config()?;
+        ctx.accounts.sweep(&config)
+    }
+
+    // SECURE: Owner Checked Before the Discriminator
+    // 1. The config account must be owned by this program; a same-named type
+    //    from any other program is rejected before decoding
+    // 2. `Account<'info, Config>` performs the same check implicitly, which
+    //    is why typed accounts are the default
+    pub fn secure_sweep_fees(ctx: Context<SweepFees>) -> Result<()> {
+        require_keys_eq!(*ctx.accounts.config.owner, crate::ID, ConfigError::ForeignConfig);
+        let config = ctx.accounts.load_config()?;
+        ctx.accounts.sweep(&config)
+    }
+}
+
+impl<'info> SweepFees<'info> {
+    /// Decodes the config, checking the discriminator only.
+    fn load_config(&self) -> Result<Config> {
+        let data = self.config.try_borrow_data()?;
+        Config::try_deserialize(&mut &data[..])
+    }
+
+    /// Pays everything above rent in the fee vault to the config's treasury,
+    /// if `admin` is the config's admin.
+    fn sweep(&self, config: &Config) -> Result<()> {
+        require_keys_eq!(self.admin.key(), config.admin, ConfigError::Unauthorized);
+        require_keys_eq!(self.treasury.key(), config.treasury, ConfigError::WrongTreasury);
+
+        let vault = self.fee_vault.to_account_info();
+        let floor = Rent::get()?.minimum_balance(vault.data_len());
+        let amount = vault.lamports().saturating_sub(floor);
+
+        **vault.try_borrow_mut_lamports()? = floor;
+        **self.treasury.try_borrow_mut_lamports()? = self
+            .treasury
+            .lamports()
+            .checked_add(amount)
+            .ok_or(ConfigError::ArithmeticError)?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = admin, space = 8 + Config::INIT_SPACE, seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+    #[account(init, payer = admin, space = 8 + FeeVault::INIT_SPACE, seeds = [b"fees"], bump)]
+    pub fee_vault: Account<'info, FeeVault>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PayFee<'info> {
+    #[account(mut, seeds = [b"fees"], bump = fee_vault.bump)]
+    pub fee_vault: Account<'info, FeeVault>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SweepFees<'info> {
+    /// CHECK: decoded in the handler; only the secure path checks the owner.
+    pub config: UncheckedAccount<'info>,
+    #[account(mut, seeds = [b"fees"], bump = fee_vault.bump)]
+    pub fee_vault: Account<'info, FeeVault>,
+    pub admin: Signer<'info>,
+    /// CHECK: must equal the config's treasury.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    pub fee_bps: u16,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct FeeVault {
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum ConfigError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Config is not owned by this program")]
+    ForeignConfig,
+    #[msg("Treasury does not match the config")]
+    WrongTreasury,
+    #[msg("Arithmetic error")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+
+    #[test]
+    fn same_struct_name_same_discriminator_across_programs() {
+        assert_eq!(Config::DISCRIMINATOR, mock_launchpad::Config::DISCRIMINATOR);
+        assert_ne!(crate::ID, mock_launchpad::ID);
+    }
+}
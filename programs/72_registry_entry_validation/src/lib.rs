@@ -0,0 +1,182 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::get_return_data;
+use anchor_lang::system_program::{transfer, Transfer};
+use mock_registry::cpi::accounts::Lookup;
+use mock_registry::program::MockRegistry;
+use mock_registry::Entry;
+
+declare_id!("DByoeDGdZJq3AMvzZPHE44rutrpZNichPTLjiQPyMdiJ");
+
+#[program]
+pub mod registry_entry_validation {
+    use super::*;
+
+    /// Settlements go to whichever adapter the registry lists under
+    /// `adapter_id`, so the curator can rotate it without touching this
+    /// program.
+    pub fn initialize(ctx: Context<Initialize>, adapter_id: u64) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.adapter_id = adapter_id;
+        config.bump = ctx.bumps.config;
+        ctx.accounts.vault.bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+        };
+        transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts), amount)
+    }
+
+    // VULNERABLE: Registry Answer Trusted Without Checking Who Answered
+    // 1. Settlement is permissionless; the keeper passes the registry
+    //    program, the entry and the adapter
+    // 2. `lookup` is CPI'd into `registry_program`, which is never checked,
+    //    with an entry whose derivation and owner are never checked
+    // 3. Anchor's `Return::get` reads return data without checking which
+    //    program set it
+    // 4. An attacker's program answering `lookup` from an entry it forged
+    //    names the attacker as the adapter, and the vault is paid out
+    pub fn vulnerable_settle(ctx: Context<VulnerableSettle>) -> Result<()> {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.registry_program.to_account_info(),
+            Lookup {
+                entry: ctx.accounts.entry.to_account_info(),
+            },
+        );
+        let adapter = mock_registry::cpi::lookup(cpi_ctx, ctx.accounts.config.adapter_id)?.get();
+
+        require_keys_eq!(ctx.accounts.adapter.key(), adapter, RegistryError::WrongAdapter);
+        settle(&ctx.accounts.vault, &ctx.accounts.adapter)
+    }
+
+    // SECURE: Entry Re-derived and Registry Pinned Locally
+    // 1. `entry` must be the PDA `[b"entry", config.adapter_id]` under the
+    //    registry program, and `Account<Entry>` checks the registry owns it
+    // 2. `registry_program` must be the registry's program id
+    // 3. Return data must come from the registry program before it is used
+    pub fn secure_settle(ctx: Context<SecureSettle>) -> Result<()> {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.registry_program.to_account_info(),
+            Lookup {
+                entry: ctx.accounts.entry.to_account_info(),
+            },
+        );
+        mock_registry::cpi::lookup(cpi_ctx, ctx.accounts.config.adapter_id)?;
+        let adapter = adapter_from_return_data()?;
+
+        require_keys_eq!(ctx.accounts.adapter.key(), adapter, RegistryError::WrongAdapter);
+        settle(&ctx.accounts.vault, &ctx.accounts.adapter)
+    }
+}
+
+/// Reads the adapter `lookup` returned, rejecting return data that is
+/// missing, from another program, or the wrong size.
+fn adapter_from_return_data() -> Result<Pubkey> {
+    let (program_id, data) = get_return_data().ok_or(RegistryError::MissingReturnData)?;
+    require_keys_eq!(program_id, mock_registry::ID, RegistryError::UnexpectedReturnProgram);
+    Pubkey::try_from_slice(&data).map_err(|_| error!(RegistryError::MalformedReturnData))
+}
+
+/// Pays everything above rent in the vault to the adapter.
+fn settle<'info>(vault: &Account<'info, Vault>, adapter: &UncheckedAccount<'info>) -> Result<()> {
+    let vault = vault.to_account_info();
+    let floor = Rent::get()?.minimum_balance(vault.data_len());
+    let amount = vault.lamports().saturating_sub(floor);
+
+    **vault.try_borrow_mut_lamports()? = floor;
+    **adapter.try_borrow_mut_lamports()? = adapter
+        .lamports()
+        .checked_add(amount)
+        .ok_or(RegistryError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = admin, space = 8 + Config::INIT_SPACE, seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+    #[account(init, payer = admin, space = 8 + Vault::INIT_SPACE, seeds = [b"vault"], bump)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableSettle<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    /// CHECK: passed through to `lookup`; never checked here.
+    pub entry: UncheckedAccount<'info>,
+    /// CHECK: assumed to be the registry; never checked.
+    pub registry_program: UncheckedAccount<'info>,
+    /// CHECK: must equal the adapter `lookup` returned.
+    #[account(mut)]
+    pub adapter: UncheckedAccount<'info>,
+    pub keeper: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SecureSettle<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    #[account(
+        seeds = [b"entry", config.adapter_id.to_le_bytes().as_ref()],
+        bump = entry.bump,
+        seeds::program = registry_program.key()
+    )]
+    pub entry: Account<'info, Entry>,
+    pub registry_program: Program<'info, MockRegistry>,
+    /// CHECK: must equal the adapter `lookup` returned.
+    #[account(mut)]
+    pub adapter: UncheckedAccount<'info>,
+    pub keeper: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    /// Registry id of the adapter settlements are routed to.
+    pub adapter_id: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum RegistryError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Adapter does not match the registry")]
+    WrongAdapter,
+    #[msg("Registry returned no data")]
+    MissingReturnData,
+    #[msg("Return data was not set by the registry program")]
+    UnexpectedReturnProgram,
+    #[msg("Return data is not an adapter address")]
+    MalformedReturnData,
+    #[msg("Arithmetic error")]
+    ArithmeticError,
+}
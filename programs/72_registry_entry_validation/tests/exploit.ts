@@ -0,0 +1,204 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("72: Registry Entry Validation", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.RegistryEntryValidation as Program<any>;
+    const registry = anchor.workspace.MockRegistry as Program<any>;
+    const rawWriter = anchor.workspace.MockRawWriter as Program<any>;
+
+    let curator: Keypair;
+    let alice: Keypair;
+    let mallory: Keypair;
+    let forgedEntry: PublicKey;
+
+    const ADAPTER_ID = 7;
+    const SETTLEMENT = 2 * LAMPORTS_PER_SOL;
+    const adapter = Keypair.generate().publicKey;
+
+    const configPda = PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId)[0];
+    const vaultPda = PublicKey.findProgramAddressSync([Buffer.from("vault")], program.programId)[0];
+    const registryPda = PublicKey.findProgramAddressSync([Buffer.from("registry")], registry.programId)[0];
+    const entryPda = PublicKey.findProgramAddressSync(
+        [Buffer.from("entry"), new anchor.BN(ADAPTER_ID).toArrayLike(Buffer, "le", 8)],
+        registry.programId
+    )[0];
+
+    function settle(
+        method: "vulnerableSettle" | "secureSettle",
+        accounts: { entry: PublicKey; registryProgram: PublicKey; adapter: PublicKey },
+        keeper: Keypair
+    ) {
+        return program.methods[method]()
+            .accounts({ config: configPda, vault: vaultPda, ...accounts, keeper: keeper.publicKey })
+            .signers([keeper])
+            .rpc();
+    }
+
+    async function deposit() {
+        await program.methods
+            .deposit(new anchor.BN(SETTLEMENT))
+            .accounts({ vault: vaultPda, payer: alice.publicKey, systemProgram: SystemProgram.programId })
+            .signers([alice])
+            .rpc();
+    }
+
+    /**
+     * Writes an `Entry` discriminator followed by Mallory's address into an
+     * account owned by the attacker's program. Asked to `lookup`, that
+     * program answers with everything after the discriminator.
+     */
+    async function forgeEntry(): Promise<PublicKey> {
+        const forged = Keypair.generate();
+        const data = Buffer.concat([
+            anchor.BorshAccountsCoder.accountDiscriminator("Entry"),
+            mallory.publicKey.toBuffer(),
+        ]);
+
+        const tx = new Transaction().add(
+            SystemProgram.createAccount({
+                fromPubkey: mallory.publicKey,
+                newAccountPubkey: forged.publicKey,
+                lamports: await provider.connection.getMinimumBalanceForRentExemption(data.length),
+                space: data.length,
+                programId: rawWriter.programId,
+            }),
+            await rawWriter.methods.write(0, data).accounts({ target: forged.publicKey }).instruction()
+        );
+        await provider.sendAndConfirm(tx, [mallory, forged]);
+        return forged.publicKey;
+    }
+
+    before(async () => {
+        curator = Keypair.generate();
+        alice = Keypair.generate();
+        mallory = Keypair.generate();
+
+        for (const kp of [curator, alice, mallory]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("The curator registers the adapter under id 7", async () => {
+            await registry.methods
+                .initialize()
+                .accounts({ registry: registryPda, curator: curator.publicKey, systemProgram: SystemProgram.programId })
+                .signers([curator])
+                .rpc();
+            await registry.methods
+                .register(new anchor.BN(ADAPTER_ID), adapter)
+                .accounts({
+                    registry: registryPda,
+                    entry: entryPda,
+                    curator: curator.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([curator])
+                .rpc();
+        });
+
+        it("Alice routes settlements to adapter id 7 and deposits", async () => {
+            await program.methods
+                .initialize(new anchor.BN(ADAPTER_ID))
+                .accounts({
+                    config: configPda,
+                    vault: vaultPda,
+                    admin: alice.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([alice])
+                .rpc();
+            await deposit();
+        });
+
+        it("Mallory forges a registry entry with the attacker's program", async () => {
+            forgedEntry = await forgeEntry();
+            const owner = (await provider.connection.getAccountInfo(forgedEntry))!.owner;
+            expect(owner.equals(rawWriter.programId)).to.equal(true);
+        });
+    });
+
+    describe("Exploit: the keeper chooses who answers the lookup", () => {
+        it("Mallory settles through the attacker's program and is paid", async () => {
+            const before = await provider.connection.getBalance(mallory.publicKey);
+            await settle(
+                "vulnerableSettle",
+                { entry: forgedEntry, registryProgram: rawWriter.programId, adapter: mallory.publicKey },
+                mallory
+            );
+            const after = await provider.connection.getBalance(mallory.publicKey);
+
+            console.log(`  Mallory received ${(after - before) / LAMPORTS_PER_SOL} SOL meant for the adapter`);
+            expect(after - before).to.equal(SETTLEMENT);
+        });
+    });
+
+    describe("Secure: entry re-derived, registry program pinned", () => {
+        it("Rejects the forged entry", async () => {
+            await deposit();
+            await expectError(
+                settle(
+                    "secureSettle",
+                    { entry: forgedEntry, registryProgram: registry.programId, adapter: mallory.publicKey },
+                    mallory
+                ),
+                "AccountOwnedByWrongProgram"
+            );
+        });
+
+        it("Rejects the attacker's program as the registry", async () => {
+            await expectError(
+                settle(
+                    "secureSettle",
+                    { entry: entryPda, registryProgram: rawWriter.programId, adapter: mallory.publicKey },
+                    mallory
+                ),
+                "InvalidProgramId"
+            );
+        });
+
+        it("Rejects Mallory as the adapter of the genuine entry", async () => {
+            await expectError(
+                settle(
+                    "secureSettle",
+                    { entry: entryPda, registryProgram: registry.programId, adapter: mallory.publicKey },
+                    mallory
+                ),
+                "WrongAdapter"
+            );
+        });
+
+        it("Any keeper settles to the registered adapter", async () => {
+            await settle(
+                "secureSettle",
+                { entry: entryPda, registryProgram: registry.programId, adapter },
+                mallory
+            );
+            expect(await provider.connection.getBalance(adapter)).to.equal(SETTLEMENT);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Registry Entry Validation ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  registry::cpi::lookup(CpiContext::new(registry_program, ..), id)?.get()  // unchecked program, entry");
+            console.log("  Risk: an attacker's program answers from a forged entry and names itself the adapter\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  seeds = [b\"entry\", id], seeds::program = registry_program.key(); Program<'info, MockRegistry>");
+            console.log("  Protection: only the registry's own entry for this id, read by the registry itself\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  A CPI answer is only as trustworthy as the program id and accounts you sent it to.");
+        });
+    });
+});
@@ -0,0 +1,357 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::system_program::{transfer, Transfer};
+use security_common::time::{ClockExt, UnixTs};
+
+declare_id!("3eoKmRmsEuxtPjRvaemoeEfgBtPfZJp7CFHmzjaBvyh7");
+
+#[program]
+pub mod sealed_bid_auction {
+    use super::*;
+
+    pub fn create_sale(ctx: Context<CreateSale>, sale_id: u64, price: u64) -> Result<()> {
+        let sale = &mut ctx.accounts.sale;
+        sale.seller = ctx.accounts.seller.key();
+        sale.sale_id = sale_id;
+        sale.price = price;
+        sale.buyer = None;
+        sale.bump = ctx.bumps.sale;
+        Ok(())
+    }
+
+    // VULNERABLE: First Transaction Wins at the Stated Price
+    // 1. The item goes to whichever `buy` lands first, at a public price
+    // 2. A pending `buy` is visible before it lands; anyone can copy it and
+    //    pay a higher priority fee to be ordered first
+    // 3. The buyer who found the deal loses it to whoever watches the
+    //    mempool and bids for ordering
+    pub fn naive_buy(ctx: Context<NaiveBuy>) -> Result<()> {
+        let sale = &mut ctx.accounts.sale;
+        require!(sale.buyer.is_none(), AuctionError::AlreadySold);
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.buyer.to_account_info(),
+            to: ctx.accounts.seller.to_account_info(),
+        };
+        transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts), sale.price)?;
+
+        sale.buyer = Some(ctx.accounts.buyer.key());
+        msg!("Sold to {} for {}", ctx.accounts.buyer.key(), sale.price);
+        Ok(())
+    }
+
+    /// Opens a sealed-bid auction: commits are accepted for `commit_secs`,
+    /// then reveals for `reveal_secs`.
+    pub fn create_auction(
+        ctx: Context<CreateAuction>,
+        auction_id: u64,
+        reserve: u64,
+        bond: u64,
+        commit_secs: i64,
+        reveal_secs: i64,
+    ) -> Result<()> {
+        require!(commit_secs > 0 && reveal_secs > 0, AuctionError::InvalidWindow);
+        let now = Clock::get()?.current_unix_ts();
+        let commit_end = now.checked_add_secs(commit_secs).ok_or(AuctionError::ArithmeticError)?;
+        let reveal_end = commit_end.checked_add_secs(reveal_secs).ok_or(AuctionError::ArithmeticError)?;
+
+        let auction = &mut ctx.accounts.auction;
+        auction.seller = ctx.accounts.seller.key();
+        auction.auction_id = auction_id;
+        auction.reserve = reserve;
+        auction.bond = bond;
+        auction.commit_end = commit_end.0;
+        auction.reveal_end = reveal_end.0;
+        auction.highest_bid = 0;
+        auction.winner = None;
+        auction.bump = ctx.bumps.auction;
+        Ok(())
+    }
+
+    // SECURE: Commit-Reveal Sealed Bid
+    // 1. During the commit window only `sha256(amount, salt, bidder)` is
+    //    published; there is nothing in the mempool worth copying
+    // 2. The bidder escrows a deposit of at least the bond, which hides the
+    //    bid among every amount up to the deposit
+    // 3. The hash binds the bidder's key, so a copied commitment cannot be
+    //    revealed by anyone else
+    pub fn commit_bid(ctx: Context<CommitBid>, commitment: [u8; 32], deposit: u64) -> Result<()> {
+        let now = Clock::get()?.current_unix_ts();
+        let auction = &ctx.accounts.auction;
+        require!(now < UnixTs(auction.commit_end), AuctionError::CommitWindowClosed);
+        require!(deposit >= auction.bond, AuctionError::DepositBelowBond);
+
+        let bid = &mut ctx.accounts.bid;
+        bid.auction = auction.key();
+        bid.bidder = ctx.accounts.bidder.key();
+        bid.commitment = commitment;
+        bid.deposit = deposit;
+        bid.amount = 0;
+        bid.revealed = false;
+        bid.bump = ctx.bumps.bid;
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.bidder.to_account_info(),
+            to: ctx.accounts.bid.to_account_info(),
+        };
+        transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts), deposit)
+    }
+
+    /// Opens a commitment during the reveal window. Ordering no longer
+    /// matters: every bid was fixed before any was visible.
+    pub fn reveal_bid(ctx: Context<RevealBid>, amount: u64, salt: [u8; 32]) -> Result<()> {
+        let now = Clock::get()?.current_unix_ts();
+        let auction = &mut ctx.accounts.auction;
+        require!(now >= UnixTs(auction.commit_end), AuctionError::RevealWindowNotOpen);
+        require!(now < UnixTs(auction.reveal_end), AuctionError::RevealWindowClosed);
+
+        let bid = &mut ctx.accounts.bid;
+        require!(!bid.revealed, AuctionError::AlreadyRevealed);
+        require!(
+            commitment(amount, &salt, &bid.bidder) == bid.commitment,
+            AuctionError::CommitmentMismatch
+        );
+        require!(amount <= bid.deposit, AuctionError::BidExceedsDeposit);
+
+        bid.amount = amount;
+        bid.revealed = true;
+        if amount >= auction.reserve && amount > auction.highest_bid {
+            auction.highest_bid = amount;
+            auction.winner = Some(bid.bidder);
+        }
+        Ok(())
+    }
+
+    // SECURE: Settlement With Slashing
+    // 1. Runs only after the reveal window; permissionless so no bidder can
+    //    hold an auction open
+    // 2. The winner's bid goes to the seller and the rest of the deposit
+    //    back to the winner; revealed losers get their deposit back
+    // 3. A bidder who never revealed forfeits the bond to the seller, so
+    //    committing many bids and revealing only the useful ones costs money
+    pub fn settle_bid(ctx: Context<SettleBid>) -> Result<()> {
+        let now = Clock::get()?.current_unix_ts();
+        let auction = &ctx.accounts.auction;
+        require!(now >= UnixTs(auction.reveal_end), AuctionError::AuctionNotOver);
+
+        let bid = &ctx.accounts.bid;
+        let owed_to_seller = if !bid.revealed {
+            msg!("Slashed {} from {}", auction.bond, bid.bidder);
+            auction.bond
+        } else if auction.winner == Some(bid.bidder) {
+            msg!("Winner {} pays {}", bid.bidder, bid.amount);
+            bid.amount
+        } else {
+            0
+        };
+        // `close = bidder` then returns the rest of the deposit and the rent.
+        move_lamports(&bid.to_account_info(), &ctx.accounts.seller, owed_to_seller)
+    }
+}
+
+/// `sha256(amount_le || salt || bidder)`.
+pub fn commitment(amount: u64, salt: &[u8; 32], bidder: &Pubkey) -> [u8; 32] {
+    hashv(&[&amount.to_le_bytes(), salt, bidder.as_ref()]).to_bytes()
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(AuctionError::ArithmeticError)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(AuctionError::ArithmeticError)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(sale_id: u64)]
+pub struct CreateSale<'info> {
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + Sale::INIT_SPACE,
+        seeds = [b"sale", sale_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub sale: Account<'info, Sale>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct NaiveBuy<'info> {
+    #[account(
+        mut,
+        seeds = [b"sale", sale.sale_id.to_le_bytes().as_ref()],
+        bump = sale.bump,
+        has_one = seller
+    )]
+    pub sale: Account<'info, Sale>,
+    /// CHECK: must equal the sale's seller; receives the price.
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct CreateAuction<'info> {
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + Auction::INIT_SPACE,
+        seeds = [b"auction", auction_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CommitBid<'info> {
+    #[account(seeds = [b"auction", auction.auction_id.to_le_bytes().as_ref()], bump = auction.bump)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + Bid::INIT_SPACE,
+        seeds = [b"bid", auction.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub bid: Account<'info, Bid>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevealBid<'info> {
+    #[account(mut, seeds = [b"auction", auction.auction_id.to_le_bytes().as_ref()], bump = auction.bump)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        mut,
+        seeds = [b"bid", auction.key().as_ref(), bidder.key().as_ref()],
+        bump = bid.bump,
+        has_one = bidder
+    )]
+    pub bid: Account<'info, Bid>,
+    pub bidder: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SettleBid<'info> {
+    #[account(
+        seeds = [b"auction", auction.auction_id.to_le_bytes().as_ref()],
+        bump = auction.bump,
+        has_one = seller
+    )]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        mut,
+        close = bidder,
+        seeds = [b"bid", auction.key().as_ref(), bidder.key().as_ref()],
+        bump = bid.bump,
+        has_one = bidder
+    )]
+    pub bid: Account<'info, Bid>,
+    /// CHECK: must equal the bid's bidder; receives the refund.
+    #[account(mut)]
+    pub bidder: UncheckedAccount<'info>,
+    /// CHECK: must equal the auction's seller; receives payment and bonds.
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+    pub cranker: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Sale {
+    pub seller: Pubkey,
+    pub sale_id: u64,
+    pub price: u64,
+    pub buyer: Option<Pubkey>,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Auction {
+    pub seller: Pubkey,
+    pub auction_id: u64,
+    /// Bids below this never win.
+    pub reserve: u64,
+    /// Minimum deposit, forfeited by bidders who do not reveal.
+    pub bond: u64,
+    /// Unix timestamp commits close and reveals open.
+    pub commit_end: i64,
+    /// Unix timestamp reveals close and settlement opens.
+    pub reveal_end: i64,
+    pub highest_bid: u64,
+    pub winner: Option<Pubkey>,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Bid {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    /// `sha256(amount_le || salt || bidder)`.
+    pub commitment: [u8; 32],
+    /// Lamports escrowed in this account on top of rent.
+    pub deposit: u64,
+    /// Set on reveal.
+    pub amount: u64,
+    pub revealed: bool,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum AuctionError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Item already sold")]
+    AlreadySold,
+    #[msg("Commit and reveal windows must be positive")]
+    InvalidWindow,
+    #[msg("Commit window has closed")]
+    CommitWindowClosed,
+    #[msg("Deposit is below the auction bond")]
+    DepositBelowBond,
+    #[msg("Reveal window has not opened")]
+    RevealWindowNotOpen,
+    #[msg("Reveal window has closed")]
+    RevealWindowClosed,
+    #[msg("Bid already revealed")]
+    AlreadyRevealed,
+    #[msg("Revealed bid does not match the commitment")]
+    CommitmentMismatch,
+    #[msg("Revealed bid exceeds the deposit")]
+    BidExceedsDeposit,
+    #[msg("Auction has not ended")]
+    AuctionNotOver,
+    #[msg("Arithmetic error")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commitment_binds_bidder() {
+        let salt = [7u8; 32];
+        let alice = Pubkey::new_unique();
+        let mallory = Pubkey::new_unique();
+        assert_ne!(commitment(100, &salt, &alice), commitment(100, &salt, &mallory));
+        assert_ne!(commitment(100, &salt, &alice), commitment(101, &salt, &alice));
+    }
+}
@@ -0,0 +1,223 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { expect } from "chai";
+import { createHash, randomBytes } from "crypto";
+import { expectError, raceTransactions, sleep } from "../../../tests/utils";
+
+describe("73: Sealed-Bid Auction", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.SealedBidAuction as Program<any>;
+
+    let seller: Keypair;
+    let alice: Keypair;
+    let bob: Keypair;
+    let mallory: Keypair;
+
+    const SALE_ID = 1;
+    const AUCTION_ID = 1;
+    const PRICE = 1 * LAMPORTS_PER_SOL;
+    const RESERVE = 1 * LAMPORTS_PER_SOL;
+    const BOND = LAMPORTS_PER_SOL / 2;
+    const WINDOW_SECS = 8;
+    const SEARCHER_FEE = 100_000;
+
+    const ALICE_BID = 3 * LAMPORTS_PER_SOL;
+    const BOB_BID = 2 * LAMPORTS_PER_SOL;
+    const DEPOSIT = 4 * LAMPORTS_PER_SOL;
+    const aliceSalt = randomBytes(32);
+    const bobSalt = randomBytes(32);
+
+    const idBytes = (id: number) => new anchor.BN(id).toArrayLike(Buffer, "le", 8);
+    const salePda = PublicKey.findProgramAddressSync([Buffer.from("sale"), idBytes(SALE_ID)], program.programId)[0];
+    const auctionPda = PublicKey.findProgramAddressSync([Buffer.from("auction"), idBytes(AUCTION_ID)], program.programId)[0];
+
+    function bidPda(bidder: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("bid"), auctionPda.toBuffer(), bidder.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    /** Matches the program's `commitment`: sha256(amount_le || salt || bidder). */
+    function commitment(amount: number, salt: Buffer, bidder: PublicKey): number[] {
+        return [...createHash("sha256").update(idBytes(amount)).update(salt).update(bidder.toBuffer()).digest()];
+    }
+
+    function buyIx(buyer: Keypair) {
+        return program.methods
+            .naiveBuy()
+            .accounts({ sale: salePda, seller: seller.publicKey, buyer: buyer.publicKey, systemProgram: SystemProgram.programId })
+            .instruction();
+    }
+
+    function commitIx(bidder: Keypair, hash: number[]) {
+        return program.methods
+            .commitBid(hash, new anchor.BN(DEPOSIT))
+            .accounts({
+                auction: auctionPda,
+                bid: bidPda(bidder.publicKey),
+                bidder: bidder.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .instruction();
+    }
+
+    function reveal(bidder: Keypair, amount: number, salt: Buffer) {
+        return program.methods
+            .revealBid(new anchor.BN(amount), [...salt])
+            .accounts({ auction: auctionPda, bid: bidPda(bidder.publicKey), bidder: bidder.publicKey })
+            .signers([bidder])
+            .rpc();
+    }
+
+    function settle(bidder: PublicKey) {
+        return program.methods
+            .settleBid()
+            .accounts({
+                auction: auctionPda,
+                bid: bidPda(bidder),
+                bidder,
+                seller: seller.publicKey,
+                cranker: provider.wallet.publicKey,
+            })
+            .rpc();
+    }
+
+    async function sleepPast(unixTs: number) {
+        await sleep(Math.max(0, unixTs - Date.now() / 1000) + 2);
+    }
+
+    before(async () => {
+        seller = Keypair.generate();
+        alice = Keypair.generate();
+        bob = Keypair.generate();
+        mallory = Keypair.generate();
+
+        for (const kp of [seller, alice, bob, mallory]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+    });
+
+    describe("Setup", () => {
+        it("The seller lists an item first-come at 1 SOL", async () => {
+            await program.methods
+                .createSale(new anchor.BN(SALE_ID), new anchor.BN(PRICE))
+                .accounts({ sale: salePda, seller: seller.publicKey, systemProgram: SystemProgram.programId })
+                .signers([seller])
+                .rpc();
+        });
+    });
+
+    describe("Exploit: first transaction wins at the stated price", () => {
+        it("Mallory copies Alice's pending buy with a higher priority fee and wins", async () => {
+            const results = await raceTransactions(provider.connection, [
+                { label: "alice", tx: new Transaction().add(await buyIx(alice)), signers: [alice] },
+                {
+                    label: "mallory",
+                    tx: new Transaction().add(await buyIx(mallory)),
+                    signers: [mallory],
+                    priorityFee: SEARCHER_FEE,
+                },
+            ]);
+            results.forEach((r) => console.log(`  ${r.label}: ${r.landed ? "landed" : "failed"}`));
+
+            const sale = await program.account.sale.fetch(salePda);
+            expect(sale.buyer.toString()).to.equal(mallory.publicKey.toString());
+            expect(results.find((r) => r.label === "alice")!.landed).to.equal(false);
+        });
+    });
+
+    describe("Secure: commit-reveal sealed bid with bonds", () => {
+        it("The seller opens a sealed-bid auction", async () => {
+            await program.methods
+                .createAuction(
+                    new anchor.BN(AUCTION_ID),
+                    new anchor.BN(RESERVE),
+                    new anchor.BN(BOND),
+                    new anchor.BN(WINDOW_SECS),
+                    new anchor.BN(WINDOW_SECS)
+                )
+                .accounts({ auction: auctionPda, seller: seller.publicKey, systemProgram: SystemProgram.programId })
+                .signers([seller])
+                .rpc();
+        });
+
+        it("Every commit lands whatever its fee; Mallory can only copy Alice's hash", async () => {
+            const aliceHash = commitment(ALICE_BID, aliceSalt, alice.publicKey);
+            const results = await raceTransactions(provider.connection, [
+                { label: "alice", tx: new Transaction().add(await commitIx(alice, aliceHash)), signers: [alice] },
+                {
+                    label: "bob",
+                    tx: new Transaction().add(await commitIx(bob, commitment(BOB_BID, bobSalt, bob.publicKey))),
+                    signers: [bob],
+                },
+                {
+                    label: "mallory",
+                    tx: new Transaction().add(await commitIx(mallory, aliceHash)),
+                    signers: [mallory],
+                    priorityFee: SEARCHER_FEE,
+                },
+            ]);
+            expect(results.every((r) => r.landed)).to.equal(true);
+        });
+
+        it("Rejects a reveal before the commit window closes", async () => {
+            await expectError(reveal(alice, ALICE_BID, aliceSalt), "RevealWindowNotOpen");
+        });
+
+        it("Alice and Bob reveal; Mallory cannot open the copied commitment", async () => {
+            const auction = await program.account.auction.fetch(auctionPda);
+            await sleepPast(auction.commitEnd.toNumber());
+
+            await reveal(alice, ALICE_BID, aliceSalt);
+            await reveal(bob, BOB_BID, bobSalt);
+            await expectError(reveal(mallory, ALICE_BID, aliceSalt), "CommitmentMismatch");
+
+            const revealed = await program.account.auction.fetch(auctionPda);
+            expect(revealed.winner.toString()).to.equal(alice.publicKey.toString());
+            expect(revealed.highestBid.toNumber()).to.equal(ALICE_BID);
+        });
+
+        it("Rejects settlement before the reveal window closes", async () => {
+            await expectError(settle(alice.publicKey), "AuctionNotOver");
+        });
+
+        it("Settles: Alice pays the winning bid, Bob is refunded, Mallory's bond is slashed", async () => {
+            const auction = await program.account.auction.fetch(auctionPda);
+            await sleepPast(auction.revealEnd.toNumber());
+
+            const sellerBefore = await provider.connection.getBalance(seller.publicKey);
+            const bobBefore = await provider.connection.getBalance(bob.publicKey);
+            for (const bidder of [alice, bob, mallory]) {
+                await settle(bidder.publicKey);
+            }
+            const sellerAfter = await provider.connection.getBalance(seller.publicKey);
+            const bobAfter = await provider.connection.getBalance(bob.publicKey);
+
+            console.log(`  Seller received ${(sellerAfter - sellerBefore) / LAMPORTS_PER_SOL} SOL (winning bid + slashed bond)`);
+            expect(sellerAfter - sellerBefore).to.equal(ALICE_BID + BOND);
+            expect(bobAfter - bobBefore).to.be.greaterThan(DEPOSIT);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Sealed-Bid Auction ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  require!(sale.buyer.is_none()); sale.buyer = Some(buyer);  // first to land wins");
+            console.log("  Risk: a searcher copies the pending buy and pays for earlier ordering\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  commit sha256(amount, salt, bidder) + bond; reveal later; slash non-revealers");
+            console.log("  Protection: nothing worth copying is public until every bid is fixed\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  If ordering decides the winner, the winner is whoever pays the leader most.");
+        });
+    });
+});
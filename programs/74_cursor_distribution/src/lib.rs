@@ -0,0 +1,209 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::compute_units::sol_remaining_compute_units;
+use anchor_lang::system_program::{self, Transfer};
+
+declare_id!("Gfhx62bWZLX5wPXjtVZbehuWiBdnNnikiNQVj38MLSA");
+
+/// Maximum number of recipients in one distribution.
+pub const MAX_RECIPIENTS: usize = 16;
+
+/// Compute units kept back when a distribution call stops early: enough to
+/// serialize the distribution account and return.
+pub const CU_RESERVE: u64 = 10_000;
+
+#[program]
+pub mod cursor_distribution {
+    use super::*;
+
+    /// Creates a distribution paying `amount_each` lamports to each listed
+    /// wallet, and funds its vault with the full total.
+    pub fn create_distribution(
+        ctx: Context<CreateDistribution>,
+        distribution_id: u64,
+        amount_each: u64,
+        recipients: Vec<Pubkey>,
+    ) -> Result<()> {
+        require!(
+            recipients.len() <= MAX_RECIPIENTS,
+            DistributionError::TooManyRecipients
+        );
+        let total = amount_each
+            .checked_mul(recipients.len() as u64)
+            .ok_or(DistributionError::ArithmeticError)?;
+
+        let distribution = &mut ctx.accounts.distribution;
+        distribution.admin = ctx.accounts.admin.key();
+        distribution.distribution_id = distribution_id;
+        distribution.amount_each = amount_each;
+        distribution.recipients = recipients;
+        distribution.cursor = 0;
+        distribution.bump = ctx.bumps.distribution;
+        distribution.vault_bump = ctx.bumps.vault;
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                },
+            ),
+            total,
+        )
+    }
+
+    // VULNERABLE: Cursor Kept in a Local Until the Loop Ends
+    // 1. A full distribution does not fit in one transaction's compute
+    //    budget, and running out of compute reverts every payment
+    // 2. The workaround checks the remaining budget and stops early, so
+    //    the payments made so far land and the next call continues
+    // 3. The cursor lives in a local and is written back only after the
+    //    loop finishes; stopping early returns without it, so the next
+    //    call starts from the old cursor and pays the same recipients again
+    pub fn vulnerable_distribute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Distribute<'info>>,
+    ) -> Result<()> {
+        let distribution = &ctx.accounts.distribution;
+        let mut cursor = distribution.cursor as usize;
+
+        for info in ctx.remaining_accounts.iter() {
+            if cursor == distribution.recipients.len() {
+                break;
+            }
+            if sol_remaining_compute_units() < CU_RESERVE {
+                msg!("Vulnerable distribute: out of budget at {}", cursor);
+                return Ok(());
+            }
+            require_keys_eq!(
+                info.key(),
+                distribution.recipients[cursor],
+                DistributionError::RecipientMismatch
+            );
+
+            pay(ctx.accounts, info.clone())?;
+            cursor += 1;
+        }
+
+        ctx.accounts.distribution.cursor = cursor as u32;
+        msg!("Vulnerable distribute: cursor now {}", cursor);
+        Ok(())
+    }
+
+    // SECURE: Cursor Persisted Before Each Payment
+    // 1. The same budget check stops the loop before compute runs out
+    // 2. The cursor in the account is advanced before each payment, so
+    //    whenever the instruction returns it already covers every
+    //    recipient paid; if compute does run out, both revert together
+    // 3. Each recipient account must match `recipients[cursor]`, so a
+    //    resumed call can only continue where the last one stopped
+    pub fn secure_distribute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Distribute<'info>>,
+    ) -> Result<()> {
+        for info in ctx.remaining_accounts.iter() {
+            let cursor = ctx.accounts.distribution.cursor as usize;
+            if cursor == ctx.accounts.distribution.recipients.len() {
+                break;
+            }
+            if sol_remaining_compute_units() < CU_RESERVE {
+                msg!("Secure distribute: out of budget at {}", cursor);
+                return Ok(());
+            }
+            require_keys_eq!(
+                info.key(),
+                ctx.accounts.distribution.recipients[cursor],
+                DistributionError::RecipientMismatch
+            );
+
+            ctx.accounts.distribution.cursor = (cursor as u32)
+                .checked_add(1)
+                .ok_or(DistributionError::ArithmeticError)?;
+            pay(ctx.accounts, info.clone())?;
+        }
+
+        msg!("Secure distribute: cursor now {}", ctx.accounts.distribution.cursor);
+        Ok(())
+    }
+}
+
+fn pay<'info>(accounts: &Distribute<'info>, recipient: AccountInfo<'info>) -> Result<()> {
+    let distribution_key = accounts.distribution.key();
+    let seeds: &[&[u8]] = &[
+        b"vault",
+        distribution_key.as_ref(),
+        &[accounts.distribution.vault_bump],
+    ];
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            accounts.system_program.to_account_info(),
+            Transfer {
+                from: accounts.vault.to_account_info(),
+                to: recipient,
+            },
+            &[seeds],
+        ),
+        accounts.distribution.amount_each,
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(distribution_id: u64)]
+pub struct CreateDistribution<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Distribution::INIT_SPACE,
+        seeds = [b"distribution", distribution_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub distribution: Account<'info, Distribution>,
+    #[account(mut, seeds = [b"vault", distribution.key().as_ref()], bump)]
+    pub vault: SystemAccount<'info>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Recipients are passed as writable `remaining_accounts`, starting at the
+/// stored cursor.
+#[derive(Accounts)]
+pub struct Distribute<'info> {
+    #[account(
+        mut,
+        seeds = [b"distribution", distribution.distribution_id.to_le_bytes().as_ref()],
+        bump = distribution.bump
+    )]
+    pub distribution: Account<'info, Distribution>,
+    #[account(
+        mut,
+        seeds = [b"vault", distribution.key().as_ref()],
+        bump = distribution.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+    pub keeper: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Distribution {
+    pub admin: Pubkey,
+    pub distribution_id: u64,
+    pub amount_each: u64,
+    #[max_len(MAX_RECIPIENTS)]
+    pub recipients: Vec<Pubkey>,
+    /// Index of the next recipient to pay.
+    pub cursor: u32,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+#[error_code]
+pub enum DistributionError {
+    #[msg("Too many recipients")]
+    TooManyRecipients,
+    #[msg("Recipient does not match the distribution's cursor")]
+    RecipientMismatch,
+    #[msg("Arithmetic error")]
+    ArithmeticError,
+}
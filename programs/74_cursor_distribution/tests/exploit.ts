@@ -0,0 +1,178 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ComputeBudgetProgram, Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("74: Cursor Distribution", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.CursorDistribution as Program<any>;
+
+    let admin: Keypair;
+
+    const VULNERABLE_ID = 1;
+    const SECURE_ID = 2;
+    const RECIPIENT_COUNT = 12;
+    const AMOUNT_EACH = LAMPORTS_PER_SOL / 100;
+    // Too small for all twelve payments, large enough for a few.
+    const CU_LIMIT = 40_000;
+
+    const vulnerableRecipients = Array.from({ length: RECIPIENT_COUNT }, () => Keypair.generate().publicKey);
+    const secureRecipients = Array.from({ length: RECIPIENT_COUNT }, () => Keypair.generate().publicKey);
+
+    function distributionPda(id: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("distribution"), new anchor.BN(id).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        )[0];
+    }
+
+    function vaultPda(id: number): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("vault"), distributionPda(id).toBuffer()], program.programId)[0];
+    }
+
+    /** Sends one distribution call under `CU_LIMIT`, passing recipients from `start`. */
+    async function distribute(
+        method: "vulnerableDistribute" | "secureDistribute",
+        id: number,
+        recipients: PublicKey[],
+        start: number
+    ) {
+        const ix = await program.methods[method]()
+            .accounts({
+                distribution: distributionPda(id),
+                vault: vaultPda(id),
+                keeper: provider.wallet.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .remainingAccounts(recipients.slice(start).map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })))
+            .instruction();
+        const tx = new Transaction().add(ComputeBudgetProgram.setComputeUnitLimit({ units: CU_LIMIT }), ix);
+        return provider.sendAndConfirm(tx);
+    }
+
+    async function cursorOf(id: number): Promise<number> {
+        return (await program.account.distribution.fetch(distributionPda(id))).cursor;
+    }
+
+    async function balances(recipients: PublicKey[]): Promise<number[]> {
+        return Promise.all(recipients.map((r) => provider.connection.getBalance(r)));
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        const sig = await provider.connection.requestAirdrop(admin.publicKey, 10 * LAMPORTS_PER_SOL);
+        await provider.connection.confirmTransaction(sig);
+    });
+
+    describe("Setup", () => {
+        it("The admin funds two distributions of 12 recipients each", async () => {
+            for (const [id, recipients] of [
+                [VULNERABLE_ID, vulnerableRecipients],
+                [SECURE_ID, secureRecipients],
+            ] as [number, PublicKey[]][]) {
+                await program.methods
+                    .createDistribution(new anchor.BN(id), new anchor.BN(AMOUNT_EACH), recipients)
+                    .accounts({
+                        distribution: distributionPda(id),
+                        vault: vaultPda(id),
+                        admin: admin.publicKey,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([admin])
+                    .rpc();
+                expect(await provider.connection.getBalance(vaultPda(id))).to.equal(AMOUNT_EACH * RECIPIENT_COUNT);
+            }
+        });
+    });
+
+    describe("Exploit: the cursor is lost when the budget runs out", () => {
+        let paidFirstCall = 0;
+
+        it("A CU-limited call pays some recipients and stops early without moving the cursor", async () => {
+            await distribute("vulnerableDistribute", VULNERABLE_ID, vulnerableRecipients, 0);
+
+            paidFirstCall = (await balances(vulnerableRecipients)).filter((b) => b > 0).length;
+            console.log(`  Paid ${paidFirstCall} of ${RECIPIENT_COUNT} recipients before stopping`);
+            expect(paidFirstCall).to.be.greaterThan(0);
+            expect(paidFirstCall).to.be.lessThan(RECIPIENT_COUNT);
+            expect(await cursorOf(VULNERABLE_ID)).to.equal(0);
+        });
+
+        it("The next call resumes from the stale cursor and pays the same recipients again", async () => {
+            await distribute("vulnerableDistribute", VULNERABLE_ID, vulnerableRecipients, await cursorOf(VULNERABLE_ID));
+
+            const after = await balances(vulnerableRecipients);
+            console.log(`  First recipient now holds ${after[0] / LAMPORTS_PER_SOL} SOL (owed ${AMOUNT_EACH / LAMPORTS_PER_SOL})`);
+            expect(after[0]).to.equal(2 * AMOUNT_EACH);
+            expect(after[paidFirstCall - 1]).to.equal(2 * AMOUNT_EACH);
+        });
+    });
+
+    describe("Secure: the cursor is persisted before each payment", () => {
+        it("A CU-limited call stops early with the cursor covering every payment", async () => {
+            await distribute("secureDistribute", SECURE_ID, secureRecipients, 0);
+
+            const cursor = await cursorOf(SECURE_ID);
+            const paid = (await balances(secureRecipients)).filter((b) => b > 0).length;
+            expect(cursor).to.be.greaterThan(0);
+            expect(cursor).to.be.lessThan(RECIPIENT_COUNT);
+            expect(paid).to.equal(cursor);
+        });
+
+        it("Rejects a resumption that skips past the cursor", async () => {
+            const cursor = await cursorOf(SECURE_ID);
+            await expectError(
+                distribute("secureDistribute", SECURE_ID, secureRecipients, cursor + 1),
+                "RecipientMismatch"
+            );
+        });
+
+        it("Rejects a resumption that restarts before the cursor", async () => {
+            await expectError(distribute("secureDistribute", SECURE_ID, secureRecipients, 0), "RecipientMismatch");
+        });
+
+        it("CU-limited calls resume from the cursor until everyone is paid exactly once", async () => {
+            let calls = 1;
+            let cursor = await cursorOf(SECURE_ID);
+            while (cursor < RECIPIENT_COUNT) {
+                await distribute("secureDistribute", SECURE_ID, secureRecipients, cursor);
+                const next = await cursorOf(SECURE_ID);
+                expect(next).to.be.greaterThan(cursor);
+                cursor = next;
+                calls++;
+            }
+            console.log(`  Distribution completed in ${calls} CU-limited calls`);
+
+            const after = await balances(secureRecipients);
+            expect(after.every((b) => b === AMOUNT_EACH)).to.equal(true);
+            expect(await provider.connection.getBalance(vaultPda(SECURE_ID))).to.equal(0);
+        });
+
+        it("A call after completion pays nobody", async () => {
+            await distribute("secureDistribute", SECURE_ID, secureRecipients, 0);
+
+            const after = await balances(secureRecipients);
+            expect(after.every((b) => b === AMOUNT_EACH)).to.equal(true);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Cursor Distribution ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  let mut cursor = dist.cursor; ... if low_cu { return Ok(()) } ... dist.cursor = cursor;");
+            console.log("  Risk: an early stop lands the payments but not the cursor; the next call pays them again\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  dist.cursor = cursor + 1; pay(recipients[cursor]);  // state before effect, every iteration");
+            console.log("  Protection: whenever the instruction returns, the cursor matches what was paid\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  A resumable loop must persist its progress on every exit path, not just the happy one.");
+        });
+    });
+});
@@ -0,0 +1,266 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("6TJzf1i6Ms3jbzEHCrMCRB56iBQfsYbxkNVgJaxnMWXh");
+
+#[program]
+pub mod cross_pool_position {
+    use super::*;
+
+    /// Creates a staking pool for `stake_mint`. Pool creation is
+    /// permissionless: anyone can open a pool for any mint.
+    pub fn create_pool(ctx: Context<CreatePool>, pool_id: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.creator = ctx.accounts.creator.key();
+        pool.pool_id = pool_id;
+        pool.stake_mint = ctx.accounts.stake_mint.key();
+        pool.total_staked = 0;
+        pool.bump = ctx.bumps.pool;
+        Ok(())
+    }
+
+    pub fn open_position(ctx: Context<OpenPosition>) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.pool = ctx.accounts.pool.key();
+        position.owner = ctx.accounts.owner.key();
+        position.amount = 0;
+        position.bump = ctx.bumps.position;
+        Ok(())
+    }
+
+    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.source.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let position = &mut ctx.accounts.position;
+        position.amount = position
+            .amount
+            .checked_add(amount)
+            .ok_or(StakingError::ArithmeticError)?;
+        let pool = &mut ctx.accounts.pool;
+        pool.total_staked = pool
+            .total_staked
+            .checked_add(amount)
+            .ok_or(StakingError::ArithmeticError)?;
+        Ok(())
+    }
+
+    // VULNERABLE: Position Not Bound to the Pool
+    // 1. `position` is an `Account<Position>` with `has_one = owner`, so a
+    //    position from any pool deserializes and passes
+    // 2. Nothing checks `position.pool == pool.key()`, and the position is
+    //    not re-derived from the pool's key
+    // 3. A position staked in a junk-mint pool is debited while this
+    //    pool's vault pays out in its own mint
+    pub fn vulnerable_unstake(ctx: Context<VulnerableUnstake>, amount: u64) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        require!(position.amount >= amount, StakingError::InsufficientStake);
+        position.amount = position
+            .amount
+            .checked_sub(amount)
+            .ok_or(StakingError::ArithmeticError)?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.total_staked = pool
+            .total_staked
+            .checked_sub(amount)
+            .ok_or(StakingError::ArithmeticError)?;
+
+        pay_out(
+            &ctx.accounts.pool,
+            &ctx.accounts.vault,
+            &ctx.accounts.destination,
+            &ctx.accounts.token_program,
+            amount,
+        )
+    }
+
+    // SECURE: Position Bound to the Pool
+    // 1. The position is re-derived from `[b"position", pool, owner]`, so
+    //    only this pool's position for the signer is accepted
+    // 2. `has_one = pool` and `has_one = owner` check the stored fields too
+    // 3. A position can only ever withdraw from the vault it was staked in
+    pub fn secure_unstake(ctx: Context<SecureUnstake>, amount: u64) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        require!(position.amount >= amount, StakingError::InsufficientStake);
+        position.amount = position
+            .amount
+            .checked_sub(amount)
+            .ok_or(StakingError::ArithmeticError)?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.total_staked = pool
+            .total_staked
+            .checked_sub(amount)
+            .ok_or(StakingError::ArithmeticError)?;
+
+        pay_out(
+            &ctx.accounts.pool,
+            &ctx.accounts.vault,
+            &ctx.accounts.destination,
+            &ctx.accounts.token_program,
+            amount,
+        )
+    }
+}
+
+fn pay_out<'info>(
+    pool: &Account<'info, Pool>,
+    vault: &Account<'info, TokenAccount>,
+    destination: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let pool_id = pool.pool_id.to_le_bytes();
+    let seeds: &[&[u8]] = &[b"pool", pool_id.as_ref(), &[pool.bump]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: vault.to_account_info(),
+                to: destination.to_account_info(),
+                authority: pool.to_account_info(),
+            },
+            &[seeds],
+        ),
+        amount,
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u64)]
+pub struct CreatePool<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Pool::INIT_SPACE,
+        seeds = [b"pool", pool_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump,
+        token::mint = stake_mint,
+        token::authority = pool
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    pub stake_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    pub pool: Account<'info, Pool>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [b"position", pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut, seeds = [b"vault", pool.key().as_ref()], bump)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"position", pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub source: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableUnstake<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut, seeds = [b"vault", pool.key().as_ref()], bump)]
+    pub vault: Account<'info, TokenAccount>,
+    // VULNERABLE: Owner checked, pool never checked
+    #[account(mut, has_one = owner @ StakingError::Unauthorized)]
+    pub position: Account<'info, Position>,
+    #[account(mut, token::mint = pool.stake_mint)]
+    pub destination: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SecureUnstake<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut, seeds = [b"vault", pool.key().as_ref()], bump)]
+    pub vault: Account<'info, TokenAccount>,
+    // SECURE: Re-derived from this pool and bound to it
+    #[account(
+        mut,
+        seeds = [b"position", pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = pool @ StakingError::WrongPool,
+        has_one = owner @ StakingError::Unauthorized
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut, token::mint = pool.stake_mint)]
+    pub destination: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub creator: Pubkey,
+    pub pool_id: u64,
+    pub stake_mint: Pubkey,
+    pub total_staked: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum StakingError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Position belongs to a different pool")]
+    WrongPool,
+    #[msg("Insufficient stake")]
+    InsufficientStake,
+    #[msg("Arithmetic error")]
+    ArithmeticError,
+}
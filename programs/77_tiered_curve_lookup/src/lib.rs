@@ -0,0 +1,267 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("tdMTThjbZyUBbasAQgsEW2bqdAKiKySxrEKZ6iMGyid");
+
+/// Number of price tiers.
+pub const TIERS: usize = 8;
+
+/// First token of each tier, plus the curve's cap. Tier `k` holds tokens
+/// `TIER_STARTS[k] <= n < TIER_STARTS[k + 1]`; each tier is twice the size
+/// of the one before, so tier `k` starts at `1_000 * (2^k - 1)`.
+pub const TIER_STARTS: [u64; TIERS + 1] = [
+    0, 1_000, 3_000, 7_000, 15_000, 31_000, 63_000, 127_000, 255_000,
+];
+
+/// Lamports per token in each tier: `1_000_000 * 2^k`.
+pub const TIER_PRICES: [u64; TIERS] = [
+    1_000_000, 2_000_000, 4_000_000, 8_000_000, 16_000_000, 32_000_000, 64_000_000, 128_000_000,
+];
+
+/// Tokens the curve can sell in total.
+pub const MAX_SUPPLY: u64 = TIER_STARTS[TIERS];
+
+/// Tier of token number `supply` (the next token to be sold), using
+/// half-open intervals. `None` once the curve is sold out.
+pub fn tier_of(supply: u64) -> Option<usize> {
+    if supply >= MAX_SUPPLY {
+        return None;
+    }
+    Some(TIER_STARTS.partition_point(|&start| start <= supply) - 1)
+}
+
+/// Cost of buying `amount` tokens when `supply` are already sold, charging
+/// each token at the price of its own tier.
+pub fn cost(supply: u64, amount: u64) -> Option<u64> {
+    let end = supply.checked_add(amount)?;
+    if end > MAX_SUPPLY {
+        return None;
+    }
+
+    let mut position = supply;
+    let mut total: u64 = 0;
+    while position < end {
+        let tier = tier_of(position)?;
+        let take = end.min(TIER_STARTS[tier + 1]) - position;
+        total = total.checked_add(take.checked_mul(TIER_PRICES[tier])?)?;
+        position += take;
+    }
+    Some(total)
+}
+
+/// The vulnerable lookup: `start < supply` treats each tier as
+/// `(start, next]`, so a supply sitting exactly on a boundary is priced in
+/// the tier below. `saturating_sub` hides the same mistake at zero.
+pub fn vulnerable_price_tier(supply: u64) -> usize {
+    TIER_STARTS
+        .partition_point(|&start| start < supply)
+        .saturating_sub(1)
+}
+
+/// First tier boundary strictly above `supply`.
+pub fn next_boundary(supply: u64) -> Option<u64> {
+    TIER_STARTS.iter().copied().find(|&start| start > supply)
+}
+
+#[program]
+pub mod tiered_curve_lookup {
+    use super::*;
+
+    pub fn create_curve(ctx: Context<CreateCurve>, curve_id: u64) -> Result<()> {
+        let curve = &mut ctx.accounts.curve;
+        curve.creator = ctx.accounts.creator.key();
+        curve.curve_id = curve_id;
+        curve.supply = 0;
+        curve.bump = ctx.bumps.curve;
+        Ok(())
+    }
+
+    // VULNERABLE: Off-by-One Tier Lookup
+    // 1. A buy is priced at one tier and may not cross the next boundary
+    // 2. The boundary comes from `next_boundary`, which is correct; the
+    //    price comes from `vulnerable_price_tier`, which is not
+    // 3. With the supply exactly on a boundary, the two disagree: the buy
+    //    may fill the whole new tier at the previous tier's price
+    pub fn vulnerable_buy(ctx: Context<Buy>, amount: u64) -> Result<()> {
+        require!(amount > 0, CurveError::ZeroAmount);
+        let supply = ctx.accounts.curve.supply;
+
+        let boundary = next_boundary(supply).ok_or(CurveError::SoldOut)?;
+        let end = supply.checked_add(amount).ok_or(CurveError::ArithmeticError)?;
+        require!(end <= boundary, CurveError::CrossesTier);
+
+        let price = TIER_PRICES[vulnerable_price_tier(supply)];
+        let total = amount.checked_mul(price).ok_or(CurveError::ArithmeticError)?;
+
+        settle(ctx, end, total)
+    }
+
+    // SECURE: Half-Open Tiers, Charged Token by Token
+    // 1. `tier_of` uses `start <= supply`: each tier is `[start, next)`,
+    //    so a boundary token belongs to the tier it starts
+    // 2. `cost` walks every tier the buy touches and charges each token at
+    //    its own tier's price, so a buy may cross boundaries safely
+    // 3. `cost` matches a reference curve on every boundary and on random
+    //    ranges (see the unit and property tests)
+    pub fn secure_buy(ctx: Context<Buy>, amount: u64) -> Result<()> {
+        require!(amount > 0, CurveError::ZeroAmount);
+        let supply = ctx.accounts.curve.supply;
+
+        let end = supply.checked_add(amount).ok_or(CurveError::ArithmeticError)?;
+        require!(end <= MAX_SUPPLY, CurveError::SoldOut);
+        let total = cost(supply, amount).ok_or(CurveError::ArithmeticError)?;
+
+        settle(ctx, end, total)
+    }
+}
+
+fn settle(ctx: Context<Buy>, new_supply: u64, total: u64) -> Result<()> {
+    transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.buyer.to_account_info(),
+                to: ctx.accounts.curve.to_account_info(),
+            },
+        ),
+        total,
+    )?;
+
+    let curve = &mut ctx.accounts.curve;
+    msg!("Bought {} tokens for {} lamports", new_supply - curve.supply, total);
+    curve.supply = new_supply;
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(curve_id: u64)]
+pub struct CreateCurve<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Curve::INIT_SPACE,
+        seeds = [b"curve", curve_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub curve: Account<'info, Curve>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Buy<'info> {
+    #[account(
+        mut,
+        seeds = [b"curve", curve.curve_id.to_le_bytes().as_ref()],
+        bump = curve.bump
+    )]
+    pub curve: Account<'info, Curve>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Curve {
+    pub creator: Pubkey,
+    pub curve_id: u64,
+    /// Tokens sold so far; the next token sold is number `supply`.
+    pub supply: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum CurveError {
+    #[msg("Amount must be positive")]
+    ZeroAmount,
+    #[msg("Curve is sold out")]
+    SoldOut,
+    #[msg("Buy crosses a tier boundary")]
+    CrossesTier,
+    #[msg("Arithmetic error")]
+    ArithmeticError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const TIER_UNIT: u64 = 1_000;
+    const BASE_PRICE: u64 = 1_000_000;
+
+    /// The curve the tables were generated from: token `n` costs
+    /// `BASE_PRICE * 2^floor(log2(n / TIER_UNIT + 1))`.
+    fn reference_price(n: u64) -> u64 {
+        BASE_PRICE << (n / TIER_UNIT + 1).ilog2()
+    }
+
+    fn reference_cost(supply: u64, amount: u64) -> u64 {
+        (supply..supply + amount).map(reference_price).sum()
+    }
+
+    #[test]
+    fn tables_match_the_reference_curve() {
+        for k in 0..TIERS {
+            assert_eq!(TIER_STARTS[k], TIER_UNIT * ((1 << k) - 1));
+            assert_eq!(TIER_PRICES[k], reference_price(TIER_STARTS[k]));
+        }
+        assert_eq!(MAX_SUPPLY, TIER_UNIT * ((1 << TIERS) - 1));
+    }
+
+    #[test]
+    fn every_boundary_is_half_open() {
+        assert_eq!(tier_of(0), Some(0));
+        for k in 1..TIERS {
+            let boundary = TIER_STARTS[k];
+            assert_eq!(tier_of(boundary - 1), Some(k - 1), "last token of tier {}", k - 1);
+            assert_eq!(tier_of(boundary), Some(k), "first token of tier {k}");
+            assert_eq!(cost(boundary - 1, 1), Some(TIER_PRICES[k - 1]));
+            assert_eq!(cost(boundary, 1), Some(TIER_PRICES[k]));
+            assert_eq!(cost(boundary - 1, 2), Some(TIER_PRICES[k - 1] + TIER_PRICES[k]));
+        }
+        assert_eq!(tier_of(MAX_SUPPLY - 1), Some(TIERS - 1));
+        assert_eq!(tier_of(MAX_SUPPLY), None);
+        assert_eq!(cost(MAX_SUPPLY - 1, 1), Some(TIER_PRICES[TIERS - 1]));
+        assert_eq!(cost(MAX_SUPPLY, 1), None);
+    }
+
+    #[test]
+    fn vulnerable_lookup_is_one_tier_low_on_every_boundary() {
+        for k in 1..TIERS {
+            let boundary = TIER_STARTS[k];
+            assert_eq!(vulnerable_price_tier(boundary), k - 1);
+            assert_eq!(vulnerable_price_tier(boundary + 1), k);
+            assert_eq!(next_boundary(boundary), Some(TIER_STARTS[k + 1]));
+        }
+    }
+
+    #[test]
+    fn whole_curve_costs_the_reference_total() {
+        assert_eq!(cost(0, MAX_SUPPLY), Some(reference_cost(0, MAX_SUPPLY)));
+    }
+
+    proptest! {
+        #[test]
+        fn tier_matches_reference(supply in 0..MAX_SUPPLY) {
+            let tier = tier_of(supply).unwrap();
+            prop_assert_eq!(TIER_PRICES[tier], reference_price(supply));
+        }
+
+        #[test]
+        fn cost_matches_reference(supply in 0..MAX_SUPPLY, amount in 1..5_000u64) {
+            let amount = amount.min(MAX_SUPPLY - supply);
+            prop_assert_eq!(cost(supply, amount), Some(reference_cost(supply, amount)));
+        }
+
+        #[test]
+        fn splitting_a_buy_costs_the_same(supply in 0..MAX_SUPPLY, a in 0..50_000u64, b in 0..50_000u64) {
+            let a = a.min(MAX_SUPPLY - supply);
+            let b = b.min(MAX_SUPPLY - supply - a);
+            let split = cost(supply, a).unwrap() + cost(supply + a, b).unwrap();
+            prop_assert_eq!(cost(supply, a + b), Some(split));
+        }
+    }
+}
@@ -10,7 +10,11 @@ import {
     PublicKey, 
     Connection,
     LAMPORTS_PER_SOL,
+    ComputeBudgetProgram,
+    Signer,
+    Transaction,
 } from "@solana/web3.js";
+import { expect } from "chai";
 
 /**
  * Airdrop SOL to an account and wait for confirmation
@@ -61,6 +65,57 @@ export function sleep(seconds: number): Promise<void> {
     return new Promise(resolve => setTimeout(resolve, seconds * 1000));
 }
 
+/**
+ * A transaction competing for ordering in `raceTransactions`
+ */
+export interface RaceEntry {
+    label: string;
+    tx: Transaction;
+    /** The first signer pays the fee */
+    signers: Signer[];
+    /** Compute unit price in micro-lamports; higher is ordered first */
+    priorityFee?: number;
+}
+
+export interface RaceResult {
+    label: string;
+    landed: boolean;
+    error?: unknown;
+}
+
+/**
+ * Submit competing transactions back to back, highest priority fee first,
+ * the way a leader orders a contended block, and report which ones landed.
+ * Simulates a searcher who saw a pending transaction and outbid it.
+ */
+export async function raceTransactions(
+    connection: Connection,
+    entries: RaceEntry[]
+): Promise<RaceResult[]> {
+    const latest = await connection.getLatestBlockhash();
+    const ordered = [...entries].sort((a, b) => (b.priorityFee ?? 0) - (a.priorityFee ?? 0));
+
+    const signatures: string[] = [];
+    for (const entry of ordered) {
+        const tx = new Transaction();
+        if (entry.priorityFee) {
+            tx.add(ComputeBudgetProgram.setComputeUnitPrice({ microLamports: entry.priorityFee }));
+        }
+        tx.add(...entry.tx.instructions);
+        tx.recentBlockhash = latest.blockhash;
+        tx.feePayer = entry.signers[0].publicKey;
+        tx.sign(...entry.signers);
+        signatures.push(await connection.sendRawTransaction(tx.serialize(), { skipPreflight: true }));
+    }
+
+    const results: RaceResult[] = [];
+    for (let i = 0; i < ordered.length; i++) {
+        const { value } = await connection.confirmTransaction({ signature: signatures[i], ...latest }, "confirmed");
+        results.push({ label: ordered[i].label, landed: value.err === null, error: value.err ?? undefined });
+    }
+    return results;
+}
+
 /**
  * Format a public key for display (first 8 chars)
  */
@@ -141,6 +196,18 @@ export async function assertRejects(
     }
 }
 
+/**
+ * Assert that a promise rejects with a specific Anchor error code
+ */
+export async function expectError(promise: Promise<unknown>, code: string): Promise<void> {
+    try {
+        await promise;
+        expect.fail("Should have thrown");
+    } catch (error: any) {
+        expect(error.error?.errorCode?.code).to.equal(code);
+    }
+}
+
 /**
  * Format token amounts with decimals
  */
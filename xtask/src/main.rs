@@ -0,0 +1,264 @@
+//! Workspace tasks, run with `cargo xtask <task>`.
+//!
+//! `challenges` writes a copy of every pattern program with its hints
+//! removed, builds the copies, and packages each one as a tarball for
+//! blind-audit exercises. `devnet-smoke` deploys the programs to devnet and
+//! runs their exploit tests there.
+
+mod smoke;
+mod strip;
+mod verify;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{exit, Command};
+
+const USAGE: &str = "\
+Usage: cargo xtask challenges [OPTIONS] [PROGRAM...]
+       cargo xtask devnet-smoke [OPTIONS] [PROGRAM...]
+
+Writes hint-free copies of the pattern programs to target/challenges,
+builds them and packages each as target/challenges/dist/<program>.tar.gz.
+PROGRAM filters by directory name prefix, e.g. `07` or `07_clock`.
+
+Options:
+  --out <DIR>   Output directory (default: target/challenges)
+  --sbf         Build with `cargo build-sbf` and include the .so
+  --no-build    Only write and verify the stripped sources
+  --no-package  Skip the tarballs
+
+See `cargo xtask devnet-smoke --help` for the smoke test options.";
+
+struct Options {
+    out: PathBuf,
+    sbf: bool,
+    build: bool,
+    package: bool,
+    filters: Vec<String>,
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let result = match args.next().as_deref() {
+        Some("challenges") => parse_options(args).and_then(|opts| challenges(&opts)),
+        Some("devnet-smoke") => smoke::run(args),
+        _ => Err(USAGE.to_string()),
+    };
+    if let Err(e) = result {
+        eprintln!("{e}");
+        exit(1);
+    }
+}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let root = workspace_root();
+    let mut opts = Options {
+        out: root.join("target").join("challenges"),
+        sbf: false,
+        build: true,
+        package: true,
+        filters: Vec::new(),
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => opts.out = args.next().ok_or("--out needs a directory")?.into(),
+            "--sbf" => opts.sbf = true,
+            "--no-build" => opts.build = false,
+            "--no-package" => opts.package = false,
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            flag if flag.starts_with('-') => return Err(format!("unknown option {flag}\n\n{USAGE}")),
+            filter => opts.filters.push(filter.to_string()),
+        }
+    }
+    Ok(opts)
+}
+
+pub(crate) fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask lives one level below the workspace root")
+        .to_path_buf()
+}
+
+/// Pattern program directories under `programs/`, in order.
+pub(crate) fn programs(root: &Path) -> std::io::Result<Vec<PathBuf>> {
+    program_dirs(&root.join("programs"))
+}
+
+/// Helper program directories under `helpers/`, in order.
+pub(crate) fn helpers(root: &Path) -> std::io::Result<Vec<PathBuf>> {
+    program_dirs(&root.join("helpers"))
+}
+
+fn program_dirs(parent: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut dirs: Vec<PathBuf> = fs::read_dir(parent)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.join("src").join("lib.rs").is_file())
+        .collect();
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// Every `.rs` file under `program/src`, in order.
+pub(crate) fn source_files(program: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![program.join("src")];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn challenges(opts: &Options) -> Result<(), String> {
+    let root = workspace_root();
+    let programs_out = opts.out.join("programs");
+    if programs_out.exists() {
+        fs::remove_dir_all(&programs_out).map_err(io_err(&programs_out))?;
+    }
+
+    let selected: Vec<PathBuf> = programs(&root)
+        .map_err(io_err(&root))?
+        .into_iter()
+        .filter(|p| {
+            let name = dir_name(p);
+            opts.filters.is_empty() || opts.filters.iter().any(|f| name.starts_with(f.as_str()))
+        })
+        .collect();
+    if selected.is_empty() {
+        return Err("no programs match".to_string());
+    }
+
+    for program in &selected {
+        write_stripped(&root, program, &programs_out.join(dir_name(program)))?;
+    }
+    write_workspace(&root, &opts.out)?;
+    println!("Stripped {} programs into {}", selected.len(), programs_out.display());
+
+    if opts.build {
+        build(&opts.out, opts.sbf)?;
+    }
+    if opts.package {
+        package(opts, &selected)?;
+    }
+    Ok(())
+}
+
+/// Copies one program with stripped sources, checking each file against
+/// its original.
+fn write_stripped(root: &Path, program: &Path, dest: &Path) -> Result<(), String> {
+    fs::create_dir_all(dest).map_err(io_err(dest))?;
+
+    // Path dependencies point back into this workspace.
+    let manifest = read(&program.join("Cargo.toml"))?;
+    let manifest = manifest.replace("path = \"../../", &format!("path = \"{}/", root.display()));
+    write(&dest.join("Cargo.toml"), &manifest)?;
+
+    for file in source_files(program).map_err(io_err(program))? {
+        let original = read(&file)?;
+        let stripped = strip::strip(&original);
+        verify::equivalent(&original, &stripped).map_err(|e| format!("{}: {e}", file.display()))?;
+
+        let relative = file.strip_prefix(program).expect("source file under program dir");
+        let target = dest.join(relative);
+        fs::create_dir_all(target.parent().expect("file has a parent")).map_err(io_err(&target))?;
+        write(&target, &stripped)?;
+    }
+    Ok(())
+}
+
+/// A standalone workspace around the stripped programs, sharing the real
+/// workspace's release profile and lock file.
+fn write_workspace(root: &Path, out: &Path) -> Result<(), String> {
+    let root_manifest = read(&root.join("Cargo.toml"))?;
+    let profiles = root_manifest
+        .find("[profile")
+        .map_or("", |at| &root_manifest[at..]);
+    let manifest = format!("[workspace]\nmembers = [\"programs/*\"]\nresolver = \"2\"\n\n{profiles}");
+    write(&out.join("Cargo.toml"), &manifest)?;
+
+    let lock = root.join("Cargo.lock");
+    if lock.is_file() {
+        fs::copy(&lock, out.join("Cargo.lock")).map_err(io_err(&lock))?;
+    }
+    Ok(())
+}
+
+fn build(out: &Path, sbf: bool) -> Result<(), String> {
+    let mut cmd = Command::new("cargo");
+    if sbf {
+        cmd.args(["build-sbf", "--workspace"]);
+    } else {
+        cmd.args(["build", "--release", "--workspace"]);
+    }
+    let status = cmd
+        .current_dir(out)
+        .status()
+        .map_err(|e| format!("failed to run cargo: {e}"))?;
+    if !status.success() {
+        return Err(format!("stripped programs failed to build ({status})"));
+    }
+    Ok(())
+}
+
+fn package(opts: &Options, selected: &[PathBuf]) -> Result<(), String> {
+    let dist = opts.out.join("dist");
+    fs::create_dir_all(&dist).map_err(io_err(&dist))?;
+
+    for program in selected {
+        let name = dir_name(program);
+        let staged = opts.out.join("programs").join(name);
+
+        if opts.sbf && opts.build {
+            let manifest = read(&staged.join("Cargo.toml"))?;
+            let lib = lib_name(&manifest).ok_or_else(|| format!("{name}: no [lib] name in Cargo.toml"))?;
+            let so = opts.out.join("target").join("deploy").join(format!("{lib}.so"));
+            fs::copy(&so, staged.join(format!("{lib}.so"))).map_err(io_err(&so))?;
+        }
+
+        let archive = dist.join(format!("{name}.tar.gz"));
+        let status = Command::new("tar")
+            .arg("-czf")
+            .arg(&archive)
+            .arg("-C")
+            .arg(opts.out.join("programs"))
+            .arg(name)
+            .status()
+            .map_err(|e| format!("failed to run tar: {e}"))?;
+        if !status.success() {
+            return Err(format!("{name}: tar failed ({status})"));
+        }
+    }
+    println!("Packaged {} challenges into {}", selected.len(), dist.display());
+    Ok(())
+}
+
+pub(crate) fn lib_name(manifest: &str) -> Option<&str> {
+    let lib = &manifest[manifest.find("[lib]")?..];
+    let line = lib.lines().find(|l| l.trim_start().starts_with("name"))?;
+    line.split('"').nth(1)
+}
+
+pub(crate) fn dir_name(path: &Path) -> &str {
+    path.file_name().and_then(|n| n.to_str()).expect("UTF-8 directory name")
+}
+
+pub(crate) fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(io_err(path))
+}
+
+pub(crate) fn write(path: &Path, contents: &str) -> Result<(), String> {
+    fs::write(path, contents).map_err(io_err(path))
+}
+
+pub(crate) fn io_err(path: &Path) -> impl Fn(std::io::Error) -> String + '_ {
+    move |e| format!("{}: {e}", path.display())
+}
@@ -0,0 +1,512 @@
+//! `devnet-smoke`: deploys the pattern programs to a live cluster and runs
+//! each program's exploit test against them.
+//!
+//! Everything goes through the same CLIs a maintainer would use by hand:
+//! `solana` for balances, airdrops and deploys, and `ts-mocha` for the
+//! tests. Faucet rate limits are the usual failure on devnet, so airdrops
+//! and test runs that hit one are retried with exponential backoff.
+
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::thread;
+use std::time::Duration;
+
+use crate::{dir_name, helpers, io_err, lib_name, programs, read, workspace_root, write};
+
+const USAGE: &str = "\
+Usage: cargo xtask devnet-smoke [OPTIONS] [PROGRAM...]
+
+Deploys the helper and pattern programs to devnet, then runs each
+program's tests/exploit.ts against them. Run `anchor build` first. PROGRAM
+filters by directory name prefix.
+
+Program ids come from the [programs.devnet] profile in Anchor.toml, or
+from the program's declare_id! when it has no entry there.
+
+Options:
+  --url <URL>          Cluster RPC URL (default: https://api.devnet.solana.com)
+  --wallet <PATH>      Payer keypair (default: ~/.config/solana/id.json)
+  --min-balance <SOL>  Top the wallet up to this before each step (default: 5)
+  --attempts <N>       Tries per airdrop, deploy and test run (default: 5)
+  --skip-deploy        Test programs that are already deployed
+  --report <PATH>      Also write the summary as JSON";
+
+const DEVNET_URL: &str = "https://api.devnet.solana.com";
+
+/// Output fragments that mean the faucet, not the program, failed.
+const FAUCET_ERRORS: &[&str] = &["429 Too Many Requests", "airdrop request failed", "Airdrop failed", "faucet has run dry"];
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+const AIRDROP_SOL: u64 = 2;
+const MAX_BACKOFF_SECS: u64 = 64;
+
+struct Options {
+    url: String,
+    wallet: PathBuf,
+    min_balance: u64,
+    attempts: u32,
+    deploy: bool,
+    report: Option<PathBuf>,
+    filters: Vec<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pass,
+    Fail,
+    Skip,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Status::Pass => "pass",
+            Status::Fail => "fail",
+            Status::Skip => "skip",
+        })
+    }
+}
+
+/// Result of one deploy or test step.
+struct Outcome {
+    program: String,
+    step: &'static str,
+    status: Status,
+    passing: u32,
+    failing: u32,
+    detail: String,
+}
+
+impl Outcome {
+    fn new(program: &str, step: &'static str, status: Status, detail: impl Into<String>) -> Self {
+        Self {
+            program: program.to_string(),
+            step,
+            status,
+            passing: 0,
+            failing: 0,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// `passing`, `failing` and `pending` counts from a mocha run.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct MochaSummary {
+    passing: u32,
+    failing: u32,
+    pending: u32,
+}
+
+pub fn run(args: impl Iterator<Item = String>) -> Result<(), String> {
+    let opts = parse_options(args)?;
+    let root = workspace_root();
+    let anchor_toml = read(&root.join("Anchor.toml"))?;
+
+    let selected: Vec<PathBuf> = programs(&root)
+        .map_err(io_err(&root))?
+        .into_iter()
+        .filter(|p| {
+            let name = dir_name(p);
+            opts.filters.is_empty() || opts.filters.iter().any(|f| name.starts_with(f.as_str()))
+        })
+        .collect();
+    if selected.is_empty() {
+        return Err("no programs match".to_string());
+    }
+
+    let mut outcomes = Vec::new();
+    if opts.deploy {
+        // Helpers are deployed whole: several tests load them from the workspace.
+        for helper in helpers(&root).map_err(io_err(&root))? {
+            outcomes.push(deploy(&opts, &root, &anchor_toml, &helper)?);
+        }
+    }
+    for program in &selected {
+        let deployed = if opts.deploy {
+            let outcome = deploy(&opts, &root, &anchor_toml, program)?;
+            let ok = outcome.status == Status::Pass;
+            outcomes.push(outcome);
+            ok
+        } else {
+            true
+        };
+        if deployed {
+            outcomes.push(test(&opts, &root, program)?);
+        } else {
+            outcomes.push(Outcome::new(dir_name(program), "test", Status::Skip, "not deployed"));
+        }
+    }
+
+    print_summary(&outcomes);
+    if let Some(path) = &opts.report {
+        write(path, &to_json(&opts.url, &outcomes))?;
+        println!("Wrote {}", path.display());
+    }
+    let failed = outcomes.iter().filter(|o| o.status == Status::Fail).count();
+    if failed > 0 {
+        return Err(format!("{failed} smoke step(s) failed"));
+    }
+    Ok(())
+}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let home = env::var("HOME").unwrap_or_default();
+    let mut opts = Options {
+        url: DEVNET_URL.to_string(),
+        wallet: Path::new(&home).join(".config").join("solana").join("id.json"),
+        min_balance: 5 * LAMPORTS_PER_SOL,
+        attempts: 5,
+        deploy: true,
+        report: None,
+        filters: Vec::new(),
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--url" => opts.url = args.next().ok_or("--url needs a URL")?,
+            "--wallet" => opts.wallet = args.next().ok_or("--wallet needs a path")?.into(),
+            "--min-balance" => {
+                let sol: u64 = args
+                    .next()
+                    .and_then(|v| v.parse().ok())
+                    .ok_or("--min-balance needs a whole number of SOL")?;
+                opts.min_balance = sol * LAMPORTS_PER_SOL;
+            }
+            "--attempts" => {
+                opts.attempts = args
+                    .next()
+                    .and_then(|v| v.parse().ok())
+                    .filter(|&n| n > 0)
+                    .ok_or("--attempts needs a positive number")?;
+            }
+            "--skip-deploy" => opts.deploy = false,
+            "--report" => opts.report = Some(args.next().ok_or("--report needs a path")?.into()),
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            flag if flag.starts_with('-') => return Err(format!("unknown option {flag}\n\n{USAGE}")),
+            filter => opts.filters.push(filter.to_string()),
+        }
+    }
+    Ok(opts)
+}
+
+/// Deploys one program with its `anchor build` keypair, after checking the
+/// keypair matches the id the tests will use.
+fn deploy(opts: &Options, root: &Path, anchor_toml: &str, program: &Path) -> Result<Outcome, String> {
+    let name = dir_name(program);
+    let manifest = read(&program.join("Cargo.toml"))?;
+    let lib = lib_name(&manifest).ok_or_else(|| format!("{name}: no [lib] name in Cargo.toml"))?;
+
+    let source = read(&program.join("src").join("lib.rs"))?;
+    let Some(expected) = profile_id(anchor_toml, "devnet", lib).or_else(|| declared_id(&source)) else {
+        return Ok(Outcome::new(name, "deploy", Status::Skip, "no devnet profile entry or declare_id!"));
+    };
+
+    let deploy_dir = root.join("target").join("deploy");
+    let so = deploy_dir.join(format!("{lib}.so"));
+    let keypair = deploy_dir.join(format!("{lib}-keypair.json"));
+    if !so.is_file() || !keypair.is_file() {
+        return Ok(Outcome::new(name, "deploy", Status::Fail, "not built; run `anchor build`"));
+    }
+    let address = Command::new("solana")
+        .arg("address")
+        .arg("-k")
+        .arg(&keypair)
+        .output()
+        .map_err(|e| format!("failed to run solana: {e}"))?;
+    let address = String::from_utf8_lossy(&address.stdout).trim().to_string();
+    if address != expected {
+        return Ok(Outcome::new(
+            name,
+            "deploy",
+            Status::Fail,
+            format!("keypair is {address} but the program expects {expected}; run `anchor keys sync`"),
+        ));
+    }
+
+    top_up(opts)?;
+    let mut last = String::new();
+    for attempt in 0..opts.attempts {
+        if attempt > 0 {
+            thread::sleep(backoff(attempt));
+        }
+        let out = solana(
+            opts,
+            &["program", "deploy", "--program-id"],
+            &[keypair.as_os_str(), so.as_os_str()],
+        )?;
+        if out.status.success() {
+            return Ok(Outcome::new(name, "deploy", Status::Pass, address));
+        }
+        last = last_line(&out);
+    }
+    Ok(Outcome::new(name, "deploy", Status::Fail, last))
+}
+
+/// Runs one program's whole exploit test, retrying when the run failed on
+/// a faucet error rather than a program error. The file runs unfiltered:
+/// Secure blocks build on the state the Exploit blocks before them leave.
+fn test(opts: &Options, root: &Path, program: &Path) -> Result<Outcome, String> {
+    let name = dir_name(program);
+    let spec = program.join("tests").join("exploit.ts");
+    if !spec.is_file() {
+        return Ok(Outcome::new(name, "test", Status::Skip, "no tests/exploit.ts"));
+    }
+
+    let mut outcome = Outcome::new(name, "test", Status::Fail, "");
+    for attempt in 0..opts.attempts {
+        if attempt > 0 {
+            thread::sleep(backoff(attempt));
+        }
+        top_up(opts)?;
+        let out = Command::new("yarn")
+            .args(["run", "ts-mocha", "-p", "./tsconfig.json", "-t", "1000000"])
+            .arg(&spec)
+            .env("ANCHOR_PROVIDER_URL", &opts.url)
+            .env("ANCHOR_WALLET", &opts.wallet)
+            .current_dir(root)
+            .output()
+            .map_err(|e| format!("failed to run yarn: {e}"))?;
+
+        let text = combined(&out);
+        let summary = mocha_summary(&text);
+        outcome.passing = summary.passing;
+        outcome.failing = summary.failing;
+        if passed(out.status.success(), &summary) {
+            outcome.status = Status::Pass;
+            outcome.detail = format!("{} pending", summary.pending);
+            return Ok(outcome);
+        }
+        outcome.detail = if summary.passing == 0 && summary.failing == 0 {
+            "0 passing".to_string()
+        } else {
+            last_line(&out)
+        };
+        if !is_faucet_error(&text) {
+            break;
+        }
+        println!("{name}: faucet error, retrying");
+    }
+    Ok(outcome)
+}
+
+/// Airdrops until the wallet holds at least `min_balance`.
+fn top_up(opts: &Options) -> Result<(), String> {
+    for attempt in 0..opts.attempts {
+        if balance(opts)? >= opts.min_balance {
+            return Ok(());
+        }
+        if attempt > 0 {
+            thread::sleep(backoff(attempt));
+        }
+        let amount = AIRDROP_SOL.to_string();
+        let out = solana(opts, &["airdrop", &amount], &[])?;
+        if !out.status.success() {
+            println!("airdrop failed: {}", last_line(&out));
+        }
+    }
+    let lamports = balance(opts)?;
+    if lamports >= opts.min_balance {
+        return Ok(());
+    }
+    Err(format!(
+        "wallet holds {} SOL after {} airdrop attempts; fund {} by hand",
+        lamports as f64 / LAMPORTS_PER_SOL as f64,
+        opts.attempts,
+        opts.wallet.display(),
+    ))
+}
+
+fn balance(opts: &Options) -> Result<u64, String> {
+    let out = solana(opts, &["balance", "--lamports"], &[])?;
+    let text = String::from_utf8_lossy(&out.stdout);
+    text.split_whitespace()
+        .next()
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(|| format!("could not read balance: {}", last_line(&out)))
+}
+
+/// Runs the `solana` CLI against the selected cluster and wallet.
+fn solana(opts: &Options, args: &[&str], paths: &[&std::ffi::OsStr]) -> Result<Output, String> {
+    Command::new("solana")
+        .args(args)
+        .args(paths)
+        .arg("--url")
+        .arg(&opts.url)
+        .arg("--keypair")
+        .arg(&opts.wallet)
+        .output()
+        .map_err(|e| format!("failed to run solana: {e}"))
+}
+
+/// `1s, 2s, 4s, ..` up to `MAX_BACKOFF_SECS` before retry number `attempt`.
+fn backoff(attempt: u32) -> Duration {
+    Duration::from_secs(1u64.checked_shl(attempt.saturating_sub(1)).unwrap_or(u64::MAX).min(MAX_BACKOFF_SECS))
+}
+
+fn is_faucet_error(output: &str) -> bool {
+    FAUCET_ERRORS.iter().any(|e| output.contains(e))
+}
+
+/// Reads the `N passing`, `N failing` and `N pending` lines mocha prints
+/// at the end of a run.
+fn mocha_summary(output: &str) -> MochaSummary {
+    let mut summary = MochaSummary::default();
+    for line in output.lines() {
+        let mut words = line.split_whitespace();
+        let (Some(count), Some(label)) = (words.next(), words.next()) else {
+            continue;
+        };
+        let Ok(count) = count.parse() else {
+            continue;
+        };
+        match label {
+            "passing" => summary.passing = count,
+            "failing" => summary.failing = count,
+            "pending" => summary.pending = count,
+            _ => {}
+        }
+    }
+    summary
+}
+
+/// Whether a test run passed. A run that passed nothing failed: mocha exits
+/// cleanly with "0 passing" when a spec loads no tests.
+fn passed(success: bool, summary: &MochaSummary) -> bool {
+    success && summary.failing == 0 && summary.passing > 0
+}
+
+/// The id for `lib` in the `[programs.<cluster>]` section of Anchor.toml.
+fn profile_id(anchor_toml: &str, cluster: &str, lib: &str) -> Option<String> {
+    let header = format!("[programs.{cluster}]");
+    anchor_toml
+        .lines()
+        .skip_while(|l| l.trim() != header)
+        .skip(1)
+        .take_while(|l| !l.trim_start().starts_with('['))
+        .find_map(|l| {
+            let (key, value) = l.split_once('=')?;
+            (key.trim() == lib).then(|| value.trim().trim_matches('"').to_string())
+        })
+}
+
+/// The id in a program's `declare_id!("..")`.
+fn declared_id(source: &str) -> Option<String> {
+    let rest = &source[source.find("declare_id!(")?..];
+    rest.split('"').nth(1).map(str::to_string)
+}
+
+fn combined(out: &Output) -> String {
+    format!("{}{}", String::from_utf8_lossy(&out.stdout), String::from_utf8_lossy(&out.stderr))
+}
+
+/// Last non-empty line of a command's output, for the summary.
+fn last_line(out: &Output) -> String {
+    combined(out)
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or("no output")
+        .to_string()
+}
+
+fn print_summary(outcomes: &[Outcome]) {
+    println!();
+    println!("{:<44} {:<7} {:<5} {:>4} {:>4}  detail", "program", "step", "", "ok", "fail");
+    for o in outcomes {
+        println!(
+            "{:<44} {:<7} {:<5} {:>4} {:>4}  {}",
+            o.program, o.step, o.status, o.passing, o.failing, o.detail
+        );
+    }
+    let count = |s: Status| outcomes.iter().filter(|o| o.status == s).count();
+    println!(
+        "\n{} passed, {} failed, {} skipped",
+        count(Status::Pass),
+        count(Status::Fail),
+        count(Status::Skip)
+    );
+}
+
+fn to_json(url: &str, outcomes: &[Outcome]) -> String {
+    let steps: Vec<String> = outcomes
+        .iter()
+        .map(|o| {
+            format!(
+                "    {{\"program\": \"{}\", \"step\": \"{}\", \"status\": \"{}\", \"passing\": {}, \"failing\": {}, \"detail\": \"{}\"}}",
+                json_escape(&o.program),
+                o.step,
+                o.status,
+                o.passing,
+                o.failing,
+                json_escape(&o.detail)
+            )
+        })
+        .collect();
+    format!("{{\n  \"cluster\": \"{}\",\n  \"steps\": [\n{}\n  ]\n}}\n", json_escape(url), steps.join(",\n"))
+}
+
+fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_mocha_summary() {
+        let output = "  76: Vault Repoint\n    ✔ works\n\n\n  7 passing (12s)\n  1 pending\n  2 failing\n\n  1) 76: Vault Repoint\n";
+        assert_eq!(mocha_summary(output), MochaSummary { passing: 7, failing: 2, pending: 1 });
+        assert_eq!(mocha_summary("no tests ran\n"), MochaSummary::default());
+    }
+
+    #[test]
+    fn zero_passing_is_a_failure() {
+        let ran = MochaSummary { passing: 3, failing: 0, pending: 1 };
+        assert!(passed(true, &ran));
+        assert!(!passed(false, &ran));
+        assert!(!passed(true, &MochaSummary { failing: 1, ..ran }));
+        assert!(!passed(true, &MochaSummary::default()));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let secs: Vec<u64> = (1..=9).map(|a| backoff(a).as_secs()).collect();
+        assert_eq!(secs, [1, 2, 4, 8, 16, 32, 64, 64, 64]);
+        assert_eq!(backoff(200).as_secs(), MAX_BACKOFF_SECS);
+    }
+
+    #[test]
+    fn profile_id_reads_only_its_cluster() {
+        let toml = "[programs.localnet]\nvault = \"Local111\"\n\n[programs.devnet]\nvault = \"Dev111\"\n\n[registry]\nvault = \"Nope\"\n";
+        assert_eq!(profile_id(toml, "devnet", "vault").as_deref(), Some("Dev111"));
+        assert_eq!(profile_id(toml, "localnet", "vault").as_deref(), Some("Local111"));
+        assert_eq!(profile_id(toml, "devnet", "other"), None);
+        assert_eq!(profile_id(toml, "mainnet", "vault"), None);
+    }
+
+    #[test]
+    fn reads_declare_id() {
+        let src = "use anchor_lang::prelude::*;\n\ndeclare_id!(\"3UFE7yLEjqFt2WDGHkWeUnfR2C3ttJUYad2ty3V2TEsa\");\n";
+        assert_eq!(declared_id(src).as_deref(), Some("3UFE7yLEjqFt2WDGHkWeUnfR2C3ttJUYad2ty3V2TEsa"));
+        assert_eq!(declared_id("fn main() {}"), None);
+    }
+
+    #[test]
+    fn escapes_json() {
+        assert_eq!(json_escape("a \"b\"\\\n\t"), "a \\\"b\\\"\\\\\\n\\u0009");
+    }
+}
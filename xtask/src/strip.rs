@@ -0,0 +1,353 @@
+//! Text-level removal of hints from program source.
+//!
+//! Works on the raw file so the output keeps the author's formatting. Only
+//! comments, marker glyphs and hint `msg!` statements are touched; every
+//! other byte is copied through. `verify` checks that claim independently.
+
+/// Glyphs used to flag the vulnerable and secure halves of an example.
+pub const MARKERS: &[&str] = &["❌", "✅"];
+
+/// A `msg!` whose format string contains one of these (case-insensitive)
+/// gives the answer away and is removed.
+pub const HINT_WORDS: &[&str] = &["vulnerab", "secure", "exploit", "attack", "bypass"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Code,
+    Str,
+    LineComment,
+    DocComment,
+    BlockComment,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    kind: Kind,
+    start: usize,
+    end: usize,
+}
+
+/// Splits `src` into code, string/char literals and comments.
+fn segments(src: &str) -> Vec<Segment> {
+    let bytes = src.as_bytes();
+    let mut out = Vec::new();
+    let mut code_start = 0;
+    let mut i = 0;
+
+    fn push(out: &mut Vec<Segment>, kind: Kind, start: usize, end: usize) {
+        if end > start {
+            out.push(Segment { kind, start, end });
+        }
+    }
+
+    while i < bytes.len() {
+        let rest = &bytes[i..];
+        let prev_is_ident = i > 0 && is_ident(bytes[i - 1]);
+
+        let (kind, end) = if rest.starts_with(b"//") {
+            let end = src[i..].find('\n').map_or(bytes.len(), |n| i + n);
+            let doc = (rest.starts_with(b"///") && !rest.starts_with(b"////")) || rest.starts_with(b"//!");
+            (if doc { Kind::DocComment } else { Kind::LineComment }, end)
+        } else if rest.starts_with(b"/*") {
+            (Kind::BlockComment, block_comment_end(bytes, i))
+        } else if rest[0] == b'"' {
+            (Kind::Str, string_end(bytes, i + 1))
+        } else if rest[0] == b'r' && !prev_is_ident && raw_string_hashes(rest).is_some() {
+            let hashes = raw_string_hashes(rest).unwrap();
+            (Kind::Str, raw_string_end(bytes, i + 2 + hashes, hashes))
+        } else if rest[0] == b'\'' {
+            match char_literal_end(src, i) {
+                Some(end) => (Kind::Str, end),
+                None => {
+                    i += 1;
+                    continue;
+                }
+            }
+        } else {
+            i += 1;
+            continue;
+        };
+
+        push(&mut out, Kind::Code, code_start, i);
+        push(&mut out, kind, i, end);
+        i = end;
+        code_start = end;
+    }
+    push(&mut out, Kind::Code, code_start, bytes.len());
+    out
+}
+
+fn is_ident(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+fn block_comment_end(bytes: &[u8], start: usize) -> usize {
+    let mut depth = 0;
+    let mut i = start;
+    while i + 1 < bytes.len() {
+        if bytes[i] == b'/' && bytes[i + 1] == b'*' {
+            depth += 1;
+            i += 2;
+        } else if bytes[i] == b'*' && bytes[i + 1] == b'/' {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return i;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    bytes.len()
+}
+
+fn string_end(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// `Some(n)` if `rest` opens a raw string `r#..#"` with `n` hashes.
+fn raw_string_hashes(rest: &[u8]) -> Option<usize> {
+    let hashes = rest[1..].iter().take_while(|&&b| b == b'#').count();
+    (rest.get(1 + hashes) == Some(&b'"')).then_some(hashes)
+}
+
+fn raw_string_end(bytes: &[u8], mut i: usize, hashes: usize) -> usize {
+    while i < bytes.len() {
+        if bytes[i] == b'"' && bytes[i + 1..].iter().take(hashes).filter(|&&b| b == b'#').count() == hashes {
+            return i + 1 + hashes;
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// End of a char literal at `start`, or `None` for a lifetime.
+fn char_literal_end(src: &str, start: usize) -> Option<usize> {
+    let bytes = src.as_bytes();
+    if *bytes.get(start + 1)? == b'\\' {
+        let close = bytes[start + 2..].iter().position(|&b| b == b'\'')?;
+        return Some(start + 2 + close + 1);
+    }
+    // One char, possibly multi-byte, then a closing quote.
+    let width = src[start + 1..].chars().next()?.len_utf8();
+    (bytes.get(start + 1 + width) == Some(&b'\'')).then_some(start + 2 + width)
+}
+
+/// Removes comments, marker glyphs and hint `msg!` statements.
+///
+/// Anchor requires a `/// CHECK:` doc on every unchecked account, so those
+/// are kept with their text removed.
+pub fn strip(src: &str) -> String {
+    let without_comments = strip_comments(src);
+    let without_msgs = strip_hint_msgs(&without_comments);
+    collapse_blank_lines(&strip_markers(&without_msgs))
+}
+
+pub fn strip_markers(src: &str) -> String {
+    MARKERS.iter().fold(src.to_string(), |acc, marker| {
+        acc.replace(&format!("{marker} "), "").replace(marker, "")
+    })
+}
+
+fn strip_comments(src: &str) -> String {
+    let mut out = String::with_capacity(src.len());
+    // Lines that held nothing but a comment are dropped entirely.
+    let mut line = String::new();
+    let mut line_had_comment = false;
+    let mut in_check_doc = false;
+
+    let flush = |out: &mut String, line: &mut String, had_comment: &mut bool| {
+        if !(*had_comment && line.trim().is_empty()) {
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        line.clear();
+        *had_comment = false;
+    };
+
+    for seg in segments(src) {
+        let text = &src[seg.start..seg.end];
+        match seg.kind {
+            Kind::Code | Kind::Str => {
+                let mut parts = text.split('\n').peekable();
+                while let Some(part) = parts.next() {
+                    line.push_str(part);
+                    if !part.trim().is_empty() {
+                        in_check_doc = false;
+                    }
+                    if parts.peek().is_some() {
+                        flush(&mut out, &mut line, &mut line_had_comment);
+                    }
+                }
+            }
+            Kind::DocComment if text.starts_with("///") && text[3..].trim_start().starts_with("CHECK") => {
+                line.push_str("/// CHECK:");
+                in_check_doc = true;
+            }
+            Kind::DocComment if in_check_doc => line_had_comment = true,
+            Kind::LineComment | Kind::DocComment | Kind::BlockComment => {
+                line_had_comment = true;
+            }
+        }
+    }
+    if !line.is_empty() {
+        flush(&mut out, &mut line, &mut line_had_comment);
+    }
+    if !src.ends_with('\n') {
+        out.pop();
+    }
+    out
+}
+
+fn strip_hint_msgs(src: &str) -> String {
+    let segs = segments(src);
+    let mut removals: Vec<(usize, usize)> = Vec::new();
+
+    for (idx, seg) in segs.iter().enumerate() {
+        if seg.kind != Kind::Code {
+            continue;
+        }
+        let text = &src[seg.start..seg.end];
+        for (offset, _) in text.match_indices("msg!") {
+            let at = seg.start + offset;
+            if at > 0 && is_ident(src.as_bytes()[at - 1]) {
+                continue;
+            }
+            if let Some((end, format)) = macro_call_end(src, &segs, idx, at + 4) {
+                if is_hint(format) {
+                    removals.push(widen_to_lines(src, at, end));
+                }
+            }
+        }
+    }
+
+    let mut out = String::with_capacity(src.len());
+    let mut cursor = 0;
+    for (start, end) in removals {
+        out.push_str(&src[cursor..start]);
+        cursor = end;
+    }
+    out.push_str(&src[cursor..]);
+    out
+}
+
+/// From just after `msg!`, finds the end of the call (past a trailing `;`)
+/// and the first string literal inside it.
+fn macro_call_end<'a>(src: &'a str, segs: &[Segment], seg_idx: usize, from: usize) -> Option<(usize, &'a str)> {
+    let mut depth = 0usize;
+    let mut format = None;
+    let mut close = None;
+
+    'outer: for seg in &segs[seg_idx..] {
+        let start = seg.start.max(from);
+        if start >= seg.end {
+            continue;
+        }
+        match seg.kind {
+            Kind::Str if depth > 0 && format.is_none() => {
+                format = Some(&src[seg.start..seg.end]);
+            }
+            Kind::Code => {
+                for (i, b) in src.as_bytes()[start..seg.end].iter().enumerate() {
+                    match b {
+                        b'(' | b'[' | b'{' => depth += 1,
+                        b')' | b']' | b'}' => {
+                            depth = depth.checked_sub(1)?;
+                            if depth == 0 {
+                                close = Some(start + i + 1);
+                                break 'outer;
+                            }
+                        }
+                        b if b.is_ascii_whitespace() => {}
+                        _ if depth == 0 => return None,
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut end = close?;
+    let after = &src[end..];
+    let trimmed = after.trim_start_matches([' ', '\t']);
+    if trimmed.starts_with(';') {
+        end += after.len() - trimmed.len() + 1;
+    }
+    Some((end, format?))
+}
+
+fn is_hint(format: &str) -> bool {
+    let lower = format.to_lowercase();
+    HINT_WORDS.iter().any(|w| lower.contains(w))
+}
+
+/// Extends a removal to whole lines when nothing else shares them.
+fn widen_to_lines(src: &str, start: usize, end: usize) -> (usize, usize) {
+    let line_start = src[..start].rfind('\n').map_or(0, |n| n + 1);
+    let line_end = src[end..].find('\n').map_or(src.len(), |n| end + n + 1);
+    if src[line_start..start].trim().is_empty() && src[end..line_end].trim().is_empty() {
+        (line_start, line_end)
+    } else {
+        (start, end)
+    }
+}
+
+/// Removes blank lines left at the top or bottom of a block and runs of
+/// blank lines.
+fn collapse_blank_lines(src: &str) -> String {
+    let mut out: Vec<&str> = Vec::new();
+    for line in src.split('\n') {
+        let trimmed = line.trim();
+        let prev = out.last().map(|l| l.trim());
+        if trimmed.is_empty() && prev.is_some_and(|p| p.is_empty() || p.ends_with('{')) {
+            continue;
+        }
+        if trimmed.starts_with('}') && prev == Some("") {
+            out.pop();
+        }
+        out.push(line);
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_comments_but_not_strings() {
+        let src = "let url = \"https://example.com\"; // trailing\n// whole line\nlet c = '/';\n";
+        assert_eq!(strip(src), "let url = \"https://example.com\";\nlet c = '/';\n");
+    }
+
+    #[test]
+    fn keeps_check_docs_without_their_text() {
+        let src = "    /// CHECK: VULNERABLE - no owner check.\n    /// More detail.\n    pub a: UncheckedAccount<'info>,\n";
+        assert_eq!(strip(src), "    /// CHECK:\n    pub a: UncheckedAccount<'info>,\n");
+    }
+
+    #[test]
+    fn removes_hint_msgs_only() {
+        let src = "fn f() {\n    msg!(\n        \"Vulnerable withdraw: {}\",\n        amount\n    );\n    msg!(\"Paid {}\", amount);\n}\n";
+        assert_eq!(strip(src), "fn f() {\n    msg!(\"Paid {}\", amount);\n}\n");
+    }
+
+    #[test]
+    fn removes_markers() {
+        assert_eq!(strip("let s = \"✅ ok\";\n"), "let s = \"ok\";\n");
+    }
+
+    #[test]
+    fn lifetimes_are_not_char_literals() {
+        let src = "fn f<'info>(a: &'info str) -> char { '\\'' } // x\n";
+        assert_eq!(strip(src), "fn f<'info>(a: &'info str) -> char { '\\'' }\n");
+    }
+}
@@ -0,0 +1,136 @@
+//! Token-level check that stripping changed nothing but hints.
+//!
+//! Independent of the text stripper: both files are lexed with
+//! `proc-macro2`, which drops ordinary comments and turns doc comments into
+//! `#[doc = ".."]` attributes. Removing doc attributes and hint `msg!` calls
+//! from both token streams must leave them identical.
+
+use std::str::FromStr;
+
+use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
+
+use crate::strip::{strip_markers, HINT_WORDS};
+
+/// `Ok(())` if `stripped` is `original` minus comments, docs and hints.
+pub fn equivalent(original: &str, stripped: &str) -> Result<(), String> {
+    let original = strip_markers(&normalized(original)?);
+    let stripped = normalized(stripped)?;
+    if original == stripped {
+        return Ok(());
+    }
+
+    let at = original
+        .char_indices()
+        .zip(stripped.chars())
+        .find(|((_, a), b)| a != b)
+        .map_or(original.len().min(stripped.len()), |((i, _), _)| i);
+    let context = |s: &str| s.get(at.saturating_sub(60)..(at + 60).min(s.len())).unwrap_or("").to_string();
+    Err(format!(
+        "token streams differ\n  original: ..{}..\n  stripped: ..{}..",
+        context(&original),
+        context(&stripped)
+    ))
+}
+
+fn normalized(src: &str) -> Result<String, String> {
+    let tokens = TokenStream::from_str(src).map_err(|e| format!("does not lex: {e}"))?;
+    Ok(filter(tokens).to_string())
+}
+
+fn filter(tokens: TokenStream) -> TokenStream {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut out = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+
+    while i < tokens.len() {
+        if let Some(len) = doc_attribute_len(&tokens[i..]).or_else(|| hint_msg_len(&tokens[i..])) {
+            i += len;
+            continue;
+        }
+        out.push(match &tokens[i] {
+            TokenTree::Group(group) => {
+                let mut filtered = Group::new(group.delimiter(), filter(group.stream()));
+                filtered.set_span(group.span());
+                TokenTree::Group(filtered)
+            }
+            other => other.clone(),
+        });
+        i += 1;
+    }
+    out.into_iter().collect()
+}
+
+/// `#[doc ..]` or `#![doc ..]`.
+fn doc_attribute_len(tokens: &[TokenTree]) -> Option<usize> {
+    let TokenTree::Punct(hash) = tokens.first()? else {
+        return None;
+    };
+    if hash.as_char() != '#' {
+        return None;
+    }
+    let bang = matches!(tokens.get(1)?, TokenTree::Punct(p) if p.as_char() == '!');
+    let TokenTree::Group(group) = tokens.get(1 + bang as usize)? else {
+        return None;
+    };
+    let is_doc = group.delimiter() == Delimiter::Bracket
+        && matches!(group.stream().into_iter().next(), Some(TokenTree::Ident(ident)) if ident == "doc");
+    is_doc.then_some(2 + bang as usize)
+}
+
+/// `msg!(..)` or `msg!(..);` whose first literal names the answer.
+fn hint_msg_len(tokens: &[TokenTree]) -> Option<usize> {
+    let TokenTree::Ident(ident) = tokens.first()? else {
+        return None;
+    };
+    if ident != "msg" || !matches!(tokens.get(1)?, TokenTree::Punct(p) if p.as_char() == '!') {
+        return None;
+    }
+    let TokenTree::Group(args) = tokens.get(2)? else {
+        return None;
+    };
+    let format = args.stream().into_iter().find_map(|t| match t {
+        TokenTree::Literal(lit) => Some(lit.to_string().to_lowercase()),
+        _ => None,
+    })?;
+    if !HINT_WORDS.iter().any(|w| format.contains(w)) {
+        return None;
+    }
+    let semicolon = matches!(tokens.get(3), Some(TokenTree::Punct(p)) if p.as_char() == ';');
+    Some(3 + semicolon as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strip::strip;
+
+    #[test]
+    fn detects_a_changed_token() {
+        assert!(equivalent("fn f() { a + b }", "fn f() { a - b }").is_err());
+    }
+
+    #[test]
+    fn ignores_comments_docs_and_hint_msgs() {
+        let original = "/// Doc\nfn f() { msg!(\"Secure path\"); g(); // note\n}";
+        assert!(equivalent(original, "fn f() { g(); }").is_ok());
+    }
+
+    /// Every program in the workspace strips to the same token stream.
+    #[test]
+    fn stripped_programs_are_equivalent() {
+        let programs = crate::programs(&crate::workspace_root()).unwrap();
+        assert!(!programs.is_empty());
+        for program in programs {
+            for file in crate::source_files(&program).unwrap() {
+                let original = std::fs::read_to_string(&file).unwrap();
+                let stripped = strip(&original);
+                if let Err(e) = equivalent(&original, &stripped) {
+                    panic!("{}: {e}", file.display());
+                }
+                for hint in ["VULNERABLE", "SECURE", "❌", "✅"] {
+                    assert!(!stripped.contains(hint), "{}: {hint} left in", file.display());
+                }
+            }
+        }
+    }
+}